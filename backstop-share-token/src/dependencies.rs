@@ -0,0 +1,33 @@
+use soroban_sdk::{contractclient, contracttype, Address, Env, Vec};
+
+/// A deposit that is queued for withdrawal
+#[derive(Clone)]
+#[contracttype]
+pub struct Q4W {
+    pub amount: i128, // the amount of shares queued for withdrawal
+    pub exp: u64,     // the expiration of the withdrawal
+}
+
+/// A user's backstop shares for a pool
+#[derive(Clone)]
+#[contracttype]
+pub struct UserBalance {
+    pub shares: i128,  // the balance of shares the user owns
+    pub q4w: Vec<Q4W>, // a list of queued withdrawals
+}
+
+/// The backstop functions used by the share token
+#[allow(dead_code)]
+#[contractclient(name = "BackstopClient")]
+pub trait Backstop {
+    /// Transfer backstop shares for a pool from `from` to `to`. Only shares that are not queued
+    /// for withdrawal or held in a lockup can be transferred.
+    fn transfer_shares(e: Env, from: Address, pool_address: Address, to: Address, amount: i128);
+
+    /// Claim backstop deposit emissions from a list of pools for `from`, and deposit them into
+    /// each pool's backstop on behalf of `to`
+    fn claim(e: Env, from: Address, pool_addresses: Vec<Address>, to: Address) -> i128;
+
+    /// Fetch the backstop shares of a user for a pool
+    fn user_balance(e: Env, pool: Address, user: Address) -> UserBalance;
+}
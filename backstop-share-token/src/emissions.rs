@@ -0,0 +1,80 @@
+use soroban_sdk::{vec, Address, Env};
+
+use crate::{
+    dependencies::BackstopClient,
+    storage::{self, UserEmissionData},
+};
+
+/// The scalar of the emissions index
+const SCALAR_14: i128 = 1_00000000_000000;
+
+/// Claim the backstop emissions earned by the wrapped shares and distribute them to share token
+/// holders through the emissions index. Claimed emissions are deposited into the backstop as
+/// shares held by the share token.
+///
+/// Returns the updated emissions index
+pub fn update_index(e: &Env) -> i128 {
+    let share_token = e.current_contract_address();
+    let pool = storage::get_pool(e);
+    let backstop_client = BackstopClient::new(e, &storage::get_backstop(e));
+    backstop_client.claim(&share_token, &vec![e, pool.clone()], &share_token);
+
+    let mut index = storage::get_index(e);
+    let supply = storage::get_supply(e);
+    let unclaimed = storage::get_unclaimed(e);
+    // any shares held beyond the wrapped and unclaimed shares have not been distributed yet,
+    // including the shares behind burnt share tokens
+    let held = backstop_client.user_balance(&pool, &share_token).shares;
+    let to_distribute = held - supply - unclaimed;
+    if to_distribute > 0 && supply > 0 {
+        index += to_distribute * SCALAR_14 / supply;
+        storage::set_index(e, index);
+        storage::set_unclaimed(e, unclaimed + to_distribute);
+    }
+    index
+}
+
+/// Accrue the emissions earned by a holder's share tokens since their last update
+///
+/// Returns the holder's updated emissions state
+///
+/// ### Arguments
+/// * `id` - The address holding share tokens
+/// * `index` - The current emissions index
+pub fn update_user(e: &Env, id: &Address, index: i128) -> UserEmissionData {
+    let user_data = match storage::get_user_emis_data(e, id) {
+        Some(mut user_data) => {
+            let balance = storage::get_balance(e, id);
+            user_data.accrued += balance * (index - user_data.index) / SCALAR_14;
+            user_data.index = index;
+            user_data
+        }
+        None => UserEmissionData { index, accrued: 0 },
+    };
+    storage::set_user_emis_data(e, id, &user_data);
+    user_data
+}
+
+/// Claim the backstop shares a holder earned from emissions
+///
+/// Returns the amount of backstop shares transferred to the holder
+///
+/// ### Arguments
+/// * `id` - The address claiming its emissions
+pub fn claim(e: &Env, id: &Address) -> i128 {
+    let index = update_index(e);
+    let mut user_data = update_user(e, id, index);
+    let to_claim = user_data.accrued;
+    if to_claim > 0 {
+        user_data.accrued = 0;
+        storage::set_user_emis_data(e, id, &user_data);
+        storage::set_unclaimed(e, storage::get_unclaimed(e) - to_claim);
+        BackstopClient::new(e, &storage::get_backstop(e)).transfer_shares(
+            &e.current_contract_address(),
+            &storage::get_pool(e),
+            id,
+            &to_claim,
+        );
+    }
+    to_claim
+}
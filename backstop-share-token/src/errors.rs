@@ -0,0 +1,18 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+/// Error codes for the backstop share token contract. Common errors are codes that match up with the
+/// built-in contracts error reporting. Share token specific errors start at 1500.
+pub enum ShareTokenError {
+    // Common Errors
+    InternalError = 1,
+    AlreadyInitializedError = 3,
+
+    NegativeAmountError = 8,
+    AllowanceError = 9,
+    BalanceError = 10,
+
+    InvalidExpiration = 1500,
+}
@@ -0,0 +1,13 @@
+#![no_std]
+
+#[cfg(any(test, feature = "testutils"))]
+extern crate std;
+
+mod dependencies;
+mod emissions;
+mod errors;
+mod share_token;
+mod storage;
+
+pub use errors::ShareTokenError;
+pub use share_token::*;
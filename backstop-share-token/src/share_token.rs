@@ -0,0 +1,305 @@
+use crate::{
+    dependencies::BackstopClient,
+    emissions,
+    errors::ShareTokenError,
+    storage::{self, AllowanceValue},
+};
+use sep_41_token::{Token, TokenEvents};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, panic_with_error, Address, Env, String, Symbol,
+};
+
+/// ### Backstop Share Token
+///
+/// A SEP-41 token representing backstop shares of a single pool. Holders wrap their backstop
+/// shares into share tokens, which can be transferred freely, and unwrap them back into backstop
+/// shares to queue them for withdrawal.
+///
+/// Wrapped shares are held by the share token in the backstop. They are never queued for
+/// withdrawal, so transfers of share tokens do not interact with the backstop's withdrawal
+/// queue.
+///
+/// Backstop emissions on wrapped shares are claimed by the share token as backstop shares and
+/// distributed to holders pro rata to their share token balances. Holders claim their part with
+/// `claim`. Burning share tokens forfeits the wrapped shares to the remaining holders.
+#[contract]
+pub struct BackstopShareTokenContract;
+
+#[contractclient(name = "BackstopShareTokenClient")]
+pub trait BackstopShareToken {
+    /// Initialize the share token
+    ///
+    /// ### Arguments
+    /// * `backstop` - The address of the backstop
+    /// * `pool` - The address of the pool whose backstop shares are wrapped
+    /// * `name` - The name of the token
+    /// * `symbol` - The symbol of the token
+    ///
+    /// ### Errors
+    /// If initialize has already been called
+    fn initialize(e: Env, backstop: Address, pool: Address, name: String, symbol: String);
+
+    /// Wrap backstop shares held by `from` into share tokens, minted 1:1 to `from`
+    ///
+    /// `from` must authorize the wrap and the nested share transfer on the backstop.
+    ///
+    /// ### Arguments
+    /// * `from` - The address wrapping backstop shares
+    /// * `amount` - The amount of backstop shares to wrap
+    ///
+    /// ### Errors
+    /// If `from` does not have enough transferable backstop shares
+    fn wrap(e: Env, from: Address, amount: i128);
+
+    /// Unwrap share tokens held by `from` into backstop shares, returned 1:1 to `from`
+    ///
+    /// ### Arguments
+    /// * `from` - The address unwrapping share tokens
+    /// * `amount` - The amount of share tokens to unwrap
+    ///
+    /// ### Errors
+    /// If `from` does not have enough share tokens
+    fn unwrap(e: Env, from: Address, amount: i128);
+
+    /// Claim the backstop shares earned by `from`'s share tokens from backstop emissions
+    ///
+    /// Returns the amount of backstop shares transferred to `from`
+    ///
+    /// ### Arguments
+    /// * `from` - The address claiming its emissions
+    fn claim(e: Env, from: Address) -> i128;
+
+    /// Fetch the address of the backstop
+    fn backstop(e: Env) -> Address;
+
+    /// Fetch the address of the pool whose backstop shares are wrapped
+    fn pool(e: Env) -> Address;
+}
+
+#[contractimpl]
+impl BackstopShareToken for BackstopShareTokenContract {
+    fn initialize(e: Env, backstop: Address, pool: Address, name: String, symbol: String) {
+        storage::extend_instance(&e);
+        if storage::get_is_init(&e) {
+            panic_with_error!(&e, ShareTokenError::AlreadyInitializedError);
+        }
+
+        storage::set_backstop(&e, &backstop);
+        storage::set_pool(&e, &pool);
+        storage::set_name(&e, &name);
+        storage::set_symbol(&e, &symbol);
+
+        storage::set_is_init(&e);
+    }
+
+    fn wrap(e: Env, from: Address, amount: i128) {
+        storage::extend_instance(&e);
+        from.require_auth();
+        require_nonnegative(&e, amount);
+
+        let index = emissions::update_index(&e);
+        emissions::update_user(&e, &from, index);
+        let share_token = e.current_contract_address();
+        BackstopClient::new(&e, &storage::get_backstop(&e)).transfer_shares(
+            &from,
+            &storage::get_pool(&e),
+            &share_token,
+            &amount,
+        );
+        receive_balance(&e, &from, amount);
+        storage::set_supply(&e, storage::get_supply(&e) + amount);
+
+        TokenEvents::mint(&e, share_token, from, amount);
+    }
+
+    fn unwrap(e: Env, from: Address, amount: i128) {
+        storage::extend_instance(&e);
+        from.require_auth();
+        require_nonnegative(&e, amount);
+
+        let index = emissions::update_index(&e);
+        emissions::update_user(&e, &from, index);
+        spend_balance(&e, &from, amount);
+        storage::set_supply(&e, storage::get_supply(&e) - amount);
+        BackstopClient::new(&e, &storage::get_backstop(&e)).transfer_shares(
+            &e.current_contract_address(),
+            &storage::get_pool(&e),
+            &from,
+            &amount,
+        );
+
+        TokenEvents::burn(&e, from, amount);
+    }
+
+    fn claim(e: Env, from: Address) -> i128 {
+        storage::extend_instance(&e);
+        from.require_auth();
+
+        let amount = emissions::claim(&e, &from);
+
+        e.events().publish((Symbol::new(&e, "claim"), from), amount);
+        amount
+    }
+
+    fn backstop(e: Env) -> Address {
+        storage::get_backstop(&e)
+    }
+
+    fn pool(e: Env) -> Address {
+        storage::get_pool(&e)
+    }
+}
+
+#[contractimpl]
+impl Token for BackstopShareTokenContract {
+    fn allowance(e: Env, from: Address, spender: Address) -> i128 {
+        storage::get_allowance(&e, &from, &spender).amount
+    }
+
+    fn approve(e: Env, from: Address, spender: Address, amount: i128, live_until_ledger: u32) {
+        storage::extend_instance(&e);
+        from.require_auth();
+        require_nonnegative(&e, amount);
+        if amount > 0 && live_until_ledger < e.ledger().sequence() {
+            panic_with_error!(&e, ShareTokenError::InvalidExpiration);
+        }
+
+        storage::set_allowance(
+            &e,
+            &from,
+            &spender,
+            &AllowanceValue {
+                amount,
+                live_until_ledger,
+            },
+        );
+
+        TokenEvents::approve(&e, from, spender, amount, live_until_ledger);
+    }
+
+    fn balance(e: Env, id: Address) -> i128 {
+        storage::get_balance(&e, &id)
+    }
+
+    fn transfer(e: Env, from: Address, to: Address, amount: i128) {
+        storage::extend_instance(&e);
+        from.require_auth();
+        require_nonnegative(&e, amount);
+
+        move_balance(&e, &from, &to, amount);
+
+        TokenEvents::transfer(&e, from, to, amount);
+    }
+
+    fn transfer_from(e: Env, spender: Address, from: Address, to: Address, amount: i128) {
+        storage::extend_instance(&e);
+        spender.require_auth();
+        require_nonnegative(&e, amount);
+
+        spend_allowance(&e, &from, &spender, amount);
+        move_balance(&e, &from, &to, amount);
+
+        TokenEvents::transfer(&e, from, to, amount);
+    }
+
+    fn burn(e: Env, from: Address, amount: i128) {
+        storage::extend_instance(&e);
+        from.require_auth();
+        require_nonnegative(&e, amount);
+
+        burn_balance(&e, &from, amount);
+
+        TokenEvents::burn(&e, from, amount);
+    }
+
+    fn burn_from(e: Env, spender: Address, from: Address, amount: i128) {
+        storage::extend_instance(&e);
+        spender.require_auth();
+        require_nonnegative(&e, amount);
+
+        spend_allowance(&e, &from, &spender, amount);
+        burn_balance(&e, &from, amount);
+
+        TokenEvents::burn(&e, from, amount);
+    }
+
+    fn decimals(_e: Env) -> u32 {
+        // backstop shares are 7 decimals
+        7
+    }
+
+    fn name(e: Env) -> String {
+        storage::get_name(&e)
+    }
+
+    fn symbol(e: Env) -> String {
+        storage::get_symbol(&e)
+    }
+}
+
+/// Require that an amount is not negative
+///
+/// ### Panics
+/// If the amount is negative
+fn require_nonnegative(e: &Env, amount: i128) {
+    if amount < 0 {
+        panic_with_error!(e, ShareTokenError::NegativeAmountError);
+    }
+}
+
+/// Remove `amount` share tokens from the balance of `id`
+///
+/// ### Panics
+/// If `id` does not have enough share tokens
+fn spend_balance(e: &Env, id: &Address, amount: i128) {
+    let balance = storage::get_balance(e, id);
+    if balance < amount {
+        panic_with_error!(e, ShareTokenError::BalanceError);
+    }
+    storage::set_balance(e, id, balance - amount);
+}
+
+/// Add `amount` share tokens to the balance of `id`
+fn receive_balance(e: &Env, id: &Address, amount: i128) {
+    let balance = storage::get_balance(e, id);
+    storage::set_balance(e, id, balance + amount);
+}
+
+/// Move `amount` share tokens from `from` to `to`, after both accrue their emissions
+///
+/// ### Panics
+/// If `from` does not have enough share tokens
+fn move_balance(e: &Env, from: &Address, to: &Address, amount: i128) {
+    let index = emissions::update_index(e);
+    emissions::update_user(e, from, index);
+    emissions::update_user(e, to, index);
+    spend_balance(e, from, amount);
+    receive_balance(e, to, amount);
+}
+
+/// Burn `amount` share tokens held by `from`, after they accrue their emissions. The wrapped
+/// shares behind the burnt share tokens are distributed to the remaining holders.
+///
+/// ### Panics
+/// If `from` does not have enough share tokens
+fn burn_balance(e: &Env, from: &Address, amount: i128) {
+    let index = emissions::update_index(e);
+    emissions::update_user(e, from, index);
+    spend_balance(e, from, amount);
+    storage::set_supply(e, storage::get_supply(e) - amount);
+}
+
+/// Consume `amount` of the allowance of `spender` for share tokens held by `from`
+///
+/// ### Panics
+/// If `spender` does not have enough allowance
+fn spend_allowance(e: &Env, from: &Address, spender: &Address, amount: i128) {
+    let mut allowance = storage::get_allowance(e, from, spender);
+    if allowance.amount < amount {
+        panic_with_error!(e, ShareTokenError::AllowanceError);
+    }
+    if amount > 0 {
+        allowance.amount -= amount;
+        storage::set_allowance(e, from, spender, &allowance);
+    }
+}
@@ -0,0 +1,327 @@
+use soroban_sdk::{contracttype, unwrap::UnwrapOptimized, Address, Env, String, Symbol};
+
+/********** Ledger Thresholds **********/
+
+const ONE_DAY_LEDGERS: u32 = 17280; // assumes 5s a ledger
+
+const LEDGER_THRESHOLD_INSTANCE: u32 = ONE_DAY_LEDGERS * 30; // ~ 30 days
+const LEDGER_BUMP_INSTANCE: u32 = LEDGER_THRESHOLD_INSTANCE + ONE_DAY_LEDGERS; // ~ 31 days
+
+const LEDGER_THRESHOLD_USER: u32 = ONE_DAY_LEDGERS * 100; // ~ 100 days
+const LEDGER_BUMP_USER: u32 = LEDGER_THRESHOLD_USER + 20 * ONE_DAY_LEDGERS; // ~ 120 days
+
+/********** Storage Types **********/
+
+const IS_INIT_KEY: &str = "IsInit";
+const BACKSTOP_KEY: &str = "Backstop";
+const POOL_KEY: &str = "Pool";
+const NAME_KEY: &str = "Name";
+const SYMBOL_KEY: &str = "Symbol";
+const SUPPLY_KEY: &str = "Supply";
+const INDEX_KEY: &str = "Index";
+const UNCLAIMED_KEY: &str = "Unclaimed";
+
+#[derive(Clone)]
+#[contracttype]
+pub struct AllowanceKey {
+    pub from: Address,
+    pub spender: Address,
+}
+
+/// An allowance for a spender to transfer or burn share tokens on behalf of a holder
+#[derive(Clone)]
+#[contracttype]
+pub struct AllowanceValue {
+    pub amount: i128,           // the amount of share tokens the spender can use
+    pub live_until_ledger: u32, // the last ledger the allowance can be used in
+}
+
+/// The emissions state of a share token holder
+#[derive(Clone)]
+#[contracttype]
+pub struct UserEmissionData {
+    pub index: i128,   // the emissions index of the holder's last update
+    pub accrued: i128, // the backstop shares accrued from emissions and not yet claimed
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub enum ShareTokenDataKey {
+    // The share token balance of an address
+    Balance(Address),
+    // The allowance of a spender for a holder's share tokens
+    Allowance(AllowanceKey),
+    // The emissions state of a share token holder
+    UserEmis(Address),
+}
+
+/// Bump the instance rent for the contract
+pub fn extend_instance(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(LEDGER_THRESHOLD_INSTANCE, LEDGER_BUMP_INSTANCE);
+}
+
+/********** Init **********/
+
+/// Check if the contract has been initialized
+pub fn get_is_init(e: &Env) -> bool {
+    e.storage().instance().has(&Symbol::new(e, IS_INIT_KEY))
+}
+
+/// Set the contract as initialized
+pub fn set_is_init(e: &Env) {
+    e.storage()
+        .instance()
+        .set::<Symbol, bool>(&Symbol::new(e, IS_INIT_KEY), &true);
+}
+
+/********** Config **********/
+
+/// Fetch the backstop Address
+pub fn get_backstop(e: &Env) -> Address {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, BACKSTOP_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the backstop Address
+///
+/// ### Arguments
+/// * `backstop` - The Address of the backstop
+pub fn set_backstop(e: &Env, backstop: &Address) {
+    e.storage()
+        .instance()
+        .set::<Symbol, Address>(&Symbol::new(e, BACKSTOP_KEY), backstop);
+}
+
+/// Fetch the Address of the pool whose backstop shares are wrapped
+pub fn get_pool(e: &Env) -> Address {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, POOL_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the Address of the pool whose backstop shares are wrapped
+///
+/// ### Arguments
+/// * `pool` - The Address of the pool
+pub fn set_pool(e: &Env, pool: &Address) {
+    e.storage()
+        .instance()
+        .set::<Symbol, Address>(&Symbol::new(e, POOL_KEY), pool);
+}
+
+/// Fetch the name of the token
+pub fn get_name(e: &Env) -> String {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, NAME_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the name of the token
+///
+/// ### Arguments
+/// * `name` - The name of the token
+pub fn set_name(e: &Env, name: &String) {
+    e.storage()
+        .instance()
+        .set::<Symbol, String>(&Symbol::new(e, NAME_KEY), name);
+}
+
+/// Fetch the symbol of the token
+pub fn get_symbol(e: &Env) -> String {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, SYMBOL_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the symbol of the token
+///
+/// ### Arguments
+/// * `symbol` - The symbol of the token
+pub fn set_symbol(e: &Env, symbol: &String) {
+    e.storage()
+        .instance()
+        .set::<Symbol, String>(&Symbol::new(e, SYMBOL_KEY), symbol);
+}
+
+/********** Supply **********/
+
+/// Fetch the total supply of share tokens
+pub fn get_supply(e: &Env) -> i128 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, SUPPLY_KEY))
+        .unwrap_or(0)
+}
+
+/// Set the total supply of share tokens
+///
+/// ### Arguments
+/// * `supply` - The total supply of share tokens
+pub fn set_supply(e: &Env, supply: i128) {
+    e.storage()
+        .instance()
+        .set::<Symbol, i128>(&Symbol::new(e, SUPPLY_KEY), &supply);
+}
+
+/********** Emissions **********/
+
+/// Fetch the emissions index, the backstop shares earned per share token scaled by 14 decimals
+pub fn get_index(e: &Env) -> i128 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, INDEX_KEY))
+        .unwrap_or(0)
+}
+
+/// Set the emissions index
+///
+/// ### Arguments
+/// * `index` - The emissions index
+pub fn set_index(e: &Env, index: i128) {
+    e.storage()
+        .instance()
+        .set::<Symbol, i128>(&Symbol::new(e, INDEX_KEY), &index);
+}
+
+/// Fetch the backstop shares held by the share token that were earned from emissions and not
+/// yet claimed by holders
+pub fn get_unclaimed(e: &Env) -> i128 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, UNCLAIMED_KEY))
+        .unwrap_or(0)
+}
+
+/// Set the backstop shares earned from emissions and not yet claimed by holders
+///
+/// ### Arguments
+/// * `unclaimed` - The unclaimed backstop shares
+pub fn set_unclaimed(e: &Env, unclaimed: i128) {
+    e.storage()
+        .instance()
+        .set::<Symbol, i128>(&Symbol::new(e, UNCLAIMED_KEY), &unclaimed);
+}
+
+/// Fetch the emissions state of a share token holder
+///
+/// ### Arguments
+/// * `id` - The address holding share tokens
+pub fn get_user_emis_data(e: &Env, id: &Address) -> Option<UserEmissionData> {
+    let key = ShareTokenDataKey::UserEmis(id.clone());
+    let result = e
+        .storage()
+        .persistent()
+        .get::<ShareTokenDataKey, UserEmissionData>(&key);
+    if result.is_some() {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+    }
+    result
+}
+
+/// Set the emissions state of a share token holder
+///
+/// ### Arguments
+/// * `id` - The address holding share tokens
+/// * `data` - The emissions state
+pub fn set_user_emis_data(e: &Env, id: &Address, data: &UserEmissionData) {
+    let key = ShareTokenDataKey::UserEmis(id.clone());
+    e.storage()
+        .persistent()
+        .set::<ShareTokenDataKey, UserEmissionData>(&key, data);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/********** Balances **********/
+
+/// Fetch the share token balance of an address
+///
+/// ### Arguments
+/// * `id` - The address holding share tokens
+pub fn get_balance(e: &Env, id: &Address) -> i128 {
+    let key = ShareTokenDataKey::Balance(id.clone());
+    if let Some(balance) = e
+        .storage()
+        .persistent()
+        .get::<ShareTokenDataKey, i128>(&key)
+    {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+        balance
+    } else {
+        0
+    }
+}
+
+/// Set the share token balance of an address
+///
+/// ### Arguments
+/// * `id` - The address holding share tokens
+/// * `balance` - The share token balance
+pub fn set_balance(e: &Env, id: &Address, balance: i128) {
+    let key = ShareTokenDataKey::Balance(id.clone());
+    e.storage()
+        .persistent()
+        .set::<ShareTokenDataKey, i128>(&key, &balance);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/********** Allowances **********/
+
+/// Fetch the allowance of a spender for a holder's share tokens. An expired allowance is returned
+/// with an amount of zero.
+///
+/// ### Arguments
+/// * `from` - The address holding share tokens
+/// * `spender` - The address spending share tokens
+pub fn get_allowance(e: &Env, from: &Address, spender: &Address) -> AllowanceValue {
+    let key = ShareTokenDataKey::Allowance(AllowanceKey {
+        from: from.clone(),
+        spender: spender.clone(),
+    });
+    match e
+        .storage()
+        .temporary()
+        .get::<ShareTokenDataKey, AllowanceValue>(&key)
+    {
+        Some(allowance) if allowance.live_until_ledger >= e.ledger().sequence() => allowance,
+        _ => AllowanceValue {
+            amount: 0,
+            live_until_ledger: 0,
+        },
+    }
+}
+
+/// Set the allowance of a spender for a holder's share tokens. The allowance is kept in temporary
+/// storage until it expires.
+///
+/// ### Arguments
+/// * `from` - The address holding share tokens
+/// * `spender` - The address spending share tokens
+/// * `allowance` - The allowance
+pub fn set_allowance(e: &Env, from: &Address, spender: &Address, allowance: &AllowanceValue) {
+    let key = ShareTokenDataKey::Allowance(AllowanceKey {
+        from: from.clone(),
+        spender: spender.clone(),
+    });
+    e.storage()
+        .temporary()
+        .set::<ShareTokenDataKey, AllowanceValue>(&key, allowance);
+    if allowance.amount > 0 {
+        let live_for = allowance.live_until_ledger - e.ledger().sequence();
+        e.storage().temporary().extend_ttl(&key, live_for, live_for);
+    }
+}
@@ -2,7 +2,10 @@ use crate::{contract::require_nonnegative, emissions, storage, BackstopError};
 use sep_41_token::TokenClient;
 use soroban_sdk::{panic_with_error, Address, Env};
 
-use super::require_is_from_pool_factory;
+use super::{
+    checkpoint_voting_weight, release_expired_lock, require_is_from_pool_factory,
+    require_not_winding_down,
+};
 
 /// Perform a deposit into the backstop module
 pub fn execute_deposit(e: &Env, from: &Address, pool_address: &Address, amount: i128) -> i128 {
@@ -10,11 +13,13 @@ pub fn execute_deposit(e: &Env, from: &Address, pool_address: &Address, amount:
     if from == pool_address || from == &e.current_contract_address() {
         panic_with_error!(e, &BackstopError::BadRequest)
     }
+    require_not_winding_down(e, pool_address);
     let mut pool_balance = storage::get_pool_balance(e, pool_address);
     require_is_from_pool_factory(e, pool_address, pool_balance.shares);
     let mut user_balance = storage::get_user_balance(e, pool_address, from);
 
     emissions::update_emissions(e, pool_address, &pool_balance, from, &user_balance);
+    release_expired_lock(e, pool_address, from);
 
     let backstop_token_client = TokenClient::new(e, &storage::get_backstop_token(e));
     backstop_token_client.transfer(from, &e.current_contract_address(), &amount);
@@ -26,6 +31,7 @@ pub fn execute_deposit(e: &Env, from: &Address, pool_address: &Address, amount:
     pool_balance.deposit(amount, to_mint);
     user_balance.add_shares(to_mint);
 
+    checkpoint_voting_weight(e, pool_address, from, user_balance.shares);
     storage::set_pool_balance(e, pool_address, &pool_balance);
     storage::set_user_balance(e, pool_address, from, &user_balance);
 
@@ -83,6 +89,9 @@ mod tests {
             let new_user_balance_0 = storage::get_user_balance(&e, &pool_0_id, &samwise);
             assert_eq!(new_user_balance_0.shares, shares_0);
             assert_eq!(shares_0, 15_0000000);
+            let vote_checkpoints = storage::get_vote_checkpoints(&e, &pool_0_id, &samwise);
+            assert_eq!(vote_checkpoints.len(), 1);
+            assert_eq!(vote_checkpoints.get_unchecked(0).shares, shares_0);
 
             let new_pool_1_balance = storage::get_pool_balance(&e, &pool_1_id);
             assert_eq!(new_pool_1_balance.shares, 70_0000000);
@@ -1,5 +1,8 @@
 use crate::{
-    constants::SCALAR_7, contract::require_nonnegative, dependencies::CometClient, storage,
+    constants::{DONATION_WINDOW, LP_TOKEN_VAL_REFRESH_INTERVAL, SCALAR_7},
+    contract::require_nonnegative,
+    dependencies::CometClient,
+    storage::{self, BackstopDonationData},
     BackstopError,
 };
 use sep_41_token::TokenClient;
@@ -38,6 +41,30 @@ pub fn execute_donate(e: &Env, from: &Address, pool_address: &Address, amount: i
 
     pool_balance.deposit(amount, 0);
     storage::set_pool_balance(e, pool_address, &pool_balance);
+
+    record_donation(e, pool_address, amount);
+}
+
+/// Record a donation to a pool's backstop in the pool's trailing donation data
+fn record_donation(e: &Env, pool_address: &Address, amount: i128) {
+    let now = e.ledger().timestamp();
+    let mut donations =
+        storage::get_pool_donations(e, pool_address).unwrap_or(BackstopDonationData {
+            total: 0,
+            prev_total: 0,
+            prev_time: now,
+            cur_total: 0,
+            cur_time: now,
+        });
+    if now - donations.cur_time >= DONATION_WINDOW {
+        // start a new window
+        donations.prev_total = donations.cur_total;
+        donations.prev_time = donations.cur_time;
+        donations.cur_total = donations.total;
+        donations.cur_time = now;
+    }
+    donations.total += amount;
+    storage::set_pool_donations(e, pool_address, &donations);
 }
 
 /// Perform an update to the Comet LP token underlying value
@@ -61,12 +88,33 @@ pub fn execute_update_comet_token_value(
 
     let lp_token_val = (blnd_per_tkn, usdc_per_tkn);
     storage::set_lp_token_val(e, &lp_token_val);
+    storage::set_lp_token_val_time(e, e.ledger().timestamp());
     lp_token_val
 }
 
+/// Refresh the Comet LP token underlying value if it is unset or has not been updated within
+/// the refresh interval
+pub fn refresh_comet_token_value(e: &Env) {
+    if storage::has_lp_token_val(e)
+        && storage::get_lp_token_val_time(e) + LP_TOKEN_VAL_REFRESH_INTERVAL
+            > e.ledger().timestamp()
+    {
+        return;
+    }
+    execute_update_comet_token_value(
+        e,
+        &storage::get_backstop_token(e),
+        &storage::get_blnd_token(e),
+        &storage::get_usdc_token(e),
+    );
+}
+
 #[cfg(test)]
 mod tests {
-    use soroban_sdk::{testutils::Address as _, Address};
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        Address,
+    };
 
     use crate::{
         backstop::execute_deposit,
@@ -108,6 +156,87 @@ mod tests {
             let new_pool_balance = storage::get_pool_balance(&e, &pool_0_id);
             assert_eq!(new_pool_balance.shares, 25_0000000);
             assert_eq!(new_pool_balance.tokens, 55_0000000);
+
+            let donations = storage::get_pool_donations(&e, &pool_0_id).unwrap_optimized();
+            assert_eq!(donations.total, 30_0000000);
+            assert_eq!(donations.prev_total, 0);
+            assert_eq!(donations.cur_total, 0);
+        });
+    }
+
+    #[test]
+    fn test_execute_donate_rolls_donation_window() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.budget().reset_unlimited();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop_id = create_backstop(&e);
+        let pool_0_id = Address::generate(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_id, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+        backstop_token_client.mint(&frodo, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_id);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_id, || {
+            execute_deposit(&e, &frodo, &pool_0_id, 25_0000000);
+            execute_donate(&e, &samwise, &pool_0_id, 1_0000000);
+        });
+
+        // donation within the window does not start a new window
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000 + DONATION_WINDOW - 1,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        e.as_contract(&backstop_id, || {
+            execute_donate(&e, &samwise, &pool_0_id, 2_0000000);
+
+            let donations = storage::get_pool_donations(&e, &pool_0_id).unwrap_optimized();
+            assert_eq!(donations.total, 3_0000000);
+            assert_eq!(donations.cur_total, 0);
+            assert_eq!(donations.cur_time, 1500000000);
+        });
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000 + DONATION_WINDOW,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        e.as_contract(&backstop_id, || {
+            execute_donate(&e, &samwise, &pool_0_id, 4_0000000);
+
+            let donations = storage::get_pool_donations(&e, &pool_0_id).unwrap_optimized();
+            assert_eq!(donations.total, 7_0000000);
+            assert_eq!(donations.prev_total, 0);
+            assert_eq!(donations.prev_time, 1500000000);
+            assert_eq!(donations.cur_total, 3_0000000);
+            assert_eq!(donations.cur_time, 1500000000 + DONATION_WINDOW);
         });
     }
 
@@ -352,4 +481,71 @@ mod tests {
             assert_eq!(usdc_per_tkn, 0_2500000);
         });
     }
+
+    #[test]
+    fn test_refresh_comet_token_value() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.budget().reset_unlimited();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 10000,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop_id = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+
+        let (usdc_token, _) = create_usdc_token(&e, &backstop_id, &bombadil);
+        let (blnd_token, _) = create_blnd_token(&e, &backstop_id, &bombadil);
+        let (comet_id, _) = create_comet_lp_pool(&e, &bombadil, &blnd_token, &usdc_token);
+
+        e.as_contract(&backstop_id, || {
+            storage::set_backstop_token(&e, &comet_id);
+
+            // an unset value is always refreshed
+            refresh_comet_token_value(&e);
+            assert_eq!(storage::get_lp_token_val(&e), (10_0000000, 0_2500000));
+            assert_eq!(storage::get_lp_token_val_time(&e), 10000);
+
+            // a recent value is not refreshed
+            storage::set_lp_token_val(&e, &(1, 1));
+            e.ledger().set(LedgerInfo {
+                timestamp: 10000 + LP_TOKEN_VAL_REFRESH_INTERVAL - 1,
+                protocol_version: 20,
+                sequence_number: 200,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 10,
+                min_persistent_entry_ttl: 10,
+                max_entry_ttl: 3110400,
+            });
+            refresh_comet_token_value(&e);
+            assert_eq!(storage::get_lp_token_val(&e), (1, 1));
+
+            // a stale value is refreshed
+            e.ledger().set(LedgerInfo {
+                timestamp: 10000 + LP_TOKEN_VAL_REFRESH_INTERVAL,
+                protocol_version: 20,
+                sequence_number: 300,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 10,
+                min_persistent_entry_ttl: 10,
+                max_entry_ttl: 3110400,
+            });
+            refresh_comet_token_value(&e);
+            assert_eq!(storage::get_lp_token_val(&e), (10_0000000, 0_2500000));
+            assert_eq!(
+                storage::get_lp_token_val_time(&e),
+                10000 + LP_TOKEN_VAL_REFRESH_INTERVAL
+            );
+        });
+    }
 }
@@ -0,0 +1,354 @@
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{contracttype, panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::{DEPOSIT_LOCK_TIERS, SCALAR_7},
+    emissions,
+    errors::BackstopError,
+    storage,
+};
+
+use super::{execute_deposit, UserBalance};
+
+/// A lockup of a user's deposit that earns emissions on bonus shares until it unlocks
+#[derive(Clone)]
+#[contracttype]
+pub struct DepositLock {
+    pub shares: i128,     // the amount of shares that cannot be queued for withdrawal
+    pub bonus: i128,      // the additional shares emissions are earned on
+    pub unlock_time: u64, // the time the shares can be queued for withdrawal
+}
+
+/// Perform a deposit into the backstop module and lock the minted shares for the lock time of
+/// `lock_tier`. The minted shares earn emissions at the tier's multiplier until the lock is
+/// released by the user's first deposit, withdrawal queue action or claim after it unlocks, or
+/// by anyone calling `release_lock`.
+///
+/// Locking into a user's existing lockup adds the shares to it and keeps the later unlock time.
+///
+/// Returns a tuple of (shares minted, the user's lockup)
+///
+/// ### Panics
+/// If the lock tier does not exist or the deposit fails
+pub fn execute_deposit_with_lock(
+    e: &Env,
+    from: &Address,
+    pool_address: &Address,
+    amount: i128,
+    lock_tier: u32,
+) -> (i128, DepositLock) {
+    if lock_tier == 0 || lock_tier as usize > DEPOSIT_LOCK_TIERS.len() {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+    let (lock_time, multiplier) = DEPOSIT_LOCK_TIERS[lock_tier as usize - 1];
+
+    // emissions are updated and any expired lockup is released during the deposit
+    let to_mint = execute_deposit(e, from, pool_address, amount);
+
+    let bonus = to_mint
+        .fixed_mul_floor(multiplier - SCALAR_7, SCALAR_7)
+        .unwrap_optimized();
+    let unlock_time = e.ledger().timestamp() + lock_time;
+    let mut lock = storage::get_deposit_lock(e, pool_address, from).unwrap_or(DepositLock {
+        shares: 0,
+        bonus: 0,
+        unlock_time,
+    });
+    lock.shares += to_mint;
+    lock.bonus += bonus;
+    lock.unlock_time = lock.unlock_time.max(unlock_time);
+
+    storage::set_deposit_lock(e, pool_address, from, &lock);
+    storage::set_lock_bonus(
+        e,
+        pool_address,
+        storage::get_lock_bonus(e, pool_address) + bonus,
+    );
+    (to_mint, lock)
+}
+
+/// Release a user's lockup if it has unlocked, removing its bonus shares from the pool
+///
+/// The user's emissions MUST be updated before calling so the bonus shares are accrued through
+/// the current time.
+pub fn release_expired_lock(e: &Env, pool_address: &Address, user: &Address) {
+    if let Some(lock) = storage::get_deposit_lock(e, pool_address, user) {
+        if lock.unlock_time <= e.ledger().timestamp() {
+            storage::set_lock_bonus(
+                e,
+                pool_address,
+                storage::get_lock_bonus(e, pool_address) - lock.bonus,
+            );
+            storage::del_deposit_lock(e, pool_address, user);
+        }
+    }
+}
+
+/// Release a user's lockup once it has unlocked, so its bonus shares stop earning emissions
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+/// * `user` - The user whose lockup is released
+///
+/// ### Panics
+/// If the user has no lockup, or it has not unlocked
+pub fn execute_release_lock(e: &Env, pool_address: &Address, user: &Address) {
+    match storage::get_deposit_lock(e, pool_address, user) {
+        Some(lock) if lock.unlock_time <= e.ledger().timestamp() => {}
+        Some(_) => panic_with_error!(e, BackstopError::DepositLocked),
+        None => panic_with_error!(e, BackstopError::BadRequest),
+    }
+    let pool_balance = storage::get_pool_balance(e, pool_address);
+    let user_balance = storage::get_user_balance(e, pool_address, user);
+    emissions::update_emissions(e, pool_address, &pool_balance, user, &user_balance);
+    release_expired_lock(e, pool_address, user);
+}
+
+/// Require that queueing `to_queue` shares for withdrawal leaves the user's locked shares in place
+///
+/// ### Panics
+/// If the user's lockup has not been released and would be queued for withdrawal
+pub fn require_unlocked_shares(
+    e: &Env,
+    pool_address: &Address,
+    user: &Address,
+    user_balance: &UserBalance,
+    to_queue: i128,
+) {
+    if let Some(lock) = storage::get_deposit_lock(e, pool_address, user) {
+        if user_balance.shares - to_queue < lock.shares {
+            panic_with_error!(e, BackstopError::DepositLocked);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        Address,
+    };
+
+    use crate::{
+        backstop::execute_queue_withdrawal,
+        testutils::{create_backstop, create_backstop_token, create_mock_pool_factory},
+    };
+
+    use super::*;
+
+    fn set_timestamp(e: &Env, timestamp: u64) {
+        e.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+    }
+
+    #[test]
+    fn test_execute_deposit_with_lock() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+        set_timestamp(&e, 1_000_000);
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_0_id = Address::generate(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_address, || {
+            let (shares, lock) = execute_deposit_with_lock(&e, &samwise, &pool_0_id, 40_0000000, 2);
+            assert_eq!(shares, 40_0000000);
+            assert_eq!(lock.shares, 40_0000000);
+            assert_eq!(lock.bonus, 10_0000000);
+            assert_eq!(lock.unlock_time, 1_000_000 + DEPOSIT_LOCK_TIERS[1].0);
+            assert_eq!(storage::get_lock_bonus(&e, &pool_0_id), 10_0000000);
+            assert_eq!(
+                storage::get_user_balance(&e, &pool_0_id, &samwise).shares,
+                40_0000000
+            );
+        });
+
+        // a shorter lock is added to the existing lock without moving its unlock time
+        e.as_contract(&backstop_address, || {
+            let (shares, lock) = execute_deposit_with_lock(&e, &samwise, &pool_0_id, 20_0000000, 1);
+            assert_eq!(shares, 20_0000000);
+            assert_eq!(lock.shares, 60_0000000);
+            assert_eq!(lock.bonus, 12_0000000);
+            assert_eq!(lock.unlock_time, 1_000_000 + DEPOSIT_LOCK_TIERS[1].0);
+            assert_eq!(storage::get_lock_bonus(&e, &pool_0_id), 12_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_execute_deposit_with_lock_invalid_tier() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_0_id = Address::generate(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit_with_lock(&e, &samwise, &pool_0_id, 40_0000000, 4);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1011)")]
+    fn test_queue_withdrawal_locked_shares() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+        set_timestamp(&e, 1_000_000);
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_0_id = Address::generate(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &samwise, &pool_0_id, 10_0000000);
+            execute_deposit_with_lock(&e, &samwise, &pool_0_id, 40_0000000, 1);
+        });
+
+        // unlocked shares can still be queued
+        e.as_contract(&backstop_address, || {
+            execute_queue_withdrawal(&e, &samwise, &pool_0_id, 10_0000000);
+        });
+
+        set_timestamp(&e, 1_000_000 + DEPOSIT_LOCK_TIERS[0].0 - 1);
+        e.as_contract(&backstop_address, || {
+            execute_queue_withdrawal(&e, &samwise, &pool_0_id, 1);
+        });
+    }
+
+    #[test]
+    fn test_queue_withdrawal_releases_expired_lock() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+        set_timestamp(&e, 1_000_000);
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool_0_id = Address::generate(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+        backstop_token_client.mint(&frodo, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit_with_lock(&e, &samwise, &pool_0_id, 40_0000000, 3);
+        });
+        e.as_contract(&backstop_address, || {
+            execute_deposit_with_lock(&e, &frodo, &pool_0_id, 10_0000000, 1);
+        });
+
+        set_timestamp(&e, 1_000_000 + DEPOSIT_LOCK_TIERS[2].0);
+        e.as_contract(&backstop_address, || {
+            execute_queue_withdrawal(&e, &samwise, &pool_0_id, 40_0000000);
+
+            assert!(storage::get_deposit_lock(&e, &pool_0_id, &samwise).is_none());
+            assert_eq!(storage::get_lock_bonus(&e, &pool_0_id), 1_0000000);
+            let user_balance = storage::get_user_balance(&e, &pool_0_id, &samwise);
+            assert_eq!(user_balance.shares, 0);
+            assert_eq!(user_balance.q4w.len(), 1);
+        });
+    }
+
+    #[test]
+    fn test_execute_release_lock() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+        set_timestamp(&e, 1_000_000);
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_0_id = Address::generate(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit_with_lock(&e, &samwise, &pool_0_id, 40_0000000, 1);
+        });
+
+        set_timestamp(&e, 1_000_000 + DEPOSIT_LOCK_TIERS[0].0);
+        e.as_contract(&backstop_address, || {
+            execute_release_lock(&e, &pool_0_id, &samwise);
+
+            assert!(storage::get_deposit_lock(&e, &pool_0_id, &samwise).is_none());
+            assert_eq!(storage::get_lock_bonus(&e, &pool_0_id), 0);
+            assert_eq!(
+                storage::get_user_balance(&e, &pool_0_id, &samwise).shares,
+                40_0000000
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1011)")]
+    fn test_execute_release_lock_not_unlocked() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+        set_timestamp(&e, 1_000_000);
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_0_id = Address::generate(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit_with_lock(&e, &samwise, &pool_0_id, 40_0000000, 1);
+        });
+
+        set_timestamp(&e, 1_000_000 + DEPOSIT_LOCK_TIERS[0].0 - 1);
+        e.as_contract(&backstop_address, || {
+            execute_release_lock(&e, &pool_0_id, &samwise);
+        });
+    }
+}
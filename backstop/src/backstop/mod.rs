@@ -1,17 +1,37 @@
 mod deposit;
 pub use deposit::execute_deposit;
 
+mod lock;
+pub use lock::{
+    execute_deposit_with_lock, execute_release_lock, release_expired_lock, require_unlocked_shares,
+    DepositLock,
+};
+
 mod fund_management;
-pub use fund_management::{execute_donate, execute_draw, execute_update_comet_token_value};
+pub use fund_management::{
+    execute_donate, execute_draw, execute_update_comet_token_value, refresh_comet_token_value,
+};
+
+mod transfer;
+pub use transfer::execute_transfer_shares;
 
 mod withdrawal;
-pub use withdrawal::{execute_dequeue_withdrawal, execute_queue_withdrawal, execute_withdraw};
+pub use withdrawal::{
+    execute_dequeue_withdrawal, execute_queue_withdrawal, execute_sweep_expired, execute_withdraw,
+};
 
 mod pool;
 pub use pool::{
-    load_pool_backstop_data, require_is_from_pool_factory, require_pool_above_threshold,
-    PoolBackstopData, PoolBalance,
+    calc_pool_coverage, execute_register_pool, load_pool_backstop_apr, load_pool_backstop_data,
+    load_pool_liabilities, require_is_from_pool_factory, require_pool_above_threshold,
+    update_pool_coverage, BackstopApr, PoolBackstopData, PoolBalance,
 };
 
 mod user;
 pub use user::{UserBalance, Q4W};
+
+mod wind_down;
+pub use wind_down::{execute_wind_down, load_q4w_waiver, require_not_winding_down};
+
+mod votes;
+pub use votes::{checkpoint_voting_weight, load_voting_weight, VoteSnapshot};
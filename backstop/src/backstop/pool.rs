@@ -1,7 +1,17 @@
 use soroban_fixed_point_math::FixedPoint;
-use soroban_sdk::{contracttype, panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+use soroban_sdk::{contracttype, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Symbol};
 
-use crate::{constants::SCALAR_7, dependencies::PoolFactoryClient, errors::BackstopError, storage};
+use cast::i128;
+
+use crate::{
+    constants::{
+        COVERAGE_THRESHOLD, DONATION_WINDOW, MAX_Q4W_LOCK_TIME, MIN_Q4W_LOCK_TIME, SCALAR_7,
+        SECONDS_PER_YEAR,
+    },
+    dependencies::{PoolClient, PoolFactoryClient},
+    errors::BackstopError,
+    storage::{self, PoolCoverage},
+};
 
 /// The pool's backstop data
 #[derive(Clone)]
@@ -42,6 +52,141 @@ pub fn load_pool_backstop_data(e: &Env, address: &Address) -> PoolBackstopData {
     }
 }
 
+/// The current yield for a pool's backstop
+#[derive(Clone)]
+#[contracttype]
+pub struct BackstopApr {
+    pub emission_apr: i128, // the APR from BLND emissions, valued in BLND (7 decimals)
+    pub interest_apr: i128, // the trailing APR from interest donated by the pool (7 decimals)
+}
+
+/// Calculate the current yield for a pool's backstop from its emission config and
+/// trailing donation data
+///
+/// ### Arguments
+/// * `address` - The address of the pool
+#[allow(clippy::zero_prefixed_literal)]
+pub fn load_pool_backstop_apr(e: &Env, address: &Address) -> BackstopApr {
+    let pool_balance = storage::get_pool_balance(e, address);
+    let now = e.ledger().timestamp();
+
+    // emissions are distributed to non-queued tokens and valued against the BLND held
+    // by the backstop token, which is 80% of the backstop token's value
+    let mut emission_apr = 0;
+    if let Some(emis_config) = storage::get_backstop_emis_config(e, address) {
+        let (blnd_per_tkn, _) = storage::get_lp_token_val(e);
+        let blnd_value = pool_balance
+            .non_queued_tokens()
+            .fixed_mul_floor(blnd_per_tkn, SCALAR_7)
+            .unwrap_optimized()
+            .fixed_div_floor(0_8000000, SCALAR_7)
+            .unwrap_optimized();
+        if emis_config.expiration > now && blnd_value > 0 {
+            emission_apr = (i128(emis_config.eps) * SECONDS_PER_YEAR)
+                .fixed_div_floor(blnd_value, SCALAR_7)
+                .unwrap_optimized();
+        }
+    }
+
+    let mut interest_apr = 0;
+    if let Some(donations) = storage::get_pool_donations(e, address) {
+        let donated = donations.total - donations.prev_total;
+        let elapsed = (now - donations.prev_time).max(DONATION_WINDOW);
+        if pool_balance.tokens > 0 {
+            interest_apr = donated
+                .fixed_div_floor(pool_balance.tokens, SCALAR_7)
+                .unwrap_optimized()
+                * SECONDS_PER_YEAR
+                / i128(elapsed);
+        }
+    }
+
+    BackstopApr {
+        emission_apr,
+        interest_apr,
+    }
+}
+
+/// Calculate the coverage of a pool's liabilities by its backstop. The backstop is valued at
+/// 5x the USDC held by its backstop tokens.
+///
+/// Returns the coverage ratio with 7 decimals, or i128::MAX if the pool has no liabilities
+///
+/// ### Arguments
+/// * `pool_backstop_data` - The pool's backstop data
+/// * `liabilities` - The pool's total liabilities, valued in USDC with 7 decimals
+pub fn calc_pool_coverage(pool_backstop_data: &PoolBackstopData, liabilities: i128) -> i128 {
+    if liabilities <= 0 {
+        return i128::MAX;
+    }
+    (pool_backstop_data.usdc * 5)
+        .fixed_div_floor(liabilities, SCALAR_7)
+        .unwrap_optimized()
+}
+
+/// Fetch the total liabilities of a pool, valued in USDC by the pool's oracle with 7 decimals, so
+/// they are in the same units as the USDC held by the backstop. If the pool cannot be queried or
+/// its oracle cannot price USDC, the liabilities from the last coverage check are used.
+///
+/// ### Arguments
+/// * `address` - The address of the pool
+pub fn load_pool_liabilities(e: &Env, address: &Address) -> i128 {
+    let usdc = storage::get_usdc_token(e);
+    match PoolClient::new(e, address).try_get_total_liabilities_in(&usdc) {
+        Ok(Ok(liabilities)) => liabilities,
+        _ => storage::get_pool_coverage(e, address).liabilities,
+    }
+}
+
+/// Update the coverage of a pool's liabilities by its backstop, and emit a "coverage_breach"
+/// event if the coverage crossed below the threshold. Also records how long the pool's backstop
+/// has been above the backstop threshold, which gates entry into the reward zone.
+///
+/// ### Arguments
+/// * `address` - The address of the pool
+/// * `liabilities` - The pool's total liabilities, valued in USDC with 7 decimals
+pub fn update_pool_coverage(e: &Env, address: &Address, liabilities: i128) {
+    // the backstop cannot be valued until the LP token value has been set
+    if !storage::has_lp_token_val(e) {
+        return;
+    }
+    let pool_data = load_pool_backstop_data(e, address);
+    checkpoint_threshold(e, address, &pool_data);
+    let prev_coverage = storage::get_pool_coverage(e, address);
+    let coverage = calc_pool_coverage(&pool_data, liabilities);
+    if prev_coverage.coverage >= COVERAGE_THRESHOLD && coverage < COVERAGE_THRESHOLD {
+        e.events().publish(
+            (Symbol::new(e, "coverage_breach"), address.clone()),
+            (coverage, liabilities),
+        );
+    }
+    storage::set_pool_coverage(
+        e,
+        address,
+        &PoolCoverage {
+            liabilities,
+            coverage,
+        },
+    );
+}
+
+/// Record the time a pool's backstop crossed above the backstop threshold, or clear the record
+/// if it is below the threshold
+///
+/// ### Arguments
+/// * `address` - The address of the pool
+/// * `pool_data` - The pool's backstop data
+fn checkpoint_threshold(e: &Env, address: &Address, pool_data: &PoolBackstopData) {
+    let above_since = storage::get_above_threshold_since(e, address);
+    if require_pool_above_threshold(e, pool_data) {
+        if above_since.is_none() {
+            storage::set_above_threshold_since(e, address, e.ledger().timestamp());
+        }
+    } else if above_since.is_some() {
+        storage::del_above_threshold_since(e, address);
+    }
+}
+
 /// Verify the pool address was deployed by the Pool Factory.
 ///
 /// If the pool has an outstanding balance, it is assumed that it was verified before.
@@ -61,25 +206,48 @@ pub fn require_is_from_pool_factory(e: &Env, address: &Address, balance: i128) {
     }
 }
 
+/// Register the Q4W lock time for a pool. The lock time can only be set once.
+///
+/// ### Arguments
+/// * `address` - The pool address
+/// * `lock_time` - The time in seconds that Q4W entries for the pool are locked for
+///
+/// ### Panics
+/// If the lock time is outside of the allowed bounds or the pool is already registered
+pub fn execute_register_pool(e: &Env, address: &Address, lock_time: u64) {
+    if !(MIN_Q4W_LOCK_TIME..=MAX_Q4W_LOCK_TIME).contains(&lock_time) {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+    if storage::has_q4w_lock_time(e, address) {
+        panic_with_error!(e, BackstopError::AlreadyInitializedError);
+    }
+    storage::set_q4w_lock_time(e, address, &lock_time);
+}
+
 /// Calculate the threshold for the pool's backstop balance
 ///
 /// Returns true if the pool's backstop balance is above the threshold
-pub fn require_pool_above_threshold(pool_backstop_data: &PoolBackstopData) -> bool {
+pub fn require_pool_above_threshold(e: &Env, pool_backstop_data: &PoolBackstopData) -> bool {
     // @dev: Calculation for pools product constant of underlying will often overflow i128
     //       so saturating mul is used. This is safe because the threshold is below i128::MAX and the
     //       protocol does not need to differentiate between pools over the threshold product constant.
     //       The calculation is:
-    //        - Threshold % = (bal_blnd^4 * bal_usdc) / PC^5 such that PC is 200k
-    let threshold_pc = 320_000_000_000_000_000_000_000_000i128; // 3.2e26 (200k^5)
+    //        - Threshold % = (bal_blnd^w_blnd * bal_usdc^w_usdc) / PC^(w_blnd + w_usdc)
+    //       which defaults to w_blnd = 4, w_usdc = 1, and PC = 200k
+    let config = storage::get_threshold_config(e);
 
     // floor balances to nearest full unit and calculate saturated pool product constant
     let bal_blnd = pool_backstop_data.blnd / SCALAR_7;
     let bal_usdc = pool_backstop_data.usdc / SCALAR_7;
-    let saturating_pool_pc = bal_blnd
-        .saturating_mul(bal_blnd)
-        .saturating_mul(bal_blnd)
-        .saturating_mul(bal_blnd)
-        .saturating_mul(bal_usdc);
+    let mut saturating_pool_pc: i128 = 1;
+    for _ in 0..config.blnd_weight {
+        saturating_pool_pc = saturating_pool_pc.saturating_mul(bal_blnd);
+    }
+    for _ in 0..config.usdc_weight {
+        saturating_pool_pc = saturating_pool_pc.saturating_mul(bal_usdc);
+    }
+    // the config bounds ensure the threshold product constant does not overflow
+    let threshold_pc = config.pc.pow(config.blnd_weight + config.usdc_weight);
     saturating_pool_pc >= threshold_pc
 }
 
@@ -172,9 +340,16 @@ impl PoolBalance {
 
 #[cfg(test)]
 mod tests {
-    use soroban_sdk::testutils::Address as _;
+    use soroban_sdk::{
+        testutils::{Address as _, Events, Ledger, LedgerInfo},
+        vec, IntoVal,
+    };
 
-    use crate::testutils::{create_backstop, create_mock_pool_factory};
+    use crate::{
+        constants::Q4W_LOCK_TIME,
+        storage::ThresholdConfig,
+        testutils::{create_backstop, create_mock_pool_factory},
+    };
 
     use super::*;
 
@@ -234,6 +409,244 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_calc_pool_coverage() {
+        let pool_backstop_data = PoolBackstopData {
+            tokens: 250_0000000,
+            q4w_pct: 0,
+            blnd: 1_250_0000000,
+            usdc: 12_5000000,
+        };
+
+        assert_eq!(
+            calc_pool_coverage(&pool_backstop_data, 500_0000000),
+            0_1250000
+        );
+        assert_eq!(calc_pool_coverage(&pool_backstop_data, 0), i128::MAX);
+    }
+
+    #[test]
+    fn test_update_pool_coverage() {
+        let e = Env::default();
+
+        let backstop_address = create_backstop(&e);
+        let pool = Address::generate(&e);
+
+        e.as_contract(&backstop_address, || {
+            // skipped until the LP token value is set
+            update_pool_coverage(&e, &pool, 500_0000000);
+            assert_eq!(storage::get_pool_coverage(&e, &pool).coverage, i128::MAX);
+
+            storage::set_pool_balance(
+                &e,
+                &pool,
+                &PoolBalance {
+                    shares: 150_0000000,
+                    tokens: 250_0000000,
+                    q4w: 0,
+                },
+            );
+            storage::set_lp_token_val(&e, &(5_0000000, 0_0500000));
+
+            update_pool_coverage(&e, &pool, 500_0000000);
+            let coverage = storage::get_pool_coverage(&e, &pool);
+            assert_eq!(coverage.liabilities, 500_0000000);
+            assert_eq!(coverage.coverage, 0_1250000);
+            assert_eq!(e.events().all().len(), 0);
+
+            // crossing below the threshold emits a breach
+            update_pool_coverage(&e, &pool, 700_0000000);
+            let coverage = storage::get_pool_coverage(&e, &pool);
+            assert_eq!(coverage.coverage, 0_0892857);
+            let events = e.events().all();
+            assert_eq!(events.len(), 1);
+            assert_eq!(
+                vec![&e, events.last_unchecked()],
+                vec![
+                    &e,
+                    (
+                        backstop_address.clone(),
+                        (Symbol::new(&e, "coverage_breach"), pool.clone()).into_val(&e),
+                        (0_0892857i128, 700_0000000i128).into_val(&e)
+                    )
+                ]
+            );
+
+            // staying below the threshold does not emit another breach
+            update_pool_coverage(&e, &pool, 800_0000000);
+            assert_eq!(e.events().all().len(), 1);
+        });
+    }
+
+    #[test]
+    fn test_update_pool_coverage_checkpoints_threshold() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1_000_000,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop_address = create_backstop(&e);
+        let pool = Address::generate(&e);
+
+        e.as_contract(&backstop_address, || {
+            storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+            storage::set_pool_balance(
+                &e,
+                &pool,
+                &PoolBalance {
+                    shares: 90_000_0000000,
+                    tokens: 100_000_0000000,
+                    q4w: 0,
+                },
+            );
+
+            update_pool_coverage(&e, &pool, 0);
+            assert_eq!(
+                storage::get_above_threshold_since(&e, &pool),
+                Some(1_000_000)
+            );
+
+            // an existing record is kept while the pool stays above the threshold
+            e.ledger().set(LedgerInfo {
+                timestamp: 1_000_500,
+                protocol_version: 20,
+                sequence_number: 200,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 10,
+                min_persistent_entry_ttl: 10,
+                max_entry_ttl: 3110400,
+            });
+            update_pool_coverage(&e, &pool, 0);
+            assert_eq!(
+                storage::get_above_threshold_since(&e, &pool),
+                Some(1_000_000)
+            );
+
+            // falling below the threshold clears the record
+            storage::set_pool_balance(
+                &e,
+                &pool,
+                &PoolBalance {
+                    shares: 90_000_0000000,
+                    tokens: 10_000_0000000,
+                    q4w: 0,
+                },
+            );
+            update_pool_coverage(&e, &pool, 0);
+            assert_eq!(storage::get_above_threshold_since(&e, &pool), None);
+        });
+    }
+
+    #[test]
+    fn test_load_pool_backstop_apr() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop_address = create_backstop(&e);
+        let pool = Address::generate(&e);
+
+        e.as_contract(&backstop_address, || {
+            storage::set_pool_balance(
+                &e,
+                &pool,
+                &PoolBalance {
+                    shares: 150_0000000,
+                    tokens: 250_0000000,
+                    q4w: 50_0000000,
+                },
+            );
+            storage::set_lp_token_val(&e, &(5_0000000, 0_0500000));
+            storage::set_backstop_emis_config(
+                &e,
+                &pool,
+                &storage::BackstopEmissionConfig {
+                    expiration: 1500000000 + 1000,
+                    eps: 0_0010000,
+                },
+            );
+            storage::set_pool_donations(
+                &e,
+                &pool,
+                &storage::BackstopDonationData {
+                    total: 15_0000000,
+                    prev_total: 5_0000000,
+                    prev_time: 1500000000 - 14 * 24 * 60 * 60,
+                    cur_total: 12_0000000,
+                    cur_time: 1500000000 - 7 * 24 * 60 * 60,
+                },
+            );
+
+            let apr = load_pool_backstop_apr(&e, &pool);
+
+            // 31536 BLND emitted per year against 166.6666667 non-queued tokens
+            // valued at 1041.6666668 BLND
+            assert_eq!(apr.emission_apr, 30_2745599);
+            // 10 tokens donated over 14 days against 250 tokens
+            assert_eq!(apr.interest_apr, 1_0428571);
+        });
+    }
+
+    #[test]
+    fn test_load_pool_backstop_apr_no_data() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop_address = create_backstop(&e);
+        let pool = Address::generate(&e);
+
+        e.as_contract(&backstop_address, || {
+            storage::set_pool_balance(
+                &e,
+                &pool,
+                &PoolBalance {
+                    shares: 150_0000000,
+                    tokens: 250_0000000,
+                    q4w: 0,
+                },
+            );
+            storage::set_lp_token_val(&e, &(5_0000000, 0_0500000));
+            // expired emissions do not contribute to the APR
+            storage::set_backstop_emis_config(
+                &e,
+                &pool,
+                &storage::BackstopEmissionConfig {
+                    expiration: 1500000000,
+                    eps: 0_0010000,
+                },
+            );
+
+            let apr = load_pool_backstop_apr(&e, &pool);
+            assert_eq!(apr.emission_apr, 0);
+            assert_eq!(apr.interest_apr, 0);
+        });
+    }
+
     /********** require_is_from_pool_factory **********/
 
     #[test]
@@ -267,6 +680,63 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_execute_register_pool() {
+        let e = Env::default();
+
+        let backstop_address = create_backstop(&e);
+        let pool_address = Address::generate(&e);
+
+        e.as_contract(&backstop_address, || {
+            assert_eq!(storage::get_q4w_lock_time(&e, &pool_address), Q4W_LOCK_TIME);
+            execute_register_pool(&e, &pool_address, MIN_Q4W_LOCK_TIME);
+            assert_eq!(
+                storage::get_q4w_lock_time(&e, &pool_address),
+                MIN_Q4W_LOCK_TIME
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_execute_register_pool_under_min() {
+        let e = Env::default();
+
+        let backstop_address = create_backstop(&e);
+        let pool_address = Address::generate(&e);
+
+        e.as_contract(&backstop_address, || {
+            execute_register_pool(&e, &pool_address, MIN_Q4W_LOCK_TIME - 1);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_execute_register_pool_over_max() {
+        let e = Env::default();
+
+        let backstop_address = create_backstop(&e);
+        let pool_address = Address::generate(&e);
+
+        e.as_contract(&backstop_address, || {
+            execute_register_pool(&e, &pool_address, MAX_Q4W_LOCK_TIME + 1);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #3)")]
+    fn test_execute_register_pool_twice() {
+        let e = Env::default();
+
+        let backstop_address = create_backstop(&e);
+        let pool_address = Address::generate(&e);
+
+        e.as_contract(&backstop_address, || {
+            execute_register_pool(&e, &pool_address, MAX_Q4W_LOCK_TIME);
+            execute_register_pool(&e, &pool_address, MIN_Q4W_LOCK_TIME);
+        });
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #1004)")]
     fn test_require_is_from_pool_factory_not_valid() {
@@ -291,6 +761,7 @@ mod tests {
     fn test_require_pool_above_threshold_under() {
         let e = Env::default();
         e.budget().reset_unlimited();
+        let backstop_address = create_backstop(&e);
 
         let pool_backstop_data = PoolBackstopData {
             blnd: 300_000_0000000,
@@ -299,14 +770,17 @@ mod tests {
             usdc: 25_000_0000000,
         }; // ~91.2% threshold
 
-        let result = require_pool_above_threshold(&pool_backstop_data);
-        assert!(!result);
+        e.as_contract(&backstop_address, || {
+            let result = require_pool_above_threshold(&e, &pool_backstop_data);
+            assert!(!result);
+        });
     }
 
     #[test]
     fn test_require_pool_above_threshold_zero() {
         let e = Env::default();
         e.budget().reset_unlimited();
+        let backstop_address = create_backstop(&e);
 
         let pool_backstop_data = PoolBackstopData {
             blnd: 5_000_0000000,
@@ -315,14 +789,17 @@ mod tests {
             usdc: 1_000_0000000,
         }; // ~3.6% threshold - rounds to zero in calc
 
-        let result = require_pool_above_threshold(&pool_backstop_data);
-        assert!(!result);
+        e.as_contract(&backstop_address, || {
+            let result = require_pool_above_threshold(&e, &pool_backstop_data);
+            assert!(!result);
+        });
     }
 
     #[test]
     fn test_require_pool_above_threshold_over() {
         let e = Env::default();
         e.budget().reset_unlimited();
+        let backstop_address = create_backstop(&e);
 
         let pool_backstop_data = PoolBackstopData {
             blnd: 364_643_0000000,
@@ -331,14 +808,17 @@ mod tests {
             usdc: 18_100_0000000,
         }; // 100% threshold
 
-        let result = require_pool_above_threshold(&pool_backstop_data);
-        assert!(result);
+        e.as_contract(&backstop_address, || {
+            let result = require_pool_above_threshold(&e, &pool_backstop_data);
+            assert!(result);
+        });
     }
 
     #[test]
     fn test_require_pool_above_threshold_saturates() {
         let e = Env::default();
         e.budget().reset_unlimited();
+        let backstop_address = create_backstop(&e);
 
         let pool_backstop_data = PoolBackstopData {
             blnd: 50_000_000_0000000,
@@ -347,8 +827,47 @@ mod tests {
             usdc: 10_000_000_0000000,
         }; // 181x threshold
 
-        let result = require_pool_above_threshold(&pool_backstop_data);
-        assert!(result);
+        e.as_contract(&backstop_address, || {
+            let result = require_pool_above_threshold(&e, &pool_backstop_data);
+            assert!(result);
+        });
+    }
+
+    #[test]
+    fn test_require_pool_above_threshold_config() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        let backstop_address = create_backstop(&e);
+
+        let pool_backstop_data = PoolBackstopData {
+            blnd: 300_000_0000000,
+            q4w_pct: 0,
+            tokens: 20_000_0000000,
+            usdc: 25_000_0000000,
+        }; // ~91.2% threshold
+
+        e.as_contract(&backstop_address, || {
+            // (300k * 25k)^(1/2) = ~86.6k
+            storage::set_threshold_config(
+                &e,
+                &ThresholdConfig {
+                    blnd_weight: 1,
+                    usdc_weight: 1,
+                    pc: 86_000,
+                },
+            );
+            assert!(require_pool_above_threshold(&e, &pool_backstop_data));
+
+            storage::set_threshold_config(
+                &e,
+                &ThresholdConfig {
+                    blnd_weight: 1,
+                    usdc_weight: 1,
+                    pc: 87_000,
+                },
+            );
+            assert!(!require_pool_above_threshold(&e, &pool_backstop_data));
+        });
     }
 
     /********** Logic **********/
@@ -0,0 +1,226 @@
+use crate::{contract::require_nonnegative, emissions, storage, BackstopError};
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use super::{checkpoint_voting_weight, release_expired_lock, require_unlocked_shares};
+
+/// Perform a transfer of backstop shares for a pool from one user to another
+///
+/// Only shares that are not queued for withdrawal and not held in a lockup can be transferred.
+/// Queued shares remain with `from` and can only be dequeued or withdrawn by them.
+pub fn execute_transfer_shares(
+    e: &Env,
+    from: &Address,
+    pool_address: &Address,
+    to: &Address,
+    amount: i128,
+) {
+    require_nonnegative(e, amount);
+    if from == to || to == pool_address || to == &e.current_contract_address() {
+        panic_with_error!(e, &BackstopError::BadRequest)
+    }
+
+    let pool_balance = storage::get_pool_balance(e, pool_address);
+    let mut from_balance = storage::get_user_balance(e, pool_address, from);
+    let mut to_balance = storage::get_user_balance(e, pool_address, to);
+
+    // both users accrue emissions on their shares held before the transfer
+    emissions::update_emissions(e, pool_address, &pool_balance, from, &from_balance);
+    emissions::update_emissions(e, pool_address, &pool_balance, to, &to_balance);
+    release_expired_lock(e, pool_address, from);
+    release_expired_lock(e, pool_address, to);
+    require_unlocked_shares(e, pool_address, from, &from_balance, amount);
+
+    if from_balance.shares < amount {
+        panic_with_error!(e, &BackstopError::BalanceError);
+    }
+    from_balance.shares -= amount;
+    to_balance.add_shares(amount);
+
+    checkpoint_voting_weight(e, pool_address, from, from_balance.shares);
+    checkpoint_voting_weight(e, pool_address, to, to_balance.shares);
+    storage::set_user_balance(e, pool_address, from, &from_balance);
+    storage::set_user_balance(e, pool_address, to, &to_balance);
+}
+
+#[cfg(test)]
+mod tests {
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        Address,
+    };
+
+    use crate::{
+        backstop::{
+            execute_deposit, execute_deposit_with_lock, execute_queue_withdrawal,
+            load_voting_weight,
+        },
+        storage::{BackstopEmissionConfig, BackstopEmissionsData},
+        testutils::{create_backstop, create_backstop_token, create_mock_pool_factory},
+    };
+
+    use super::*;
+
+    fn set_ledger(e: &Env, timestamp: u64, sequence_number: u32) {
+        e.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 20,
+            sequence_number,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+    }
+
+    #[test]
+    fn test_execute_transfer_shares() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+        set_ledger(&e, 1_000_000, 100);
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool_0_id = Address::generate(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &samwise, &pool_0_id, 100_0000000);
+            execute_queue_withdrawal(&e, &samwise, &pool_0_id, 20_0000000);
+            storage::set_backstop_emis_config(
+                &e,
+                &pool_0_id,
+                &BackstopEmissionConfig {
+                    expiration: 1_000_000 + 10_000,
+                    eps: 0_1000000,
+                },
+            );
+            storage::set_backstop_emis_data(
+                &e,
+                &pool_0_id,
+                &BackstopEmissionsData {
+                    index: 0,
+                    last_time: 1_000_000,
+                },
+            );
+        });
+
+        set_ledger(&e, 1_000_000 + 1000, 101);
+        e.as_contract(&backstop_address, || {
+            execute_transfer_shares(&e, &samwise, &pool_0_id, &frodo, 30_0000000);
+
+            let sam_balance = storage::get_user_balance(&e, &pool_0_id, &samwise);
+            assert_eq!(sam_balance.shares, 50_0000000);
+            assert_eq!(sam_balance.q4w.len(), 1);
+            assert_eq!(sam_balance.q4w.get_unchecked(0).amount, 20_0000000);
+            let frodo_balance = storage::get_user_balance(&e, &pool_0_id, &frodo);
+            assert_eq!(frodo_balance.shares, 30_0000000);
+            assert_eq!(frodo_balance.q4w.len(), 0);
+
+            // the pool's shares are unchanged
+            let pool_balance = storage::get_pool_balance(&e, &pool_0_id);
+            assert_eq!(pool_balance.shares, 100_0000000);
+            assert_eq!(pool_balance.q4w, 20_0000000);
+
+            // emissions before the transfer accrue to samwise
+            let sam_emis = storage::get_user_emis_data(&e, &pool_0_id, &samwise).unwrap();
+            assert_eq!(sam_emis.accrued, 100_0000000);
+            let frodo_emis = storage::get_user_emis_data(&e, &pool_0_id, &frodo).unwrap();
+            assert_eq!(frodo_emis.accrued, 0);
+            assert_eq!(frodo_emis.index, sam_emis.index);
+
+            assert_eq!(
+                load_voting_weight(&e, &pool_0_id, &samwise, 101).shares,
+                50_0000000
+            );
+            assert_eq!(
+                load_voting_weight(&e, &pool_0_id, &frodo, 101).shares,
+                30_0000000
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #10)")]
+    fn test_execute_transfer_shares_queued_shares() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool_0_id = Address::generate(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &samwise, &pool_0_id, 100_0000000);
+            execute_queue_withdrawal(&e, &samwise, &pool_0_id, 20_0000000);
+            execute_transfer_shares(&e, &samwise, &pool_0_id, &frodo, 80_0000001);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1011)")]
+    fn test_execute_transfer_shares_locked_shares() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool_0_id = Address::generate(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &samwise, &pool_0_id, 50_0000000);
+            execute_deposit_with_lock(&e, &samwise, &pool_0_id, 50_0000000, 1);
+            execute_transfer_shares(&e, &samwise, &pool_0_id, &frodo, 50_0000001);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_execute_transfer_shares_to_self() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_0_id = Address::generate(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_0_id);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &samwise, &pool_0_id, 100_0000000);
+            execute_transfer_shares(&e, &samwise, &pool_0_id, &samwise, 10_0000000);
+        });
+    }
+}
@@ -1,9 +1,6 @@
 use soroban_sdk::{contracttype, panic_with_error, vec, Env, Vec};
 
-use crate::{
-    constants::{MAX_Q4W_SIZE, Q4W_LOCK_TIME},
-    errors::BackstopError,
-};
+use crate::{constants::MAX_Q4W_SIZE, errors::BackstopError};
 
 /// A deposit that is queued for withdrawal
 #[derive(Clone)]
@@ -47,10 +44,11 @@ impl UserBalance {
     ///
     /// ### Arguments
     /// * `to_q` - The amount of new shares to queue for withdraw
+    /// * `lock_time` - The time in seconds the shares are locked for
     ///
     /// ### Errors
     /// If the amount to queue is greater than the available shares
-    pub fn queue_shares_for_withdrawal(&mut self, e: &Env, to_q: i128) {
+    pub fn queue_shares_for_withdrawal(&mut self, e: &Env, to_q: i128, lock_time: u64) {
         if self.shares < to_q {
             panic_with_error!(e, BackstopError::BalanceError);
         }
@@ -62,7 +60,7 @@ impl UserBalance {
         // user has enough tokens to withdrawal, add Q4W
         let new_q4w = Q4W {
             amount: to_q,
-            exp: e.ledger().timestamp() + Q4W_LOCK_TIME,
+            exp: e.ledger().timestamp() + lock_time,
         };
         self.q4w.push_back(new_q4w.clone());
     }
@@ -77,19 +75,43 @@ impl UserBalance {
     /// ### Errors
     /// If the user does not have enough shares currently queued to dequeue,
     /// or if they don't have enough queued shares to dequeue
-    #[allow(clippy::comparison_chain)]
     pub fn dequeue_shares_for_withdrawal(
         &mut self,
         e: &Env,
         to_dequeue: i128,
         require_expired: bool,
     ) {
+        let expired_by = if require_expired {
+            Some(e.ledger().timestamp())
+        } else {
+            None
+        };
+        self.dequeue_shares(e, to_dequeue, expired_by);
+    }
+
+    /// Dequeue expired shares from the withdrawal queue for a withdrawal, treating each Q4W as
+    /// expiring `waiver` seconds early
+    ///
+    /// ### Arguments
+    /// * `to_dequeue` - The amount of shares to dequeue from the withdrawal queue
+    /// * `waiver` - The time in seconds waived from each Q4W's lock
+    ///
+    /// ### Errors
+    /// If the user does not have enough expired shares queued to dequeue
+    pub fn dequeue_shares_with_waiver(&mut self, e: &Env, to_dequeue: i128, waiver: u64) {
+        self.dequeue_shares(e, to_dequeue, Some(e.ledger().timestamp() + waiver));
+    }
+
+    /// Dequeue shares from the withdrawal queue, requiring each dequeued Q4W to expire by
+    /// `expired_by` if it is set
+    #[allow(clippy::comparison_chain)]
+    fn dequeue_shares(&mut self, e: &Env, to_dequeue: i128, expired_by: Option<u64>) {
         // validate the invoke has enough unlocked Q4W to claim
         // manage the q4w list while verifying
         let mut left_to_dequeue: i128 = to_dequeue;
         for _index in 0..self.q4w.len() {
             let mut cur_q4w = self.q4w.pop_front_unchecked();
-            if !require_expired || cur_q4w.exp <= e.ledger().timestamp() {
+            if expired_by.is_none_or(|time| cur_q4w.exp <= time) {
                 if cur_q4w.amount > left_to_dequeue {
                     // last record we need to update, but the q4w should remain
                     cur_q4w.amount -= left_to_dequeue;
@@ -117,7 +139,7 @@ impl UserBalance {
 
 #[cfg(test)]
 mod tests {
-    use crate::testutils::assert_eq_vec_q4w;
+    use crate::{constants::Q4W_LOCK_TIME, testutils::assert_eq_vec_q4w};
 
     use super::*;
     use soroban_sdk::{
@@ -165,7 +187,7 @@ mod tests {
         });
 
         let to_queue = 500;
-        user.queue_shares_for_withdrawal(&e, to_queue);
+        user.queue_shares_for_withdrawal(&e, to_queue, Q4W_LOCK_TIME);
         assert_eq_vec_q4w(
             &user.q4w,
             &vec![
@@ -206,7 +228,7 @@ mod tests {
         });
 
         let to_queue = 500;
-        user.queue_shares_for_withdrawal(&e, to_queue);
+        user.queue_shares_for_withdrawal(&e, to_queue, Q4W_LOCK_TIME);
         cur_q4w.push_back(Q4W {
             amount: to_queue,
             exp: 11000000 + 21 * 24 * 60 * 60,
@@ -242,7 +264,7 @@ mod tests {
         });
 
         let to_queue = 500;
-        user.queue_shares_for_withdrawal(&e, to_queue);
+        user.queue_shares_for_withdrawal(&e, to_queue, Q4W_LOCK_TIME);
         cur_q4w.push_back(Q4W {
             amount: to_queue,
             exp: 11000000 + 21 * 24 * 60 * 60,
@@ -280,7 +302,7 @@ mod tests {
         });
 
         let to_queue = 500;
-        user.queue_shares_for_withdrawal(&e, to_queue);
+        user.queue_shares_for_withdrawal(&e, to_queue, Q4W_LOCK_TIME);
     }
 
     #[test]
@@ -312,7 +334,7 @@ mod tests {
         });
 
         let to_queue = 801;
-        user.queue_shares_for_withdrawal(&e, to_queue);
+        user.queue_shares_for_withdrawal(&e, to_queue, Q4W_LOCK_TIME);
     }
 
     #[test]
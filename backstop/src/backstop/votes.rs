@@ -0,0 +1,224 @@
+use crate::{constants::MAX_VOTE_CHECKPOINTS, storage, BackstopError};
+use soroban_sdk::{contracttype, panic_with_error, Address, Env};
+
+/// The voting weight of a user's backstop deposit in a pool as of a ledger
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct VoteSnapshot {
+    pub sequence: u32, // the ledger sequence the weight was recorded at
+    pub shares: i128,  // the user's shares that are not queued for withdrawal
+}
+
+/// Record a checkpoint of the user's non-queued shares in a pool at the current ledger. This
+/// must be called before the user's updated balance is stored.
+///
+/// Only the most recent MAX_VOTE_CHECKPOINTS checkpoints are kept.
+///
+/// ### Arguments
+/// * `pool` - The pool the deposit is associated with
+/// * `user` - The owner of the deposit
+/// * `shares` - The user's non-queued shares after the update
+pub fn checkpoint_voting_weight(e: &Env, pool: &Address, user: &Address, shares: i128) {
+    let sequence = e.ledger().sequence();
+    let mut checkpoints = storage::get_vote_checkpoints(e, pool, user);
+    match checkpoints.last() {
+        Some(last) => {
+            if last.shares == shares {
+                return;
+            }
+            if last.sequence == sequence {
+                checkpoints.pop_back();
+            }
+        }
+        None => {
+            // record the shares held before checkpoints were tracked for the user
+            let prev_shares = storage::get_user_balance(e, pool, user).shares;
+            if prev_shares == shares {
+                return;
+            }
+            if prev_shares > 0 {
+                checkpoints.push_back(VoteSnapshot {
+                    sequence: 0,
+                    shares: prev_shares,
+                });
+            }
+        }
+    }
+    if checkpoints.len() >= MAX_VOTE_CHECKPOINTS {
+        checkpoints.pop_front();
+    }
+    checkpoints.push_back(VoteSnapshot { sequence, shares });
+    storage::set_vote_checkpoints(e, pool, user, &checkpoints);
+}
+
+/// Load the voting weight of a user's backstop deposit in a pool as of a ledger sequence
+///
+/// ### Arguments
+/// * `pool` - The pool the deposit is associated with
+/// * `user` - The owner of the deposit
+/// * `sequence` - The ledger sequence to load the voting weight at
+///
+/// ### Panics
+/// If the sequence is in the future, or is older than the checkpoints kept for the user
+pub fn load_voting_weight(e: &Env, pool: &Address, user: &Address, sequence: u32) -> VoteSnapshot {
+    if sequence > e.ledger().sequence() {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+    let checkpoints = storage::get_vote_checkpoints(e, pool, user);
+    if checkpoints.is_empty() {
+        // the user's shares have not changed since checkpoints were tracked
+        return VoteSnapshot {
+            sequence: 0,
+            shares: storage::get_user_balance(e, pool, user).shares,
+        };
+    }
+    for checkpoint in checkpoints.iter().rev() {
+        if checkpoint.sequence <= sequence {
+            return checkpoint;
+        }
+    }
+    if checkpoints.len() >= MAX_VOTE_CHECKPOINTS {
+        panic_with_error!(e, BackstopError::VoteSnapshotUnavailable);
+    }
+    VoteSnapshot {
+        sequence: 0,
+        shares: 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec,
+    };
+
+    use crate::{backstop::UserBalance, testutils::create_backstop};
+
+    use super::*;
+
+    fn set_sequence(e: &Env, sequence: u32) {
+        e.ledger().set(LedgerInfo {
+            timestamp: sequence as u64 * 5,
+            protocol_version: 20,
+            sequence_number: sequence,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+    }
+
+    #[test]
+    fn test_checkpoint_voting_weight() {
+        let e = Env::default();
+        let backstop_address = create_backstop(&e);
+        let pool = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        set_sequence(&e, 100);
+        e.as_contract(&backstop_address, || {
+            // samwise held shares before checkpoints were tracked
+            storage::set_user_balance(
+                &e,
+                &pool,
+                &samwise,
+                &UserBalance {
+                    shares: 50,
+                    q4w: vec![&e],
+                },
+            );
+            let snapshot = load_voting_weight(&e, &pool, &samwise, 100);
+            assert_eq!(
+                snapshot,
+                VoteSnapshot {
+                    sequence: 0,
+                    shares: 50
+                }
+            );
+
+            checkpoint_voting_weight(&e, &pool, &samwise, 80);
+            set_sequence(&e, 105);
+            checkpoint_voting_weight(&e, &pool, &samwise, 30);
+            // a second update in the same ledger replaces the checkpoint
+            checkpoint_voting_weight(&e, &pool, &samwise, 20);
+            // an unchanged balance does not add a checkpoint
+            checkpoint_voting_weight(&e, &pool, &samwise, 20);
+            assert_eq!(storage::get_vote_checkpoints(&e, &pool, &samwise).len(), 3);
+
+            let snapshot = load_voting_weight(&e, &pool, &samwise, 99);
+            assert_eq!(
+                snapshot,
+                VoteSnapshot {
+                    sequence: 0,
+                    shares: 50
+                }
+            );
+            let snapshot = load_voting_weight(&e, &pool, &samwise, 104);
+            assert_eq!(
+                snapshot,
+                VoteSnapshot {
+                    sequence: 100,
+                    shares: 80
+                }
+            );
+            let snapshot = load_voting_weight(&e, &pool, &samwise, 105);
+            assert_eq!(
+                snapshot,
+                VoteSnapshot {
+                    sequence: 105,
+                    shares: 20
+                }
+            );
+
+            // frodo had no shares before their first checkpoint
+            checkpoint_voting_weight(&e, &pool, &frodo, 10);
+            assert_eq!(storage::get_vote_checkpoints(&e, &pool, &frodo).len(), 1);
+            let snapshot = load_voting_weight(&e, &pool, &frodo, 104);
+            assert_eq!(
+                snapshot,
+                VoteSnapshot {
+                    sequence: 0,
+                    shares: 0
+                }
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1009)")]
+    fn test_load_voting_weight_truncated() {
+        let e = Env::default();
+        let backstop_address = create_backstop(&e);
+        let pool = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        e.as_contract(&backstop_address, || {
+            for i in 1..=(MAX_VOTE_CHECKPOINTS + 1) {
+                set_sequence(&e, 100 + i);
+                checkpoint_voting_weight(&e, &pool, &samwise, i as i128);
+            }
+            let checkpoints = storage::get_vote_checkpoints(&e, &pool, &samwise);
+            assert_eq!(checkpoints.len(), MAX_VOTE_CHECKPOINTS);
+            assert_eq!(checkpoints.first().unwrap().shares, 2);
+
+            load_voting_weight(&e, &pool, &samwise, 101);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_load_voting_weight_future_sequence() {
+        let e = Env::default();
+        let backstop_address = create_backstop(&e);
+        let pool = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        set_sequence(&e, 100);
+        e.as_contract(&backstop_address, || {
+            load_voting_weight(&e, &pool, &samwise, 101);
+        });
+    }
+}
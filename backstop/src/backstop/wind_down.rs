@@ -0,0 +1,286 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{
+    constants::WIND_DOWN_PERIOD, dependencies::PoolClient, emissions, errors::BackstopError,
+    storage,
+};
+
+/// Begin winding down the backstop of a retired pool. A winding down backstop does not accept
+/// deposits, its Q4W lock is waived linearly over `WIND_DOWN_PERIOD`, and the pool is removed from
+/// the reward zone with any residual emissions carried over to the rest of the reward zone.
+///
+/// A pool is retired once it is frozen and has no liabilities left, so its backstop no longer
+/// covers any debt. Anyone can begin the wind down of a retired pool, and it cannot be reversed.
+///
+/// Returns the amount of residual emissions carried over
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+///
+/// ### Panics
+/// If the pool is not frozen, the pool has liabilities, or the backstop is already winding down
+pub fn execute_wind_down(e: &Env, pool_address: &Address) -> i128 {
+    if storage::get_wind_down_time(e, pool_address).is_some() {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+    // only admin frozen (4) or backstop frozen (5) pools without liabilities can be wound down
+    let pool_client = PoolClient::new(e, pool_address);
+    let status = pool_client.get_status();
+    if !(status == 4 || status == 5) || pool_client.get_total_liabilities() != 0 {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+
+    storage::set_wind_down_time(e, pool_address, e.ledger().timestamp());
+    emissions::stop_pool_emissions(e, pool_address)
+}
+
+/// Fetch the time in seconds waived from the Q4W lock of a pool's backstop. The waiver grows
+/// linearly from zero when the backstop begins winding down to the pool's full Q4W lock time
+/// after `WIND_DOWN_PERIOD`.
+///
+/// ### Arguments
+/// * `pool_address` - The address of the pool
+pub fn load_q4w_waiver(e: &Env, pool_address: &Address) -> u64 {
+    match storage::get_wind_down_time(e, pool_address) {
+        Some(wind_down_time) => {
+            let elapsed = (e.ledger().timestamp() - wind_down_time).min(WIND_DOWN_PERIOD);
+            storage::get_q4w_lock_time(e, pool_address) * elapsed / WIND_DOWN_PERIOD
+        }
+        None => 0,
+    }
+}
+
+/// Require that a pool's backstop is not winding down
+///
+/// ### Panics
+/// If the pool's backstop is winding down
+pub fn require_not_winding_down(e: &Env, pool_address: &Address) {
+    if storage::get_wind_down_time(e, pool_address).is_some() {
+        panic_with_error!(e, BackstopError::PoolWindingDown);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec, Address,
+    };
+
+    use crate::{
+        backstop::{execute_deposit, execute_queue_withdrawal, execute_withdraw, PoolBalance},
+        constants::{Q4W_LOCK_TIME, SCALAR_7},
+        storage::{BackstopEmissionConfig, BackstopEmissionsData},
+        testutils::{
+            create_backstop, create_backstop_token, create_mock_pool, create_mock_pool_factory,
+            create_pause_registry,
+        },
+    };
+
+    use super::*;
+
+    fn set_timestamp(e: &Env, timestamp: u64) {
+        e.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+    }
+
+    #[test]
+    fn test_execute_wind_down() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+        set_timestamp(&e, 1_000_000);
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+        let (pool, pool_client) = create_mock_pool(&e);
+        pool_client.set_status(&4);
+        let other_pool = Address::generate(&e);
+
+        e.as_contract(&backstop_address, || {
+            storage::set_reward_zone(&e, &vec![&e, other_pool.clone(), pool.clone()]);
+            storage::set_pool_emissions(&e, &pool, 100_0000000);
+            storage::set_pool_balance(
+                &e,
+                &pool,
+                &PoolBalance {
+                    tokens: 1_000_0000000,
+                    shares: 1_000_0000000,
+                    q4w: 0,
+                },
+            );
+            storage::set_backstop_emis_config(
+                &e,
+                &pool,
+                &BackstopEmissionConfig {
+                    expiration: 1_000_000 + 1000,
+                    eps: 0_1000000,
+                },
+            );
+            storage::set_backstop_emis_data(
+                &e,
+                &pool,
+                &BackstopEmissionsData {
+                    index: 0,
+                    last_time: 1_000_000 - 1000,
+                },
+            );
+
+            let residual = execute_wind_down(&e, &pool);
+
+            // 100 pool emissions + 1000s * 0.1 eps of backstop emissions
+            assert_eq!(residual, 200_0000000);
+            assert_eq!(storage::get_residual_emissions(&e), 200_0000000);
+            assert_eq!(storage::get_wind_down_time(&e, &pool), Some(1_000_000));
+            assert_eq!(storage::get_reward_zone(&e), vec![&e, other_pool.clone()]);
+            assert_eq!(storage::get_pool_emissions(&e, &pool), 0);
+            let emis_config = storage::get_backstop_emis_config(&e, &pool).unwrap();
+            assert_eq!(emis_config.expiration, 1_000_000);
+            // depositors accrue emissions through the wind down
+            let emis_data = storage::get_backstop_emis_data(&e, &pool).unwrap();
+            assert_eq!(emis_data.last_time, 1_000_000);
+            assert_eq!(emis_data.index, 0_1000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_execute_wind_down_requires_frozen() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = create_backstop(&e);
+        let (pool, pool_client) = create_mock_pool(&e);
+        pool_client.set_status(&3);
+
+        e.as_contract(&backstop_address, || {
+            execute_wind_down(&e, &pool);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_execute_wind_down_requires_frozen_not_setup() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = create_backstop(&e);
+        let (pool, pool_client) = create_mock_pool(&e);
+        pool_client.set_status(&6);
+
+        e.as_contract(&backstop_address, || {
+            execute_wind_down(&e, &pool);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_execute_wind_down_requires_no_liabilities() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = create_backstop(&e);
+        let (pool, pool_client) = create_mock_pool(&e);
+        pool_client.set_status(&4);
+        pool_client.set_total_liabilities(&1);
+
+        e.as_contract(&backstop_address, || {
+            execute_wind_down(&e, &pool);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_execute_wind_down_twice() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+        let (pool, pool_client) = create_mock_pool(&e);
+        pool_client.set_status(&5);
+
+        e.as_contract(&backstop_address, || {
+            execute_wind_down(&e, &pool);
+            execute_wind_down(&e, &pool);
+        });
+    }
+
+    #[test]
+    fn test_wind_down_waives_q4w() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.budget().reset_unlimited();
+        set_timestamp(&e, 1_000_000);
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+        let (pool, pool_client) = create_mock_pool(&e);
+        pool_client.set_status(&4);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool);
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &samwise, &pool, 100_0000000);
+            execute_queue_withdrawal(&e, &samwise, &pool, 50_0000000);
+            execute_wind_down(&e, &pool);
+            assert_eq!(load_q4w_waiver(&e, &pool), 0);
+        });
+
+        // half of the wind down period waives half of the lock
+        set_timestamp(&e, 1_000_000 + WIND_DOWN_PERIOD / 2);
+        e.as_contract(&backstop_address, || {
+            assert_eq!(load_q4w_waiver(&e, &pool), Q4W_LOCK_TIME / 2);
+        });
+
+        // the entire lock is waived by the end of the wind down period
+        set_timestamp(&e, 1_000_000 + WIND_DOWN_PERIOD);
+        e.as_contract(&backstop_address, || {
+            assert_eq!(load_q4w_waiver(&e, &pool), Q4W_LOCK_TIME);
+            execute_queue_withdrawal(&e, &samwise, &pool, 50_0000000);
+            let withdrawn = execute_withdraw(&e, &samwise, &pool, 100_0000000);
+            assert_eq!(withdrawn, 100 * SCALAR_7);
+            assert_eq!(backstop_token_client.balance(&samwise), 100_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1013)")]
+    fn test_wind_down_blocks_deposits() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.budget().reset_unlimited();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+        let (pool, pool_client) = create_mock_pool(&e);
+        pool_client.set_status(&4);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool);
+
+        e.as_contract(&backstop_address, || {
+            execute_wind_down(&e, &pool);
+            execute_deposit(&e, &samwise, &pool, 100_0000000);
+        });
+    }
+}
@@ -1,8 +1,13 @@
-use crate::{contract::require_nonnegative, emissions, storage, BackstopError};
+use crate::{
+    constants::Q4W_SWEEP_GRACE_PERIOD, contract::require_nonnegative, emissions, storage,
+    BackstopError,
+};
 use sep_41_token::TokenClient;
-use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env, Symbol};
 
-use super::Q4W;
+use super::{
+    checkpoint_voting_weight, load_q4w_waiver, release_expired_lock, require_unlocked_shares, Q4W,
+};
 
 /// Perform a queue for withdraw from the backstop module
 pub fn execute_queue_withdrawal(
@@ -18,10 +23,14 @@ pub fn execute_queue_withdrawal(
 
     // update emissions
     emissions::update_emissions(e, pool_address, &pool_balance, from, &user_balance);
+    release_expired_lock(e, pool_address, from);
+    require_unlocked_shares(e, pool_address, from, &user_balance, amount);
 
-    user_balance.queue_shares_for_withdrawal(e, amount);
+    let lock_time = storage::get_q4w_lock_time(e, pool_address);
+    user_balance.queue_shares_for_withdrawal(e, amount, lock_time);
     pool_balance.queue_for_withdraw(amount);
 
+    checkpoint_voting_weight(e, pool_address, from, user_balance.shares);
     storage::set_user_balance(e, pool_address, from, &user_balance);
     storage::set_pool_balance(e, pool_address, &pool_balance);
 
@@ -37,11 +46,13 @@ pub fn execute_dequeue_withdrawal(e: &Env, from: &Address, pool_address: &Addres
 
     // update emissions
     emissions::update_emissions(e, pool_address, &pool_balance, from, &user_balance);
+    release_expired_lock(e, pool_address, from);
 
     user_balance.dequeue_shares_for_withdrawal(e, amount, false);
     user_balance.add_shares(amount);
     pool_balance.dequeue_q4w(e, amount);
 
+    checkpoint_voting_weight(e, pool_address, from, user_balance.shares);
     storage::set_user_balance(e, pool_address, from, &user_balance);
     storage::set_pool_balance(e, pool_address, &pool_balance);
 }
@@ -53,7 +64,8 @@ pub fn execute_withdraw(e: &Env, from: &Address, pool_address: &Address, amount:
     let mut pool_balance = storage::get_pool_balance(e, pool_address);
     let mut user_balance = storage::get_user_balance(e, pool_address, from);
 
-    user_balance.dequeue_shares_for_withdrawal(e, amount, true);
+    let waiver = load_q4w_waiver(e, pool_address);
+    user_balance.dequeue_shares_with_waiver(e, amount, waiver);
 
     let to_return = pool_balance.convert_to_tokens(amount);
     if to_return == 0 {
@@ -70,11 +82,48 @@ pub fn execute_withdraw(e: &Env, from: &Address, pool_address: &Address, amount:
     to_return
 }
 
+/// Perform a sweep of a user's Q4W entries that expired more than the grace period ago, withdrawing
+/// them to the user. Entries that are executable but still within the grace period emit a
+/// "q4w_executable" event as a reminder to the user.
+///
+/// Returns a tuple of (shares, tokens) swept to the user
+pub fn execute_sweep_expired(e: &Env, user: &Address, pool_address: &Address) -> (i128, i128) {
+    let user_balance = storage::get_user_balance(e, pool_address, user);
+
+    let now = e.ledger().timestamp();
+    let mut to_sweep: i128 = 0;
+    let mut is_sweepable = true;
+    for q4w in user_balance.q4w.iter() {
+        if is_sweepable && q4w.exp + Q4W_SWEEP_GRACE_PERIOD <= now {
+            to_sweep += q4w.amount;
+        } else {
+            // only a leading run of entries can be withdrawn
+            is_sweepable = false;
+            if q4w.exp <= now {
+                e.events().publish(
+                    (
+                        Symbol::new(e, "q4w_executable"),
+                        pool_address.clone(),
+                        user.clone(),
+                    ),
+                    (q4w.amount, q4w.exp),
+                );
+            }
+        }
+    }
+
+    if to_sweep == 0 {
+        return (0, 0);
+    }
+    let to_return = execute_withdraw(e, user, pool_address, to_sweep);
+    (to_sweep, to_return)
+}
+
 #[cfg(test)]
 mod tests {
     use soroban_sdk::{
-        testutils::{Address as _, Ledger, LedgerInfo},
-        vec, Address,
+        testutils::{Address as _, Events, Ledger, LedgerInfo},
+        vec, Address, IntoVal,
     };
 
     use crate::{
@@ -145,6 +194,44 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_execute_queue_withdrawal_uses_pool_lock_time() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let backstop_address = create_backstop(&e);
+        let pool_address = Address::generate(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_address);
+
+        e.as_contract(&backstop_address, || {
+            storage::set_q4w_lock_time(&e, &pool_address, &(7 * 24 * 60 * 60));
+            execute_deposit(&e, &samwise, &pool_address, 100_0000000);
+        });
+
+        e.ledger().set(LedgerInfo {
+            protocol_version: 20,
+            sequence_number: 200,
+            timestamp: 10000,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        e.as_contract(&backstop_address, || {
+            let q4w = execute_queue_withdrawal(&e, &samwise, &pool_address, 42_0000000);
+            assert_eq!(q4w.exp, 10000 + 7 * 24 * 60 * 60);
+        });
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #8)")]
     fn test_execute_queue_withdrawal_negative_amount() {
@@ -471,4 +558,101 @@ mod tests {
             execute_withdraw(&e, &samwise, &pool_address, 1_0000000);
         });
     }
+
+    #[test]
+    fn test_execute_sweep_expired() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let backstop_address = create_backstop(&e);
+        let pool_address = Address::generate(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let (_, backstop_token_client) = create_backstop_token(&e, &backstop_address, &bombadil);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        let (_, mock_pool_factory_client) = create_mock_pool_factory(&e, &backstop_address);
+        mock_pool_factory_client.set_pool(&pool_address);
+
+        e.ledger().set(LedgerInfo {
+            protocol_version: 20,
+            sequence_number: 200,
+            timestamp: 10000,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        e.as_contract(&backstop_address, || {
+            execute_deposit(&e, &samwise, &pool_address, 100_0000000);
+            execute_queue_withdrawal(&e, &samwise, &pool_address, 30_0000000);
+        });
+
+        e.ledger().set(LedgerInfo {
+            protocol_version: 20,
+            sequence_number: 200,
+            timestamp: 10000 + 7 * 24 * 60 * 60,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        e.as_contract(&backstop_address, || {
+            execute_queue_withdrawal(&e, &samwise, &pool_address, 20_0000000);
+        });
+
+        // first entry is past the grace period and the second is executable
+        e.ledger().set(LedgerInfo {
+            protocol_version: 20,
+            sequence_number: 200,
+            timestamp: 10000 + 28 * 24 * 60 * 60 + 1,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        e.as_contract(&backstop_address, || {
+            let (shares, tokens) = execute_sweep_expired(&e, &samwise, &pool_address);
+            assert_eq!(shares, 30_0000000);
+            assert_eq!(tokens, 30_0000000);
+
+            // the reminder is emitted before the swept tokens are transferred
+            let events = e.events().all();
+            let event = events.get_unchecked(events.len() - 2);
+            assert_eq!(
+                vec![&e, event],
+                vec![
+                    &e,
+                    (
+                        backstop_address.clone(),
+                        (
+                            Symbol::new(&e, "q4w_executable"),
+                            pool_address.clone(),
+                            samwise.clone()
+                        )
+                            .into_val(&e),
+                        (20_0000000i128, 10000 + 28 * 24 * 60 * 60u64).into_val(&e)
+                    )
+                ]
+            );
+
+            let new_user_balance = storage::get_user_balance(&e, &pool_address, &samwise);
+            assert_eq!(new_user_balance.shares, 50_0000000);
+            assert_eq!(new_user_balance.q4w.len(), 1);
+            assert_eq!(backstop_token_client.balance(&samwise), 30_0000000);
+
+            // nothing left to sweep
+            let (shares, tokens) = execute_sweep_expired(&e, &samwise, &pool_address);
+            assert_eq!(shares, 0);
+            assert_eq!(tokens, 0);
+            assert_eq!(backstop_token_client.balance(&samwise), 30_0000000);
+        });
+    }
 }
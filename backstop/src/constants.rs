@@ -10,5 +10,86 @@ pub const BACKSTOP_EPOCH: u64 = 1713139200;
 /// Set such that a user can create a maximum of 1 entry per day over the 21 day lock period.
 pub const MAX_Q4W_SIZE: u32 = 21;
 
-/// The time in seconds that a Q4W entry is locked for (21 days).
+/// The default time in seconds that a Q4W entry is locked for (21 days). Used for pools that
+/// were not registered with a lock time.
 pub const Q4W_LOCK_TIME: u64 = 21 * 24 * 60 * 60;
+
+/// The minimum time in seconds a pool can set its Q4W lock time to (7 days).
+pub const MIN_Q4W_LOCK_TIME: u64 = 7 * 24 * 60 * 60;
+
+/// The maximum time in seconds a pool can set its Q4W lock time to (30 days).
+pub const MAX_Q4W_LOCK_TIME: u64 = 30 * 24 * 60 * 60;
+
+/// The maximum amount of voting weight checkpoints kept for a user against a single backstop.
+pub const MAX_VOTE_CHECKPOINTS: u32 = 20;
+
+/// The time in seconds after a Q4W entry expires before anyone can sweep it to the user (7 days).
+pub const Q4W_SWEEP_GRACE_PERIOD: u64 = 7 * 24 * 60 * 60;
+
+/// The time in seconds a pool's backstop must stay above the threshold before the pool can be
+/// added to the reward zone (7 days).
+pub const RZ_ELIGIBILITY_PERIOD: u64 = 7 * 24 * 60 * 60;
+
+/// The default weights of a backstop's BLND and USDC balances in the backstop threshold, matching
+/// the 80/20 BLND:USDC split of the backstop token.
+pub const DEFAULT_THRESHOLD_BLND_WEIGHT: u32 = 4;
+pub const DEFAULT_THRESHOLD_USDC_WEIGHT: u32 = 1;
+
+/// The default product constant of the backstop threshold in whole tokens (200k).
+pub const DEFAULT_THRESHOLD_PC: i128 = 200_000;
+
+/// The maximum sum of the BLND and USDC weights of the backstop threshold.
+pub const MAX_THRESHOLD_WEIGHT: u32 = 5;
+
+/// The minimum and maximum product constant of the backstop threshold in whole tokens.
+pub const MIN_THRESHOLD_PC: i128 = 10_000;
+pub const MAX_THRESHOLD_PC: i128 = 10_000_000;
+
+/// The time in seconds an update to the backstop threshold must be queued before it can be
+/// executed (7 days).
+pub const THRESHOLD_TIMELOCK: u64 = 7 * 24 * 60 * 60;
+
+/// The time in seconds over which the Q4W lock of a winding down pool's backstop is waived
+/// (30 days). This covers the maximum Q4W lock time, so every queued withdrawal is executable
+/// once it passes.
+pub const WIND_DOWN_PERIOD: u64 = 30 * 24 * 60 * 60;
+
+/// The number of seconds in a year
+pub const SECONDS_PER_YEAR: i128 = 31536000;
+
+/// The minimum time in seconds that trailing backstop donations are measured over (7 days).
+pub const DONATION_WINDOW: u64 = 7 * 24 * 60 * 60;
+
+/// The coverage ratio of a pool's liabilities by its backstop under which the backstop is considered
+/// to be in breach (10%).
+pub const COVERAGE_THRESHOLD: i128 = 0_1000000;
+
+/// The minimum time in seconds between automatic refreshes of the backstop token's underlying
+/// value during deposits and withdrawals (1 hour).
+pub const LP_TOKEN_VAL_REFRESH_INTERVAL: u64 = 60 * 60;
+
+/// The time in seconds an upgrade of the backstop must be queued before it can be executed (211
+/// days). This exceeds the longest deposit lock plus the maximum Q4W lock time, so every depositor
+/// can exit before an upgrade takes effect.
+pub const UPGRADE_TIMELOCK: u64 =
+    DEPOSIT_LOCK_TIERS[DEPOSIT_LOCK_TIERS.len() - 1].0 + MAX_Q4W_LOCK_TIME + 24 * 60 * 60;
+
+/// The default time in seconds a user's emissions can go unclaimed before they can be recycled into
+/// the next emission cycle (2 years).
+pub const DEFAULT_CLAIM_DEADLINE: u64 = 2 * 365 * 24 * 60 * 60;
+
+/// The bounds on the claim deadline, in seconds (1 to 10 years).
+pub const MIN_CLAIM_DEADLINE: u64 = 365 * 24 * 60 * 60;
+pub const MAX_CLAIM_DEADLINE: u64 = 10 * 365 * 24 * 60 * 60;
+
+/// The version of the backstop's stored state layout
+pub const STORAGE_VERSION: u32 = 1;
+
+/// The lockup tiers a deposit can be made with, as (lock time in seconds, emission multiplier with
+/// 7 decimals). Tier 1 locks for 30 days at 1.1x, tier 2 for 90 days at 1.25x, and tier 3 for 180
+/// days at 1.5x.
+pub const DEPOSIT_LOCK_TIERS: [(u64, i128); 3] = [
+    (30 * 24 * 60 * 60, 1_1000000),
+    (90 * 24 * 60 * 60, 1_2500000),
+    (180 * 24 * 60 * 60, 1_5000000),
+];
@@ -1,12 +1,17 @@
 use crate::{
-    backstop::{self, load_pool_backstop_data, PoolBackstopData, UserBalance, Q4W},
-    dependencies::EmitterClient,
+    backstop::{
+        self, load_pool_backstop_apr, load_pool_backstop_data, BackstopApr, DepositLock,
+        PoolBackstopData, UserBalance, VoteSnapshot, Q4W,
+    },
+    constants::STORAGE_VERSION,
+    dependencies::{EmitterClient, PauseRegistryClient},
     emissions,
     errors::BackstopError,
-    storage,
+    storage::{self, QueuedUpgrade, ThresholdConfig},
+    threshold, upgrade,
 };
 use soroban_sdk::{
-    contract, contractclient, contractimpl, panic_with_error, Address, Env, Symbol, Vec,
+    contract, contractclient, contractimpl, panic_with_error, Address, BytesN, Env, Symbol, Vec,
 };
 
 /// ### Backstop
@@ -27,6 +32,7 @@ pub trait Backstop {
     /// * `blnd_token` - The BLND token ID
     /// * `usdc_token` - The USDC token ID
     /// * `pool_factory` - The pool factory ID
+    /// * `pause_registry` - The pause registry ID consulted before state-mutating actions
     /// * `drop_list` - The list of addresses to distribute initial BLND to and the percent of the distribution they should receive
     ///
     /// ### Errors
@@ -38,9 +44,22 @@ pub trait Backstop {
         blnd_token: Address,
         usdc_token: Address,
         pool_factory: Address,
+        pause_registry: Address,
         drop_list: Vec<(Address, i128)>,
     );
 
+    /// (Pool Factory only) Register the Q4W lock time for a newly deployed pool
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    /// * `q4w_lock_time` - The time in seconds that Q4W entries for the pool are locked for,
+    ///   between 7 and 30 days
+    ///
+    /// ### Panics
+    /// If the caller is not the pool factory, the lock time is out of bounds, or the pool
+    /// has already been registered
+    fn register_pool(e: Env, pool_address: Address, q4w_lock_time: u64);
+
     /********** Core **********/
 
     /// Deposit backstop tokens from "from" into the backstop of a pool
@@ -53,6 +72,39 @@ pub trait Backstop {
     /// * `amount` - The amount of tokens to deposit
     fn deposit(e: Env, from: Address, pool_address: Address, amount: i128) -> i128;
 
+    /// Deposit backstop tokens from "from" into the backstop of a pool and lock the minted shares.
+    /// Locked shares cannot be queued for withdrawal until the lock expires, and earn emissions
+    /// at the lock tier's multiplier until the lock is released.
+    ///
+    /// Returns the number of backstop pool shares minted
+    ///
+    /// ### Arguments
+    /// * `from` - The address depositing into the backstop
+    /// * `pool_address` - The address of the pool
+    /// * `amount` - The amount of tokens to deposit
+    /// * `lock_tier` - The lock tier (1 => 30 days at 1.1x, 2 => 90 days at 1.25x, 3 => 180 days at 1.5x)
+    ///
+    /// ### Errors
+    /// If the lock tier does not exist
+    fn deposit_with_lock(
+        e: Env,
+        from: Address,
+        pool_address: Address,
+        amount: i128,
+        lock_tier: u32,
+    ) -> i128;
+
+    /// Release a user's deposit lockup for a pool once it has unlocked, so its bonus shares stop
+    /// earning emissions. Anyone can release an unlocked lockup.
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    /// * `user` - The user whose lockup is released
+    ///
+    /// ### Errors
+    /// If the user has no lockup, or it has not unlocked
+    fn release_lock(e: Env, pool_address: Address, user: Address);
+
     /// Queue deposited pool shares from "from" for withdraw from a backstop of a pool
     ///
     /// Returns the created queue for withdrawal
@@ -81,6 +133,32 @@ pub trait Backstop {
     /// * `amount` - The amount of shares to withdraw
     fn withdraw(e: Env, from: Address, pool_address: Address, amount: i128) -> i128;
 
+    /// Transfer backstop shares for a pool from one user to another. Only shares that are not
+    /// queued for withdrawal or held in a lockup can be transferred.
+    ///
+    /// ### Arguments
+    /// * `from` - The address transferring the shares
+    /// * `pool_address` - The address of the pool
+    /// * `to` - The address receiving the shares
+    /// * `amount` - The amount of shares to transfer
+    ///
+    /// ### Errors
+    /// If `from` does not have enough transferable shares
+    fn transfer_shares(e: Env, from: Address, pool_address: Address, to: Address, amount: i128);
+
+    /// Withdraw all of a user's queued shares for a pool that expired more than 7 days ago to the
+    /// user. Anyone can sweep expired withdrawals for any user.
+    ///
+    /// Emits a "q4w_executable" event for each queued withdrawal that can be withdrawn but is
+    /// still within the grace period.
+    ///
+    /// Returns a tuple of (shares, tokens) swept to the user
+    ///
+    /// ### Arguments
+    /// * `user` - The address whose expired queued shares are being swept
+    /// * `pool_address` - The address of the pool
+    fn sweep_expired(e: Env, user: Address, pool_address: Address) -> (i128, i128);
+
     /// Fetch the balance of backstop shares of a pool for the user
     ///
     /// ### Arguments
@@ -88,6 +166,13 @@ pub trait Backstop {
     /// * `user` - The user to fetch the balance for
     fn user_balance(e: Env, pool: Address, user: Address) -> UserBalance;
 
+    /// Fetch the lockup of the user's deposit in the backstop of a pool, if one exists
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    /// * `user` - The user to fetch the lockup for
+    fn deposit_lock(e: Env, pool: Address, user: Address) -> Option<DepositLock>;
+
     /// Fetch the backstop data for the pool
     ///
     /// Return a summary of the pool's backstop data
@@ -96,9 +181,48 @@ pub trait Backstop {
     /// * `pool_address` - The address of the pool
     fn pool_data(e: Env, pool: Address) -> PoolBackstopData;
 
+    /// Fetch the current yield for the pool's backstop
+    ///
+    /// Returns the APR from BLND emissions and the trailing APR from interest donated by the pool
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    fn get_backstop_apr(e: Env, pool: Address) -> BackstopApr;
+
+    /// Fetch the coverage of the pool's liabilities by its backstop, where the backstop is valued
+    /// at 5x the USDC held by its backstop tokens
+    ///
+    /// Returns the coverage ratio with 7 decimals, or i128::MAX if the pool has no liabilities
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    fn get_pool_coverage(e: Env, pool: Address) -> i128;
+
+    /// Fetch the time in seconds that Q4W entries for a pool are locked for
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    fn q4w_lock_time(e: Env, pool: Address) -> u64;
+
+    /// Fetch the voting weight of a user's backstop deposit in a pool as of a ledger sequence, so
+    /// governance contracts can weight votes by backstop deposits without moving funds. Shares
+    /// queued for withdrawal are not counted.
+    ///
+    /// ### Arguments
+    /// * `pool` - The address of the pool
+    /// * `user` - The address of the user
+    /// * `sequence` - The ledger sequence to fetch the voting weight at
+    ///
+    /// ### Panics
+    /// If the sequence is in the future, or is older than the checkpoints kept for the user
+    fn voting_weight(e: Env, pool: Address, user: Address, sequence: u32) -> VoteSnapshot;
+
     /// Fetch the backstop token for the backstop
     fn backstop_token(e: Env) -> Address;
 
+    /// Fetch the emitter for the backstop
+    fn emitter(e: Env) -> Address;
+
     /********** Emissions **********/
 
     /// Consume emissions from the Emitter and distribute them to backstops and pools in the reward zone
@@ -117,7 +241,9 @@ pub trait Backstop {
     /// Consume the emissions for a pool and approve
     fn gulp_pool_emissions(e: Env, pool_address: Address) -> i128;
 
-    /// Claim backstop deposit emissions from a list of pools for `from`
+    /// Claim backstop deposit emissions from a list of pools for `from`. The claimed emissions
+    /// are deposited into each pool's backstop on behalf of `to`. Only `from` is required to
+    /// authorize the claim.
     ///
     /// Returns the amount of BLND emissions claimed
     ///
@@ -130,6 +256,45 @@ pub trait Backstop {
     /// If an invalid pool address is included
     fn claim(e: Env, from: Address, pool_addresses: Vec<Address>, to: Address) -> i128;
 
+    /// Claim backstop deposit emissions from a list of pools for `from` and deposit them back
+    /// into the same pools' backstops for `from`
+    ///
+    /// Returns the amount of backstop tokens deposited
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user compounding emissions
+    /// * `pool_addresses` - The Vec of addresses to claim and deposit backstop emissions for
+    /// * `min_lp_out` - The minimum amount of backstop tokens to receive for the claimed BLND
+    ///
+    /// ### Errors
+    /// If an invalid pool address is included, or if less than `min_lp_out` backstop tokens
+    /// would be received
+    fn compound(e: Env, from: Address, pool_addresses: Vec<Address>, min_lp_out: i128) -> i128;
+
+    /// Recycle a user's backstop emissions for a pool that have gone unclaimed for longer than the
+    /// claim deadline into the next emission cycle. Anyone can recycle unclaimed emissions for any
+    /// user.
+    ///
+    /// Returns the amount of emissions recycled
+    ///
+    /// ### Arguments
+    /// * `user` - The address whose unclaimed emissions are being recycled
+    /// * `pool_address` - The address of the pool
+    fn recycle_unclaimed(e: Env, user: Address, pool_address: Address) -> i128;
+
+    /// (Pause registry admin only) Set the time in seconds a user's emissions can go unclaimed
+    /// before they can be recycled
+    ///
+    /// ### Arguments
+    /// * `deadline` - The claim deadline in seconds
+    ///
+    /// ### Errors
+    /// If the deadline is less than 1 year or more than 10 years
+    fn set_claim_deadline(e: Env, deadline: u64);
+
+    /// Fetch the time in seconds a user's emissions can go unclaimed before they can be recycled
+    fn claim_deadline(e: Env) -> u64;
+
     /// Drop initial BLND to a list of addresses through the emitter
     fn drop(e: Env);
 
@@ -162,7 +327,8 @@ pub trait Backstop {
     /// authorize the call
     fn donate(e: Env, from: Address, pool_address: Address, amount: i128);
 
-    /// Updates the underlying value of 1 backstop token
+    /// Updates the underlying value of 1 backstop token. The value is also refreshed during
+    /// deposits and withdrawals if it has not been updated within the last hour.
     ///
     /// ### Returns
     /// A tuple of (blnd_per_tkn, usdc_per_tkn) of underlying value per backstop token
@@ -170,6 +336,95 @@ pub trait Backstop {
     /// ### Errors
     /// If the underlying value is unable to be computed
     fn update_tkn_val(e: Env) -> (i128, i128);
+
+    /********** Upgrade **********/
+
+    /// (Pause registry admin only) Queue an upgrade of the backstop's WASM. The upgrade can be
+    /// executed after a 211 day timelock, longer than the longest deposit lock plus the maximum
+    /// Q4W lock time.
+    ///
+    /// ### Arguments
+    /// * `wasm_hash` - The hash of the installed WASM to upgrade to
+    ///
+    /// ### Errors
+    /// If an upgrade is already queued
+    fn queue_upgrade(e: Env, wasm_hash: BytesN<32>);
+
+    /// (Pause registry admin only) Cancel the queued upgrade of the backstop's WASM
+    ///
+    /// ### Errors
+    /// If no upgrade is queued
+    fn cancel_upgrade(e: Env);
+
+    /// Execute the queued upgrade of the backstop's WASM. The new WASM is used starting with the
+    /// next invocation.
+    ///
+    /// ### Errors
+    /// If no upgrade is queued or the queued upgrade has not been unlocked
+    fn upgrade(e: Env);
+
+    /// Migrate the backstop's stored state to the layout version of the current WASM
+    ///
+    /// ### Errors
+    /// If the stored state is already at the current layout version
+    fn migrate(e: Env);
+
+    /// Fetch the layout version of the backstop's stored state
+    fn storage_version(e: Env) -> u32;
+
+    /// Fetch the queued upgrade of the backstop's WASM, including the hash of the WASM and the
+    /// time it can be executed at, if one exists
+    fn get_queued_upgrade(e: Env) -> Option<QueuedUpgrade>;
+
+    /********** Backstop Threshold **********/
+
+    /// (Pause registry admin only) Queue an update to the backstop threshold a pool's backstop
+    /// must be above to enter the reward zone. The update can be executed after a 7 day timelock.
+    ///
+    /// ### Arguments
+    /// * `config` - The new threshold config
+    ///
+    /// ### Errors
+    /// If an update is already queued, either weight is zero, the weights sum to more than 5, or
+    /// the product constant is outside of [10k, 10M]
+    fn queue_threshold_config(e: Env, config: ThresholdConfig);
+
+    /// (Pause registry admin only) Cancel the queued update to the backstop threshold
+    ///
+    /// ### Errors
+    /// If no update is queued
+    fn cancel_threshold_config(e: Env);
+
+    /// Execute the queued update to the backstop threshold
+    ///
+    /// ### Errors
+    /// If no update is queued or the queued update has not been unlocked
+    fn set_threshold_config(e: Env);
+
+    /// Fetch the backstop threshold config
+    fn threshold_config(e: Env) -> ThresholdConfig;
+
+    /********** Wind Down **********/
+
+    /// Begin winding down the backstop of a retired pool, which is frozen and has no liabilities
+    /// left. New deposits are rejected, the Q4W lock is waived linearly over 30 days, and the pool's
+    /// remaining emissions are redistributed to the reward zone. A wind down cannot be reversed.
+    ///
+    /// Returns the amount of residual emissions redistributed
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The address of the pool
+    ///
+    /// ### Errors
+    /// If the pool is not frozen, the pool has liabilities, or the backstop is already winding
+    /// down
+    fn wind_down(e: Env, pool_address: Address) -> i128;
+
+    /// Fetch the time a pool's backstop began winding down, if it is winding down
+    ///
+    /// ### Arguments
+    /// * `pool` - The address of the pool
+    fn wind_down_time(e: Env, pool: Address) -> Option<u64>;
 }
 
 /// @dev
@@ -184,6 +439,7 @@ impl Backstop for BackstopContract {
         usdc_token: Address,
         blnd_token: Address,
         pool_factory: Address,
+        pause_registry: Address,
         drop_list: Vec<(Address, i128)>,
     ) {
         storage::extend_instance(&e);
@@ -195,6 +451,7 @@ impl Backstop for BackstopContract {
         storage::set_blnd_token(&e, &blnd_token);
         storage::set_usdc_token(&e, &usdc_token);
         storage::set_pool_factory(&e, &pool_factory);
+        storage::set_pause_registry(&e, &pause_registry);
         // NOTE: For a replacement backstop, this value likely needs to be stored in persistent storage to avoid
         //       an expiration occuring before a backstop swap is finalized.
         storage::set_drop_list(&e, &drop_list);
@@ -203,20 +460,41 @@ impl Backstop for BackstopContract {
         // fetch last distribution time from emitter
         // NOTE: For a replacement backstop, this must be fetched after the swap is completed, but this is
         //       a shortcut for the first backstop.
-        let last_distribution_time =
-            EmitterClient::new(&e, &emitter).get_last_distro(&e.current_contract_address());
+        let emitter_client = EmitterClient::new(&e, &emitter);
+        let last_distribution_time = emitter_client.get_last_distro(&e.current_contract_address());
         storage::set_last_distribution_time(&e, &last_distribution_time);
+        storage::set_distributed(
+            &e,
+            &emitter_client.get_distributed(&e.current_contract_address()),
+        );
 
+        storage::set_storage_version(&e, STORAGE_VERSION);
         storage::set_is_init(&e);
     }
 
+    fn register_pool(e: Env, pool_address: Address, q4w_lock_time: u64) {
+        storage::extend_instance(&e);
+        storage::get_pool_factory(&e).require_auth();
+
+        backstop::execute_register_pool(&e, &pool_address, q4w_lock_time);
+
+        e.events().publish(
+            (Symbol::new(&e, "register_pool"), pool_address),
+            q4w_lock_time,
+        );
+    }
+
     /********** Core **********/
 
     fn deposit(e: Env, from: Address, pool_address: Address, amount: i128) -> i128 {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         from.require_auth();
 
         let to_mint = backstop::execute_deposit(&e, &from, &pool_address, amount);
+        backstop::refresh_comet_token_value(&e);
+        let liabilities = backstop::load_pool_liabilities(&e, &pool_address);
+        backstop::update_pool_coverage(&e, &pool_address, liabilities);
 
         e.events().publish(
             (Symbol::new(&e, "deposit"), pool_address, from),
@@ -225,8 +503,51 @@ impl Backstop for BackstopContract {
         to_mint
     }
 
+    fn deposit_with_lock(
+        e: Env,
+        from: Address,
+        pool_address: Address,
+        amount: i128,
+        lock_tier: u32,
+    ) -> i128 {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        from.require_auth();
+
+        let (to_mint, lock) =
+            backstop::execute_deposit_with_lock(&e, &from, &pool_address, amount, lock_tier);
+        backstop::refresh_comet_token_value(&e);
+        let liabilities = backstop::load_pool_liabilities(&e, &pool_address);
+        backstop::update_pool_coverage(&e, &pool_address, liabilities);
+
+        e.events().publish(
+            (
+                Symbol::new(&e, "deposit"),
+                pool_address.clone(),
+                from.clone(),
+            ),
+            (amount, to_mint),
+        );
+        e.events().publish(
+            (Symbol::new(&e, "deposit_lock"), pool_address, from),
+            (lock_tier, lock),
+        );
+        to_mint
+    }
+
+    fn release_lock(e: Env, pool_address: Address, user: Address) {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+
+        backstop::execute_release_lock(&e, &pool_address, &user);
+
+        e.events()
+            .publish((Symbol::new(&e, "release_lock"), pool_address, user), ());
+    }
+
     fn queue_withdrawal(e: Env, from: Address, pool_address: Address, amount: i128) -> Q4W {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         from.require_auth();
 
         let to_queue = backstop::execute_queue_withdrawal(&e, &from, &pool_address, amount);
@@ -240,6 +561,7 @@ impl Backstop for BackstopContract {
 
     fn dequeue_withdrawal(e: Env, from: Address, pool_address: Address, amount: i128) {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         from.require_auth();
 
         backstop::execute_dequeue_withdrawal(&e, &from, &pool_address, amount);
@@ -252,9 +574,13 @@ impl Backstop for BackstopContract {
 
     fn withdraw(e: Env, from: Address, pool_address: Address, amount: i128) -> i128 {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         from.require_auth();
 
         let to_withdraw = backstop::execute_withdraw(&e, &from, &pool_address, amount);
+        backstop::refresh_comet_token_value(&e);
+        let liabilities = backstop::load_pool_liabilities(&e, &pool_address);
+        backstop::update_pool_coverage(&e, &pool_address, liabilities);
 
         e.events().publish(
             (Symbol::new(&e, "withdraw"), pool_address, from),
@@ -263,22 +589,80 @@ impl Backstop for BackstopContract {
         to_withdraw
     }
 
+    fn transfer_shares(e: Env, from: Address, pool_address: Address, to: Address, amount: i128) {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        from.require_auth();
+
+        backstop::execute_transfer_shares(&e, &from, &pool_address, &to, amount);
+
+        e.events().publish(
+            (Symbol::new(&e, "transfer_shares"), pool_address, from),
+            (to, amount),
+        );
+    }
+
+    fn sweep_expired(e: Env, user: Address, pool_address: Address) -> (i128, i128) {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+
+        let (shares, tokens) = backstop::execute_sweep_expired(&e, &user, &pool_address);
+        if shares > 0 {
+            let liabilities = backstop::load_pool_liabilities(&e, &pool_address);
+            backstop::update_pool_coverage(&e, &pool_address, liabilities);
+
+            e.events().publish(
+                (Symbol::new(&e, "sweep_expired"), pool_address, user),
+                (shares, tokens),
+            );
+        }
+        (shares, tokens)
+    }
+
     fn user_balance(e: Env, pool: Address, user: Address) -> UserBalance {
         storage::get_user_balance(&e, &pool, &user)
     }
 
+    fn deposit_lock(e: Env, pool: Address, user: Address) -> Option<DepositLock> {
+        storage::get_deposit_lock(&e, &pool, &user)
+    }
+
     fn pool_data(e: Env, pool: Address) -> PoolBackstopData {
         load_pool_backstop_data(&e, &pool)
     }
 
+    fn get_backstop_apr(e: Env, pool: Address) -> BackstopApr {
+        load_pool_backstop_apr(&e, &pool)
+    }
+
+    fn get_pool_coverage(e: Env, pool: Address) -> i128 {
+        backstop::calc_pool_coverage(
+            &load_pool_backstop_data(&e, &pool),
+            backstop::load_pool_liabilities(&e, &pool),
+        )
+    }
+
+    fn q4w_lock_time(e: Env, pool: Address) -> u64 {
+        storage::get_q4w_lock_time(&e, &pool)
+    }
+
+    fn voting_weight(e: Env, pool: Address, user: Address, sequence: u32) -> VoteSnapshot {
+        backstop::load_voting_weight(&e, &pool, &user, sequence)
+    }
+
     fn backstop_token(e: Env) -> Address {
         storage::get_backstop_token(&e)
     }
 
+    fn emitter(e: Env) -> Address {
+        storage::get_emitter(&e)
+    }
+
     /********** Emissions **********/
 
     fn gulp_emissions(e: Env) {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         let new_tokens_emitted = emissions::gulp_emissions(&e);
 
         e.events()
@@ -287,6 +671,7 @@ impl Backstop for BackstopContract {
 
     fn add_reward(e: Env, to_add: Address, to_remove: Address) {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         emissions::add_to_reward_zone(&e, to_add.clone(), to_remove.clone());
 
         e.events()
@@ -295,20 +680,62 @@ impl Backstop for BackstopContract {
 
     fn gulp_pool_emissions(e: Env, pool_address: Address) -> i128 {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         pool_address.require_auth();
         emissions::gulp_pool_emissions(&e, &pool_address)
     }
 
     fn claim(e: Env, from: Address, pool_addresses: Vec<Address>, to: Address) -> i128 {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         from.require_auth();
 
         let amount = emissions::execute_claim(&e, &from, &pool_addresses, &to);
 
-        e.events().publish((Symbol::new(&e, "claim"), from), amount);
+        e.events()
+            .publish((Symbol::new(&e, "claim"), from, to), amount);
         amount
     }
 
+    fn compound(e: Env, from: Address, pool_addresses: Vec<Address>, min_lp_out: i128) -> i128 {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        require_nonnegative(&e, min_lp_out);
+        from.require_auth();
+
+        let lp_tokens_out = emissions::execute_compound(&e, &from, &pool_addresses, min_lp_out);
+
+        e.events()
+            .publish((Symbol::new(&e, "compound"), from), lp_tokens_out);
+        lp_tokens_out
+    }
+
+    fn recycle_unclaimed(e: Env, user: Address, pool_address: Address) -> i128 {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+
+        let recycled = emissions::execute_recycle_unclaimed(&e, &user, &pool_address);
+        if recycled > 0 {
+            e.events().publish(
+                (Symbol::new(&e, "recycle_unclaimed"), pool_address, user),
+                recycled,
+            );
+        }
+        recycled
+    }
+
+    fn set_claim_deadline(e: Env, deadline: u64) {
+        storage::extend_instance(&e);
+        emissions::execute_set_claim_deadline(&e, deadline);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_claim_deadline"),), deadline);
+    }
+
+    fn claim_deadline(e: Env) -> u64 {
+        storage::get_claim_deadline(&e)
+    }
+
     fn drop(e: Env) {
         EmitterClient::new(&e, &storage::get_emitter(&e)).drop(&storage::get_drop_list(&e))
     }
@@ -317,9 +744,13 @@ impl Backstop for BackstopContract {
 
     fn draw(e: Env, pool_address: Address, amount: i128, to: Address) {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         pool_address.require_auth();
 
         backstop::execute_draw(&e, &pool_address, amount, &to);
+        // the pool cannot be queried while it is the caller, so the last known liabilities are used
+        let liabilities = storage::get_pool_coverage(&e, &pool_address).liabilities;
+        backstop::update_pool_coverage(&e, &pool_address, liabilities);
 
         e.events()
             .publish((Symbol::new(&e, "draw"), pool_address), (to, amount));
@@ -327,6 +758,7 @@ impl Backstop for BackstopContract {
 
     fn donate(e: Env, from: Address, pool_address: Address, amount: i128) {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         from.require_auth();
         pool_address.require_auth();
 
@@ -344,6 +776,87 @@ impl Backstop for BackstopContract {
 
         backstop::execute_update_comet_token_value(&e, &backstop_token, &blnd_token, &usdc_token)
     }
+
+    /********** Upgrade **********/
+
+    fn queue_upgrade(e: Env, wasm_hash: BytesN<32>) {
+        storage::extend_instance(&e);
+        let queued_upgrade = upgrade::execute_queue_upgrade(&e, &wasm_hash);
+
+        e.events()
+            .publish((Symbol::new(&e, "queue_upgrade"),), queued_upgrade);
+    }
+
+    fn cancel_upgrade(e: Env) {
+        storage::extend_instance(&e);
+        let queued_upgrade = upgrade::execute_cancel_upgrade(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "cancel_upgrade"),), queued_upgrade);
+    }
+
+    fn upgrade(e: Env) {
+        storage::extend_instance(&e);
+        let wasm_hash = upgrade::execute_upgrade(&e);
+
+        e.events().publish((Symbol::new(&e, "upgrade"),), wasm_hash);
+    }
+
+    fn migrate(e: Env) {
+        storage::extend_instance(&e);
+        let version = upgrade::execute_migrate(&e);
+
+        e.events().publish((Symbol::new(&e, "migrate"),), version);
+    }
+
+    fn storage_version(e: Env) -> u32 {
+        storage::get_storage_version(&e)
+    }
+
+    fn get_queued_upgrade(e: Env) -> Option<QueuedUpgrade> {
+        storage::get_queued_upgrade(&e)
+    }
+
+    fn queue_threshold_config(e: Env, config: ThresholdConfig) {
+        storage::extend_instance(&e);
+        let queued_config = threshold::execute_queue_threshold_config(&e, &config);
+
+        e.events()
+            .publish((Symbol::new(&e, "queue_threshold_config"),), queued_config);
+    }
+
+    fn cancel_threshold_config(e: Env) {
+        storage::extend_instance(&e);
+        let queued_config = threshold::execute_cancel_threshold_config(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "cancel_threshold_config"),), queued_config);
+    }
+
+    fn set_threshold_config(e: Env) {
+        storage::extend_instance(&e);
+        let config = threshold::execute_set_threshold_config(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_threshold_config"),), config);
+    }
+
+    fn threshold_config(e: Env) -> ThresholdConfig {
+        storage::get_threshold_config(&e)
+    }
+
+    fn wind_down(e: Env, pool_address: Address) -> i128 {
+        storage::extend_instance(&e);
+        let residual = backstop::execute_wind_down(&e, &pool_address);
+
+        e.events()
+            .publish((Symbol::new(&e, "wind_down"), pool_address), residual);
+        residual
+    }
+
+    fn wind_down_time(e: Env, pool: Address) -> Option<u64> {
+        storage::get_wind_down_time(&e, &pool)
+    }
 }
 
 /// Require that an incoming amount is not negative
@@ -358,3 +871,14 @@ pub fn require_nonnegative(e: &Env, amount: i128) {
         panic_with_error!(e, BackstopError::NegativeAmountError);
     }
 }
+
+/// Require that the protocol is not paused by the pause registry
+///
+/// ### Errors
+/// If the pause registry reports the protocol as paused
+pub fn require_not_paused(e: &Env) {
+    let pause_registry = storage::get_pause_registry(e);
+    if PauseRegistryClient::new(e, &pause_registry).is_paused() {
+        panic_with_error!(e, BackstopError::ContractPaused);
+    }
+}
@@ -9,3 +9,9 @@ pub use comet::WASM as COMET_WASM;
 
 mod emitter;
 pub use emitter::Client as EmitterClient;
+
+mod pause_registry;
+pub use pause_registry::Client as PauseRegistryClient;
+
+mod pool;
+pub use pool::PoolClient;
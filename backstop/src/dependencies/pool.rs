@@ -0,0 +1,17 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// The subset of the pool interface used by the backstop. The pool's wasm is not imported as
+/// the pool depends on the backstop's wasm.
+#[allow(dead_code)]
+#[contractclient(name = "PoolClient")]
+pub trait Pool {
+    /// Fetch the total liabilities of the pool, valued in the oracle's base asset with 7 decimals
+    fn get_total_liabilities(e: Env) -> i128;
+
+    /// Fetch the total liabilities of the pool, valued in an asset priced by the pool's oracle
+    /// with 7 decimals
+    fn get_total_liabilities_in(e: Env, asset: Address) -> i128;
+
+    /// Fetch the pool's status
+    fn get_status(e: Env) -> u32;
+}
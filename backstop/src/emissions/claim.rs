@@ -1,14 +1,61 @@
-use crate::{dependencies::CometClient, errors::BackstopError, storage};
+use crate::{
+    backstop::{checkpoint_voting_weight, release_expired_lock},
+    constants::{MAX_CLAIM_DEADLINE, MIN_CLAIM_DEADLINE},
+    dependencies::CometClient,
+    errors::BackstopError,
+    storage::{self, UserEmissionData},
+    upgrade::require_admin,
+};
+use sep_41_token::TokenClient;
 use soroban_fixed_point_math::FixedPoint;
 use soroban_sdk::{
     auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
     panic_with_error, vec, Address, Env, IntoVal, Map, Symbol, Val, Vec,
 };
 
-use super::distributor::claim_emissions;
+use super::distributor::{claim_emissions, update_emissions};
 
-/// Perform a claim for backstop deposit emissions by a user from the backstop module
+/// Perform a claim for backstop deposit emissions by a user from the backstop module. The
+/// claimed emissions are deposited into each pool's backstop on behalf of `to`.
+///
+/// `from` MUST be authenticated before calling. `to` does not need to authorize the claim.
 pub fn execute_claim(e: &Env, from: &Address, pool_addresses: &Vec<Address>, to: &Address) -> i128 {
+    let (claimed, _) = claim_and_deposit(e, from, pool_addresses, to, 0);
+    claimed
+}
+
+/// Perform a claim for backstop deposit emissions by a user from the backstop module, and
+/// deposit the emissions back into each pool's backstop on behalf of the user.
+///
+/// Returns the number of backstop tokens deposited
+///
+/// `from` MUST be authenticated before calling.
+///
+/// ### Arguments
+/// * `from` - The user compounding their emissions
+/// * `pool_addresses` - The pools to claim and deposit backstop emissions for
+/// * `min_lp_out` - The minimum number of backstop tokens to receive for the claimed BLND
+pub fn execute_compound(
+    e: &Env,
+    from: &Address,
+    pool_addresses: &Vec<Address>,
+    min_lp_out: i128,
+) -> i128 {
+    let (_, lp_tokens_out) = claim_and_deposit(e, from, pool_addresses, from, min_lp_out);
+    lp_tokens_out
+}
+
+/// Claim backstop deposit emissions for `from`, join the backstop token with the claimed BLND,
+/// and deposit the backstop tokens into each pool's backstop on behalf of `to`
+///
+/// Returns (claimed BLND, backstop tokens deposited)
+fn claim_and_deposit(
+    e: &Env,
+    from: &Address,
+    pool_addresses: &Vec<Address>,
+    to: &Address,
+    min_lp_out: i128,
+) -> (i128, i128) {
     if pool_addresses.is_empty() {
         panic_with_error!(e, BackstopError::BadRequest);
     }
@@ -19,11 +66,19 @@ pub fn execute_claim(e: &Env, from: &Address, pool_addresses: &Vec<Address>, to:
         let pool_balance = storage::get_pool_balance(e, &pool_id);
         let user_balance = storage::get_user_balance(e, &pool_id, from);
         let claim_amt = claim_emissions(e, &pool_id, &pool_balance, from, &user_balance);
+        release_expired_lock(e, &pool_id, from);
 
         claimed += claim_amt;
-        claims.set(pool_id, claim_amt);
+        // a pool included more than once is only claimed the first time
+        if claim_amt > 0 {
+            claims.set(
+                pool_id.clone(),
+                claims.get(pool_id).unwrap_or(0) + claim_amt,
+            );
+        }
     }
 
+    let mut lp_tokens_out: i128 = 0;
     if claimed > 0 {
         let blnd_id = storage::get_blnd_token(e);
         let lp_id = storage::get_backstop_token(e);
@@ -43,60 +98,643 @@ pub fn execute_claim(e: &Env, from: &Address, pool_addresses: &Vec<Address>, to:
                     fn_name: Symbol::new(e, "approve"),
                     args: args.clone(),
                 },
-                sub_invocations: vec![e],
-            }),
-        ]);
-        let lp_tokens_out = CometClient::new(e, &lp_id).dep_tokn_amt_in_get_lp_tokns_out(
-            &blnd_id,
-            &claimed,
-            &0,
-            &e.current_contract_address(),
-        );
-        for pool_id in pool_addresses.iter() {
-            let claim_amount = claims.get(pool_id.clone()).unwrap();
-            let deposit_amount = lp_tokens_out
-                .fixed_mul_floor(claim_amount, claimed)
-                .unwrap();
-            let mut pool_balance = storage::get_pool_balance(e, &pool_id);
-            let mut user_balance = storage::get_user_balance(e, &pool_id, to);
-
-            // Deposit LP tokens into pool backstop
-            let to_mint = pool_balance.convert_to_shares(deposit_amount);
-            pool_balance.deposit(deposit_amount, to_mint);
-            user_balance.add_shares(to_mint);
-
-            storage::set_pool_balance(e, &pool_id, &pool_balance);
-            storage::set_user_balance(e, &pool_id, to, &user_balance);
-            e.events().publish(
-                (Symbol::new(&e, "deposit"), pool_id, to),
-                (deposit_amount, to_mint),
+                sub_invocations: vec![e],
+            }),
+        ]);
+        lp_tokens_out = CometClient::new(e, &lp_id).dep_tokn_amt_in_get_lp_tokns_out(
+            &blnd_id,
+            &claimed,
+            &min_lp_out,
+            &e.current_contract_address(),
+        );
+        // deposit once per pool that had emissions to claim
+        for (pool_id, claim_amount) in claims.iter() {
+            let deposit_amount = lp_tokens_out
+                .fixed_mul_floor(claim_amount, claimed)
+                .unwrap();
+            if storage::get_wind_down_time(e, &pool_id).is_some() {
+                // a winding down backstop does not accept deposits, so send the tokens to `to`
+                TokenClient::new(e, &lp_id).transfer(
+                    &e.current_contract_address(),
+                    to,
+                    &deposit_amount,
+                );
+                continue;
+            }
+            let mut pool_balance = storage::get_pool_balance(e, &pool_id);
+            let mut user_balance = storage::get_user_balance(e, &pool_id, to);
+            if to != from {
+                // `from` was updated during the claim, but `to` needs to accrue emissions
+                // against their existing balance before receiving new shares
+                update_emissions(e, &pool_id, &pool_balance, to, &user_balance);
+            }
+
+            // Deposit LP tokens into pool backstop
+            let to_mint = pool_balance.convert_to_shares(deposit_amount);
+            pool_balance.deposit(deposit_amount, to_mint);
+            user_balance.add_shares(to_mint);
+
+            checkpoint_voting_weight(e, &pool_id, to, user_balance.shares);
+            storage::set_pool_balance(e, &pool_id, &pool_balance);
+            storage::set_user_balance(e, &pool_id, to, &user_balance);
+            e.events().publish(
+                (Symbol::new(&e, "deposit"), pool_id, to),
+                (deposit_amount, to_mint),
+            );
+        }
+    }
+
+    (claimed, lp_tokens_out)
+}
+
+/// Recycle a user's backstop emissions for a pool that have gone unclaimed for longer than the
+/// claim deadline. The emissions are carried over to the next `gulp_emissions`. Anyone can recycle
+/// unclaimed emissions for any user.
+///
+/// The deadline is measured from the last time the user's unclaimed emissions were updated, so any
+/// interaction with the pool's backstop restarts it. A user whose accrual time has not been
+/// recorded yet has it recorded now, so they always have a full claim deadline to claim before
+/// anything is recycled.
+///
+/// Returns the amount of emissions recycled
+///
+/// ### Arguments
+/// * `user` - The user whose unclaimed emissions are being recycled
+/// * `pool_id` - The pool whose backstop the emissions are for
+pub fn execute_recycle_unclaimed(e: &Env, user: &Address, pool_id: &Address) -> i128 {
+    // the accrual time is read before the user's emissions are updated, as the update would
+    // restart it
+    let accrual_time = storage::get_user_accrual_time(e, pool_id, user);
+    let pool_balance = storage::get_pool_balance(e, pool_id);
+    let user_balance = storage::get_user_balance(e, pool_id, user);
+    update_emissions(e, pool_id, &pool_balance, user, &user_balance);
+
+    let user_data = match storage::get_user_emis_data(e, pool_id, user) {
+        Some(user_data) if user_data.accrued > 0 => user_data,
+        _ => return 0,
+    };
+    let accrual_time = match accrual_time {
+        Some(time) => time,
+        None => {
+            storage::set_user_accrual_time(e, pool_id, user, e.ledger().timestamp());
+            return 0;
+        }
+    };
+    if accrual_time + storage::get_claim_deadline(e) > e.ledger().timestamp() {
+        return 0;
+    }
+    storage::set_user_emis_data(
+        e,
+        pool_id,
+        user,
+        &UserEmissionData {
+            index: user_data.index,
+            accrued: 0,
+        },
+    );
+    storage::del_user_accrual_time(e, pool_id, user);
+    storage::set_residual_emissions(e, storage::get_residual_emissions(e) + user_data.accrued);
+    user_data.accrued
+}
+
+/// Set the time in seconds a user's emissions can go unclaimed before they can be recycled
+///
+/// ### Arguments
+/// * `deadline` - The claim deadline in seconds
+///
+/// ### Panics
+/// If the caller is not the pause registry admin or the deadline is outside of [1 year, 10 years]
+pub fn execute_set_claim_deadline(e: &Env, deadline: u64) {
+    require_admin(e);
+    if !(MIN_CLAIM_DEADLINE..=MAX_CLAIM_DEADLINE).contains(&deadline) {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+    storage::set_claim_deadline(e, deadline);
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{
+        backstop::{DepositLock, PoolBalance, UserBalance},
+        constants::{DEFAULT_CLAIM_DEADLINE, SCALAR_7},
+        storage::{BackstopEmissionConfig, BackstopEmissionsData},
+        testutils::{
+            create_backstop, create_blnd_token, create_comet_lp_pool, create_pause_registry,
+            create_usdc_token,
+        },
+    };
+
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        unwrap::UnwrapOptimized,
+        vec,
+    };
+
+    /********** claim **********/
+
+    #[test]
+    fn test_claim() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let block_timestamp = 1500000000 + 12345;
+        e.ledger().set(LedgerInfo {
+            timestamp: block_timestamp,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        e.budget().reset_unlimited();
+
+        let backstop_address = create_backstop(&e);
+        let pool_1_id = Address::generate(&e);
+        let pool_2_id = Address::generate(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let (blnd_address, blnd_token_client) = create_blnd_token(&e, &backstop_address, &bombadil);
+        let (usdc_address, _) = create_usdc_token(&e, &backstop_address, &bombadil);
+        blnd_token_client.mint(&backstop_address, &100_0000000);
+        let backstop_1_emissions_config = BackstopEmissionConfig {
+            expiration: 1500000000 + 7 * 24 * 60 * 60,
+            eps: 0_1000000,
+        };
+        let backstop_1_emissions_data = BackstopEmissionsData {
+            index: 22222,
+            last_time: 1500000000,
+        };
+        let user_1_emissions_data = UserEmissionData {
+            index: 11111,
+            accrued: 1_2345678,
+        };
+
+        let backstop_2_emissions_config = BackstopEmissionConfig {
+            expiration: 1500000000 + 7 * 24 * 60 * 60,
+            eps: 0_0200000,
+        };
+        let backstop_2_emissions_data = BackstopEmissionsData {
+            index: 0,
+            last_time: 1500010000,
+        };
+        let user_2_emissions_data = UserEmissionData {
+            index: 0,
+            accrued: 0,
+        };
+        let (lp_address, lp_client) =
+            create_comet_lp_pool(&e, &bombadil, &blnd_address, &usdc_address);
+        e.as_contract(&backstop_address, || {
+            storage::set_backstop_emis_config(&e, &pool_1_id, &backstop_1_emissions_config);
+            storage::set_backstop_emis_data(&e, &pool_1_id, &backstop_1_emissions_data);
+            storage::set_user_emis_data(&e, &pool_1_id, &samwise, &user_1_emissions_data);
+            storage::set_user_accrual_time(&e, &pool_1_id, &samwise, 1500000000);
+            storage::set_backstop_emis_config(&e, &pool_2_id, &backstop_2_emissions_config);
+            storage::set_backstop_emis_data(&e, &pool_2_id, &backstop_2_emissions_data);
+            storage::set_user_emis_data(&e, &pool_2_id, &samwise, &user_2_emissions_data);
+            storage::set_backstop_token(&e, &lp_address);
+            storage::set_blnd_token(&e, &blnd_address);
+
+            storage::set_pool_balance(
+                &e,
+                &pool_1_id,
+                &PoolBalance {
+                    shares: 150_0000000,
+                    tokens: 200_0000000,
+                    q4w: 2_0000000,
+                },
+            );
+            storage::set_user_balance(
+                &e,
+                &pool_1_id,
+                &samwise,
+                &UserBalance {
+                    shares: 9_0000000,
+                    q4w: vec![&e],
+                },
+            );
+            storage::set_pool_balance(
+                &e,
+                &pool_2_id,
+                &PoolBalance {
+                    shares: 70_0000000,
+                    tokens: 75_0000000,
+                    q4w: 3_5000000,
+                },
+            );
+            storage::set_user_balance(
+                &e,
+                &pool_2_id,
+                &samwise,
+                &UserBalance {
+                    shares: 7_5000000,
+                    q4w: vec![&e],
+                },
+            );
+            let backstop_lp_balance = lp_client.balance(&backstop_address);
+            let pre_frodo_balance_1 = storage::get_user_balance(&e, &pool_1_id, &frodo).shares;
+            let pre_frodo_balance_2 = storage::get_user_balance(&e, &pool_2_id, &frodo).shares;
+            let pre_pool_tokens_1 = storage::get_pool_balance(&e, &pool_1_id).tokens;
+            let pre_pool_tokens_2 = storage::get_pool_balance(&e, &pool_2_id).tokens;
+            let pre_pool_shares_1 = storage::get_pool_balance(&e, &pool_1_id).shares;
+            let pre_pool_shares_2 = storage::get_pool_balance(&e, &pool_2_id).shares;
+            e.budget().reset_default();
+            let result = execute_claim(
+                &e,
+                &samwise,
+                &vec![&e, pool_1_id.clone(), pool_2_id.clone()],
+                &frodo,
+            );
+            assert_eq!(result, 75_3145677 + 6_2904190);
+            assert_eq!(
+                lp_client.balance(&backstop_address),
+                backstop_lp_balance + 6_4729326
+            );
+            assert_eq!(
+                blnd_token_client.balance(&backstop_address),
+                100_0000000 - (75_3145677 + 6_2904190)
+            );
+            let sam_balance_1 = storage::get_user_balance(&e, &pool_1_id, &samwise);
+            assert_eq!(sam_balance_1.shares, 9_0000000);
+            let frodo_balance_1 = storage::get_user_balance(&e, &pool_1_id, &frodo);
+            assert_eq!(frodo_balance_1.shares, pre_frodo_balance_1 + 4_5400274);
+            let sam_balance_2 = storage::get_user_balance(&e, &pool_2_id, &samwise);
+            assert_eq!(sam_balance_2.shares, 7_5000000);
+            let frodo_balance_2 = storage::get_user_balance(&e, &pool_2_id, &frodo);
+            assert_eq!(frodo_balance_2.shares, pre_frodo_balance_2 + 0_3915917);
+
+            let pool_balance_1 = storage::get_pool_balance(&e, &pool_1_id);
+            assert_eq!(pool_balance_1.tokens, pre_pool_tokens_1 + 6_0533699);
+            assert_eq!(pool_balance_1.shares, pre_pool_shares_1 + 4_5400274);
+            let pool_balance_2 = storage::get_pool_balance(&e, &pool_2_id);
+            assert_eq!(pool_balance_2.tokens, pre_pool_tokens_2 + 0_4195626);
+            assert_eq!(pool_balance_2.shares, pre_pool_shares_2 + 0_3915917);
+
+            let new_backstop_1_data =
+                storage::get_backstop_emis_data(&e, &pool_1_id).unwrap_optimized();
+            let new_user_1_data =
+                storage::get_user_emis_data(&e, &pool_1_id, &samwise).unwrap_optimized();
+            assert_eq!(new_backstop_1_data.last_time, block_timestamp);
+            assert_eq!(new_backstop_1_data.index, 83434384);
+            assert_eq!(new_user_1_data.accrued, 0);
+            assert_eq!(new_user_1_data.index, 83434384);
+            assert!(storage::get_user_accrual_time(&e, &pool_1_id, &samwise).is_none());
+
+            let new_backstop_2_data =
+                storage::get_backstop_emis_data(&e, &pool_2_id).unwrap_optimized();
+            let new_user_2_data =
+                storage::get_user_emis_data(&e, &pool_2_id, &samwise).unwrap_optimized();
+            assert_eq!(new_backstop_2_data.last_time, block_timestamp);
+            assert_eq!(new_backstop_2_data.index, 7052631);
+            assert_eq!(new_user_2_data.accrued, 0);
+            assert_eq!(new_user_2_data.index, 7052631);
+        });
+    }
+
+    #[test]
+    fn test_claim_duplicate_pool() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let block_timestamp = 1500000000 + 12345;
+        e.ledger().set(LedgerInfo {
+            timestamp: block_timestamp,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        e.budget().reset_unlimited();
+
+        let backstop_address = create_backstop(&e);
+        let pool_1_id = Address::generate(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let (blnd_address, blnd_token_client) = create_blnd_token(&e, &backstop_address, &bombadil);
+        let (usdc_address, _) = create_usdc_token(&e, &backstop_address, &bombadil);
+        blnd_token_client.mint(&backstop_address, &100_0000000);
+        let backstop_1_emissions_config = BackstopEmissionConfig {
+            expiration: 1500000000 + 7 * 24 * 60 * 60,
+            eps: 0_1000000,
+        };
+        let backstop_1_emissions_data = BackstopEmissionsData {
+            index: 22222,
+            last_time: 1500000000,
+        };
+        let user_1_emissions_data = UserEmissionData {
+            index: 11111,
+            accrued: 1_2345678,
+        };
+        let (lp_address, lp_client) =
+            create_comet_lp_pool(&e, &bombadil, &blnd_address, &usdc_address);
+        e.as_contract(&backstop_address, || {
+            storage::set_backstop_emis_config(&e, &pool_1_id, &backstop_1_emissions_config);
+            storage::set_backstop_emis_data(&e, &pool_1_id, &backstop_1_emissions_data);
+            storage::set_user_emis_data(&e, &pool_1_id, &samwise, &user_1_emissions_data);
+            storage::set_backstop_token(&e, &lp_address);
+            storage::set_blnd_token(&e, &blnd_address);
+
+            storage::set_pool_balance(
+                &e,
+                &pool_1_id,
+                &PoolBalance {
+                    shares: 150_0000000,
+                    tokens: 200_0000000,
+                    q4w: 2_0000000,
+                },
+            );
+            storage::set_user_balance(
+                &e,
+                &pool_1_id,
+                &samwise,
+                &UserBalance {
+                    shares: 9_0000000,
+                    q4w: vec![&e],
+                },
+            );
+            let backstop_lp_balance = lp_client.balance(&backstop_address);
+            let pre_pool_tokens_1 = storage::get_pool_balance(&e, &pool_1_id).tokens;
+
+            let result = execute_claim(
+                &e,
+                &samwise,
+                &vec![&e, pool_1_id.clone(), pool_1_id.clone()],
+                &frodo,
+            );
+            assert_eq!(result, 76_3155135);
+
+            // all backstop tokens joined with the claimed BLND are deposited for frodo
+            let lp_tokens_out = lp_client.balance(&backstop_address) - backstop_lp_balance;
+            assert!(lp_tokens_out > 0);
+            let pool_balance_1 = storage::get_pool_balance(&e, &pool_1_id);
+            assert_eq!(pool_balance_1.tokens, pre_pool_tokens_1 + lp_tokens_out);
+            let frodo_balance_1 = storage::get_user_balance(&e, &pool_1_id, &frodo);
+            assert_eq!(frodo_balance_1.shares, pool_balance_1.shares - 150_0000000);
+        });
+    }
+
+    #[test]
+    fn test_compound() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let block_timestamp = 1500000000 + 12345;
+        e.ledger().set(LedgerInfo {
+            timestamp: block_timestamp,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        e.budget().reset_unlimited();
+
+        let backstop_address = create_backstop(&e);
+        let pool_1_id = Address::generate(&e);
+        let pool_2_id = Address::generate(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let (blnd_address, blnd_token_client) = create_blnd_token(&e, &backstop_address, &bombadil);
+        let (usdc_address, _) = create_usdc_token(&e, &backstop_address, &bombadil);
+        blnd_token_client.mint(&backstop_address, &100_0000000);
+        let backstop_1_emissions_config = BackstopEmissionConfig {
+            expiration: 1500000000 + 7 * 24 * 60 * 60,
+            eps: 0_1000000,
+        };
+        let backstop_1_emissions_data = BackstopEmissionsData {
+            index: 22222,
+            last_time: 1500000000,
+        };
+        let user_1_emissions_data = UserEmissionData {
+            index: 11111,
+            accrued: 1_2345678,
+        };
+
+        let backstop_2_emissions_config = BackstopEmissionConfig {
+            expiration: 1500000000 + 7 * 24 * 60 * 60,
+            eps: 0_0200000,
+        };
+        let backstop_2_emissions_data = BackstopEmissionsData {
+            index: 0,
+            last_time: 1500010000,
+        };
+        let user_2_emissions_data = UserEmissionData {
+            index: 0,
+            accrued: 0,
+        };
+        let (lp_address, lp_client) =
+            create_comet_lp_pool(&e, &bombadil, &blnd_address, &usdc_address);
+        e.as_contract(&backstop_address, || {
+            storage::set_backstop_emis_config(&e, &pool_1_id, &backstop_1_emissions_config);
+            storage::set_backstop_emis_data(&e, &pool_1_id, &backstop_1_emissions_data);
+            storage::set_user_emis_data(&e, &pool_1_id, &samwise, &user_1_emissions_data);
+            storage::set_backstop_emis_config(&e, &pool_2_id, &backstop_2_emissions_config);
+            storage::set_backstop_emis_data(&e, &pool_2_id, &backstop_2_emissions_data);
+            storage::set_user_emis_data(&e, &pool_2_id, &samwise, &user_2_emissions_data);
+            storage::set_backstop_token(&e, &lp_address);
+            storage::set_blnd_token(&e, &blnd_address);
+
+            storage::set_pool_balance(
+                &e,
+                &pool_1_id,
+                &PoolBalance {
+                    shares: 150_0000000,
+                    tokens: 200_0000000,
+                    q4w: 2_0000000,
+                },
+            );
+            storage::set_user_balance(
+                &e,
+                &pool_1_id,
+                &samwise,
+                &UserBalance {
+                    shares: 9_0000000,
+                    q4w: vec![&e],
+                },
+            );
+            storage::set_pool_balance(
+                &e,
+                &pool_2_id,
+                &PoolBalance {
+                    shares: 70_0000000,
+                    tokens: 75_0000000,
+                    q4w: 3_5000000,
+                },
+            );
+            storage::set_user_balance(
+                &e,
+                &pool_2_id,
+                &samwise,
+                &UserBalance {
+                    shares: 7_5000000,
+                    q4w: vec![&e],
+                },
+            );
+            let backstop_lp_balance = lp_client.balance(&backstop_address);
+            let pre_frodo_balance_1 = storage::get_user_balance(&e, &pool_1_id, &frodo).shares;
+            let pre_pool_tokens_1 = storage::get_pool_balance(&e, &pool_1_id).tokens;
+            let pre_pool_tokens_2 = storage::get_pool_balance(&e, &pool_2_id).tokens;
+            let pre_pool_shares_1 = storage::get_pool_balance(&e, &pool_1_id).shares;
+            let pre_pool_shares_2 = storage::get_pool_balance(&e, &pool_2_id).shares;
+            e.budget().reset_default();
+            let result = execute_compound(
+                &e,
+                &samwise,
+                &vec![&e, pool_1_id.clone(), pool_2_id.clone()],
+                6_4729326,
+            );
+            assert_eq!(result, 6_4729326);
+            assert_eq!(
+                lp_client.balance(&backstop_address),
+                backstop_lp_balance + 6_4729326
+            );
+            assert_eq!(
+                blnd_token_client.balance(&backstop_address),
+                100_0000000 - (75_3145677 + 6_2904190)
+            );
+            let sam_balance_1 = storage::get_user_balance(&e, &pool_1_id, &samwise);
+            assert_eq!(sam_balance_1.shares, 9_0000000 + 4_5400274);
+            let sam_balance_2 = storage::get_user_balance(&e, &pool_2_id, &samwise);
+            assert_eq!(sam_balance_2.shares, 7_5000000 + 0_3915917);
+            let frodo_balance_1 = storage::get_user_balance(&e, &pool_1_id, &frodo);
+            assert_eq!(frodo_balance_1.shares, pre_frodo_balance_1);
+
+            let pool_balance_1 = storage::get_pool_balance(&e, &pool_1_id);
+            assert_eq!(pool_balance_1.tokens, pre_pool_tokens_1 + 6_0533699);
+            assert_eq!(pool_balance_1.shares, pre_pool_shares_1 + 4_5400274);
+            let pool_balance_2 = storage::get_pool_balance(&e, &pool_2_id);
+            assert_eq!(pool_balance_2.tokens, pre_pool_tokens_2 + 0_4195626);
+            assert_eq!(pool_balance_2.shares, pre_pool_shares_2 + 0_3915917);
+
+            let new_user_1_data =
+                storage::get_user_emis_data(&e, &pool_1_id, &samwise).unwrap_optimized();
+            assert_eq!(new_user_1_data.accrued, 0);
+            assert_eq!(new_user_1_data.index, 83434384);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_compound_under_min_lp_out() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let block_timestamp = 1500000000 + 12345;
+        e.ledger().set(LedgerInfo {
+            timestamp: block_timestamp,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        e.budget().reset_unlimited();
+
+        let backstop_address = create_backstop(&e);
+        let pool_1_id = Address::generate(&e);
+        let pool_2_id = Address::generate(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let (blnd_address, blnd_token_client) = create_blnd_token(&e, &backstop_address, &bombadil);
+        let (usdc_address, _) = create_usdc_token(&e, &backstop_address, &bombadil);
+        blnd_token_client.mint(&backstop_address, &100_0000000);
+        let backstop_1_emissions_config = BackstopEmissionConfig {
+            expiration: 1500000000 + 7 * 24 * 60 * 60,
+            eps: 0_1000000,
+        };
+        let backstop_1_emissions_data = BackstopEmissionsData {
+            index: 22222,
+            last_time: 1500000000,
+        };
+        let user_1_emissions_data = UserEmissionData {
+            index: 11111,
+            accrued: 1_2345678,
+        };
+
+        let backstop_2_emissions_config = BackstopEmissionConfig {
+            expiration: 1500000000 + 7 * 24 * 60 * 60,
+            eps: 0_0200000,
+        };
+        let backstop_2_emissions_data = BackstopEmissionsData {
+            index: 0,
+            last_time: 1500010000,
+        };
+        let user_2_emissions_data = UserEmissionData {
+            index: 0,
+            accrued: 0,
+        };
+        let (lp_address, _) = create_comet_lp_pool(&e, &bombadil, &blnd_address, &usdc_address);
+        e.as_contract(&backstop_address, || {
+            storage::set_backstop_emis_config(&e, &pool_1_id, &backstop_1_emissions_config);
+            storage::set_backstop_emis_data(&e, &pool_1_id, &backstop_1_emissions_data);
+            storage::set_user_emis_data(&e, &pool_1_id, &samwise, &user_1_emissions_data);
+            storage::set_backstop_emis_config(&e, &pool_2_id, &backstop_2_emissions_config);
+            storage::set_backstop_emis_data(&e, &pool_2_id, &backstop_2_emissions_data);
+            storage::set_user_emis_data(&e, &pool_2_id, &samwise, &user_2_emissions_data);
+            storage::set_backstop_token(&e, &lp_address);
+            storage::set_blnd_token(&e, &blnd_address);
+
+            storage::set_pool_balance(
+                &e,
+                &pool_1_id,
+                &PoolBalance {
+                    shares: 150_0000000,
+                    tokens: 200_0000000,
+                    q4w: 2_0000000,
+                },
+            );
+            storage::set_user_balance(
+                &e,
+                &pool_1_id,
+                &samwise,
+                &UserBalance {
+                    shares: 9_0000000,
+                    q4w: vec![&e],
+                },
+            );
+            storage::set_pool_balance(
+                &e,
+                &pool_2_id,
+                &PoolBalance {
+                    shares: 70_0000000,
+                    tokens: 75_0000000,
+                    q4w: 3_5000000,
+                },
             );
-        }
+            storage::set_user_balance(
+                &e,
+                &pool_2_id,
+                &samwise,
+                &UserBalance {
+                    shares: 7_5000000,
+                    q4w: vec![&e],
+                },
+            );
+            e.budget().reset_default();
+            execute_compound(
+                &e,
+                &samwise,
+                &vec![&e, pool_1_id.clone(), pool_2_id.clone()],
+                6_4729327,
+            );
+        });
     }
 
-    claimed
-}
-
-#[cfg(test)]
-mod tests {
-
-    use crate::{
-        backstop::{PoolBalance, UserBalance},
-        storage::{BackstopEmissionConfig, BackstopEmissionsData, UserEmissionData},
-        testutils::{create_backstop, create_blnd_token, create_comet_lp_pool, create_usdc_token},
-    };
-
-    use super::*;
-    use soroban_sdk::{
-        testutils::{Address as _, Ledger, LedgerInfo},
-        unwrap::UnwrapOptimized,
-        vec,
-    };
-
-    /********** claim **********/
-
     #[test]
-    fn test_claim() {
+    fn test_claim_to_accrues_recipient_emissions() {
         let e = Env::default();
         e.mock_all_auths();
         let block_timestamp = 1500000000 + 12345;
@@ -156,6 +794,24 @@ mod tests {
             storage::set_backstop_emis_config(&e, &pool_2_id, &backstop_2_emissions_config);
             storage::set_backstop_emis_data(&e, &pool_2_id, &backstop_2_emissions_data);
             storage::set_user_emis_data(&e, &pool_2_id, &samwise, &user_2_emissions_data);
+            storage::set_user_emis_data(
+                &e,
+                &pool_1_id,
+                &frodo,
+                &UserEmissionData {
+                    index: 11111,
+                    accrued: 0_1000000,
+                },
+            );
+            storage::set_user_balance(
+                &e,
+                &pool_1_id,
+                &frodo,
+                &UserBalance {
+                    shares: 5_0000000,
+                    q4w: vec![&e],
+                },
+            );
             storage::set_backstop_token(&e, &lp_address);
             storage::set_blnd_token(&e, &blnd_address);
 
@@ -234,23 +890,21 @@ mod tests {
             assert_eq!(pool_balance_2.tokens, pre_pool_tokens_2 + 0_4195626);
             assert_eq!(pool_balance_2.shares, pre_pool_shares_2 + 0_3915917);
 
-            let new_backstop_1_data =
-                storage::get_backstop_emis_data(&e, &pool_1_id).unwrap_optimized();
-            let new_user_1_data =
-                storage::get_user_emis_data(&e, &pool_1_id, &samwise).unwrap_optimized();
-            assert_eq!(new_backstop_1_data.last_time, block_timestamp);
-            assert_eq!(new_backstop_1_data.index, 83434384);
-            assert_eq!(new_user_1_data.accrued, 0);
-            assert_eq!(new_user_1_data.index, 83434384);
-
-            let new_backstop_2_data =
-                storage::get_backstop_emis_data(&e, &pool_2_id).unwrap_optimized();
-            let new_user_2_data =
-                storage::get_user_emis_data(&e, &pool_2_id, &samwise).unwrap_optimized();
-            assert_eq!(new_backstop_2_data.last_time, block_timestamp);
-            assert_eq!(new_backstop_2_data.index, 7052631);
-            assert_eq!(new_user_2_data.accrued, 0);
-            assert_eq!(new_user_2_data.index, 7052631);
+            // frodo accrues emissions against their balance before receiving new shares
+            let new_frodo_1_data =
+                storage::get_user_emis_data(&e, &pool_1_id, &frodo).unwrap_optimized();
+            assert_eq!(new_frodo_1_data.index, 83434384);
+            assert_eq!(
+                new_frodo_1_data.accrued,
+                0_1000000
+                    + 5_0000000
+                        .fixed_mul_floor(83434384 - 11111, SCALAR_7)
+                        .unwrap_optimized()
+            );
+            let new_frodo_2_data =
+                storage::get_user_emis_data(&e, &pool_2_id, &frodo).unwrap_optimized();
+            assert_eq!(new_frodo_2_data.index, 7052631);
+            assert_eq!(new_frodo_2_data.accrued, 0);
         });
     }
 
@@ -576,4 +1230,283 @@ mod tests {
             assert_eq!(new_user_2_data.index, 6700000);
         });
     }
+
+    #[test]
+    fn test_claim_releases_expired_lock() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let block_timestamp = 1500000000 + 12345;
+        e.ledger().set(LedgerInfo {
+            timestamp: block_timestamp,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop_address = create_backstop(&e);
+        let pool_1_id = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        e.as_contract(&backstop_address, || {
+            storage::set_user_balance(
+                &e,
+                &pool_1_id,
+                &samwise,
+                &UserBalance {
+                    shares: 10_0000000,
+                    q4w: vec![&e],
+                },
+            );
+            storage::set_deposit_lock(
+                &e,
+                &pool_1_id,
+                &samwise,
+                &DepositLock {
+                    shares: 10_0000000,
+                    bonus: 1_0000000,
+                    unlock_time: block_timestamp,
+                },
+            );
+            storage::set_lock_bonus(&e, &pool_1_id, 3_0000000);
+
+            let result = execute_claim(&e, &samwise, &vec![&e, pool_1_id.clone()], &frodo);
+            assert_eq!(result, 0);
+            assert!(storage::get_deposit_lock(&e, &pool_1_id, &samwise).is_none());
+            assert_eq!(storage::get_lock_bonus(&e, &pool_1_id), 2_0000000);
+        });
+    }
+
+    /********** recycle_unclaimed **********/
+
+    /// Setup a user with 5 BLND of emissions accrued for a pool whose emissions have expired
+    fn setup_unclaimed_emissions(
+        e: &Env,
+        backstop_address: &Address,
+        pool: &Address,
+        user: &Address,
+    ) {
+        e.as_contract(backstop_address, || {
+            storage::set_backstop_emis_config(
+                e,
+                pool,
+                &BackstopEmissionConfig {
+                    expiration: 1500000000 + 1000,
+                    eps: 0_1000000,
+                },
+            );
+            storage::set_backstop_emis_data(
+                e,
+                pool,
+                &BackstopEmissionsData {
+                    index: 1234567,
+                    last_time: 1500000000 + 1000,
+                },
+            );
+            storage::set_user_emis_data(
+                e,
+                pool,
+                user,
+                &UserEmissionData {
+                    index: 1234567,
+                    accrued: 5_0000000,
+                },
+            );
+            storage::set_pool_balance(
+                e,
+                pool,
+                &PoolBalance {
+                    shares: 150_0000000,
+                    tokens: 200_0000000,
+                    q4w: 0,
+                },
+            );
+            storage::set_user_balance(
+                e,
+                pool,
+                user,
+                &UserBalance {
+                    shares: 9_0000000,
+                    q4w: vec![e],
+                },
+            );
+            storage::set_residual_emissions(e, 1_0000000);
+        });
+    }
+
+    fn set_timestamp(e: &Env, timestamp: u64) {
+        e.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+    }
+
+    #[test]
+    fn test_recycle_unclaimed() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        set_timestamp(&e, 1500000000 + DEFAULT_CLAIM_DEADLINE);
+
+        let backstop_address = create_backstop(&e);
+        let pool_1_id = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        setup_unclaimed_emissions(&e, &backstop_address, &pool_1_id, &samwise);
+
+        e.as_contract(&backstop_address, || {
+            storage::set_user_accrual_time(&e, &pool_1_id, &samwise, 1500000000);
+
+            let result = execute_recycle_unclaimed(&e, &samwise, &pool_1_id);
+            assert_eq!(result, 5_0000000);
+            assert_eq!(storage::get_residual_emissions(&e), 6_0000000);
+            let user_data = storage::get_user_emis_data(&e, &pool_1_id, &samwise).unwrap();
+            assert_eq!(user_data.accrued, 0);
+            assert_eq!(user_data.index, 1234567);
+            assert!(storage::get_user_accrual_time(&e, &pool_1_id, &samwise).is_none());
+
+            // nothing is left to recycle
+            let result = execute_recycle_unclaimed(&e, &samwise, &pool_1_id);
+            assert_eq!(result, 0);
+            assert_eq!(storage::get_residual_emissions(&e), 6_0000000);
+        });
+    }
+
+    #[test]
+    fn test_recycle_unclaimed_before_deadline() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        set_timestamp(&e, 1500000000 + DEFAULT_CLAIM_DEADLINE - 1);
+
+        let backstop_address = create_backstop(&e);
+        let pool_1_id = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        setup_unclaimed_emissions(&e, &backstop_address, &pool_1_id, &samwise);
+
+        e.as_contract(&backstop_address, || {
+            storage::set_user_accrual_time(&e, &pool_1_id, &samwise, 1500000000);
+
+            let result = execute_recycle_unclaimed(&e, &samwise, &pool_1_id);
+            assert_eq!(result, 0);
+            assert_eq!(storage::get_residual_emissions(&e), 1_0000000);
+            let user_data = storage::get_user_emis_data(&e, &pool_1_id, &samwise).unwrap();
+            assert_eq!(user_data.accrued, 5_0000000);
+            assert_eq!(
+                storage::get_user_accrual_time(&e, &pool_1_id, &samwise),
+                Some(1500000000)
+            );
+        });
+    }
+
+    #[test]
+    fn test_recycle_unclaimed_records_accrual_time() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        let now = 1500000000 + DEFAULT_CLAIM_DEADLINE;
+        set_timestamp(&e, now);
+
+        let backstop_address = create_backstop(&e);
+        let pool_1_id = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        setup_unclaimed_emissions(&e, &backstop_address, &pool_1_id, &samwise);
+
+        // the accrual start was never recorded, so samwise has a full deadline from now to claim
+        e.as_contract(&backstop_address, || {
+            let result = execute_recycle_unclaimed(&e, &samwise, &pool_1_id);
+            assert_eq!(result, 0);
+            assert_eq!(
+                storage::get_user_accrual_time(&e, &pool_1_id, &samwise),
+                Some(now)
+            );
+        });
+
+        set_timestamp(&e, now + DEFAULT_CLAIM_DEADLINE);
+        e.as_contract(&backstop_address, || {
+            let result = execute_recycle_unclaimed(&e, &samwise, &pool_1_id);
+            assert_eq!(result, 5_0000000);
+            assert_eq!(storage::get_residual_emissions(&e), 6_0000000);
+        });
+    }
+
+    #[test]
+    fn test_recycle_unclaimed_restarted_by_update() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        let now = 1500000000 + DEFAULT_CLAIM_DEADLINE;
+        set_timestamp(&e, now);
+
+        let backstop_address = create_backstop(&e);
+        let pool_1_id = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        setup_unclaimed_emissions(&e, &backstop_address, &pool_1_id, &samwise);
+
+        // samwise interacts with the backstop just before the deadline, which restarts it
+        set_timestamp(&e, now - 1);
+        e.as_contract(&backstop_address, || {
+            storage::set_user_accrual_time(&e, &pool_1_id, &samwise, 1500000000);
+            storage::set_backstop_emis_data(
+                &e,
+                &pool_1_id,
+                &BackstopEmissionsData {
+                    index: 2345678,
+                    last_time: now - 1,
+                },
+            );
+            let pool_balance = storage::get_pool_balance(&e, &pool_1_id);
+            let user_balance = storage::get_user_balance(&e, &pool_1_id, &samwise);
+            update_emissions(&e, &pool_1_id, &pool_balance, &samwise, &user_balance);
+            assert_eq!(
+                storage::get_user_accrual_time(&e, &pool_1_id, &samwise),
+                Some(now - 1)
+            );
+        });
+
+        set_timestamp(&e, now);
+        e.as_contract(&backstop_address, || {
+            let result = execute_recycle_unclaimed(&e, &samwise, &pool_1_id);
+            assert_eq!(result, 0);
+            assert_eq!(storage::get_residual_emissions(&e), 1_0000000);
+        });
+    }
+
+    /********** set_claim_deadline **********/
+
+    #[test]
+    fn test_set_claim_deadline() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+
+        e.as_contract(&backstop_address, || {
+            assert_eq!(storage::get_claim_deadline(&e), DEFAULT_CLAIM_DEADLINE);
+            execute_set_claim_deadline(&e, MIN_CLAIM_DEADLINE);
+            assert_eq!(storage::get_claim_deadline(&e), MIN_CLAIM_DEADLINE);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_set_claim_deadline_too_short() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+
+        e.as_contract(&backstop_address, || {
+            execute_set_claim_deadline(&e, MIN_CLAIM_DEADLINE - 1);
+        });
+    }
 }
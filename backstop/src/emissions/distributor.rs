@@ -94,8 +94,10 @@ pub fn update_emission_data_with_config(
 
     let unqueued_shares = pool_balance.shares - pool_balance.q4w;
     require_nonnegative(e, unqueued_shares);
+    // locked shares are never queued, so their bonus shares earn emissions until released
+    let emission_shares = unqueued_shares + storage::get_lock_bonus(e, pool_id);
     let additional_idx = (i128(max_timestamp - emis_data.last_time) * i128(emis_config.eps))
-        .fixed_div_floor(unqueued_shares, SCALAR_7)
+        .fixed_div_floor(emission_shares, SCALAR_7)
         .unwrap_optimized();
     let new_data = BackstopEmissionsData {
         index: additional_idx + emis_data.index,
@@ -118,13 +120,16 @@ fn update_user_emissions(
     user_balance: &UserBalance,
     to_claim: bool,
 ) -> i128 {
+    // a deposit lockup grants bonus shares that earn emissions alongside the user's shares
+    let emission_shares =
+        user_balance.shares + storage::get_deposit_lock(e, pool, user).map_or(0, |lock| lock.bonus);
     if let Some(user_data) = storage::get_user_emis_data(e, pool, user) {
         if user_data.index != emis_data.index || to_claim {
             let mut accrual = user_data.accrued;
-            if user_balance.shares != 0 {
+            if emission_shares != 0 {
                 let delta_index = emis_data.index - user_data.index;
                 require_nonnegative(e, delta_index);
-                let to_accrue = (user_balance.shares)
+                let to_accrue = emission_shares
                     .fixed_mul_floor(delta_index, SCALAR_7)
                     .unwrap_optimized();
                 accrual += to_accrue;
@@ -133,19 +138,20 @@ fn update_user_emissions(
         }
         // no accrual occured and no claim requested
         return 0;
-    } else if user_balance.shares == 0 {
+    } else if emission_shares == 0 {
         // first time the user registered an action with the asset since emissions were added
         return set_user_emissions(e, pool, user, emis_data.index, 0, to_claim);
     } else {
         // user had tokens before emissions began, they are due any historical emissions
-        let to_accrue = user_balance
-            .shares
+        let to_accrue = emission_shares
             .fixed_mul_floor(emis_data.index, SCALAR_7)
             .unwrap_optimized();
         return set_user_emissions(e, pool, user, emis_data.index, to_accrue, to_claim);
     }
 }
 
+/// Store the user's emissions and track when their unclaimed emissions were last updated, so they
+/// can be recycled once they have gone untouched for the claim deadline
 fn set_user_emissions(
     e: &Env,
     pool_id: &Address,
@@ -156,9 +162,13 @@ fn set_user_emissions(
 ) -> i128 {
     if to_claim {
         storage::set_user_emis_data(e, pool_id, user, &UserEmissionData { index, accrued: 0 });
+        storage::del_user_accrual_time(e, pool_id, user);
         accrued
     } else {
         storage::set_user_emis_data(e, pool_id, user, &UserEmissionData { index, accrued });
+        if accrued > 0 {
+            storage::set_user_accrual_time(e, pool_id, user, e.ledger().timestamp());
+        }
         0
     }
 }
@@ -166,7 +176,8 @@ fn set_user_emissions(
 #[cfg(test)]
 mod tests {
     use crate::{
-        constants::BACKSTOP_EPOCH, storage::BackstopEmissionConfig, testutils::create_backstop, Q4W,
+        backstop::DepositLock, constants::BACKSTOP_EPOCH, storage::BackstopEmissionConfig,
+        testutils::create_backstop, Q4W,
     };
 
     use super::*;
@@ -213,6 +224,7 @@ mod tests {
             storage::set_backstop_emis_config(&e, &pool_1, &backstop_emissions_config);
             storage::set_backstop_emis_data(&e, &pool_1, &backstop_emissions_data);
             storage::set_user_emis_data(&e, &pool_1, &samwise, &user_emissions_data);
+            storage::set_user_accrual_time(&e, &pool_1, &samwise, BACKSTOP_EPOCH);
 
             let pool_balance = PoolBalance {
                 shares: 150_0000000,
@@ -233,6 +245,81 @@ mod tests {
             assert_eq!(new_backstop_data.index, 8248888);
             assert_eq!(new_user_data.accrued, 7_4139996);
             assert_eq!(new_user_data.index, 8248888);
+            assert_eq!(
+                storage::get_user_accrual_time(&e, &pool_1, &samwise),
+                Some(block_timestamp)
+            );
+        });
+    }
+
+    #[test]
+    fn test_update_emissions_with_deposit_lock() {
+        let e = Env::default();
+        let block_timestamp = BACKSTOP_EPOCH + 1234;
+        e.ledger().set(LedgerInfo {
+            timestamp: block_timestamp,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop_id = create_backstop(&e);
+        let pool_1 = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let backstop_emissions_config = BackstopEmissionConfig {
+            expiration: BACKSTOP_EPOCH + 7 * 24 * 60 * 60,
+            eps: 0_1000000,
+        };
+        let backstop_emissions_data = BackstopEmissionsData {
+            index: 22222,
+            last_time: BACKSTOP_EPOCH,
+        };
+        let user_emissions_data = UserEmissionData {
+            index: 11111,
+            accrued: 3,
+        };
+        e.as_contract(&backstop_id, || {
+            storage::set_last_distribution_time(&e, &BACKSTOP_EPOCH);
+            storage::set_backstop_emis_config(&e, &pool_1, &backstop_emissions_config);
+            storage::set_backstop_emis_data(&e, &pool_1, &backstop_emissions_data);
+            storage::set_user_emis_data(&e, &pool_1, &samwise, &user_emissions_data);
+            // samwise locked all 9 shares at 1.5x, and other users locked 50 more bonus shares
+            storage::set_deposit_lock(
+                &e,
+                &pool_1,
+                &samwise,
+                &DepositLock {
+                    shares: 9_0000000,
+                    bonus: 4_5000000,
+                    unlock_time: block_timestamp + 1,
+                },
+            );
+            storage::set_lock_bonus(&e, &pool_1, 54_5000000);
+
+            let pool_balance = PoolBalance {
+                shares: 150_0000000,
+                tokens: 200_0000000,
+                q4w: 4_5000000,
+            };
+            let user_balance = UserBalance {
+                shares: 9_0000000,
+                q4w: vec![&e],
+            };
+
+            update_emissions(&e, &pool_1, &pool_balance, &samwise, &user_balance);
+
+            let new_backstop_data = storage::get_backstop_emis_data(&e, &pool_1).unwrap_optimized();
+            let new_user_data =
+                storage::get_user_emis_data(&e, &pool_1, &samwise).unwrap_optimized();
+            assert_eq!(new_backstop_data.last_time, block_timestamp);
+            assert_eq!(new_backstop_data.index, 6192222);
+            assert_eq!(new_user_data.accrued, 8_3445001);
+            assert_eq!(new_user_data.index, 6192222);
         });
     }
 
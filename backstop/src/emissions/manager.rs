@@ -5,8 +5,8 @@ use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, vec, Address, Env,
 
 use crate::{
     backstop::{load_pool_backstop_data, require_pool_above_threshold},
-    constants::{BACKSTOP_EPOCH, SCALAR_7},
-    dependencies::EmitterClient,
+    constants::{BACKSTOP_EPOCH, RZ_ELIGIBILITY_PERIOD, SCALAR_7},
+    dependencies::{EmitterClient, PoolClient},
     errors::BackstopError,
     storage::{self, BackstopEmissionConfig, BackstopEmissionsData},
     PoolBalance,
@@ -15,6 +15,12 @@ use crate::{
 use super::distributor::update_emission_data_with_config;
 
 /// Add a pool to the reward zone. If the reward zone is full, attempt to swap it with the pool to remove.
+///
+/// A pool can only be added if it is active and its backstop has been above the backstop threshold
+/// since at least `RZ_ELIGIBILITY_PERIOD` ago, as recorded when its backstop balance changes.
+///
+/// The backstop emissions the removed pool has left to emit are re-split across the pools that
+/// remain in the reward zone.
 pub fn add_to_reward_zone(e: &Env, to_add: Address, to_remove: Address) {
     let mut reward_zone = storage::get_reward_zone(e);
     let max_rz_len = if e.ledger().timestamp() < BACKSTOP_EPOCH {
@@ -31,7 +37,16 @@ pub fn add_to_reward_zone(e: &Env, to_add: Address, to_remove: Address) {
     // enusre to_add has met the minimum backstop deposit threshold
     // NOTE: "to_add" can only carry a pool balance if it is a deployed pool from the factory
     let pool_data = load_pool_backstop_data(e, &to_add);
-    if !require_pool_above_threshold(&pool_data) {
+    if !require_pool_above_threshold(e, &pool_data) {
+        panic_with_error!(e, BackstopError::InvalidRewardZoneEntry);
+    }
+    // ensure to_add has stayed above the threshold, so short-lived deposits cannot capture emissions
+    match storage::get_above_threshold_since(e, &to_add) {
+        Some(since) if since + RZ_ELIGIBILITY_PERIOD <= e.ledger().timestamp() => {}
+        _ => panic_with_error!(e, BackstopError::InvalidRewardZoneEntry),
+    }
+    // ensure to_add is active (status 0 or 1), and is not on-ice, frozen, or being set up
+    if PoolClient::new(e, &to_add).get_status() > 1 {
         panic_with_error!(e, BackstopError::InvalidRewardZoneEntry);
     }
 
@@ -54,6 +69,9 @@ pub fn add_to_reward_zone(e: &Env, to_add: Address, to_remove: Address) {
                 if pool_data.tokens <= storage::get_pool_balance(e, &to_remove).tokens {
                     panic_with_error!(e, BackstopError::InvalidRewardZoneEntry);
                 }
+                let mut remaining_pools = reward_zone.clone();
+                remaining_pools.remove(idx);
+                redistribute_backstop_emissions(e, &to_remove, &remaining_pools);
                 reward_zone.set(idx, to_add.clone());
             }
             None => panic_with_error!(e, BackstopError::InvalidRewardZoneEntry),
@@ -72,9 +90,8 @@ pub fn gulp_emissions(e: &Env) -> i128 {
     if rz_len == 0 {
         panic_with_error!(e, BackstopError::BadRequest);
     }
-    let emitter = storage::get_emitter(e);
-    let emitter_last_distribution =
-        EmitterClient::new(&e, &emitter).get_last_distro(&e.current_contract_address());
+    let emitter_client = EmitterClient::new(e, &storage::get_emitter(e));
+    let emitter_last_distribution = emitter_client.get_last_distro(&e.current_contract_address());
     let last_distribution = storage::get_last_distribution_time(e);
 
     // ensure enough time has passed between the last emitter distribution and gulp_emissions
@@ -83,11 +100,22 @@ pub fn gulp_emissions(e: &Env) -> i128 {
         panic_with_error!(e, BackstopError::BadRequest);
     }
     storage::set_last_distribution_time(e, &emitter_last_distribution);
-    let new_emissions = i128(emitter_last_distribution - last_distribution) * SCALAR_7; // emitter releases 1 token per second
-    let total_backstop_emissions = new_emissions
+    // only BLND the emitter actually minted to the backstop is distributed. Time emissions were
+    // paused, emissions streamed directly to pools, and emissions past the emission cap were
+    // never minted.
+    let distributed = emitter_client.get_distributed(&e.current_contract_address());
+    let new_emissions = distributed - storage::get_distributed(e);
+    storage::set_distributed(e, &distributed);
+    // emissions carried over from wound down pools are distributed with the new emissions
+    let residual_emissions = storage::get_residual_emissions(e);
+    if residual_emissions > 0 {
+        storage::set_residual_emissions(e, 0);
+    }
+    let total_emissions = new_emissions + residual_emissions;
+    let total_backstop_emissions = total_emissions
         .fixed_mul_floor(0_7000000, SCALAR_7)
         .unwrap_optimized();
-    let total_pool_emissions = new_emissions
+    let total_pool_emissions = total_emissions
         .fixed_mul_floor(0_3000000, SCALAR_7)
         .unwrap_optimized();
 
@@ -149,6 +177,115 @@ pub fn gulp_pool_emissions(e: &Env, pool_id: &Address) -> i128 {
     pool_emissions
 }
 
+/// Stop emissions to a pool and its backstop, removing the pool from the reward zone. Pool
+/// emissions that have not been gulped and backstop emissions that have not been emitted are
+/// carried over to the next `gulp_emissions` for the rest of the reward zone.
+///
+/// Returns the amount of emissions carried over
+pub fn stop_pool_emissions(e: &Env, pool_id: &Address) -> i128 {
+    let mut reward_zone = storage::get_reward_zone(e);
+    if let Some(idx) = reward_zone.first_index_of(pool_id.clone()) {
+        reward_zone.remove(idx);
+        storage::set_reward_zone(e, &reward_zone);
+    }
+
+    let mut residual = storage::get_pool_emissions(e, pool_id);
+    if residual > 0 {
+        storage::set_pool_emissions(e, pool_id, 0);
+    }
+    residual += end_backstop_emissions(e, pool_id);
+
+    if residual > 0 {
+        storage::set_residual_emissions(e, storage::get_residual_emissions(e) + residual);
+    }
+    residual
+}
+
+/// End the backstop emissions of a pool at the current timestamp. Emissions are accrued to the
+/// pool's depositors through now before the config is ended.
+///
+/// Returns the amount of backstop emissions that were left to emit
+fn end_backstop_emissions(e: &Env, pool_id: &Address) -> i128 {
+    let now = e.ledger().timestamp();
+    if let Some(emis_config) = storage::get_backstop_emis_config(e, pool_id) {
+        if emis_config.expiration > now {
+            let pool_balance = storage::get_pool_balance(e, pool_id);
+            update_emission_data_with_config(e, pool_id, &pool_balance, &emis_config);
+            storage::set_backstop_emis_config(
+                e,
+                pool_id,
+                &BackstopEmissionConfig {
+                    expiration: now,
+                    eps: emis_config.eps,
+                },
+            );
+            return i128(emis_config.eps * (emis_config.expiration - now));
+        }
+    }
+    0
+}
+
+/// Re-split the backstop emissions a removed pool has left to emit across the remaining pools,
+/// based on each pool's share of non-queued tokens like `gulp_emissions`. Each pool's emissions are
+/// accrued through now at its old EPS before the EPS is raised, and its expiration is unchanged.
+///
+/// Emissions that cannot be re-split, either due to rounding or because a pool has no active
+/// config, are carried over to the next `gulp_emissions`.
+fn redistribute_backstop_emissions(e: &Env, removed_pool: &Address, pools: &Vec<Address>) {
+    let tokens_left = end_backstop_emissions(e, removed_pool);
+    if tokens_left == 0 {
+        return;
+    }
+
+    let now = e.ledger().timestamp();
+    let mut pool_balances: Vec<PoolBalance> = vec![e];
+    let mut total_non_queued_tokens: i128 = 0;
+    for pool in pools.iter() {
+        let pool_balance = storage::get_pool_balance(e, &pool);
+        total_non_queued_tokens += pool_balance.non_queued_tokens();
+        pool_balances.push_back(pool_balance);
+    }
+
+    let mut tokens_redistributed: i128 = 0;
+    if total_non_queued_tokens > 0 {
+        for (pool, pool_balance) in pools.iter().zip(pool_balances.iter()) {
+            let emis_config = match storage::get_backstop_emis_config(e, &pool) {
+                Some(config) if config.expiration > now => config,
+                _ => continue,
+            };
+            let share = pool_balance
+                .non_queued_tokens()
+                .fixed_div_floor(total_non_queued_tokens, SCALAR_7)
+                .unwrap_optimized();
+            let new_pool_tokens = share
+                .fixed_mul_floor(tokens_left, SCALAR_7)
+                .unwrap_optimized();
+            let time_left = emis_config.expiration - now;
+            let added_eps = u64(new_pool_tokens / i128(time_left)).unwrap_optimized();
+            if added_eps == 0 {
+                continue;
+            }
+
+            // checkpoint the pool's emissions so the higher EPS only applies from now
+            update_emission_data_with_config(e, &pool, &pool_balance, &emis_config);
+            storage::set_backstop_emis_config(
+                e,
+                &pool,
+                &BackstopEmissionConfig {
+                    expiration: emis_config.expiration,
+                    eps: emis_config.eps + added_eps,
+                },
+            );
+            tokens_redistributed += i128(added_eps * time_left);
+        }
+    }
+
+    let residual = tokens_left - tokens_redistributed;
+    if residual > 0 {
+        storage::set_residual_emissions(e, storage::get_residual_emissions(e) + residual);
+    }
+}
+
 /// Set a new EPS for the backstop
 pub fn set_backstop_emission_config(
     e: &Env,
@@ -201,7 +338,10 @@ mod tests {
     use crate::{
         backstop::PoolBalance,
         storage::BackstopEmissionConfig,
-        testutils::{create_backstop, create_blnd_token, create_emitter},
+        testutils::{
+            create_backstop, create_blnd_token, create_emitter, create_mock_pool,
+            set_emitter_distributed,
+        },
     };
 
     /********** gulp_emissions **********/
@@ -224,13 +364,14 @@ mod tests {
 
         let backstop = create_backstop(&e);
         let emitter_distro_time = BACKSTOP_EPOCH - 10;
-        create_emitter(
+        let (emitter, _) = create_emitter(
             &e,
             &backstop,
             &Address::generate(&e),
             &Address::generate(&e),
             emitter_distro_time,
         );
+        set_emitter_distributed(&e, &emitter, &backstop, 604_800_0000000);
         let pool_1 = Address::generate(&e);
         let pool_2 = Address::generate(&e);
         let pool_3 = Address::generate(&e);
@@ -439,8 +580,113 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_gulp_emissions_includes_residual() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: BACKSTOP_EPOCH,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop = create_backstop(&e);
+        let emitter_distro_time = BACKSTOP_EPOCH - 10;
+        let (emitter, _) = create_emitter(
+            &e,
+            &backstop,
+            &Address::generate(&e),
+            &Address::generate(&e),
+            emitter_distro_time,
+        );
+        set_emitter_distributed(&e, &emitter, &backstop, 604_800_0000000);
+        let pool_1 = Address::generate(&e);
+        let reward_zone: Vec<Address> = vec![&e, pool_1.clone()];
+
+        e.as_contract(&backstop, || {
+            storage::set_last_distribution_time(&e, &(emitter_distro_time - 7 * 24 * 60 * 60));
+            storage::set_reward_zone(&e, &reward_zone);
+            storage::set_residual_emissions(&e, 10_000_0000000);
+            storage::set_pool_balance(
+                &e,
+                &pool_1,
+                &PoolBalance {
+                    tokens: 300_000_0000000,
+                    shares: 200_000_0000000,
+                    q4w: 0,
+                },
+            );
+
+            gulp_emissions(&e);
+
+            // 604,800 new emissions + 10,000 residual emissions
+            assert_eq!(storage::get_residual_emissions(&e), 0);
+            assert_eq!(storage::get_pool_emissions(&e, &pool_1), 184_440_0000000);
+            let new_pool_1_config =
+                storage::get_backstop_emis_config(&e, &pool_1).unwrap_optimized();
+            assert_eq!(new_pool_1_config.eps, 0_7115740);
+        });
+    }
+
     /********** gulp_pool_emissions **********/
 
+    #[test]
+    fn test_gulp_emissions_only_minted() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: BACKSTOP_EPOCH,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop = create_backstop(&e);
+        let emitter_distro_time = BACKSTOP_EPOCH - 10;
+        let (emitter, _) = create_emitter(
+            &e,
+            &backstop,
+            &Address::generate(&e),
+            &Address::generate(&e),
+            emitter_distro_time,
+        );
+        // the emitter only minted half of the last 7 days of emissions
+        set_emitter_distributed(&e, &emitter, &backstop, 1_000_000_0000000 + 302_400_0000000);
+        let pool_1 = Address::generate(&e);
+        let reward_zone: Vec<Address> = vec![&e, pool_1.clone()];
+
+        e.as_contract(&backstop, || {
+            storage::set_last_distribution_time(&e, &(emitter_distro_time - 7 * 24 * 60 * 60));
+            storage::set_distributed(&e, &1_000_000_0000000);
+            storage::set_reward_zone(&e, &reward_zone);
+            storage::set_pool_balance(
+                &e,
+                &pool_1,
+                &PoolBalance {
+                    tokens: 300_000_0000000,
+                    shares: 200_000_0000000,
+                    q4w: 0,
+                },
+            );
+
+            let result = gulp_emissions(&e);
+            assert_eq!(result, 302_400_0000000);
+            assert_eq!(storage::get_distributed(&e), 1_302_400_0000000);
+            assert_eq!(storage::get_pool_emissions(&e, &pool_1), 90_720_0000000);
+        });
+    }
+
     #[test]
     fn test_gulp_pool_emissions() {
         let e = Env::default();
@@ -555,7 +801,7 @@ mod tests {
         });
 
         let backstop_id = create_backstop(&e);
-        let to_add = Address::generate(&e);
+        let (to_add, _) = create_mock_pool(&e);
 
         e.as_contract(&backstop_id, || {
             storage::set_pool_balance(
@@ -568,6 +814,7 @@ mod tests {
                 },
             );
             storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+            storage::set_above_threshold_since(&e, &to_add, 0);
 
             add_to_reward_zone(&e, to_add.clone(), Address::generate(&e));
             let actual_rz = storage::get_reward_zone(&e);
@@ -591,7 +838,7 @@ mod tests {
         });
 
         let backstop_id = create_backstop(&e);
-        let to_add = Address::generate(&e);
+        let (to_add, _) = create_mock_pool(&e);
         let mut reward_zone: Vec<Address> = vec![
             &e,
             Address::generate(&e),
@@ -617,6 +864,7 @@ mod tests {
                 },
             );
             storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+            storage::set_above_threshold_since(&e, &to_add, 0);
 
             add_to_reward_zone(&e, to_add.clone(), Address::generate(&e));
             let actual_rz = storage::get_reward_zone(&e);
@@ -641,7 +889,7 @@ mod tests {
         });
 
         let backstop_id = create_backstop(&e);
-        let to_add = Address::generate(&e);
+        let (to_add, _) = create_mock_pool(&e);
 
         e.as_contract(&backstop_id, || {
             storage::set_pool_balance(
@@ -654,6 +902,7 @@ mod tests {
                 },
             );
             storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+            storage::set_above_threshold_since(&e, &to_add, 0);
 
             add_to_reward_zone(&e, to_add.clone(), Address::generate(&e));
             let actual_rz = storage::get_reward_zone(&e);
@@ -662,6 +911,115 @@ mod tests {
         });
     }
 
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1002)")]
+    fn test_add_to_rz_above_threshold_too_recently() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: BACKSTOP_EPOCH,
+            protocol_version: 20,
+            sequence_number: 0,
+            base_reserve: 10,
+            network_id: Default::default(),
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop_id = create_backstop(&e);
+        let (to_add, _) = create_mock_pool(&e);
+
+        e.as_contract(&backstop_id, || {
+            storage::set_pool_balance(
+                &e,
+                &to_add,
+                &PoolBalance {
+                    shares: 90_000_0000000,
+                    tokens: 100_000_0000000,
+                    q4w: 1_000_0000000,
+                },
+            );
+            storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+            storage::set_above_threshold_since(
+                &e,
+                &to_add,
+                BACKSTOP_EPOCH - RZ_ELIGIBILITY_PERIOD + 1,
+            );
+
+            add_to_reward_zone(&e, to_add.clone(), Address::generate(&e));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1002)")]
+    fn test_add_to_rz_never_above_threshold() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: BACKSTOP_EPOCH,
+            protocol_version: 20,
+            sequence_number: 0,
+            base_reserve: 10,
+            network_id: Default::default(),
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop_id = create_backstop(&e);
+        let (to_add, _) = create_mock_pool(&e);
+
+        e.as_contract(&backstop_id, || {
+            storage::set_pool_balance(
+                &e,
+                &to_add,
+                &PoolBalance {
+                    shares: 90_000_0000000,
+                    tokens: 100_000_0000000,
+                    q4w: 1_000_0000000,
+                },
+            );
+            storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+
+            add_to_reward_zone(&e, to_add.clone(), Address::generate(&e));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1002)")]
+    fn test_add_to_rz_pool_not_active() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: BACKSTOP_EPOCH,
+            protocol_version: 20,
+            sequence_number: 0,
+            base_reserve: 10,
+            network_id: Default::default(),
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop_id = create_backstop(&e);
+        let (to_add, pool_client) = create_mock_pool(&e);
+        pool_client.set_status(&3);
+
+        e.as_contract(&backstop_id, || {
+            storage::set_pool_balance(
+                &e,
+                &to_add,
+                &PoolBalance {
+                    shares: 90_000_0000000,
+                    tokens: 100_000_0000000,
+                    q4w: 1_000_0000000,
+                },
+            );
+            storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+            storage::set_above_threshold_since(&e, &to_add, 0);
+
+            add_to_reward_zone(&e, to_add.clone(), Address::generate(&e));
+        });
+    }
+
     #[test]
     fn test_add_to_rz_increases_size_over_time() {
         let e = Env::default();
@@ -677,7 +1035,7 @@ mod tests {
         });
 
         let backstop_id = create_backstop(&e);
-        let to_add = Address::generate(&e);
+        let (to_add, _) = create_mock_pool(&e);
         let mut reward_zone: Vec<Address> = vec![
             &e,
             Address::generate(&e),
@@ -704,6 +1062,7 @@ mod tests {
                 },
             );
             storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+            storage::set_above_threshold_since(&e, &to_add, 0);
 
             add_to_reward_zone(&e, to_add.clone(), Address::generate(&e));
             let actual_rz = storage::get_reward_zone(&e);
@@ -728,7 +1087,7 @@ mod tests {
         });
 
         let backstop_id = create_backstop(&e);
-        let to_add = Address::generate(&e);
+        let (to_add, _) = create_mock_pool(&e);
         let reward_zone: Vec<Address> = vec![
             &e,
             Address::generate(&e),
@@ -755,6 +1114,7 @@ mod tests {
                 },
             );
             storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+            storage::set_above_threshold_since(&e, &to_add, 0);
 
             add_to_reward_zone(&e, to_add.clone(), Address::generate(&e));
         });
@@ -775,7 +1135,7 @@ mod tests {
         });
 
         let backstop_id = create_backstop(&e);
-        let to_add = Address::generate(&e);
+        let (to_add, _) = create_mock_pool(&e);
         let to_remove = Address::generate(&e);
         let mut reward_zone: Vec<Address> = vec![
             &e,
@@ -814,6 +1174,7 @@ mod tests {
                 },
             );
             storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+            storage::set_above_threshold_since(&e, &to_add, 0);
 
             add_to_reward_zone(&e, to_add.clone(), to_remove.clone());
 
@@ -826,6 +1187,149 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_add_to_rz_swap_redistributes_backstop_emissions() {
+        let e = Env::default();
+        e.ledger().set(LedgerInfo {
+            timestamp: BACKSTOP_EPOCH,
+            protocol_version: 20,
+            sequence_number: 0,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop_id = create_backstop(&e);
+        let (to_add, _) = create_mock_pool(&e);
+        let to_remove = Address::generate(&e);
+        let pool_a = Address::generate(&e);
+        let pool_b = Address::generate(&e);
+        let mut reward_zone: Vec<Address> = vec![
+            &e,
+            pool_a.clone(),
+            pool_b.clone(),
+            Address::generate(&e),
+            Address::generate(&e),
+            Address::generate(&e),
+            Address::generate(&e),
+            Address::generate(&e),
+            to_remove.clone(), // index 7
+            Address::generate(&e),
+            Address::generate(&e),
+        ];
+
+        e.as_contract(&backstop_id, || {
+            storage::set_reward_zone(&e, &reward_zone);
+            storage::set_last_distribution_time(&e, &(BACKSTOP_EPOCH - 1000));
+            storage::set_pool_balance(
+                &e,
+                &to_add,
+                &PoolBalance {
+                    shares: 90_000_0000000,
+                    tokens: 100_001_0000000,
+                    q4w: 1_000_0000000,
+                },
+            );
+            storage::set_above_threshold_since(&e, &to_add, 0);
+            storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+
+            // to_remove has 1000 seconds of emissions left at 0.1 EPS
+            storage::set_pool_balance(
+                &e,
+                &to_remove,
+                &PoolBalance {
+                    shares: 90_000_0000000,
+                    tokens: 100_000_0000000,
+                    q4w: 1_000_0000000,
+                },
+            );
+            storage::set_backstop_emis_config(
+                &e,
+                &to_remove,
+                &BackstopEmissionConfig {
+                    expiration: BACKSTOP_EPOCH + 1000,
+                    eps: 0_1000000,
+                },
+            );
+            storage::set_backstop_emis_data(
+                &e,
+                &to_remove,
+                &BackstopEmissionsData {
+                    index: 0,
+                    last_time: BACKSTOP_EPOCH - 1000,
+                },
+            );
+
+            // pool_a is emitting and holds 2/3 of the remaining non-queued tokens
+            storage::set_pool_balance(
+                &e,
+                &pool_a,
+                &PoolBalance {
+                    shares: 100_000_0000000,
+                    tokens: 100_000_0000000,
+                    q4w: 0,
+                },
+            );
+            storage::set_backstop_emis_config(
+                &e,
+                &pool_a,
+                &BackstopEmissionConfig {
+                    expiration: BACKSTOP_EPOCH + 1000,
+                    eps: 0_2000000,
+                },
+            );
+            storage::set_backstop_emis_data(
+                &e,
+                &pool_a,
+                &BackstopEmissionsData {
+                    index: 0,
+                    last_time: BACKSTOP_EPOCH - 1000,
+                },
+            );
+
+            // pool_b holds 1/3 of the remaining non-queued tokens but has no emissions config
+            storage::set_pool_balance(
+                &e,
+                &pool_b,
+                &PoolBalance {
+                    shares: 50_000_0000000,
+                    tokens: 50_000_0000000,
+                    q4w: 0,
+                },
+            );
+
+            add_to_reward_zone(&e, to_add.clone(), to_remove.clone());
+
+            let actual_rz = storage::get_reward_zone(&e);
+            reward_zone.set(7, to_add);
+            assert_eq!(actual_rz, reward_zone);
+
+            // to_remove accrued through now and stopped emitting
+            let remove_config = storage::get_backstop_emis_config(&e, &to_remove).unwrap();
+            assert_eq!(remove_config.expiration, BACKSTOP_EPOCH);
+            let remove_data = storage::get_backstop_emis_data(&e, &to_remove).unwrap();
+            assert_eq!(remove_data.last_time, BACKSTOP_EPOCH);
+            assert_eq!(remove_data.index, 11235);
+
+            // pool_a accrued at its old EPS through now and picks up its share of the 100 tokens
+            let a_config = storage::get_backstop_emis_config(&e, &pool_a).unwrap();
+            assert_eq!(a_config.expiration, BACKSTOP_EPOCH + 1000);
+            assert_eq!(a_config.eps, 0_2000000 + 0_0666666);
+            let a_data = storage::get_backstop_emis_data(&e, &pool_a).unwrap();
+            assert_eq!(a_data.last_time, BACKSTOP_EPOCH);
+            assert_eq!(a_data.index, 20000);
+
+            // pool_b's share and rounding dust are carried over to the next gulp
+            assert!(storage::get_backstop_emis_config(&e, &pool_b).is_none());
+            assert_eq!(
+                storage::get_residual_emissions(&e),
+                100_0000000 - 0_0666666 * 1000
+            );
+        });
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #1002)")]
     fn test_add_to_rz_swap_not_enough_tokens() {
@@ -842,7 +1346,7 @@ mod tests {
         });
 
         let backstop_id = create_backstop(&e);
-        let to_add = Address::generate(&e);
+        let (to_add, _) = create_mock_pool(&e);
         let to_remove = Address::generate(&e);
         let reward_zone: Vec<Address> = vec![
             &e,
@@ -881,6 +1385,7 @@ mod tests {
                 },
             );
             storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+            storage::set_above_threshold_since(&e, &to_add, 0);
 
             add_to_reward_zone(&e, to_add.clone(), to_remove);
         });
@@ -902,7 +1407,7 @@ mod tests {
         });
 
         let backstop_id = create_backstop(&e);
-        let to_add = Address::generate(&e);
+        let (to_add, _) = create_mock_pool(&e);
         let to_remove = Address::generate(&e);
         let reward_zone: Vec<Address> = vec![
             &e,
@@ -941,6 +1446,7 @@ mod tests {
                 },
             );
             storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+            storage::set_above_threshold_since(&e, &to_add, 0);
 
             add_to_reward_zone(&e, to_add.clone(), to_remove);
         });
@@ -962,7 +1468,7 @@ mod tests {
         });
 
         let backstop_id = create_backstop(&e);
-        let to_add = Address::generate(&e);
+        let (to_add, _) = create_mock_pool(&e);
         let to_remove = Address::generate(&e);
         let reward_zone: Vec<Address> = vec![
             &e,
@@ -1001,6 +1507,7 @@ mod tests {
                 },
             );
             storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+            storage::set_above_threshold_since(&e, &to_add, 0);
 
             add_to_reward_zone(&e, to_add.clone(), to_remove);
         });
@@ -1022,7 +1529,7 @@ mod tests {
         });
 
         let backstop_id = create_backstop(&e);
-        let to_add = Address::generate(&e);
+        let (to_add, _) = create_mock_pool(&e);
         let to_remove = Address::generate(&e);
         let reward_zone: Vec<Address> = vec![
             &e,
@@ -1061,6 +1568,7 @@ mod tests {
                 },
             );
             storage::set_lp_token_val(&e, &(5_0000000, 0_1000000));
+            storage::set_above_threshold_since(&e, &to_add, 0);
 
             add_to_reward_zone(&e, to_add.clone(), to_remove.clone());
         });
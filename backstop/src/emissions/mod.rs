@@ -1,8 +1,10 @@
 mod claim;
-pub use claim::execute_claim;
+pub use claim::{
+    execute_claim, execute_compound, execute_recycle_unclaimed, execute_set_claim_deadline,
+};
 
 mod distributor;
 pub use distributor::update_emissions;
 
 mod manager;
-pub use manager::{add_to_reward_zone, gulp_emissions, gulp_pool_emissions};
+pub use manager::{add_to_reward_zone, gulp_emissions, gulp_pool_emissions, stop_pool_emissions};
@@ -25,4 +25,10 @@ pub enum BackstopError {
     InvalidShareMintAmount = 1005,
     InvalidTokenWithdrawAmount = 1006,
     TooManyQ4WEntries = 1007,
+    ContractPaused = 1008,
+    VoteSnapshotUnavailable = 1009,
+    UpgradeNotUnlocked = 1010,
+    DepositLocked = 1011,
+    ThresholdNotUnlocked = 1012,
+    PoolWindingDown = 1013,
 }
@@ -11,10 +11,16 @@ mod emissions;
 mod errors;
 mod storage;
 mod testutils;
+mod threshold;
+mod upgrade;
 
-pub use backstop::{PoolBackstopData, PoolBalance, UserBalance, Q4W};
+pub use backstop::{
+    BackstopApr, DepositLock, PoolBackstopData, PoolBalance, UserBalance, VoteSnapshot, Q4W,
+};
 pub use contract::*;
 pub use errors::BackstopError;
 pub use storage::{
-    BackstopDataKey, BackstopEmissionConfig, BackstopEmissionsData, PoolUserKey, UserEmissionData,
+    BackstopDataKey, BackstopDonationData, BackstopEmissionConfig, BackstopEmissionsData,
+    PoolCoverage, PoolUserKey, QueuedThresholdConfig, QueuedUpgrade, ThresholdConfig,
+    UserEmissionData,
 };
@@ -1,8 +1,15 @@
 use soroban_sdk::{
-    contracttype, unwrap::UnwrapOptimized, vec, Address, Env, IntoVal, Symbol, TryFromVal, Val, Vec,
+    contracttype, unwrap::UnwrapOptimized, vec, Address, BytesN, Env, IntoVal, Symbol, TryFromVal,
+    Val, Vec,
 };
 
-use crate::backstop::{PoolBalance, UserBalance};
+use crate::{
+    backstop::{DepositLock, PoolBalance, UserBalance, VoteSnapshot},
+    constants::{
+        DEFAULT_CLAIM_DEADLINE, DEFAULT_THRESHOLD_BLND_WEIGHT, DEFAULT_THRESHOLD_PC,
+        DEFAULT_THRESHOLD_USDC_WEIGHT, Q4W_LOCK_TIME,
+    },
+};
 
 /********** Ledger Thresholds **********/
 
@@ -43,18 +50,73 @@ pub struct UserEmissionData {
     pub accrued: i128,
 }
 
+/// The trailing donation data for a pool's backstop
+#[derive(Clone)]
+#[contracttype]
+pub struct BackstopDonationData {
+    pub total: i128,      // the lifetime amount of backstop tokens donated
+    pub prev_total: i128, // the lifetime amount donated as of prev_time
+    pub prev_time: u64,   // the start of the previous donation window
+    pub cur_total: i128,  // the lifetime amount donated as of cur_time
+    pub cur_time: u64,    // the start of the current donation window
+}
+
+/// The coverage of a pool's liabilities by its backstop as of the last check
+#[derive(Clone)]
+#[contracttype]
+pub struct PoolCoverage {
+    pub liabilities: i128, // the pool's total liabilities, valued in USDC with 7 decimals
+    pub coverage: i128, // the ratio of the backstop's value to the pool's liabilities (7 decimals)
+}
+
+/// A queued upgrade of the backstop's WASM
+#[derive(Clone)]
+#[contracttype]
+pub struct QueuedUpgrade {
+    pub wasm_hash: BytesN<32>,
+    pub unlock_time: u64,
+}
+
+/// The backstop threshold a pool's backstop must be above to enter the reward zone. A backstop is
+/// above the threshold when blnd^blnd_weight * usdc^usdc_weight >= pc^(blnd_weight + usdc_weight),
+/// with balances in whole tokens.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ThresholdConfig {
+    pub blnd_weight: u32, // the exponent applied to the backstop's BLND balance
+    pub usdc_weight: u32, // the exponent applied to the backstop's USDC balance
+    pub pc: i128,         // the product constant the balances are measured against in whole tokens
+}
+
+/// A queued update to the backstop threshold
+#[derive(Clone)]
+#[contracttype]
+pub struct QueuedThresholdConfig {
+    pub config: ThresholdConfig,
+    pub unlock_time: u64,
+}
+
 /********** Storage Key Types **********/
 
 const IS_INIT_KEY: &str = "IsInit";
+const VERSION_KEY: &str = "Version";
+const UPGRADE_KEY: &str = "Upgrade";
+const THRESHOLD_KEY: &str = "Threshold";
+const THRESHOLD_INIT_KEY: &str = "ThresholdInit";
 const EMITTER_KEY: &str = "Emitter";
 const BACKSTOP_TOKEN_KEY: &str = "BToken";
 const POOL_FACTORY_KEY: &str = "PoolFact";
 const BLND_TOKEN_KEY: &str = "BLNDTkn";
 const USDC_TOKEN_KEY: &str = "USDCTkn";
 const LAST_DISTRO_KEY: &str = "LastDist";
+const DISTRIBUTED_KEY: &str = "Distributed";
+const RESIDUAL_EMIS_KEY: &str = "ResidualEmis";
+const CLAIM_DEADLINE_KEY: &str = "ClaimDeadline";
 const REWARD_ZONE_KEY: &str = "RZ";
 const DROP_LIST_KEY: &str = "DropList";
 const LP_TOKEN_VAL_KEY: &str = "LPTknVal";
+const LP_TOKEN_VAL_TIME_KEY: &str = "LPTknValTime";
+const PAUSE_REGISTRY_KEY: &str = "PauseReg";
 
 #[derive(Clone)]
 #[contracttype]
@@ -73,6 +135,15 @@ pub enum BackstopDataKey {
     BEmisCfg(Address),
     BEmisData(Address),
     UEmisData(PoolUserKey),
+    UEmisTime(PoolUserKey),
+    Donations(Address),
+    Coverage(Address),
+    Q4WLock(Address),
+    Votes(PoolUserKey),
+    DepLock(PoolUserKey),
+    LockBonus(Address),
+    AboveThresh(Address),
+    WindDown(Address),
 }
 
 /****************************
@@ -118,6 +189,94 @@ pub fn set_is_init(e: &Env) {
         .set::<Symbol, bool>(&Symbol::new(e, IS_INIT_KEY), &true);
 }
 
+/// Fetch the version of the stored state layout. Backstops deployed before the layout was
+/// versioned report version 0.
+pub fn get_storage_version(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get::<Symbol, u32>(&Symbol::new(e, VERSION_KEY))
+        .unwrap_or(0)
+}
+
+/// Set the version of the stored state layout
+///
+/// ### Arguments
+/// * `version` - The layout version
+pub fn set_storage_version(e: &Env, version: u32) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u32>(&Symbol::new(e, VERSION_KEY), &version);
+}
+
+/// Fetch the queued upgrade of the backstop's WASM, if one exists
+pub fn get_queued_upgrade(e: &Env) -> Option<QueuedUpgrade> {
+    e.storage()
+        .instance()
+        .get::<Symbol, QueuedUpgrade>(&Symbol::new(e, UPGRADE_KEY))
+}
+
+/// Set the queued upgrade of the backstop's WASM
+///
+/// ### Arguments
+/// * `queued_upgrade` - The queued upgrade
+pub fn set_queued_upgrade(e: &Env, queued_upgrade: &QueuedUpgrade) {
+    e.storage()
+        .instance()
+        .set::<Symbol, QueuedUpgrade>(&Symbol::new(e, UPGRADE_KEY), queued_upgrade);
+}
+
+/// Delete the queued upgrade of the backstop's WASM
+pub fn del_queued_upgrade(e: &Env) {
+    e.storage().instance().remove(&Symbol::new(e, UPGRADE_KEY));
+}
+
+/// Fetch the backstop threshold config, or the default 80/20 weighting against a 200k product
+/// constant if not set
+pub fn get_threshold_config(e: &Env) -> ThresholdConfig {
+    e.storage()
+        .instance()
+        .get::<Symbol, ThresholdConfig>(&Symbol::new(e, THRESHOLD_KEY))
+        .unwrap_or(ThresholdConfig {
+            blnd_weight: DEFAULT_THRESHOLD_BLND_WEIGHT,
+            usdc_weight: DEFAULT_THRESHOLD_USDC_WEIGHT,
+            pc: DEFAULT_THRESHOLD_PC,
+        })
+}
+
+/// Set the backstop threshold config
+///
+/// ### Arguments
+/// * `config` - The threshold config
+pub fn set_threshold_config(e: &Env, config: &ThresholdConfig) {
+    e.storage()
+        .instance()
+        .set::<Symbol, ThresholdConfig>(&Symbol::new(e, THRESHOLD_KEY), config);
+}
+
+/// Fetch the queued update to the backstop threshold config, if one exists
+pub fn get_queued_threshold_config(e: &Env) -> Option<QueuedThresholdConfig> {
+    e.storage()
+        .instance()
+        .get::<Symbol, QueuedThresholdConfig>(&Symbol::new(e, THRESHOLD_INIT_KEY))
+}
+
+/// Set the queued update to the backstop threshold config
+///
+/// ### Arguments
+/// * `queued_config` - The queued threshold config
+pub fn set_queued_threshold_config(e: &Env, queued_config: &QueuedThresholdConfig) {
+    e.storage()
+        .instance()
+        .set::<Symbol, QueuedThresholdConfig>(&Symbol::new(e, THRESHOLD_INIT_KEY), queued_config);
+}
+
+/// Delete the queued update to the backstop threshold config
+pub fn del_queued_threshold_config(e: &Env) {
+    e.storage()
+        .instance()
+        .remove(&Symbol::new(e, THRESHOLD_INIT_KEY));
+}
+
 /// Fetch the pool factory id
 pub fn get_emitter(e: &Env) -> Address {
     e.storage()
@@ -136,6 +295,24 @@ pub fn set_emitter(e: &Env, pool_factory_id: &Address) {
         .set::<Symbol, Address>(&Symbol::new(e, EMITTER_KEY), pool_factory_id);
 }
 
+/// Fetch the pause registry id
+pub fn get_pause_registry(e: &Env) -> Address {
+    e.storage()
+        .instance()
+        .get::<Symbol, Address>(&Symbol::new(e, PAUSE_REGISTRY_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the pause registry
+///
+/// ### Arguments
+/// * `pause_registry_id` - The ID of the pause registry
+pub fn set_pause_registry(e: &Env, pause_registry_id: &Address) {
+    e.storage()
+        .instance()
+        .set::<Symbol, Address>(&Symbol::new(e, PAUSE_REGISTRY_KEY), pause_registry_id);
+}
+
 /// Fetch the pool factory id
 pub fn get_pool_factory(e: &Env) -> Address {
     e.storage()
@@ -248,6 +425,51 @@ pub fn set_user_balance(e: &Env, pool: &Address, user: &Address, balance: &UserB
         .set::<BackstopDataKey, UserBalance>(&key, balance);
 }
 
+/// Fetch the lockup of a user's deposit in a pool, if one exists
+///
+/// ### Arguments
+/// * `pool` - The pool the deposit is associated with
+/// * `user` - The owner of the deposit
+pub fn get_deposit_lock(e: &Env, pool: &Address, user: &Address) -> Option<DepositLock> {
+    let key = BackstopDataKey::DepLock(PoolUserKey {
+        pool: pool.clone(),
+        user: user.clone(),
+    });
+    get_persistent_default(e, &key, || None, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
+}
+
+/// Set the lockup of a user's deposit in a pool
+///
+/// ### Arguments
+/// * `pool` - The pool the deposit is associated with
+/// * `user` - The owner of the deposit
+/// * `lock` - The lockup of the deposit
+pub fn set_deposit_lock(e: &Env, pool: &Address, user: &Address, lock: &DepositLock) {
+    let key = BackstopDataKey::DepLock(PoolUserKey {
+        pool: pool.clone(),
+        user: user.clone(),
+    });
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, DepositLock>(&key, lock);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Remove the lockup of a user's deposit in a pool
+///
+/// ### Arguments
+/// * `pool` - The pool the deposit is associated with
+/// * `user` - The owner of the deposit
+pub fn del_deposit_lock(e: &Env, pool: &Address, user: &Address) {
+    let key = BackstopDataKey::DepLock(PoolUserKey {
+        pool: pool.clone(),
+        user: user.clone(),
+    });
+    e.storage().persistent().remove(&key);
+}
+
 /********** Pool Balance **********/
 
 /// Fetch the balances for a given pool
@@ -284,6 +506,208 @@ pub fn set_pool_balance(e: &Env, pool: &Address, balance: &PoolBalance) {
         .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
 }
 
+/// Fetch the trailing donation data for a pool's backstop, or None
+///
+/// ### Arguments
+/// * `pool` - The pool the donations are associated with
+pub fn get_pool_donations(e: &Env, pool: &Address) -> Option<BackstopDonationData> {
+    let key = BackstopDataKey::Donations(pool.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the trailing donation data for a pool's backstop
+///
+/// ### Arguments
+/// * `pool` - The pool the donations are associated with
+/// * `donations` - The donation data
+pub fn set_pool_donations(e: &Env, pool: &Address, donations: &BackstopDonationData) {
+    let key = BackstopDataKey::Donations(pool.clone());
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, BackstopDonationData>(&key, donations);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Get the coverage of a pool's liabilities by its backstop as of the last check
+///
+/// ### Arguments
+/// * `pool` - The pool the coverage is associated with
+pub fn get_pool_coverage(e: &Env, pool: &Address) -> PoolCoverage {
+    let key = BackstopDataKey::Coverage(pool.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || PoolCoverage {
+            liabilities: 0,
+            coverage: i128::MAX,
+        },
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the coverage of a pool's liabilities by its backstop
+///
+/// ### Arguments
+/// * `pool` - The pool the coverage is associated with
+/// * `coverage` - The coverage data
+pub fn set_pool_coverage(e: &Env, pool: &Address, coverage: &PoolCoverage) {
+    let key = BackstopDataKey::Coverage(pool.clone());
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, PoolCoverage>(&key, coverage);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Get the time a pool's backstop has been above the threshold since, as of the last check
+///
+/// ### Arguments
+/// * `pool` - The pool
+pub fn get_above_threshold_since(e: &Env, pool: &Address) -> Option<u64> {
+    let key = BackstopDataKey::AboveThresh(pool.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the time a pool's backstop has been above the threshold since
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `timestamp` - The time the backstop was first seen above the threshold
+pub fn set_above_threshold_since(e: &Env, pool: &Address, timestamp: u64) {
+    let key = BackstopDataKey::AboveThresh(pool.clone());
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, u64>(&key, &timestamp);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Remove the time a pool's backstop has been above the threshold since
+///
+/// ### Arguments
+/// * `pool` - The pool
+pub fn del_above_threshold_since(e: &Env, pool: &Address) {
+    let key = BackstopDataKey::AboveThresh(pool.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/// Fetch the time a pool's backstop began winding down, if it is winding down
+///
+/// ### Arguments
+/// * `pool` - The pool
+pub fn get_wind_down_time(e: &Env, pool: &Address) -> Option<u64> {
+    let key = BackstopDataKey::WindDown(pool.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the time a pool's backstop began winding down
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `timestamp` - The time the wind down began
+pub fn set_wind_down_time(e: &Env, pool: &Address, timestamp: u64) {
+    let key = BackstopDataKey::WindDown(pool.clone());
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, u64>(&key, &timestamp);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Check if a Q4W lock time has been set for a pool
+///
+/// ### Arguments
+/// * `pool` - The pool
+pub fn has_q4w_lock_time(e: &Env, pool: &Address) -> bool {
+    let key = BackstopDataKey::Q4WLock(pool.clone());
+    e.storage().persistent().has(&key)
+}
+
+/// Fetch the time in seconds that a Q4W entry is locked for in a pool. Defaults to
+/// Q4W_LOCK_TIME if the pool has not set one.
+///
+/// ### Arguments
+/// * `pool` - The pool
+pub fn get_q4w_lock_time(e: &Env, pool: &Address) -> u64 {
+    let key = BackstopDataKey::Q4WLock(pool.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || Q4W_LOCK_TIME,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the time in seconds that a Q4W entry is locked for in a pool
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `lock_time` - The lock time in seconds
+pub fn set_q4w_lock_time(e: &Env, pool: &Address, lock_time: &u64) {
+    let key = BackstopDataKey::Q4WLock(pool.clone());
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, u64>(&key, lock_time);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Fetch the total bonus shares granted by deposit lockups in a pool
+///
+/// ### Arguments
+/// * `pool` - The pool
+pub fn get_lock_bonus(e: &Env, pool: &Address) -> i128 {
+    let key = BackstopDataKey::LockBonus(pool.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || 0i128,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the total bonus shares granted by deposit lockups in a pool
+///
+/// ### Arguments
+/// * `pool` - The pool
+/// * `bonus` - The total bonus shares
+pub fn set_lock_bonus(e: &Env, pool: &Address, bonus: i128) {
+    let key = BackstopDataKey::LockBonus(pool.clone());
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, i128>(&key, &bonus);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
 /********** Distribution / Reward Zone **********/
 
 /// Get the timestamp of when the next emission cycle begins
@@ -312,6 +736,34 @@ pub fn set_last_distribution_time(e: &Env, timestamp: &u64) {
     );
 }
 
+/// Get the cumulative amount of BLND the emitter had distributed to the backstop as of the last
+/// emission cycle
+pub fn get_distributed(e: &Env) -> i128 {
+    get_persistent_default(
+        e,
+        &Symbol::new(e, DISTRIBUTED_KEY),
+        || 0i128,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the cumulative amount of BLND the emitter had distributed to the backstop as of the last
+/// emission cycle
+///
+/// ### Arguments
+/// * `distributed` - The cumulative amount of BLND distributed
+pub fn set_distributed(e: &Env, distributed: &i128) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, i128>(&Symbol::new(e, DISTRIBUTED_KEY), distributed);
+    e.storage().persistent().extend_ttl(
+        &Symbol::new(e, DISTRIBUTED_KEY),
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    );
+}
+
 /// Get the current pool addresses that are in the reward zone
 pub fn get_reward_zone(e: &Env) -> Vec<Address> {
     get_persistent_default(
@@ -338,6 +790,32 @@ pub fn set_reward_zone(e: &Env, reward_zone: &Vec<Address>) {
     );
 }
 
+/// Get the emissions carried over from wound down pools to the next distribution
+pub fn get_residual_emissions(e: &Env) -> i128 {
+    get_persistent_default(
+        e,
+        &Symbol::new(e, RESIDUAL_EMIS_KEY),
+        || 0i128,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the emissions carried over from wound down pools to the next distribution
+///
+/// ### Arguments
+/// * `emissions` - The number of tokens to carry over
+pub fn set_residual_emissions(e: &Env, emissions: i128) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, i128>(&Symbol::new(e, RESIDUAL_EMIS_KEY), &emissions);
+    e.storage().persistent().extend_ttl(
+        &Symbol::new(e, RESIDUAL_EMIS_KEY),
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    );
+}
+
 /// Get the current emissions accrued for the pool
 ///
 /// ### Arguments
@@ -462,6 +940,71 @@ pub fn set_user_emis_data(
         .set::<BackstopDataKey, UserEmissionData>(&key, user_emis_data);
 }
 
+/// Get the time the user's unclaimed backstop emissions began accruing, or None if the user has no
+/// unclaimed emissions recorded
+///
+/// ### Arguments
+/// * `pool` - The pool whose backstop the user's emissions are for
+/// * `user` - The user's address
+pub fn get_user_accrual_time(e: &Env, pool: &Address, user: &Address) -> Option<u64> {
+    let key = BackstopDataKey::UEmisTime(PoolUserKey {
+        pool: pool.clone(),
+        user: user.clone(),
+    });
+    get_persistent_default(e, &key, || None, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
+}
+
+/// Set the time the user's unclaimed backstop emissions began accruing
+///
+/// ### Arguments
+/// * `pool` - The pool whose backstop the user's emissions are for
+/// * `user` - The user's address
+/// * `time` - The timestamp the unclaimed emissions began accruing
+pub fn set_user_accrual_time(e: &Env, pool: &Address, user: &Address, time: u64) {
+    let key = BackstopDataKey::UEmisTime(PoolUserKey {
+        pool: pool.clone(),
+        user: user.clone(),
+    });
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, u64>(&key, &time);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Remove the time the user's unclaimed backstop emissions began accruing
+///
+/// ### Arguments
+/// * `pool` - The pool whose backstop the user's emissions are for
+/// * `user` - The user's address
+pub fn del_user_accrual_time(e: &Env, pool: &Address, user: &Address) {
+    let key = BackstopDataKey::UEmisTime(PoolUserKey {
+        pool: pool.clone(),
+        user: user.clone(),
+    });
+    e.storage().persistent().remove(&key);
+}
+
+/// Fetch the time in seconds a user's emissions can go unclaimed before they can be recycled, or
+/// the default of 2 years if not set
+pub fn get_claim_deadline(e: &Env) -> u64 {
+    e.storage()
+        .instance()
+        .get::<Symbol, u64>(&Symbol::new(e, CLAIM_DEADLINE_KEY))
+        .unwrap_or(DEFAULT_CLAIM_DEADLINE)
+}
+
+/// Set the time in seconds a user's emissions can go unclaimed before they can be recycled
+///
+/// ### Arguments
+/// * `deadline` - The claim deadline in seconds
+pub fn set_claim_deadline(e: &Env, deadline: u64) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u64>(&Symbol::new(e, CLAIM_DEADLINE_KEY), &deadline);
+}
+
 /********** Drop Emissions **********/
 
 /// Get the current pool addresses that are in the drop list and the amount of the initial distribution they receive
@@ -489,6 +1032,13 @@ pub fn set_drop_list(e: &Env, drop_list: &Vec<(Address, i128)>) {
 
 /********** LP Token Value **********/
 
+/// Check if the token value for the LP pool has been set
+pub fn has_lp_token_val(e: &Env) -> bool {
+    e.storage()
+        .persistent()
+        .has(&Symbol::new(e, LP_TOKEN_VAL_KEY))
+}
+
 /// Get the last updated token value for the LP pool
 pub fn get_lp_token_val(e: &Env) -> (i128, i128) {
     e.storage().persistent().extend_ttl(
@@ -516,3 +1066,65 @@ pub fn set_lp_token_val(e: &Env, share_val: &(i128, i128)) {
         LEDGER_BUMP_SHARED,
     );
 }
+
+/// Get the time the token value for the LP pool was last updated
+pub fn get_lp_token_val_time(e: &Env) -> u64 {
+    get_persistent_default(
+        e,
+        &Symbol::new(e, LP_TOKEN_VAL_TIME_KEY),
+        || 0u64,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the time the token value for the LP pool was last updated
+///
+/// ### Arguments
+/// * `time` - The ledger timestamp of the update
+pub fn set_lp_token_val_time(e: &Env, time: u64) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, u64>(&Symbol::new(e, LP_TOKEN_VAL_TIME_KEY), &time);
+    e.storage().persistent().extend_ttl(
+        &Symbol::new(e, LP_TOKEN_VAL_TIME_KEY),
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    );
+}
+
+/********** Voting Weight **********/
+
+/// Fetch the voting weight checkpoints for a user in a pool, ordered from oldest to newest
+///
+/// ### Arguments
+/// * `pool` - The pool the checkpoints are associated with
+/// * `user` - The owner of the deposit
+pub fn get_vote_checkpoints(e: &Env, pool: &Address, user: &Address) -> Vec<VoteSnapshot> {
+    let key = BackstopDataKey::Votes(PoolUserKey {
+        pool: pool.clone(),
+        user: user.clone(),
+    });
+    get_persistent_default(e, &key, || vec![e], LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
+}
+
+/// Set the voting weight checkpoints for a user in a pool
+///
+/// ### Arguments
+/// * `pool` - The pool the checkpoints are associated with
+/// * `user` - The owner of the deposit
+/// * `checkpoints` - The checkpoints, ordered from oldest to newest
+pub fn set_vote_checkpoints(
+    e: &Env,
+    pool: &Address,
+    user: &Address,
+    checkpoints: &Vec<VoteSnapshot>,
+) {
+    let key = BackstopDataKey::Votes(PoolUserKey {
+        pool: pool.clone(),
+        user: user.clone(),
+    });
+    e.storage()
+        .persistent()
+        .set::<BackstopDataKey, Vec<VoteSnapshot>>(&key, checkpoints);
+}
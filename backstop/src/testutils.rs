@@ -8,15 +8,17 @@ use crate::{
 };
 
 use soroban_sdk::{
+    contract, contractimpl,
     testutils::{Address as _, Ledger, LedgerInfo},
     unwrap::UnwrapOptimized,
-    vec, Address, Env, IntoVal, Vec,
+    vec, Address, Env, IntoVal, Symbol, Vec,
 };
 
 use sep_41_token::testutils::{MockTokenClient, MockTokenWASM};
 
 use emitter::{EmitterClient, EmitterContract};
 use mock_pool_factory::{MockPoolFactory, MockPoolFactoryClient};
+use pause_registry::{PauseRegistryClient, PauseRegistryContract};
 
 pub(crate) fn create_backstop(e: &Env) -> Address {
     e.register_contract(None, BackstopContract {})
@@ -69,6 +71,39 @@ pub(crate) fn create_backstop_token<'a>(
     (contract_address, client)
 }
 
+// ***** Pool *****
+
+/// A minimal pool that reports a settable status and total liabilities
+#[contract]
+pub struct MockPool;
+
+#[contractimpl]
+impl MockPool {
+    pub fn set_status(e: Env, status: u32) {
+        e.storage().instance().set(&0u32, &status);
+    }
+
+    pub fn get_status(e: Env) -> u32 {
+        e.storage().instance().get(&0u32).unwrap_or(1)
+    }
+
+    pub fn set_total_liabilities(e: Env, liabilities: i128) {
+        e.storage().instance().set(&1u32, &liabilities);
+    }
+
+    pub fn get_total_liabilities(e: Env) -> i128 {
+        e.storage().instance().get(&1u32).unwrap_or(0)
+    }
+}
+
+pub(crate) fn create_mock_pool<'a>(e: &Env) -> (Address, MockPoolClient<'a>) {
+    let contract_address = e.register_contract(None, MockPool {});
+    (
+        contract_address.clone(),
+        MockPoolClient::new(e, &contract_address),
+    )
+}
+
 pub(crate) fn create_mock_pool_factory<'a>(
     e: &Env,
     backstop: &Address,
@@ -83,6 +118,20 @@ pub(crate) fn create_mock_pool_factory<'a>(
     )
 }
 
+pub(crate) fn create_pause_registry<'a>(
+    e: &Env,
+    backstop: &Address,
+    admin: &Address,
+) -> (Address, PauseRegistryClient<'a>) {
+    let contract_address = e.register_contract(None, PauseRegistryContract {});
+    let client = PauseRegistryClient::new(e, &contract_address);
+    client.initialize(admin);
+    e.as_contract(backstop, || {
+        storage::set_pause_registry(e, &contract_address);
+    });
+    (contract_address, client)
+}
+
 pub(crate) fn create_emitter<'a>(
     e: &Env,
     backstop: &Address,
@@ -106,8 +155,9 @@ pub(crate) fn create_emitter<'a>(
     e.as_contract(backstop, || {
         storage::set_emitter(e, &contract_address);
     });
+    let (pause_registry, _) = create_pause_registry(e, backstop, &Address::generate(e));
     let client = EmitterClient::new(e, &contract_address);
-    client.initialize(&blnd_token, &backstop, &backstop_token);
+    client.initialize(&blnd_token, &backstop, &backstop_token, &pause_registry);
     e.ledger().set(LedgerInfo {
         timestamp: prev_timestamp,
         protocol_version: 20,
@@ -121,6 +171,21 @@ pub(crate) fn create_emitter<'a>(
     (contract_address.clone(), client)
 }
 
+/// Set the cumulative amount of BLND the emitter has distributed to the backstop
+pub(crate) fn set_emitter_distributed(
+    e: &Env,
+    emitter: &Address,
+    backstop: &Address,
+    distributed: i128,
+) {
+    e.as_contract(emitter, || {
+        e.storage().persistent().set(
+            &(Symbol::new(e, "Distributed"), backstop.clone()),
+            &distributed,
+        );
+    });
+}
+
 /// Deploy a test Comet LP pool of 80% BLND / 20% USDC and set it as the backstop token.
 ///
 /// Initializes the pool with the following settings:
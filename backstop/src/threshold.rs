@@ -0,0 +1,255 @@
+use soroban_sdk::{panic_with_error, Env};
+
+use crate::{
+    constants::{MAX_THRESHOLD_PC, MAX_THRESHOLD_WEIGHT, MIN_THRESHOLD_PC, THRESHOLD_TIMELOCK},
+    storage::{self, QueuedThresholdConfig, ThresholdConfig},
+    upgrade::require_admin,
+    BackstopError,
+};
+
+/// Queue an update to the backstop threshold config
+///
+/// ### Arguments
+/// * `config` - The new threshold config
+///
+/// ### Panics
+/// If the caller is not the pause registry admin, an update is already queued, or the config is
+/// invalid
+pub fn execute_queue_threshold_config(e: &Env, config: &ThresholdConfig) -> QueuedThresholdConfig {
+    require_admin(e);
+    if storage::get_queued_threshold_config(e).is_some() {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+    require_valid_threshold_config(e, config);
+
+    let queued_config = QueuedThresholdConfig {
+        config: config.clone(),
+        unlock_time: e.ledger().timestamp() + THRESHOLD_TIMELOCK,
+    };
+    storage::set_queued_threshold_config(e, &queued_config);
+    queued_config
+}
+
+/// Cancel the queued update to the backstop threshold config
+///
+/// ### Panics
+/// If the caller is not the pause registry admin or no update is queued
+pub fn execute_cancel_threshold_config(e: &Env) -> QueuedThresholdConfig {
+    require_admin(e);
+    let queued_config = storage::get_queued_threshold_config(e)
+        .unwrap_or_else(|| panic_with_error!(e, BackstopError::BadRequest));
+
+    storage::del_queued_threshold_config(e);
+    queued_config
+}
+
+/// Execute the queued update to the backstop threshold config
+///
+/// Returns the new threshold config
+///
+/// ### Panics
+/// If no update is queued or the queued update has not been unlocked
+pub fn execute_set_threshold_config(e: &Env) -> ThresholdConfig {
+    let queued_config = storage::get_queued_threshold_config(e)
+        .unwrap_or_else(|| panic_with_error!(e, BackstopError::BadRequest));
+    if queued_config.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, BackstopError::ThresholdNotUnlocked);
+    }
+
+    storage::del_queued_threshold_config(e);
+    storage::set_threshold_config(e, &queued_config.config);
+    queued_config.config
+}
+
+/// Require that a threshold config weights both balances, and that the threshold product constant
+/// cannot overflow
+fn require_valid_threshold_config(e: &Env, config: &ThresholdConfig) {
+    if config.blnd_weight == 0
+        || config.usdc_weight == 0
+        || config.blnd_weight + config.usdc_weight > MAX_THRESHOLD_WEIGHT
+        || !(MIN_THRESHOLD_PC..=MAX_THRESHOLD_PC).contains(&config.pc)
+    {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        Address,
+    };
+
+    use crate::testutils::{create_backstop, create_pause_registry};
+
+    use super::*;
+
+    fn set_timestamp(e: &Env, timestamp: u64) {
+        e.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+    }
+
+    #[test]
+    fn test_set_threshold_config() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 1_000_000);
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+        let config = ThresholdConfig {
+            blnd_weight: 1,
+            usdc_weight: 1,
+            pc: 50_000,
+        };
+
+        e.as_contract(&backstop_address, || {
+            assert_eq!(
+                storage::get_threshold_config(&e),
+                ThresholdConfig {
+                    blnd_weight: 4,
+                    usdc_weight: 1,
+                    pc: 200_000,
+                }
+            );
+            let queued_config = execute_queue_threshold_config(&e, &config);
+            assert_eq!(queued_config.config, config);
+            assert_eq!(queued_config.unlock_time, 1_000_000 + THRESHOLD_TIMELOCK);
+        });
+
+        set_timestamp(&e, 1_000_000 + THRESHOLD_TIMELOCK);
+        e.as_contract(&backstop_address, || {
+            let new_config = execute_set_threshold_config(&e);
+            assert_eq!(new_config, config);
+            assert_eq!(storage::get_threshold_config(&e), config);
+            assert!(storage::get_queued_threshold_config(&e).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1012)")]
+    fn test_set_threshold_config_not_unlocked() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 1_000_000);
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+
+        e.as_contract(&backstop_address, || {
+            execute_queue_threshold_config(
+                &e,
+                &ThresholdConfig {
+                    blnd_weight: 1,
+                    usdc_weight: 1,
+                    pc: 50_000,
+                },
+            );
+        });
+
+        set_timestamp(&e, 1_000_000 + THRESHOLD_TIMELOCK - 1);
+        e.as_contract(&backstop_address, || {
+            execute_set_threshold_config(&e);
+        });
+    }
+
+    #[test]
+    fn test_cancel_threshold_config() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 1_000_000);
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+
+        e.as_contract(&backstop_address, || {
+            execute_queue_threshold_config(
+                &e,
+                &ThresholdConfig {
+                    blnd_weight: 1,
+                    usdc_weight: 1,
+                    pc: 50_000,
+                },
+            );
+            execute_cancel_threshold_config(&e);
+            assert!(storage::get_queued_threshold_config(&e).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_queue_threshold_config_requires_usdc_weight() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+
+        e.as_contract(&backstop_address, || {
+            execute_queue_threshold_config(
+                &e,
+                &ThresholdConfig {
+                    blnd_weight: 4,
+                    usdc_weight: 0,
+                    pc: 200_000,
+                },
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_queue_threshold_config_validates_weight() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+
+        e.as_contract(&backstop_address, || {
+            execute_queue_threshold_config(
+                &e,
+                &ThresholdConfig {
+                    blnd_weight: 4,
+                    usdc_weight: 2,
+                    pc: 200_000,
+                },
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_queue_threshold_config_validates_pc() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+
+        e.as_contract(&backstop_address, || {
+            execute_queue_threshold_config(
+                &e,
+                &ThresholdConfig {
+                    blnd_weight: 4,
+                    usdc_weight: 1,
+                    pc: MAX_THRESHOLD_PC + 1,
+                },
+            );
+        });
+    }
+}
@@ -0,0 +1,225 @@
+use soroban_sdk::{panic_with_error, BytesN, Env};
+
+use crate::{
+    constants::{STORAGE_VERSION, UPGRADE_TIMELOCK},
+    dependencies::PauseRegistryClient,
+    storage::{self, QueuedUpgrade},
+    BackstopError,
+};
+
+/// Require that the pause registry admin has authorized the call
+pub(crate) fn require_admin(e: &Env) {
+    let pause_registry = storage::get_pause_registry(e);
+    PauseRegistryClient::new(e, &pause_registry)
+        .get_admin()
+        .require_auth();
+}
+
+/// Queue an upgrade of the backstop's WASM
+///
+/// ### Arguments
+/// * `wasm_hash` - The hash of the installed WASM to upgrade to
+///
+/// ### Panics
+/// If the caller is not the pause registry admin or an upgrade is already queued
+pub fn execute_queue_upgrade(e: &Env, wasm_hash: &BytesN<32>) -> QueuedUpgrade {
+    require_admin(e);
+    if storage::get_queued_upgrade(e).is_some() {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+
+    let queued_upgrade = QueuedUpgrade {
+        wasm_hash: wasm_hash.clone(),
+        unlock_time: e.ledger().timestamp() + UPGRADE_TIMELOCK,
+    };
+    storage::set_queued_upgrade(e, &queued_upgrade);
+    queued_upgrade
+}
+
+/// Cancel the queued upgrade of the backstop's WASM
+///
+/// ### Panics
+/// If the caller is not the pause registry admin or no upgrade is queued
+pub fn execute_cancel_upgrade(e: &Env) -> QueuedUpgrade {
+    require_admin(e);
+    let queued_upgrade = storage::get_queued_upgrade(e)
+        .unwrap_or_else(|| panic_with_error!(e, BackstopError::BadRequest));
+
+    storage::del_queued_upgrade(e);
+    queued_upgrade
+}
+
+/// Execute the queued upgrade of the backstop's WASM. The new WASM is used starting with the
+/// next invocation, where `execute_migrate` brings the stored state up to its layout version.
+///
+/// Returns the hash of the new WASM
+///
+/// ### Panics
+/// If no upgrade is queued or the queued upgrade has not been unlocked
+pub fn execute_upgrade(e: &Env) -> BytesN<32> {
+    let queued_upgrade = storage::get_queued_upgrade(e)
+        .unwrap_or_else(|| panic_with_error!(e, BackstopError::BadRequest));
+    if queued_upgrade.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, BackstopError::UpgradeNotUnlocked);
+    }
+
+    storage::del_queued_upgrade(e);
+    e.deployer()
+        .update_current_contract_wasm(queued_upgrade.wasm_hash.clone());
+    queued_upgrade.wasm_hash
+}
+
+/// Migrate the backstop's stored state to the current layout version
+///
+/// Only instance storage is migrated here. PoolBalance and UserBalance entries are stored per pool
+/// and per user and cannot all be rewritten in one invocation, so a version that changes their
+/// layout must convert them as they are loaded, based on the stored layout version.
+///
+/// Returns the new layout version
+///
+/// ### Panics
+/// If the stored state is already at the current layout version
+pub fn execute_migrate(e: &Env) -> u32 {
+    let version = storage::get_storage_version(e);
+    if version >= STORAGE_VERSION {
+        panic_with_error!(e, BackstopError::BadRequest);
+    }
+
+    // version 0 -> 1: backstops deployed before the layout was versioned already use the
+    // version 1 layout, so only the marker is written
+    storage::set_storage_version(e, STORAGE_VERSION);
+    STORAGE_VERSION
+}
+
+#[cfg(test)]
+mod tests {
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        Address,
+    };
+
+    use crate::testutils::{create_backstop, create_pause_registry};
+
+    use super::*;
+
+    #[test]
+    fn test_upgrade() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1_000_000,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+        let wasm_hash = e
+            .deployer()
+            .upload_contract_wasm(sep_41_token::testutils::MockTokenWASM);
+
+        e.as_contract(&backstop_address, || {
+            let queued_upgrade = execute_queue_upgrade(&e, &wasm_hash);
+            assert_eq!(queued_upgrade.wasm_hash, wasm_hash);
+            assert_eq!(queued_upgrade.unlock_time, 1_000_000 + UPGRADE_TIMELOCK);
+        });
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1_000_000 + UPGRADE_TIMELOCK,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        e.as_contract(&backstop_address, || {
+            let new_wasm_hash = execute_upgrade(&e);
+            assert_eq!(new_wasm_hash, wasm_hash);
+            assert!(storage::get_queued_upgrade(&e).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1010)")]
+    fn test_upgrade_requires_unlock() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+
+        e.as_contract(&backstop_address, || {
+            execute_queue_upgrade(&e, &BytesN::from_array(&e, &[1; 32]));
+            execute_upgrade(&e);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_queue_upgrade_duplicate() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+
+        e.as_contract(&backstop_address, || {
+            execute_queue_upgrade(&e, &BytesN::from_array(&e, &[1; 32]));
+            execute_queue_upgrade(&e, &BytesN::from_array(&e, &[2; 32]));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_cancel_upgrade() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop_address = create_backstop(&e);
+        let bombadil = Address::generate(&e);
+        create_pause_registry(&e, &backstop_address, &bombadil);
+
+        e.as_contract(&backstop_address, || {
+            execute_queue_upgrade(&e, &BytesN::from_array(&e, &[1; 32]));
+            execute_cancel_upgrade(&e);
+            assert!(storage::get_queued_upgrade(&e).is_none());
+
+            execute_upgrade(&e);
+        });
+    }
+
+    #[test]
+    fn test_migrate() {
+        let e = Env::default();
+        let backstop_address = create_backstop(&e);
+
+        e.as_contract(&backstop_address, || {
+            assert_eq!(storage::get_storage_version(&e), 0);
+            let version = execute_migrate(&e);
+            assert_eq!(version, STORAGE_VERSION);
+            assert_eq!(storage::get_storage_version(&e), STORAGE_VERSION);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1000)")]
+    fn test_migrate_current_version() {
+        let e = Env::default();
+        let backstop_address = create_backstop(&e);
+
+        e.as_contract(&backstop_address, || {
+            storage::set_storage_version(&e, STORAGE_VERSION);
+            execute_migrate(&e);
+        });
+    }
+}
@@ -1,17 +1,27 @@
 #![no_std]
 
+#[cfg(feature = "std")]
+extern crate std;
+
+#[cfg(feature = "contracts")]
 pub mod backstop {
     soroban_sdk::contractimport!(file = "./wasm/backstop.wasm");
 }
+#[cfg(feature = "contracts")]
 pub mod emitter {
     soroban_sdk::contractimport!(file = "./wasm/emitter.wasm");
 }
+#[cfg(feature = "contracts")]
 pub mod pool_factory {
     soroban_sdk::contractimport!(file = "./wasm/pool_factory.wasm");
 }
+#[cfg(feature = "contracts")]
 pub mod pool {
     soroban_sdk::contractimport!(file = "./wasm/pool.wasm");
 }
 
-#[cfg(any(test, feature = "testutils"))]
+#[cfg(all(feature = "contracts", any(test, feature = "testutils")))]
 pub mod testutils;
+
+#[cfg(feature = "std")]
+pub mod tx;
@@ -25,6 +25,76 @@ pub fn default_reserve_config() -> pool::ReserveConfig {
     }
 }
 
+/// Deploy a test Comet LP pool of 80% token_1 / 20% token_2. The tokens must be Stellar Asset
+/// Contracts, as the underlying tokens are minted to the admin to initialize the pool.
+///
+/// Initializes the pool with the following settings:
+/// - Swap fee: 0.3%
+/// - Token 1: 1,000
+/// - Token 2: 25
+/// - Shares: 100
+///
+/// This function also resets the env budget via `reset_unlimited`.
+///
+/// ### Arguments
+/// * `env` - The environment to deploy the contract in
+/// * `admin` - The address of the pool admin
+/// * `token_1` - The address of the 80% weighted token (e.g. BLND)
+/// * `token_2` - The address of the 20% weighted token (e.g. USDC)
+pub fn create_comet_lp_pool<'a>(
+    env: &Env,
+    admin: &Address,
+    token_1: &Address,
+    token_2: &Address,
+) -> (Address, comet::Client<'a>) {
+    env.budget().reset_unlimited();
+    let contract_address = env.register_contract_wasm(None, comet::WASM);
+    let client = comet::Client::new(env, &contract_address);
+
+    StellarAssetClient::new(env, token_1)
+        .mock_all_auths()
+        .mint(admin, &1_000_0000000);
+    StellarAssetClient::new(env, token_2)
+        .mock_all_auths()
+        .mint(admin, &25_0000000);
+
+    client.mock_all_auths().init(
+        admin,
+        &vec![env, token_1.clone(), token_2.clone()],
+        &vec![env, 0_8000000, 0_2000000],
+        &vec![env, 1_000_0000000, 25_0000000],
+        &0_0030000,
+    );
+
+    (contract_address, client)
+}
+
+/// Mint Comet LP tokens to an address by minting the required underlying tokens and joining
+/// the pool. The underlying tokens must be Stellar Asset Contracts.
+///
+/// Can be used to fund users with backstop tokens for backstop deposits.
+///
+/// ### Arguments
+/// * `env` - The environment the Comet LP pool is deployed in
+/// * `comet` - The Comet LP pool client
+/// * `to` - The address receiving the LP tokens
+/// * `amount` - The amount of LP tokens to mint
+pub fn mint_comet_lp_tokens(env: &Env, comet: &comet::Client, to: &Address, amount: i128) {
+    let total_supply = comet.get_total_supply();
+    let mut max_amounts_in: Vec<i128> = Vec::new(env);
+    for token in comet.get_tokens().iter() {
+        // add a buffer to account for rounding within the pool
+        let amount_in = comet.get_balance(&token) * amount / total_supply + 1;
+        StellarAssetClient::new(env, &token)
+            .mock_all_auths()
+            .mint(to, &amount_in);
+        max_amounts_in.push_back(amount_in);
+    }
+    comet
+        .mock_all_auths()
+        .join_pool(&amount, &max_amounts_in, to);
+}
+
 /// Fixture for deploying and interacting with the Blend Protocol contracts in Rust tests.
 pub struct BlendFixture<'a> {
     pub backstop: backstop::Client<'a>,
@@ -126,7 +196,9 @@ mod tests {
 
     use crate::{
         pool,
-        testutils::{default_reserve_config, BlendFixture},
+        testutils::{
+            create_comet_lp_pool, default_reserve_config, mint_comet_lp_tokens, BlendFixture,
+        },
     };
 
     #[test]
@@ -165,4 +237,23 @@ mod tests {
         assert_eq!(pool_client.update_status(), 1); // pool is active
         assert!(blend.pool_factory.is_pool(&pool)); // pool factory knows about the pool
     }
+
+    #[test]
+    fn test_comet_lp_pool() {
+        let env = Env::default();
+        let admin = Address::generate(&env);
+        let samwise = Address::generate(&env);
+        let blnd = env.register_stellar_asset_contract(admin.clone());
+        let usdc = env.register_stellar_asset_contract(admin.clone());
+
+        let (_, comet_client) = create_comet_lp_pool(&env, &admin, &blnd, &usdc);
+        assert_eq!(comet_client.get_total_supply(), 100_0000000);
+        assert_eq!(comet_client.balance(&admin), 100_0000000);
+
+        mint_comet_lp_tokens(&env, &comet_client, &samwise, 50_0000000);
+        assert_eq!(comet_client.balance(&samwise), 50_0000000);
+        assert_eq!(comet_client.get_total_supply(), 150_0000000);
+        assert_eq!(comet_client.get_balance(&blnd), 1_500_0000000);
+        assert_eq!(comet_client.get_balance(&usdc), 37_5000000);
+    }
 }
@@ -0,0 +1,431 @@
+//! Helpers for building Blend Protocol transactions off-chain, like from a bot or backend
+//! service. Each helper builds the `InvokeHostFunctionOp` for a common flow. The op's
+//! authorization entries are left empty, and should be filled in from the result of simulating
+//! the transaction with Soroban RPC.
+//!
+//! Addresses are `ScAddress`es, which can be parsed from strkeys (`G...` or `C...`) with
+//! `ScAddress::from_str`.
+
+use std::{string::String, vec::Vec};
+
+use stellar_xdr::curr::{
+    Error, HostFunction, InvokeContractArgs, InvokeHostFunctionOp, Limits, Operation,
+    OperationBody, ScAddress, ScMap, ScSymbol, ScVal, ScVec, WriteXdr,
+};
+
+/// The type of request to be made against a pool
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u32)]
+pub enum RequestType {
+    Supply = 0,
+    Withdraw = 1,
+    SupplyCollateral = 2,
+    WithdrawCollateral = 3,
+    Borrow = 4,
+    Repay = 5,
+    FillUserLiquidationAuction = 6,
+    FillBadDebtAuction = 7,
+    FillInterestAuction = 8,
+    DeleteLiquidationAuction = 9,
+    ClosePosition = 10,
+    LoopSupplyBorrow = 11,
+    ExecuteQueuedWithdraw = 12,
+    RepayFillWithSwap = 13,
+    CapFillBid = 14,
+}
+
+/// A request a user makes against a pool
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Request {
+    pub request_type: RequestType,
+    pub address: ScAddress, // asset address or liquidatee
+    pub amount: i128,
+}
+
+impl Request {
+    /// Create a request
+    ///
+    /// ### Arguments
+    /// * `request_type` - The type of request
+    /// * `address` - The asset address, or the user being liquidated for auction requests
+    /// * `amount` - The amount of the request
+    pub fn new(request_type: RequestType, address: ScAddress, amount: i128) -> Self {
+        Request {
+            request_type,
+            address,
+            amount,
+        }
+    }
+
+    /// Create a request to supply `amount` of `asset` without using it as collateral
+    pub fn supply(asset: ScAddress, amount: i128) -> Self {
+        Request::new(RequestType::Supply, asset, amount)
+    }
+
+    /// Create a request to withdraw `amount` of `asset` that was not supplied as collateral
+    pub fn withdraw(asset: ScAddress, amount: i128) -> Self {
+        Request::new(RequestType::Withdraw, asset, amount)
+    }
+
+    /// Create a request to supply `amount` of `asset` as collateral
+    pub fn supply_collateral(asset: ScAddress, amount: i128) -> Self {
+        Request::new(RequestType::SupplyCollateral, asset, amount)
+    }
+
+    /// Create a request to withdraw `amount` of `asset` from collateral
+    pub fn withdraw_collateral(asset: ScAddress, amount: i128) -> Self {
+        Request::new(RequestType::WithdrawCollateral, asset, amount)
+    }
+
+    /// Create a request to borrow `amount` of `asset`
+    pub fn borrow(asset: ScAddress, amount: i128) -> Self {
+        Request::new(RequestType::Borrow, asset, amount)
+    }
+
+    /// Create a request to repay `amount` of `asset`
+    pub fn repay(asset: ScAddress, amount: i128) -> Self {
+        Request::new(RequestType::Repay, asset, amount)
+    }
+
+    /// Create a request to fill `percent` of the liquidation auction of `user`
+    pub fn fill_user_liquidation_auction(user: ScAddress, percent: i128) -> Self {
+        Request::new(RequestType::FillUserLiquidationAuction, user, percent)
+    }
+
+    /// Create a request to fill `percent` of the bad debt auction of `backstop`
+    pub fn fill_bad_debt_auction(backstop: ScAddress, percent: i128) -> Self {
+        Request::new(RequestType::FillBadDebtAuction, backstop, percent)
+    }
+
+    /// Create a request to fill `percent` of the interest auction of `backstop`
+    pub fn fill_interest_auction(backstop: ScAddress, percent: i128) -> Self {
+        Request::new(RequestType::FillInterestAuction, backstop, percent)
+    }
+
+    /// Convert the request to the `ScVal` expected by the pool
+    pub fn to_sc_val(&self) -> Result<ScVal, Error> {
+        let map = ScMap::sorted_from(std::vec![
+            (
+                ScVal::Symbol(ScSymbol("address".try_into()?)),
+                ScVal::Address(self.address.clone()),
+            ),
+            (
+                ScVal::Symbol(ScSymbol("amount".try_into()?)),
+                ScVal::from(self.amount),
+            ),
+            (
+                ScVal::Symbol(ScSymbol("request_type".try_into()?)),
+                ScVal::from(self.request_type as u32),
+            ),
+        ])?;
+        Ok(ScVal::Map(Some(map)))
+    }
+}
+
+/// Build an op to invoke `function` on `contract` with `args`
+///
+/// ### Arguments
+/// * `contract` - The address of the contract
+/// * `function` - The name of the function to invoke
+/// * `args` - The arguments to invoke the function with
+pub fn invoke_contract(
+    contract: &ScAddress,
+    function: &str,
+    args: Vec<ScVal>,
+) -> Result<InvokeHostFunctionOp, Error> {
+    Ok(InvokeHostFunctionOp {
+        host_function: HostFunction::InvokeContract(InvokeContractArgs {
+            contract_address: contract.clone(),
+            function_name: ScSymbol(function.try_into()?),
+            args: args.try_into()?,
+        }),
+        auth: Default::default(),
+    })
+}
+
+/// Encode an op as the base64 XDR of an `Operation`, ready to be added to a transaction
+///
+/// ### Arguments
+/// * `op` - The op
+pub fn to_operation_xdr(op: InvokeHostFunctionOp) -> Result<String, Error> {
+    Operation {
+        source_account: None,
+        body: OperationBody::InvokeHostFunction(op),
+    }
+    .to_xdr_base64(Limits::none())
+}
+
+/********** Pool **********/
+
+/// Build an op to submit a set of requests to a pool
+///
+/// ### Arguments
+/// * `pool` - The address of the pool
+/// * `from` - The address of the user whose positions are being modified
+/// * `spender` - The address of the user who is sending tokens to the pool
+/// * `to` - The address of the user who is receiving tokens from the pool
+/// * `requests` - The requests to submit
+pub fn pool_submit(
+    pool: &ScAddress,
+    from: &ScAddress,
+    spender: &ScAddress,
+    to: &ScAddress,
+    requests: &[Request],
+) -> Result<InvokeHostFunctionOp, Error> {
+    let requests = requests
+        .iter()
+        .map(Request::to_sc_val)
+        .collect::<Result<Vec<_>, _>>()?;
+    invoke_contract(
+        pool,
+        "submit",
+        std::vec![
+            ScVal::Address(from.clone()),
+            ScVal::Address(spender.clone()),
+            ScVal::Address(to.clone()),
+            ScVal::Vec(Some(ScVec(requests.try_into()?))),
+        ],
+    )
+}
+
+/// Build an op to claim emissions from a pool
+///
+/// ### Arguments
+/// * `pool` - The address of the pool
+/// * `from` - The address of the user claiming emissions
+/// * `reserve_token_ids` - The reserve token IDs to claim emissions for
+/// * `to` - The address receiving the emissions
+pub fn pool_claim(
+    pool: &ScAddress,
+    from: &ScAddress,
+    reserve_token_ids: &[u32],
+    to: &ScAddress,
+) -> Result<InvokeHostFunctionOp, Error> {
+    let reserve_token_ids = reserve_token_ids
+        .iter()
+        .map(ScVal::from)
+        .collect::<Vec<_>>();
+    invoke_contract(
+        pool,
+        "claim",
+        std::vec![
+            ScVal::Address(from.clone()),
+            ScVal::Vec(Some(ScVec(reserve_token_ids.try_into()?))),
+            ScVal::Address(to.clone()),
+        ],
+    )
+}
+
+/********** Backstop **********/
+
+/// Build an op to deposit backstop tokens into a pool's backstop
+///
+/// ### Arguments
+/// * `backstop` - The address of the backstop
+/// * `from` - The address of the user depositing
+/// * `pool` - The address of the pool
+/// * `amount` - The amount of backstop tokens to deposit
+pub fn backstop_deposit(
+    backstop: &ScAddress,
+    from: &ScAddress,
+    pool: &ScAddress,
+    amount: i128,
+) -> Result<InvokeHostFunctionOp, Error> {
+    backstop_pool_action(backstop, "deposit", from, pool, amount)
+}
+
+/// Build an op to queue shares for withdrawal from a pool's backstop
+///
+/// ### Arguments
+/// * `backstop` - The address of the backstop
+/// * `from` - The address of the user queuing shares
+/// * `pool` - The address of the pool
+/// * `amount` - The amount of shares to queue
+pub fn backstop_queue_withdrawal(
+    backstop: &ScAddress,
+    from: &ScAddress,
+    pool: &ScAddress,
+    amount: i128,
+) -> Result<InvokeHostFunctionOp, Error> {
+    backstop_pool_action(backstop, "queue_withdrawal", from, pool, amount)
+}
+
+/// Build an op to dequeue shares queued for withdrawal from a pool's backstop
+///
+/// ### Arguments
+/// * `backstop` - The address of the backstop
+/// * `from` - The address of the user dequeuing shares
+/// * `pool` - The address of the pool
+/// * `amount` - The amount of shares to dequeue
+pub fn backstop_dequeue_withdrawal(
+    backstop: &ScAddress,
+    from: &ScAddress,
+    pool: &ScAddress,
+    amount: i128,
+) -> Result<InvokeHostFunctionOp, Error> {
+    backstop_pool_action(backstop, "dequeue_withdrawal", from, pool, amount)
+}
+
+/// Build an op to withdraw expired queued shares from a pool's backstop
+///
+/// ### Arguments
+/// * `backstop` - The address of the backstop
+/// * `from` - The address of the user withdrawing
+/// * `pool` - The address of the pool
+/// * `amount` - The amount of shares to withdraw
+pub fn backstop_withdraw(
+    backstop: &ScAddress,
+    from: &ScAddress,
+    pool: &ScAddress,
+    amount: i128,
+) -> Result<InvokeHostFunctionOp, Error> {
+    backstop_pool_action(backstop, "withdraw", from, pool, amount)
+}
+
+/// Build an op to claim backstop emissions from a set of pools
+///
+/// ### Arguments
+/// * `backstop` - The address of the backstop
+/// * `from` - The address of the user claiming emissions
+/// * `pools` - The addresses of the pools to claim emissions from
+/// * `to` - The address receiving the emissions
+pub fn backstop_claim(
+    backstop: &ScAddress,
+    from: &ScAddress,
+    pools: &[ScAddress],
+    to: &ScAddress,
+) -> Result<InvokeHostFunctionOp, Error> {
+    let pools = pools
+        .iter()
+        .map(|pool| ScVal::Address(pool.clone()))
+        .collect::<Vec<_>>();
+    invoke_contract(
+        backstop,
+        "claim",
+        std::vec![
+            ScVal::Address(from.clone()),
+            ScVal::Vec(Some(ScVec(pools.try_into()?))),
+            ScVal::Address(to.clone()),
+        ],
+    )
+}
+
+/// Build an op for a backstop function that takes `(from, pool_address, amount)`
+fn backstop_pool_action(
+    backstop: &ScAddress,
+    function: &str,
+    from: &ScAddress,
+    pool: &ScAddress,
+    amount: i128,
+) -> Result<InvokeHostFunctionOp, Error> {
+    invoke_contract(
+        backstop,
+        function,
+        std::vec![
+            ScVal::Address(from.clone()),
+            ScVal::Address(pool.clone()),
+            ScVal::from(amount),
+        ],
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+    use std::string::ToString;
+
+    use soroban_sdk::{testutils::Address as _, vec, Address, Env, IntoVal, TryFromVal, Val};
+    use stellar_xdr::curr::{ReadXdr, VecM};
+
+    use super::*;
+    use crate::pool;
+
+    fn to_sc_address(address: &Address) -> ScAddress {
+        ScAddress::try_from(address).unwrap()
+    }
+
+    #[test]
+    fn test_request_to_sc_val() {
+        let e = Env::default();
+        let asset = Address::generate(&e);
+
+        let request = Request::supply_collateral(to_sc_address(&asset), 123_4567890);
+
+        // validate the request is encoded the same way the contract encodes it
+        let contract_request = pool::Request {
+            request_type: 2,
+            address: asset.clone(),
+            amount: 123_4567890,
+        };
+        let contract_val: Val = contract_request.into_val(&e);
+        let expected = ScVal::try_from_val(&e, &contract_val).unwrap();
+        assert_eq!(request.to_sc_val().unwrap(), expected);
+    }
+
+    #[test]
+    fn test_pool_submit() {
+        let e = Env::default();
+        let pool_address = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let asset_0 = Address::generate(&e);
+        let asset_1 = Address::generate(&e);
+
+        let requests = std::vec![
+            Request::supply_collateral(to_sc_address(&asset_0), 100_0000000),
+            Request::borrow(to_sc_address(&asset_1), 50_0000000),
+        ];
+        let op = pool_submit(
+            &to_sc_address(&pool_address),
+            &to_sc_address(&samwise),
+            &to_sc_address(&samwise),
+            &to_sc_address(&samwise),
+            &requests,
+        )
+        .unwrap();
+
+        let contract_requests = vec![
+            &e,
+            pool::Request {
+                request_type: 2,
+                address: asset_0.clone(),
+                amount: 100_0000000,
+            },
+            pool::Request {
+                request_type: 4,
+                address: asset_1.clone(),
+                amount: 50_0000000,
+            },
+        ];
+        let contract_val: Val = contract_requests.into_val(&e);
+        match op.host_function {
+            HostFunction::InvokeContract(args) => {
+                assert_eq!(args.contract_address, to_sc_address(&pool_address));
+                assert_eq!(args.function_name, ScSymbol("submit".try_into().unwrap()));
+                assert_eq!(args.args.len(), 4);
+                assert_eq!(args.args[0], ScVal::Address(to_sc_address(&samwise)));
+                assert_eq!(
+                    args.args[3],
+                    ScVal::try_from_val(&e, &contract_val).unwrap()
+                );
+            }
+            _ => panic!("expected contract invocation"),
+        }
+        assert_eq!(op.auth, VecM::default());
+    }
+
+    #[test]
+    fn test_to_operation_xdr() {
+        let e = Env::default();
+        let backstop = to_sc_address(&Address::generate(&e));
+        let pool = to_sc_address(&Address::generate(&e));
+        let samwise =
+            ScAddress::from_str(&to_sc_address(&Address::generate(&e)).to_string()).unwrap();
+
+        let op = backstop_deposit(&backstop, &samwise, &pool, 1_000_0000000).unwrap();
+        let xdr = to_operation_xdr(op.clone()).unwrap();
+
+        let decoded = Operation::from_xdr_base64(xdr, Limits::none()).unwrap();
+        assert_eq!(decoded.source_account, None);
+        assert_eq!(decoded.body, OperationBody::InvokeHostFunction(op));
+    }
+}
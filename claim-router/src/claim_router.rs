@@ -0,0 +1,146 @@
+use crate::{
+    dependencies::{BackstopClient, PoolClient},
+    errors::ClaimRouterError,
+    storage,
+};
+use sep_41_token::TokenClient;
+use soroban_sdk::{
+    contract, contractclient, contractimpl, contracttype, panic_with_error, Address, Env, Symbol,
+    Vec,
+};
+
+/// ### Claim Router
+///
+/// Claims pool and backstop emissions for a user across many pools in a single transaction.
+/// BLND claimed from the pools is collected by the router and paid out in a single transfer.
+#[contract]
+pub struct ClaimRouterContract;
+
+/// The reserve tokens to claim pool emissions for in a pool
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PoolClaim {
+    pub pool: Address,               // the address of the pool
+    pub reserve_token_ids: Vec<u32>, // the reserve token ids to claim emissions for
+}
+
+/// The emissions claimed by a routed claim
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub struct ClaimResult {
+    pub pool_emissions: i128,     // the BLND claimed from the pools and paid out
+    pub backstop_emissions: i128, // the BLND claimed from the backstop and deposited into each pool's backstop
+}
+
+#[contractclient(name = "ClaimRouterClient")]
+pub trait ClaimRouter {
+    /// Initialize the claim router
+    ///
+    /// ### Arguments
+    /// * `backstop` - The address of the backstop
+    /// * `blnd_token` - The address of the BLND token
+    ///
+    /// ### Errors
+    /// If initialize has already been called
+    fn initialize(e: Env, backstop: Address, blnd_token: Address);
+
+    /// Claim pool emissions for `from` across `pool_claims` and backstop deposit emissions for
+    /// `from` across `backstop_pools`. The BLND claimed from the pools is paid to `to` in a
+    /// single transfer. Backstop emissions are deposited into each pool's backstop on behalf
+    /// of `to`, as they are when claimed from the backstop directly.
+    ///
+    /// `from` must authorize the claim on the router and the nested claims on each pool and
+    /// the backstop.
+    ///
+    /// Returns the emissions claimed
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user claiming emissions
+    /// * `pool_claims` - The pools and reserve token ids to claim pool emissions for
+    /// * `backstop_pools` - The pools to claim backstop deposit emissions for
+    /// * `to` - The address receiving the emissions
+    ///
+    /// ### Errors
+    /// If there is nothing to claim, or any of the claims fail
+    fn claim(
+        e: Env,
+        from: Address,
+        pool_claims: Vec<PoolClaim>,
+        backstop_pools: Vec<Address>,
+        to: Address,
+    ) -> ClaimResult;
+
+    /// Fetch the address of the backstop
+    fn backstop(e: Env) -> Address;
+
+    /// Fetch the address of the BLND token
+    fn blnd_token(e: Env) -> Address;
+}
+
+#[contractimpl]
+impl ClaimRouter for ClaimRouterContract {
+    fn initialize(e: Env, backstop: Address, blnd_token: Address) {
+        storage::extend_instance(&e);
+        if storage::get_is_init(&e) {
+            panic_with_error!(&e, ClaimRouterError::AlreadyInitializedError);
+        }
+
+        storage::set_backstop(&e, &backstop);
+        storage::set_blnd_token(&e, &blnd_token);
+
+        storage::set_is_init(&e);
+    }
+
+    fn claim(
+        e: Env,
+        from: Address,
+        pool_claims: Vec<PoolClaim>,
+        backstop_pools: Vec<Address>,
+        to: Address,
+    ) -> ClaimResult {
+        storage::extend_instance(&e);
+        from.require_auth();
+        if pool_claims.is_empty() && backstop_pools.is_empty() {
+            panic_with_error!(&e, ClaimRouterError::EmptyClaim);
+        }
+
+        let mut result = ClaimResult::default();
+        let router = e.current_contract_address();
+        for pool_claim in pool_claims.iter() {
+            result.pool_emissions += PoolClient::new(&e, &pool_claim.pool).claim(
+                &from,
+                &pool_claim.reserve_token_ids,
+                &router,
+            );
+        }
+        if result.pool_emissions > 0 {
+            TokenClient::new(&e, &storage::get_blnd_token(&e)).transfer(
+                &router,
+                &to,
+                &result.pool_emissions,
+            );
+        }
+
+        if !backstop_pools.is_empty() {
+            result.backstop_emissions = BackstopClient::new(&e, &storage::get_backstop(&e)).claim(
+                &from,
+                &backstop_pools,
+                &to,
+            );
+        }
+
+        e.events().publish(
+            (Symbol::new(&e, "claim"), from),
+            (to, result.pool_emissions, result.backstop_emissions),
+        );
+        result
+    }
+
+    fn backstop(e: Env) -> Address {
+        storage::get_backstop(&e)
+    }
+
+    fn blnd_token(e: Env) -> Address {
+        storage::get_blnd_token(&e)
+    }
+}
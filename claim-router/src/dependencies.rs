@@ -0,0 +1,18 @@
+use soroban_sdk::{contractclient, Address, Env, Vec};
+
+/// The pool functions used by the claim router
+#[allow(dead_code)]
+#[contractclient(name = "PoolClient")]
+pub trait Pool {
+    /// Claim outstanding emissions for `from` for the given reserve token ids and send them to `to`
+    fn claim(e: Env, from: Address, reserve_token_ids: Vec<u32>, to: Address) -> i128;
+}
+
+/// The backstop functions used by the claim router
+#[allow(dead_code)]
+#[contractclient(name = "BackstopClient")]
+pub trait Backstop {
+    /// Claim backstop deposit emissions for `from` from the given pools and deposit them into
+    /// each pool's backstop on behalf of `to`
+    fn claim(e: Env, from: Address, pool_addresses: Vec<Address>, to: Address) -> i128;
+}
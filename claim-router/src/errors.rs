@@ -0,0 +1,14 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+/// Error codes for the claim router contract. Common errors are codes that match up with the built-in
+/// contracts error reporting. Claim router specific errors start at 1400.
+pub enum ClaimRouterError {
+    // Common Errors
+    InternalError = 1,
+    AlreadyInitializedError = 3,
+
+    EmptyClaim = 1400,
+}
@@ -0,0 +1,12 @@
+#![no_std]
+
+#[cfg(any(test, feature = "testutils"))]
+extern crate std;
+
+mod claim_router;
+mod dependencies;
+mod errors;
+mod storage;
+
+pub use claim_router::*;
+pub use errors::ClaimRouterError;
@@ -0,0 +1,399 @@
+use sep_41_token::StellarAssetClient;
+use soroban_sdk::{contracttype, panic_with_error, Address, Env};
+
+use crate::{
+    backstop_manager::is_new_backstop_is_larger, constants::BLND_ADMIN_TIMELOCK, storage,
+    EmitterError,
+};
+
+#[derive(Clone)]
+#[contracttype]
+pub struct AdminHandover {
+    pub new_admin: Address,
+    pub unlock_time: u64,
+}
+
+/// Queue a handover of the BLND token admin to a new address. Like a backstop swap, the new
+/// admin must hold more backstop tokens than the backstop, so the BLND admin can only move to
+/// a contract the backstop depositors have migrated to.
+///
+/// ### Arguments
+/// * `new_admin` - The address that will become the BLND token admin
+///
+/// ### Panics
+/// If a handover is already queued or the new admin does not hold more backstop tokens than
+/// the backstop
+pub fn execute_queue_admin_handover(e: &Env, new_admin: &Address) -> AdminHandover {
+    if storage::get_queued_admin(e).is_some() {
+        panic_with_error!(e, EmitterError::AdminHandoverAlreadyQueued);
+    }
+    if !is_new_backstop_is_larger(e, new_admin, &storage::get_backstop(e)) {
+        panic_with_error!(e, EmitterError::InsufficientBackstopSize);
+    }
+
+    let handover = AdminHandover {
+        new_admin: new_admin.clone(),
+        unlock_time: e.ledger().timestamp() + BLND_ADMIN_TIMELOCK,
+    };
+    storage::set_queued_admin(e, &handover);
+    handover
+}
+
+/// Cancel the queued BLND admin handover if the new admin no longer holds more backstop tokens
+/// than the backstop
+///
+/// ### Panics
+/// If no handover is queued or the new admin still holds more backstop tokens than the backstop
+pub fn execute_cancel_admin_handover(e: &Env) -> AdminHandover {
+    let handover = storage::get_queued_admin(e)
+        .unwrap_or_else(|| panic_with_error!(e, EmitterError::AdminHandoverNotQueued));
+    if is_new_backstop_is_larger(e, &handover.new_admin, &storage::get_backstop(e)) {
+        panic_with_error!(e, EmitterError::AdminHandoverCannotBeCanceled);
+    }
+
+    storage::del_queued_admin(e);
+    handover
+}
+
+/// Accept the queued BLND admin handover and transfer the BLND token admin to the new admin.
+/// The new admin must authorize the call, so an address that cannot confirm the handover never
+/// receives the admin rights.
+///
+/// ### Panics
+/// If no handover is queued, the handover has not been unlocked, the new admin no longer holds
+/// more backstop tokens than the backstop, or the new admin has not authorized the call
+pub fn execute_accept_admin_handover(e: &Env) -> AdminHandover {
+    let handover = storage::get_queued_admin(e)
+        .unwrap_or_else(|| panic_with_error!(e, EmitterError::AdminHandoverNotQueued));
+    handover.new_admin.require_auth();
+
+    if handover.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, EmitterError::AdminHandoverNotUnlocked);
+    }
+    if !is_new_backstop_is_larger(e, &handover.new_admin, &storage::get_backstop(e)) {
+        panic_with_error!(e, EmitterError::InsufficientBackstopSize);
+    }
+
+    storage::del_queued_admin(e);
+    StellarAssetClient::new(e, &storage::get_blnd_token(e)).set_admin(&handover.new_admin);
+    handover
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{constants::SCALAR_7, storage, testutils::create_emitter};
+
+    use super::*;
+    use sep_41_token::testutils::MockTokenClient;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        token::StellarAssetClient as SdkStellarAssetClient,
+    };
+
+    #[test]
+    fn test_admin_handover() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let backstop = Address::generate(&e);
+        let new_emitter = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let backstop_token = e.register_stellar_asset_contract(bombadil.clone());
+        let backstop_token_client = MockTokenClient::new(&e, &backstop_token);
+        backstop_token_client.mint(&backstop, &(1_000_000 * SCALAR_7));
+        backstop_token_client.mint(&new_emitter, &(1_000_001 * SCALAR_7));
+        e.as_contract(&emitter, || {
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_backstop(&e, &backstop);
+            storage::set_backstop_token(&e, &backstop_token);
+        });
+
+        e.as_contract(&emitter, || {
+            let handover = execute_queue_admin_handover(&e, &new_emitter);
+            assert_eq!(handover.new_admin, new_emitter);
+            assert_eq!(handover.unlock_time, 12345 + 31 * 24 * 60 * 60);
+            let stored_handover = storage::get_queued_admin(&e).unwrap();
+            assert_eq!(stored_handover.new_admin, new_emitter);
+            assert_eq!(stored_handover.unlock_time, handover.unlock_time);
+        });
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345 + 31 * 24 * 60 * 60,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        e.as_contract(&emitter, || {
+            let handover = execute_accept_admin_handover(&e);
+            assert_eq!(handover.new_admin, new_emitter);
+            assert!(storage::get_queued_admin(&e).is_none());
+        });
+        assert_eq!(
+            SdkStellarAssetClient::new(&e, &blnd_id).admin(),
+            new_emitter
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1112)")]
+    fn test_queue_admin_handover_already_queued() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let backstop = Address::generate(&e);
+        let new_emitter = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let backstop_token = e.register_stellar_asset_contract(bombadil.clone());
+        let backstop_token_client = MockTokenClient::new(&e, &backstop_token);
+        backstop_token_client.mint(&backstop, &(1_000_000 * SCALAR_7));
+        backstop_token_client.mint(&new_emitter, &(1_000_001 * SCALAR_7));
+        e.as_contract(&emitter, || {
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_backstop(&e, &backstop);
+            storage::set_backstop_token(&e, &backstop_token);
+        });
+
+        e.as_contract(&emitter, || {
+            execute_queue_admin_handover(&e, &new_emitter);
+            execute_queue_admin_handover(&e, &new_emitter);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1100)")]
+    fn test_queue_admin_handover_insufficient_backstop_size() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let backstop = Address::generate(&e);
+        let new_emitter = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let backstop_token = e.register_stellar_asset_contract(bombadil.clone());
+        let backstop_token_client = MockTokenClient::new(&e, &backstop_token);
+        backstop_token_client.mint(&backstop, &(1_000_000 * SCALAR_7));
+        backstop_token_client.mint(&new_emitter, &(1_000_001 * SCALAR_7));
+        e.as_contract(&emitter, || {
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_backstop(&e, &backstop);
+            storage::set_backstop_token(&e, &backstop_token);
+        });
+
+        backstop_token_client.mint(&backstop, &(1 * SCALAR_7));
+
+        e.as_contract(&emitter, || {
+            execute_queue_admin_handover(&e, &new_emitter);
+        });
+    }
+
+    #[test]
+    fn test_cancel_admin_handover() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let backstop = Address::generate(&e);
+        let new_emitter = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let backstop_token = e.register_stellar_asset_contract(bombadil.clone());
+        let backstop_token_client = MockTokenClient::new(&e, &backstop_token);
+        backstop_token_client.mint(&backstop, &(1_000_000 * SCALAR_7));
+        backstop_token_client.mint(&new_emitter, &(1_000_001 * SCALAR_7));
+        e.as_contract(&emitter, || {
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_backstop(&e, &backstop);
+            storage::set_backstop_token(&e, &backstop_token);
+        });
+
+        e.as_contract(&emitter, || {
+            execute_queue_admin_handover(&e, &new_emitter);
+        });
+        backstop_token_client.mint(&backstop, &(1 * SCALAR_7));
+
+        e.as_contract(&emitter, || {
+            let handover = execute_cancel_admin_handover(&e);
+            assert_eq!(handover.new_admin, new_emitter);
+            assert!(storage::get_queued_admin(&e).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1118)")]
+    fn test_cancel_admin_handover_still_larger() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let backstop = Address::generate(&e);
+        let new_emitter = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let backstop_token = e.register_stellar_asset_contract(bombadil.clone());
+        let backstop_token_client = MockTokenClient::new(&e, &backstop_token);
+        backstop_token_client.mint(&backstop, &(1_000_000 * SCALAR_7));
+        backstop_token_client.mint(&new_emitter, &(1_000_001 * SCALAR_7));
+        e.as_contract(&emitter, || {
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_backstop(&e, &backstop);
+            storage::set_backstop_token(&e, &backstop_token);
+        });
+
+        e.as_contract(&emitter, || {
+            execute_queue_admin_handover(&e, &new_emitter);
+            execute_cancel_admin_handover(&e);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1111)")]
+    fn test_cancel_admin_handover_not_queued() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let backstop = Address::generate(&e);
+        let new_emitter = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let backstop_token = e.register_stellar_asset_contract(bombadil.clone());
+        let backstop_token_client = MockTokenClient::new(&e, &backstop_token);
+        backstop_token_client.mint(&backstop, &(1_000_000 * SCALAR_7));
+        backstop_token_client.mint(&new_emitter, &(1_000_001 * SCALAR_7));
+        e.as_contract(&emitter, || {
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_backstop(&e, &backstop);
+            storage::set_backstop_token(&e, &backstop_token);
+        });
+
+        e.as_contract(&emitter, || {
+            execute_cancel_admin_handover(&e);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1113)")]
+    fn test_accept_admin_handover_not_unlocked() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let backstop = Address::generate(&e);
+        let new_emitter = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let backstop_token = e.register_stellar_asset_contract(bombadil.clone());
+        let backstop_token_client = MockTokenClient::new(&e, &backstop_token);
+        backstop_token_client.mint(&backstop, &(1_000_000 * SCALAR_7));
+        backstop_token_client.mint(&new_emitter, &(1_000_001 * SCALAR_7));
+        e.as_contract(&emitter, || {
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_backstop(&e, &backstop);
+            storage::set_backstop_token(&e, &backstop_token);
+        });
+
+        e.as_contract(&emitter, || {
+            execute_queue_admin_handover(&e, &new_emitter);
+        });
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345 + 31 * 24 * 60 * 60 - 1,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        e.as_contract(&emitter, || {
+            execute_accept_admin_handover(&e);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1100)")]
+    fn test_accept_admin_handover_insufficient_backstop_size() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let backstop = Address::generate(&e);
+        let new_emitter = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let backstop_token = e.register_stellar_asset_contract(bombadil.clone());
+        let backstop_token_client = MockTokenClient::new(&e, &backstop_token);
+        backstop_token_client.mint(&backstop, &(1_000_000 * SCALAR_7));
+        backstop_token_client.mint(&new_emitter, &(1_000_001 * SCALAR_7));
+        e.as_contract(&emitter, || {
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_backstop(&e, &backstop);
+            storage::set_backstop_token(&e, &backstop_token);
+        });
+
+        e.as_contract(&emitter, || {
+            execute_queue_admin_handover(&e, &new_emitter);
+        });
+        backstop_token_client.mint(&backstop, &(1 * SCALAR_7));
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345 + 31 * 24 * 60 * 60,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        e.as_contract(&emitter, || {
+            execute_accept_admin_handover(&e);
+        });
+    }
+}
@@ -1,7 +1,10 @@
 use sep_41_token::TokenClient;
 use soroban_sdk::{contracttype, panic_with_error, Address, Env};
 
-use crate::{emitter, storage, EmitterError};
+use crate::{
+    dependencies::BackstopClient, direct_manager::checkpoint_direct_total, emitter, storage,
+    EmitterError,
+};
 
 #[derive(Clone)]
 #[contracttype]
@@ -14,7 +17,11 @@ pub struct Swap {
 /// Require that the new backstop is larger than the backstop
 ///
 /// Panics otherwise
-fn is_new_backstop_is_larger(e: &Env, new_backstop: &Address, backstop: &Address) -> bool {
+pub(crate) fn is_new_backstop_is_larger(
+    e: &Env,
+    new_backstop: &Address,
+    backstop: &Address,
+) -> bool {
     let backstop_token = storage::get_backstop_token(e);
     let backstop_token_client = TokenClient::new(e, &backstop_token);
 
@@ -23,6 +30,16 @@ fn is_new_backstop_is_larger(e: &Env, new_backstop: &Address, backstop: &Address
     return new_backstop_balance > backstop_balance;
 }
 
+/// Check if the new backstop of a swap is a backstop contract for the swap's backstop token, by
+/// probing it with a read-only call. Accounts and contracts that do not implement the backstop
+/// interface fail the probe.
+fn is_compatible_backstop(e: &Env, swap: &Swap) -> bool {
+    match BackstopClient::new(e, &swap.new_backstop).try_backstop_token() {
+        Ok(Ok(backstop_token)) => backstop_token == swap.new_backstop_token,
+        _ => false,
+    }
+}
+
 /// Perform a backstop swap
 pub fn execute_queue_swap_backstop(
     e: &Env,
@@ -76,6 +93,12 @@ pub fn execute_swap_backstop(e: &Env) -> Swap {
         panic_with_error!(e, EmitterError::InsufficientBackstopSize);
     }
 
+    // emissions can only be distributed by the backstop, so an incompatible backstop would
+    // stop emissions entirely
+    if !is_compatible_backstop(e, &swap) {
+        panic_with_error!(e, EmitterError::IncompatibleBackstop);
+    }
+
     // distribute before swapping to ensure the old backstop gets their tokens
     emitter::execute_distribute(e, &backstop);
 
@@ -86,6 +109,7 @@ pub fn execute_swap_backstop(e: &Env) -> Swap {
 
     // start distribution for new backstop
     storage::set_last_distro_time(e, &swap.new_backstop, e.ledger().timestamp());
+    storage::set_direct_mark(e, &swap.new_backstop, checkpoint_direct_total(e));
 
     swap
 }
@@ -93,7 +117,11 @@ pub fn execute_swap_backstop(e: &Env) -> Swap {
 #[cfg(test)]
 mod tests {
 
-    use crate::{constants::SCALAR_7, storage, testutils::create_emitter};
+    use crate::{
+        constants::SCALAR_7,
+        storage,
+        testutils::{create_emitter, create_mock_backstop},
+    };
 
     use super::*;
     use sep_41_token::testutils::MockTokenClient;
@@ -404,11 +432,11 @@ mod tests {
         let blnd_token_client = MockTokenClient::new(&e, &blnd_token);
 
         let backstop = Address::generate(&e);
-        let new_backstop = Address::generate(&e);
 
         let backstop_token = e.register_stellar_asset_contract(bombadil.clone());
         let backstop_token_client = MockTokenClient::new(&e, &backstop_token);
         let new_backstop_token = Address::generate(&e);
+        let new_backstop = create_mock_backstop(&e, &new_backstop_token);
 
         backstop_token_client.mint(&backstop, &(1_000_000 * SCALAR_7));
         backstop_token_client.mint(&new_backstop, &(1_000_001 * SCALAR_7));
@@ -442,6 +470,110 @@ mod tests {
             assert_eq!(storage::get_last_distro_time(&e, &new_backstop), 12345);
             assert_eq!(blnd_token_client.balance(&backstop), 2345 * SCALAR_7);
             assert_eq!(blnd_token_client.balance(&new_backstop), 0);
+            assert_eq!(storage::get_distributed(&e, &backstop), 2345 * SCALAR_7);
+            assert_eq!(storage::get_distributed(&e, &new_backstop), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1117)")]
+    fn test_execute_swap_backstop_not_backstop() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 500,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let emitter = create_emitter(&e);
+
+        let blnd_token = e.register_stellar_asset_contract(emitter.clone());
+        let blnd_token_client = MockTokenClient::new(&e, &blnd_token);
+
+        let backstop = Address::generate(&e);
+
+        let backstop_token = e.register_stellar_asset_contract(bombadil.clone());
+        let backstop_token_client = MockTokenClient::new(&e, &backstop_token);
+        let new_backstop_token = Address::generate(&e);
+        let new_backstop = Address::generate(&e);
+
+        backstop_token_client.mint(&backstop, &(1_000_000 * SCALAR_7));
+        backstop_token_client.mint(&new_backstop, &(1_000_001 * SCALAR_7));
+
+        let swap = Swap {
+            new_backstop: new_backstop.clone(),
+            new_backstop_token: new_backstop_token.clone(),
+            unlock_time: 12345,
+        };
+
+        e.as_contract(&emitter, || {
+            storage::set_last_distro_time(&e, &backstop, 10000);
+            storage::set_backstop(&e, &backstop);
+            storage::set_backstop_token(&e, &backstop_token);
+            storage::set_blnd_token(&e, &blnd_token);
+            storage::set_drop_status(&e, &backstop);
+            storage::set_queued_swap(&e, &swap);
+
+            execute_swap_backstop(&e);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1117)")]
+    fn test_execute_swap_backstop_wrong_token() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 500,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let emitter = create_emitter(&e);
+
+        let blnd_token = e.register_stellar_asset_contract(emitter.clone());
+        let blnd_token_client = MockTokenClient::new(&e, &blnd_token);
+
+        let backstop = Address::generate(&e);
+
+        let backstop_token = e.register_stellar_asset_contract(bombadil.clone());
+        let backstop_token_client = MockTokenClient::new(&e, &backstop_token);
+        let new_backstop_token = Address::generate(&e);
+        let new_backstop = create_mock_backstop(&e, &backstop_token);
+
+        backstop_token_client.mint(&backstop, &(1_000_000 * SCALAR_7));
+        backstop_token_client.mint(&new_backstop, &(1_000_001 * SCALAR_7));
+
+        let swap = Swap {
+            new_backstop: new_backstop.clone(),
+            new_backstop_token: new_backstop_token.clone(),
+            unlock_time: 12345,
+        };
+
+        e.as_contract(&emitter, || {
+            storage::set_last_distro_time(&e, &backstop, 10000);
+            storage::set_backstop(&e, &backstop);
+            storage::set_backstop_token(&e, &backstop_token);
+            storage::set_blnd_token(&e, &blnd_token);
+            storage::set_drop_status(&e, &backstop);
+            storage::set_queued_swap(&e, &swap);
+
+            execute_swap_backstop(&e);
         });
     }
 
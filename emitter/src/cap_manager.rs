@@ -0,0 +1,274 @@
+use sep_41_token::StellarAssetClient;
+use soroban_sdk::{contracttype, panic_with_error, Address, Env, Symbol};
+
+use crate::{dependencies::BuybackClient, pause_manager::require_admin, storage, EmitterError};
+
+#[derive(Clone)]
+#[contracttype]
+pub struct EmissionCap {
+    pub max_supply: i128, // the maximum BLND supply the emitter will mint up to
+    pub buyback: Address, // the buyback module notified of emissions past the cap
+}
+
+/// Set the BLND emission cap and seed the tracked BLND supply the cap is checked against. Once
+/// set, the cap can be lowered but never raised or removed, and the tracked supply can only grow,
+/// so the supply schedule it enforces cannot be loosened.
+///
+/// ### Arguments
+/// * `max_supply` - The maximum BLND supply the emitter will mint up to
+/// * `supply` - The current BLND supply, including BLND minted before supply tracking began
+/// * `buyback` - The buyback module notified of emissions past the cap
+///
+/// ### Panics
+/// If the caller is not the pause registry admin, the max supply is negative, the max supply
+/// is higher than the current cap, or the supply is lower than the tracked supply
+pub fn execute_set_emission_cap(
+    e: &Env,
+    max_supply: i128,
+    supply: i128,
+    buyback: &Address,
+) -> EmissionCap {
+    require_admin(e);
+    if max_supply < 0 || supply < storage::get_supply(e) {
+        panic_with_error!(e, EmitterError::BadEmissionCap);
+    }
+    if let Some(cur_cap) = storage::get_emission_cap(e) {
+        if max_supply > cur_cap.max_supply {
+            panic_with_error!(e, EmitterError::BadEmissionCap);
+        }
+    }
+
+    let cap = EmissionCap {
+        max_supply,
+        buyback: buyback.clone(),
+    };
+    storage::set_emission_cap(e, &cap);
+    storage::set_supply(e, supply);
+    cap
+}
+
+/// Mint BLND emissions to an address, up to the emission cap. Emissions past the cap are not
+/// minted, and the buyback module is notified so it can source them for `to` instead. A failed
+/// notification does not block the emissions that were minted, and is published as an event so
+/// the capped emissions can be sourced manually.
+///
+/// Returns the amount of BLND minted
+///
+/// ### Arguments
+/// * `to` - The address receiving the emissions
+/// * `amount` - The amount of emissions
+pub(crate) fn mint_emissions(e: &Env, to: &Address, amount: i128) -> i128 {
+    let supply = storage::get_supply(e);
+    let mut to_mint = amount;
+    let cap = storage::get_emission_cap(e);
+    if let Some(cap) = &cap {
+        to_mint = amount.min(cap.max_supply - supply).max(0);
+    }
+
+    if to_mint > 0 {
+        let blnd_id = storage::get_blnd_token(e);
+        StellarAssetClient::new(e, &blnd_id).mint(to, &to_mint);
+        storage::set_supply(e, supply + to_mint);
+    }
+    if let Some(cap) = cap {
+        let capped = amount - to_mint;
+        if capped > 0 {
+            let result = BuybackClient::new(e, &cap.buyback).try_on_capped_emissions(to, &capped);
+            if !matches!(result, Ok(Ok(()))) {
+                e.events().publish(
+                    (Symbol::new(e, "buyback_failed"), to.clone()),
+                    (cap.buyback, capped),
+                );
+            }
+        }
+    }
+    to_mint
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testutils::{
+        create_emitter, create_mock_backstop, create_mock_buyback, create_pause_registry,
+    };
+
+    use super::*;
+    use sep_41_token::testutils::MockTokenClient;
+    use soroban_sdk::{
+        testutils::{Address as _, Events},
+        vec, IntoVal,
+    };
+
+    #[test]
+    fn test_set_emission_cap() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let emitter = create_emitter(&e);
+        let bombadil = Address::generate(&e);
+        let buyback = Address::generate(&e);
+        create_pause_registry(&e, &emitter, &bombadil);
+
+        e.as_contract(&emitter, || {
+            assert!(storage::get_emission_cap(&e).is_none());
+            let cap =
+                execute_set_emission_cap(&e, 100_000_000_0000000, 60_000_000_0000000, &buyback);
+            assert_eq!(storage::get_supply(&e), 60_000_000_0000000);
+            assert_eq!(cap.max_supply, 100_000_000_0000000);
+            assert_eq!(cap.buyback, buyback);
+
+            // the cap can be lowered
+            let new_buyback = Address::generate(&e);
+            execute_set_emission_cap(&e, 90_000_000_0000000, 60_000_000_0000000, &new_buyback);
+            let cap = storage::get_emission_cap(&e).unwrap();
+            assert_eq!(cap.max_supply, 90_000_000_0000000);
+            assert_eq!(cap.buyback, new_buyback);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1116)")]
+    fn test_set_emission_cap_cannot_raise() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let emitter = create_emitter(&e);
+        let bombadil = Address::generate(&e);
+        let buyback = Address::generate(&e);
+        create_pause_registry(&e, &emitter, &bombadil);
+
+        e.as_contract(&emitter, || {
+            execute_set_emission_cap(&e, 100_000_000_0000000, 0, &buyback);
+            execute_set_emission_cap(&e, 100_000_000_0000001, 0, &buyback);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1116)")]
+    fn test_set_emission_cap_cannot_lower_supply() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let emitter = create_emitter(&e);
+        let bombadil = Address::generate(&e);
+        let buyback = Address::generate(&e);
+        create_pause_registry(&e, &emitter, &bombadil);
+
+        e.as_contract(&emitter, || {
+            storage::set_supply(&e, 1000_0000000);
+            execute_set_emission_cap(&e, 100_000_000_0000000, 999_0000000, &buyback);
+        });
+    }
+
+    #[test]
+    fn test_mint_emissions() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let emitter = create_emitter(&e);
+        let backstop = Address::generate(&e);
+        let (buyback, buyback_client) = create_mock_buyback(&e);
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let blnd_client = MockTokenClient::new(&e, &blnd_id);
+
+        e.as_contract(&emitter, || {
+            storage::set_blnd_token(&e, &blnd_id);
+
+            // no cap, everything is minted
+            let minted = mint_emissions(&e, &backstop, 600_0000000);
+            assert_eq!(minted, 600_0000000);
+            assert_eq!(storage::get_supply(&e), 600_0000000);
+
+            // the cap is hit part way through
+            storage::set_emission_cap(
+                &e,
+                &EmissionCap {
+                    max_supply: 1000_0000000,
+                    buyback: buyback.clone(),
+                },
+            );
+            let minted = mint_emissions(&e, &backstop, 500_0000000);
+            assert_eq!(minted, 400_0000000);
+            assert_eq!(storage::get_supply(&e), 1000_0000000);
+
+            // the cap is already hit
+            let minted = mint_emissions(&e, &backstop, 50_0000000);
+            assert_eq!(minted, 0);
+            assert_eq!(storage::get_supply(&e), 1000_0000000);
+        });
+        assert_eq!(blnd_client.balance(&backstop), 1000_0000000);
+        assert_eq!(buyback_client.capped(&backstop), 150_0000000);
+    }
+
+    #[test]
+    fn test_mint_emissions_buyback_fails() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let emitter = create_emitter(&e);
+        let backstop = Address::generate(&e);
+        // a contract that does not implement the buyback interface
+        let buyback = create_mock_backstop(&e, &Address::generate(&e));
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let blnd_client = MockTokenClient::new(&e, &blnd_id);
+
+        e.as_contract(&emitter, || {
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_supply(&e, 900_0000000);
+            storage::set_emission_cap(
+                &e,
+                &EmissionCap {
+                    max_supply: 1000_0000000,
+                    buyback: buyback.clone(),
+                },
+            );
+
+            let minted = mint_emissions(&e, &backstop, 150_0000000);
+            assert_eq!(minted, 100_0000000);
+            assert_eq!(storage::get_supply(&e), 1000_0000000);
+
+            let event = vec![&e, e.events().all().last_unchecked()];
+            assert_eq!(
+                event,
+                vec![
+                    &e,
+                    (
+                        emitter.clone(),
+                        (Symbol::new(&e, "buyback_failed"), backstop.clone()).into_val(&e),
+                        (buyback.clone(), 50_0000000i128).into_val(&e)
+                    )
+                ]
+            );
+        });
+        assert_eq!(blnd_client.balance(&backstop), 100_0000000);
+    }
+
+    #[test]
+    fn test_mint_emissions_cap_below_supply() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let emitter = create_emitter(&e);
+        let backstop = Address::generate(&e);
+        let (buyback, buyback_client) = create_mock_buyback(&e);
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let blnd_client = MockTokenClient::new(&e, &blnd_id);
+
+        e.as_contract(&emitter, || {
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_supply(&e, 1200_0000000);
+            storage::set_emission_cap(
+                &e,
+                &EmissionCap {
+                    max_supply: 1000_0000000,
+                    buyback: buyback.clone(),
+                },
+            );
+
+            let minted = mint_emissions(&e, &backstop, 100_0000000);
+            assert_eq!(minted, 0);
+            assert_eq!(storage::get_supply(&e), 1200_0000000);
+        });
+        assert_eq!(blnd_client.balance(&backstop), 0);
+        assert_eq!(buyback_client.capped(&backstop), 100_0000000);
+    }
+}
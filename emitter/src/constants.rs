@@ -1,2 +1,17 @@
 /// Fixed-point scalar for 7 decimal numbers
 pub const SCALAR_7: i128 = 1_0000000;
+
+/// The maximum total share of the base emission rate that can be streamed directly to pools
+pub const MAX_DIRECT_SHARE: u64 = 0_5000000;
+
+/// The time a direct emissions configuration must be queued before it can be applied
+pub const DIRECT_EMISSIONS_TIMELOCK: u64 = 31 * 24 * 60 * 60;
+
+/// The time a BLND admin handover must be queued before it can be accepted
+pub const BLND_ADMIN_TIMELOCK: u64 = 31 * 24 * 60 * 60;
+
+/// The maximum time emissions can be paused by the guardian at once
+pub const MAX_EMISSION_PAUSE: u64 = 7 * 24 * 60 * 60;
+
+/// The time after an emission pause ends before the guardian can pause emissions again
+pub const EMISSION_PAUSE_COOLDOWN: u64 = 7 * 24 * 60 * 60;
@@ -1,6 +1,9 @@
-use crate::{backstop_manager, emitter, errors::EmitterError, storage};
+use crate::{
+    admin_manager, backstop_manager, cap_manager, dependencies::PauseRegistryClient,
+    direct_manager, emitter, errors::EmitterError, pause_manager, storage,
+};
 use soroban_sdk::{
-    contract, contractclient, contractimpl, panic_with_error, Address, Env, Symbol, Vec,
+    contract, contractclient, contractimpl, panic_with_error, Address, Env, Map, Symbol, Vec,
 };
 
 /// ### Emitter
@@ -17,11 +20,21 @@ pub trait Emitter {
     /// * `blnd_token` - The Blend token Address the Emitter will distribute
     /// * `backstop` - The backstop module address to emit to
     /// * `backstop_token` - The token the backstop takes deposits in
-    fn initialize(e: Env, blnd_token: Address, backstop: Address, backstop_token: Address);
+    /// * `pause_registry` - The pause registry contract consulted before state-mutating actions
+    fn initialize(
+        e: Env,
+        blnd_token: Address,
+        backstop: Address,
+        backstop_token: Address,
+        pause_registry: Address,
+    );
 
     /// Distributes BLND tokens to the listed backstop module
     ///
     /// Returns the amount of BLND tokens distributed
+    ///
+    /// ### Errors
+    /// If emissions are paused by the guardian
     fn distribute(e: Env) -> i128;
 
     /// Fetch the last time the Emitter distributed to the backstop module
@@ -30,6 +43,13 @@ pub trait Emitter {
     /// * `backstop` - The backstop module Address ID
     fn get_last_distro(e: Env, backstop_id: Address) -> u64;
 
+    /// Fetch the cumulative amount of BLND the Emitter has distributed to a backstop module,
+    /// including distributions made before the backstop was swapped out
+    ///
+    /// ### Arguments
+    /// * `backstop` - The backstop module Address ID
+    fn get_distributed(e: Env, backstop_id: Address) -> i128;
+
     /// Fetch the current backstop
     fn get_backstop(e: Env) -> Address;
 
@@ -47,6 +67,9 @@ pub trait Emitter {
     /// Fetch the queued backstop swap, or None if nothing is queued.
     fn get_queued_swap(e: Env) -> Option<backstop_manager::Swap>;
 
+    /// Fetch the time the queued backstop swap can be executed, or None if nothing is queued.
+    fn get_swap_unlock_time(e: Env) -> Option<u64>;
+
     /// Verifies that a queued swap still meets the requirements to be executed. If not,
     /// the queued swap is cancelled and must be recreated.
     ///
@@ -58,9 +81,128 @@ pub trait Emitter {
     ///
     /// ### Errors
     /// If the input contract does not have more backstop deposits than the listed backstop module,
-    /// or if the queued swap has not been unlocked.
+    /// if the queued swap has not been unlocked, or if the new backstop is not a backstop contract
+    /// for the queued backstop token.
     fn swap_backstop(e: Env);
 
+    /// (Backstop only) Queues up a new direct emissions configuration. Pools in the configuration
+    /// are streamed their share of the base emission rate directly, and the streamed emissions are
+    /// deducted from the emissions distributed to the backstop module.
+    ///
+    /// ### Arguments
+    /// * `config` - The map of pool address to share of the base emission rate, in 7 decimals
+    ///
+    /// ### Errors
+    /// If a configuration is already queued, a share is zero, or the total share exceeds the maximum
+    fn queue_direct_emissions(e: Env, config: Map<Address, u64>);
+
+    /// Fetch the queued direct emissions configuration, or None if nothing is queued.
+    fn get_queued_direct_emissions(e: Env) -> Option<direct_manager::DirectEmissionsQueue>;
+
+    /// (Backstop only) Cancels the queued direct emissions configuration
+    ///
+    /// ### Errors
+    /// If no configuration is queued
+    fn cancel_direct_emissions(e: Env);
+
+    /// Applies the queued direct emissions configuration
+    ///
+    /// ### Errors
+    /// If no configuration is queued or the queued configuration has not been unlocked
+    fn set_direct_emissions(e: Env);
+
+    /// Fetch the current direct emissions configuration
+    fn get_direct_emissions(e: Env) -> Map<Address, u64>;
+
+    /// Queues up a handover of the BLND token admin to a new address that holds more backstop
+    /// tokens than the backstop. The handover must be accepted by the new admin after the
+    /// timelock expires.
+    ///
+    /// ### Arguments
+    /// * `new_admin` - The address that will become the BLND token admin
+    ///
+    /// ### Errors
+    /// If a handover is already queued or the new admin does not hold more backstop tokens than
+    /// the backstop
+    fn queue_blnd_admin(e: Env, new_admin: Address);
+
+    /// Fetch the queued BLND admin handover, or None if nothing is queued.
+    fn get_queued_blnd_admin(e: Env) -> Option<admin_manager::AdminHandover>;
+
+    /// Cancels the queued BLND admin handover if the new admin no longer holds more backstop
+    /// tokens than the backstop
+    ///
+    /// ### Errors
+    /// If no handover is queued or the new admin still holds more backstop tokens than the
+    /// backstop
+    fn cancel_blnd_admin(e: Env);
+
+    /// (New admin only) Accepts the queued BLND admin handover, transferring the BLND token admin
+    /// to the new admin
+    ///
+    /// ### Errors
+    /// If no handover is queued, the queued handover has not been unlocked, or the new admin no
+    /// longer holds more backstop tokens than the backstop
+    fn accept_blnd_admin(e: Env);
+
+    /// (Pause registry admin only) Sets the guardian that can pause emissions
+    ///
+    /// ### Arguments
+    /// * `guardian` - The address that can pause emissions
+    fn set_guardian(e: Env, guardian: Address);
+
+    /// Fetch the guardian that can pause emissions, or None if no guardian is set
+    fn get_guardian(e: Env) -> Option<Address>;
+
+    /// (Guardian only) Pauses the distribution of emissions to the backstop and pools for up
+    /// to 7 days. Emissions do not accrue while paused, so the time paused is never
+    /// distributed.
+    ///
+    /// ### Errors
+    /// If no guardian is set, or the previous pause ended less than 7 days ago
+    fn pause_emissions(e: Env);
+
+    /// (Guardian only) Ends the active emission pause early
+    ///
+    /// ### Errors
+    /// If no guardian is set or emissions are not paused
+    fn unpause_emissions(e: Env);
+
+    /// Fetch the most recent emission pause, or None if emissions have never been paused
+    fn get_emission_pause(e: Env) -> Option<pause_manager::EmissionPause>;
+
+    /// (Pause registry admin only) Sets the cap on the BLND supply the emitter will mint. Emissions
+    /// past the cap are not minted, and the buyback module is notified of them instead. Once set,
+    /// the cap can be lowered but never raised.
+    ///
+    /// The cap is checked against the BLND supply tracked by the emitter. BLND does not expose its
+    /// total supply, so the tracked supply is seeded with the current supply, including any BLND
+    /// minted before supply tracking began. The tracked supply can only grow.
+    ///
+    /// ### Arguments
+    /// * `max_supply` - The maximum BLND supply the emitter will mint up to
+    /// * `supply` - The current BLND supply
+    /// * `buyback` - The buyback module notified of emissions past the cap
+    ///
+    /// ### Errors
+    /// If the max supply is negative or higher than the current cap, or the supply is lower than
+    /// the tracked supply
+    fn set_emission_cap(e: Env, max_supply: i128, supply: i128, buyback: Address);
+
+    /// Fetch the BLND emission cap, or None if no cap is set
+    fn get_emission_cap(e: Env) -> Option<cap_manager::EmissionCap>;
+
+    /// Fetch the BLND supply tracked by the emitter for the emission cap
+    fn get_supply(e: Env) -> i128;
+
+    /// (Pool only) Distributes the BLND tokens streamed directly to the pool
+    ///
+    /// Returns the amount of BLND tokens distributed
+    ///
+    /// ### Arguments
+    /// * `pool` - The pool Address to distribute to
+    fn distribute_direct(e: Env, pool: Address) -> i128;
+
     /// (Backstop only) Distributes initial BLND after a new backstop is set
     ///
     /// ### Arguments
@@ -74,7 +216,13 @@ pub trait Emitter {
 
 #[contractimpl]
 impl Emitter for EmitterContract {
-    fn initialize(e: Env, blnd_token: Address, backstop: Address, backstop_token: Address) {
+    fn initialize(
+        e: Env,
+        blnd_token: Address,
+        backstop: Address,
+        backstop_token: Address,
+        pause_registry: Address,
+    ) {
         storage::extend_instance(&e);
         if storage::get_is_init(&e) {
             panic_with_error!(&e, EmitterError::AlreadyInitializedError)
@@ -83,6 +231,7 @@ impl Emitter for EmitterContract {
         storage::set_blnd_token(&e, &blnd_token);
         storage::set_backstop(&e, &backstop);
         storage::set_backstop_token(&e, &backstop_token);
+        storage::set_pause_registry(&e, &pause_registry);
         storage::set_last_distro_time(&e, &backstop, e.ledger().timestamp());
 
         storage::set_is_init(&e);
@@ -90,6 +239,7 @@ impl Emitter for EmitterContract {
 
     fn distribute(e: Env) -> i128 {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         let backstop_address = storage::get_backstop(&e);
 
         let distribution_amount = emitter::execute_distribute(&e, &backstop_address);
@@ -105,12 +255,17 @@ impl Emitter for EmitterContract {
         storage::get_last_distro_time(&e, &backstop_id)
     }
 
+    fn get_distributed(e: Env, backstop_id: Address) -> i128 {
+        storage::get_distributed(&e, &backstop_id)
+    }
+
     fn get_backstop(e: Env) -> Address {
         storage::get_backstop(&e)
     }
 
     fn queue_swap_backstop(e: Env, new_backstop: Address, new_backstop_token: Address) {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         let swap =
             backstop_manager::execute_queue_swap_backstop(&e, &new_backstop, &new_backstop_token);
 
@@ -121,8 +276,13 @@ impl Emitter for EmitterContract {
         storage::get_queued_swap(&e)
     }
 
+    fn get_swap_unlock_time(e: Env) -> Option<u64> {
+        storage::get_queued_swap(&e).map(|swap| swap.unlock_time)
+    }
+
     fn cancel_swap_backstop(e: Env) {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         let swap = backstop_manager::execute_cancel_swap_backstop(&e);
 
         e.events().publish((Symbol::new(&e, "del_swap"),), swap);
@@ -130,15 +290,151 @@ impl Emitter for EmitterContract {
 
     fn swap_backstop(e: Env) {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         let swap = backstop_manager::execute_swap_backstop(&e);
 
         e.events().publish((Symbol::new(&e, "swap"),), swap);
     }
 
+    fn queue_direct_emissions(e: Env, config: Map<Address, u64>) {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        let queue = direct_manager::execute_queue_direct_emissions(&e, &config);
+
+        e.events().publish((Symbol::new(&e, "q_direct"),), queue);
+    }
+
+    fn get_queued_direct_emissions(e: Env) -> Option<direct_manager::DirectEmissionsQueue> {
+        storage::get_queued_direct_emissions(&e)
+    }
+
+    fn cancel_direct_emissions(e: Env) {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        let queue = direct_manager::execute_cancel_direct_emissions(&e);
+
+        e.events().publish((Symbol::new(&e, "del_direct"),), queue);
+    }
+
+    fn set_direct_emissions(e: Env) {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        let config = direct_manager::execute_set_direct_emissions(&e);
+
+        e.events().publish((Symbol::new(&e, "set_direct"),), config);
+    }
+
+    fn get_direct_emissions(e: Env) -> Map<Address, u64> {
+        storage::get_direct_emissions(&e)
+    }
+
+    fn queue_blnd_admin(e: Env, new_admin: Address) {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        let handover = admin_manager::execute_queue_admin_handover(&e, &new_admin);
+
+        e.events().publish((Symbol::new(&e, "q_admin"),), handover);
+    }
+
+    fn get_queued_blnd_admin(e: Env) -> Option<admin_manager::AdminHandover> {
+        storage::get_queued_admin(&e)
+    }
+
+    fn cancel_blnd_admin(e: Env) {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        let handover = admin_manager::execute_cancel_admin_handover(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "del_admin"),), handover);
+    }
+
+    fn accept_blnd_admin(e: Env) {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        let handover = admin_manager::execute_accept_admin_handover(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_admin"),), handover);
+    }
+
+    fn set_guardian(e: Env, guardian: Address) {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        pause_manager::execute_set_guardian(&e, &guardian);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_guardian"),), guardian);
+    }
+
+    fn get_guardian(e: Env) -> Option<Address> {
+        storage::get_guardian(&e)
+    }
+
+    fn pause_emissions(e: Env) {
+        storage::extend_instance(&e);
+        let pause = pause_manager::execute_pause_emissions(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "pause_emissions"),), pause);
+    }
+
+    fn unpause_emissions(e: Env) {
+        storage::extend_instance(&e);
+        let pause = pause_manager::execute_unpause_emissions(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "unpause_emissions"),), pause);
+    }
+
+    fn get_emission_pause(e: Env) -> Option<pause_manager::EmissionPause> {
+        storage::get_emission_pause(&e)
+    }
+
+    fn set_emission_cap(e: Env, max_supply: i128, supply: i128, buyback: Address) {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        let cap = cap_manager::execute_set_emission_cap(&e, max_supply, supply, &buyback);
+
+        e.events().publish((Symbol::new(&e, "set_cap"),), cap);
+    }
+
+    fn get_emission_cap(e: Env) -> Option<cap_manager::EmissionCap> {
+        storage::get_emission_cap(&e)
+    }
+
+    fn get_supply(e: Env) -> i128 {
+        storage::get_supply(&e)
+    }
+
+    fn distribute_direct(e: Env, pool: Address) -> i128 {
+        storage::extend_instance(&e);
+        require_not_paused(&e);
+        let distribution_amount = direct_manager::execute_distribute_direct(&e, &pool);
+
+        e.events().publish(
+            (Symbol::new(&e, "distribute_direct"),),
+            (pool, distribution_amount),
+        );
+        distribution_amount
+    }
+
     fn drop(e: Env, list: Vec<(Address, i128)>) {
         storage::extend_instance(&e);
+        require_not_paused(&e);
         emitter::execute_drop(&e, &list);
 
         e.events().publish((Symbol::new(&e, "drop"),), list);
     }
 }
+
+/// Require that the protocol is not paused by the pause registry
+///
+/// ### Errors
+/// If the pause registry reports the protocol as paused
+fn require_not_paused(e: &Env) {
+    let pause_registry = storage::get_pause_registry(e);
+    if PauseRegistryClient::new(e, &pause_registry).is_paused() {
+        panic_with_error!(e, EmitterError::ContractPaused);
+    }
+}
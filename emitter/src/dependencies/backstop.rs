@@ -0,0 +1,9 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// The interface the emitter requires of a backstop it distributes emissions to
+#[allow(dead_code)]
+#[contractclient(name = "BackstopClient")]
+pub trait BackstopModule {
+    /// Fetch the token deposited into the backstop
+    fn backstop_token(e: Env) -> Address;
+}
@@ -0,0 +1,15 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// The interface of the buyback module notified of emissions past the BLND emission cap
+#[allow(dead_code)]
+#[contractclient(name = "BuybackClient")]
+pub trait BuybackModule {
+    /// Notify the buyback module of emissions that were not minted because the emission cap was
+    /// reached. The module is responsible for sourcing the emissions for `to`, like buying back
+    /// BLND with protocol revenue.
+    ///
+    /// ### Arguments
+    /// * `to` - The address the emissions were for
+    /// * `amount` - The amount of emissions that were not minted
+    fn on_capped_emissions(e: Env, to: Address, amount: i128);
+}
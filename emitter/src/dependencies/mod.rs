@@ -0,0 +1,10 @@
+mod backstop;
+pub use backstop::BackstopClient;
+
+mod buyback;
+pub use buyback::BuybackClient;
+
+mod pause_registry;
+pub use pause_registry::Client as PauseRegistryClient;
+#[cfg(test)]
+pub use pause_registry::WASM as PAUSE_REGISTRY_WASM;
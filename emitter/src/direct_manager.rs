@@ -0,0 +1,470 @@
+use soroban_sdk::{contracttype, panic_with_error, Address, Env, Map};
+
+use crate::{
+    cap_manager::mint_emissions,
+    constants::{DIRECT_EMISSIONS_TIMELOCK, MAX_DIRECT_SHARE},
+    pause_manager::{calc_active_seconds, require_emissions_active},
+    storage::{self, DirectEmissionData},
+    EmitterError,
+};
+
+#[derive(Clone)]
+#[contracttype]
+pub struct DirectEmissionsQueue {
+    pub config: Map<Address, u64>,
+    pub unlock_time: u64,
+}
+
+/// Queue a new direct emissions configuration
+///
+/// ### Arguments
+/// * `config` - The map of pool address to share of the base emission rate
+///
+/// ### Panics
+/// If the caller is not the backstop, a configuration is already queued, or the configuration
+/// is invalid
+pub fn execute_queue_direct_emissions(e: &Env, config: &Map<Address, u64>) -> DirectEmissionsQueue {
+    storage::get_backstop(e).require_auth();
+    if storage::get_queued_direct_emissions(e).is_some() {
+        panic_with_error!(e, EmitterError::DirectEmissionsAlreadyQueued);
+    }
+
+    let mut total_share: u64 = 0;
+    for (_, share) in config.iter() {
+        if share == 0 {
+            panic_with_error!(e, EmitterError::BadDirectEmissions);
+        }
+        total_share += share;
+    }
+    if total_share > MAX_DIRECT_SHARE {
+        panic_with_error!(e, EmitterError::BadDirectEmissions);
+    }
+
+    let queue = DirectEmissionsQueue {
+        config: config.clone(),
+        unlock_time: e.ledger().timestamp() + DIRECT_EMISSIONS_TIMELOCK,
+    };
+    storage::set_queued_direct_emissions(e, &queue);
+    queue
+}
+
+/// Cancel the queued direct emissions configuration
+///
+/// ### Panics
+/// If the caller is not the backstop or no configuration is queued
+pub fn execute_cancel_direct_emissions(e: &Env) -> DirectEmissionsQueue {
+    storage::get_backstop(e).require_auth();
+    let queue = storage::get_queued_direct_emissions(e)
+        .unwrap_or_else(|| panic_with_error!(e, EmitterError::DirectEmissionsNotQueued));
+
+    storage::del_queued_direct_emissions(e);
+    queue
+}
+
+/// Apply the queued direct emissions configuration if it has been unlocked. Emissions accrued
+/// under the previous configuration are kept for each pool until they are distributed.
+///
+/// ### Panics
+/// If no configuration is queued or the queued configuration has not been unlocked
+pub fn execute_set_direct_emissions(e: &Env) -> Map<Address, u64> {
+    let queue = storage::get_queued_direct_emissions(e)
+        .unwrap_or_else(|| panic_with_error!(e, EmitterError::DirectEmissionsNotQueued));
+
+    if queue.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, EmitterError::DirectEmissionsNotUnlocked);
+    }
+
+    // checkpoint every pool and the total under the old config before the shares change
+    checkpoint_direct_emissions(e);
+    let cur_config = storage::get_direct_emissions(e);
+    for (pool, _) in queue.config.iter() {
+        if !cur_config.contains_key(pool.clone()) {
+            let data = accrue_direct_emissions(e, &pool, 0);
+            storage::set_direct_emis_data(e, &pool, &data);
+        }
+    }
+
+    storage::del_queued_direct_emissions(e);
+    storage::set_direct_emissions(e, &queue.config);
+    queue.config
+}
+
+/// Mint the direct emissions accrued to a pool. Emissions past the emission cap are not minted,
+/// and are passed to the buyback module instead.
+///
+/// Returns the amount of BLND tokens minted to the pool
+///
+/// ### Arguments
+/// * `pool` - The pool address
+///
+/// ### Panics
+/// If the pool has not authorized the call or emissions are paused
+pub fn execute_distribute_direct(e: &Env, pool: &Address) -> i128 {
+    pool.require_auth();
+    require_emissions_active(e);
+
+    let share = storage::get_direct_emissions(e)
+        .get(pool.clone())
+        .unwrap_or(0);
+    let mut data = accrue_direct_emissions(e, pool, share);
+    let distribution_amount = data.accrued;
+    data.accrued = 0;
+    storage::set_direct_emis_data(e, pool, &data);
+
+    if distribution_amount > 0 {
+        mint_emissions(e, pool, distribution_amount)
+    } else {
+        0
+    }
+}
+
+/// Accrue the cumulative direct emissions of all pools up to the current timestamp. The direct
+/// emissions are deducted from what is distributed to the backstop, so the total emission rate
+/// stays at the base emission rate.
+///
+/// Returns the cumulative direct emissions
+pub(crate) fn checkpoint_direct_total(e: &Env) -> i128 {
+    let timestamp = e.ledger().timestamp();
+    let total = match storage::get_direct_total(e) {
+        Some(data) => {
+            let mut total_share: i128 = 0;
+            for (_, share) in storage::get_direct_emissions(e).iter() {
+                total_share += share as i128;
+            }
+            data.accrued + (calc_active_seconds(e, data.last_time) as i128) * total_share
+        }
+        None => 0,
+    };
+    storage::set_direct_total(
+        e,
+        &DirectEmissionData {
+            last_time: timestamp,
+            accrued: total,
+        },
+    );
+    total
+}
+
+/// Checkpoint the direct emissions total and every configured pool's direct emissions up to the
+/// current timestamp
+pub(crate) fn checkpoint_direct_emissions(e: &Env) {
+    checkpoint_direct_total(e);
+    for (pool, share) in storage::get_direct_emissions(e).iter() {
+        let data = accrue_direct_emissions(e, &pool, share);
+        storage::set_direct_emis_data(e, &pool, &data);
+    }
+}
+
+/// Accrue a pool's direct emissions up to the current timestamp at the given share
+fn accrue_direct_emissions(e: &Env, pool: &Address, share: u64) -> DirectEmissionData {
+    let timestamp = e.ledger().timestamp();
+    match storage::get_direct_emis_data(e, pool) {
+        Some(data) => DirectEmissionData {
+            last_time: timestamp,
+            // the base emission rate is 1 token per second, so a share of it is emitted per second
+            accrued: data.accrued
+                + (calc_active_seconds(e, data.last_time) as i128) * (share as i128),
+        },
+        None => DirectEmissionData {
+            last_time: timestamp,
+            accrued: 0,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::{constants::SCALAR_7, storage, testutils::create_emitter};
+
+    use super::*;
+    use sep_41_token::testutils::MockTokenClient;
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+    };
+
+    /********** execute_queue_direct_emissions **********/
+
+    #[test]
+    fn test_queue_direct_emissions() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let backstop = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        e.as_contract(&emitter, || storage::set_backstop(&e, &backstop));
+        let pool_1 = Address::generate(&e);
+        let pool_2 = Address::generate(&e);
+        let config = map![&e, (pool_1, 0_3000000), (pool_2, 0_2000000)];
+
+        e.as_contract(&emitter, || {
+            let queue = execute_queue_direct_emissions(&e, &config);
+            assert_eq!(queue.config, config);
+            assert_eq!(queue.unlock_time, 12345 + 31 * 24 * 60 * 60);
+
+            let stored_queue = storage::get_queued_direct_emissions(&e).unwrap();
+            assert_eq!(stored_queue.config, config);
+            assert_eq!(stored_queue.unlock_time, queue.unlock_time);
+            assert_eq!(storage::get_direct_emissions(&e).len(), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1107)")]
+    fn test_queue_direct_emissions_over_max_share() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        e.as_contract(&emitter, || storage::set_backstop(&e, &backstop));
+        let pool_1 = Address::generate(&e);
+        let pool_2 = Address::generate(&e);
+        let config = map![&e, (pool_1, 0_3000000), (pool_2, 0_2000001)];
+
+        e.as_contract(&emitter, || {
+            execute_queue_direct_emissions(&e, &config);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1107)")]
+    fn test_queue_direct_emissions_zero_share() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        e.as_contract(&emitter, || storage::set_backstop(&e, &backstop));
+        let pool_1 = Address::generate(&e);
+        let config = map![&e, (pool_1, 0)];
+
+        e.as_contract(&emitter, || {
+            execute_queue_direct_emissions(&e, &config);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1109)")]
+    fn test_queue_direct_emissions_already_queued() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        e.as_contract(&emitter, || storage::set_backstop(&e, &backstop));
+        let pool_1 = Address::generate(&e);
+        let config = map![&e, (pool_1, 0_3000000)];
+
+        e.as_contract(&emitter, || {
+            execute_queue_direct_emissions(&e, &config);
+        });
+        e.as_contract(&emitter, || {
+            execute_queue_direct_emissions(&e, &config);
+        });
+    }
+
+    /********** execute_cancel_direct_emissions **********/
+
+    #[test]
+    fn test_cancel_direct_emissions() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        e.as_contract(&emitter, || storage::set_backstop(&e, &backstop));
+        let pool_1 = Address::generate(&e);
+        let config = map![&e, (pool_1, 0_3000000)];
+
+        e.as_contract(&emitter, || {
+            execute_queue_direct_emissions(&e, &config);
+        });
+        e.as_contract(&emitter, || {
+            let queue = execute_cancel_direct_emissions(&e);
+            assert_eq!(queue.config, config);
+            assert!(storage::get_queued_direct_emissions(&e).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1108)")]
+    fn test_cancel_direct_emissions_not_queued() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let backstop = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        e.as_contract(&emitter, || storage::set_backstop(&e, &backstop));
+
+        e.as_contract(&emitter, || {
+            execute_cancel_direct_emissions(&e);
+        });
+    }
+
+    /********** execute_set_direct_emissions **********/
+
+    #[test]
+    fn test_set_direct_emissions() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let emitter = create_emitter(&e);
+        let pool_1 = Address::generate(&e);
+        let pool_2 = Address::generate(&e);
+        let new_config = map![&e, (pool_1.clone(), 0_1000000), (pool_2.clone(), 0_3000000)];
+
+        e.as_contract(&emitter, || {
+            storage::set_direct_emissions(&e, &map![&e, (pool_1.clone(), 0_2000000)]);
+            storage::set_direct_emis_data(
+                &e,
+                &pool_1,
+                &DirectEmissionData {
+                    last_time: 10000,
+                    accrued: 1_0000000,
+                },
+            );
+            storage::set_direct_total(
+                &e,
+                &DirectEmissionData {
+                    last_time: 10000,
+                    accrued: 5_0000000,
+                },
+            );
+            storage::set_queued_direct_emissions(
+                &e,
+                &DirectEmissionsQueue {
+                    config: new_config.clone(),
+                    unlock_time: 12345,
+                },
+            );
+
+            let result = execute_set_direct_emissions(&e);
+            assert_eq!(result, new_config);
+            assert_eq!(storage::get_direct_emissions(&e), new_config);
+            assert!(storage::get_queued_direct_emissions(&e).is_none());
+
+            // pool 1 accrues under the old share
+            let pool_1_data = storage::get_direct_emis_data(&e, &pool_1).unwrap();
+            assert_eq!(pool_1_data.last_time, 12345);
+            assert_eq!(pool_1_data.accrued, 1_0000000 + 2345 * 0_2000000);
+
+            // pool 2 starts accruing now
+            let pool_2_data = storage::get_direct_emis_data(&e, &pool_2).unwrap();
+            assert_eq!(pool_2_data.last_time, 12345);
+            assert_eq!(pool_2_data.accrued, 0);
+
+            // the total accrues under the old shares
+            let total = storage::get_direct_total(&e).unwrap();
+            assert_eq!(total.last_time, 12345);
+            assert_eq!(total.accrued, 5_0000000 + 2345 * 0_2000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1110)")]
+    fn test_set_direct_emissions_not_unlocked() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let emitter = create_emitter(&e);
+        let pool_1 = Address::generate(&e);
+
+        e.as_contract(&emitter, || {
+            storage::set_queued_direct_emissions(
+                &e,
+                &DirectEmissionsQueue {
+                    config: map![&e, (pool_1.clone(), 0_1000000)],
+                    unlock_time: 12346,
+                },
+            );
+
+            execute_set_direct_emissions(&e);
+        });
+    }
+
+    /********** execute_distribute_direct **********/
+
+    #[test]
+    fn test_distribute_direct() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let emitter = create_emitter(&e);
+        let pool_1 = Address::generate(&e);
+        let pool_2 = Address::generate(&e);
+
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let blnd_client = MockTokenClient::new(&e, &blnd_id);
+
+        e.as_contract(&emitter, || {
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_direct_emissions(&e, &map![&e, (pool_1.clone(), 0_2000000)]);
+            storage::set_direct_emis_data(
+                &e,
+                &pool_1,
+                &DirectEmissionData {
+                    last_time: 1000,
+                    accrued: 5 * SCALAR_7,
+                },
+            );
+
+            let result = execute_distribute_direct(&e, &pool_1);
+            assert_eq!(result, 5 * SCALAR_7 + 11345 * 0_2000000);
+            assert_eq!(blnd_client.balance(&pool_1), result);
+            let pool_1_data = storage::get_direct_emis_data(&e, &pool_1).unwrap();
+            assert_eq!(pool_1_data.last_time, 12345);
+            assert_eq!(pool_1_data.accrued, 0);
+
+            // pools without a direct emissions config receive nothing
+            let result = execute_distribute_direct(&e, &pool_2);
+            assert_eq!(result, 0);
+            assert_eq!(blnd_client.balance(&pool_2), 0);
+        });
+    }
+}
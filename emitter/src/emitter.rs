@@ -1,19 +1,42 @@
-use crate::{constants::SCALAR_7, errors::EmitterError, storage};
+use crate::{
+    cap_manager::mint_emissions,
+    constants::SCALAR_7,
+    direct_manager::checkpoint_direct_total,
+    errors::EmitterError,
+    pause_manager::{calc_active_seconds, require_emissions_active},
+    storage,
+};
 use sep_41_token::StellarAssetClient;
 use soroban_sdk::{panic_with_error, Address, Env, Vec};
 
-/// Perform a distribution
+/// Perform a distribution. Emissions streamed directly to pools since the last distribution are
+/// deducted from the backstop's emissions. Emissions past the emission cap are not minted, and are
+/// passed to the buyback module instead. Time emissions were paused is not distributed.
+///
+/// Returns the amount of BLND minted to the backstop
+///
+/// ### Panics
+/// If emissions are paused
 pub fn execute_distribute(e: &Env, backstop: &Address) -> i128 {
+    require_emissions_active(e);
     let timestamp = e.ledger().timestamp();
-    let seconds_since_last_distro = timestamp - storage::get_last_distro_time(e, backstop);
-    // Blend tokens are distributed at a rate of 1 token per second
-    let distribution_amount = (seconds_since_last_distro as i128) * SCALAR_7;
+    // time emissions were paused by the guardian is not distributed
+    let seconds_since_last_distro =
+        calc_active_seconds(e, storage::get_last_distro_time(e, backstop));
+    // Blend tokens are distributed at a rate of 1 token per second, shared with pools that are
+    // streamed emissions directly
+    let direct_total = checkpoint_direct_total(e);
+    let direct_amount = direct_total - storage::get_direct_mark(e, backstop);
+    let emission_amount = ((seconds_since_last_distro as i128) * SCALAR_7 - direct_amount).max(0);
     storage::set_last_distro_time(e, backstop, timestamp);
-
-    let blnd_id = storage::get_blnd_token(e);
-    let blnd_client = StellarAssetClient::new(e, &blnd_id);
-    blnd_client.mint(backstop, &distribution_amount);
-
+    storage::set_direct_mark(e, backstop, direct_total);
+
+    let distribution_amount = mint_emissions(e, backstop, emission_amount);
+    storage::set_distributed(
+        e,
+        backstop,
+        storage::get_distributed(e, backstop) + distribution_amount,
+    );
     distribution_amount
 }
 
@@ -34,23 +57,36 @@ pub fn execute_drop(e: &Env, list: &Vec<(Address, i128)>) {
     if drop_amount > 50_000_000 * SCALAR_7 {
         panic_with_error!(e, EmitterError::BadDrop);
     }
+    // drop cannot exceed the emission cap
+    let supply = storage::get_supply(e) + drop_amount;
+    if let Some(cap) = storage::get_emission_cap(e) {
+        if supply > cap.max_supply {
+            panic_with_error!(e, EmitterError::BadDrop);
+        }
+    }
 
     let blnd_id = storage::get_blnd_token(e);
     let blnd_client = StellarAssetClient::new(e, &blnd_id);
     for (addr, amt) in list.iter() {
         blnd_client.mint(&addr, &amt);
     }
+    storage::set_supply(e, supply);
     storage::set_drop_status(e, &backstop);
 }
 
 #[cfg(test)]
 mod tests {
 
-    use crate::{storage, testutils::create_emitter};
+    use crate::{
+        cap_manager::EmissionCap,
+        storage::{self, DirectEmissionData},
+        testutils::{create_emitter, create_mock_buyback},
+    };
 
     use super::*;
     use sep_41_token::testutils::MockTokenClient;
     use soroban_sdk::{
+        map,
         testutils::{Address as _, Ledger, LedgerInfo},
         vec,
     };
@@ -86,6 +122,137 @@ mod tests {
             assert_eq!(result, 11345_0000000);
             assert_eq!(blnd_client.balance(&backstop), 11345_0000000);
             assert_eq!(storage::get_last_distro_time(&e, &backstop), 12345);
+            assert_eq!(storage::get_distributed(&e, &backstop), 11345_0000000);
+            assert_eq!(storage::get_supply(&e), 11345_0000000);
+        });
+    }
+
+    #[test]
+    fn test_distribute_deducts_direct_emissions() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let emitter = create_emitter(&e);
+        let backstop = Address::generate(&e);
+        let pool = Address::generate(&e);
+
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let blnd_client = MockTokenClient::new(&e, &blnd_id);
+
+        e.as_contract(&emitter, || {
+            storage::set_last_distro_time(&e, &backstop, 1000);
+            storage::set_backstop(&e, &backstop);
+            storage::set_blnd_token(&e, &blnd_id);
+            // 20% of the base emission rate has been streamed to the pool since 1000
+            storage::set_direct_emissions(&e, &map![&e, (pool, 0_2000000)]);
+            storage::set_direct_total(
+                &e,
+                &DirectEmissionData {
+                    last_time: 1000,
+                    accrued: 500_0000000,
+                },
+            );
+            storage::set_direct_mark(&e, &backstop, 500_0000000);
+
+            let result = execute_distribute(&e, &backstop);
+            assert_eq!(result, 9076_0000000);
+            assert_eq!(blnd_client.balance(&backstop), 9076_0000000);
+            assert_eq!(storage::get_direct_mark(&e, &backstop), 2769_0000000);
+            assert_eq!(storage::get_direct_total(&e).unwrap().last_time, 12345);
+            assert_eq!(storage::get_supply(&e), 9076_0000000);
+        });
+    }
+
+    #[test]
+    fn test_distribute_capped() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let emitter = create_emitter(&e);
+        let backstop = Address::generate(&e);
+        let (buyback, buyback_client) = create_mock_buyback(&e);
+
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let blnd_client = MockTokenClient::new(&e, &blnd_id);
+
+        e.as_contract(&emitter, || {
+            storage::set_last_distro_time(&e, &backstop, 1000);
+            storage::set_backstop(&e, &backstop);
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_supply(&e, 99_990_000_0000000);
+            storage::set_emission_cap(
+                &e,
+                &EmissionCap {
+                    max_supply: 100_000_000_0000000,
+                    buyback: buyback.clone(),
+                },
+            );
+
+            let result = execute_distribute(&e, &backstop);
+            assert_eq!(result, 10000_0000000);
+            assert_eq!(blnd_client.balance(&backstop), 10000_0000000);
+            assert_eq!(storage::get_last_distro_time(&e, &backstop), 12345);
+            assert_eq!(storage::get_distributed(&e, &backstop), 10000_0000000);
+            assert_eq!(storage::get_supply(&e), 100_000_000_0000000);
+        });
+        assert_eq!(buyback_client.capped(&backstop), 1345_0000000);
+    }
+
+    #[test]
+    fn test_distribute_accumulates() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let emitter = create_emitter(&e);
+        let backstop = Address::generate(&e);
+        let other_backstop = Address::generate(&e);
+
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+
+        e.as_contract(&emitter, || {
+            storage::set_last_distro_time(&e, &backstop, 1000);
+            storage::set_distributed(&e, &backstop, 500_0000000);
+            storage::set_last_distro_time(&e, &other_backstop, 12000);
+            storage::set_backstop(&e, &backstop);
+            storage::set_blnd_token(&e, &blnd_id);
+
+            execute_distribute(&e, &backstop);
+            execute_distribute(&e, &other_backstop);
+            assert_eq!(storage::get_distributed(&e, &backstop), 11845_0000000);
+            assert_eq!(storage::get_distributed(&e, &other_backstop), 345_0000000);
         });
     }
 
@@ -127,6 +294,36 @@ mod tests {
             assert_eq!(storage::get_drop_status(&e, &backstop), true);
             assert_eq!(blnd_client.balance(&frodo), 20_000_000 * SCALAR_7);
             assert_eq!(blnd_client.balance(&samwise), 30_000_000 * SCALAR_7);
+            assert_eq!(storage::get_supply(&e), 50_000_000 * SCALAR_7);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1101)")]
+    fn test_drop_over_emission_cap() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let frodo = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        let backstop = Address::generate(&e);
+
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let drop_list = vec![&e, (frodo.clone(), 20_000_000 * SCALAR_7)];
+
+        e.as_contract(&emitter, || {
+            storage::set_backstop(&e, &backstop);
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_supply(&e, 80_000_000 * SCALAR_7);
+            storage::set_emission_cap(
+                &e,
+                &EmissionCap {
+                    max_supply: 100_000_000 * SCALAR_7 - 1,
+                    buyback: Address::generate(&e),
+                },
+            );
+
+            execute_drop(&e, &drop_list);
         });
     }
 
@@ -19,4 +19,29 @@ pub enum EmitterError {
     SwapAlreadyExists = 1103,
     SwapNotUnlocked = 1104,
     SwapCannotBeCanceled = 1105,
+
+    // Pause
+    ContractPaused = 1106,
+
+    // Direct Emissions
+    BadDirectEmissions = 1107,
+    DirectEmissionsNotQueued = 1108,
+    DirectEmissionsAlreadyQueued = 1109,
+    DirectEmissionsNotUnlocked = 1110,
+
+    // BLND Admin
+    AdminHandoverNotQueued = 1111,
+    AdminHandoverAlreadyQueued = 1112,
+    AdminHandoverNotUnlocked = 1113,
+    AdminHandoverCannotBeCanceled = 1118,
+
+    // Emission Pause
+    EmissionsPaused = 1114,
+    BadEmissionPause = 1115,
+
+    // Emission Cap
+    BadEmissionCap = 1116,
+
+    // Backstop Swap
+    IncompatibleBackstop = 1117,
 }
@@ -3,15 +3,24 @@
 #[cfg(any(test, feature = "testutils"))]
 extern crate std;
 
+mod admin_manager;
 mod backstop_manager;
+mod cap_manager;
 mod constants;
 mod contract;
+mod dependencies;
+mod direct_manager;
 mod emitter;
 mod errors;
+mod pause_manager;
 mod storage;
 mod testutils;
 
+pub use admin_manager::AdminHandover;
 pub use backstop_manager::Swap;
+pub use cap_manager::EmissionCap;
 pub use contract::*;
+pub use direct_manager::DirectEmissionsQueue;
 pub use errors::EmitterError;
-pub use storage::EmitterDataKey;
+pub use pause_manager::EmissionPause;
+pub use storage::{DirectEmissionData, EmitterDataKey};
@@ -0,0 +1,383 @@
+use soroban_sdk::{contracttype, panic_with_error, Address, Env};
+
+use crate::{
+    constants::{EMISSION_PAUSE_COOLDOWN, MAX_EMISSION_PAUSE},
+    dependencies::PauseRegistryClient,
+    direct_manager::checkpoint_direct_emissions,
+    storage, EmitterError,
+};
+
+#[derive(Clone)]
+#[contracttype]
+pub struct EmissionPause {
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+/// Require that the pause registry admin has authorized the call
+pub(crate) fn require_admin(e: &Env) {
+    let pause_registry = storage::get_pause_registry(e);
+    PauseRegistryClient::new(e, &pause_registry)
+        .get_admin()
+        .require_auth();
+}
+
+/// Require that the guardian has authorized the call
+///
+/// ### Panics
+/// If no guardian is set
+fn require_guardian(e: &Env) {
+    storage::get_guardian(e)
+        .unwrap_or_else(|| panic_with_error!(e, EmitterError::BadEmissionPause))
+        .require_auth();
+}
+
+/// Require that emissions are not paused
+///
+/// ### Panics
+/// If emissions are paused
+pub(crate) fn require_emissions_active(e: &Env) {
+    if let Some(pause) = storage::get_emission_pause(e) {
+        if pause.end_time > e.ledger().timestamp() {
+            panic_with_error!(e, EmitterError::EmissionsPaused);
+        }
+    }
+}
+
+/// Calculate the number of seconds since `from` that emissions were not paused
+///
+/// ### Arguments
+/// * `from` - The timestamp to count from
+pub(crate) fn calc_active_seconds(e: &Env, from: u64) -> u64 {
+    let timestamp = e.ledger().timestamp();
+    let mut seconds = timestamp - from;
+    if let Some(pause) = storage::get_emission_pause(e) {
+        let paused_from = pause.start_time.max(from);
+        let paused_to = pause.end_time.min(timestamp);
+        if paused_to > paused_from {
+            seconds -= paused_to - paused_from;
+        }
+    }
+    seconds
+}
+
+/// Set the guardian that can pause emissions
+///
+/// ### Arguments
+/// * `guardian` - The address that can pause emissions
+///
+/// ### Panics
+/// If the caller is not the pause registry admin
+pub fn execute_set_guardian(e: &Env, guardian: &Address) {
+    require_admin(e);
+    storage::set_guardian(e, guardian);
+}
+
+/// Pause the distribution of emissions for up to MAX_EMISSION_PAUSE. Emissions do not accrue
+/// while paused, so the time paused is never distributed.
+///
+/// ### Panics
+/// If the caller is not the guardian, or the previous pause ended less than
+/// EMISSION_PAUSE_COOLDOWN ago
+pub fn execute_pause_emissions(e: &Env) -> EmissionPause {
+    require_guardian(e);
+    let timestamp = e.ledger().timestamp();
+    if let Some(pause) = storage::get_emission_pause(e) {
+        if pause.end_time + EMISSION_PAUSE_COOLDOWN > timestamp {
+            panic_with_error!(e, EmitterError::BadEmissionPause);
+        }
+
+        // move every distribution past the previous pause before it is overwritten
+        let backstop = storage::get_backstop(e);
+        let last_distro = storage::get_last_distro_time(e, &backstop);
+        storage::set_last_distro_time(
+            e,
+            &backstop,
+            timestamp - calc_active_seconds(e, last_distro),
+        );
+        checkpoint_direct_emissions(e);
+    }
+
+    let pause = EmissionPause {
+        start_time: timestamp,
+        end_time: timestamp + MAX_EMISSION_PAUSE,
+    };
+    storage::set_emission_pause(e, &pause);
+    pause
+}
+
+/// End the active emission pause early
+///
+/// ### Panics
+/// If the caller is not the guardian or emissions are not paused
+pub fn execute_unpause_emissions(e: &Env) -> EmissionPause {
+    require_guardian(e);
+    let timestamp = e.ledger().timestamp();
+    let mut pause = match storage::get_emission_pause(e) {
+        Some(pause) if pause.end_time > timestamp => pause,
+        _ => panic_with_error!(e, EmitterError::BadEmissionPause),
+    };
+
+    pause.end_time = timestamp;
+    storage::set_emission_pause(e, &pause);
+    pause
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        direct_manager::{checkpoint_direct_total, execute_distribute_direct},
+        emitter::execute_distribute,
+        storage::DirectEmissionData,
+        testutils::{create_emitter, create_pause_registry},
+    };
+
+    use super::*;
+    use sep_41_token::testutils::MockTokenClient;
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+    };
+
+    fn set_timestamp(e: &Env, timestamp: u64) {
+        e.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+    }
+
+    #[test]
+    fn test_pause_emissions() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 12345);
+
+        let bombadil = Address::generate(&e);
+        let guardian = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        create_pause_registry(&e, &emitter, &bombadil);
+
+        e.as_contract(&emitter, || {
+            let backstop = Address::generate(&e);
+            storage::set_backstop(&e, &backstop);
+            storage::set_last_distro_time(&e, &backstop, 12345);
+            execute_set_guardian(&e, &guardian);
+            assert_eq!(storage::get_guardian(&e), Some(guardian.clone()));
+        });
+        e.as_contract(&emitter, || {
+            let pause = execute_pause_emissions(&e);
+            assert_eq!(pause.start_time, 12345);
+            assert_eq!(pause.end_time, 12345 + MAX_EMISSION_PAUSE);
+        });
+
+        // the pause ends on its own after the maximum pause length
+        set_timestamp(&e, 12345 + MAX_EMISSION_PAUSE);
+        e.as_contract(&emitter, || {
+            require_emissions_active(&e);
+        });
+
+        // the guardian can pause again after the cooldown
+        set_timestamp(&e, 12345 + MAX_EMISSION_PAUSE + EMISSION_PAUSE_COOLDOWN);
+        e.as_contract(&emitter, || {
+            execute_pause_emissions(&e);
+        });
+        e.as_contract(&emitter, || {
+            let pause = execute_unpause_emissions(&e);
+            assert_eq!(
+                pause.end_time,
+                12345 + MAX_EMISSION_PAUSE + EMISSION_PAUSE_COOLDOWN
+            );
+            require_emissions_active(&e);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1114)")]
+    fn test_pause_emissions_blocks_distribute() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 12345);
+
+        let bombadil = Address::generate(&e);
+        let guardian = Address::generate(&e);
+        let backstop = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        create_pause_registry(&e, &emitter, &bombadil);
+
+        e.as_contract(&emitter, || {
+            storage::set_guardian(&e, &guardian);
+            storage::set_last_distro_time(&e, &backstop, 1000);
+            execute_pause_emissions(&e);
+
+            set_timestamp(&e, 12345 + MAX_EMISSION_PAUSE - 1);
+            execute_distribute(&e, &backstop);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1115)")]
+    fn test_pause_emissions_cooldown() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 12345);
+
+        let guardian = Address::generate(&e);
+        let emitter = create_emitter(&e);
+
+        e.as_contract(&emitter, || {
+            storage::set_guardian(&e, &guardian);
+            execute_pause_emissions(&e);
+        });
+        e.as_contract(&emitter, || {
+            execute_unpause_emissions(&e);
+        });
+
+        set_timestamp(&e, 12345 + EMISSION_PAUSE_COOLDOWN - 1);
+        e.as_contract(&emitter, || {
+            execute_pause_emissions(&e);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1115)")]
+    fn test_pause_emissions_no_guardian() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let emitter = create_emitter(&e);
+
+        e.as_contract(&emitter, || {
+            execute_pause_emissions(&e);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1115)")]
+    fn test_unpause_emissions_not_paused() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let guardian = Address::generate(&e);
+        let emitter = create_emitter(&e);
+
+        e.as_contract(&emitter, || {
+            storage::set_guardian(&e, &guardian);
+            execute_unpause_emissions(&e);
+        });
+    }
+
+    #[test]
+    fn test_pause_emissions_skips_paused_time() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 12345);
+
+        let guardian = Address::generate(&e);
+        let backstop = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let blnd_client = MockTokenClient::new(&e, &blnd_id);
+
+        e.as_contract(&emitter, || {
+            storage::set_guardian(&e, &guardian);
+            storage::set_backstop(&e, &backstop);
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_last_distro_time(&e, &backstop, 12345 - 1000);
+            execute_pause_emissions(&e);
+        });
+
+        // the guardian ends the pause early
+        set_timestamp(&e, 12345 + 2000);
+        e.as_contract(&emitter, || {
+            execute_unpause_emissions(&e);
+        });
+
+        set_timestamp(&e, 12345 + 2500);
+        e.as_contract(&emitter, || {
+            let result = execute_distribute(&e, &backstop);
+            assert_eq!(result, 1500_0000000);
+            assert_eq!(blnd_client.balance(&backstop), 1500_0000000);
+        });
+
+        // the next pause expires on its own, and is overwritten by a later pause before the
+        // backstop distributes again
+        let second_start = 12345 + 2000 + EMISSION_PAUSE_COOLDOWN;
+        set_timestamp(&e, second_start);
+        e.as_contract(&emitter, || {
+            execute_pause_emissions(&e);
+        });
+
+        let third_start = second_start + MAX_EMISSION_PAUSE + EMISSION_PAUSE_COOLDOWN;
+        set_timestamp(&e, third_start);
+        e.as_contract(&emitter, || {
+            execute_pause_emissions(&e);
+        });
+        set_timestamp(&e, third_start + 100);
+        e.as_contract(&emitter, || {
+            execute_unpause_emissions(&e);
+        });
+
+        set_timestamp(&e, third_start + 200);
+        e.as_contract(&emitter, || {
+            let result = execute_distribute(&e, &backstop);
+            let active_seconds = (third_start + 200 - (12345 + 2500)) - MAX_EMISSION_PAUSE - 100;
+            assert_eq!(result, (active_seconds as i128) * 1_0000000);
+            assert_eq!(
+                storage::get_last_distro_time(&e, &backstop),
+                third_start + 200
+            );
+        });
+    }
+
+    #[test]
+    fn test_pause_emissions_skips_paused_direct_emissions() {
+        let e = Env::default();
+        e.mock_all_auths();
+        set_timestamp(&e, 12345);
+
+        let guardian = Address::generate(&e);
+        let backstop = Address::generate(&e);
+        let pool = Address::generate(&e);
+        let emitter = create_emitter(&e);
+        let blnd_id = e.register_stellar_asset_contract(emitter.clone());
+        let blnd_client = MockTokenClient::new(&e, &blnd_id);
+
+        e.as_contract(&emitter, || {
+            storage::set_guardian(&e, &guardian);
+            storage::set_backstop(&e, &backstop);
+            storage::set_blnd_token(&e, &blnd_id);
+            storage::set_direct_emissions(&e, &map![&e, (pool.clone(), 0_1000000)]);
+            storage::set_direct_emis_data(
+                &e,
+                &pool,
+                &DirectEmissionData {
+                    last_time: 12345 - 1000,
+                    accrued: 0,
+                },
+            );
+            storage::set_direct_total(
+                &e,
+                &DirectEmissionData {
+                    last_time: 12345 - 1000,
+                    accrued: 0,
+                },
+            );
+            execute_pause_emissions(&e);
+        });
+
+        // the pause expires on its own
+        set_timestamp(&e, 12345 + MAX_EMISSION_PAUSE + 500);
+        e.as_contract(&emitter, || {
+            let result = execute_distribute_direct(&e, &pool);
+            assert_eq!(result, 1500 * 0_1000000);
+            assert_eq!(blnd_client.balance(&pool), 1500 * 0_1000000);
+            assert_eq!(checkpoint_direct_total(&e), 1500 * 0_1000000);
+        });
+    }
+}
@@ -1,6 +1,9 @@
-use soroban_sdk::{contracttype, unwrap::UnwrapOptimized, Address, Env, Symbol};
+use soroban_sdk::{contracttype, map, unwrap::UnwrapOptimized, Address, Env, Map, Symbol};
 
-use crate::backstop_manager::Swap;
+use crate::{
+    admin_manager::AdminHandover, backstop_manager::Swap, cap_manager::EmissionCap,
+    direct_manager::DirectEmissionsQueue, pause_manager::EmissionPause,
+};
 
 /********** Ledger Thresholds **********/
 
@@ -19,6 +22,15 @@ const BACKSTOP_KEY: &str = "Backstop";
 const BACKSTOP_TOKEN_KEY: &str = "BToken";
 const BLND_TOKEN_KEY: &str = "BLNDTkn";
 const SWAP_KEY: &str = "Swap";
+const PAUSE_REGISTRY_KEY: &str = "PauseReg";
+const DIRECT_KEY: &str = "Direct";
+const DIRECT_QUEUE_KEY: &str = "DirectQ";
+const DIRECT_TOTAL_KEY: &str = "DirectTot";
+const ADMIN_QUEUE_KEY: &str = "AdminQ";
+const GUARDIAN_KEY: &str = "Guardian";
+const EMISSION_PAUSE_KEY: &str = "EmisPause";
+const EMISSION_CAP_KEY: &str = "EmisCap";
+const SUPPLY_KEY: &str = "Supply";
 
 // Emitter Data Keys
 #[derive(Clone)]
@@ -28,6 +40,20 @@ pub enum EmitterDataKey {
     LastDistro(Address),
     // Stores the list of backstop addresses that have dropped
     Dropped(Address),
+    // The direct emission data for a pool
+    DirectEmis(Address),
+    // The cumulative amount of BLND distributed to a backstop
+    Distributed(Address),
+    // The cumulative direct emissions as of the last distribution to a backstop
+    DirectMark(Address),
+}
+
+/// The direct emission data for a pool
+#[derive(Clone)]
+#[contracttype]
+pub struct DirectEmissionData {
+    pub last_time: u64, // the last time the pool's direct emissions were accrued
+    pub accrued: i128,  // the tokens accrued to the pool that have not been distributed
 }
 
 /// Bump the instance rent for the contract
@@ -51,6 +77,107 @@ pub fn set_is_init(e: &Env) {
         .set::<Symbol, bool>(&Symbol::new(e, IS_INIT_KEY), &true);
 }
 
+/********** Pause Registry **********/
+
+/// Fetch the pause registry address
+pub fn get_pause_registry(e: &Env) -> Address {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, PAUSE_REGISTRY_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the pause registry address
+///
+/// ### Arguments
+/// * `pause_registry` - The pause registry contract address
+pub fn set_pause_registry(e: &Env, pause_registry: &Address) {
+    e.storage()
+        .instance()
+        .set::<Symbol, Address>(&Symbol::new(e, PAUSE_REGISTRY_KEY), pause_registry);
+}
+
+/********** Emission Pause **********/
+
+/// Fetch the guardian address, or None if no guardian is set
+pub fn get_guardian(e: &Env) -> Option<Address> {
+    e.storage().instance().get(&Symbol::new(e, GUARDIAN_KEY))
+}
+
+/// Set the guardian address
+///
+/// ### Arguments
+/// * `guardian` - The address that can pause emissions
+pub fn set_guardian(e: &Env, guardian: &Address) {
+    e.storage()
+        .instance()
+        .set::<Symbol, Address>(&Symbol::new(e, GUARDIAN_KEY), guardian);
+}
+
+/// Fetch the most recent emission pause, or None if emissions have never been paused
+pub fn get_emission_pause(e: &Env) -> Option<EmissionPause> {
+    let key = Symbol::new(e, EMISSION_PAUSE_KEY);
+    if let Some(result) = e.storage().persistent().get(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Set the most recent emission pause
+///
+/// ### Arguments
+/// * `pause` - The emission pause
+pub fn set_emission_pause(e: &Env, pause: &EmissionPause) {
+    let key = Symbol::new(e, EMISSION_PAUSE_KEY);
+    e.storage()
+        .persistent()
+        .set::<Symbol, EmissionPause>(&key, pause);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/********** Emission Cap **********/
+
+/// Fetch the BLND emission cap, or None if no cap is set
+pub fn get_emission_cap(e: &Env) -> Option<EmissionCap> {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, EMISSION_CAP_KEY))
+}
+
+/// Set the BLND emission cap
+///
+/// ### Arguments
+/// * `cap` - The emission cap
+pub fn set_emission_cap(e: &Env, cap: &EmissionCap) {
+    e.storage()
+        .instance()
+        .set::<Symbol, EmissionCap>(&Symbol::new(e, EMISSION_CAP_KEY), cap);
+}
+
+/// Fetch the BLND supply tracked for the emission cap, seeded when the cap is set
+pub fn get_supply(e: &Env) -> i128 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, SUPPLY_KEY))
+        .unwrap_or(0)
+}
+
+/// Set the BLND supply tracked for the emission cap
+///
+/// ### Arguments
+/// * `supply` - The BLND supply
+pub fn set_supply(e: &Env, supply: i128) {
+    e.storage()
+        .instance()
+        .set::<Symbol, i128>(&Symbol::new(e, SUPPLY_KEY), &supply);
+}
+
 /********** Backstop **********/
 
 /// Fetch the current backstop address
@@ -180,6 +307,37 @@ pub fn set_last_distro_time(e: &Env, backstop: &Address, last_distro: u64) {
         .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
 }
 
+/// Fetch the cumulative amount of BLND distributed to a backstop
+///
+/// ### Arguments
+/// * `backstop` - The backstop module Address
+pub fn get_distributed(e: &Env, backstop: &Address) -> i128 {
+    let key = EmitterDataKey::Distributed(backstop.clone());
+    if let Some(result) = e.storage().persistent().get(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+        result
+    } else {
+        0
+    }
+}
+
+/// Set the cumulative amount of BLND distributed to a backstop
+///
+/// ### Arguments
+/// * `backstop` - The backstop module Address
+/// * `distributed` - The cumulative amount of BLND distributed
+pub fn set_distributed(e: &Env, backstop: &Address, distributed: i128) {
+    let key = EmitterDataKey::Distributed(backstop.clone());
+    e.storage()
+        .persistent()
+        .set::<EmitterDataKey, i128>(&key, &distributed);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
 /// Get whether the emitter has performed the drop distribution or not for the current backstop
 ///
 /// Returns true if the emitter has dropped
@@ -199,3 +357,191 @@ pub fn set_drop_status(e: &Env, backstop: &Address) {
         .persistent()
         .set::<EmitterDataKey, bool>(&EmitterDataKey::Dropped(backstop.clone()), &true);
 }
+
+/********** Direct Emissions **********/
+
+/// Fetch the direct emissions configuration as a map of pool address to share of the
+/// base emission rate
+pub fn get_direct_emissions(e: &Env) -> Map<Address, u64> {
+    let key = Symbol::new(e, DIRECT_KEY);
+    if let Some(result) = e.storage().persistent().get(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+        result
+    } else {
+        map![e]
+    }
+}
+
+/// Set the direct emissions configuration
+///
+/// ### Arguments
+/// * `config` - The map of pool address to share of the base emission rate
+pub fn set_direct_emissions(e: &Env, config: &Map<Address, u64>) {
+    let key = Symbol::new(e, DIRECT_KEY);
+    e.storage()
+        .persistent()
+        .set::<Symbol, Map<Address, u64>>(&key, config);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Fetch the queued direct emissions configuration, or None
+pub fn get_queued_direct_emissions(e: &Env) -> Option<DirectEmissionsQueue> {
+    let key = Symbol::new(e, DIRECT_QUEUE_KEY);
+    if let Some(result) = e.storage().persistent().get(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Set a new direct emissions configuration in the queue
+///
+/// ### Arguments
+/// * `queue` - The queued direct emissions configuration
+pub fn set_queued_direct_emissions(e: &Env, queue: &DirectEmissionsQueue) {
+    let key = Symbol::new(e, DIRECT_QUEUE_KEY);
+    e.storage()
+        .persistent()
+        .set::<Symbol, DirectEmissionsQueue>(&key, queue);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Delete the queued direct emissions configuration
+pub fn del_queued_direct_emissions(e: &Env) {
+    e.storage()
+        .persistent()
+        .remove(&Symbol::new(e, DIRECT_QUEUE_KEY));
+}
+
+/// Fetch the cumulative direct emissions accrued to all pools, or None if no pool has been
+/// configured for direct emissions
+pub fn get_direct_total(e: &Env) -> Option<DirectEmissionData> {
+    let key = Symbol::new(e, DIRECT_TOTAL_KEY);
+    if let Some(result) = e.storage().persistent().get(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Set the cumulative direct emissions accrued to all pools
+///
+/// ### Arguments
+/// * `data` - The cumulative direct emission data
+pub fn set_direct_total(e: &Env, data: &DirectEmissionData) {
+    let key = Symbol::new(e, DIRECT_TOTAL_KEY);
+    e.storage()
+        .persistent()
+        .set::<Symbol, DirectEmissionData>(&key, data);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Fetch the cumulative direct emissions as of the last distribution to a backstop
+///
+/// ### Arguments
+/// * `backstop` - The backstop address
+pub fn get_direct_mark(e: &Env, backstop: &Address) -> i128 {
+    let key = EmitterDataKey::DirectMark(backstop.clone());
+    if let Some(result) = e.storage().persistent().get(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+        result
+    } else {
+        0
+    }
+}
+
+/// Set the cumulative direct emissions as of the last distribution to a backstop
+///
+/// ### Arguments
+/// * `backstop` - The backstop address
+/// * `mark` - The cumulative direct emissions
+pub fn set_direct_mark(e: &Env, backstop: &Address, mark: i128) {
+    let key = EmitterDataKey::DirectMark(backstop.clone());
+    e.storage()
+        .persistent()
+        .set::<EmitterDataKey, i128>(&key, &mark);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Fetch the queued BLND admin handover, or None
+pub fn get_queued_admin(e: &Env) -> Option<AdminHandover> {
+    let key = Symbol::new(e, ADMIN_QUEUE_KEY);
+    if let Some(result) = e.storage().persistent().get(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Set a new BLND admin handover in the queue
+///
+/// ### Arguments
+/// * `handover` - The queued BLND admin handover
+pub fn set_queued_admin(e: &Env, handover: &AdminHandover) {
+    let key = Symbol::new(e, ADMIN_QUEUE_KEY);
+    e.storage()
+        .persistent()
+        .set::<Symbol, AdminHandover>(&key, handover);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Delete the queued BLND admin handover
+pub fn del_queued_admin(e: &Env) {
+    e.storage()
+        .persistent()
+        .remove(&Symbol::new(e, ADMIN_QUEUE_KEY));
+}
+
+/// Fetch the direct emission data for a pool, or None
+///
+/// ### Arguments
+/// * `pool` - The pool address
+pub fn get_direct_emis_data(e: &Env, pool: &Address) -> Option<DirectEmissionData> {
+    let key = EmitterDataKey::DirectEmis(pool.clone());
+    if let Some(result) = e.storage().persistent().get(&key) {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Set the direct emission data for a pool
+///
+/// ### Arguments
+/// * `pool` - The pool address
+/// * `data` - The direct emission data for the pool
+pub fn set_direct_emis_data(e: &Env, pool: &Address, data: &DirectEmissionData) {
+    let key = EmitterDataKey::DirectEmis(pool.clone());
+    e.storage()
+        .persistent()
+        .set::<EmitterDataKey, DirectEmissionData>(&key, data);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
@@ -1,9 +1,69 @@
 #![cfg(test)]
 
-use soroban_sdk::{Address, Env};
+use soroban_sdk::{contract, contractimpl, Address, Env, Symbol};
 
-use crate::EmitterContract;
+use crate::{
+    dependencies::{PauseRegistryClient, PAUSE_REGISTRY_WASM},
+    storage, EmitterContract,
+};
 
 pub(crate) fn create_emitter(e: &Env) -> Address {
     e.register_contract(None, EmitterContract {})
 }
+
+pub(crate) fn create_pause_registry(e: &Env, emitter: &Address, admin: &Address) -> Address {
+    let contract_address = e.register_contract_wasm(None, PAUSE_REGISTRY_WASM);
+    PauseRegistryClient::new(e, &contract_address).initialize(admin);
+    e.as_contract(emitter, || {
+        storage::set_pause_registry(e, &contract_address);
+    });
+    contract_address
+}
+
+/// A backstop that only implements the read-only backstop interface the emitter probes
+#[contract]
+pub struct MockBackstop;
+
+#[contractimpl]
+impl MockBackstop {
+    pub fn backstop_token(e: Env) -> Address {
+        e.storage()
+            .instance()
+            .get(&Symbol::new(&e, "Token"))
+            .unwrap()
+    }
+}
+
+pub(crate) fn create_mock_backstop(e: &Env, backstop_token: &Address) -> Address {
+    let contract_address = e.register_contract(None, MockBackstop {});
+    e.as_contract(&contract_address, || {
+        e.storage()
+            .instance()
+            .set(&Symbol::new(e, "Token"), backstop_token);
+    });
+    contract_address
+}
+
+/// A buyback module that records the emissions it is notified of for each address
+#[contract]
+pub struct MockBuyback;
+
+#[contractimpl]
+impl MockBuyback {
+    pub fn on_capped_emissions(e: Env, to: Address, amount: i128) {
+        let capped = Self::capped(e.clone(), to.clone());
+        e.storage().instance().set(&to, &(capped + amount));
+    }
+
+    pub fn capped(e: Env, to: Address) -> i128 {
+        e.storage().instance().get(&to).unwrap_or(0)
+    }
+}
+
+pub(crate) fn create_mock_buyback<'a>(e: &Env) -> (Address, MockBuybackClient<'a>) {
+    let contract_address = e.register_contract(None, MockBuyback {});
+    (
+        contract_address.clone(),
+        MockBuybackClient::new(e, &contract_address),
+    )
+}
@@ -0,0 +1,14 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+/// Error codes for the median oracle contract. Common errors are codes that match up with the
+/// built-in contracts error reporting. Median oracle specific errors start at 1600.
+pub enum MedianOracleError {
+    // Common Errors
+    InternalError = 1,
+    AlreadyInitializedError = 3,
+
+    InvalidOracleConfig = 1600,
+}
@@ -0,0 +1,11 @@
+#![no_std]
+
+#[cfg(any(test, feature = "testutils"))]
+extern crate std;
+
+mod errors;
+mod median_oracle;
+mod storage;
+
+pub use errors::MedianOracleError;
+pub use median_oracle::*;
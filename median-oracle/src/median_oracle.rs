@@ -0,0 +1,211 @@
+use crate::{errors::MedianOracleError, storage};
+use sep_40_oracle::{Asset, PriceData, PriceFeedClient, PriceFeedTrait};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, panic_with_error, vec, Address, Env, Vec,
+};
+
+/// ### Median Oracle
+///
+/// A SEP-40 price feed that serves the median price reported by a set of oracles, so a pool can
+/// compose price sources without trusting a single feed.
+///
+/// Prices older than the max age, non-positive prices, and oracles that fail to respond are
+/// ignored. If fewer than the minimum number of oracles report a usable price, no price is
+/// reported for the asset. Every oracle must report prices in the same base asset. The quoted
+/// assets are those of the first oracle.
+#[contract]
+pub struct MedianOracleContract;
+
+#[contractclient(name = "MedianOracleClient")]
+pub trait MedianOracle {
+    /// Initialize the median oracle
+    ///
+    /// ### Arguments
+    /// * `oracles` - The addresses of the oracles prices are sourced from
+    /// * `max_age` - The maximum age of a price in seconds for it to be used
+    /// * `min_sources` - The minimum number of oracles that must report a usable price
+    ///
+    /// ### Errors
+    /// If initialize has already been called, if there are no oracles, if `min_sources` is not
+    /// in [1, oracles], if `max_age` is zero, or if the oracles report prices in different base
+    /// assets or with different decimals or resolutions
+    fn initialize(e: Env, oracles: Vec<Address>, max_age: u64, min_sources: u32);
+
+    /// Fetch the addresses of the oracles prices are sourced from
+    fn oracles(e: Env) -> Vec<Address>;
+
+    /// Fetch the maximum age of a price in seconds for it to be used
+    fn max_age(e: Env) -> u64;
+
+    /// Fetch the minimum number of oracles that must report a usable price
+    fn min_sources(e: Env) -> u32;
+}
+
+#[contractimpl]
+impl MedianOracle for MedianOracleContract {
+    fn initialize(e: Env, oracles: Vec<Address>, max_age: u64, min_sources: u32) {
+        storage::extend_instance(&e);
+        if storage::get_is_init(&e) {
+            panic_with_error!(&e, MedianOracleError::AlreadyInitializedError);
+        }
+        if oracles.is_empty() || min_sources == 0 || min_sources > oracles.len() || max_age == 0 {
+            panic_with_error!(&e, MedianOracleError::InvalidOracleConfig);
+        }
+
+        let first_oracle = PriceFeedClient::new(&e, &oracles.get_unchecked(0));
+        let base = first_oracle.base();
+        let decimals = first_oracle.decimals();
+        let resolution = first_oracle.resolution();
+        if resolution == 0 {
+            panic_with_error!(&e, MedianOracleError::InvalidOracleConfig);
+        }
+        for oracle in oracles.iter().skip(1) {
+            let oracle_client = PriceFeedClient::new(&e, &oracle);
+            if !is_same_asset(&oracle_client.base(), &base)
+                || oracle_client.decimals() != decimals
+                || oracle_client.resolution() != resolution
+            {
+                panic_with_error!(&e, MedianOracleError::InvalidOracleConfig);
+            }
+        }
+
+        storage::set_oracles(&e, &oracles);
+        storage::set_decimals(&e, decimals);
+        storage::set_resolution(&e, resolution);
+        storage::set_max_age(&e, max_age);
+        storage::set_min_sources(&e, min_sources);
+
+        storage::set_is_init(&e);
+    }
+
+    fn oracles(e: Env) -> Vec<Address> {
+        storage::get_oracles(&e)
+    }
+
+    fn max_age(e: Env) -> u64 {
+        storage::get_max_age(&e)
+    }
+
+    fn min_sources(e: Env) -> u32 {
+        storage::get_min_sources(&e)
+    }
+}
+
+#[contractimpl]
+impl PriceFeedTrait for MedianOracleContract {
+    fn base(env: Env) -> Asset {
+        PriceFeedClient::new(&env, &storage::get_oracles(&env).get_unchecked(0)).base()
+    }
+
+    fn assets(env: Env) -> Vec<Asset> {
+        PriceFeedClient::new(&env, &storage::get_oracles(&env).get_unchecked(0)).assets()
+    }
+
+    fn decimals(env: Env) -> u32 {
+        storage::get_decimals(&env)
+    }
+
+    fn resolution(env: Env) -> u32 {
+        storage::get_resolution(&env)
+    }
+
+    fn price(env: Env, asset: Asset, timestamp: u64) -> Option<PriceData> {
+        storage::extend_instance(&env);
+        load_median_price(&env, timestamp, |oracle_client| {
+            match oracle_client.try_price(&asset, &timestamp) {
+                Ok(Ok(price_data)) => price_data,
+                _ => None,
+            }
+        })
+    }
+
+    fn prices(env: Env, asset: Asset, records: u32) -> Option<Vec<PriceData>> {
+        storage::extend_instance(&env);
+        let resolution = u64::from(storage::get_resolution(&env));
+        let mut timestamp = env.ledger().timestamp() / resolution * resolution;
+        let mut price_records = vec![&env];
+        for _ in 0..records {
+            if let Some(price_data) = Self::price(env.clone(), asset.clone(), timestamp) {
+                price_records.push_back(price_data);
+            }
+            if timestamp < resolution {
+                break;
+            }
+            timestamp -= resolution;
+        }
+        if price_records.is_empty() {
+            None
+        } else {
+            Some(price_records)
+        }
+    }
+
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData> {
+        storage::extend_instance(&env);
+        load_median_price(
+            &env,
+            env.ledger().timestamp(),
+            |oracle_client| match oracle_client.try_lastprice(&asset) {
+                Ok(Ok(price_data)) => price_data,
+                _ => None,
+            },
+        )
+    }
+}
+
+/// Load a price from each oracle and return the median of the usable prices. A price is usable
+/// if it is positive and was reported within `max_age` seconds before `as_of`. The timestamp of
+/// the median is the oldest timestamp of the usable prices.
+///
+/// Returns None if fewer than `min_sources` oracles report a usable price
+///
+/// ### Arguments
+/// * `as_of` - The timestamp the prices are evaluated at
+/// * `load_price` - Loads the price from an oracle, or None if the oracle has no price
+fn load_median_price(
+    e: &Env,
+    as_of: u64,
+    load_price: impl Fn(&PriceFeedClient) -> Option<PriceData>,
+) -> Option<PriceData> {
+    let max_age = storage::get_max_age(e);
+    let mut sorted_prices: Vec<i128> = vec![e];
+    let mut timestamp = as_of;
+    for oracle in storage::get_oracles(e).iter() {
+        let price_data = match load_price(&PriceFeedClient::new(e, &oracle)) {
+            Some(price_data)
+                if price_data.price > 0
+                    && price_data.timestamp <= as_of
+                    && price_data.timestamp + max_age >= as_of =>
+            {
+                price_data
+            }
+            _ => continue,
+        };
+        let mut index = 0;
+        while index < sorted_prices.len() && sorted_prices.get_unchecked(index) < price_data.price {
+            index += 1;
+        }
+        sorted_prices.insert(index, price_data.price);
+        timestamp = timestamp.min(price_data.timestamp);
+    }
+
+    let count = sorted_prices.len();
+    if count == 0 || count < storage::get_min_sources(e) {
+        return None;
+    }
+    let price = if count % 2 == 1 {
+        sorted_prices.get_unchecked(count / 2)
+    } else {
+        (sorted_prices.get_unchecked(count / 2 - 1) + sorted_prices.get_unchecked(count / 2)) / 2
+    };
+    Some(PriceData { price, timestamp })
+}
+
+/// Check if two assets are the same asset
+fn is_same_asset(a: &Asset, b: &Asset) -> bool {
+    match (a, b) {
+        (Asset::Stellar(a), Asset::Stellar(b)) => a == b,
+        (Asset::Other(a), Asset::Other(b)) => a == b,
+        _ => false,
+    }
+}
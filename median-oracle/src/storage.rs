@@ -0,0 +1,130 @@
+use soroban_sdk::{unwrap::UnwrapOptimized, Address, Env, Symbol, Vec};
+
+/********** Ledger Thresholds **********/
+
+const ONE_DAY_LEDGERS: u32 = 17280; // assumes 5s a ledger
+
+const LEDGER_THRESHOLD_INSTANCE: u32 = ONE_DAY_LEDGERS * 30; // ~ 30 days
+const LEDGER_BUMP_INSTANCE: u32 = LEDGER_THRESHOLD_INSTANCE + ONE_DAY_LEDGERS; // ~ 31 days
+
+/********** Storage **********/
+
+const IS_INIT_KEY: &str = "IsInit";
+const ORACLES_KEY: &str = "Oracles";
+const DECIMALS_KEY: &str = "Decimals";
+const RESOLUTION_KEY: &str = "Resolution";
+const MAX_AGE_KEY: &str = "MaxAge";
+const MIN_SOURCES_KEY: &str = "MinSources";
+
+/// Bump the instance rent for the contract
+pub fn extend_instance(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(LEDGER_THRESHOLD_INSTANCE, LEDGER_BUMP_INSTANCE);
+}
+
+/********** Init **********/
+
+/// Check if the contract has been initialized
+pub fn get_is_init(e: &Env) -> bool {
+    e.storage().instance().has(&Symbol::new(e, IS_INIT_KEY))
+}
+
+/// Set the contract as initialized
+pub fn set_is_init(e: &Env) {
+    e.storage()
+        .instance()
+        .set::<Symbol, bool>(&Symbol::new(e, IS_INIT_KEY), &true);
+}
+
+/********** Config **********/
+
+/// Fetch the oracles prices are sourced from
+pub fn get_oracles(e: &Env) -> Vec<Address> {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, ORACLES_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the oracles prices are sourced from
+///
+/// ### Arguments
+/// * `oracles` - The Addresses of the oracles
+pub fn set_oracles(e: &Env, oracles: &Vec<Address>) {
+    e.storage()
+        .instance()
+        .set::<Symbol, Vec<Address>>(&Symbol::new(e, ORACLES_KEY), oracles);
+}
+
+/// Fetch the decimals of the prices reported by the oracles
+pub fn get_decimals(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, DECIMALS_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the decimals of the prices reported by the oracles
+///
+/// ### Arguments
+/// * `decimals` - The decimals of the prices
+pub fn set_decimals(e: &Env, decimals: u32) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u32>(&Symbol::new(e, DECIMALS_KEY), &decimals);
+}
+
+/// Fetch the tick period of the prices in seconds
+pub fn get_resolution(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, RESOLUTION_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the tick period of the prices in seconds
+///
+/// ### Arguments
+/// * `resolution` - The tick period in seconds
+pub fn set_resolution(e: &Env, resolution: u32) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u32>(&Symbol::new(e, RESOLUTION_KEY), &resolution);
+}
+
+/// Fetch the maximum age of a price in seconds for it to be used
+pub fn get_max_age(e: &Env) -> u64 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, MAX_AGE_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the maximum age of a price in seconds for it to be used
+///
+/// ### Arguments
+/// * `max_age` - The maximum age in seconds
+pub fn set_max_age(e: &Env, max_age: u64) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u64>(&Symbol::new(e, MAX_AGE_KEY), &max_age);
+}
+
+/// Fetch the minimum number of oracles that must report a fresh price
+pub fn get_min_sources(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, MIN_SOURCES_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the minimum number of oracles that must report a fresh price
+///
+/// ### Arguments
+/// * `min_sources` - The minimum number of oracles
+pub fn set_min_sources(e: &Env, min_sources: u32) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u32>(&Symbol::new(e, MIN_SOURCES_KEY), &min_sources);
+}
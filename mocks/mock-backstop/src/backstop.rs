@@ -0,0 +1,166 @@
+use crate::{
+    storage::{self, PoolBackstopData},
+    MockBackstopError,
+};
+use sep_41_token::TokenClient;
+use soroban_sdk::{contract, contractimpl, panic_with_error, Address, Env};
+
+#[contract]
+pub struct MockBackstop;
+
+pub trait MockBackstopTrait {
+    /// Setup the mock backstop
+    ///
+    /// ### Arguments
+    /// * `backstop_token` - The backstop token address
+    /// * `emitter` - The emitter address
+    fn initialize(e: Env, backstop_token: Address, emitter: Address);
+
+    /// Fetch the backstop data for a pool
+    ///
+    /// ### Arguments
+    /// * `pool` - The pool address
+    fn pool_data(e: Env, pool: Address) -> PoolBackstopData;
+
+    /// Fetch the backstop token address
+    fn backstop_token(e: Env) -> Address;
+
+    /// Fetch the emitter address
+    fn emitter(e: Env) -> Address;
+
+    /// Gulp the emissions available for a pool
+    ///
+    /// Returns the amount of emissions gulped
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The pool address
+    fn gulp_pool_emissions(e: Env, pool_address: Address) -> i128;
+
+    /// Draw backstop tokens from a pool's backstop. Reduces the pool's reported tokens.
+    ///
+    /// ### Arguments
+    /// * `pool_address` - The pool address
+    /// * `amount` - The amount of backstop tokens to draw
+    /// * `to` - The address receiving the backstop tokens
+    ///
+    /// ### Errors
+    /// If draw calls are configured to fail
+    fn draw(e: Env, pool_address: Address, amount: i128, to: Address);
+
+    /// Donate backstop tokens to a pool's backstop. Increases the pool's reported tokens.
+    ///
+    /// ### Arguments
+    /// * `from` - The address donating backstop tokens
+    /// * `pool_address` - The pool address
+    /// * `amount` - The amount of backstop tokens to donate
+    ///
+    /// ### Errors
+    /// If donate calls are configured to fail
+    fn donate(e: Env, from: Address, pool_address: Address, amount: i128);
+
+    /// Mock Only: Set the backstop data reported for a pool
+    ///
+    /// ### Arguments
+    /// * `pool` - The pool address
+    /// * `data` - The backstop data to report
+    fn set_pool_data(e: Env, pool: Address, data: PoolBackstopData);
+
+    /// Mock Only: Set the emissions available for a pool to gulp
+    ///
+    /// ### Arguments
+    /// * `pool` - The pool address
+    /// * `emissions` - The emissions available to gulp
+    fn set_pool_emissions(e: Env, pool: Address, emissions: i128);
+
+    /// Mock Only: Set if draw calls should fail
+    ///
+    /// ### Arguments
+    /// * `fails` - If draw calls should fail
+    fn set_draw_fails(e: Env, fails: bool);
+
+    /// Mock Only: Set if donate calls should fail
+    ///
+    /// ### Arguments
+    /// * `fails` - If donate calls should fail
+    fn set_donate_fails(e: Env, fails: bool);
+}
+
+#[contractimpl]
+impl MockBackstopTrait for MockBackstop {
+    fn initialize(e: Env, backstop_token: Address, emitter: Address) {
+        storage::set_backstop_token(&e, &backstop_token);
+        storage::set_emitter(&e, &emitter);
+    }
+
+    fn pool_data(e: Env, pool: Address) -> PoolBackstopData {
+        storage::get_pool_data(&e, &pool)
+    }
+
+    fn backstop_token(e: Env) -> Address {
+        storage::get_backstop_token(&e)
+    }
+
+    fn emitter(e: Env) -> Address {
+        storage::get_emitter(&e)
+    }
+
+    fn gulp_pool_emissions(e: Env, pool_address: Address) -> i128 {
+        storage::extend_instance(&e);
+        let emissions = storage::get_pool_emissions(&e, &pool_address);
+        storage::set_pool_emissions(&e, &pool_address, 0);
+        emissions
+    }
+
+    fn draw(e: Env, pool_address: Address, amount: i128, to: Address) {
+        storage::extend_instance(&e);
+        pool_address.require_auth();
+        if storage::get_draw_fails(&e) {
+            panic_with_error!(&e, MockBackstopError::DrawFailed);
+        }
+
+        let mut pool_data = storage::get_pool_data(&e, &pool_address);
+        pool_data.tokens -= amount;
+        storage::set_pool_data(&e, &pool_address, &pool_data);
+
+        TokenClient::new(&e, &storage::get_backstop_token(&e)).transfer(
+            &e.current_contract_address(),
+            &to,
+            &amount,
+        );
+    }
+
+    fn donate(e: Env, from: Address, pool_address: Address, amount: i128) {
+        storage::extend_instance(&e);
+        from.require_auth();
+        pool_address.require_auth();
+        if storage::get_donate_fails(&e) {
+            panic_with_error!(&e, MockBackstopError::DonateFailed);
+        }
+
+        TokenClient::new(&e, &storage::get_backstop_token(&e)).transfer(
+            &from,
+            &e.current_contract_address(),
+            &amount,
+        );
+
+        let mut pool_data = storage::get_pool_data(&e, &pool_address);
+        pool_data.tokens += amount;
+        storage::set_pool_data(&e, &pool_address, &pool_data);
+    }
+
+    fn set_pool_data(e: Env, pool: Address, data: PoolBackstopData) {
+        storage::set_pool_data(&e, &pool, &data);
+    }
+
+    fn set_pool_emissions(e: Env, pool: Address, emissions: i128) {
+        storage::set_pool_emissions(&e, &pool, emissions);
+    }
+
+    fn set_draw_fails(e: Env, fails: bool) {
+        storage::set_draw_fails(&e, fails);
+    }
+
+    fn set_donate_fails(e: Env, fails: bool) {
+        storage::set_donate_fails(&e, fails);
+    }
+}
@@ -0,0 +1,12 @@
+#![no_std]
+
+#[cfg(any(test, feature = "testutils"))]
+extern crate std;
+
+mod backstop;
+mod errors;
+mod storage;
+
+pub use backstop::*;
+pub use errors::MockBackstopError;
+pub use storage::{MockBackstopDataKey, PoolBackstopData};
@@ -0,0 +1,165 @@
+use soroban_sdk::{contracttype, unwrap::UnwrapOptimized, Address, Env};
+
+/********** Ledger Thresholds **********/
+
+const ONE_DAY_LEDGERS: u32 = 17280; // assumes 5s a ledger
+
+const LEDGER_THRESHOLD_INSTANCE: u32 = ONE_DAY_LEDGERS * 30; // ~ 30 days
+const LEDGER_BUMP_INSTANCE: u32 = LEDGER_THRESHOLD_INSTANCE + ONE_DAY_LEDGERS; // ~ 31 days
+
+const LEDGER_THRESHOLD_SHARED: u32 = ONE_DAY_LEDGERS * 45; // ~ 45 days
+const LEDGER_BUMP_SHARED: u32 = LEDGER_THRESHOLD_SHARED + ONE_DAY_LEDGERS; // ~ 46 days
+
+#[derive(Clone)]
+#[contracttype]
+pub enum MockBackstopDataKey {
+    BackstopToken,
+    Emitter,
+    DrawFails,
+    DonateFails,
+    PoolData(Address),
+    PoolEmis(Address),
+}
+
+/// The pool's backstop data, as reported by the backstop
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub struct PoolBackstopData {
+    pub tokens: i128,  // the number of backstop tokens held in the pool's backstop
+    pub q4w_pct: i128, // the percentage of tokens queued for withdrawal
+    pub blnd: i128,    // the amount of blnd held in the pool's backstop via backstop tokens
+    pub usdc: i128,    // the amount of usdc held in the pool's backstop via backstop tokens
+}
+
+/// Bump the instance rent for the contract
+pub fn extend_instance(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(LEDGER_THRESHOLD_INSTANCE, LEDGER_BUMP_INSTANCE);
+}
+
+/// Fetch the backstop token address
+pub fn get_backstop_token(e: &Env) -> Address {
+    e.storage()
+        .instance()
+        .get::<MockBackstopDataKey, Address>(&MockBackstopDataKey::BackstopToken)
+        .unwrap_optimized()
+}
+
+/// Set the backstop token address
+///
+/// ### Arguments
+/// * `backstop_token` - The backstop token address
+pub fn set_backstop_token(e: &Env, backstop_token: &Address) {
+    e.storage()
+        .instance()
+        .set::<MockBackstopDataKey, Address>(&MockBackstopDataKey::BackstopToken, backstop_token);
+}
+
+/// Fetch the emitter address
+pub fn get_emitter(e: &Env) -> Address {
+    e.storage()
+        .instance()
+        .get::<MockBackstopDataKey, Address>(&MockBackstopDataKey::Emitter)
+        .unwrap_optimized()
+}
+
+/// Set the emitter address
+///
+/// ### Arguments
+/// * `emitter` - The emitter address
+pub fn set_emitter(e: &Env, emitter: &Address) {
+    e.storage()
+        .instance()
+        .set::<MockBackstopDataKey, Address>(&MockBackstopDataKey::Emitter, emitter);
+}
+
+/// Fetch if draw calls are configured to fail
+pub fn get_draw_fails(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get::<MockBackstopDataKey, bool>(&MockBackstopDataKey::DrawFails)
+        .unwrap_or(false)
+}
+
+/// Set if draw calls should fail
+///
+/// ### Arguments
+/// * `fails` - If draw calls should fail
+pub fn set_draw_fails(e: &Env, fails: bool) {
+    e.storage()
+        .instance()
+        .set::<MockBackstopDataKey, bool>(&MockBackstopDataKey::DrawFails, &fails);
+}
+
+/// Fetch if donate calls are configured to fail
+pub fn get_donate_fails(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get::<MockBackstopDataKey, bool>(&MockBackstopDataKey::DonateFails)
+        .unwrap_or(false)
+}
+
+/// Set if donate calls should fail
+///
+/// ### Arguments
+/// * `fails` - If donate calls should fail
+pub fn set_donate_fails(e: &Env, fails: bool) {
+    e.storage()
+        .instance()
+        .set::<MockBackstopDataKey, bool>(&MockBackstopDataKey::DonateFails, &fails);
+}
+
+/// Fetch the backstop data reported for a pool
+///
+/// ### Arguments
+/// * `pool` - The pool address
+pub fn get_pool_data(e: &Env, pool: &Address) -> PoolBackstopData {
+    let key = MockBackstopDataKey::PoolData(pool.clone());
+    e.storage()
+        .persistent()
+        .get::<MockBackstopDataKey, PoolBackstopData>(&key)
+        .unwrap_or_default()
+}
+
+/// Set the backstop data reported for a pool
+///
+/// ### Arguments
+/// * `pool` - The pool address
+/// * `data` - The backstop data to report
+pub fn set_pool_data(e: &Env, pool: &Address, data: &PoolBackstopData) {
+    let key = MockBackstopDataKey::PoolData(pool.clone());
+    e.storage()
+        .persistent()
+        .set::<MockBackstopDataKey, PoolBackstopData>(&key, data);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Fetch the emissions available for a pool to gulp
+///
+/// ### Arguments
+/// * `pool` - The pool address
+pub fn get_pool_emissions(e: &Env, pool: &Address) -> i128 {
+    let key = MockBackstopDataKey::PoolEmis(pool.clone());
+    e.storage()
+        .persistent()
+        .get::<MockBackstopDataKey, i128>(&key)
+        .unwrap_or(0)
+}
+
+/// Set the emissions available for a pool to gulp
+///
+/// ### Arguments
+/// * `pool` - The pool address
+/// * `emissions` - The emissions available to gulp
+pub fn set_pool_emissions(e: &Env, pool: &Address, emissions: i128) {
+    let key = MockBackstopDataKey::PoolEmis(pool.clone());
+    e.storage()
+        .persistent()
+        .set::<MockBackstopDataKey, i128>(&key, &emissions);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
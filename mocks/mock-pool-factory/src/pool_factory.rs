@@ -26,6 +26,7 @@ pub trait MockPoolFactoryTrait {
     /// * `name` - The name of the pool
     /// * `oracle` - The oracle address for the pool
     /// * `backstop_take_rate` - The backstop take rate for the pool (7 decimals)
+    /// * `q4w_lock_time` - The time in seconds backstop withdrawals are queued for
     fn deploy(
         e: Env,
         admin: Address,
@@ -34,6 +35,7 @@ pub trait MockPoolFactoryTrait {
         oracle: Address,
         backstop_take_rate: u32,
         max_positions: u32,
+        q4w_lock_time: u64,
     ) -> Address;
 
     /// Checks if contract address was deployed by the factory
@@ -68,6 +70,7 @@ impl MockPoolFactoryTrait for MockPoolFactory {
         oracle: Address,
         backstop_take_rate: u32,
         max_positions: u32,
+        q4w_lock_time: u64,
     ) -> Address {
         storage::extend_instance(&e);
         admin.require_auth();
@@ -86,12 +89,20 @@ impl MockPoolFactoryTrait for MockPoolFactory {
         init_args.push_back(max_positions.into_val(&e));
         init_args.push_back(pool_init_meta.backstop.to_val());
         init_args.push_back(pool_init_meta.blnd_id.to_val());
+        init_args.push_back(pool_init_meta.pause_registry.to_val());
 
         let pool_address = e.register_contract(None, PoolContract {});
         e.invoke_contract::<Val>(&pool_address, &Symbol::new(&e, "initialize"), init_args);
 
         storage::set_deployed(&e, &pool_address);
 
+        // register the pool's withdrawal queue lock time with the backstop
+        e.invoke_contract::<Val>(
+            &pool_init_meta.backstop,
+            &Symbol::new(&e, "register_pool"),
+            vec![&e, pool_address.to_val(), q4w_lock_time.into_val(&e)],
+        );
+
         e.events()
             .publish((Symbol::new(&e, "deploy"),), pool_address.clone());
         pool_address
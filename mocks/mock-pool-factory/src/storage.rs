@@ -23,6 +23,7 @@ pub struct PoolInitMeta {
     pub pool_hash: BytesN<32>,
     pub backstop: Address,
     pub blnd_id: Address,
+    pub pause_registry: Address,
 }
 
 /// Bump the instance rent for the contract
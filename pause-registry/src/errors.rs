@@ -0,0 +1,12 @@
+use soroban_sdk::contracterror;
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+/// Error codes for the pause registry contract. Common errors are codes that match up with the built-in
+/// contracts error reporting.
+pub enum PauseRegistryError {
+    // Common Errors
+    InternalError = 1,
+    AlreadyInitializedError = 3,
+}
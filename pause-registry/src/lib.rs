@@ -0,0 +1,12 @@
+#![no_std]
+
+#[cfg(any(test, feature = "testutils"))]
+extern crate std;
+
+mod errors;
+mod pause_registry;
+mod storage;
+mod test;
+
+pub use errors::PauseRegistryError;
+pub use pause_registry::*;
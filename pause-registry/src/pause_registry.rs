@@ -0,0 +1,92 @@
+use crate::{errors::PauseRegistryError, storage};
+use soroban_sdk::{contract, contractclient, contractimpl, panic_with_error, Address, Env, Symbol};
+
+/// ### Pause Registry
+///
+/// Holds a global emergency-pause flag consulted by the pools, backstop, and emitter
+/// of the Blend protocol. The admin is expected to be a multisig account.
+#[contract]
+pub struct PauseRegistryContract;
+
+#[contractclient(name = "PauseRegistryClient")]
+pub trait PauseRegistry {
+    /// Initialize the pause registry
+    ///
+    /// ### Arguments
+    /// * `admin` - The Address allowed to pause and unpause the protocol
+    ///
+    /// ### Errors
+    /// If initialize has already been called
+    fn initialize(e: Env, admin: Address);
+
+    /// (Admin only) Set a new address as the admin of the registry
+    ///
+    /// ### Arguments
+    /// * `new_admin` - The new admin address
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    fn set_admin(e: Env, new_admin: Address);
+
+    /// (Admin only) Set the global pause flag
+    ///
+    /// ### Arguments
+    /// * `paused` - True to pause the protocol, false to unpause it
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    fn set_paused(e: Env, paused: bool);
+
+    /// Fetch the admin of the registry
+    fn get_admin(e: Env) -> Address;
+
+    /// Fetch whether the protocol is paused
+    fn is_paused(e: Env) -> bool;
+}
+
+#[contractimpl]
+impl PauseRegistry for PauseRegistryContract {
+    fn initialize(e: Env, admin: Address) {
+        storage::extend_instance(&e);
+        if storage::get_is_init(&e) {
+            panic_with_error!(&e, PauseRegistryError::AlreadyInitializedError);
+        }
+
+        storage::set_admin(&e, &admin);
+        storage::set_paused(&e, false);
+
+        storage::set_is_init(&e);
+    }
+
+    fn set_admin(e: Env, new_admin: Address) {
+        storage::extend_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+        new_admin.require_auth();
+
+        storage::set_admin(&e, &new_admin);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_admin"), admin), new_admin);
+    }
+
+    fn set_paused(e: Env, paused: bool) {
+        storage::extend_instance(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        storage::set_paused(&e, paused);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_paused"), admin), paused);
+    }
+
+    fn get_admin(e: Env) -> Address {
+        storage::get_admin(&e)
+    }
+
+    fn is_paused(e: Env) -> bool {
+        storage::extend_instance(&e);
+        storage::get_paused(&e)
+    }
+}
@@ -0,0 +1,75 @@
+use soroban_sdk::{unwrap::UnwrapOptimized, Address, Env, Symbol};
+
+/********** Ledger Thresholds **********/
+
+const ONE_DAY_LEDGERS: u32 = 17280; // assumes 5s a ledger
+
+const LEDGER_THRESHOLD_INSTANCE: u32 = ONE_DAY_LEDGERS * 30; // ~ 30 days
+const LEDGER_BUMP_INSTANCE: u32 = LEDGER_THRESHOLD_INSTANCE + ONE_DAY_LEDGERS; // ~ 31 days
+
+/********** Storage **********/
+
+const IS_INIT_KEY: &str = "IsInit";
+const ADMIN_KEY: &str = "Admin";
+const PAUSED_KEY: &str = "Paused";
+
+/// Bump the instance rent for the contract
+pub fn extend_instance(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(LEDGER_THRESHOLD_INSTANCE, LEDGER_BUMP_INSTANCE);
+}
+
+/********** Init **********/
+
+/// Check if the contract has been initialized
+pub fn get_is_init(e: &Env) -> bool {
+    e.storage().instance().has(&Symbol::new(e, IS_INIT_KEY))
+}
+
+/// Set the contract as initialized
+pub fn set_is_init(e: &Env) {
+    e.storage()
+        .instance()
+        .set::<Symbol, bool>(&Symbol::new(e, IS_INIT_KEY), &true);
+}
+
+/********** Admin **********/
+
+/// Fetch the current admin Address
+pub fn get_admin(e: &Env) -> Address {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, ADMIN_KEY))
+        .unwrap_optimized()
+}
+
+/// Set a new admin
+///
+/// ### Arguments
+/// * `new_admin` - The Address for the admin
+pub fn set_admin(e: &Env, new_admin: &Address) {
+    e.storage()
+        .instance()
+        .set::<Symbol, Address>(&Symbol::new(e, ADMIN_KEY), new_admin);
+}
+
+/********** Pause **********/
+
+/// Fetch the current pause flag
+pub fn get_paused(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get::<Symbol, bool>(&Symbol::new(e, PAUSED_KEY))
+        .unwrap_or(false)
+}
+
+/// Set the pause flag
+///
+/// ### Arguments
+/// * `paused` - True if the protocol should be paused, false otherwise
+pub fn set_paused(e: &Env, paused: bool) {
+    e.storage()
+        .instance()
+        .set::<Symbol, bool>(&Symbol::new(e, PAUSED_KEY), &paused);
+}
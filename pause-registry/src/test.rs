@@ -0,0 +1,94 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events},
+    vec, Address, Env, IntoVal, Symbol,
+};
+
+use crate::{PauseRegistryClient, PauseRegistryContract};
+
+fn create_pause_registry(e: &Env) -> (Address, PauseRegistryClient) {
+    let contract_id = e.register_contract(None, PauseRegistryContract {});
+    (
+        contract_id.clone(),
+        PauseRegistryClient::new(e, &contract_id),
+    )
+}
+
+#[test]
+fn test_pause_registry() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (registry_address, registry_client) = create_pause_registry(&e);
+
+    let bombadil = Address::generate(&e);
+    registry_client.initialize(&bombadil);
+
+    // verify initialize can't be run twice
+    let result = registry_client.try_initialize(&bombadil);
+    assert!(result.is_err());
+
+    assert_eq!(registry_client.get_admin(), bombadil);
+    assert!(!registry_client.is_paused());
+
+    registry_client.set_paused(&true);
+    assert_eq!(
+        e.auths()[0],
+        (
+            bombadil.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    registry_address.clone(),
+                    Symbol::new(&e, "set_paused"),
+                    vec![&e, true.into_val(&e)]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let event = vec![&e, e.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &e,
+            (
+                registry_address.clone(),
+                (Symbol::new(&e, "set_paused"), bombadil.clone()).into_val(&e),
+                true.into_val(&e)
+            )
+        ]
+    );
+    assert!(registry_client.is_paused());
+
+    registry_client.set_paused(&false);
+    assert!(!registry_client.is_paused());
+}
+
+#[test]
+fn test_pause_registry_set_admin() {
+    let e = Env::default();
+    e.mock_all_auths();
+    let (_, registry_client) = create_pause_registry(&e);
+
+    let bombadil = Address::generate(&e);
+    let samwise = Address::generate(&e);
+    registry_client.initialize(&bombadil);
+
+    registry_client.set_admin(&samwise);
+    assert_eq!(e.auths().len(), 2);
+    assert_eq!(e.auths()[0].0, bombadil);
+    assert_eq!(e.auths()[1].0, samwise);
+    assert_eq!(registry_client.get_admin(), samwise);
+}
+
+#[test]
+#[should_panic]
+fn test_pause_registry_set_paused_requires_admin() {
+    let e = Env::default();
+    let (_, registry_client) = create_pause_registry(&e);
+
+    let bombadil = Address::generate(&e);
+    registry_client.initialize(&bombadil);
+
+    registry_client.set_paused(&true);
+}
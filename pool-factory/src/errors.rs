@@ -12,4 +12,6 @@ pub enum PoolFactoryError {
 
     // Pool Factory
     InvalidPoolInitArgs = 1300,
+    NotPool = 1301,
+    VerifierNotSet = 1302,
 }
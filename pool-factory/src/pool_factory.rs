@@ -29,6 +29,7 @@ pub trait PoolFactory {
     /// * `oracle` - The oracle address for the pool
     /// * `backstop_take_rate` - The backstop take rate for the pool (7 decimals)
     /// * `max_positions` - The maximum user positions supported by the pool
+    /// * `q4w_lock_time` - The time in seconds backstop withdrawals are queued for (7 to 30 days)
     fn deploy(
         e: Env,
         admin: Address,
@@ -37,6 +38,7 @@ pub trait PoolFactory {
         oracle: Address,
         backstop_take_rate: u32,
         max_positions: u32,
+        q4w_lock_time: u64,
     ) -> Address;
 
     /// Checks if contract address was deployed by the factory
@@ -46,6 +48,40 @@ pub trait PoolFactory {
     /// ### Arguments
     /// * `pool_id` - The contract address to be checked
     fn is_pool(e: Env, pool_id: Address) -> bool;
+
+    /// (Pause registry admin only) Set the verifier that can attest to deployed pools
+    ///
+    /// ### Arguments
+    /// * `verifier` - The verifier address
+    fn set_verifier(e: Env, verifier: Address);
+
+    /// Fetch the verifier that can attest to deployed pools, or None if it has not been set
+    fn get_verifier(e: Env) -> Option<Address>;
+
+    /// (Verifier only) Mark a pool deployed by the factory as reviewed
+    ///
+    /// ### Arguments
+    /// * `pool_id` - The pool address
+    /// * `metadata_hash` - The hash of the reviewed pool metadata
+    ///
+    /// ### Panics
+    /// If no verifier is set or the pool was not deployed by the factory
+    fn verify_pool(e: Env, pool_id: Address, metadata_hash: BytesN<32>);
+
+    /// (Verifier only) Remove the review attestation of a pool
+    ///
+    /// ### Arguments
+    /// * `pool_id` - The pool address
+    ///
+    /// ### Panics
+    /// If no verifier is set
+    fn revoke_pool(e: Env, pool_id: Address);
+
+    /// Fetch the metadata hash a pool was reviewed with, or None if the pool has not been reviewed
+    ///
+    /// ### Arguments
+    /// * `pool_id` - The pool address
+    fn get_verification(e: Env, pool_id: Address) -> Option<BytesN<32>>;
 }
 
 #[contractimpl]
@@ -69,6 +105,7 @@ impl PoolFactory for PoolFactoryContract {
         oracle: Address,
         backstop_take_rate: u32,
         max_positions: u32,
+        q4w_lock_time: u64,
     ) -> Address {
         admin.require_auth();
         storage::extend_instance(&e);
@@ -98,6 +135,7 @@ impl PoolFactory for PoolFactoryContract {
         init_args.push_back(max_positions.into_val(&e));
         init_args.push_back(pool_init_meta.backstop.to_val());
         init_args.push_back(pool_init_meta.blnd_id.to_val());
+        init_args.push_back(pool_init_meta.pause_registry.to_val());
         let pool_address = e
             .deployer()
             .with_current_contract(new_salt)
@@ -106,6 +144,13 @@ impl PoolFactory for PoolFactoryContract {
 
         storage::set_deployed(&e, &pool_address);
 
+        // register the pool's withdrawal queue lock time with the backstop
+        e.invoke_contract::<Val>(
+            &pool_init_meta.backstop,
+            &Symbol::new(&e, "register_pool"),
+            vec![&e, pool_address.to_val(), q4w_lock_time.into_val(&e)],
+        );
+
         e.events()
             .publish((Symbol::new(&e, "deploy"),), pool_address.clone());
         pool_address
@@ -115,4 +160,62 @@ impl PoolFactory for PoolFactoryContract {
         storage::extend_instance(&e);
         storage::is_deployed(&e, &pool_address)
     }
+
+    fn set_verifier(e: Env, verifier: Address) {
+        storage::extend_instance(&e);
+        let pool_init_meta = storage::get_pool_init_meta(&e);
+        let admin = e.invoke_contract::<Address>(
+            &pool_init_meta.pause_registry,
+            &Symbol::new(&e, "get_admin"),
+            vec![&e],
+        );
+        admin.require_auth();
+
+        storage::set_verifier(&e, &verifier);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_verifier"),), verifier);
+    }
+
+    fn get_verifier(e: Env) -> Option<Address> {
+        storage::get_verifier(&e)
+    }
+
+    fn verify_pool(e: Env, pool_id: Address, metadata_hash: BytesN<32>) {
+        storage::extend_instance(&e);
+        require_verifier(&e);
+        if !storage::is_deployed(&e, &pool_id) {
+            panic_with_error!(&e, PoolFactoryError::NotPool);
+        }
+
+        storage::set_verification(&e, &pool_id, &metadata_hash);
+
+        e.events()
+            .publish((Symbol::new(&e, "verify_pool"), pool_id), metadata_hash);
+    }
+
+    fn revoke_pool(e: Env, pool_id: Address) {
+        storage::extend_instance(&e);
+        require_verifier(&e);
+
+        storage::del_verification(&e, &pool_id);
+
+        e.events()
+            .publish((Symbol::new(&e, "revoke_pool"), pool_id), ());
+    }
+
+    fn get_verification(e: Env, pool_id: Address) -> Option<BytesN<32>> {
+        storage::get_verification(&e, &pool_id)
+    }
+}
+
+/// Require that the verifier has authorized the call
+///
+/// ### Panics
+/// If no verifier is set
+fn require_verifier(e: &Env) {
+    match storage::get_verifier(e) {
+        Some(verifier) => verifier.require_auth(),
+        None => panic_with_error!(e, PoolFactoryError::VerifierNotSet),
+    }
 }
@@ -11,11 +11,13 @@ const LEDGER_THRESHOLD_USER: u32 = ONE_DAY_LEDGERS * 100; // ~ 100 days
 const LEDGER_BUMP_USER: u32 = LEDGER_THRESHOLD_USER + 20 * ONE_DAY_LEDGERS; // ~ 120 days
 
 const IS_INIT_KEY: &str = "IsInit";
+const VERIFIER_KEY: &str = "Verifier";
 
 #[derive(Clone)]
 #[contracttype]
 pub enum PoolFactoryDataKey {
     Contracts(Address),
+    Verified(Address),
 }
 
 #[derive(Clone)]
@@ -24,6 +26,7 @@ pub struct PoolInitMeta {
     pub pool_hash: BytesN<32>,
     pub backstop: Address,
     pub blnd_id: Address,
+    pub pause_registry: Address,
 }
 
 /// Bump the instance rent for the contract
@@ -96,3 +99,64 @@ pub fn set_deployed(e: &Env, contract_id: &Address) {
         .persistent()
         .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
 }
+
+/// Fetch the verifier that can attest to deployed pools, or None if it has not been set
+pub fn get_verifier(e: &Env) -> Option<Address> {
+    e.storage()
+        .instance()
+        .get::<Symbol, Address>(&Symbol::new(e, VERIFIER_KEY))
+}
+
+/// Set the verifier that can attest to deployed pools
+///
+/// ### Arguments
+/// * `verifier` - The verifier address
+pub fn set_verifier(e: &Env, verifier: &Address) {
+    e.storage()
+        .instance()
+        .set::<Symbol, Address>(&Symbol::new(e, VERIFIER_KEY), verifier);
+}
+
+/// Fetch the metadata hash a pool was verified with, or None if the pool is not verified
+///
+/// ### Arguments
+/// * `contract_id` - The pool contract_id
+pub fn get_verification(e: &Env, contract_id: &Address) -> Option<BytesN<32>> {
+    let key = PoolFactoryDataKey::Verified(contract_id.clone());
+    if let Some(result) = e
+        .storage()
+        .persistent()
+        .get::<PoolFactoryDataKey, BytesN<32>>(&key)
+    {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+        Some(result)
+    } else {
+        None
+    }
+}
+
+/// Set the metadata hash a pool was verified with
+///
+/// ### Arguments
+/// * `contract_id` - The pool contract_id
+/// * `metadata_hash` - The hash of the reviewed pool metadata
+pub fn set_verification(e: &Env, contract_id: &Address, metadata_hash: &BytesN<32>) {
+    let key = PoolFactoryDataKey::Verified(contract_id.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolFactoryDataKey, BytesN<32>>(&key, metadata_hash);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Remove the verification of a pool
+///
+/// ### Arguments
+/// * `contract_id` - The pool contract_id
+pub fn del_verification(e: &Env, contract_id: &Address) {
+    let key = PoolFactoryDataKey::Verified(contract_id.clone());
+    e.storage().persistent().remove(&key);
+}
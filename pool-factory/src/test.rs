@@ -1,16 +1,54 @@
 #![cfg(test)]
 
 use soroban_sdk::{
-    testutils::{Address as _, BytesN as _, Events},
-    vec, Address, BytesN, Env, IntoVal, String, Symbol,
+    contract, contractimpl,
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, BytesN as _, Events},
+    vec, Address, BytesN, Env, Error, IntoVal, String, Symbol,
 };
 
-use crate::{PoolFactoryClient, PoolFactoryContract, PoolInitMeta};
+use crate::{PoolFactoryClient, PoolFactoryContract, PoolFactoryError, PoolInitMeta};
+
+const Q4W_LOCK_TIME: u64 = 14 * 24 * 60 * 60;
 
 mod pool {
     soroban_sdk::contractimport!(file = "../target/wasm32-unknown-unknown/optimized/pool.wasm");
 }
 
+/// Backstop that records the Q4W lock time pools are registered with
+#[contract]
+struct MockBackstop;
+
+#[contractimpl]
+impl MockBackstop {
+    pub fn register_pool(e: Env, pool_address: Address, q4w_lock_time: u64) {
+        e.storage().persistent().set(&pool_address, &q4w_lock_time);
+    }
+
+    pub fn q4w_lock_time(e: Env, pool: Address) -> u64 {
+        e.storage().persistent().get(&pool).unwrap()
+    }
+}
+
+/// Pause registry that reports a fixed admin
+#[contract]
+struct MockPauseRegistry;
+
+#[contractimpl]
+impl MockPauseRegistry {
+    pub fn set_admin(e: Env, admin: Address) {
+        e.storage()
+            .instance()
+            .set(&Symbol::new(&e, "Admin"), &admin);
+    }
+
+    pub fn get_admin(e: Env) -> Address {
+        e.storage()
+            .instance()
+            .get(&Symbol::new(&e, "Admin"))
+            .unwrap()
+    }
+}
+
 fn create_pool_factory(e: &Env) -> (Address, PoolFactoryClient) {
     let contract_id = e.register_contract(None, PoolFactoryContract {});
     (contract_id.clone(), PoolFactoryClient::new(e, &contract_id))
@@ -28,15 +66,17 @@ fn test_pool_factory() {
     let bombadil = Address::generate(&e);
 
     let oracle = Address::generate(&e);
-    let backstop_id = Address::generate(&e);
+    let backstop_id = e.register_contract(None, MockBackstop {});
     let backstop_rate: u32 = 0_1000000;
     let max_positions: u32 = 6;
     let blnd_id = Address::generate(&e);
+    let pause_registry = Address::generate(&e);
 
     let pool_init_meta = PoolInitMeta {
         backstop: backstop_id.clone(),
         pool_hash: wasm_hash.clone(),
         blnd_id: blnd_id.clone(),
+        pause_registry: pause_registry.clone(),
     };
     pool_factory_client.initialize(&pool_init_meta);
 
@@ -55,6 +95,7 @@ fn test_pool_factory() {
         &oracle,
         &backstop_rate,
         &max_positions,
+        &Q4W_LOCK_TIME,
     );
 
     let event = vec![&e, e.events().all().last_unchecked()];
@@ -78,6 +119,7 @@ fn test_pool_factory() {
         &oracle,
         &backstop_rate,
         &max_positions,
+        &Q4W_LOCK_TIME,
     );
 
     e.as_contract(&deployed_pool_address_1, || {
@@ -114,7 +156,19 @@ fn test_pool_factory() {
                 .unwrap(),
             blnd_id.clone()
         );
+        assert_eq!(
+            e.storage()
+                .instance()
+                .get::<_, Address>(&Symbol::new(&e, "PauseReg"))
+                .unwrap(),
+            pause_registry.clone()
+        );
     });
+    let backstop_client = MockBackstopClient::new(&e, &backstop_id);
+    assert_eq!(
+        backstop_client.q4w_lock_time(&deployed_pool_address_1),
+        Q4W_LOCK_TIME
+    );
     assert_ne!(deployed_pool_address_1, deployed_pool_address_2);
     assert!(pool_factory_client.is_pool(&deployed_pool_address_1));
     assert!(pool_factory_client.is_pool(&deployed_pool_address_2));
@@ -133,11 +187,13 @@ fn test_pool_factory_invalid_pool_init_args_backstop_rate() {
 
     let backstop_id = Address::generate(&e);
     let blnd_id = Address::generate(&e);
+    let pause_registry = Address::generate(&e);
 
     let pool_init_meta = PoolInitMeta {
         backstop: backstop_id.clone(),
         pool_hash: wasm_hash.clone(),
         blnd_id: blnd_id.clone(),
+        pause_registry: pause_registry.clone(),
     };
     pool_factory_client.initialize(&pool_init_meta);
 
@@ -156,6 +212,7 @@ fn test_pool_factory_invalid_pool_init_args_backstop_rate() {
         &oracle,
         &backstop_rate,
         &max_positions,
+        &Q4W_LOCK_TIME,
     );
 }
 
@@ -171,11 +228,13 @@ fn test_pool_factory_invalid_pool_init_args_max_positions() {
 
     let backstop_id = Address::generate(&e);
     let blnd_id = Address::generate(&e);
+    let pause_registry = Address::generate(&e);
 
     let pool_init_meta = PoolInitMeta {
         backstop: backstop_id.clone(),
         pool_hash: wasm_hash.clone(),
         blnd_id: blnd_id.clone(),
+        pause_registry: pause_registry.clone(),
     };
     pool_factory_client.initialize(&pool_init_meta);
 
@@ -194,6 +253,7 @@ fn test_pool_factory_invalid_pool_init_args_max_positions() {
         &oracle,
         &backstop_rate,
         &max_positions,
+        &Q4W_LOCK_TIME,
     );
 }
 
@@ -211,15 +271,17 @@ fn test_pool_factory_frontrun_protection() {
     let sauron = Address::generate(&e);
 
     let oracle = Address::generate(&e);
-    let backstop_id = Address::generate(&e);
+    let backstop_id = e.register_contract(None, MockBackstop {});
     let backstop_rate: u32 = 0_1000000;
     let max_positions: u32 = 6;
     let blnd_id = Address::generate(&e);
+    let pause_registry = Address::generate(&e);
 
     let pool_init_meta = PoolInitMeta {
         backstop: backstop_id.clone(),
         pool_hash: wasm_hash.clone(),
         blnd_id: blnd_id.clone(),
+        pause_registry: pause_registry.clone(),
     };
     pool_factory_client.initialize(&pool_init_meta);
 
@@ -237,6 +299,7 @@ fn test_pool_factory_frontrun_protection() {
         &oracle,
         &backstop_rate,
         &max_positions,
+        &Q4W_LOCK_TIME,
     );
     e.budget().print();
 
@@ -247,9 +310,104 @@ fn test_pool_factory_frontrun_protection() {
         &oracle,
         &backstop_rate,
         &max_positions,
+        &Q4W_LOCK_TIME,
     );
 
     assert!(deployed_pool_address_sauron != deployed_pool_address_bombadil);
     assert!(pool_factory_client.is_pool(&deployed_pool_address_sauron));
     assert!(pool_factory_client.is_pool(&deployed_pool_address_bombadil));
 }
+
+#[test]
+fn test_pool_factory_verify_pool() {
+    let e = Env::default();
+    e.budget().reset_unlimited();
+    e.mock_all_auths();
+    let (pool_factory_address, pool_factory_client) = create_pool_factory(&e);
+
+    let wasm_hash = e.deployer().upload_contract_wasm(pool::WASM);
+
+    let bombadil = Address::generate(&e);
+    let frodo = Address::generate(&e);
+
+    let pause_registry = e.register_contract(None, MockPauseRegistry {});
+    MockPauseRegistryClient::new(&e, &pause_registry).set_admin(&bombadil);
+
+    let pool_init_meta = PoolInitMeta {
+        backstop: e.register_contract(None, MockBackstop {}),
+        pool_hash: wasm_hash.clone(),
+        blnd_id: Address::generate(&e),
+        pause_registry: pause_registry.clone(),
+    };
+    pool_factory_client.initialize(&pool_init_meta);
+
+    let pool_address = pool_factory_client.deploy(
+        &bombadil,
+        &String::from_str(&e, "pool1"),
+        &BytesN::<32>::random(&e),
+        &Address::generate(&e),
+        &0_1000000,
+        &6,
+        &Q4W_LOCK_TIME,
+    );
+    let metadata_hash = BytesN::<32>::random(&e);
+
+    // verifying requires a verifier
+    let result = pool_factory_client.try_verify_pool(&pool_address, &metadata_hash);
+    assert_eq!(
+        result.err(),
+        Some(Ok(Error::from_contract_error(
+            PoolFactoryError::VerifierNotSet as u32
+        )))
+    );
+
+    pool_factory_client.set_verifier(&frodo);
+    assert_eq!(
+        e.auths()[0],
+        (
+            bombadil.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    pool_factory_address.clone(),
+                    Symbol::new(&e, "set_verifier"),
+                    vec![&e, frodo.to_val()]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    assert_eq!(pool_factory_client.get_verifier(), Some(frodo.clone()));
+    assert_eq!(pool_factory_client.get_verification(&pool_address), None);
+
+    pool_factory_client.verify_pool(&pool_address, &metadata_hash);
+    assert_eq!(
+        e.auths()[0],
+        (
+            frodo.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    pool_factory_address.clone(),
+                    Symbol::new(&e, "verify_pool"),
+                    vec![&e, pool_address.to_val(), metadata_hash.to_val()]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    assert_eq!(
+        pool_factory_client.get_verification(&pool_address),
+        Some(metadata_hash.clone())
+    );
+
+    // only pools deployed by the factory can be verified
+    let result = pool_factory_client.try_verify_pool(&Address::generate(&e), &metadata_hash);
+    assert_eq!(
+        result.err(),
+        Some(Ok(Error::from_contract_error(
+            PoolFactoryError::NotPool as u32
+        )))
+    );
+
+    pool_factory_client.revoke_pool(&pool_address);
+    assert_eq!(pool_factory_client.get_verification(&pool_address), None);
+}
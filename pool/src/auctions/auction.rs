@@ -1,19 +1,21 @@
 use crate::{
-    constants::SCALAR_7,
-    errors::PoolError,
-    pool::{Pool, User},
-    storage,
+    constants::{MAX_MIN_LIQUIDATION_PCT, SCALAR_7},
+    dependencies::BackstopClient,
+    errors::{panic_with_context, PoolError},
+    pool::{Pool, Reserve, User},
+    storage::{self, AuctionCurve},
 };
 use cast::i128;
 use soroban_fixed_point_math::FixedPoint;
-use soroban_sdk::{
-    contracttype, map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map, Vec,
-};
+use soroban_sdk::{contracttype, map, unwrap::UnwrapOptimized, vec, Address, Env, Map, Vec};
 
 use super::{
     backstop_interest_auction::{create_interest_auction_data, fill_interest_auction},
     bad_debt_auction::{create_bad_debt_auction_data, fill_bad_debt_auction},
-    user_liquidation_auction::{create_user_liq_auction_data, fill_user_liq_auction},
+    surplus_auction::{create_surplus_auction_data, fill_surplus_auction},
+    user_liquidation_auction::{
+        create_user_liq_auction_data, fill_user_liq_auction, is_user_liquidatable,
+    },
 };
 
 #[derive(Clone, PartialEq)]
@@ -22,6 +24,7 @@ pub enum AuctionType {
     UserLiquidation = 0,
     BadDebtAuction = 1,
     InterestAuction = 2,
+    SurplusAuction = 3,
 }
 
 impl AuctionType {
@@ -30,7 +33,8 @@ impl AuctionType {
             0 => AuctionType::UserLiquidation,
             1 => AuctionType::BadDebtAuction,
             2 => AuctionType::InterestAuction,
-            _ => panic_with_error!(e, PoolError::BadRequest),
+            3 => AuctionType::SurplusAuction,
+            _ => panic_with_context(e, PoolError::BadRequest, None, None),
         }
     }
 }
@@ -43,6 +47,16 @@ pub struct AuctionData {
     pub block: u32,
 }
 
+/// A quote for filling part of an auction at the current block
+#[derive(Clone)]
+#[contracttype]
+pub struct FillPreview {
+    pub bid: Map<Address, i128>, // the scaled bid the filler would pay
+    pub lot: Map<Address, i128>, // the scaled lot the filler would receive
+    pub bid_value: i128,         // the value of the bid in the oracle's base asset
+    pub lot_value: i128,         // the value of the lot in the oracle's base asset
+}
+
 /// Create a bad debt auction. Stores the resulting auction to the ledger to begin on the next block
 ///
 /// Returns the AuctionData object created.
@@ -59,6 +73,7 @@ pub fn create_bad_debt_auction(e: &Env) -> AuctionData {
         &backstop,
         &auction_data,
     );
+    add_to_auction_list(e, AuctionType::BadDebtAuction as u32, &backstop);
 
     auction_data
 }
@@ -82,27 +97,94 @@ pub fn create_interest_auction(e: &Env, assets: &Vec<Address>) -> AuctionData {
         &backstop,
         &auction_data,
     );
+    add_to_auction_list(e, AuctionType::InterestAuction as u32, &backstop);
+
+    auction_data
+}
+
+/// Create a surplus auction for a user whose remaining collateral exceeds their remaining
+/// liabilities after a liquidation auction against them was filled. Stores the resulting auction
+/// to the ledger to begin on the next block
+///
+/// Returns the AuctionData object created.
+///
+/// ### Arguments
+/// * `user` - The user whose surplus collateral is being auctioned
+///
+/// ### Panics
+/// If the auction is unable to be created
+pub fn create_surplus_auction(e: &Env, user: &Address) -> AuctionData {
+    let auction_data = create_surplus_auction_data(e, user);
+
+    storage::del_liquidation_filled(e, user);
+    storage::set_auction(
+        e,
+        &(AuctionType::SurplusAuction as u32),
+        user,
+        &auction_data,
+    );
+    add_to_auction_list(e, AuctionType::SurplusAuction as u32, user);
 
     auction_data
 }
 
+/// Set or remove the minimum percent of a user's liabilities a liquidation auction can liquidate
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `percent` - The minimum percent liquidated (15 => 15%), or None to remove the minimum
+///
+/// ### Panics
+/// If the percent is zero or over the maximum minimum liquidation percent
+pub fn execute_set_min_liquidation(e: &Env, user: &Address, percent: Option<u64>) {
+    match percent {
+        Some(percent) => {
+            if percent == 0 || percent > MAX_MIN_LIQUIDATION_PCT {
+                panic_with_context(e, PoolError::BadRequest, None, Some(user.clone()));
+            }
+            storage::set_min_liquidation(e, user, percent);
+        }
+        None => storage::del_min_liquidation(e, user),
+    }
+}
+
 /// Create a liquidation auction. Stores the resulting auction to the ledger to begin on the next block
 ///
 /// Returns the AuctionData object created.
 ///
 /// ### Arguments
 /// * `user` - The user being liquidated
-/// * `liq_data` - The liquidation metadata
+/// * `percent_liquidated` - The percent of the bid liabilities being liquidated (15 => 15%)
+/// * `bid_assets` - The liability assets to include in the bid, or an empty vec to include all
+///   of the user's liabilities
 ///
 /// ### Panics
-/// If the auction is unable to be created
-pub fn create_liquidation(e: &Env, user: &Address, percent_liquidated: u64) -> AuctionData {
+/// If the auction is unable to be created, or if the pool is within the liquidation grace period
+/// after returning to an active status
+pub fn create_liquidation(
+    e: &Env,
+    user: &Address,
+    percent_liquidated: u64,
+    bid_assets: &Vec<Address>,
+) -> AuctionData {
     let user_clone = user.clone();
     if user_clone == e.current_contract_address() || user_clone == storage::get_backstop(e) {
-        panic_with_error!(e, PoolError::InvalidLiquidation);
+        panic_with_context(e, PoolError::InvalidLiquidation, None, Some(user_clone));
+    }
+    // users have a grace period to restore their positions after the pool returns to an active
+    // status, as they may have been unable to act while the pool was on-ice or frozen
+    if let Some(unfreeze_time) = storage::get_unfreeze_time(e) {
+        if e.ledger().timestamp() < unfreeze_time + storage::get_liquidation_grace(e) {
+            panic_with_context(
+                e,
+                PoolError::LiquidationGracePeriod,
+                None,
+                Some(user.clone()),
+            );
+        }
     }
 
-    let auction_data = create_user_liq_auction_data(e, user, percent_liquidated);
+    let auction_data = create_user_liq_auction_data(e, user, percent_liquidated, bid_assets);
 
     storage::set_auction(
         e,
@@ -110,10 +192,38 @@ pub fn create_liquidation(e: &Env, user: &Address, percent_liquidated: u64) -> A
         user,
         &auction_data,
     );
+    add_to_auction_list(e, AuctionType::UserLiquidation as u32, user);
 
     auction_data
 }
 
+/// Create liquidation auctions for a batch of users. Stores the resulting auctions to the ledger to
+/// begin on the next block. Users whose liabilities are covered by their collateral are skipped.
+///
+/// Returns the (user, AuctionData) for each auction created and the users that were skipped
+///
+/// ### Arguments
+/// * `liquidations` - A vec of (user, percent_liquidated) for each user to liquidate
+///
+/// ### Panics
+/// If an auction for a user that is not skipped is unable to be created
+pub fn create_liquidations(
+    e: &Env,
+    liquidations: &Vec<(Address, u64)>,
+) -> (Vec<(Address, AuctionData)>, Vec<Address>) {
+    let mut created: Vec<(Address, AuctionData)> = vec![e];
+    let mut skipped: Vec<Address> = vec![e];
+    for (user, percent_liquidated) in liquidations.iter() {
+        if is_user_liquidatable(e, &user) {
+            let auction_data = create_liquidation(e, &user, percent_liquidated, &vec![e]);
+            created.push_back((user, auction_data));
+        } else {
+            skipped.push_back(user);
+        }
+    }
+    (created, skipped)
+}
+
 /// Delete a liquidation auction if the user being liquidated
 ///
 /// NOTE: Does not verify if the user's positions are healthy. This must be done before calling.
@@ -125,9 +235,10 @@ pub fn create_liquidation(e: &Env, user: &Address, percent_liquidated: u64) -> A
 /// If no auction exists for the user
 pub fn delete_liquidation(e: &Env, user: &Address) {
     if !storage::has_auction(e, &(AuctionType::UserLiquidation as u32), user) {
-        panic_with_error!(e, PoolError::BadRequest);
+        panic_with_context(e, PoolError::BadRequest, None, Some(user.clone()));
     }
     storage::del_auction(e, &(AuctionType::UserLiquidation as u32), user);
+    remove_from_auction_list(e, AuctionType::UserLiquidation as u32, user);
 }
 
 /// Fills the auction from the invoker.
@@ -139,6 +250,8 @@ pub fn delete_liquidation(e: &Env, user: &Address) {
 /// * `filler_state` - The Address filling the auction
 /// * `percent_filled` - The percentage being filled as a number (i.e. 15 => 15%)
 ///
+/// Returns the portion of the auction that was filled
+///
 /// ### Panics
 /// If the auction does not exist, or if the pool is unable to fulfill either side
 /// of the auction quote
@@ -149,13 +262,19 @@ pub fn fill(
     user: &Address,
     filler_state: &mut User,
     percent_filled: u64,
-) {
+) -> AuctionData {
     if user.clone() == filler_state.address {
-        panic_with_error!(e, PoolError::InvalidLiquidation);
+        panic_with_context(e, PoolError::InvalidLiquidation, None, Some(user.clone()));
     }
-    let auction_data = storage::get_auction(e, &auction_type, user);
-    let (to_fill_auction, remaining_auction) = scale_auction(e, &auction_data, percent_filled);
-    match AuctionType::from_u32(e, auction_type) {
+    // filling the rest of an auction is always allowed
+    if percent_filled < 100 && percent_filled < storage::get_min_fill_pct(e) {
+        panic_with_context(e, PoolError::FillTooSmall, None, Some(user.clone()));
+    }
+    let (auction_data, curve) = storage::get_auction_with_curve(e, &auction_type, user);
+    let auction_type_enum = AuctionType::from_u32(e, auction_type);
+    let (to_fill_auction, remaining_auction) =
+        scale_fill(e, &auction_type_enum, &auction_data, &curve, percent_filled);
+    match auction_type_enum {
         AuctionType::UserLiquidation => {
             fill_user_liq_auction(e, pool, &to_fill_auction, user, filler_state)
         }
@@ -165,20 +284,202 @@ pub fn fill(
         AuctionType::InterestAuction => {
             fill_interest_auction(e, pool, &to_fill_auction, &filler_state.address)
         }
+        AuctionType::SurplusAuction => {
+            fill_surplus_auction(e, pool, &to_fill_auction, user, filler_state)
+        }
     };
 
     if let Some(auction_to_store) = remaining_auction {
-        storage::set_auction(e, &auction_type, user, &auction_to_store);
+        storage::set_auction_with_curve(e, &auction_type, user, &auction_to_store, &curve);
     } else {
         storage::del_auction(e, &auction_type, user);
+        remove_from_auction_list(e, auction_type, user);
+    }
+    to_fill_auction
+}
+
+/// Preview filling an auction at the current block without modifying any state
+///
+/// Returns the scaled bid and lot, and their values in the oracle's base asset. bTokens and
+/// dTokens are valued at the reserve's current rates, and backstop tokens are valued from the
+/// USDC held in the pool's backstop.
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction to preview
+/// * `user` - The Address involved in the auction
+/// * `percent_filled` - The percentage being filled as a number (i.e. 15 => 15%)
+///
+/// ### Panics
+/// If the auction does not exist or the percent filled is not within (0, 100]
+pub fn preview_fill(
+    e: &Env,
+    auction_type: u32,
+    user: &Address,
+    percent_filled: u64,
+) -> FillPreview {
+    let (auction_data, curve) = storage::get_auction_with_curve(e, &auction_type, user);
+    let auction_type_enum = AuctionType::from_u32(e, auction_type);
+    let (to_fill_auction, _) =
+        scale_fill(e, &auction_type_enum, &auction_data, &curve, percent_filled);
+
+    let mut pool = Pool::load(e);
+    let (bid_value, lot_value) = match auction_type_enum {
+        AuctionType::UserLiquidation | AuctionType::SurplusAuction => (
+            calc_reserve_value(e, &mut pool, &to_fill_auction.bid, |reserve, d_tokens| {
+                reserve.to_asset_from_d_token(d_tokens)
+            }),
+            calc_reserve_value(e, &mut pool, &to_fill_auction.lot, |reserve, b_tokens| {
+                reserve.to_asset_from_b_token(b_tokens)
+            }),
+        ),
+        AuctionType::BadDebtAuction => (
+            calc_reserve_value(e, &mut pool, &to_fill_auction.bid, |reserve, d_tokens| {
+                reserve.to_asset_from_d_token(d_tokens)
+            }),
+            calc_backstop_token_value(e, &mut pool, &to_fill_auction.lot),
+        ),
+        AuctionType::InterestAuction => (
+            calc_backstop_token_value(e, &mut pool, &to_fill_auction.bid),
+            calc_reserve_value(e, &mut pool, &to_fill_auction.lot, |_, amount| amount),
+        ),
+    };
+
+    FillPreview {
+        bid: to_fill_auction.bid,
+        lot: to_fill_auction.lot,
+        bid_value,
+        lot_value,
+    }
+}
+
+/// Value reserve token amounts in the oracle's base asset
+///
+/// ### Arguments
+/// * `amounts` - The token amounts keyed by reserve asset
+/// * `to_underlying` - Converts a token amount of a reserve to underlying
+fn calc_reserve_value(
+    e: &Env,
+    pool: &mut Pool,
+    amounts: &Map<Address, i128>,
+    to_underlying: impl Fn(&Reserve, i128) -> i128,
+) -> i128 {
+    let mut value = 0;
+    for (res_asset_address, amount) in amounts.iter() {
+        let reserve = pool.load_reserve(e, &res_asset_address, false);
+        let asset_to_base = pool.load_price(e, &res_asset_address);
+        value += asset_to_base
+            .fixed_mul_floor(to_underlying(&reserve, amount), reserve.scalar)
+            .unwrap_optimized();
+    }
+    value
+}
+
+/// Value backstop token amounts in the oracle's base asset
+fn calc_backstop_token_value(e: &Env, pool: &mut Pool, amounts: &Map<Address, i128>) -> i128 {
+    let oracle_scalar = 10i128.pow(pool.load_price_decimals(e));
+    let backstop_client = BackstopClient::new(e, &storage::get_backstop(e));
+    let pool_backstop_data = backstop_client.pool_data(&e.current_contract_address());
+    // the backstop LP token is an 80/20 split of BLND/USDC, so the USDC held is 20% of its value
+    let backstop_token_to_base = (pool_backstop_data
+        .usdc
+        .fixed_mul_floor(oracle_scalar, SCALAR_7)
+        .unwrap_optimized()
+        * 5)
+    .fixed_div_floor(pool_backstop_data.tokens, SCALAR_7)
+    .unwrap_optimized();
+    let mut value = 0;
+    for (_, amount) in amounts.iter() {
+        value += amount
+            .fixed_mul_floor(backstop_token_to_base, SCALAR_7)
+            .unwrap_optimized();
+    }
+    value
+}
+
+/// Fetch the active auctions of a given type
+///
+/// Returns a vec of (user, auction data) for each active auction
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction to fetch
+/// * `start` - The index in the auction list to start from
+/// * `limit` - The maximum number of auctions to return
+pub fn get_auctions(
+    e: &Env,
+    auction_type: u32,
+    start: u32,
+    limit: u32,
+) -> Vec<(Address, AuctionData)> {
+    let mut auctions = vec![e];
+    let end = start
+        .saturating_add(limit)
+        .min(storage::get_auction_count(e, &auction_type));
+    for index in start..end {
+        let user = storage::get_auction_list_entry(e, &auction_type, index);
+        // skip auctions that have expired from temporary storage
+        if storage::has_auction(e, &auction_type, &user) {
+            let auction_data = storage::get_auction(e, &auction_type, &user);
+            auctions.push_back((user, auction_data));
+        }
+    }
+    auctions
+}
+
+/// Add a user to the list of active auctions of a given type. Each entry is stored under its
+/// own key, so the cost of updating the list does not grow with its length.
+fn add_to_auction_list(e: &Env, auction_type: u32, user: &Address) {
+    if storage::get_auction_list_index(e, &auction_type, user).is_none() {
+        let count = storage::get_auction_count(e, &auction_type);
+        storage::set_auction_list_entry(e, &auction_type, count, user);
+        storage::set_auction_count(e, &auction_type, count + 1);
+    }
+}
+
+/// Remove a user from the list of active auctions of a given type. The last entry is moved
+/// into the removed entry's position.
+fn remove_from_auction_list(e: &Env, auction_type: u32, user: &Address) {
+    if let Some(index) = storage::get_auction_list_index(e, &auction_type, user) {
+        let last_index = storage::get_auction_count(e, &auction_type) - 1;
+        let last_user = storage::get_auction_list_entry(e, &auction_type, last_index);
+        storage::del_auction_list_entry(e, &auction_type, last_index, user);
+        if index != last_index {
+            storage::set_auction_list_entry(e, &auction_type, index, &last_user);
+        }
+        storage::set_auction_count(e, &auction_type, last_index);
+    }
+}
+
+/// Scale the auction to be filled. Surplus auctions are filled at the fixed price they were created
+/// at, and all other auctions follow their auction curve.
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `auction_data` - The auction data to scale
+/// * `curve` - The auction curve the auction was created with
+/// * `percent_filled` - The percentage being filled as a number (i.e. 15 => 15%)
+///
+/// Returns the (Scaled Auction, Remaining Auction)
+fn scale_fill(
+    e: &Env,
+    auction_type: &AuctionType,
+    auction_data: &AuctionData,
+    curve: &AuctionCurve,
+    percent_filled: u64,
+) -> (AuctionData, Option<AuctionData>) {
+    match auction_type {
+        AuctionType::SurplusAuction => {
+            split_auction(e, auction_data, percent_filled, SCALAR_7, SCALAR_7)
+        }
+        _ => scale_auction(e, auction_data, curve, percent_filled),
     }
 }
 
 /// Scale the auction based on the percent being filled and the amount of blocks that have passed
-/// since the auction began.
+/// since the auction began, following the auction's curve.
 ///
 /// ### Arguments
 /// * `auction_data` - The auction data to scale
+/// * `curve` - The auction curve the auction was created with
 /// * `percent_filled` - The percentage being filled as a number (i.e. 15 => 15%)
 ///
 /// Returns the (Scaled Auction, Remaining Auction) such that:
@@ -191,42 +492,75 @@ pub fn fill(
 fn scale_auction(
     e: &Env,
     auction_data: &AuctionData,
+    curve: &AuctionCurve,
     percent_filled: u64,
 ) -> (AuctionData, Option<AuctionData>) {
-    if percent_filled > 100 || percent_filled == 0 {
-        panic_with_error!(e, PoolError::BadRequest);
-    }
-
-    let mut to_fill_auction = AuctionData {
-        bid: map![e],
-        lot: map![e],
-        block: auction_data.block,
-    };
-    let mut remaining_auction = AuctionData {
-        bid: map![e],
-        lot: map![e],
-        block: auction_data.block,
-    };
-
     // determine block based auction modifiers
     let bid_modifier: i128;
     let lot_modifier: i128;
-    let per_block_scalar: i128 = 0_0050000; // modifier moves 0.5% every block
+    let lot_blocks = i128(curve.lot_blocks);
+    let bid_blocks = i128(curve.bid_blocks);
     let block_dif = i128(e.ledger().sequence() - auction_data.block);
-    if block_dif > 200 {
+    if block_dif > lot_blocks {
         // lot 100%, bid scaling down from 100% to 0%
         lot_modifier = SCALAR_7;
-        if block_dif < 400 {
-            bid_modifier = SCALAR_7 - (block_dif - 200) * per_block_scalar;
+        let bid_block_dif = block_dif - lot_blocks;
+        if bid_block_dif < bid_blocks {
+            bid_modifier = SCALAR_7
+                - bid_block_dif
+                    .fixed_div_floor(bid_blocks, SCALAR_7)
+                    .unwrap_optimized();
         } else {
             bid_modifier = 0;
         }
     } else {
         // lot scaling from 0% to 100%, bid 100%
-        lot_modifier = block_dif * per_block_scalar;
+        lot_modifier = block_dif
+            .fixed_div_floor(lot_blocks, SCALAR_7)
+            .unwrap_optimized();
         bid_modifier = SCALAR_7;
     }
 
+    split_auction(e, auction_data, percent_filled, bid_modifier, lot_modifier)
+}
+
+/// Split the auction based on the percent being filled, and scale the filled portion's bid and lot
+/// by the given modifiers
+///
+/// ### Arguments
+/// * `auction_data` - The auction data to split
+/// * `percent_filled` - The percentage being filled as a number (i.e. 15 => 15%)
+/// * `bid_modifier` - The scalar applied to the filled bid, in 7 decimals
+/// * `lot_modifier` - The scalar applied to the filled lot, in 7 decimals
+///
+/// Returns the (Scaled Auction, Remaining Auction) such that:
+/// - Scaled Auction is the auction data scaled
+/// - Remaining Auction is the leftover auction data that will be stored in the ledger, or deleted if None
+///
+/// ### Panics
+/// If the percent filled is greater than 100 or less than 0
+fn split_auction(
+    e: &Env,
+    auction_data: &AuctionData,
+    percent_filled: u64,
+    bid_modifier: i128,
+    lot_modifier: i128,
+) -> (AuctionData, Option<AuctionData>) {
+    if percent_filled > 100 || percent_filled == 0 {
+        panic_with_context(e, PoolError::BadRequest, None, None);
+    }
+
+    let mut to_fill_auction = AuctionData {
+        bid: map![e],
+        lot: map![e],
+        block: auction_data.block,
+    };
+    let mut remaining_auction = AuctionData {
+        bid: map![e],
+        lot: map![e],
+        block: auction_data.block,
+    };
+
     // scale the auction
     let percent_filled_i128 = i128(percent_filled) * 1_00000; // scale to decimal form in 7 decimals from percentage
     for (asset, amount) in auction_data.bid.iter() {
@@ -289,6 +623,15 @@ mod tests {
         vec, Symbol,
     };
 
+    /// Load every user in the list of auctions of a given type, in list order
+    fn load_auction_list(e: &Env, auction_type: u32) -> Vec<Address> {
+        let mut users = vec![e];
+        for index in 0..storage::get_auction_count(e, &auction_type) {
+            users.push_back(storage::get_auction_list_entry(e, &auction_type, index));
+        }
+        users
+    }
+
     #[test]
     fn test_create_bad_debt_auction() {
         let e = Env::default();
@@ -634,18 +977,21 @@ mod tests {
             storage::set_backstop(&e, &Address::generate(&e));
             storage::set_user_positions(&e, &samwise, &positions);
             storage::set_pool_config(&e, &pool_config);
+            // the liquidation grace period has just ended
+            storage::set_liquidation_grace(&e, &3600);
+            storage::set_unfreeze_time(&e, 12345 - 3600);
 
             e.budget().reset_unlimited();
-            create_liquidation(&e, &samwise, liq_pct);
+            create_liquidation(&e, &samwise, liq_pct, &vec![&e]);
             assert!(storage::has_auction(&e, &0, &samwise));
+            assert_eq!(load_auction_list(&e, 0), vec![&e, samwise.clone()]);
         });
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #1211)")]
-    fn test_create_liquidation_for_pool() {
+    fn test_create_liquidations() {
         let e = Env::default();
-        e.budget().reset_unlimited();
+
         e.mock_all_auths();
         e.ledger().set(LedgerInfo {
             timestamp: 12345,
@@ -659,10 +1005,15 @@ mod tests {
         });
 
         let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let merry = Address::generate(&e);
 
         let pool_address = create_pool(&e);
         let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
 
+        // creating reserves for a pool exhausts the budget
+        e.budget().reset_unlimited();
         let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
         let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
         reserve_data_0.last_time = 12345;
@@ -738,16 +1089,45 @@ mod tests {
         };
         e.as_contract(&pool_address, || {
             storage::set_backstop(&e, &Address::generate(&e));
-            storage::set_user_positions(&e, &pool_address, &positions);
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_user_positions(
+                &e,
+                &frodo,
+                &Positions {
+                    collateral: map![&e, (reserve_config_0.index, 10_0000000)],
+                    liabilities: map![&e],
+                    supply: map![&e],
+                },
+            );
             storage::set_pool_config(&e, &pool_config);
 
-            create_liquidation(&e, &pool_address, liq_pct);
+            e.budget().reset_unlimited();
+            let (created, skipped) = create_liquidations(
+                &e,
+                &vec![
+                    &e,
+                    (samwise.clone(), liq_pct),
+                    (frodo.clone(), liq_pct),
+                    (merry.clone(), liq_pct),
+                ],
+            );
+            assert_eq!(created.len(), 1);
+            let (created_user, auction_data) = created.get_unchecked(0);
+            assert_eq!(created_user, samwise);
+            let stored_auction = storage::get_auction(&e, &0, &samwise);
+            assert_eq!(auction_data.bid, stored_auction.bid);
+            assert_eq!(auction_data.lot, stored_auction.lot);
+            assert_eq!(auction_data.block, 51);
+            assert_eq!(skipped, vec![&e, frodo.clone(), merry.clone()]);
+            assert!(!storage::has_auction(&e, &0, &frodo));
+            assert!(!storage::has_auction(&e, &0, &merry));
+            assert_eq!(load_auction_list(&e, 0), vec![&e, samwise.clone()]);
         });
     }
 
     #[test]
     #[should_panic(expected = "Error(Contract, #1211)")]
-    fn test_create_liquidation_for_backstop() {
+    fn test_create_liquidation_for_pool() {
         let e = Env::default();
         e.budget().reset_unlimited();
         e.mock_all_auths();
@@ -765,7 +1145,6 @@ mod tests {
         let bombadil = Address::generate(&e);
 
         let pool_address = create_pool(&e);
-        let backstop = Address::generate(&e);
         let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
 
         let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
@@ -842,86 +1221,43 @@ mod tests {
             max_positions: 4,
         };
         e.as_contract(&pool_address, || {
-            storage::set_backstop(&e, &backstop);
-            storage::set_user_positions(&e, &backstop, &positions);
+            storage::set_backstop(&e, &Address::generate(&e));
+            storage::set_user_positions(&e, &pool_address, &positions);
             storage::set_pool_config(&e, &pool_config);
 
-            create_liquidation(&e, &backstop, liq_pct);
-        });
-    }
-
-    #[test]
-    fn test_delete_user_liquidation() {
-        let e = Env::default();
-        e.mock_all_auths();
-
-        let pool_id = create_pool(&e);
-        let samwise = Address::generate(&e);
-
-        let auction_data = AuctionData {
-            bid: map![&e],
-            lot: map![&e],
-            block: 100,
-        };
-        e.as_contract(&pool_id, || {
-            storage::set_auction(
-                &e,
-                &(AuctionType::UserLiquidation as u32),
-                &samwise,
-                &auction_data,
-            );
-
-            delete_liquidation(&e, &samwise);
-            assert!(!storage::has_auction(
-                &e,
-                &(AuctionType::UserLiquidation as u32),
-                &samwise
-            ));
-        });
-    }
-
-    #[test]
-    #[should_panic(expected = "Error(Contract, #1200)")]
-    fn test_delete_user_liquidation_does_not_exist() {
-        let e = Env::default();
-        e.mock_all_auths();
-        let pool_id = create_pool(&e);
-
-        let samwise = Address::generate(&e);
-
-        e.as_contract(&pool_id, || {
-            delete_liquidation(&e, &samwise);
+            create_liquidation(&e, &pool_address, liq_pct, &vec![&e]);
         });
     }
 
     #[test]
-    fn test_fill() {
+    #[should_panic(expected = "Error(Contract, #1211)")]
+    fn test_create_liquidation_for_backstop() {
         let e = Env::default();
-
+        e.budget().reset_unlimited();
         e.mock_all_auths();
         e.ledger().set(LedgerInfo {
             timestamp: 12345,
             protocol_version: 20,
-            sequence_number: 175,
+            sequence_number: 50,
             network_id: Default::default(),
             base_reserve: 10,
-            min_temp_entry_ttl: 172800,
-            min_persistent_entry_ttl: 172800,
-            max_entry_ttl: 9999999,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
         });
 
         let bombadil = Address::generate(&e);
-        let samwise = Address::generate(&e);
-        let frodo = Address::generate(&e);
 
         let pool_address = create_pool(&e);
+        let backstop = Address::generate(&e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
 
-        let (oracle_address, _) = testutils::create_mock_oracle(&e);
-
-        // creating reserves for a pool exhausts the budget
-        e.budget().reset_unlimited();
         let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
-        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta();
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.b_rate = 1_100_000_000;
+        reserve_config_0.c_factor = 0_8500000;
+        reserve_config_0.l_factor = 0_9000000;
         reserve_config_0.index = 0;
         testutils::create_reserve(
             &e,
@@ -932,7 +1268,11 @@ mod tests {
         );
 
         let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
-        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta();
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.b_rate = 1_200_000_000;
+        reserve_config_1.c_factor = 0_7500000;
+        reserve_config_1.l_factor = 0_7500000;
+        reserve_data_1.last_time = 12345;
         reserve_config_1.index = 1;
         testutils::create_reserve(
             &e,
@@ -944,7 +1284,277 @@ mod tests {
 
         let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
         let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
-        reserve_config_2.index = 2;
+        reserve_config_2.c_factor = 0_0000000;
+        reserve_config_2.l_factor = 0_7000000;
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0),
+                Asset::Stellar(underlying_1),
+                Asset::Stellar(underlying_2),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 50_0000000]);
+
+        let liq_pct = 45;
+        let positions: Positions = Positions {
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 90_9100000),
+                (reserve_config_1.index, 04_5800000),
+            ],
+            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
+            supply: map![&e],
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_backstop(&e, &backstop);
+            storage::set_user_positions(&e, &backstop, &positions);
+            storage::set_pool_config(&e, &pool_config);
+
+            create_liquidation(&e, &backstop, liq_pct, &vec![&e]);
+        });
+    }
+
+    #[test]
+    fn test_execute_set_min_liquidation() {
+        let e = Env::default();
+        let pool_address = create_pool(&e);
+        let samwise = Address::generate(&e);
+
+        e.as_contract(&pool_address, || {
+            execute_set_min_liquidation(&e, &samwise, Some(50));
+            assert_eq!(storage::get_min_liquidation(&e, &samwise), Some(50));
+
+            execute_set_min_liquidation(&e, &samwise, None);
+            assert_eq!(storage::get_min_liquidation(&e, &samwise), None);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_min_liquidation_over_max() {
+        let e = Env::default();
+        let pool_address = create_pool(&e);
+        let samwise = Address::generate(&e);
+
+        e.as_contract(&pool_address, || {
+            execute_set_min_liquidation(&e, &samwise, Some(51));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1237)")]
+    fn test_create_liquidation_during_grace_period() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool_address = create_pool(&e);
+        let samwise = Address::generate(&e);
+
+        e.as_contract(&pool_address, || {
+            storage::set_backstop(&e, &Address::generate(&e));
+            storage::set_liquidation_grace(&e, &3600);
+            storage::set_unfreeze_time(&e, 12345 - 3599);
+
+            create_liquidation(&e, &samwise, 50, &vec![&e]);
+        });
+    }
+
+    #[test]
+    fn test_delete_user_liquidation() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let pool_id = create_pool(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let merry = Address::generate(&e);
+
+        let auction_data = AuctionData {
+            bid: map![&e],
+            lot: map![&e],
+            block: 100,
+        };
+        e.as_contract(&pool_id, || {
+            storage::set_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise,
+                &auction_data,
+            );
+            add_to_auction_list(&e, AuctionType::UserLiquidation as u32, &frodo);
+            add_to_auction_list(&e, AuctionType::UserLiquidation as u32, &samwise);
+            add_to_auction_list(&e, AuctionType::UserLiquidation as u32, &merry);
+
+            delete_liquidation(&e, &samwise);
+            assert!(!storage::has_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise
+            ));
+            assert_eq!(
+                load_auction_list(&e, AuctionType::UserLiquidation as u32),
+                vec![&e, frodo.clone(), merry.clone()]
+            );
+            assert_eq!(
+                storage::get_auction_list_index(
+                    &e,
+                    &(AuctionType::UserLiquidation as u32),
+                    &samwise
+                ),
+                None
+            );
+        });
+    }
+
+    #[test]
+    fn test_get_auctions() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool_id = create_pool(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let merry = Address::generate(&e);
+        let pippin = Address::generate(&e);
+
+        let auction_data = AuctionData {
+            bid: map![&e],
+            lot: map![&e],
+            block: 100,
+        };
+        e.as_contract(&pool_id, || {
+            for user in [&samwise, &frodo, &merry] {
+                storage::set_auction(&e, &0, user, &auction_data);
+            }
+            // pippin's auction expired from temporary storage
+            for user in [&samwise, &pippin, &frodo, &merry] {
+                add_to_auction_list(&e, 0, user);
+            }
+
+            let auctions = get_auctions(&e, 0, 0, 10);
+            assert_eq!(auctions.len(), 3);
+            assert_eq!(auctions.get_unchecked(0).0, samwise);
+            assert_eq!(auctions.get_unchecked(1).0, frodo);
+            assert_eq!(auctions.get_unchecked(2).0, merry);
+            assert_eq!(auctions.get_unchecked(2).1.block, 100);
+
+            let auctions = get_auctions(&e, 0, 1, 2);
+            assert_eq!(auctions.len(), 1);
+            assert_eq!(auctions.get_unchecked(0).0, frodo);
+
+            let auctions = get_auctions(&e, 0, 4, 2);
+            assert_eq!(auctions.len(), 0);
+
+            let auctions = get_auctions(&e, 1, 0, u32::MAX);
+            assert_eq!(auctions.len(), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_delete_user_liquidation_does_not_exist() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool_id = create_pool(&e);
+
+        let samwise = Address::generate(&e);
+
+        e.as_contract(&pool_id, || {
+            delete_liquidation(&e, &samwise);
+        });
+    }
+
+    #[test]
+    fn test_fill() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 175,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+
+        let (oracle_address, _) = testutils::create_mock_oracle(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta();
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta();
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
+        reserve_config_2.index = 2;
         testutils::create_reserve(
             &e,
             &pool_address,
@@ -982,6 +1592,7 @@ mod tests {
             storage::set_user_positions(&e, &samwise, &positions);
             storage::set_pool_config(&e, &pool_config);
             storage::set_auction(&e, &0, &samwise, &auction_data);
+            add_to_auction_list(&e, 0, &samwise);
 
             e.ledger().set(LedgerInfo {
                 timestamp: 12345 + 200 * 5,
@@ -999,6 +1610,7 @@ mod tests {
             fill(&e, &mut pool, 0, &samwise, &mut frodo_state, 100);
             let has_auction = storage::has_auction(&e, &0, &samwise);
             assert_eq!(has_auction, false);
+            assert_eq!(storage::get_auction_count(&e, &0), 0);
         });
     }
 
@@ -1555,55 +2167,381 @@ mod tests {
     }
 
     #[test]
-    fn test_scale_auction_100_fill_pct() {
-        // 0 blocks
+    #[should_panic(expected = "Error(Contract, #1226)")]
+    fn test_fill_fails_under_min_fill_pct() {
         let e = Env::default();
-        let underlying_0 = Address::generate(&e);
-        let underlying_1 = Address::generate(&e);
-
-        let base_auction_data = AuctionData {
-            bid: map![&e, (underlying_0.clone(), 100_0000000)],
-            lot: map![&e, (underlying_1.clone(), 100_0000000)],
-            block: 1000,
-        };
 
-        // 0 blocks
+        e.mock_all_auths();
         e.ledger().set(LedgerInfo {
             timestamp: 12345,
             protocol_version: 20,
-            sequence_number: 1000,
+            sequence_number: 175,
             network_id: Default::default(),
             base_reserve: 10,
             min_temp_entry_ttl: 172800,
             min_persistent_entry_ttl: 172800,
             max_entry_ttl: 9999999,
         });
-        let (scaled_auction, remaining_auction) = scale_auction(&e, &base_auction_data, 100);
-        assert_eq!(
-            scaled_auction.bid.get_unchecked(underlying_0.clone()),
-            100_0000000
-        );
-        assert_eq!(scaled_auction.lot.len(), 0);
-        assert!(remaining_auction.is_none());
 
-        // 100 blocks
-        e.ledger().set(LedgerInfo {
-            timestamp: 12345,
-            protocol_version: 20,
-            sequence_number: 1100,
-            network_id: Default::default(),
-            base_reserve: 10,
-            min_temp_entry_ttl: 172800,
-            min_persistent_entry_ttl: 172800,
-            max_entry_ttl: 9999999,
-        });
-        let (scaled_auction, remaining_auction) = scale_auction(&e, &base_auction_data, 100);
-        assert_eq!(
-            scaled_auction.bid.get_unchecked(underlying_0.clone()),
-            100_0000000
-        );
-        assert_eq!(
-            scaled_auction.lot.get_unchecked(underlying_1.clone()),
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+
+        let (oracle_address, _) = testutils::create_mock_oracle(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, reserve_data_0) = testutils::default_reserve_meta();
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, reserve_data_1) = testutils::default_reserve_meta();
+
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
+
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+        e.budget().reset_unlimited();
+        let auction_data = AuctionData {
+            bid: map![&e, (underlying_2.clone(), 1_2375000)],
+            lot: map![
+                &e,
+                (underlying_0.clone(), 30_5595329),
+                (underlying_1.clone(), 1_5395739)
+            ],
+            block: 176,
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let positions: Positions = Positions {
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 90_9100000),
+                (reserve_config_1.index, 04_5800000),
+            ],
+            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
+            supply: map![&e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_auction(&e, &0, &samwise, &auction_data);
+            storage::set_min_fill_pct(&e, &5);
+
+            e.ledger().set(LedgerInfo {
+                timestamp: 12345 + 200 * 5,
+                protocol_version: 20,
+                sequence_number: 176 + 200,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 172800,
+                min_persistent_entry_ttl: 172800,
+                max_entry_ttl: 9999999,
+            });
+            e.budget().reset_unlimited();
+            let mut pool = Pool::load(&e);
+            let mut frodo_state = User::load(&e, &frodo);
+            fill(&e, &mut pool, 0, &samwise, &mut frodo_state, 4);
+        });
+    }
+
+    #[test]
+    fn test_preview_fill_user_liquidation() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited(); // setup exhausts budget
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 175,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.d_rate = 1_100_000_000;
+        reserve_data_1.last_time = 12345;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000]);
+
+        let auction_data = AuctionData {
+            bid: map![&e, (underlying_1.clone(), 10_0000000)],
+            lot: map![&e, (underlying_0.clone(), 30_0000000)],
+            block: 176,
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_auction(&e, &0, &samwise, &auction_data);
+
+            // 100 blocks, lot is 50% ramped in
+            e.ledger().set(LedgerInfo {
+                timestamp: 12345,
+                protocol_version: 20,
+                sequence_number: 176 + 100,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 172800,
+                min_persistent_entry_ttl: 172800,
+                max_entry_ttl: 9999999,
+            });
+            let preview = preview_fill(&e, 0, &samwise, 50);
+            assert_eq!(preview.bid.get_unchecked(underlying_1.clone()), 5_0000000);
+            assert_eq!(preview.lot.get_unchecked(underlying_0.clone()), 7_5000000);
+            assert_eq!(preview.bid_value, 22_0000000);
+            assert_eq!(preview.lot_value, 15_0000000);
+
+            // the preview does not modify the auction
+            let stored_auction = storage::get_auction(&e, &0, &samwise);
+            assert_eq!(stored_auction.bid, auction_data.bid);
+            assert_eq!(stored_auction.lot, auction_data.lot);
+        });
+    }
+
+    #[test]
+    fn test_preview_fill_interest_auction() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited(); // setup exhausts budget
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+        let (usdc_id, _) = testutils::create_token_contract(&e, &bombadil);
+        let (blnd_id, _) = testutils::create_blnd_token(&e, &pool_address, &bombadil);
+
+        let (backstop_token_id, _) = create_comet_lp_pool(&e, &bombadil, &blnd_id, &usdc_id);
+        let (backstop_address, backstop_client) = testutils::create_backstop(&e);
+        testutils::setup_backstop(
+            &e,
+            &pool_address,
+            &backstop_address,
+            &backstop_token_id,
+            &usdc_id,
+            &blnd_id,
+        );
+        backstop_client.deposit(&bombadil, &pool_address, &(50 * SCALAR_7));
+        backstop_client.update_tkn_val();
+        let (oracle_id, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.backstop_credit = 100_0000000;
+        reserve_data_0.b_supply = 1000_0000000;
+        reserve_data_0.d_supply = 750_0000000;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.last_time = 12345;
+        reserve_data_1.backstop_credit = 25_0000000;
+        reserve_data_1.b_supply = 250_0000000;
+        reserve_data_1.d_supply = 187_5000000;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(usdc_id),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 1_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle: oracle_id,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            create_interest_auction(&e, &vec![&e, underlying_0.clone(), underlying_1.clone()]);
+
+            // 200 blocks, lot is fully ramped in and the bid has not decayed
+            e.ledger().set(LedgerInfo {
+                timestamp: 12345,
+                protocol_version: 20,
+                sequence_number: 51 + 200,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 10,
+                min_persistent_entry_ttl: 10,
+                max_entry_ttl: 3110400,
+            });
+            let preview = preview_fill(&e, 2, &backstop_address, 100);
+            assert_eq!(preview.lot.get_unchecked(underlying_0), 100_0000000);
+            assert_eq!(preview.lot.get_unchecked(underlying_1), 25_0000000);
+            assert_eq!(preview.lot_value, 300_0000000);
+            // the bid is created worth 140% of the lot
+            assert!((preview.bid_value - 420_0000000).abs() <= 0_0001000);
+        });
+    }
+
+    #[test]
+    fn test_scale_auction_100_fill_pct() {
+        // 0 blocks
+        let e = Env::default();
+        let underlying_0 = Address::generate(&e);
+        let underlying_1 = Address::generate(&e);
+
+        let base_auction_data = AuctionData {
+            bid: map![&e, (underlying_0.clone(), 100_0000000)],
+            lot: map![&e, (underlying_1.clone(), 100_0000000)],
+            block: 1000,
+        };
+        let curve = AuctionCurve {
+            lot_blocks: 200,
+            bid_blocks: 200,
+        };
+
+        // 0 blocks
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 1000,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+        let (scaled_auction, remaining_auction) =
+            scale_auction(&e, &base_auction_data, &curve, 100);
+        assert_eq!(
+            scaled_auction.bid.get_unchecked(underlying_0.clone()),
+            100_0000000
+        );
+        assert_eq!(scaled_auction.lot.len(), 0);
+        assert!(remaining_auction.is_none());
+
+        // 100 blocks
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 1100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+        let (scaled_auction, remaining_auction) =
+            scale_auction(&e, &base_auction_data, &curve, 100);
+        assert_eq!(
+            scaled_auction.bid.get_unchecked(underlying_0.clone()),
+            100_0000000
+        );
+        assert_eq!(
+            scaled_auction.lot.get_unchecked(underlying_1.clone()),
             50_0000000
         );
         assert!(remaining_auction.is_none());
@@ -1619,7 +2557,8 @@ mod tests {
             min_persistent_entry_ttl: 172800,
             max_entry_ttl: 9999999,
         });
-        let (scaled_auction, remaining_auction) = scale_auction(&e, &base_auction_data, 100);
+        let (scaled_auction, remaining_auction) =
+            scale_auction(&e, &base_auction_data, &curve, 100);
         assert_eq!(
             scaled_auction.bid.get_unchecked(underlying_0.clone()),
             100_0000000
@@ -1641,7 +2580,8 @@ mod tests {
             min_persistent_entry_ttl: 172800,
             max_entry_ttl: 9999999,
         });
-        let (scaled_auction, remaining_auction) = scale_auction(&e, &base_auction_data, 100);
+        let (scaled_auction, remaining_auction) =
+            scale_auction(&e, &base_auction_data, &curve, 100);
         assert_eq!(
             scaled_auction.bid.get_unchecked(underlying_0.clone()),
             50_0000000
@@ -1663,7 +2603,8 @@ mod tests {
             min_persistent_entry_ttl: 172800,
             max_entry_ttl: 9999999,
         });
-        let (scaled_auction, remaining_auction) = scale_auction(&e, &base_auction_data, 100);
+        let (scaled_auction, remaining_auction) =
+            scale_auction(&e, &base_auction_data, &curve, 100);
         assert_eq!(scaled_auction.bid.len(), 0);
         assert_eq!(
             scaled_auction.lot.get_unchecked(underlying_1.clone()),
@@ -1672,6 +2613,104 @@ mod tests {
         assert!(remaining_auction.is_none());
     }
 
+    #[test]
+    fn test_scale_auction_custom_curve() {
+        let e = Env::default();
+        let underlying_0 = Address::generate(&e);
+        let underlying_1 = Address::generate(&e);
+
+        let base_auction_data = AuctionData {
+            bid: map![&e, (underlying_0.clone(), 100_0000000)],
+            lot: map![&e, (underlying_1.clone(), 100_0000000)],
+            block: 1000,
+        };
+        let curve = AuctionCurve {
+            lot_blocks: 300,
+            bid_blocks: 50,
+        };
+
+        // 100 blocks
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 1100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+        let (scaled_auction, _) = scale_auction(&e, &base_auction_data, &curve, 100);
+        assert_eq!(
+            scaled_auction.bid.get_unchecked(underlying_0.clone()),
+            100_0000000
+        );
+        assert_eq!(
+            scaled_auction.lot.get_unchecked(underlying_1.clone()),
+            33_3333300
+        );
+
+        // 300 blocks
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 1300,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+        let (scaled_auction, _) = scale_auction(&e, &base_auction_data, &curve, 100);
+        assert_eq!(
+            scaled_auction.bid.get_unchecked(underlying_0.clone()),
+            100_0000000
+        );
+        assert_eq!(
+            scaled_auction.lot.get_unchecked(underlying_1.clone()),
+            100_0000000
+        );
+
+        // 320 blocks
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 1320,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+        let (scaled_auction, _) = scale_auction(&e, &base_auction_data, &curve, 100);
+        assert_eq!(
+            scaled_auction.bid.get_unchecked(underlying_0.clone()),
+            60_0000000
+        );
+        assert_eq!(
+            scaled_auction.lot.get_unchecked(underlying_1.clone()),
+            100_0000000
+        );
+
+        // 350 blocks
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 1350,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 172800,
+            min_persistent_entry_ttl: 172800,
+            max_entry_ttl: 9999999,
+        });
+        let (scaled_auction, _) = scale_auction(&e, &base_auction_data, &curve, 100);
+        assert_eq!(scaled_auction.bid.len(), 0);
+        assert_eq!(
+            scaled_auction.lot.get_unchecked(underlying_1.clone()),
+            100_0000000
+        );
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #1211)")]
     fn test_fill_liquidation_same_address() {
@@ -1791,6 +2830,10 @@ mod tests {
             lot: map![&e, (underlying_1.clone(), 25_0000005)],
             block: 1000,
         };
+        let curve = AuctionCurve {
+            lot_blocks: 200,
+            bid_blocks: 200,
+        };
 
         // 0 blocks
         e.ledger().set(LedgerInfo {
@@ -1803,7 +2846,8 @@ mod tests {
             min_persistent_entry_ttl: 172800,
             max_entry_ttl: 9999999,
         });
-        let (scaled_auction, remaining_auction_option) = scale_auction(&e, &base_auction_data, 50);
+        let (scaled_auction, remaining_auction_option) =
+            scale_auction(&e, &base_auction_data, &curve, 50);
         let remaining_auction = remaining_auction_option.unwrap();
         assert_eq!(
             scaled_auction.bid.get_unchecked(underlying_0.clone()),
@@ -1831,7 +2875,8 @@ mod tests {
             max_entry_ttl: 9999999,
         });
 
-        let (scaled_auction, remaining_auction_option) = scale_auction(&e, &base_auction_data, 60);
+        let (scaled_auction, remaining_auction_option) =
+            scale_auction(&e, &base_auction_data, &curve, 60);
         let remaining_auction = remaining_auction_option.unwrap();
         assert_eq!(
             scaled_auction.bid.get_unchecked(underlying_0.clone()),
@@ -1862,7 +2907,8 @@ mod tests {
             max_entry_ttl: 9999999,
         });
 
-        let (scaled_auction, remaining_auction_option) = scale_auction(&e, &base_auction_data, 60);
+        let (scaled_auction, remaining_auction_option) =
+            scale_auction(&e, &base_auction_data, &curve, 60);
         let remaining_auction = remaining_auction_option.unwrap();
         assert_eq!(
             scaled_auction.bid.get_unchecked(underlying_0.clone()),
@@ -1892,7 +2938,8 @@ mod tests {
             min_persistent_entry_ttl: 172800,
             max_entry_ttl: 9999999,
         });
-        let (scaled_auction, remaining_auction_option) = scale_auction(&e, &base_auction_data, 50);
+        let (scaled_auction, remaining_auction_option) =
+            scale_auction(&e, &base_auction_data, &curve, 50);
         let remaining_auction = remaining_auction_option.unwrap();
         assert_eq!(scaled_auction.bid.len(), 0);
         assert_eq!(
@@ -1921,6 +2968,10 @@ mod tests {
             lot: map![&e, (underlying_1.clone(), 25_0000005)],
             block: 1000,
         };
+        let curve = AuctionCurve {
+            lot_blocks: 200,
+            bid_blocks: 200,
+        };
 
         // 0 blocks
         e.ledger().set(LedgerInfo {
@@ -1934,7 +2985,7 @@ mod tests {
             max_entry_ttl: 9999999,
         });
 
-        let (_, _) = scale_auction(&e, &base_auction_data, 0);
+        let (_, _) = scale_auction(&e, &base_auction_data, &curve, 0);
     }
 
     #[test]
@@ -1949,6 +3000,10 @@ mod tests {
             lot: map![&e, (underlying_1.clone(), 25_0000005)],
             block: 1000,
         };
+        let curve = AuctionCurve {
+            lot_blocks: 200,
+            bid_blocks: 200,
+        };
 
         // 0 blocks
         e.ledger().set(LedgerInfo {
@@ -1962,6 +3017,6 @@ mod tests {
             max_entry_ttl: 9999999,
         });
 
-        let (_, _) = scale_auction(&e, &base_auction_data, 101);
+        let (_, _) = scale_auction(&e, &base_auction_data, &curve, 101);
     }
 }
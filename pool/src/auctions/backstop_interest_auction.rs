@@ -1,10 +1,13 @@
 use crate::{
-    constants::SCALAR_7, dependencies::BackstopClient, errors::PoolError, pool::Pool, storage,
+    constants::SCALAR_7,
+    dependencies::BackstopClient,
+    errors::{panic_with_context, PoolError},
+    pool::Pool,
+    storage,
 };
 use cast::i128;
-use sep_41_token::TokenClient;
 use soroban_fixed_point_math::FixedPoint;
-use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Vec};
+use soroban_sdk::{map, unwrap::UnwrapOptimized, Address, Env, Vec};
 
 use super::{AuctionData, AuctionType};
 
@@ -14,7 +17,12 @@ pub fn create_interest_auction_data(
     assets: &Vec<Address>,
 ) -> AuctionData {
     if storage::has_auction(e, &(AuctionType::InterestAuction as u32), backstop) {
-        panic_with_error!(e, PoolError::AuctionInProgress);
+        panic_with_context(
+            e,
+            PoolError::AuctionInProgress,
+            None,
+            Some(backstop.clone()),
+        );
     }
 
     let mut pool = Pool::load(e);
@@ -27,6 +35,10 @@ pub fn create_interest_auction_data(
 
     let mut interest_value = 0; // expressed in the oracle's decimals
     for res_asset_address in assets.iter() {
+        // each asset can only be included in the lot once
+        if auction_data.lot.contains_key(res_asset_address.clone()) {
+            panic_with_context(e, PoolError::BadRequest, None, Some(res_asset_address));
+        }
         // don't store updated reserve data back to ledger. This will occur on the the auction's fill.
         let reserve = pool.load_reserve(e, &res_asset_address, false);
         if reserve.backstop_credit > 0 {
@@ -42,11 +54,11 @@ pub fn create_interest_auction_data(
 
     // Ensure that the interest value is at least 200 USDC
     if interest_value <= (200 * 10i128.pow(pool.load_price_decimals(e))) {
-        panic_with_error!(e, PoolError::InterestTooSmall);
+        panic_with_context(e, PoolError::InterestTooSmall, None, Some(backstop.clone()));
     }
 
     if auction_data.lot.is_empty() || interest_value == 0 {
-        panic_with_error!(e, PoolError::BadRequest);
+        panic_with_context(e, PoolError::BadRequest, None, Some(backstop.clone()));
     }
 
     let backstop_client = BackstopClient::new(&e, &storage::get_backstop(e));
@@ -79,7 +91,7 @@ pub fn fill_interest_auction(
     // bid only contains the Backstop token
     let backstop = storage::get_backstop(e);
     if filler.clone() == backstop {
-        panic_with_error!(e, PoolError::BadRequest);
+        panic_with_context(e, PoolError::BadRequest, None, Some(filler.clone()));
     }
     let backstop_client = BackstopClient::new(&e, &backstop);
     let backstop_token: Address = backstop_client.backstop_token();
@@ -91,16 +103,12 @@ pub fn fill_interest_auction(
         &backstop_token_bid_amount,
     );
 
-    // lot contains underlying tokens, but the backstop credit must be updated on the reserve
+    // lot contains underlying tokens, but the backstop credit must be updated on the reserve. The
+    // lot is transferred by the caller after all state has been written.
     for (res_asset_address, lot_amount) in auction_data.lot.iter() {
         let mut reserve = pool.load_reserve(e, &res_asset_address, true);
         reserve.backstop_credit -= lot_amount;
         pool.cache_reserve(reserve);
-        TokenClient::new(e, &res_asset_address).transfer(
-            &e.current_contract_address(),
-            filler,
-            &lot_amount,
-        );
     }
 }
 
@@ -282,6 +290,112 @@ mod tests {
         });
     }
 
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_create_interest_auction_duplicate_asset() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited(); // setup exhausts budget
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+        let (usdc_id, _) = testutils::create_token_contract(&e, &bombadil);
+        let (backstop_address, _backstop_client) = testutils::create_backstop(&e);
+        testutils::setup_backstop(
+            &e,
+            &pool_address,
+            &backstop_address,
+            &Address::generate(&e),
+            &usdc_id,
+            &Address::generate(&e),
+        );
+        let (oracle_id, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.b_rate = 1_100_000_000;
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.backstop_credit = 60_0000000;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.b_rate = 1_100_000_000;
+        reserve_data_1.last_time = 12345;
+        reserve_data_1.backstop_credit = 2_5000000;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, mut reserve_data_2) = testutils::default_reserve_meta();
+        reserve_data_2.b_rate = 1_100_000_000;
+        reserve_data_2.last_time = 12345;
+        reserve_config_2.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2),
+                Asset::Stellar(usdc_id.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 100_0000000, 1_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle: oracle_id,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            create_interest_auction_data(
+                &e,
+                &backstop_address,
+                &vec![&e, underlying_0.clone(), underlying_0.clone()],
+            );
+        });
+    }
+
     #[test]
     fn test_create_interest_auction() {
         let e = Env::default();
@@ -764,8 +878,9 @@ mod tests {
                 backstop_token_client.balance(&backstop_address),
                 backstop_token_balance_pre_fill + 75_0000000
             );
-            assert_eq!(underlying_0_client.balance(&samwise), 100_0000000);
-            assert_eq!(underlying_1_client.balance(&samwise), 25_0000000);
+            // lot is transferred by the caller
+            assert_eq!(underlying_0_client.balance(&samwise), 0);
+            assert_eq!(underlying_1_client.balance(&samwise), 0);
             // verify only filled backstop credits get deducted from total
             let reserve_0_data = storage::get_res_data(&e, &underlying_0);
             assert_eq!(reserve_0_data.backstop_credit, 0);
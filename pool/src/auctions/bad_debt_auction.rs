@@ -1,19 +1,34 @@
 use crate::{
     constants::SCALAR_7,
     dependencies::BackstopClient,
-    errors::PoolError,
+    errors::{panic_with_context, PoolError},
     pool::{calc_pool_backstop_threshold, Pool, User},
     storage,
 };
 use cast::i128;
 use soroban_fixed_point_math::FixedPoint;
-use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Symbol};
+use soroban_sdk::{map, unwrap::UnwrapOptimized, vec, Address, Env, Symbol, Vec};
 
 use super::{AuctionData, AuctionType};
 
+/// Create the auction data for a bad debt auction
+///
+/// The bid includes each liability of the backstop. If a maximum number of bid assets is set, only
+/// that many of the largest liabilities by value are included, and the lot covers those alone.
+///
+/// ### Arguments
+/// * `backstop` - The address of the backstop
+///
+/// ### Panics
+/// If an auction is already in progress, or the backstop has no bad debt
 pub fn create_bad_debt_auction_data(e: &Env, backstop: &Address) -> AuctionData {
     if storage::has_auction(e, &(AuctionType::BadDebtAuction as u32), backstop) {
-        panic_with_error!(e, PoolError::AuctionInProgress);
+        panic_with_context(
+            e,
+            PoolError::AuctionInProgress,
+            None,
+            Some(backstop.clone()),
+        );
     }
 
     let mut auction_data = AuctionData {
@@ -26,21 +41,41 @@ pub fn create_bad_debt_auction_data(e: &Env, backstop: &Address) -> AuctionData
     let oracle_scalar = 10i128.pow(pool.load_price_decimals(e));
     let backstop_positions = storage::get_user_positions(e, backstop);
     let reserve_list = storage::get_res_list(e);
-    let mut debt_value = 0;
+    let mut liabilities: Vec<(Address, i128, i128)> = vec![e];
     for (reserve_index, liability_balance) in backstop_positions.liabilities.iter() {
         let res_asset_address = reserve_list.get_unchecked(reserve_index);
         if liability_balance > 0 {
             let reserve = pool.load_reserve(e, &res_asset_address, false);
             let asset_to_base = pool.load_price(e, &res_asset_address);
             let asset_balance = reserve.to_asset_from_d_token(liability_balance);
-            debt_value += i128(asset_to_base)
+            let liability_value = i128(asset_to_base)
                 .fixed_mul_floor(asset_balance, reserve.scalar)
                 .unwrap_optimized();
-            auction_data.bid.set(res_asset_address, liability_balance);
+            liabilities.push_back((res_asset_address, liability_balance, liability_value));
         }
     }
+
+    // repay the largest liabilities first, so the auction can be filled by holders of fewer assets
+    let max_assets = storage::get_bad_debt_max_assets(e);
+    if max_assets > 0 {
+        while liabilities.len() > max_assets {
+            let mut smallest = 0;
+            for i in 1..liabilities.len() {
+                if liabilities.get_unchecked(i).2 < liabilities.get_unchecked(smallest).2 {
+                    smallest = i;
+                }
+            }
+            liabilities.remove(smallest);
+        }
+    }
+
+    let mut debt_value = 0;
+    for (res_asset_address, liability_balance, liability_value) in liabilities.iter() {
+        debt_value += liability_value;
+        auction_data.bid.set(res_asset_address, liability_balance);
+    }
     if auction_data.bid.is_empty() || debt_value == 0 {
-        panic_with_error!(e, PoolError::BadRequest);
+        panic_with_context(e, PoolError::BadRequest, None, Some(backstop.clone()));
     }
 
     // get value of backstop_token (BLND-USDC LP token) to base
@@ -77,7 +112,7 @@ pub fn fill_bad_debt_auction(
 ) {
     let backstop_address = storage::get_backstop(e);
     if filler_state.address == backstop_address {
-        panic_with_error!(e, PoolError::BadRequest);
+        panic_with_context(e, PoolError::BadRequest, None, Some(backstop_address));
     }
     let mut backstop_state = User::load(e, &backstop_address);
 
@@ -336,6 +371,141 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_create_bad_debt_auction_max_assets() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.budget().reset_unlimited(); // setup exhausts budget
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_address = create_pool(&e);
+
+        let (blnd, blnd_client) = testutils::create_blnd_token(&e, &pool_address, &bombadil);
+        let (usdc, usdc_client) = testutils::create_token_contract(&e, &bombadil);
+        let (lp_token, lp_token_client) =
+            testutils::create_comet_lp_pool(&e, &bombadil, &blnd, &usdc);
+        let (backstop_address, backstop_client) = testutils::create_backstop(&e);
+        testutils::setup_backstop(
+            &e,
+            &pool_address,
+            &backstop_address,
+            &lp_token,
+            &usdc,
+            &blnd,
+        );
+        // mint lp tokens
+        blnd_client.mint(&samwise, &500_001_0000000);
+        blnd_client.approve(&samwise, &lp_token, &i128::MAX, &99999);
+        usdc_client.mint(&samwise, &12_501_0000000);
+        usdc_client.approve(&samwise, &lp_token, &i128::MAX, &99999);
+        lp_token_client.join_pool(
+            &50_000_0000000,
+            &vec![&e, 500_001_0000000, 12_501_0000000],
+            &samwise,
+        );
+        backstop_client.deposit(&samwise, &pool_address, &50_000_0000000);
+        backstop_client.update_tkn_val();
+
+        let (oracle_id, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.d_rate = 1_100_000_000;
+        reserve_data_0.last_time = 12345;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.d_rate = 1_200_000_000;
+        reserve_data_1.last_time = 12345;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, mut reserve_data_2) = testutils::default_reserve_meta();
+        reserve_data_2.b_rate = 1_100_000_000;
+        reserve_data_2.last_time = 12345;
+        reserve_config_2.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2),
+                Asset::Stellar(usdc),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 100_0000000, 1_0000000]);
+
+        let positions: Positions = Positions {
+            collateral: map![&e],
+            liabilities: map![
+                &e,
+                (reserve_config_0.index, 10_0000000),
+                (reserve_config_1.index, 2_5000000)
+            ],
+            supply: map![&e],
+        };
+
+        let pool_config = PoolConfig {
+            oracle: oracle_id,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &backstop_address, &positions);
+            storage::set_bad_debt_max_assets(&e, &1);
+
+            let result = create_bad_debt_auction_data(&e, &backstop_address);
+
+            assert_eq!(result.block, 51);
+            // only the larger liability is included, worth 22 of the 34 of bad debt
+            assert_eq!(result.bid.get_unchecked(underlying_0), 10_0000000);
+            assert_eq!(result.bid.len(), 1);
+            assert_eq!(result.lot.get_unchecked(lp_token), 24_6400000);
+            assert_eq!(result.lot.len(), 1);
+        });
+    }
+
     #[test]
     fn test_create_bad_debt_auction_oracle_14_decimals() {
         let e = Env::default();
@@ -891,6 +1061,74 @@ mod tests {
         });
     }
 
+    #[test]
+    #[should_panic(expected = "Error(Contract, #10)")]
+    fn test_fill_bad_debt_auction_draw_fails() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.budget().reset_unlimited();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 51,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+
+        let (lp_token, lp_token_client) = testutils::create_token_contract(&e, &bombadil);
+        let (backstop_address, backstop_client) =
+            testutils::create_mock_backstop(&e, &pool_address, &lp_token);
+        lp_token_client.mint(&backstop_address, &50_000_0000000);
+        backstop_client.set_draw_fails(&true);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.d_rate = 1_100_000_000;
+        reserve_data_0.last_time = 12345;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let auction_data = AuctionData {
+            bid: map![&e, (underlying_0, 10_0000000)],
+            lot: map![&e, (lp_token.clone(), 47_6000000)],
+            block: 51,
+        };
+        let positions: Positions = Positions {
+            collateral: map![&e],
+            liabilities: map![&e, (reserve_config_0.index, 10_0000000)],
+            supply: map![&e],
+        };
+
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &backstop_address, &positions);
+
+            let mut pool = Pool::load(&e);
+            let mut samwise_state = User::load(&e, &samwise);
+            fill_bad_debt_auction(&e, &mut pool, &auction_data, &mut samwise_state);
+        });
+    }
+
     #[test]
     fn test_fill_bad_debt_auction_leftover_debt_small_backstop_burns() {
         let e = Env::default();
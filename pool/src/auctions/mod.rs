@@ -1,6 +1,7 @@
 mod auction;
 mod backstop_interest_auction;
 mod bad_debt_auction;
+mod surplus_auction;
 mod user_liquidation_auction;
 
 pub use auction::*;
@@ -0,0 +1,358 @@
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::unwrap::UnwrapOptimized;
+use soroban_sdk::{map, Address, Env};
+
+use crate::auctions::auction::AuctionData;
+use crate::constants::{SCALAR_7, SURPLUS_AUCTION_PREMIUM};
+use crate::pool::{rebase_fixed_loans, Pool, PositionData, User};
+use crate::{
+    errors::{panic_with_context, PoolError},
+    storage,
+};
+
+use super::AuctionType;
+
+/// Create the auction data for a surplus auction
+///
+/// A surplus auction sells a slice of a liquidated user's remaining collateral, worth their
+/// remaining liabilities plus a premium, for those liabilities. The rest of the collateral is left
+/// with the user free of debt. The auction is filled at this fixed price.
+///
+/// ### Arguments
+/// * `user` - The user whose surplus collateral is being auctioned
+///
+/// ### Panics
+/// If the user has not had a liquidation auction filled since their last surplus auction, if
+/// another auction is in progress for the user, or if the user's collateral does not exceed their
+/// remaining liabilities plus the premium
+pub fn create_surplus_auction_data(e: &Env, user: &Address) -> AuctionData {
+    if storage::has_auction(e, &(AuctionType::SurplusAuction as u32), user)
+        || storage::has_auction(e, &(AuctionType::UserLiquidation as u32), user)
+    {
+        panic_with_context(e, PoolError::AuctionInProgress, None, Some(user.clone()));
+    }
+    if !storage::has_liquidation_filled(e, user) {
+        panic_with_context(e, PoolError::InvalidLiquidation, None, Some(user.clone()));
+    }
+
+    let mut pool = Pool::load(e);
+    let user_state = User::load(e, user);
+    let reserve_list = storage::get_res_list(e);
+    let position_data = PositionData::calculate_from_positions(e, &mut pool, &user_state.positions);
+
+    // ensure the user has remaining liabilities and enough collateral to cover them with a surplus
+    let withdrawn_collateral = position_data
+        .liability_raw
+        .fixed_mul_ceil(SURPLUS_AUCTION_PREMIUM, SCALAR_7)
+        .unwrap_optimized();
+    if position_data.liability_raw == 0 || withdrawn_collateral >= position_data.collateral_raw {
+        panic_with_context(e, PoolError::InvalidLiquidation, None, Some(user.clone()));
+    }
+    let withdrawn_collateral_pct = withdrawn_collateral
+        .fixed_div_ceil(position_data.collateral_raw, position_data.scalar)
+        .unwrap_optimized();
+
+    let mut surplus_quote = AuctionData {
+        bid: map![e],
+        lot: map![e],
+        block: e.ledger().sequence() + 1,
+    };
+    for (asset, amount) in user_state.positions.collateral.iter() {
+        let b_tokens_removed = amount
+            .fixed_mul_ceil(withdrawn_collateral_pct, position_data.scalar)
+            .unwrap_optimized();
+        surplus_quote
+            .lot
+            .set(reserve_list.get_unchecked(asset), b_tokens_removed);
+    }
+    for (asset, amount) in user_state.positions.liabilities.iter() {
+        surplus_quote
+            .bid
+            .set(reserve_list.get_unchecked(asset), amount);
+    }
+    surplus_quote
+}
+
+/// Fill a surplus auction. The filler takes on the bid liabilities and receives the lot
+/// collateral from the user.
+///
+/// ### Panics
+/// If the user is left with liabilities and an unhealthy position
+pub fn fill_surplus_auction(
+    e: &Env,
+    pool: &mut Pool,
+    auction_data: &AuctionData,
+    user: &Address,
+    filler_state: &mut User,
+) {
+    let mut user_state = User::load(e, user);
+    user_state.rm_positions(e, pool, auction_data.lot.clone(), auction_data.bid.clone());
+    filler_state.add_positions(e, pool, auction_data.lot.clone(), auction_data.bid.clone());
+    // a fill removes collateral from the user, so it must leave them healthy
+    if user_state.has_liabilities() {
+        let position_data = PositionData::calculate_from_positions(e, pool, &user_state.positions);
+        if position_data.is_hf_under(1_0000100) {
+            panic_with_context(e, PoolError::InvalidHf, None, Some(user.clone()));
+        }
+    }
+    user_state.store(e);
+    rebase_fixed_loans(e, &user_state);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        auctions::{create_surplus_auction, fill, get_auctions},
+        pool::Positions,
+        testutils,
+    };
+
+    use super::*;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger},
+        vec,
+    };
+
+    #[test]
+    fn test_create_surplus_auction() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 2_0000000)],
+            collateral: map![&e, (0, 50_0000000)],
+            supply: map![&e],
+        };
+        let (pool, underlying_0, underlying_1) =
+            testutils::create_priced_pool(&e, &samwise, &user_positions);
+        e.as_contract(&pool, || storage::set_liquidation_filled(&e, &samwise));
+
+        e.as_contract(&pool, || {
+            let auction_data = create_surplus_auction(&e, &samwise);
+            // liabilities worth 10 plus the 5% premium are sold from 50 of collateral
+            assert_eq!(auction_data.block, 1235);
+            assert_eq!(
+                auction_data.bid,
+                map![&e, (underlying_1.clone(), 2_0000000)]
+            );
+            assert_eq!(
+                auction_data.lot,
+                map![&e, (underlying_0.clone(), 10_5000000)]
+            );
+
+            assert!(storage::has_auction(
+                &e,
+                &(AuctionType::SurplusAuction as u32),
+                &samwise
+            ));
+            assert_eq!(
+                get_auctions(&e, AuctionType::SurplusAuction as u32, 0, 10).len(),
+                1
+            );
+            assert!(!storage::has_liquidation_filled(&e, &samwise));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1211)")]
+    fn test_create_surplus_auction_not_liquidated() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 2_0000000)],
+            collateral: map![&e, (0, 50_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, _) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+        e.as_contract(&pool, || storage::set_liquidation_filled(&e, &samwise));
+
+        e.as_contract(&pool, || {
+            storage::del_liquidation_filled(&e, &samwise);
+            create_surplus_auction_data(&e, &samwise);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1211)")]
+    fn test_create_surplus_auction_no_surplus() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        // liabilities worth 48 plus the premium exceed the 50 of collateral
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 9_6000000)],
+            collateral: map![&e, (0, 50_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, _) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+        e.as_contract(&pool, || storage::set_liquidation_filled(&e, &samwise));
+
+        e.as_contract(&pool, || {
+            create_surplus_auction_data(&e, &samwise);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1212)")]
+    fn test_create_surplus_auction_liquidation_in_progress() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 2_0000000)],
+            collateral: map![&e, (0, 50_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, _) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+        e.as_contract(&pool, || storage::set_liquidation_filled(&e, &samwise));
+
+        let auction_data = AuctionData {
+            bid: map![&e],
+            lot: map![&e],
+            block: 1235,
+        };
+        e.as_contract(&pool, || {
+            storage::set_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise,
+                &auction_data,
+            );
+            create_surplus_auction_data(&e, &samwise);
+        });
+    }
+
+    #[test]
+    fn test_fill_surplus_auction() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 2_0000000)],
+            collateral: map![&e, (0, 50_0000000)],
+            supply: map![&e],
+        };
+        let (pool_address, underlying_0, underlying_1) =
+            testutils::create_priced_pool(&e, &samwise, &user_positions);
+        e.as_contract(&pool_address, || {
+            storage::set_liquidation_filled(&e, &samwise)
+        });
+
+        let auction_data = AuctionData {
+            bid: map![&e, (underlying_1, 2_0000000)],
+            lot: map![&e, (underlying_0, 10_5000000)],
+            block: 1235,
+        };
+        e.as_contract(&pool_address, || {
+            let mut pool = Pool::load(&e);
+            let mut frodo_state = User::load(&e, &frodo);
+            fill_surplus_auction(&e, &mut pool, &auction_data, &samwise, &mut frodo_state);
+
+            assert_eq!(frodo_state.positions.collateral, map![&e, (0, 10_5000000)]);
+            assert_eq!(frodo_state.positions.liabilities, map![&e, (1, 2_0000000)]);
+
+            // the surplus collateral is left with samwise free of debt
+            let samwise_positions = storage::get_user_positions(&e, &samwise);
+            assert_eq!(samwise_positions.collateral, map![&e, (0, 39_5000000)]);
+            assert_eq!(samwise_positions.liabilities.len(), 0);
+        });
+    }
+
+    #[test]
+    fn test_fill_surplus_auction_fixed_price() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 2_0000000)],
+            collateral: map![&e, (0, 50_0000000)],
+            supply: map![&e],
+        };
+        let (pool_address, _, _) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+        e.as_contract(&pool_address, || {
+            storage::set_liquidation_filled(&e, &samwise)
+        });
+
+        e.as_contract(&pool_address, || {
+            create_surplus_auction(&e, &samwise);
+        });
+
+        // the bid and lot don't move as blocks pass
+        e.ledger().with_mut(|li| li.sequence_number += 500);
+        e.as_contract(&pool_address, || {
+            let mut pool = Pool::load(&e);
+            let mut frodo_state = User::load(&e, &frodo);
+            let filled = fill(
+                &e,
+                &mut pool,
+                AuctionType::SurplusAuction as u32,
+                &samwise,
+                &mut frodo_state,
+                50,
+            );
+            assert_eq!(frodo_state.positions.collateral, map![&e, (0, 5_2500000)]);
+            assert_eq!(frodo_state.positions.liabilities, map![&e, (1, 1_0000000)]);
+            assert_eq!(filled.lot.values(), vec![&e, 5_2500000]);
+            assert_eq!(filled.bid.values(), vec![&e, 1_0000000]);
+
+            let samwise_positions = storage::get_user_positions(&e, &samwise);
+            assert_eq!(samwise_positions.collateral, map![&e, (0, 44_7500000)]);
+            assert_eq!(samwise_positions.liabilities, map![&e, (1, 1_0000000)]);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1205)")]
+    fn test_fill_surplus_auction_leaves_user_unhealthy() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 2_0000000)],
+            collateral: map![&e, (0, 50_0000000)],
+            supply: map![&e],
+        };
+        let (pool_address, underlying_0, underlying_1) =
+            testutils::create_priced_pool(&e, &samwise, &user_positions);
+        e.as_contract(&pool_address, || {
+            storage::set_liquidation_filled(&e, &samwise)
+        });
+
+        // the lot takes far more collateral than the bid repays
+        let auction_data = AuctionData {
+            bid: map![&e, (underlying_1, 0_2000000)],
+            lot: map![&e, (underlying_0, 40_0000000)],
+            block: 1235,
+        };
+        e.as_contract(&pool_address, || {
+            let mut pool = Pool::load(&e);
+            let mut frodo_state = User::load(&e, &frodo);
+            fill_surplus_auction(&e, &mut pool, &auction_data, &samwise, &mut frodo_state);
+        });
+    }
+}
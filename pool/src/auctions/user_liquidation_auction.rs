@@ -1,24 +1,38 @@
 use cast::i128;
 use soroban_fixed_point_math::FixedPoint;
 use soroban_sdk::unwrap::UnwrapOptimized;
-use soroban_sdk::{map, panic_with_error, Address, Env};
+use soroban_sdk::{map, Address, Env, Map, Vec};
 
 use crate::auctions::auction::AuctionData;
-use crate::pool::{Pool, PositionData, User};
-use crate::{errors::PoolError, storage};
+use crate::pool::{rebase_fixed_loans, Pool, PositionData, Positions, User};
+use crate::{
+    errors::{panic_with_context, PoolError},
+    storage,
+};
 
 use super::AuctionType;
 
+/// Create the auction data for a user liquidation auction
+///
+/// ### Arguments
+/// * `user` - The user being liquidated
+/// * `percent_liquidated` - The percent of the bid liabilities being liquidated (15 => 15%)
+/// * `bid_assets` - The liability assets to include in the bid, or an empty vec to include all
+///   of the user's liabilities
+///
+/// ### Panics
+/// If the auction is unable to be created, or if a bid asset is not a liability of the user
 pub fn create_user_liq_auction_data(
     e: &Env,
     user: &Address,
     percent_liquidated: u64,
+    bid_assets: &Vec<Address>,
 ) -> AuctionData {
     if storage::has_auction(e, &(AuctionType::UserLiquidation as u32), user) {
-        panic_with_error!(e, PoolError::AuctionInProgress);
+        panic_with_context(e, PoolError::AuctionInProgress, None, Some(user.clone()));
     }
     if percent_liquidated > 100 || percent_liquidated == 0 {
-        panic_with_error!(e, PoolError::InvalidLiquidation);
+        panic_with_context(e, PoolError::InvalidLiquidation, None, Some(user.clone()));
     }
 
     let mut liquidation_quote = AuctionData {
@@ -34,20 +48,41 @@ pub fn create_user_liq_auction_data(
 
     // ensure the user has less collateral than liabilities
     if position_data.liability_base < position_data.collateral_base {
-        panic_with_error!(e, PoolError::InvalidLiquidation);
+        panic_with_context(e, PoolError::InvalidLiquidation, None, Some(user.clone()));
     }
 
-    let percent_liquidated_i128_scaled = i128(percent_liquidated) * position_data.scalar / 100; // scale to decimal form with scalar decimals
+    // select the liabilities being repaid through the bid
+    let mut bid_liabilities = map![e];
+    for (asset, amount) in user_state.positions.liabilities.iter() {
+        if bid_assets.is_empty() || bid_assets.contains(reserve_list.get_unchecked(asset)) {
+            bid_liabilities.set(asset, amount);
+        }
+    }
+    let is_full_bid = bid_liabilities.len() == user_state.positions.liabilities.len();
+    if bid_liabilities.is_empty()
+        || (!bid_assets.is_empty() && bid_liabilities.len() != bid_assets.len())
+    {
+        panic_with_context(e, PoolError::InvalidLiquidation, None, Some(user.clone()));
+    }
+    let bid_data = PositionData::calculate_from_positions(
+        e,
+        &mut pool,
+        &Positions {
+            liabilities: bid_liabilities.clone(),
+            collateral: user_state.positions.collateral.clone(),
+            supply: map![e],
+        },
+    );
 
     // ensure liquidation size is fair and the collateral is large enough to allow for the auction to price the liquidation
     let avg_cf = position_data
         .collateral_base
         .fixed_div_floor(position_data.collateral_raw, position_data.scalar)
         .unwrap_optimized();
-    // avg_lf is the inverse of the average liability factor
-    let avg_lf = position_data
+    // avg_lf is the inverse of the average liability factor of the bid liabilities
+    let avg_lf = bid_data
         .liability_base
-        .fixed_div_floor(position_data.liability_raw, position_data.scalar)
+        .fixed_div_floor(bid_data.liability_raw, position_data.scalar)
         .unwrap_optimized();
     let est_incentive = (position_data.scalar
         - avg_cf
@@ -57,8 +92,91 @@ pub fn create_user_liq_auction_data(
     .unwrap_optimized()
         + position_data.scalar;
 
-    let est_withdrawn_collateral = position_data
-        .liability_raw
+    let est_withdrawn_collateral = build_liquidation_quote(
+        &mut liquidation_quote,
+        &user_state,
+        &reserve_list,
+        &bid_liabilities,
+        &position_data,
+        bid_data.liability_raw,
+        est_incentive,
+        percent_liquidated,
+    );
+
+    if percent_liquidated == 100 && is_full_bid {
+        // ensure that there isn't enough collateral to fill without fully liquidating
+        if est_withdrawn_collateral < position_data.collateral_raw {
+            panic_with_context(e, PoolError::InvalidLiqTooLarge, None, Some(user.clone()));
+        }
+    } else {
+        user_state.rm_positions(
+            e,
+            &mut pool,
+            liquidation_quote.lot.clone(),
+            liquidation_quote.bid.clone(),
+        );
+        let new_data = PositionData::calculate_from_positions(e, &mut pool, &user_state.positions);
+
+        // Post-liq health factor must be under 1.15
+        if new_data.is_hf_over(1_1500000) {
+            panic_with_context(e, PoolError::InvalidLiqTooLarge, None, Some(user.clone()))
+        };
+
+        // Post-liq heath factor must be over 1.03
+        if new_data.is_hf_under(1_0300000) {
+            panic_with_context(e, PoolError::InvalidLiqTooSmall, None, Some(user.clone()))
+        };
+    }
+
+    // a user's minimum liquidation is respected unless liquidating the minimum would push
+    // their health factor over the post-liquidation cap
+    if let Some(min_percent) = storage::get_min_liquidation(e, user) {
+        if percent_liquidated < min_percent {
+            let mut min_user_state = User::load(e, user);
+            let mut min_quote = AuctionData {
+                bid: map![e],
+                lot: map![e],
+                block: liquidation_quote.block,
+            };
+            build_liquidation_quote(
+                &mut min_quote,
+                &min_user_state,
+                &reserve_list,
+                &bid_liabilities,
+                &position_data,
+                bid_data.liability_raw,
+                est_incentive,
+                min_percent,
+            );
+            min_user_state.rm_positions(e, &mut pool, min_quote.lot, min_quote.bid);
+            let min_data =
+                PositionData::calculate_from_positions(e, &mut pool, &min_user_state.positions);
+            if !min_data.is_hf_over(1_1500000) {
+                panic_with_context(e, PoolError::InvalidLiqTooSmall, None, Some(user.clone()));
+            }
+        }
+    }
+    liquidation_quote
+}
+
+/// Build the lot and bid of a user liquidation auction that liquidates a percent of the bid
+/// liabilities
+///
+/// Returns the estimated collateral withdrawn by the auction, in the base asset
+#[allow(clippy::too_many_arguments)]
+fn build_liquidation_quote(
+    liquidation_quote: &mut AuctionData,
+    user_state: &User,
+    reserve_list: &Vec<Address>,
+    bid_liabilities: &Map<u32, i128>,
+    position_data: &PositionData,
+    bid_liability_raw: i128,
+    est_incentive: i128,
+    percent_liquidated: u64,
+) -> i128 {
+    let percent_liquidated_i128_scaled = i128(percent_liquidated) * position_data.scalar / 100; // scale to decimal form with scalar decimals
+
+    let est_withdrawn_collateral = bid_liability_raw
         .fixed_mul_floor(percent_liquidated_i128_scaled, position_data.scalar)
         .unwrap_optimized()
         .fixed_mul_floor(est_incentive, position_data.scalar)
@@ -82,7 +200,7 @@ pub fn create_user_liq_auction_data(
             .set(res_asset_address, b_tokens_removed);
     }
 
-    for (asset, amount) in user_state.positions.liabilities.iter() {
+    for (asset, amount) in bid_liabilities.iter() {
         let res_asset_address = reserve_list.get_unchecked(asset);
         let d_tokens_removed = amount
             .fixed_mul_ceil(percent_liquidated_i128_scaled, position_data.scalar)
@@ -91,32 +209,20 @@ pub fn create_user_liq_auction_data(
             .bid
             .set(res_asset_address, d_tokens_removed);
     }
+    est_withdrawn_collateral
+}
 
-    if percent_liquidated == 100 {
-        // ensure that there isn't enough collateral to fill without fully liquidating
-        if est_withdrawn_collateral < position_data.collateral_raw {
-            panic_with_error!(e, PoolError::InvalidLiqTooLarge);
-        }
-    } else {
-        user_state.rm_positions(
-            e,
-            &mut pool,
-            liquidation_quote.lot.clone(),
-            liquidation_quote.bid.clone(),
-        );
-        let new_data = PositionData::calculate_from_positions(e, &mut pool, &user_state.positions);
-
-        // Post-liq health factor must be under 1.15
-        if new_data.is_hf_over(1_1500000) {
-            panic_with_error!(e, PoolError::InvalidLiqTooLarge)
-        };
-
-        // Post-liq heath factor must be over 1.03
-        if new_data.is_hf_under(1_0300000) {
-            panic_with_error!(e, PoolError::InvalidLiqTooSmall)
-        };
-    }
-    liquidation_quote
+/// Check if a user holds liabilities that are not covered by their collateral, such that
+/// a liquidation auction can be created for them
+///
+/// ### Arguments
+/// * `user` - The user to check
+pub fn is_user_liquidatable(e: &Env, user: &Address) -> bool {
+    let mut pool = Pool::load(e);
+    let positions = storage::get_user_positions(e, user);
+    let position_data = PositionData::calculate_from_positions(e, &mut pool, &positions);
+    position_data.liability_base > 0
+        && position_data.liability_base >= position_data.collateral_base
 }
 
 pub fn fill_user_liq_auction(
@@ -130,6 +236,10 @@ pub fn fill_user_liq_auction(
     user_state.rm_positions(e, pool, auction_data.lot.clone(), auction_data.bid.clone());
     filler_state.add_positions(e, pool, auction_data.lot.clone(), auction_data.bid.clone());
     user_state.store(e);
+    rebase_fixed_loans(e, &user_state);
+    // a user's remaining collateral can be auctioned off against their remaining liabilities
+    // once a liquidation against them is filled
+    storage::set_liquidation_filled(e, user);
 }
 
 #[cfg(test)]
@@ -192,7 +302,7 @@ mod tests {
                 &samwise,
                 &auction_data,
             );
-            create_user_liq_auction_data(&e, &samwise, liq_pct);
+            create_user_liq_auction_data(&e, &samwise, liq_pct, &vec![&e]);
         });
     }
 
@@ -297,7 +407,7 @@ mod tests {
             storage::set_user_positions(&e, &samwise, &positions);
             storage::set_pool_config(&e, &pool_config);
 
-            let result = create_user_liq_auction_data(&e, &samwise, liq_pct);
+            let result = create_user_liq_auction_data(&e, &samwise, liq_pct, &vec![&e]);
             assert_eq!(result.block, 51);
             assert_eq!(result.bid.get_unchecked(underlying_2), 1_2375000);
             assert_eq!(result.bid.len(), 1);
@@ -307,6 +417,229 @@ mod tests {
         });
     }
 
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1214)")]
+    fn test_create_user_liquidation_auction_under_min_liquidation() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.b_rate = 1_100_000_000;
+        reserve_config_0.c_factor = 0_8500000;
+        reserve_config_0.l_factor = 0_9000000;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.b_rate = 1_200_000_000;
+        reserve_config_1.c_factor = 0_7500000;
+        reserve_config_1.l_factor = 0_7500000;
+        reserve_data_1.last_time = 12345;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
+        reserve_config_2.c_factor = 0_0000000;
+        reserve_config_2.l_factor = 0_7000000;
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 50_0000000]);
+
+        let liq_pct = 40;
+        let positions: Positions = Positions {
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 90_9100000),
+                (reserve_config_1.index, 04_5800000),
+            ],
+            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
+            supply: map![&e],
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+
+            // a 45% liquidation is valid, so the user's minimum is respected
+            storage::set_min_liquidation(&e, &samwise, 45);
+
+            create_user_liq_auction_data(&e, &samwise, liq_pct, &vec![&e]);
+        });
+    }
+
+    #[test]
+    fn test_create_user_liquidation_auction_min_liquidation_too_large() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.b_rate = 1_100_000_000;
+        reserve_config_0.c_factor = 0_8500000;
+        reserve_config_0.l_factor = 0_9000000;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.b_rate = 1_200_000_000;
+        reserve_config_1.c_factor = 0_7500000;
+        reserve_config_1.l_factor = 0_7500000;
+        reserve_data_1.last_time = 12345;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
+        reserve_config_2.c_factor = 0_0000000;
+        reserve_config_2.l_factor = 0_7000000;
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 50_0000000]);
+
+        let liq_pct = 45;
+        let positions: Positions = Positions {
+            collateral: map![
+                &e,
+                (reserve_config_0.index, 90_9100000),
+                (reserve_config_1.index, 04_5800000),
+            ],
+            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
+            supply: map![&e],
+        };
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+
+            // a 46% liquidation is too large, so the user's minimum cannot be respected
+            storage::set_min_liquidation(&e, &samwise, 50);
+
+            let result = create_user_liq_auction_data(&e, &samwise, liq_pct, &vec![&e]);
+            assert_eq!(result.bid.get_unchecked(underlying_2.clone()), 1_2375000);
+            assert_eq!(result.lot.get_unchecked(underlying_0.clone()), 30_5595329);
+            assert_eq!(result.lot.get_unchecked(underlying_1.clone()), 1_5395739);
+
+            let result = create_user_liq_auction_data(&e, &samwise, 40, &vec![&e]);
+            assert_eq!(result.bid.get_unchecked(underlying_2), 1_1000000);
+        });
+    }
+
     #[test]
     fn test_create_user_liquidation_auction_weird_scalar() {
         let e = Env::default();
@@ -389,7 +722,7 @@ mod tests {
             storage::set_user_positions(&e, &samwise, &positions);
             storage::set_pool_config(&e, &pool_config);
 
-            let result = create_user_liq_auction_data(&e, &samwise, liq_pct);
+            let result = create_user_liq_auction_data(&e, &samwise, liq_pct, &vec![&e]);
 
             assert_eq!(result.block, 51);
             assert_eq!(result.bid.get_unchecked(underlying_1), 731_0913452);
@@ -481,7 +814,7 @@ mod tests {
         e.as_contract(&pool_address, || {
             storage::set_user_positions(&e, &samwise, &positions);
             storage::set_pool_config(&e, &pool_config);
-            let result = create_user_liq_auction_data(&e, &samwise, liq_pct);
+            let result = create_user_liq_auction_data(&e, &samwise, liq_pct, &vec![&e]);
             assert_eq!(result.block, 51);
             assert_eq!(result.bid.get_unchecked(underlying_1), 10_0000000);
             assert_eq!(result.bid.len(), 1);
@@ -593,7 +926,226 @@ mod tests {
             storage::set_user_positions(&e, &samwise, &positions);
             storage::set_pool_config(&e, &pool_config);
 
-            create_user_liq_auction_data(&e, &samwise, liq_pct);
+            create_user_liq_auction_data(&e, &samwise, liq_pct, &vec![&e]);
+        });
+    }
+    #[test]
+    fn test_create_user_liquidation_auction_bid_assets() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.b_rate = 1_100_000_000;
+        reserve_config_0.c_factor = 0_8500000;
+        reserve_config_0.l_factor = 0_9000000;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.b_rate = 1_200_000_000;
+        reserve_config_1.c_factor = 0_7500000;
+        reserve_config_1.l_factor = 0_7500000;
+        reserve_data_1.last_time = 12345;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
+        reserve_config_2.c_factor = 0_0000000;
+        reserve_config_2.l_factor = 0_7000000;
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 50_0000000]);
+
+        let liq_pct = 75;
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let positions: Positions = Positions {
+            collateral: map![&e, (reserve_config_0.index, 100_0000000)],
+            liabilities: map![
+                &e,
+                (reserve_config_1.index, 10_0000000),
+                (reserve_config_2.index, 02_2000000),
+            ],
+            supply: map![&e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+
+            let result = create_user_liq_auction_data(
+                &e,
+                &samwise,
+                liq_pct,
+                &vec![&e, underlying_2.clone()],
+            );
+            assert_eq!(result.block, 51);
+            assert_eq!(result.bid, map![&e, (underlying_2.clone(), 1_6500000)]);
+            assert_eq!(result.lot, map![&e, (underlying_0.clone(), 45_0948100)]);
+        });
+    }
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1211)")]
+    fn test_create_user_liquidation_auction_bid_asset_not_liability() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 50,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+
+        let (oracle_address, oracle_client) = testutils::create_mock_oracle(&e);
+
+        // creating reserves for a pool exhausts the budget
+        e.budget().reset_unlimited();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
+        reserve_data_0.last_time = 12345;
+        reserve_data_0.b_rate = 1_100_000_000;
+        reserve_config_0.c_factor = 0_8500000;
+        reserve_config_0.l_factor = 0_9000000;
+        reserve_config_0.index = 0;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
+        reserve_data_1.b_rate = 1_200_000_000;
+        reserve_config_1.c_factor = 0_7500000;
+        reserve_config_1.l_factor = 0_7500000;
+        reserve_data_1.last_time = 12345;
+        reserve_config_1.index = 1;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
+        reserve_config_2.c_factor = 0_0000000;
+        reserve_config_2.l_factor = 0_7000000;
+        reserve_config_2.index = 2;
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 50_0000000]);
+
+        let liq_pct = 40;
+        let pool_config = PoolConfig {
+            oracle: oracle_address,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let positions: Positions = Positions {
+            collateral: map![&e, (reserve_config_0.index, 100_0000000)],
+            liabilities: map![
+                &e,
+                (reserve_config_1.index, 10_0000000),
+                (reserve_config_2.index, 02_2000000),
+            ],
+            supply: map![&e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_pool_config(&e, &pool_config);
+
+            create_user_liq_auction_data(&e, &samwise, liq_pct, &vec![&e, underlying_0.clone()]);
         });
     }
     #[test]
@@ -699,7 +1251,7 @@ mod tests {
             storage::set_user_positions(&e, &samwise, &positions);
             storage::set_pool_config(&e, &pool_config);
 
-            create_user_liq_auction_data(&e, &samwise, liq_pct);
+            create_user_liq_auction_data(&e, &samwise, liq_pct, &vec![&e]);
         });
     }
 
@@ -806,7 +1358,7 @@ mod tests {
             storage::set_user_positions(&e, &samwise, &positions);
             storage::set_pool_config(&e, &pool_config);
 
-            create_user_liq_auction_data(&e, &samwise, liq_pct);
+            create_user_liq_auction_data(&e, &samwise, liq_pct, &vec![&e]);
         });
     }
 
@@ -981,6 +1533,7 @@ mod tests {
                     .unwrap_optimized(),
                 02_7500000 - 1_2375000
             );
+            assert!(storage::has_liquidation_filled(&e, &samwise));
         });
     }
 
@@ -6,6 +6,137 @@ pub const SCALAR_9: i128 = 1_000_000_000;
 /// Fixed-point scalar for 7 decimal numbers
 pub const SCALAR_7: i128 = 1_0000000;
 
+/********** Risk Parameters **********/
+
+/// The maximum collateral factor for a reserve (98%)
+pub const MAX_C_FACTOR: u32 = 0_9800000;
+
+/// The maximum liability factor for a reserve (100%)
+pub const MAX_L_FACTOR: u32 = 1_0000000;
+
+/// The exclusive upper bound for a reserve's target utilization (95%)
+pub const MAX_TARGET_UTIL: u32 = 0_9500000;
+
+/// The maximum reactivity constant for a reserve
+pub const MAX_REACTIVITY: u32 = 0_0001000;
+
+/// The maximum time in seconds a reduction to a reserve's collateral factor can be ramped in
+/// over (30 days)
+pub const MAX_C_FACTOR_RAMP: u64 = 30 * 24 * 60 * 60;
+
+/// The window the utilization used to update a reserve's interest rate modifier is averaged
+/// over (1 hour)
+pub const UTIL_AVG_WINDOW: u64 = 60 * 60;
+
+/// The maximum time in seconds a large withdrawal from a reserve can be required to be queued
+/// for (3 days)
+pub const MAX_WITHDRAW_QUEUE_DELAY: u64 = 3 * 24 * 60 * 60;
+
+/// The number of virtual bTokens included in a reserve's bToken supply when interest or credit is
+/// distributed to suppliers. The virtual bTokens absorb their share of any credit, so inflating the
+/// bToken rate of a nearly empty reserve costs the attacker almost the entire amount credited.
+pub const VIRTUAL_B_TOKENS: i128 = 1000;
+
+/********** Rate History **********/
+
+/// The minimum time in seconds between checkpoints of a reserve's bToken and dToken rates (1 day)
+pub const RATE_CHECKPOINT_INTERVAL: u64 = 24 * 60 * 60;
+
+/// The number of rate checkpoints kept per reserve before the oldest is overwritten (90 days)
+pub const MAX_RATE_CHECKPOINTS: u32 = 90;
+
+/********** Oracle **********/
+
+/// The minimum time in seconds between updates to an asset's cached oracle price (1 hour)
+pub const PRICE_CACHE_INTERVAL: u64 = 60 * 60;
+
+/// The time in seconds over which collateral valued at a cached price decays to zero once the
+/// oracle stops reporting a price for the asset (7 days)
+pub const PRICE_EMERGENCY_DECAY: u64 = 7 * 24 * 60 * 60;
+
+/********** Auctions **********/
+
+/// The default number of blocks an auction's lot is ramped in over, and its bid is decayed over
+/// (200 blocks, a 0.5% step per block)
+pub const DEFAULT_AUCTION_CURVE_BLOCKS: u32 = 200;
+
+/// The minimum number of blocks an auction's lot ramp or bid decay can be set to (20 blocks, a 5%
+/// step per block)
+pub const MIN_AUCTION_CURVE_BLOCKS: u32 = 20;
+
+/// The maximum number of blocks an auction's lot ramp or bid decay can be set to (2000 blocks, a
+/// 0.05% step per block)
+pub const MAX_AUCTION_CURVE_BLOCKS: u32 = 2000;
+
+/// The maximum minimum liquidation percent a user can register for their liquidation auctions
+/// (50%)
+pub const MAX_MIN_LIQUIDATION_PCT: u64 = 50;
+
+/// The premium over a user's remaining liabilities included in the lot of a surplus auction (5%)
+pub const SURPLUS_AUCTION_PREMIUM: i128 = 1_0500000;
+
+/********** Fixed-Term Loans **********/
+
+/// The minimum term in seconds a borrow's interest rate can be locked for (7 days)
+pub const MIN_FIXED_TERM: u64 = 7 * 24 * 60 * 60;
+
+/// The maximum term in seconds a borrow's interest rate can be locked for (365 days)
+pub const MAX_FIXED_TERM: u64 = 365 * 24 * 60 * 60;
+
+/// The penalty, as a percentage of principal, paid to the backstop when a fixed-term loan is
+/// repaid before it matures (1%)
+pub const FIXED_EARLY_REPAY_PENALTY: i128 = 0_0100000;
+
+/********** Stable Rate Debt **********/
+
+/// The premium over the variable borrow rate charged for borrowing at a stable rate (1%)
+pub const STABLE_RATE_PREMIUM: i128 = 0_0100000;
+
+/// The amount a stable rate can deviate from the variable borrow rate plus the premium before it
+/// can be rebalanced (5%)
+pub const STABLE_REBALANCE_DELTA: i128 = 0_0500000;
+
+/********** Collateral Top-Ups **********/
+
+/// The bounty, as a percentage of the amount topped up, paid to the keeper executing a top-up
+/// (0.5%)
+pub const TOP_UP_BOUNTY: i128 = 0_0050000;
+
+/********** Pool Status **********/
+
+/// The backstop threshold, as a percentage^5, a pool must reach to return to active from on-ice
+/// or frozen (~105%)
+pub const STATUS_ACTIVE_THRESHOLD: i128 = 1_2762816;
+
+/// The amount the percent of backstop deposits queued for withdrawal must fall below a status's
+/// entry point before the pool can leave that status (5%)
+pub const STATUS_Q4W_BAND: i128 = 0_0500000;
+
+/// The minimum time in seconds a pool must remain in a status before it can be relaxed to a less
+/// restrictive status (12 hours)
+pub const MIN_STATUS_DWELL: u64 = 12 * 60 * 60;
+
+/// The maximum time in seconds liquidations can be blocked after a pool returns to an active
+/// status (1 day)
+pub const MAX_LIQUIDATION_GRACE: u64 = 24 * 60 * 60;
+
+/********** Upgrades **********/
+
+/// The time in seconds an upgrade of the pool's WASM is timelocked for (31 days). This exceeds the
+/// backstop's maximum Q4W lock time, so backstop depositors can withdraw before a queued upgrade
+/// can be executed.
+pub const UPGRADE_TIMELOCK: u64 = 31 * 24 * 60 * 60;
+
+/********** Views **********/
+
+/// The maximum number of users whose positions can be fetched in a single batch
+pub const MAX_POSITIONS_BATCH: u32 = 50;
+
+/********** Swaps **********/
+
+/// The maximum slippage from the oracle price accepted when the pool swaps borrowed tokens (5%)
+pub const MAX_SWAP_SLIPPAGE: i128 = 0_0500000;
+
 // seconds per year
 pub const SECONDS_PER_YEAR: i128 = 31536000;
 
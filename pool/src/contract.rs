@@ -1,10 +1,20 @@
 use crate::{
-    auctions::{self, AuctionData},
-    emissions::{self, ReserveEmissionMetadata},
-    pool::{self, Positions, Request},
-    storage::{self, ReserveConfig},
+    auctions::{self, AuctionData, FillPreview},
+    emissions::{self, ReserveEmissionInfo, ReserveEmissionMetadata},
+    pool::{
+        self, InterestStats, Positions, Request, ReserveRates, SubmitSimulation, UserAccount,
+        UserNetRate,
+    },
+    storage::{
+        self, AuctionCurve, BadDebtRecord, CachedPrice, ExchangeRateConfig, FixedLoan,
+        PartnerCollateral, PoolMetadata, QueuedReserveInit, QueuedWithdrawal, RateCheckpoint,
+        ReserveBond, ReserveConfig, StableDebt, TopUpRule, WithdrawQueueConfig,
+    },
+    validator::{require_not_migrating, require_not_paused},
+};
+use soroban_sdk::{
+    contract, contractclient, contractimpl, Address, BytesN, Env, Map, String, Symbol, Vec,
 };
-use soroban_sdk::{contract, contractclient, contractimpl, Address, Env, String, Symbol, Vec};
 
 /// ### Pool
 ///
@@ -27,6 +37,7 @@ pub trait Pool {
     /// Pool Factory supplied:
     /// * `backstop_id` - The contract address of the pool's backstop module
     /// * `blnd_id` - The contract ID of the BLND token
+    /// * `pause_registry` - The contract ID of the pause registry
     #[allow(clippy::too_many_arguments)]
     fn initialize(
         e: Env,
@@ -37,6 +48,7 @@ pub trait Pool {
         max_positions: u32,
         backstop_id: Address,
         blnd_id: Address,
+        pause_registry: Address,
     );
 
     /// (Admin only) Set a new address as the admin of this pool
@@ -58,267 +70,1559 @@ pub trait Pool {
     /// If the caller is not the admin
     fn update_pool(e: Env, backstop_take_rate: u32, max_positions: u32);
 
-    /// (Admin only) Queues setting data for a reserve in the pool
+    /// (Admin only) Set the minimum percent of an auction that must be filled at once. Fills
+    /// that complete the remainder of an auction are always allowed.
     ///
     /// ### Arguments
-    /// * `asset` - The underlying asset to add as a reserve
-    /// * `config` - The ReserveConfig for the reserve
+    /// * `min_fill_pct` - The minimum fill percent (5 => 5%)
     ///
     /// ### Panics
-    /// If the caller is not the admin
-    fn queue_set_reserve(e: Env, asset: Address, metadata: ReserveConfig);
+    /// If the caller is not the admin or the percent is over 100
+    fn set_min_fill_pct(e: Env, min_fill_pct: u64);
 
-    /// (Admin only) Cancels the queued set of a reserve in the pool
+    /// Fetch the minimum percent of an auction that must be filled at once
+    fn get_min_fill_pct(e: Env) -> u64;
+
+    /// (Admin only) Set the maximum number of liabilities included in the bid of a bad debt
+    /// auction. When set, the largest liabilities by value are repaid first, so the auction can
+    /// be filled by holders of fewer assets. Remaining bad debt can be auctioned once the auction
+    /// is filled.
     ///
     /// ### Arguments
-    /// * `asset` - The underlying asset to add as a reserve
+    /// * `max_assets` - The maximum number of bid assets, or 0 to include every liability
     ///
     /// ### Panics
-    /// If the caller is not the admin or the reserve is not queued for initialization
-    fn cancel_set_reserve(e: Env, asset: Address);
+    /// If the caller is not the admin
+    fn set_bad_debt_max_assets(e: Env, max_assets: u32);
+
+    /// Fetch the maximum number of liabilities included in the bid of a bad debt auction
+    fn get_bad_debt_max_assets(e: Env) -> u32;
 
-    /// (Admin only) Executes the queued set of a reserve in the pool
+    /// (Admin only) Set the Dutch auction curve used to scale auctions when they are filled. The
+    /// lot is ramped in from 0% to 100% over `lot_blocks`, then the bid is decayed from 100% to 0%
+    /// over `bid_blocks`. Auctions already in progress keep the curve they were created with.
     ///
     /// ### Arguments
-    /// * `asset` - The underlying asset to add as a reserve
+    /// * `curve` - The auction curve. Defaults to 200 blocks for both.
     ///
     /// ### Panics
-    /// If the reserve is not queued for initialization
-    /// or is already setup
-    /// or has invalid metadata
-    fn set_reserve(e: Env, asset: Address) -> u32;
+    /// If the caller is not the admin or either window is outside of [20, 2000] blocks
+    fn set_auction_curve(e: Env, curve: AuctionCurve);
 
-    /// Fetch the positions for an address
+    /// Fetch the Dutch auction curve used to scale auctions
+    fn get_auction_curve(e: Env) -> AuctionCurve;
+
+    /// (Admin only) Set the maximum utilization a reserve can be left at by a Withdraw or
+    /// WithdrawCollateral request. Withdrawals that would exceed it fail with WithdrawUtilExceeded.
     ///
     /// ### Arguments
-    /// * `address` - The address to fetch positions for
-    fn get_positions(e: Env, address: Address) -> Positions;
-
-    /// Submit a set of requests to the pool where 'from' takes on the position, 'sender' sends any
-    /// required tokens to the pool and 'to' receives any tokens sent from the pool
+    /// * `util_cap` - The utilization cap (7 decimals). Defaults to 100%.
     ///
-    /// Returns the new positions for 'from'
+    /// ### Panics
+    /// If the caller is not the admin or the cap is zero or over 100%
+    fn set_withdraw_util_cap(e: Env, util_cap: u32);
+
+    /// Fetch the maximum utilization a reserve can be left at by a withdrawal (7 decimals)
+    fn get_withdraw_util_cap(e: Env) -> u32;
+
+    /// (Admin only) Set the time a reduction to a reserve's collateral factor is ramped in over
+    /// once the reserve update is executed. A ramp period of 0 applies reductions instantly.
     ///
     /// ### Arguments
-    /// * `from` - The address of the user whose positions are being modified
-    /// * `spender` - The address of the user who is sending tokens to the pool
-    /// * `to` - The address of the user who is receiving tokens from the pool
-    /// * `requests` - A vec of requests to be processed
+    /// * `ramp_period` - The ramp period in seconds. Defaults to one week.
     ///
     /// ### Panics
-    /// If the request is not able to be completed for cases like insufficient funds or invalid health factor
-    fn submit(
-        e: Env,
-        from: Address,
-        spender: Address,
-        to: Address,
-        requests: Vec<Request>,
-    ) -> Positions;
+    /// If the caller is not the admin or the ramp period is over 30 days
+    fn set_c_factor_ramp(e: Env, ramp_period: u64);
 
-    /// Manage bad debt. Debt is considered "bad" if there is no longer has any collateral posted.
+    /// Fetch the time in seconds a reduction to a reserve's collateral factor is ramped in over
+    fn get_c_factor_ramp(e: Env) -> u64;
+
+    /// (Admin only) Set the percent an asset's oracle price can move between pool interactions
+    /// before borrowing from its reserve is restricted
     ///
-    /// To manage a user's bad debt, all collateralized reserves for the user must be liquidated
-    /// before debt can be transferred to the backstop.
+    /// ### Arguments
+    /// * `threshold` - The price move threshold expressed in 7 decimals, or 0 to disable
     ///
-    /// To manage a backstop's bad debt, the backstop module must be below a critical threshold
-    /// to allow bad debt to be burnt.
+    /// ### Panics
+    /// If the caller is not the admin or the threshold is over 100%
+    fn set_price_breaker(e: Env, threshold: u32);
+
+    /// Fetch the percent an asset's oracle price can move between pool interactions before
+    /// borrowing from its reserve is restricted, or 0 if disabled
+    fn get_price_breaker(e: Env) -> u32;
+
+    /// (Admin only) Set the percent a reserve's bToken or dToken supply can grow within a
+    /// single ledger before the change is rejected. Decreases and admin flows are not limited.
     ///
     /// ### Arguments
-    /// * `user` - The user who currently possesses bad debt
+    /// * `threshold` - The supply change threshold expressed in 7 decimals, or 0 to disable
     ///
     /// ### Panics
-    /// If the user has collateral posted
-    fn bad_debt(e: Env, user: Address);
+    /// If the caller is not the admin or the threshold is over 100%
+    fn set_supply_breaker(e: Env, threshold: u32);
 
-    /// Update the pool status based on the backstop state - backstop triggered status' are odd numbers
-    /// * 1 = backstop active - if the minimum backstop deposit has been reached
-    ///                and 30% of backstop deposits are not queued for withdrawal
-    ///                then all pool operations are permitted
-    /// * 3 = backstop on-ice - if the minimum backstop deposit has not been reached
-    ///                or 30% of backstop deposits are queued for withdrawal and admin active isn't set
-    ///                or 50% of backstop deposits are queued for withdrawal
-    ///                then borrowing and cancelling liquidations are not permitted
-    /// * 5 = backstop frozen - if 60% of backstop deposits are queued for withdrawal and admin on-ice isn't set
-    ///                or 75% of backstop deposits are queued for withdrawal
-    ///                then all borrowing, cancelling liquidations, and supplying are not permitted
+    /// Fetch the percent a reserve's bToken or dToken supply can grow within a single ledger
+    /// before the change is rejected, or 0 if disabled
+    fn get_supply_breaker(e: Env) -> u32;
+
+    /// (Admin only) Set the time in seconds liquidations are blocked after the pool returns to an
+    /// active status from on-ice or frozen, so users can restore positions that became unhealthy
+    /// while they were unable to act
+    ///
+    /// ### Arguments
+    /// * `grace` - The grace period in seconds, or 0 to disable
     ///
     /// ### Panics
-    /// If the pool is currently on status 4, "admin-freeze", where only the admin
-    /// can perform a status update via `set_status`
-    fn update_status(e: Env) -> u32;
+    /// If the caller is not the admin or the grace period is over 1 day
+    fn set_liquidation_grace(e: Env, grace: u64);
 
-    /// (Admin only) Pool status is changed to "pool_status"
-    /// * 0 = admin active - requires that the backstop threshold is met
-    ///                 and less than 50% of backstop deposits are queued for withdrawal
-    /// * 2 = admin on-ice - requires that less than 75% of backstop deposits are queued for withdrawal
-    /// * 4 = admin frozen - can always be set
+    /// Fetch the time in seconds liquidations are blocked after the pool returns to an active
+    /// status, or 0 if disabled
+    fn get_liquidation_grace(e: Env) -> u64;
+
+    /// (Admin only) Resume borrowing from a reserve restricted by the price breaker
     ///
     /// ### Arguments
-    /// * 'pool_status' - The pool status to be set
+    /// * `asset` - The underlying asset of the reserve
     ///
     /// ### Panics
-    /// If the caller is not the admin
-    /// If the specified conditions are not met for the status to be set
-    fn set_status(e: Env, pool_status: u32);
+    /// If the caller is not the admin or the reserve is not restricted
+    fn clear_reserve_restriction(e: Env, asset: Address);
 
-    /********* Emission Functions **********/
+    /// Check if borrowing from a reserve is restricted by the price breaker
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn is_reserve_restricted(e: Env, asset: Address) -> bool;
 
-    /// Consume emissions from the backstop and distribute to the reserves based
-    /// on the reserve emission configuration.
+    /// (Admin only) Require withdrawals from a reserve above a percent of its available liquidity
+    /// to be queued before they can be executed, or remove the requirement if `config` is None
     ///
-    /// Returns amount of new tokens emitted
-    fn gulp_emissions(e: Env) -> i128;
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `config` - The withdrawal queue config for the reserve
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the asset is not a reserve, or the config is invalid
+    fn set_withdraw_queue(e: Env, asset: Address, config: Option<WithdrawQueueConfig>);
 
-    /// (Admin only) Set the emission configuration for the pool
+    /// Fetch the withdrawal queue config for a reserve, if one is set
     ///
-    /// Changes will be applied in the next pool `update_emissions`, and affect the next emission cycle
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_withdraw_queue(e: Env, asset: Address) -> Option<WithdrawQueueConfig>;
+
+    /// (Admin only) Hold a stablecoin reserve's price to a band around its peg of 1.00 in the
+    /// oracle's base asset, or remove the band if `band` is None. Collateral is valued at no more
+    /// than 1.00 + band and liabilities at no less than 1.00 - band, so a depeg in either direction
+    /// can't create free borrowing power or mass liquidations.
     ///
     /// ### Arguments
-    /// * `res_emission_metadata` - A vector of ReserveEmissionMetadata to update metadata to
+    /// * `asset` - The underlying asset of the reserve
+    /// * `band` - The peg band expressed in 7 decimals
     ///
     /// ### Panics
-    /// * If the caller is not the admin
-    /// * If the sum of ReserveEmissionMetadata shares is greater than 1
-    fn set_emissions_config(e: Env, res_emission_metadata: Vec<ReserveEmissionMetadata>);
+    /// If the caller is not the admin, the asset is not a reserve, or the band is not under 100%
+    fn set_peg_band(e: Env, asset: Address, band: Option<u32>);
 
-    /// Claims outstanding emissions for the caller for the given reserve's
+    /// Fetch the peg band for a stablecoin reserve, if one is set
     ///
-    /// Returns the number of tokens claimed
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_peg_band(e: Env, asset: Address) -> Option<u32>;
+
+    /// Fetch a user's queued withdrawal from a reserve, if one exists
     ///
     /// ### Arguments
-    /// * `from` - The address claiming
-    /// * `reserve_token_ids` - Vector of reserve token ids
-    /// * `to` - The Address to send the claimed tokens to
-    fn claim(e: Env, from: Address, reserve_token_ids: Vec<u32>, to: Address) -> i128;
+    /// * `user` - The address of the user
+    /// * `asset` - The underlying asset of the reserve
+    fn get_queued_withdrawal(e: Env, user: Address, asset: Address) -> Option<QueuedWithdrawal>;
 
-    /***** Auction / Liquidation Functions *****/
+    /// (Admin only) Queues setting the AMM used to sell collateral for ClosePosition requests
+    ///
+    /// ### Arguments
+    /// * `amm` - The contract address of the AMM
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or an AMM update is already queued
+    fn queue_set_amm(e: Env, amm: Address);
 
-    /// Creates a new user liquidation auction
+    /// (Admin only) Cancels the queued update to the AMM
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    fn cancel_set_amm(e: Env);
+
+    /// Executes the queued update to the AMM
+    ///
+    /// ### Panics
+    /// If no AMM update is queued or it is still locked
+    fn set_amm(e: Env);
+
+    /// Fetch the AMM used to sell collateral for ClosePosition requests, if one is set
+    fn get_amm(e: Env) -> Option<Address>;
+
+    /// (Admin only) Queues setting data for a reserve in the pool
     ///
     /// ### Arguments
-    /// * `user` - The user getting liquidated through the auction
-    /// * `percent_liquidated` - The percent of the user's position being liquidated as a percentage (15 => 15%)
+    /// * `asset` - The underlying asset to add as a reserve
+    /// * `config` - The ReserveConfig for the reserve
     ///
     /// ### Panics
-    /// If the user liquidation auction was unable to be created
-    fn new_liquidation_auction(e: Env, user: Address, percent_liquidated: u64) -> AuctionData;
+    /// If the caller is not the admin
+    fn queue_set_reserve(e: Env, asset: Address, metadata: ReserveConfig);
 
-    /// Fetch an auction from the ledger. Returns a quote based on the current block.
+    /// (Admin only) Cancels the queued set of a reserve in the pool. If the reserve was proposed
+    /// with a bond, the bond is slashed to the pool's backstop.
     ///
     /// ### Arguments
-    /// * `auction_type` - The type of auction, 0 for liquidation auction, 1 for bad debt auction, and 2 for interest auction
-    /// * `user` - The Address involved in the auction
+    /// * `asset` - The underlying asset to add as a reserve
     ///
     /// ### Panics
-    /// If the auction does not exist
-    fn get_auction(e: Env, auction_type: u32, user: Address) -> AuctionData;
+    /// If the caller is not the admin or the reserve is not queued for initialization
+    fn cancel_set_reserve(e: Env, asset: Address);
 
-    /// Creates a new bad debt auction
+    /// (Admin only) Executes the queued set of a reserve in the pool. If the reserve was proposed
+    /// with a bond, the bond is refunded to the proposer.
     ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset to add as a reserve
     ///
     /// ### Panics
-    /// If the auction was unable to be created
-    fn new_bad_debt_auction(e: Env) -> AuctionData;
+    /// If the reserve is not queued for initialization
+    /// or is already setup
+    /// or has invalid metadata
+    fn set_reserve(e: Env, asset: Address) -> u32;
 
-    /// Creates a new interest auction
+    /// Propose a new reserve for the pool by bonding the pool's listing bond in backstop tokens.
+    /// The reserve is queued as if by the admin. The bond is refunded once the reserve is set,
+    /// and slashed to the pool's backstop if the admin cancels the proposal.
     ///
     /// ### Arguments
-    /// * `assets` - The assets interest is being auctioned off for
+    /// * `from` - The address proposing the reserve and posting the bond
+    /// * `asset` - The underlying asset to add as a reserve
+    /// * `metadata` - The ReserveConfig for the reserve
     ///
     /// ### Panics
-    /// If the auction was unable to be created
-    fn new_interest_auction(e: Env, assets: Vec<Address>) -> AuctionData;
-}
+    /// If the pool does not accept reserve proposals, the asset is already a reserve or is
+    /// queued, or the metadata is invalid
+    fn propose_reserve(e: Env, from: Address, asset: Address, metadata: ReserveConfig);
 
-#[contractimpl]
-impl Pool for PoolContract {
-    #[allow(clippy::too_many_arguments)]
-    fn initialize(
-        e: Env,
-        admin: Address,
-        name: String,
-        oracle: Address,
-        bstop_rate: u32,
-        max_postions: u32,
-        backstop_id: Address,
-        blnd_id: Address,
-    ) {
-        storage::extend_instance(&e);
-        admin.require_auth();
+    /// (Admin only) Set the amount of backstop tokens that must be bonded to propose a new
+    /// reserve. Reserve proposals are disabled if the amount is 0.
+    ///
+    /// ### Arguments
+    /// * `amount` - The bond amount
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or the amount is negative
+    fn set_listing_bond(e: Env, amount: i128);
 
-        pool::execute_initialize(
-            &e,
-            &admin,
-            &name,
-            &oracle,
-            &bstop_rate,
-            &max_postions,
-            &backstop_id,
-            &blnd_id,
-        );
-    }
+    /// Fetch the amount of backstop tokens that must be bonded to propose a new reserve, or 0
+    /// if reserve proposals are disabled
+    fn get_listing_bond(e: Env) -> i128;
 
-    fn set_admin(e: Env, new_admin: Address) {
-        storage::extend_instance(&e);
-        let admin = storage::get_admin(&e);
-        admin.require_auth();
-        new_admin.require_auth();
+    /// Fetch the bond posted to propose a reserve, if one exists
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the proposed reserve
+    fn get_reserve_bond(e: Env, asset: Address) -> Option<ReserveBond>;
 
-        storage::set_admin(&e, &new_admin);
+    /// Fetch all pending queued reserve sets, keyed by asset. Each entry contains the queued
+    /// ReserveConfig and the timestamp at which `set_reserve` can execute it.
+    fn get_queued_reserve_changes(e: Env) -> Map<Address, QueuedReserveInit>;
 
-        e.events()
-            .publish((Symbol::new(&e, "set_admin"), admin), new_admin);
-    }
+    /// (Admin only) Queues setting the exchange rate source used to price a yield-bearing reserve
+    /// asset from a base asset's oracle price, or removing it if `config` is None
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `config` - The ExchangeRateConfig for the reserve
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the asset is not a reserve, an update is already queued,
+    /// or the config is invalid
+    fn queue_set_exchange_rate(e: Env, asset: Address, config: Option<ExchangeRateConfig>);
 
-    fn update_pool(e: Env, backstop_take_rate: u32, max_positions: u32) {
-        storage::extend_instance(&e);
-        let admin = storage::get_admin(&e);
-        admin.require_auth();
+    /// (Admin only) Cancels the queued exchange rate update for a reserve
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    fn cancel_set_exchange_rate(e: Env, asset: Address);
 
-        pool::execute_update_pool(&e, backstop_take_rate, max_positions);
+    /// Executes the queued exchange rate update for a reserve
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    ///
+    /// ### Panics
+    /// If no update is queued or it is still locked
+    fn set_exchange_rate(e: Env, asset: Address);
 
-        e.events().publish(
-            (Symbol::new(&e, "update_pool"), admin),
-            (backstop_take_rate, max_positions),
-        );
-    }
+    /// Fetch the exchange rate config for a reserve, if one is set
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_exchange_rate(e: Env, asset: Address) -> Option<ExchangeRateConfig>;
 
-    fn queue_set_reserve(e: Env, asset: Address, metadata: ReserveConfig) {
-        storage::extend_instance(&e);
-        let admin = storage::get_admin(&e);
-        admin.require_auth();
+    /// (Admin only) Add or remove a pool from the partner pool whitelist. A pool that already
+    /// whitelists this pool cannot be added.
+    ///
+    /// ### Arguments
+    /// * `pool` - The address of the partner pool
+    /// * `enabled` - If the pool is whitelisted
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the pool is this pool or already whitelists this pool, or
+    /// a reserve is still priced from a pool being removed
+    fn set_partner_pool(e: Env, pool: Address, enabled: bool);
 
-        pool::execute_queue_set_reserve(&e, &asset, &metadata);
+    /// Check if a pool is a whitelisted partner pool
+    ///
+    /// ### Arguments
+    /// * `pool` - The address of the pool
+    fn is_partner_pool(e: Env, pool: Address) -> bool;
 
-        e.events().publish(
-            (Symbol::new(&e, "queue_set_reserve"), admin),
-            (asset, metadata),
-        );
-    }
+    /// (Admin only) Set a reserve to be priced as bTokens of a whitelisted partner pool, or
+    /// remove the config if `config` is None
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `config` - The PartnerCollateral config for the reserve
+    ///
+    /// ### Panics
+    /// If the caller is not the admin, the asset is not a reserve, or the config is invalid
+    fn set_partner_collateral(e: Env, asset: Address, config: Option<PartnerCollateral>);
 
-    fn cancel_set_reserve(e: Env, asset: Address) {
-        storage::extend_instance(&e);
-        let admin = storage::get_admin(&e);
-        admin.require_auth();
+    /// Fetch the partner pool bToken config for a reserve, if one is set
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_partner_collateral(e: Env, asset: Address) -> Option<PartnerCollateral>;
 
-        pool::execute_cancel_queued_set_reserve(&e, &asset);
+    /// Fetch the current bToken rate of a reserve, including interest accrued since the reserve
+    /// was last updated, expressed in 9 decimals
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    ///
+    /// ### Panics
+    /// If the asset is not a reserve in the pool
+    fn get_b_rate(e: Env, asset: Address) -> i128;
 
-        e.events()
-            .publish((Symbol::new(&e, "cancel_set_reserve"), admin), asset);
-    }
+    /// Fetch up to the latest `records` checkpoints of a reserve's bToken and dToken rates,
+    /// ordered from oldest to newest. Checkpoints are taken at most once per day when the
+    /// reserve is updated, and the latest 90 are kept.
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `records` - The maximum number of checkpoints to return
+    fn get_rate_history(e: Env, asset: Address, records: u32) -> Vec<RateCheckpoint>;
 
-    fn set_reserve(e: Env, asset: Address) -> u32 {
-        let index = pool::execute_set_reserve(&e, &asset);
+    /// Fetch the last oracle price the pool cached for an asset, if one exists. If the cached
+    /// price is marked as an emergency, the oracle no longer reports a price for the asset and
+    /// the pool is valuing positions in it with the cached price.
+    ///
+    /// ### Arguments
+    /// * `asset` - The asset priced by the oracle
+    fn get_cached_price(e: Env, asset: Address) -> Option<CachedPrice>;
 
-        e.events()
-            .publish((Symbol::new(&e, "set_reserve"),), (asset, index));
-        index
+    /// (Admin only) Queues an update to the pool's metadata
+    ///
+    /// ### Arguments
+    /// * `metadata` - The new PoolMetadata for the pool
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or a metadata update is already queued
+    fn queue_set_metadata(e: Env, metadata: PoolMetadata);
+
+    /// (Admin only) Cancels the queued update to the pool's metadata
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    fn cancel_set_metadata(e: Env);
+
+    /// Executes the queued update to the pool's metadata
+    ///
+    /// ### Panics
+    /// If no metadata update is queued or it is still locked
+    fn set_metadata(e: Env);
+
+    /// Fetch the pool's metadata
+    fn get_metadata(e: Env) -> PoolMetadata;
+
+    /// (Admin only) Queues an upgrade of the pool's WASM, which can be executed after 31 days
+    ///
+    /// ### Arguments
+    /// * `wasm_hash` - The hash of the installed WASM to upgrade to
+    ///
+    /// ### Panics
+    /// If the caller is not the admin or an upgrade is already queued
+    fn queue_upgrade(e: Env, wasm_hash: BytesN<32>);
+
+    /// (Admin only) Cancels the queued upgrade of the pool's WASM
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    fn cancel_upgrade(e: Env);
+
+    /// Executes the queued upgrade of the pool's WASM. The new WASM is used starting with the
+    /// next invocation, and `migrate` must be called to complete the upgrade. All other state
+    /// changing functions fail until it is.
+    ///
+    /// ### Panics
+    /// If no upgrade is queued or it is still locked
+    fn upgrade(e: Env);
+
+    /// Migrates the pool's stored state after an upgrade, rewriting every reserve's config and
+    /// data in the current storage layout
+    ///
+    /// ### Panics
+    /// If no migration is pending
+    fn migrate(e: Env);
+
+    /// Fetch the positions for an address
+    ///
+    /// ### Arguments
+    /// * `address` - The address to fetch positions for
+    fn get_positions(e: Env, address: Address) -> Positions;
+
+    /// Fetch the positions for a batch of addresses, so the positions of many users can be
+    /// snapshotted with a single read
+    ///
+    /// Returns a vec of (address, positions) in the order the addresses are given
+    ///
+    /// ### Arguments
+    /// * `users` - The addresses to fetch positions for
+    ///
+    /// ### Panics
+    /// If more than 50 addresses are given
+    fn get_positions_batch(e: Env, users: Vec<Address>) -> Vec<(Address, Positions)>;
+
+    /// Fetch a snapshot of a user's account, including their positions, the underlying
+    /// value of each position, their claimable emissions, and any active liquidation auction
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_user_account(e: Env, user: Address) -> UserAccount;
+
+    /// Fetch the annual rates a user earns and pays across all of their positions, relative to
+    /// the net value of their account. This includes the interest earned on supply, the interest
+    /// paid on liabilities, and the value of the emissions earned if the oracle can price BLND.
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    ///
+    /// ### Panics
+    /// If the price of a reserve the user has a position in is stale
+    fn get_user_net_rate(e: Env, user: Address) -> UserNetRate;
+
+    /// Fetch the lifetime interest paid by borrowers and earned by suppliers for a reserve
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    ///
+    /// ### Panics
+    /// If the asset is not a reserve in the pool
+    fn get_reserve_interest(e: Env, asset: Address) -> InterestStats;
+
+    /// Fetch the lifetime interest paid and earned by a user for a reserve. Interest is
+    /// tracked from the first time the user's position in the reserve is modified.
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `asset` - The underlying asset of the reserve
+    ///
+    /// ### Panics
+    /// If the asset is not a reserve in the pool
+    fn get_user_interest(e: Env, user: Address, asset: Address) -> InterestStats;
+
+    /// Fetch the maximum amount of an asset a user can withdraw from their supply and collateral
+    /// without exceeding the withdraw utilization cap. Collateral withdrawals must also leave the
+    /// user's account healthy, which is not accounted for.
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    /// * `user` - The address of the user
+    ///
+    /// ### Panics
+    /// If the asset is not a reserve in the pool
+    fn max_withdrawable(e: Env, asset: Address, user: Address) -> i128;
+
+    /// Fetch the current borrow and supply interest rates for a reserve, along with the
+    /// utilization and interest rate modifier they are derived from
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    ///
+    /// ### Panics
+    /// If the asset is not a reserve in the pool
+    fn get_reserve_rates(e: Env, asset: Address) -> ReserveRates;
+
+    /// Fetch the total liabilities of the pool, including interest accrued since each reserve
+    /// was last updated, valued in the oracle's base asset with 7 decimals
+    ///
+    /// ### Panics
+    /// If the price of a reserve with liabilities is stale
+    fn get_total_liabilities(e: Env) -> i128;
+
+    /// Fetch the total liabilities of the pool, including interest accrued since each reserve
+    /// was last updated, valued in an asset priced by the pool's oracle with 7 decimals
+    ///
+    /// ### Arguments
+    /// * `asset` - The asset to value the liabilities in
+    ///
+    /// ### Panics
+    /// If the price of the asset or a reserve with liabilities is stale
+    fn get_total_liabilities_in(e: Env, asset: Address) -> i128;
+
+    /// Submit a set of requests to the pool where 'from' takes on the position, 'sender' sends any
+    /// required tokens to the pool and 'to' receives any tokens sent from the pool
+    ///
+    /// Auctions for any number of users can be filled in one submit. The health of 'from' is checked
+    /// once after all requests are processed and token transfers are aggregated per asset. The lot
+    /// of a filled interest auction is sent to 'to', while its bid is paid by 'from'.
+    ///
+    /// Returns the new positions for 'from'
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user whose positions are being modified
+    /// * `spender` - The address of the user who is sending tokens to the pool
+    /// * `to` - The address of the user who is receiving tokens from the pool
+    /// * `requests` - A vec of requests to be processed
+    ///
+    /// ### Panics
+    /// If the request is not able to be completed for cases like insufficient funds or invalid health factor
+    fn submit(
+        e: Env,
+        from: Address,
+        spender: Address,
+        to: Address,
+        requests: Vec<Request>,
+    ) -> Positions;
+
+    /// Simulate a submit for a user at the current ledger without requiring auth, transferring
+    /// tokens, or storing the resulting positions. Only supply, withdraw, borrow, and repay
+    /// requests can be simulated.
+    ///
+    /// Returns the tokens the spender would send, the tokens the pool would send, the resulting
+    /// positions, and the resulting health factor with 7 decimals
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user whose positions are being simulated
+    /// * `requests` - A vec of requests to be simulated
+    ///
+    /// ### Panics
+    /// If a request cannot be simulated or is unable to be completed
+    fn simulate_submit(e: Env, from: Address, requests: Vec<Request>) -> SubmitSimulation;
+
+    /// Borrow from a reserve at its current borrow rate, locked for a fixed term. The loan is
+    /// held as a regular liability for health and liquidation purposes, but can only be repaid
+    /// with `repay_fixed`.
+    ///
+    /// Returns the fixed-term loan
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user borrowing
+    /// * `to` - The address receiving the borrowed tokens
+    /// * `asset` - The underlying asset to borrow
+    /// * `amount` - The amount of underlying tokens to borrow
+    /// * `term` - The time in seconds the interest rate is locked for
+    ///
+    /// ### Panics
+    /// If the term is outside the allowed range, the user already has a fixed-term loan for the
+    /// reserve, or the borrow leaves the user below the minimum health factor
+    fn borrow_fixed(
+        e: Env,
+        from: Address,
+        to: Address,
+        asset: Address,
+        amount: i128,
+        term: u64,
+    ) -> FixedLoan;
+
+    /// Repay a fixed-term loan with interest at its locked rate. Loans repaid before they mature
+    /// pay a penalty to the backstop.
+    ///
+    /// Returns the total amount of underlying tokens paid by the spender
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user whose loan is repaid
+    /// * `spender` - The address sending the tokens to the pool
+    /// * `asset` - The underlying asset of the loan
+    ///
+    /// ### Panics
+    /// If the user has no fixed-term loan for the reserve
+    fn repay_fixed(e: Env, from: Address, spender: Address, asset: Address) -> i128;
+
+    /// Fetch a user's fixed-term loans, keyed by reserve index
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_fixed_loans(e: Env, user: Address) -> Map<u32, FixedLoan>;
+
+    /// Borrow from a reserve at a stable rate, set to the reserve's variable borrow rate plus a
+    /// premium. The debt is held as a regular liability for health and liquidation purposes, but
+    /// must be swapped to the variable rate with `swap_to_variable` before it can be repaid.
+    ///
+    /// Returns the user's stable rate debt for the reserve
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user borrowing
+    /// * `to` - The address receiving the borrowed tokens
+    /// * `asset` - The underlying asset to borrow
+    /// * `amount` - The amount of underlying tokens to borrow
+    ///
+    /// ### Panics
+    /// If the borrow leaves the user below the minimum health factor
+    fn borrow_stable(
+        e: Env,
+        from: Address,
+        to: Address,
+        asset: Address,
+        amount: i128,
+    ) -> StableDebt;
+
+    /// Move a user's stable rate debt for a reserve to the variable rate
+    ///
+    /// Returns the dTokens moved to the variable rate
+    ///
+    /// ### Arguments
+    /// * `from` - The address of the user
+    /// * `asset` - The underlying asset of the debt
+    ///
+    /// ### Panics
+    /// If the user has no stable rate debt for the reserve
+    fn swap_to_variable(e: Env, from: Address, asset: Address) -> i128;
+
+    /// (Permissionless) Rebalance a user's stable rate debt to the reserve's current stable rate.
+    /// Only debt whose rate deviates from the current stable rate by more than the rebalance
+    /// delta can be rebalanced.
+    ///
+    /// Returns the new stable rate of the debt
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user whose debt is rebalanced
+    /// * `asset` - The underlying asset of the debt
+    ///
+    /// ### Panics
+    /// If the user has no stable rate debt for the reserve or the debt's rate is within the
+    /// rebalance delta
+    fn rebalance_stable(e: Env, user: Address, asset: Address) -> i128;
+
+    /// Fetch a user's stable rate debts, keyed by reserve index
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_stable_debts(e: Env, user: Address) -> Map<u32, StableDebt>;
+
+    /// Fetch the dTokens of a reserve backing stable rate debt
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_stable_supply(e: Env, asset: Address) -> i128;
+
+    /// Manage bad debt. Debt is considered "bad" if there is no longer has any collateral posted.
+    ///
+    /// To manage a user's bad debt, all collateralized reserves for the user must be liquidated
+    /// before debt can be transferred to the backstop.
+    ///
+    /// To manage a backstop's bad debt, the backstop module must be below a critical threshold
+    /// to allow bad debt to be burnt.
+    ///
+    /// ### Arguments
+    /// * `user` - The user who currently possesses bad debt
+    ///
+    /// ### Panics
+    /// If the user has collateral posted
+    fn bad_debt(e: Env, user: Address);
+
+    /// Fetch records from the bad debt log. A record is created for each liability transferred
+    /// from a user to the backstop, in the order they were transferred.
+    ///
+    /// ### Arguments
+    /// * `start` - The index to start enumerating records from
+    /// * `limit` - The maximum number of records to return
+    fn get_bad_debt_log(e: Env, start: u32, limit: u32) -> Vec<BadDebtRecord>;
+
+    /// Update the pool status based on the backstop state - backstop triggered status' are odd numbers
+    /// * 1 = backstop active - if the minimum backstop deposit has been reached
+    ///                and 30% of backstop deposits are not queued for withdrawal
+    ///                then all pool operations are permitted
+    /// * 3 = backstop on-ice - if the minimum backstop deposit has not been reached
+    ///                or 30% of backstop deposits are queued for withdrawal and admin active isn't set
+    ///                or 50% of backstop deposits are queued for withdrawal
+    ///                then borrowing and cancelling liquidations are not permitted
+    /// * 5 = backstop frozen - if 60% of backstop deposits are queued for withdrawal and admin on-ice isn't set
+    ///                or 75% of backstop deposits are queued for withdrawal
+    ///                then all borrowing, cancelling liquidations, and supplying are not permitted
+    ///
+    /// To prevent oscillation, a backstop triggered status is only left once the queued percentage
+    /// falls 5% below its entry point (and the backstop reaches ~105% of the minimum deposit to
+    /// become active), and a less restrictive status can only be set 12 hours after the last
+    /// status change.
+    ///
+    /// ### Panics
+    /// If the pool is currently on status 4, "admin-freeze", where only the admin
+    /// can perform a status update via `set_status`
+    fn update_status(e: Env) -> u32;
+
+    /// (Admin only) Pool status is changed to "pool_status"
+    /// * 0 = admin active - requires that the backstop threshold is met
+    ///                 and less than 50% of backstop deposits are queued for withdrawal
+    /// * 2 = admin on-ice - requires that less than 75% of backstop deposits are queued for withdrawal
+    /// * 4 = admin frozen - can always be set
+    ///
+    /// ### Arguments
+    /// * 'pool_status' - The pool status to be set
+    ///
+    /// ### Panics
+    /// If the caller is not the admin
+    /// If the specified conditions are not met for the status to be set
+    fn set_status(e: Env, pool_status: u32);
+
+    /// Fetch the pool's status
+    fn get_status(e: Env) -> u32;
+
+    /// Bring the given reserves up to date without changing any user positions. Accrues interest,
+    /// updates the interest rate modifier, refreshes the reserve token emission indexes, and
+    /// extends the TTLs of the reserves' ledger entries. Can be called by anyone.
+    ///
+    /// ### Arguments
+    /// * `assets` - The underlying assets of the reserves to update
+    ///
+    /// ### Panics
+    /// If an asset is not a reserve of the pool
+    fn crank(e: Env, assets: Vec<Address>);
+
+    /********* Emission Functions **********/
+
+    /// Consume emissions from the backstop and distribute to the reserves based
+    /// on the reserve emission configuration.
+    ///
+    /// Returns amount of new tokens emitted
+    fn gulp_emissions(e: Env) -> i128;
+
+    /// Consume BLND tokens streamed directly to the pool by the emitter and distribute them
+    /// to the reserves based on the reserve emission configuration.
+    ///
+    /// Returns amount of new tokens emitted
+    fn gulp_direct_emissions(e: Env) -> i128;
+
+    /// (Admin only) Set the emission configuration for the pool
+    ///
+    /// Changes will be applied in the next pool `update_emissions`, and affect the next emission cycle
+    ///
+    /// ### Arguments
+    /// * `res_emission_metadata` - A vector of ReserveEmissionMetadata to update metadata to
+    ///
+    /// ### Panics
+    /// * If the caller is not the admin
+    /// * If a ReserveEmissionMetadata entry is not a b or d token of a reserve in the pool
+    /// * If a reserve token is included more than once
+    /// * If the sum of ReserveEmissionMetadata shares is not exactly 1
+    fn set_emissions_config(e: Env, res_emission_metadata: Vec<ReserveEmissionMetadata>);
+
+    /// Fetch the emission config of a reserve token, including the tokens it has left to emit
+    /// before the config expires, or None if the reserve token has never received emissions
+    ///
+    /// ### Arguments
+    /// * `reserve_token_id` - The reserve token id => (reserve index * 2 + (0 for debtToken or 1 for blendToken)),
+    ///   offset by 0x10000 for collateral blendTokens or 0x20000 for non-collateral blendTokens
+    fn get_emission_config(e: Env, reserve_token_id: u32) -> Option<ReserveEmissionInfo>;
+
+    /// Claims outstanding emissions for the caller for the given reserve's
+    ///
+    /// Returns the number of tokens claimed
+    ///
+    /// ### Arguments
+    /// * `from` - The address claiming
+    /// * `reserve_token_ids` - Vector of reserve token ids => (reserve index * 2 + (0 for debtToken or 1 for blendToken)),
+    ///   offset by 0x10000 for collateral blendTokens or 0x20000 for non-collateral blendTokens
+    /// * `to` - The Address to send the claimed tokens to
+    fn claim(e: Env, from: Address, reserve_token_ids: Vec<u32>, to: Address) -> i128;
+
+    /// Register an address anyone can claim the caller's emissions to, or remove the
+    /// registration if `to` is None
+    ///
+    /// ### Arguments
+    /// * `from` - The address registering
+    /// * `to` - The Address to send claimed tokens to, or None to disable auto claims
+    fn set_auto_claim(e: Env, from: Address, to: Option<Address>);
+
+    /// Fetch the address a user's emissions can be claimed to by anyone, if one is registered
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_auto_claim(e: Env, user: Address) -> Option<Address>;
+
+    /// Register a contract the pool calls with a summary of each of the caller's submits after
+    /// it completes, or remove the registration if `hook` is None
+    ///
+    /// Soroban does not allow the hook to re-enter the pool, so it cannot submit to the pool. A
+    /// hook that fails is reported with a "submit_hook_failed" event and does not fail the submit,
+    /// but it shares the submit's resource budget.
+    ///
+    /// ### Arguments
+    /// * `from` - The address registering
+    /// * `hook` - The address of the hook contract, or None to disable the hook
+    fn set_submit_hook(e: Env, from: Address, hook: Option<Address>);
+
+    /// Fetch the contract notified after a user's submits, if one is registered
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_submit_hook(e: Env, user: Address) -> Option<Address>;
+
+    /// Register the minimum percent of the caller's liabilities a liquidation auction can
+    /// liquidate, or remove the registration if `percent` is None. A smaller liquidation is
+    /// only allowed if liquidating the minimum would raise the caller's health factor over the
+    /// post-liquidation cap.
+    ///
+    /// ### Arguments
+    /// * `from` - The address registering
+    /// * `percent` - The minimum percent liquidated (15 => 15%), or None to remove the minimum
+    ///
+    /// ### Panics
+    /// If the percent is zero or over the protocol's maximum minimum liquidation percent
+    fn set_min_liquidation(e: Env, from: Address, percent: Option<u64>);
+
+    /// Fetch the minimum percent of a user's liabilities a liquidation auction can liquidate,
+    /// if one is registered
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    fn get_min_liquidation(e: Env, user: Address) -> Option<u64>;
+
+    /// Register a rule allowing keepers to top up the caller's collateral in a reserve when
+    /// their health factor falls below the rule's trigger, or remove the rule if `rule` is None.
+    /// Top-ups are pulled from the caller's token allowance to the pool.
+    ///
+    /// ### Arguments
+    /// * `from` - The address registering
+    /// * `asset` - The underlying asset of the reserve to top up
+    /// * `rule` - The top-up rule, or None to remove the rule
+    ///
+    /// ### Panics
+    /// If the asset is not a reserve that can be used as collateral, or the rule does not
+    /// trigger above a health factor of 1 or has no amount to top up
+    fn set_top_up(e: Env, from: Address, asset: Address, rule: Option<TopUpRule>);
+
+    /// Fetch a user's collateral top-up rule for a reserve, if one is registered
+    ///
+    /// ### Arguments
+    /// * `user` - The address of the user
+    /// * `asset` - The underlying asset of the reserve
+    fn get_top_up(e: Env, user: Address, asset: Address) -> Option<TopUpRule>;
+
+    /// Top up a user's collateral in a reserve from their allowance to the pool, restoring
+    /// their health factor to the trigger of their top-up rule. The keeper is paid a bounty
+    /// from the amount topped up.
+    ///
+    /// Returns the amount of underlying tokens supplied as collateral for the user
+    ///
+    /// ### Arguments
+    /// * `keeper` - The address executing the top-up, who receives the bounty
+    /// * `user` - The address of the user whose collateral is topped up
+    /// * `asset` - The underlying asset of the reserve to top up
+    ///
+    /// ### Panics
+    /// If the user has no top-up rule for the reserve, the user's health factor is not under
+    /// the rule's trigger, or the user's allowance or balance is too low
+    fn top_up(e: Env, keeper: Address, user: Address, asset: Address) -> i128;
+
+    /// Claims outstanding emissions for a user for the given reserve's and sends them to the
+    /// user's registered auto claim address. Can be called by anyone.
+    ///
+    /// Returns the number of tokens claimed
+    ///
+    /// ### Arguments
+    /// * `user` - The address whose emissions are claimed
+    /// * `reserve_token_ids` - Vector of reserve token ids, formatted the same as `claim`
+    ///
+    /// ### Panics
+    /// If the user has not registered an auto claim address
+    fn claim_for(e: Env, user: Address, reserve_token_ids: Vec<u32>) -> i128;
+
+    /***** Auction / Liquidation Functions *****/
+
+    /// Creates a new user liquidation auction
+    ///
+    /// ### Arguments
+    /// * `user` - The user getting liquidated through the auction
+    /// * `percent_liquidated` - The percent of the user's position being liquidated as a percentage (15 => 15%)
+    ///
+    /// ### Panics
+    /// If the user liquidation auction was unable to be created
+    fn new_liquidation_auction(e: Env, user: Address, percent_liquidated: u64) -> AuctionData;
+
+    /// Creates a new user liquidation auction where the bid is restricted to a subset of the
+    /// user's liabilities
+    ///
+    /// ### Arguments
+    /// * `user` - The user getting liquidated through the auction
+    /// * `percent_liquidated` - The percent of each bid liability being liquidated as a percentage (15 => 15%)
+    /// * `bid_assets` - The liability assets to include in the bid
+    ///
+    /// ### Panics
+    /// If the user liquidation auction was unable to be created, or if a bid asset is not
+    /// a liability of the user
+    fn new_liquidation_auction_with_bid(
+        e: Env,
+        user: Address,
+        percent_liquidated: u64,
+        bid_assets: Vec<Address>,
+    ) -> AuctionData;
+
+    /// Creates new user liquidation auctions for a batch of users. Users whose liabilities are
+    /// covered by their collateral are skipped and reported with a "liquidation_skipped" event.
+    ///
+    /// Returns a vec of (user, auction data) for each auction created
+    ///
+    /// ### Arguments
+    /// * `liquidations` - A vec of (user, percent_liquidated) for each user to liquidate
+    ///
+    /// ### Panics
+    /// If a user liquidation auction for a user that was not skipped was unable to be created
+    fn new_liquidation_auctions(
+        e: Env,
+        liquidations: Vec<(Address, u64)>,
+    ) -> Vec<(Address, AuctionData)>;
+
+    /// (User only) Creates a new surplus auction for a user whose collateral exceeds their remaining
+    /// liabilities after a liquidation auction against them was filled. The auction sells the
+    /// user's collateral worth their remaining liabilities plus a premium for those liabilities at
+    /// a fixed price, and the rest of the collateral stays with the user.
+    ///
+    /// ### Arguments
+    /// * `user` - The user whose surplus collateral is being auctioned
+    ///
+    /// ### Panics
+    /// If the surplus auction was unable to be created
+    fn new_surplus_auction(e: Env, user: Address) -> AuctionData;
+
+    /// Fetch an auction from the ledger. Returns a quote based on the current block.
+    ///
+    /// ### Arguments
+    /// * `auction_type` - The type of auction, 0 for liquidation auction, 1 for bad debt auction, 2 for interest auction, and 3 for surplus auction
+    /// * `user` - The Address involved in the auction
+    ///
+    /// ### Panics
+    /// If the auction does not exist
+    fn get_auction(e: Env, auction_type: u32, user: Address) -> AuctionData;
+
+    /// Preview filling an auction at the current block. Returns the scaled bid and lot, and their
+    /// values in the oracle's base asset.
+    ///
+    /// ### Arguments
+    /// * `auction_type` - The type of auction, 0 for liquidation auction, 1 for bad debt auction, 2 for interest auction, and 3 for surplus auction
+    /// * `user` - The Address involved in the auction
+    /// * `percent_filled` - The percentage being filled as a number (i.e. 15 => 15%)
+    ///
+    /// ### Panics
+    /// If the auction does not exist or the percent filled is not within (0, 100]
+    fn preview_fill(e: Env, auction_type: u32, user: Address, percent_filled: u64) -> FillPreview;
+
+    /// Fetch the active auctions of a given type
+    ///
+    /// Returns a vec of (user, auction data) for each active auction
+    ///
+    /// ### Arguments
+    /// * `auction_type` - The type of auction, 0 for liquidation auction, 1 for bad debt auction, 2 for interest auction, and 3 for surplus auction
+    /// * `start` - The index to start enumerating auctions from
+    /// * `limit` - The maximum number of auctions to return
+    fn get_auctions(
+        e: Env,
+        auction_type: u32,
+        start: u32,
+        limit: u32,
+    ) -> Vec<(Address, AuctionData)>;
+
+    /// Creates a new bad debt auction
+    ///
+    ///
+    /// ### Panics
+    /// If the auction was unable to be created
+    fn new_bad_debt_auction(e: Env) -> AuctionData;
+
+    /// Creates a new interest auction
+    ///
+    /// ### Arguments
+    /// * `assets` - The assets interest is being auctioned off for. Only the listed reserves
+    ///   are included in the lot.
+    ///
+    /// ### Panics
+    /// If the auction was unable to be created or an asset is listed more than once
+    fn new_interest_auction(e: Env, assets: Vec<Address>) -> AuctionData;
+}
+
+#[contractimpl]
+impl Pool for PoolContract {
+    #[allow(clippy::too_many_arguments)]
+    fn initialize(
+        e: Env,
+        admin: Address,
+        name: String,
+        oracle: Address,
+        bstop_rate: u32,
+        max_postions: u32,
+        backstop_id: Address,
+        blnd_id: Address,
+        pause_registry: Address,
+    ) {
+        storage::extend_instance(&e);
+        admin.require_auth();
+
+        pool::execute_initialize(
+            &e,
+            &admin,
+            &name,
+            &oracle,
+            &bstop_rate,
+            &max_postions,
+            &backstop_id,
+            &blnd_id,
+            &pause_registry,
+        );
+    }
+
+    fn set_admin(e: Env, new_admin: Address) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+        new_admin.require_auth();
+
+        storage::set_admin(&e, &new_admin);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_admin"), admin), new_admin);
+    }
+
+    fn update_pool(e: Env, backstop_take_rate: u32, max_positions: u32) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_update_pool(&e, backstop_take_rate, max_positions);
+
+        e.events().publish(
+            (Symbol::new(&e, "update_pool"), admin),
+            (backstop_take_rate, max_positions),
+        );
+    }
+
+    fn set_min_fill_pct(e: Env, min_fill_pct: u64) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_min_fill_pct(&e, min_fill_pct);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_min_fill_pct"), admin), min_fill_pct);
+    }
+
+    fn get_min_fill_pct(e: Env) -> u64 {
+        storage::get_min_fill_pct(&e)
+    }
+
+    fn set_bad_debt_max_assets(e: Env, max_assets: u32) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        storage::set_bad_debt_max_assets(&e, &max_assets);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_bad_debt_max_assets"), admin),
+            max_assets,
+        );
+    }
+
+    fn get_bad_debt_max_assets(e: Env) -> u32 {
+        storage::get_bad_debt_max_assets(&e)
+    }
+
+    fn set_auction_curve(e: Env, curve: AuctionCurve) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_auction_curve(&e, &curve);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_auction_curve"), admin), curve);
+    }
+
+    fn get_auction_curve(e: Env) -> AuctionCurve {
+        storage::get_auction_curve(&e)
+    }
+
+    fn set_withdraw_util_cap(e: Env, util_cap: u32) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_withdraw_util_cap(&e, util_cap);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_withdraw_util_cap"), admin), util_cap);
+    }
+
+    fn get_withdraw_util_cap(e: Env) -> u32 {
+        storage::get_withdraw_util_cap(&e)
+    }
+
+    fn set_c_factor_ramp(e: Env, ramp_period: u64) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_c_factor_ramp(&e, ramp_period);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_c_factor_ramp"), admin), ramp_period);
+    }
+
+    fn get_c_factor_ramp(e: Env) -> u64 {
+        storage::get_c_factor_ramp(&e)
+    }
+
+    fn set_price_breaker(e: Env, threshold: u32) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_price_breaker(&e, threshold);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_price_breaker"), admin), threshold);
+    }
+
+    fn get_price_breaker(e: Env) -> u32 {
+        storage::get_price_breaker(&e)
+    }
+
+    fn set_supply_breaker(e: Env, threshold: u32) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_supply_breaker(&e, threshold);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_supply_breaker"), admin), threshold);
+    }
+
+    fn get_supply_breaker(e: Env) -> u32 {
+        storage::get_supply_breaker(&e)
+    }
+
+    fn set_liquidation_grace(e: Env, grace: u64) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_liquidation_grace(&e, grace);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_liquidation_grace"), admin), grace);
+    }
+
+    fn get_liquidation_grace(e: Env) -> u64 {
+        storage::get_liquidation_grace(&e)
+    }
+
+    fn clear_reserve_restriction(e: Env, asset: Address) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_clear_reserve_restriction(&e, &asset);
+
+        e.events()
+            .publish((Symbol::new(&e, "clear_reserve_restriction"), admin), asset);
+    }
+
+    fn is_reserve_restricted(e: Env, asset: Address) -> bool {
+        storage::get_reserve_restricted(&e, &asset)
+    }
+
+    fn set_withdraw_queue(e: Env, asset: Address, config: Option<WithdrawQueueConfig>) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_withdraw_queue(&e, &asset, &config);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_withdraw_queue"), admin),
+            (asset, config),
+        );
+    }
+
+    fn get_withdraw_queue(e: Env, asset: Address) -> Option<WithdrawQueueConfig> {
+        storage::get_withdraw_queue_config(&e, &asset)
+    }
+
+    fn set_peg_band(e: Env, asset: Address, band: Option<u32>) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_peg_band(&e, &asset, &band);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_peg_band"), admin), (asset, band));
+    }
+
+    fn get_peg_band(e: Env, asset: Address) -> Option<u32> {
+        storage::get_peg_band(&e, &asset)
+    }
+
+    fn get_queued_withdrawal(e: Env, user: Address, asset: Address) -> Option<QueuedWithdrawal> {
+        let reserve_config = storage::get_res_config(&e, &asset);
+        storage::get_queued_withdrawal(&e, &user, reserve_config.index)
+    }
+
+    fn queue_set_amm(e: Env, amm: Address) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_queue_set_amm(&e, &amm);
+
+        e.events()
+            .publish((Symbol::new(&e, "queue_set_amm"), admin), amm);
+    }
+
+    fn cancel_set_amm(e: Env) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_cancel_queued_set_amm(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "cancel_set_amm"), admin), ());
+    }
+
+    fn set_amm(e: Env) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let amm = pool::execute_set_amm(&e);
+
+        e.events().publish((Symbol::new(&e, "set_amm"),), amm);
+    }
+
+    fn get_amm(e: Env) -> Option<Address> {
+        storage::get_amm(&e)
+    }
+
+    fn queue_set_reserve(e: Env, asset: Address, metadata: ReserveConfig) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_queue_set_reserve(&e, &asset, &metadata);
+
+        e.events().publish(
+            (Symbol::new(&e, "queue_set_reserve"), admin),
+            (asset, metadata),
+        );
+    }
+
+    fn cancel_set_reserve(e: Env, asset: Address) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        let bond = pool::execute_cancel_queued_set_reserve(&e, &asset);
+
+        if let Some(bond) = bond {
+            e.events().publish(
+                (Symbol::new(&e, "slash_reserve_bond"), bond.proposer),
+                (asset.clone(), bond.amount),
+            );
+        }
+        e.events()
+            .publish((Symbol::new(&e, "cancel_set_reserve"), admin), asset);
+    }
+
+    fn set_reserve(e: Env, asset: Address) -> u32 {
+        require_not_migrating(&e);
+        let index = pool::execute_set_reserve(&e, &asset);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_reserve"),), (asset, index));
+        index
+    }
+
+    fn propose_reserve(e: Env, from: Address, asset: Address, metadata: ReserveConfig) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
+        from.require_auth();
+
+        let bond = pool::execute_propose_reserve(&e, &from, &asset, &metadata);
+
+        e.events().publish(
+            (Symbol::new(&e, "propose_reserve"), from),
+            (asset, metadata, bond.amount),
+        );
+    }
+
+    fn set_listing_bond(e: Env, amount: i128) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_listing_bond(&e, amount);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_listing_bond"), admin), amount);
+    }
+
+    fn get_listing_bond(e: Env) -> i128 {
+        storage::get_listing_bond(&e)
+    }
+
+    fn get_reserve_bond(e: Env, asset: Address) -> Option<ReserveBond> {
+        storage::get_reserve_bond(&e, &asset)
+    }
+
+    fn get_queued_reserve_changes(e: Env) -> Map<Address, QueuedReserveInit> {
+        pool::get_queued_reserve_changes(&e)
+    }
+
+    fn queue_set_exchange_rate(e: Env, asset: Address, config: Option<ExchangeRateConfig>) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_queue_set_exchange_rate(&e, &asset, &config);
+
+        e.events().publish(
+            (Symbol::new(&e, "queue_set_exchange_rate"), admin),
+            (asset, config),
+        );
+    }
+
+    fn cancel_set_exchange_rate(e: Env, asset: Address) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_cancel_queued_set_exchange_rate(&e, &asset);
+
+        e.events()
+            .publish((Symbol::new(&e, "cancel_set_exchange_rate"), admin), asset);
+    }
+
+    fn set_exchange_rate(e: Env, asset: Address) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let config = pool::execute_set_exchange_rate(&e, &asset);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_exchange_rate"),), (asset, config));
+    }
+
+    fn get_exchange_rate(e: Env, asset: Address) -> Option<ExchangeRateConfig> {
+        storage::get_exchange_rate_config(&e, &asset)
+    }
+
+    fn set_partner_pool(e: Env, pool: Address, enabled: bool) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_partner_pool(&e, &pool, enabled);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_partner_pool"), admin),
+            (pool, enabled),
+        );
+    }
+
+    fn is_partner_pool(e: Env, pool: Address) -> bool {
+        storage::is_partner_pool(&e, &pool)
+    }
+
+    fn set_partner_collateral(e: Env, asset: Address, config: Option<PartnerCollateral>) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_set_partner_collateral(&e, &asset, &config);
+
+        e.events().publish(
+            (Symbol::new(&e, "set_partner_collateral"), admin),
+            (asset, config),
+        );
+    }
+
+    fn get_partner_collateral(e: Env, asset: Address) -> Option<PartnerCollateral> {
+        storage::get_partner_collateral(&e, &asset)
+    }
+
+    fn get_b_rate(e: Env, asset: Address) -> i128 {
+        let mut pool = pool::Pool::load(&e);
+        pool.load_reserve(&e, &asset, false).b_rate
+    }
+
+    fn get_rate_history(e: Env, asset: Address, records: u32) -> Vec<RateCheckpoint> {
+        let history = storage::get_rate_history(&e, &asset);
+        let start = history.len().saturating_sub(records);
+        history.slice(start..)
+    }
+
+    fn get_cached_price(e: Env, asset: Address) -> Option<CachedPrice> {
+        storage::get_cached_price(&e, &asset)
+    }
+
+    fn queue_set_metadata(e: Env, metadata: PoolMetadata) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_queue_set_metadata(&e, &metadata);
+
+        e.events()
+            .publish((Symbol::new(&e, "queue_set_metadata"), admin), metadata);
+    }
+
+    fn cancel_set_metadata(e: Env) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_cancel_queued_set_metadata(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "cancel_set_metadata"), admin), ());
+    }
+
+    fn set_metadata(e: Env) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let metadata = pool::execute_set_metadata(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_metadata"),), metadata);
+    }
+
+    fn get_metadata(e: Env) -> PoolMetadata {
+        storage::get_metadata(&e)
+    }
+
+    fn queue_upgrade(e: Env, wasm_hash: BytesN<32>) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_queue_upgrade(&e, &wasm_hash);
+
+        e.events()
+            .publish((Symbol::new(&e, "queue_upgrade"), admin), wasm_hash);
+    }
+
+    fn cancel_upgrade(e: Env) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let admin = storage::get_admin(&e);
+        admin.require_auth();
+
+        pool::execute_cancel_queued_upgrade(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "cancel_upgrade"), admin), ());
+    }
+
+    fn upgrade(e: Env) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        let wasm_hash = pool::execute_upgrade(&e);
+
+        e.events().publish((Symbol::new(&e, "upgrade"),), wasm_hash);
+    }
+
+    fn migrate(e: Env) {
+        storage::extend_instance(&e);
+        pool::execute_migrate(&e);
+
+        e.events().publish((Symbol::new(&e, "migrate"),), ());
     }
 
     fn get_positions(e: Env, address: Address) -> Positions {
         storage::get_user_positions(&e, &address)
     }
 
+    fn get_positions_batch(e: Env, users: Vec<Address>) -> Vec<(Address, Positions)> {
+        pool::load_positions_batch(&e, &users)
+    }
+
+    fn get_user_account(e: Env, user: Address) -> UserAccount {
+        pool::load_user_account(&e, &user)
+    }
+
+    fn get_user_net_rate(e: Env, user: Address) -> UserNetRate {
+        pool::calc_user_net_rate(&e, &user)
+    }
+
+    fn get_reserve_interest(e: Env, asset: Address) -> InterestStats {
+        pool::calc_reserve_interest(&e, &asset)
+    }
+
+    fn get_user_interest(e: Env, user: Address, asset: Address) -> InterestStats {
+        pool::calc_user_interest(&e, &user, &asset)
+    }
+
+    fn max_withdrawable(e: Env, asset: Address, user: Address) -> i128 {
+        pool::calc_max_withdrawable(&e, &asset, &user)
+    }
+
+    fn get_reserve_rates(e: Env, asset: Address) -> ReserveRates {
+        pool::calc_reserve_rates(&e, &asset)
+    }
+
+    fn get_total_liabilities(e: Env) -> i128 {
+        pool::calc_total_liabilities(&e)
+    }
+
+    fn get_total_liabilities_in(e: Env, asset: Address) -> i128 {
+        pool::calc_total_liabilities_in(&e, &asset)
+    }
+
     fn submit(
         e: Env,
         from: Address,
@@ -327,6 +1631,8 @@ impl Pool for PoolContract {
         requests: Vec<Request>,
     ) -> Positions {
         storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
         spender.require_auth();
         if from != spender {
             from.require_auth();
@@ -335,12 +1641,113 @@ impl Pool for PoolContract {
         pool::execute_submit(&e, &from, &spender, &to, requests)
     }
 
+    fn simulate_submit(e: Env, from: Address, requests: Vec<Request>) -> SubmitSimulation {
+        pool::simulate_submit(&e, &from, requests)
+    }
+
+    fn borrow_fixed(
+        e: Env,
+        from: Address,
+        to: Address,
+        asset: Address,
+        amount: i128,
+        term: u64,
+    ) -> FixedLoan {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
+        from.require_auth();
+
+        let loan = pool::execute_borrow_fixed(&e, &from, &to, &asset, amount, term);
+        e.events().publish(
+            (Symbol::new(&e, "borrow_fixed"), asset, from),
+            (loan.principal, loan.d_tokens, loan.rate, loan.maturity),
+        );
+        loan
+    }
+
+    fn repay_fixed(e: Env, from: Address, spender: Address, asset: Address) -> i128 {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
+        spender.require_auth();
+
+        let (repaid, penalty) = pool::execute_repay_fixed(&e, &from, &spender, &asset);
+        e.events().publish(
+            (Symbol::new(&e, "repay_fixed"), asset, from),
+            (repaid, penalty),
+        );
+        repaid + penalty
+    }
+
+    fn get_fixed_loans(e: Env, user: Address) -> Map<u32, FixedLoan> {
+        storage::get_fixed_loans(&e, &user)
+    }
+
+    fn borrow_stable(
+        e: Env,
+        from: Address,
+        to: Address,
+        asset: Address,
+        amount: i128,
+    ) -> StableDebt {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
+        from.require_auth();
+
+        let (d_tokens_minted, debt) = pool::execute_borrow_stable(&e, &from, &to, &asset, amount);
+        e.events().publish(
+            (Symbol::new(&e, "borrow_stable"), asset, from),
+            (amount, d_tokens_minted, debt.rate),
+        );
+        debt
+    }
+
+    fn swap_to_variable(e: Env, from: Address, asset: Address) -> i128 {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
+        from.require_auth();
+
+        let d_tokens = pool::execute_swap_to_variable(&e, &from, &asset);
+        e.events()
+            .publish((Symbol::new(&e, "swap_to_variable"), asset, from), d_tokens);
+        d_tokens
+    }
+
+    fn rebalance_stable(e: Env, user: Address, asset: Address) -> i128 {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
+
+        let rate = pool::execute_rebalance_stable(&e, &user, &asset);
+        e.events()
+            .publish((Symbol::new(&e, "rebalance_stable"), asset, user), rate);
+        rate
+    }
+
+    fn get_stable_debts(e: Env, user: Address) -> Map<u32, StableDebt> {
+        storage::get_stable_debts(&e, &user)
+    }
+
+    fn get_stable_supply(e: Env, asset: Address) -> i128 {
+        storage::get_stable_supply(&e, &asset)
+    }
+
     fn bad_debt(e: Env, user: Address) {
+        require_not_migrating(&e);
+        require_not_paused(&e);
         pool::transfer_bad_debt_to_backstop(&e, &user);
     }
 
+    fn get_bad_debt_log(e: Env, start: u32, limit: u32) -> Vec<BadDebtRecord> {
+        pool::get_bad_debt_log(&e, start, limit)
+    }
+
     fn update_status(e: Env) -> u32 {
         storage::extend_instance(&e);
+        require_not_migrating(&e);
         let new_status = pool::execute_update_pool_status(&e);
 
         e.events()
@@ -350,6 +1757,7 @@ impl Pool for PoolContract {
 
     fn set_status(e: Env, pool_status: u32) {
         storage::extend_instance(&e);
+        require_not_migrating(&e);
         let admin = storage::get_admin(&e);
         admin.require_auth();
         pool::execute_set_pool_status(&e, pool_status);
@@ -357,10 +1765,25 @@ impl Pool for PoolContract {
             .publish((Symbol::new(&e, "set_status"), admin), pool_status);
     }
 
+    fn get_status(e: Env) -> u32 {
+        storage::get_pool_config(&e).status
+    }
+
+    fn crank(e: Env, assets: Vec<Address>) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
+        pool::execute_crank(&e, &assets);
+
+        e.events().publish((Symbol::new(&e, "crank"),), assets);
+    }
+
     /********* Emission Functions **********/
 
     fn gulp_emissions(e: Env) -> i128 {
         storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
         let next_expiration = emissions::gulp_emissions(&e);
 
         e.events()
@@ -368,15 +1791,33 @@ impl Pool for PoolContract {
         next_expiration
     }
 
+    fn gulp_direct_emissions(e: Env) -> i128 {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
+        let new_emissions = emissions::gulp_direct_emissions(&e);
+
+        e.events()
+            .publish((Symbol::new(&e, "gulp_direct_emissions"),), new_emissions);
+        new_emissions
+    }
+
     fn set_emissions_config(e: Env, res_emission_metadata: Vec<ReserveEmissionMetadata>) {
+        require_not_migrating(&e);
         let admin = storage::get_admin(&e);
         admin.require_auth();
 
         emissions::set_pool_emissions(&e, res_emission_metadata);
     }
 
+    fn get_emission_config(e: Env, reserve_token_id: u32) -> Option<ReserveEmissionInfo> {
+        emissions::load_emission_info(&e, reserve_token_id)
+    }
+
     fn claim(e: Env, from: Address, reserve_token_ids: Vec<u32>, to: Address) -> i128 {
         storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
         from.require_auth();
 
         let amount_claimed = emissions::execute_claim(&e, &from, &reserve_token_ids, &to);
@@ -389,10 +1830,129 @@ impl Pool for PoolContract {
         amount_claimed
     }
 
+    fn set_auto_claim(e: Env, from: Address, to: Option<Address>) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        from.require_auth();
+
+        match &to {
+            Some(to) => storage::set_auto_claim(&e, &from, to),
+            None => storage::del_auto_claim(&e, &from),
+        }
+
+        e.events()
+            .publish((Symbol::new(&e, "set_auto_claim"), from), to);
+    }
+
+    fn get_auto_claim(e: Env, user: Address) -> Option<Address> {
+        storage::get_auto_claim(&e, &user)
+    }
+
+    fn set_submit_hook(e: Env, from: Address, hook: Option<Address>) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        from.require_auth();
+
+        match &hook {
+            Some(hook) => storage::set_submit_hook(&e, &from, hook),
+            None => storage::del_submit_hook(&e, &from),
+        }
+
+        e.events()
+            .publish((Symbol::new(&e, "set_submit_hook"), from), hook);
+    }
+
+    fn get_submit_hook(e: Env, user: Address) -> Option<Address> {
+        storage::get_submit_hook(&e, &user)
+    }
+
+    fn set_min_liquidation(e: Env, from: Address, percent: Option<u64>) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        from.require_auth();
+
+        auctions::execute_set_min_liquidation(&e, &from, percent);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_min_liquidation"), from), percent);
+    }
+
+    fn get_min_liquidation(e: Env, user: Address) -> Option<u64> {
+        storage::get_min_liquidation(&e, &user)
+    }
+
+    fn set_top_up(e: Env, from: Address, asset: Address, rule: Option<TopUpRule>) {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        from.require_auth();
+
+        pool::execute_set_top_up(&e, &from, &asset, &rule);
+
+        e.events()
+            .publish((Symbol::new(&e, "set_top_up"), asset, from), rule);
+    }
+
+    fn get_top_up(e: Env, user: Address, asset: Address) -> Option<TopUpRule> {
+        if !storage::has_res(&e, &asset) {
+            return None;
+        }
+        let reserve_index = storage::get_res_config(&e, &asset).index;
+        storage::get_top_up_rule(&e, &user, reserve_index)
+    }
+
+    fn top_up(e: Env, keeper: Address, user: Address, asset: Address) -> i128 {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
+        keeper.require_auth();
+
+        let (amount_supplied, bounty) = pool::execute_top_up(&e, &keeper, &user, &asset);
+        e.events().publish(
+            (Symbol::new(&e, "top_up"), asset, user),
+            (keeper, amount_supplied, bounty),
+        );
+        amount_supplied
+    }
+
+    fn claim_for(e: Env, user: Address, reserve_token_ids: Vec<u32>) -> i128 {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
+
+        let (to, amount_claimed) = emissions::execute_claim_for(&e, &user, &reserve_token_ids);
+
+        e.events().publish(
+            (Symbol::new(&e, "claim_for"), user),
+            (reserve_token_ids, amount_claimed, to),
+        );
+
+        amount_claimed
+    }
+
     /***** Auction / Liquidation Functions *****/
 
     fn new_liquidation_auction(e: Env, user: Address, percent_liquidated: u64) -> AuctionData {
-        let auction_data = auctions::create_liquidation(&e, &user, percent_liquidated);
+        require_not_migrating(&e);
+        require_not_paused(&e);
+        let auction_data =
+            auctions::create_liquidation(&e, &user, percent_liquidated, &Vec::new(&e));
+
+        e.events().publish(
+            (Symbol::new(&e, "new_liquidation_auction"), user),
+            auction_data.clone(),
+        );
+        auction_data
+    }
+
+    fn new_liquidation_auction_with_bid(
+        e: Env,
+        user: Address,
+        percent_liquidated: u64,
+        bid_assets: Vec<Address>,
+    ) -> AuctionData {
+        require_not_migrating(&e);
+        require_not_paused(&e);
+        let auction_data = auctions::create_liquidation(&e, &user, percent_liquidated, &bid_assets);
 
         e.events().publish(
             (Symbol::new(&e, "new_liquidation_auction"), user),
@@ -401,12 +1961,62 @@ impl Pool for PoolContract {
         auction_data
     }
 
+    fn new_liquidation_auctions(
+        e: Env,
+        liquidations: Vec<(Address, u64)>,
+    ) -> Vec<(Address, AuctionData)> {
+        require_not_migrating(&e);
+        require_not_paused(&e);
+        let (created, skipped) = auctions::create_liquidations(&e, &liquidations);
+
+        for (user, auction_data) in created.iter() {
+            e.events().publish(
+                (Symbol::new(&e, "new_liquidation_auction"), user),
+                auction_data,
+            );
+        }
+        for user in skipped.iter() {
+            e.events()
+                .publish((Symbol::new(&e, "liquidation_skipped"), user), ());
+        }
+        created
+    }
+
+    fn new_surplus_auction(e: Env, user: Address) -> AuctionData {
+        storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
+        user.require_auth();
+        let auction_data = auctions::create_surplus_auction(&e, &user);
+
+        e.events().publish(
+            (Symbol::new(&e, "new_surplus_auction"), user),
+            auction_data.clone(),
+        );
+        auction_data
+    }
+
     fn get_auction(e: Env, auction_type: u32, user: Address) -> AuctionData {
         storage::get_auction(&e, &auction_type, &user)
     }
 
+    fn preview_fill(e: Env, auction_type: u32, user: Address, percent_filled: u64) -> FillPreview {
+        auctions::preview_fill(&e, auction_type, &user, percent_filled)
+    }
+
+    fn get_auctions(
+        e: Env,
+        auction_type: u32,
+        start: u32,
+        limit: u32,
+    ) -> Vec<(Address, AuctionData)> {
+        auctions::get_auctions(&e, auction_type, start, limit)
+    }
+
     fn new_bad_debt_auction(e: Env) -> AuctionData {
         storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
         let auction_data = auctions::create_bad_debt_auction(&e);
 
         e.events().publish(
@@ -419,6 +2029,8 @@ impl Pool for PoolContract {
 
     fn new_interest_auction(e: Env, assets: Vec<Address>) -> AuctionData {
         storage::extend_instance(&e);
+        require_not_migrating(&e);
+        require_not_paused(&e);
         let auction_data = auctions::create_interest_auction(&e, &assets);
 
         e.events().publish(
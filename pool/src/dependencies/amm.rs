@@ -0,0 +1,20 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// The interface the pool requires from an AMM to sell collateral. Matches the Comet
+/// swap interface.
+#[allow(dead_code)]
+#[contractclient(name = "AmmClient")]
+pub trait Amm {
+    /// Swap at most `max_amount_in` of `token_in` for exactly `token_amount_out` of `token_out`
+    ///
+    /// Returns (amount_in, spot_price_after)
+    fn swap_exact_amount_out(
+        e: Env,
+        token_in: Address,
+        max_amount_in: i128,
+        token_out: Address,
+        token_amount_out: i128,
+        max_price: i128,
+        user: Address,
+    ) -> (i128, i128);
+}
@@ -0,0 +1,13 @@
+use soroban_sdk::{contractclient, Env};
+
+/// The interface the pool requires from a contract reporting the exchange rate of a
+/// yield-bearing token to its base asset, such as a liquid staking token
+#[allow(dead_code)]
+#[contractclient(name = "ExchangeRateClient")]
+pub trait ExchangeRate {
+    /// Fetch the amount of the base asset one token is worth
+    ///
+    /// Returns (rate, timestamp) where rate is expressed in 9 decimals and timestamp is the
+    /// ledger timestamp the rate was last updated at
+    fn exchange_rate(e: Env) -> (i128, u64);
+}
@@ -1,2 +1,20 @@
+mod amm;
+pub use amm::AmmClient;
+
+mod exchange_rate;
+pub use exchange_rate::ExchangeRateClient;
+
 mod backstop;
 pub use backstop::{Client as BackstopClient, PoolBackstopData};
+
+mod emitter;
+pub use emitter::Client as EmitterClient;
+
+mod partner_pool;
+pub use partner_pool::PartnerPoolClient;
+
+mod pause_registry;
+pub use pause_registry::Client as PauseRegistryClient;
+
+mod submit_hook;
+pub use submit_hook::SubmitHookClient;
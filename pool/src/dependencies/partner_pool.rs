@@ -0,0 +1,19 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// The interface the pool requires from a partner pool whose bTokens are used as collateral.
+/// Blend pools implement this interface natively.
+#[allow(dead_code)]
+#[contractclient(name = "PartnerPoolClient")]
+pub trait PartnerPool {
+    /// Fetch the current bToken rate of a reserve, expressed in 9 decimals
+    ///
+    /// ### Arguments
+    /// * `asset` - The underlying asset of the reserve
+    fn get_b_rate(e: Env, asset: Address) -> i128;
+
+    /// Check if a pool is whitelisted as a partner pool
+    ///
+    /// ### Arguments
+    /// * `pool` - The address of the pool
+    fn is_partner_pool(e: Env, pool: Address) -> bool;
+}
@@ -0,0 +1,17 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+use crate::pool::SubmitSummary;
+
+/// The interface the pool calls on a contract a user registered to be notified after each of
+/// their submits completes
+#[allow(dead_code)]
+#[contractclient(name = "SubmitHookClient")]
+pub trait SubmitHook {
+    /// Called after a submit for `user` completes and all tokens have been transferred
+    ///
+    /// ### Arguments
+    /// * `pool` - The address of the pool
+    /// * `user` - The address of the user whose positions were modified
+    /// * `summary` - The requests and transfers of the submit, and the user's resulting positions
+    fn on_submit(e: Env, pool: Address, user: Address, summary: SubmitSummary);
+}
@@ -5,25 +5,53 @@ use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env, Vec};
 
 use crate::{
     errors::PoolError,
-    pool::User,
+    pool::{Positions, Reserve, User},
     storage::{self, ReserveEmissionsData, UserEmissionData},
     validator::require_nonnegative,
     ReserveEmissionsConfig,
 };
 
+/// The offset between the reserve token ids of each blendToken emission type
+const RES_TYPE_ID_OFFSET: u32 = 0x10000;
+
+/// Get the reserve token id used to track emissions for a reserve token type
+///
+/// ### Arguments
+/// * `res_index` - The index of the reserve
+/// * `res_type` - The reserve token type => (0 for debtToken, 1 for blendToken, 2 for collateral blendToken, or 3 for non-collateral blendToken)
+pub fn to_res_token_id(res_index: u32, res_type: u32) -> u32 {
+    match res_type {
+        0 => res_index * 2,
+        _ => (res_type - 1) * RES_TYPE_ID_OFFSET + res_index * 2 + 1,
+    }
+}
+
+/// Get the reserve index and reserve token type from a reserve token id
+///
+/// ### Arguments
+/// * `res_token_id` - The reserve token id
+pub fn from_res_token_id(res_token_id: u32) -> (u32, u32) {
+    let res_index = (res_token_id % RES_TYPE_ID_OFFSET) / 2;
+    if res_token_id % 2 == 1 {
+        (res_index, res_token_id / RES_TYPE_ID_OFFSET + 1)
+    } else {
+        (res_index, 0)
+    }
+}
+
 /// Performs a claim against the given "reserve_token_ids" for "from"
 pub fn execute_claim(e: &Env, from: &Address, reserve_token_ids: &Vec<u32>, to: &Address) -> i128 {
     let from_state = User::load(e, from);
     let reserve_list = storage::get_res_list(e);
     let mut to_claim = 0;
     for reserve_token_id in reserve_token_ids.clone() {
-        let reserve_index = reserve_token_id / 2;
+        let (reserve_index, res_type) = from_res_token_id(reserve_token_id);
         let reserve_addr = reserve_list.get(reserve_index);
         match reserve_addr {
             Some(res_address) => {
                 let reserve_config = storage::get_res_config(e, &res_address);
                 let reserve_data = storage::get_res_data(e, &res_address);
-                let (user_balance, supply) = match reserve_token_id % 2 {
+                let (user_balance, supply) = match res_type {
                     0 => (
                         from_state.get_liabilities(reserve_index),
                         reserve_data.d_supply,
@@ -32,6 +60,14 @@ pub fn execute_claim(e: &Env, from: &Address, reserve_token_ids: &Vec<u32>, to:
                         from_state.get_total_supply(reserve_index),
                         reserve_data.b_supply,
                     ),
+                    2 => (
+                        from_state.get_collateral(reserve_index),
+                        reserve_data.c_supply,
+                    ),
+                    3 => (
+                        from_state.get_supply(reserve_index),
+                        reserve_data.b_supply - reserve_data.c_supply,
+                    ),
                     _ => panic_with_error!(e, PoolError::BadRequest),
                 };
                 to_claim += claim_emissions(
@@ -50,18 +86,120 @@ pub fn execute_claim(e: &Env, from: &Address, reserve_token_ids: &Vec<u32>, to:
     }
 
     if to_claim > 0 {
-        let backstop = storage::get_backstop(e);
-        let blnd_token = storage::get_blnd_token(e);
-        TokenClient::new(e, &blnd_token).transfer_from(
-            &e.current_contract_address(),
-            &backstop,
-            to,
-            &to_claim,
-        );
+        let blnd_token_client = TokenClient::new(e, &storage::get_blnd_token(e));
+        // pay out of direct emissions held by the pool before drawing from the backstop
+        let direct_emissions = storage::get_direct_emissions(e);
+        let from_direct = to_claim.min(direct_emissions);
+        if from_direct > 0 {
+            storage::set_direct_emissions(e, &(direct_emissions - from_direct));
+            blnd_token_client.transfer(&e.current_contract_address(), to, &from_direct);
+        }
+        let from_backstop = to_claim - from_direct;
+        if from_backstop > 0 {
+            let backstop = storage::get_backstop(e);
+            blnd_token_client.transfer_from(
+                &e.current_contract_address(),
+                &backstop,
+                to,
+                &from_backstop,
+            );
+        }
     }
     to_claim
 }
 
+/// Performs a claim against the given "reserve_token_ids" for "user" on their behalf, sending
+/// the emissions to the address the user registered for auto claims
+///
+/// Returns a tuple of (to, amount_claimed)
+///
+/// ### Panics
+/// If the user has not registered an auto claim address
+pub fn execute_claim_for(e: &Env, user: &Address, reserve_token_ids: &Vec<u32>) -> (Address, i128) {
+    let to = match storage::get_auto_claim(e, user) {
+        Some(to) => to,
+        None => panic_with_error!(e, PoolError::AutoClaimNotSet),
+    };
+    let amount_claimed = execute_claim(e, user, reserve_token_ids, &to);
+    (to, amount_claimed)
+}
+
+/// Calculate the emissions claimable by a user across all reserve tokens at the current
+/// ledger timestamp. Does not write to the ledger.
+///
+/// ### Arguments
+/// * `user` - The user
+/// * `positions` - The user's positions
+pub fn calc_claimable_emissions(e: &Env, user: &Address, positions: &Positions) -> i128 {
+    let reserve_list = storage::get_res_list(e);
+    let mut claimable = 0;
+    for (reserve_index, res_address) in (0u32..).zip(reserve_list.iter()) {
+        let reserve_config = storage::get_res_config(e, &res_address);
+        let reserve_data = storage::get_res_data(e, &res_address);
+        let supply_scalar = 10i128.pow(reserve_config.decimals);
+        let collateral = positions.collateral.get(reserve_index).unwrap_or(0);
+        let supply = positions.supply.get(reserve_index).unwrap_or(0);
+        let d_token = (
+            to_res_token_id(reserve_index, 0),
+            reserve_data.d_supply,
+            positions.liabilities.get(reserve_index).unwrap_or(0),
+        );
+        let b_token = (
+            to_res_token_id(reserve_index, 1),
+            reserve_data.b_supply,
+            collateral + supply,
+        );
+        let c_token = (
+            to_res_token_id(reserve_index, 2),
+            reserve_data.c_supply,
+            collateral,
+        );
+        let s_token = (
+            to_res_token_id(reserve_index, 3),
+            reserve_data.b_supply - reserve_data.c_supply,
+            supply,
+        );
+        for (res_token_id, supply, balance) in [d_token, b_token, c_token, s_token] {
+            if let Some(emis_config) = storage::get_res_emis_config(e, &res_token_id) {
+                let token_emission_data =
+                    storage::get_res_emis_data(e, &res_token_id).unwrap_optimized();
+                let res_emis_data = calc_emission_data(
+                    e,
+                    &token_emission_data,
+                    supply,
+                    supply_scalar,
+                    &emis_config,
+                );
+                let user_data = storage::get_user_emissions(e, user, &res_token_id);
+                claimable +=
+                    calc_user_accrued(e, &res_emis_data, supply_scalar, &user_data, balance);
+            }
+        }
+    }
+    claimable
+}
+
+/// Update the emission indexes of each reserve token of a reserve to the current ledger timestamp
+///
+/// ### Arguments
+/// * `reserve` - The reserve, with its token supplies as of the current ledger timestamp
+pub fn update_reserve_emissions(e: &Env, reserve: &Reserve) {
+    let supplies = [
+        (0, reserve.d_supply),
+        (1, reserve.b_supply),
+        (2, reserve.c_supply),
+        (3, reserve.b_supply - reserve.c_supply),
+    ];
+    for (res_type, supply) in supplies {
+        update_emission_data(
+            e,
+            to_res_token_id(reserve.index, res_type),
+            supply,
+            reserve.scalar,
+        );
+    }
+}
+
 /// Update the emissions information about a reserve token. Must be called before any update
 /// is made to the supply of debtTokens or blendTokens.
 ///
@@ -171,31 +309,52 @@ pub(super) fn update_emission_data_with_config(
     emis_config: &ReserveEmissionsConfig,
 ) -> ReserveEmissionsData {
     let token_emission_data = storage::get_res_emis_data(e, &res_token_id).unwrap_optimized(); // exists if config is written to
+    let new_data = calc_emission_data(e, &token_emission_data, supply, supply_scalar, emis_config);
+    if new_data.last_time != token_emission_data.last_time {
+        storage::set_res_emis_data(e, &res_token_id, &new_data);
+    }
+    new_data
+}
 
+/// Calculate the reserve token emission data at the current ledger timestamp. Emissions are
+/// prorated up to the expiration of the config, and emissions for any time the reserve token
+/// had no supply are skipped rather than credited to the next holders.
+///
+/// ### Arguments
+/// * `token_emission_data` - The last recorded reserve token emission data
+/// * `supply` - The current supply of the reserve token
+/// * `supply_scalar` - The scalar of the reserve token
+/// * `emis_config` - The reserve token emission configuration
+fn calc_emission_data(
+    e: &Env,
+    token_emission_data: &ReserveEmissionsData,
+    supply: i128,
+    supply_scalar: i128,
+    emis_config: &ReserveEmissionsConfig,
+) -> ReserveEmissionsData {
+    let end_time = e.ledger().timestamp().min(emis_config.expiration);
     if token_emission_data.last_time >= emis_config.expiration
-        || e.ledger().timestamp() == token_emission_data.last_time
+        || end_time == token_emission_data.last_time
         || emis_config.eps == 0
-        || supply == 0
     {
-        return token_emission_data;
+        return token_emission_data.clone();
+    }
+    let elapsed = end_time - token_emission_data.last_time;
+    if supply == 0 {
+        // no holders to credit for the elapsed time
+        return ReserveEmissionsData {
+            index: token_emission_data.index,
+            last_time: end_time,
+        };
     }
 
-    let ledger_timestamp = if e.ledger().timestamp() > emis_config.expiration {
-        emis_config.expiration
-    } else {
-        e.ledger().timestamp()
-    };
-
-    let additional_idx = (i128(ledger_timestamp - token_emission_data.last_time)
-        * i128(emis_config.eps))
-    .fixed_div_floor(supply, supply_scalar)
-    .unwrap_optimized();
-    let new_data = ReserveEmissionsData {
+    let additional_idx = (i128(elapsed) * i128(emis_config.eps))
+        .fixed_div_floor(supply, supply_scalar)
+        .unwrap_optimized();
+    ReserveEmissionsData {
         index: additional_idx + token_emission_data.index,
-        last_time: ledger_timestamp,
-    };
-    storage::set_res_emis_data(e, &res_token_id, &new_data);
-    new_data
+        last_time: end_time,
+    }
 }
 
 fn update_user_emissions(
@@ -207,29 +366,43 @@ fn update_user_emissions(
     balance: i128,
     claim: bool,
 ) -> i128 {
-    if let Some(user_data) = storage::get_user_emissions(e, user, &res_token_id) {
-        if user_data.index != res_emis_data.index || claim {
+    let user_data = storage::get_user_emissions(e, user, &res_token_id);
+    if let Some(cur_data) = &user_data {
+        if cur_data.index == res_emis_data.index && !claim {
+            return 0;
+        }
+    }
+    let accrued = calc_user_accrued(e, res_emis_data, supply_scalar, &user_data, balance);
+    set_user_emissions(e, user, res_token_id, res_emis_data.index, accrued, claim)
+}
+
+/// Calculate the emissions accrued by a user for a reserve token at the given emission index
+fn calc_user_accrued(
+    e: &Env,
+    res_emis_data: &ReserveEmissionsData,
+    supply_scalar: i128,
+    user_data: &Option<UserEmissionData>,
+    balance: i128,
+) -> i128 {
+    match user_data {
+        Some(user_data) => {
             let mut accrual = user_data.accrued;
             if balance != 0 {
                 let delta_index = res_emis_data.index - user_data.index;
                 require_nonnegative(e, &delta_index);
                 let to_accrue = balance
-                    .fixed_mul_floor(res_emis_data.index - user_data.index, supply_scalar)
+                    .fixed_mul_floor(delta_index, supply_scalar)
                     .unwrap_optimized();
                 accrual += to_accrue;
             }
-            return set_user_emissions(e, user, res_token_id, res_emis_data.index, accrual, claim);
+            accrual
         }
-        0
-    } else if balance == 0 {
         // first time the user registered an action with the asset since emissions were added
-        return set_user_emissions(e, user, res_token_id, res_emis_data.index, 0, claim);
-    } else {
+        None if balance == 0 => 0,
         // user had tokens before emissions began, they are due any historical emissions
-        let to_accrue = balance
+        None => balance
             .fixed_mul_floor(res_emis_data.index, supply_scalar)
-            .unwrap_optimized();
-        return set_user_emissions(e, user, res_token_id, res_emis_data.index, to_accrue, claim);
+            .unwrap_optimized(),
     }
 }
 
@@ -723,7 +896,7 @@ mod tests {
     }
 
     #[test]
-    fn test_update_emission_data_no_supply_returns_old() {
+    fn test_update_emission_data_no_supply_skips_interval() {
         let e = Env::default();
         e.mock_all_auths();
 
@@ -762,10 +935,8 @@ mod tests {
                 Some(_) => {
                     let new_reserve_emission_data =
                         storage::get_res_emis_data(&e, &res_token_index).unwrap_optimized();
-                    assert_eq!(
-                        new_reserve_emission_data.last_time,
-                        reserve_emission_data.last_time
-                    );
+                    // the interval without supply is not credited to future holders
+                    assert_eq!(new_reserve_emission_data.last_time, 1501000000);
                     assert_eq!(new_reserve_emission_data.index, reserve_emission_data.index);
                 }
                 None => assert!(false),
@@ -773,6 +944,56 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_update_emission_data_no_supply_past_exp() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let pool = testutils::create_pool(&e);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1700000000,
+            protocol_version: 20,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let supply_scalar = 1_0000000;
+        e.as_contract(&pool, || {
+            let reserve_emission_config = ReserveEmissionsConfig {
+                expiration: 1600000000,
+                eps: 0_0100000,
+            };
+            let reserve_emission_data = ReserveEmissionsData {
+                index: 123456789,
+                last_time: 1500000000,
+            };
+
+            let res_token_type = 0;
+            let res_token_index = 2 + res_token_type;
+            storage::set_res_emis_config(&e, &res_token_index, &reserve_emission_config);
+            storage::set_res_emis_data(&e, &res_token_index, &reserve_emission_data);
+
+            // the reserve token had no supply through the expiration
+            update_emission_data(&e, res_token_index, 0, supply_scalar);
+            let new_reserve_emission_data =
+                storage::get_res_emis_data(&e, &res_token_index).unwrap_optimized();
+            assert_eq!(new_reserve_emission_data.last_time, 1600000000);
+            assert_eq!(new_reserve_emission_data.index, 123456789);
+
+            // a later supplier is not credited for the interval before the expiration
+            update_emission_data(&e, res_token_index, 100_0000000, supply_scalar);
+            let new_reserve_emission_data =
+                storage::get_res_emis_data(&e, &res_token_index).unwrap_optimized();
+            assert_eq!(new_reserve_emission_data.last_time, 1600000000);
+            assert_eq!(new_reserve_emission_data.index, 123456789);
+        });
+    }
+
     #[test]
     fn test_update_emission_data_past_exp() {
         let e = Env::default();
@@ -1271,6 +1492,25 @@ mod tests {
         });
     }
 
+    //********** res token ids **********//
+
+    #[test]
+    fn test_res_token_ids() {
+        assert_eq!(to_res_token_id(0, 0), 0);
+        assert_eq!(to_res_token_id(0, 1), 1);
+        assert_eq!(to_res_token_id(3, 0), 6);
+        assert_eq!(to_res_token_id(3, 1), 7);
+        assert_eq!(to_res_token_id(3, 2), 0x10007);
+        assert_eq!(to_res_token_id(3, 3), 0x20007);
+
+        for res_index in 0..5 {
+            for res_type in 0..4 {
+                let res_token_id = to_res_token_id(res_index, res_type);
+                assert_eq!(from_res_token_id(res_token_id), (res_index, res_type));
+            }
+        }
+    }
+
     //********** execute claim **********//
 
     #[test]
@@ -1398,6 +1638,322 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_execute_claim_for() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.budget().reset_unlimited();
+
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let merry = Address::generate(&e);
+
+        let (_, blnd_token_client) = testutils::create_blnd_token(&e, &pool, &bombadil);
+        let (backstop, _) = testutils::create_backstop(&e);
+        // mock backstop having emissions for pool
+        e.as_contract(&backstop, || {
+            blnd_token_client.approve(&backstop, &pool, &1_000_000_000_000_i128, &1000000);
+        });
+        blnd_token_client.mint(&backstop, &1_000_000_000_000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1501000000, // 10^6 seconds have passed
+            protocol_version: 20,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.decimals = 5;
+        reserve_data.b_supply = 100_00000;
+        reserve_data.d_supply = 50_00000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 2_00000)],
+            collateral: map![&e],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_backstop(&e, &backstop);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_auto_claim(&e, &samwise, &merry);
+
+            let reserve_emission_config_0 = ReserveEmissionsConfig {
+                expiration: 1600000000,
+                eps: 0_0100000,
+            };
+            let reserve_emission_data_0 = ReserveEmissionsData {
+                index: 2345678,
+                last_time: 1500000000,
+            };
+            let user_emission_data_0 = UserEmissionData {
+                index: 1234567,
+                accrued: 0_1000000,
+            };
+            let res_token_index_0 = 0; // d_token for reserve 0
+
+            storage::set_res_emis_config(&e, &res_token_index_0, &reserve_emission_config_0);
+            storage::set_res_emis_data(&e, &res_token_index_0, &reserve_emission_data_0);
+            storage::set_user_emissions(&e, &samwise, &res_token_index_0, &user_emission_data_0);
+
+            let reserve_token_ids: Vec<u32> = vec![&e, res_token_index_0];
+            let (to, result) = execute_claim_for(&e, &samwise, &reserve_token_ids);
+
+            let new_user_emission_data =
+                storage::get_user_emissions(&e, &samwise, &res_token_index_0).unwrap_optimized();
+            assert_eq!(new_user_emission_data.accrued, 0);
+            assert_eq!(to, merry);
+            assert_eq!(result, 400_3222222);
+
+            // verify tokens are sent to the registered address
+            assert_eq!(blnd_token_client.balance(&merry), 400_3222222);
+            assert_eq!(blnd_token_client.balance(&samwise), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1229)")]
+    fn test_execute_claim_for_requires_auto_claim() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let pool = testutils::create_pool(&e);
+        let samwise = Address::generate(&e);
+        let merry = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_auto_claim(&e, &samwise, &merry);
+            storage::del_auto_claim(&e, &samwise);
+
+            let reserve_token_ids: Vec<u32> = vec![&e, 0];
+            execute_claim_for(&e, &samwise, &reserve_token_ids);
+        });
+    }
+
+    #[test]
+    fn test_execute_claim_pays_direct_emissions_first() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.budget().reset_unlimited();
+
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let merry = Address::generate(&e);
+
+        let (_, blnd_token_client) = testutils::create_blnd_token(&e, &pool, &bombadil);
+        let (backstop, _) = testutils::create_backstop(&e);
+        // mock backstop having emissions for pool
+        e.as_contract(&backstop, || {
+            blnd_token_client.approve(&backstop, &pool, &100_000_0000000_i128, &1000000);
+        });
+        blnd_token_client.mint(&backstop, &100_000_0000000);
+        // mock the emitter having streamed emissions directly to the pool
+        blnd_token_client.mint(&pool, &500_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1501000000, // 10^6 seconds have passed
+            protocol_version: 20,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.decimals = 5;
+        reserve_data.b_supply = 100_00000;
+        reserve_data.d_supply = 50_00000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.decimals = 9;
+        reserve_config.index = 1;
+        reserve_data.b_supply = 100_000_000_000;
+        reserve_data.d_supply = 50_000_000_000;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 2_00000)],
+            collateral: map![&e, (1, 1_000_000_000)],
+            supply: map![&e, (1, 1_000_000_000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_backstop(&e, &backstop);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_direct_emissions(&e, &500_0000000);
+
+            let reserve_emission_config_0 = ReserveEmissionsConfig {
+                expiration: 1600000000,
+                eps: 0_0100000,
+            };
+            let reserve_emission_data_0 = ReserveEmissionsData {
+                index: 2345678,
+                last_time: 1500000000,
+            };
+            let user_emission_data_0 = UserEmissionData {
+                index: 1234567,
+                accrued: 0_1000000,
+            };
+            let res_token_index_0 = 0; // d_token for reserve 0
+
+            let reserve_emission_config_1 = ReserveEmissionsConfig {
+                expiration: 1600000000,
+                eps: 0_0150000,
+            };
+            let reserve_emission_data_1 = ReserveEmissionsData {
+                index: 1345678,
+                last_time: 1500000000,
+            };
+            let user_emission_data_1 = UserEmissionData {
+                index: 1234567,
+                accrued: 1_0000000,
+            };
+            let res_token_index_1 = 3; // b_token for reserve 1
+
+            storage::set_res_emis_config(&e, &res_token_index_0, &reserve_emission_config_0);
+            storage::set_res_emis_data(&e, &res_token_index_0, &reserve_emission_data_0);
+            storage::set_user_emissions(&e, &samwise, &res_token_index_0, &user_emission_data_0);
+
+            storage::set_res_emis_config(&e, &res_token_index_1, &reserve_emission_config_1);
+            storage::set_res_emis_data(&e, &res_token_index_1, &reserve_emission_data_1);
+            storage::set_user_emissions(&e, &samwise, &res_token_index_1, &user_emission_data_1);
+
+            let reserve_token_ids: Vec<u32> = vec![&e, res_token_index_0, res_token_index_1];
+            let result = execute_claim(&e, &samwise, &reserve_token_ids, &merry);
+
+            assert_eq!(result, 400_3222222 + 301_0222222);
+
+            // verify direct emissions are paid out before the backstop
+            assert_eq!(blnd_token_client.balance(&merry), 400_3222222 + 301_0222222);
+            assert_eq!(blnd_token_client.balance(&pool), 0);
+            assert_eq!(storage::get_direct_emissions(&e), 0);
+            assert_eq!(
+                blnd_token_client.balance(&backstop),
+                100_000_0000000 - (400_3222222 + 301_0222222 - 500_0000000)
+            )
+        });
+    }
+
+    #[test]
+    fn test_calc_claimable_emissions() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.budget().reset_unlimited();
+
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let merry = Address::generate(&e);
+
+        let (_, blnd_token_client) = testutils::create_blnd_token(&e, &pool, &bombadil);
+        let (backstop, _) = testutils::create_backstop(&e);
+        // mock backstop having emissions for pool
+        e.as_contract(&backstop, || {
+            blnd_token_client.approve(&backstop, &pool, &100_000_0000000_i128, &1000000);
+        });
+        blnd_token_client.mint(&backstop, &100_000_0000000);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1501000000, // 10^6 seconds have passed
+            protocol_version: 20,
+            sequence_number: 123,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.decimals = 5;
+        reserve_data.b_supply = 100_00000;
+        reserve_data.d_supply = 50_00000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.decimals = 9;
+        reserve_config.index = 1;
+        reserve_data.b_supply = 100_000_000_000;
+        reserve_data.d_supply = 50_000_000_000;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 2_00000)],
+            collateral: map![&e, (1, 1_000_000_000)],
+            supply: map![&e, (1, 1_000_000_000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_backstop(&e, &backstop);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let reserve_emission_config_0 = ReserveEmissionsConfig {
+                expiration: 1600000000,
+                eps: 0_0100000,
+            };
+            let reserve_emission_data_0 = ReserveEmissionsData {
+                index: 2345678,
+                last_time: 1500000000,
+            };
+            let user_emission_data_0 = UserEmissionData {
+                index: 1234567,
+                accrued: 0_1000000,
+            };
+            let res_token_index_0 = 0; // d_token for reserve 0
+
+            let reserve_emission_config_1 = ReserveEmissionsConfig {
+                expiration: 1600000000,
+                eps: 0_0150000,
+            };
+            let reserve_emission_data_1 = ReserveEmissionsData {
+                index: 1345678,
+                last_time: 1500000000,
+            };
+            let user_emission_data_1 = UserEmissionData {
+                index: 1234567,
+                accrued: 1_0000000,
+            };
+            let res_token_index_1 = 3; // b_token for reserve 1
+
+            storage::set_res_emis_config(&e, &res_token_index_0, &reserve_emission_config_0);
+            storage::set_res_emis_data(&e, &res_token_index_0, &reserve_emission_data_0);
+            storage::set_user_emissions(&e, &samwise, &res_token_index_0, &user_emission_data_0);
+
+            storage::set_res_emis_config(&e, &res_token_index_1, &reserve_emission_config_1);
+            storage::set_res_emis_data(&e, &res_token_index_1, &reserve_emission_data_1);
+            storage::set_user_emissions(&e, &samwise, &res_token_index_1, &user_emission_data_1);
+
+            let result = calc_claimable_emissions(&e, &samwise, &user_positions);
+            assert_eq!(result, 400_3222222 + 301_0222222);
+
+            // verify nothing is written to the ledger
+            let new_reserve_emission_data =
+                storage::get_res_emis_data(&e, &res_token_index_0).unwrap_optimized();
+            assert_eq!(new_reserve_emission_data.last_time, 1500000000);
+            let new_user_emission_data_1 =
+                storage::get_user_emissions(&e, &samwise, &res_token_index_1).unwrap_optimized();
+            assert_eq!(new_user_emission_data_1.accrued, 1_0000000);
+
+            // verify the calculated amount matches the claimed amount
+            let reserve_token_ids: Vec<u32> = vec![&e, res_token_index_0, res_token_index_1];
+            let claimed = execute_claim(&e, &samwise, &reserve_token_ids, &merry);
+            assert_eq!(claimed, result);
+        });
+    }
+
     #[test]
     fn test_execute_claim_with_already_claimed_reserve() {
         let e = Env::default();
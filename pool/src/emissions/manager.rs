@@ -1,6 +1,6 @@
 use crate::{
     constants::SCALAR_7,
-    dependencies::BackstopClient,
+    dependencies::{BackstopClient, EmitterClient},
     errors::PoolError,
     storage::{self, ReserveEmissionsConfig, ReserveEmissionsData},
 };
@@ -10,16 +10,27 @@ use soroban_sdk::{
     contracttype, map, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map, Symbol, Vec,
 };
 
-use super::distributor;
+use super::distributor::{self, from_res_token_id, to_res_token_id};
 
 // Types
 
 /// Metadata for a pool's reserve emission configuration
 #[contracttype]
 pub struct ReserveEmissionMetadata {
-    pub res_index: u32,
-    pub res_type: u32,
-    pub share: u64,
+    pub res_index: u32, // the index of the reserve
+    pub res_type: u32, // 0 for dToken, 1 for bToken, 2 for collateral bToken, 3 for non-collateral bToken
+    pub share: u64,    // the share of the pool eps, in 7 decimals
+}
+
+/// The emission config of a reserve token and the tokens it has left to emit
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ReserveEmissionInfo {
+    pub expiration: u64, // the time the config stops emitting
+    pub eps: u64,        // the tokens emitted per second
+    pub index: i128,     // the emission index as of the last update
+    pub last_time: u64,  // the time of the last update to the emission index
+    pub remaining: i128, // the tokens left to emit before the config expires
 }
 
 /// Set the pool emissions
@@ -31,23 +42,28 @@ pub struct ReserveEmissionMetadata {
 ///                             if the total pool eps
 ///
 /// ### Panics
-/// If the total share of the pool eps from the reserves is over 1
+/// * If a reserve token type is not valid or the reserve is not in the pool
+/// * If a reserve token is included more than once
+/// * If the total share of the pool eps from the reserves is not exactly 1
 pub fn set_pool_emissions(e: &Env, res_emission_metadata: Vec<ReserveEmissionMetadata>) {
     let mut pool_emissions: Map<u32, u64> = map![e];
     let mut total_share = 0;
 
     let reserve_list = storage::get_res_list(e);
     for metadata in res_emission_metadata {
-        let key = metadata.res_index * 2 + metadata.res_type;
-        if metadata.res_type > 1 || reserve_list.get(metadata.res_index).is_none() {
-            panic_with_error!(e, PoolError::BadRequest);
+        if metadata.res_type > 3 || reserve_list.get(metadata.res_index).is_none() {
+            panic_with_error!(e, PoolError::InvalidEmissionEntry);
+        }
+        let key = to_res_token_id(metadata.res_index, metadata.res_type);
+        if pool_emissions.contains_key(key) {
+            panic_with_error!(e, PoolError::DuplicateEmissionEntry);
         }
         pool_emissions.set(key, metadata.share);
         total_share += metadata.share;
     }
 
-    if total_share > SCALAR_7 as u64 {
-        panic_with_error!(e, PoolError::BadRequest);
+    if total_share != SCALAR_7 as u64 {
+        panic_with_error!(e, PoolError::InvalidEmissionShares);
     }
 
     storage::set_pool_emissions(e, &pool_emissions);
@@ -67,6 +83,22 @@ pub fn gulp_emissions(e: &Env) -> i128 {
     new_emissions
 }
 
+/// Consume tokens streamed directly to the pool by the emitter and distribute them to reserves
+///
+/// Returns the number of new tokens distributed for emissions
+///
+/// ### Panics
+/// If less than one token has been streamed to the pool since the last gulp
+pub fn gulp_direct_emissions(e: &Env) -> i128 {
+    let backstop = storage::get_backstop(e);
+    let emitter = BackstopClient::new(e, &backstop).emitter();
+    let new_emissions =
+        EmitterClient::new(e, &emitter).distribute_direct(&e.current_contract_address());
+    do_gulp_emissions(e, new_emissions);
+    storage::set_direct_emissions(e, &(storage::get_direct_emissions(e) + new_emissions));
+    new_emissions
+}
+
 fn do_gulp_emissions(e: &Env, new_emissions: i128) {
     // ensure enough tokens are being emitted to avoid rounding issues
     if new_emissions < SCALAR_7 {
@@ -75,7 +107,7 @@ fn do_gulp_emissions(e: &Env, new_emissions: i128) {
     let pool_emissions = storage::get_pool_emissions(e);
     let reserve_list = storage::get_res_list(e);
     for (res_token_id, res_eps_share) in pool_emissions.iter() {
-        let reserve_index = res_token_id / 2;
+        let (reserve_index, _) = from_res_token_id(res_token_id);
         let res_asset_address = reserve_list.get_unchecked(reserve_index);
         let new_reserve_emissions = i128(res_eps_share)
             .fixed_mul_floor(new_emissions, SCALAR_7)
@@ -95,9 +127,11 @@ fn update_reserve_emission_config(
         // data exists - update it with old config
         let reserve_config = storage::get_res_config(e, asset);
         let reserve_data = storage::get_res_data(e, asset);
-        let supply = match res_token_id % 2 {
+        let supply = match from_res_token_id(res_token_id).1 {
             0 => reserve_data.d_supply,
             1 => reserve_data.b_supply,
+            2 => reserve_data.c_supply,
+            3 => reserve_data.b_supply - reserve_data.c_supply,
             _ => panic_with_error!(e, PoolError::BadRequest),
         };
         let mut emission_data = distributor::update_emission_data_with_config(
@@ -140,6 +174,28 @@ fn update_reserve_emission_config(
     )
 }
 
+/// Load the emission config of a reserve token, including the tokens it has left to emit, or
+/// None if the reserve token has never received emissions
+///
+/// ### Arguments
+/// * `res_token_id` - The reserve token id
+pub fn load_emission_info(e: &Env, res_token_id: u32) -> Option<ReserveEmissionInfo> {
+    let emis_config = storage::get_res_emis_config(e, &res_token_id)?;
+    let emis_data = storage::get_res_emis_data(e, &res_token_id).unwrap_optimized(); // exists if config is written to
+    let remaining = if emis_config.expiration > e.ledger().timestamp() {
+        i128(emis_config.eps) * i128(emis_config.expiration - e.ledger().timestamp())
+    } else {
+        0
+    };
+    Some(ReserveEmissionInfo {
+        expiration: emis_config.expiration,
+        eps: emis_config.eps,
+        index: emis_data.index,
+        last_time: emis_data.last_time,
+        remaining,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use crate::testutils;
@@ -414,7 +470,75 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_set_pool_emissions_collateral_and_supply() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000,
+            protocol_version: 20,
+            sequence_number: 20100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_2, &reserve_config, &reserve_data);
+        let (underlying_3, _) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_3, &reserve_config, &reserve_data);
+
+        let pool_emissions: Map<u32, u64> = map![&e, (2, 0_7500000),];
+        let res_emission_metadata: Vec<ReserveEmissionMetadata> = vec![
+            &e,
+            ReserveEmissionMetadata {
+                res_index: 0,
+                res_type: 2,
+                share: 0_3500000,
+            },
+            ReserveEmissionMetadata {
+                res_index: 0,
+                res_type: 3,
+                share: 0_1500000,
+            },
+            ReserveEmissionMetadata {
+                res_index: 3,
+                res_type: 1,
+                share: 0_5000000,
+            },
+        ];
+
+        e.as_contract(&pool, || {
+            storage::set_pool_emissions(&e, &pool_emissions);
+
+            set_pool_emissions(&e, res_emission_metadata);
+
+            let new_pool_emissions = storage::get_pool_emissions(&e);
+            assert_eq!(new_pool_emissions.len(), 3);
+            assert_eq!(
+                new_pool_emissions.get(0x10001).unwrap_optimized(),
+                0_3500000
+            );
+            assert_eq!(
+                new_pool_emissions.get(0x20001).unwrap_optimized(),
+                0_1500000
+            );
+            assert_eq!(new_pool_emissions.get(7).unwrap_optimized(), 0_5000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1223)")]
     fn test_set_pool_emissions_panics_if_over_100() {
         let e = Env::default();
         e.ledger().set(LedgerInfo {
@@ -464,9 +588,9 @@ mod tests {
     }
 
     #[test]
-    fn test_set_pool_emissions_ok_if_under_100() {
+    #[should_panic(expected = "Error(Contract, #1223)")]
+    fn test_set_pool_emissions_panics_if_under_100() {
         let e = Env::default();
-        e.budget().reset_unlimited();
 
         e.ledger().set(LedgerInfo {
             timestamp: 1500000000,
@@ -511,11 +635,180 @@ mod tests {
             storage::set_pool_emissions(&e, &pool_emissions);
 
             set_pool_emissions(&e, res_emission_metadata);
+        });
+    }
 
-            let new_pool_emissions = storage::get_pool_emissions(&e);
-            assert_eq!(new_pool_emissions.len(), 2);
-            assert_eq!(new_pool_emissions.get(1).unwrap_optimized(), 0_3400000);
-            assert_eq!(new_pool_emissions.get(6).unwrap_optimized(), 0_6500000);
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1224)")]
+    fn test_set_pool_emissions_panics_if_duplicate() {
+        let e = Env::default();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000,
+            protocol_version: 20,
+            sequence_number: 20100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_2, &reserve_config, &reserve_data);
+        let (underlying_3, _) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_3, &reserve_config, &reserve_data);
+
+        let pool_emissions: Map<u32, u64> = map![&e, (2, 0_7500000),];
+        let res_emission_metadata: Vec<ReserveEmissionMetadata> = vec![
+            &e,
+            ReserveEmissionMetadata {
+                res_index: 0,
+                res_type: 1,
+                share: 0_3400000,
+            },
+            ReserveEmissionMetadata {
+                res_index: 3,
+                res_type: 0,
+                share: 0_3300000,
+            },
+            ReserveEmissionMetadata {
+                res_index: 3,
+                res_type: 0,
+                share: 0_3300000,
+            },
+        ];
+
+        e.as_contract(&pool, || {
+            storage::set_pool_emissions(&e, &pool_emissions);
+
+            set_pool_emissions(&e, res_emission_metadata);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1225)")]
+    fn test_set_pool_emissions_panics_if_invalid_res_type() {
+        let e = Env::default();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000,
+            protocol_version: 20,
+            sequence_number: 20100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_2, &reserve_config, &reserve_data);
+        let (underlying_3, _) = testutils::create_token_contract(&e, &bombadil);
+        testutils::create_reserve(&e, &pool, &underlying_3, &reserve_config, &reserve_data);
+
+        let pool_emissions: Map<u32, u64> = map![&e, (2, 0_7500000),];
+        let res_emission_metadata: Vec<ReserveEmissionMetadata> = vec![
+            &e,
+            ReserveEmissionMetadata {
+                res_index: 0,
+                res_type: 1,
+                share: 0_3400000,
+            },
+            ReserveEmissionMetadata {
+                res_index: 3,
+                res_type: 4,
+                share: 0_6600000,
+            },
+        ];
+
+        e.as_contract(&pool, || {
+            storage::set_pool_emissions(&e, &pool_emissions);
+
+            set_pool_emissions(&e, res_emission_metadata);
+        });
+    }
+
+    #[test]
+    fn test_load_emission_info() {
+        let e = Env::default();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000000,
+            protocol_version: 20,
+            sequence_number: 20100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            assert_eq!(load_emission_info(&e, 1), None);
+
+            storage::set_res_emis_config(
+                &e,
+                &1,
+                &ReserveEmissionsConfig {
+                    expiration: 1500000200,
+                    eps: 0_1000000,
+                },
+            );
+            storage::set_res_emis_data(
+                &e,
+                &1,
+                &ReserveEmissionsData {
+                    index: 123_4567890,
+                    last_time: 1499990000,
+                },
+            );
+
+            let info = load_emission_info(&e, 1).unwrap();
+            assert_eq!(
+                info,
+                ReserveEmissionInfo {
+                    expiration: 1500000200,
+                    eps: 0_1000000,
+                    index: 123_4567890,
+                    last_time: 1499990000,
+                    remaining: 20_0000000,
+                }
+            );
+        });
+
+        // no tokens remain once the config expires
+        e.ledger().set(LedgerInfo {
+            timestamp: 1500000300,
+            protocol_version: 20,
+            sequence_number: 20120,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        e.as_contract(&pool, || {
+            let info = load_emission_info(&e, 1).unwrap();
+            assert_eq!(info.remaining, 0);
         });
     }
 }
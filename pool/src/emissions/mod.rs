@@ -1,5 +1,11 @@
 mod manager;
-pub use manager::{gulp_emissions, set_pool_emissions, ReserveEmissionMetadata};
+pub use manager::{
+    gulp_direct_emissions, gulp_emissions, load_emission_info, set_pool_emissions,
+    ReserveEmissionInfo, ReserveEmissionMetadata,
+};
 
 mod distributor;
-pub use distributor::{execute_claim, update_emissions};
+pub use distributor::{
+    calc_claimable_emissions, execute_claim, execute_claim_for, to_res_token_id, update_emissions,
+    update_reserve_emissions,
+};
@@ -1,4 +1,4 @@
-use soroban_sdk::contracterror;
+use soroban_sdk::{contracterror, panic_with_error, Address, Env, Symbol};
 
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
@@ -22,16 +22,28 @@ pub enum PoolError {
     InvalidReserveMetadata = 1202,
     InitNotUnlocked = 1203,
     StatusNotAllowed = 1204,
+    InvalidReserveToken = 1222,
+    AmmNotSet = 1227,
+    AmmSwapFailed = 1239,
+    TransferShortfall = 1241,
 
     // Pool State Errors
     InvalidHf = 1205,
     InvalidPoolStatus = 1206,
     InvalidUtilRate = 1207,
     MaxPositionsExceeded = 1208,
+    WithdrawUtilExceeded = 1228,
+    WithdrawNotUnlocked = 1230,
+    ReserveRestricted = 1231,
+    SupplyBreakerTripped = 1236,
     InternalReserveNotFound = 1209,
+    ContractPaused = 1220,
+    MigrationPending = 1240,
 
     // Oracle Errors
     StalePrice = 1210,
+    StaleExchangeRate = 1232,
+    PriceEmergency = 1238,
 
     // Auction Errors
     InvalidLiquidation = 1211,
@@ -39,10 +51,44 @@ pub enum PoolError {
     InvalidLiqTooLarge = 1213,
     InvalidLiqTooSmall = 1214,
     InterestTooSmall = 1215,
+    FillTooSmall = 1226,
+    BidCapExceeded = 1233,
+    LiquidationGracePeriod = 1237,
 
     // Share Token Errors
     InvalidBTokenMintAmount = 1216,
     InvalidBTokenBurnAmount = 1217,
     InvalidDTokenMintAmount = 1218,
     InvalidDTokenBurnAmount = 1219,
+    FirstSupplyTooSmall = 1235,
+
+    // Emission Errors
+    InvalidEmissionShares = 1223,
+    DuplicateEmissionEntry = 1224,
+    InvalidEmissionEntry = 1225,
+    AutoClaimNotSet = 1229,
+    TopUpNotSet = 1234,
+}
+
+/// Panic with an error after publishing an "error" event that describes what caused it. The
+/// event is rolled back with the failed transaction, but is included in the diagnostic events
+/// returned when the transaction is simulated.
+///
+/// Emits an event with topics `("error", error_code)` and data `(request_index, address)`.
+///
+/// ### Arguments
+/// * `error` - The error to panic with
+/// * `request_index` - The index of the submitted request being processed, if any
+/// * `address` - The asset or user the error relates to, if any
+pub fn panic_with_context(
+    e: &Env,
+    error: PoolError,
+    request_index: Option<u32>,
+    address: Option<Address>,
+) -> ! {
+    e.events().publish(
+        (Symbol::new(e, "error"), error as u32),
+        (request_index, address),
+    );
+    panic_with_error!(e, error)
 }
@@ -16,12 +16,19 @@ mod storage;
 mod testutils;
 mod validator;
 
-pub use auctions::{AuctionData, AuctionType};
+pub use auctions::{AuctionData, AuctionType, FillPreview};
 pub use contract::*;
-pub use emissions::ReserveEmissionMetadata;
+pub use emissions::{ReserveEmissionInfo, ReserveEmissionMetadata};
 pub use errors::PoolError;
-pub use pool::{Positions, Request, RequestType};
+pub use pool::{
+    InterestStats, Positions, Request, RequestType, ReserveRates, SubmitSimulation, SubmitSummary,
+    UserAccount, UserNetRate,
+};
 pub use storage::{
-    AuctionKey, PoolConfig, PoolDataKey, PoolEmissionConfig, ReserveConfig, ReserveData,
-    ReserveEmissionsConfig, ReserveEmissionsData, UserEmissionData, UserReserveKey,
+    AuctionCurve, AuctionKey, BadDebtRecord, CachedPrice, ExchangeRateConfig, FixedLoan,
+    PackedReserveConfig, PackedReserveData, PartnerCollateral, PoolConfig, PoolDataKey,
+    PoolEmissionConfig, PoolMetadata, QueuedReserveInit, QueuedWithdrawal, RateCheckpoint,
+    ReserveBond, ReserveConfig, ReserveData, ReserveEmissionsConfig, ReserveEmissionsData,
+    StableDebt, SupplySnapshot, TopUpRule, UserEmissionData, UserInterestData, UserReserveKey,
+    WithdrawQueueConfig,
 };
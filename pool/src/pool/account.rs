@@ -0,0 +1,699 @@
+use cast::i128;
+use sep_40_oracle::{Asset, PriceFeedClient};
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{
+    contracttype, map, panic_with_error, unwrap::UnwrapOptimized, vec, Address, Env, Map, Vec,
+};
+
+use crate::{
+    auctions::{AuctionData, AuctionType},
+    constants::{MAX_POSITIONS_BATCH, SCALAR_7, SECONDS_PER_YEAR},
+    emissions,
+    errors::PoolError,
+    storage,
+};
+
+use super::{
+    fixed_loan::get_fixed_d_tokens, interest::calc_rates_for_reserve,
+    stable_rate::get_stable_d_tokens, Pool, Positions, User,
+};
+
+/// A snapshot of a user's account with the pool
+#[derive(Clone)]
+#[contracttype]
+pub struct UserAccount {
+    pub positions: Positions, // the user's positions expressed in b and d tokens
+    pub liabilities: Map<Address, i128>, // Map of reserve asset to liabilities in underlying
+    pub collateral: Map<Address, i128>, // Map of reserve asset to collateral in underlying
+    pub supply: Map<Address, i128>, // Map of reserve asset to non-collateral supply in underlying
+    pub emissions: i128,      // the emissions claimable by the user
+    pub liquidation: AuctionData, // the user's active liquidation auction, or an empty auction with block 0 if none
+}
+
+/// Load a snapshot of a user's account, with positions converted to underlying amounts at
+/// the current ledger timestamp
+///
+/// ### Arguments
+/// * `user` - The user
+pub fn load_user_account(e: &Env, user: &Address) -> UserAccount {
+    let mut pool = Pool::load(e);
+    let reserve_list = storage::get_res_list(e);
+    let positions = storage::get_user_positions(e, user);
+
+    let mut liabilities = map![e];
+    for (index, d_tokens) in positions.liabilities.iter() {
+        let reserve = pool.load_reserve(e, &reserve_list.get_unchecked(index), false);
+        liabilities.set(
+            reserve.asset.clone(),
+            reserve.to_asset_from_d_token(d_tokens),
+        );
+        pool.cache_reserve(reserve);
+    }
+    let mut collateral = map![e];
+    for (index, b_tokens) in positions.collateral.iter() {
+        let reserve = pool.load_reserve(e, &reserve_list.get_unchecked(index), false);
+        collateral.set(
+            reserve.asset.clone(),
+            reserve.to_asset_from_b_token(b_tokens),
+        );
+        pool.cache_reserve(reserve);
+    }
+    let mut supply = map![e];
+    for (index, b_tokens) in positions.supply.iter() {
+        let reserve = pool.load_reserve(e, &reserve_list.get_unchecked(index), false);
+        supply.set(
+            reserve.asset.clone(),
+            reserve.to_asset_from_b_token(b_tokens),
+        );
+        pool.cache_reserve(reserve);
+    }
+
+    let emissions = emissions::calc_claimable_emissions(e, user, &positions);
+
+    let liquidation_type = AuctionType::UserLiquidation as u32;
+    let liquidation = if storage::has_auction(e, &liquidation_type, user) {
+        storage::get_auction(e, &liquidation_type, user)
+    } else {
+        AuctionData {
+            bid: map![e],
+            lot: map![e],
+            block: 0,
+        }
+    };
+
+    UserAccount {
+        positions,
+        liabilities,
+        collateral,
+        supply,
+        emissions,
+        liquidation,
+    }
+}
+
+/// The annual rates a user earns and pays across all of their positions. Each rate is relative to
+/// the user's net value, so `net_apr` is the annual return on the value the user has in the pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct UserNetRate {
+    pub net_value: i128, // the value of the user's supply and collateral less their liabilities in the oracle's base asset
+    pub supply_apr: i128, // the interest earned on the user's supply and collateral (7 decimals)
+    pub borrow_apr: i128, // the interest paid on the user's liabilities (7 decimals)
+    pub emission_apr: i128, // the value of the emissions earned, or 0 if BLND has no oracle price (7 decimals)
+    pub net_apr: i128,      // supply_apr - borrow_apr + emission_apr (7 decimals)
+}
+
+/// Calculate the annual rates a user earns and pays across all of their positions at the current
+/// ledger timestamp. Liabilities backing a fixed-term loan or stable rate debt are charged at that
+/// loan's rate instead of the reserve's borrow rate.
+///
+/// The rates are all 0 if the user's liabilities are worth at least as much as their supply and
+/// collateral.
+///
+/// ### Arguments
+/// * `user` - The user
+///
+/// ### Panics
+/// If the price of a reserve the user has a position in is stale
+pub fn calc_user_net_rate(e: &Env, user: &Address) -> UserNetRate {
+    let mut pool = Pool::load(e);
+    let user_state = User::load(e, user);
+    let reserve_list = storage::get_res_list(e);
+    let fixed_loans = storage::get_fixed_loans(e, user);
+    let stable_debts = storage::get_stable_debts(e, user);
+    let blnd_price = load_blnd_price(e, &pool);
+
+    let mut supply_value = 0;
+    let mut liability_value = 0;
+    let mut supply_interest = 0;
+    let mut borrow_interest = 0;
+    let mut emissions_value = 0;
+    for (index, asset) in reserve_list.iter().enumerate() {
+        let index = index as u32;
+        let d_tokens = user_state.get_liabilities(index);
+        let b_tokens = user_state.get_total_supply(index);
+        if d_tokens == 0 && b_tokens == 0 {
+            continue;
+        }
+        let reserve = pool.load_reserve(e, &asset, false);
+        let price = pool.load_price(e, &asset);
+        let rates = calc_rates_for_reserve(e, pool.config.bstop_rate, &reserve);
+        let to_value = |amount: i128| {
+            amount
+                .fixed_mul_floor(price, reserve.scalar)
+                .unwrap_optimized()
+        };
+
+        let value = to_value(reserve.to_asset_from_b_token(b_tokens));
+        supply_value += value;
+        supply_interest += value * rates.supply_apr;
+
+        let fixed_d_tokens = get_fixed_d_tokens(e, &user_state, index);
+        let stable_d_tokens = get_stable_d_tokens(e, &user_state, index);
+        let variable_d_tokens = d_tokens - fixed_d_tokens - stable_d_tokens;
+        let value = to_value(reserve.to_asset_from_d_token(d_tokens));
+        liability_value += value;
+        borrow_interest +=
+            to_value(reserve.to_asset_from_d_token(variable_d_tokens)) * rates.borrow_apr;
+        if fixed_d_tokens > 0 {
+            borrow_interest += to_value(reserve.to_asset_from_d_token(fixed_d_tokens))
+                * fixed_loans.get_unchecked(index).rate;
+        }
+        if stable_d_tokens > 0 {
+            borrow_interest += to_value(reserve.to_asset_from_d_token(stable_d_tokens))
+                * stable_debts.get_unchecked(index).rate;
+        }
+
+        if let Some(blnd_price) = blnd_price {
+            let emitted = calc_annual_emissions(
+                e,
+                &[
+                    (0, d_tokens, reserve.d_supply),
+                    (1, b_tokens, reserve.b_supply),
+                    (2, user_state.get_collateral(index), reserve.c_supply),
+                    (
+                        3,
+                        user_state.get_supply(index),
+                        reserve.b_supply - reserve.c_supply,
+                    ),
+                ],
+                index,
+            );
+            emissions_value += emitted
+                .fixed_mul_floor(blnd_price, SCALAR_7)
+                .unwrap_optimized();
+        }
+        pool.cache_reserve(reserve);
+    }
+
+    let net_value = supply_value - liability_value;
+    if net_value <= 0 {
+        return UserNetRate {
+            net_value,
+            supply_apr: 0,
+            borrow_apr: 0,
+            emission_apr: 0,
+            net_apr: 0,
+        };
+    }
+    let supply_apr = supply_interest / net_value;
+    let borrow_apr = borrow_interest / net_value;
+    let emission_apr = emissions_value
+        .fixed_div_floor(net_value, SCALAR_7)
+        .unwrap_optimized();
+    UserNetRate {
+        net_value,
+        supply_apr,
+        borrow_apr,
+        emission_apr,
+        net_apr: supply_apr - borrow_apr + emission_apr,
+    }
+}
+
+/// Calculate the BLND a user earns over a year from a reserve at the current emission rates
+///
+/// ### Arguments
+/// * `balances` - The (reserve token type, user balance, total supply) of each reserve token
+/// * `reserve_index` - The index of the reserve
+fn calc_annual_emissions(e: &Env, balances: &[(u32, i128, i128)], reserve_index: u32) -> i128 {
+    let mut emitted = 0;
+    for (res_type, balance, supply) in balances.iter() {
+        if *balance == 0 || *supply <= 0 {
+            continue;
+        }
+        let res_token_id = emissions::to_res_token_id(reserve_index, *res_type);
+        if let Some(config) = storage::get_res_emis_config(e, &res_token_id) {
+            if config.expiration > e.ledger().timestamp() {
+                emitted += (i128(config.eps) * SECONDS_PER_YEAR)
+                    .fixed_mul_floor(*balance, *supply)
+                    .unwrap_optimized();
+            }
+        }
+    }
+    emitted
+}
+
+/// Load the price of BLND from the pool's oracle, if the oracle has a recent price for it
+fn load_blnd_price(e: &Env, pool: &Pool) -> Option<i128> {
+    let oracle_client = PriceFeedClient::new(e, &pool.config.oracle);
+    let blnd = Asset::Stellar(storage::get_blnd_token(e));
+    match oracle_client.try_lastprice(&blnd) {
+        Ok(Ok(Some(price_data)))
+            if price_data.timestamp + 24 * 60 * 60 >= e.ledger().timestamp() =>
+        {
+            Some(price_data.price)
+        }
+        _ => None,
+    }
+}
+
+/// Calculate the maximum amount of an asset a user can withdraw from their supply and collateral
+/// positions without leaving the reserve above the pool's withdraw utilization cap. This does not
+/// account for the health factor check performed when collateral is withdrawn.
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+/// * `user` - The user
+///
+/// ### Panics
+/// If the asset is not a reserve in the pool
+pub fn calc_max_withdrawable(e: &Env, asset: &Address, user: &Address) -> i128 {
+    let mut pool = Pool::load(e);
+    let reserve = pool.load_reserve(e, asset, false);
+    let positions = storage::get_user_positions(e, user);
+
+    let b_tokens = positions.collateral.get(reserve.index).unwrap_or(0)
+        + positions.supply.get(reserve.index).unwrap_or(0);
+    reserve
+        .to_asset_from_b_token(b_tokens)
+        .min(reserve.max_withdrawable(i128(storage::get_withdraw_util_cap(e))))
+}
+
+/// Load the positions for a batch of users, in the order the users are given
+///
+/// ### Arguments
+/// * `users` - The users
+///
+/// ### Panics
+/// If more than MAX_POSITIONS_BATCH users are given
+pub fn load_positions_batch(e: &Env, users: &Vec<Address>) -> Vec<(Address, Positions)> {
+    if users.len() > MAX_POSITIONS_BATCH {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let mut positions = vec![e];
+    for user in users.iter() {
+        let user_positions = storage::get_user_positions(e, &user);
+        positions.push_back((user, user_positions));
+    }
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage::{PoolConfig, ReserveEmissionsConfig, ReserveEmissionsData, StableDebt},
+        testutils,
+    };
+    use sep_40_oracle::testutils::Asset;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec, Symbol,
+    };
+
+    #[test]
+    fn test_load_user_account() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 123456 * 5,
+            protocol_version: 20,
+            sequence_number: 123456,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 123456 * 5 - 10000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.index = 1;
+        reserve_data.b_rate = 1_100_000_000;
+        reserve_data.d_rate = 1_200_000_000;
+        reserve_data.last_time = 123456 * 5 - 10000;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let positions = Positions {
+            liabilities: map![&e, (1, 5_0000000)],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e, (1, 10_0000000)],
+        };
+        let auction_data = AuctionData {
+            bid: map![&e, (underlying_1.clone(), 1_0000000)],
+            lot: map![&e, (underlying_0.clone(), 2_0000000)],
+            block: 123,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_auction(&e, &0, &samwise, &auction_data);
+
+            let mut pool = Pool::load(&e);
+            let reserve_0 = pool.load_reserve(&e, &underlying_0, false);
+            let reserve_1 = pool.load_reserve(&e, &underlying_1, false);
+
+            let account = load_user_account(&e, &samwise);
+            assert_eq!(account.positions.liabilities, positions.liabilities);
+            assert_eq!(account.positions.collateral, positions.collateral);
+            assert_eq!(account.positions.supply, positions.supply);
+            assert_eq!(
+                account.liabilities,
+                map![
+                    &e,
+                    (
+                        underlying_1.clone(),
+                        reserve_1.to_asset_from_d_token(5_0000000)
+                    )
+                ]
+            );
+            assert_eq!(
+                account.collateral,
+                map![
+                    &e,
+                    (
+                        underlying_0.clone(),
+                        reserve_0.to_asset_from_b_token(20_0000000)
+                    )
+                ]
+            );
+            assert_eq!(
+                account.supply,
+                map![
+                    &e,
+                    (
+                        underlying_1.clone(),
+                        reserve_1.to_asset_from_b_token(10_0000000)
+                    )
+                ]
+            );
+            // interest has accrued since the reserves were last updated
+            assert!(account.liabilities.get_unchecked(underlying_1.clone()) > 6_0000000);
+            assert_eq!(account.emissions, 0);
+            assert_eq!(account.liquidation.block, 123);
+            assert_eq!(account.liquidation.bid, auction_data.bid);
+
+            let account = load_user_account(&e, &frodo);
+            assert_eq!(account.positions.liabilities.len(), 0);
+            assert_eq!(account.liabilities.len(), 0);
+            assert_eq!(account.collateral.len(), 0);
+            assert_eq!(account.supply.len(), 0);
+            assert_eq!(account.liquidation.block, 0);
+            assert_eq!(account.liquidation.lot.len(), 0);
+        });
+    }
+
+    #[test]
+    fn test_calc_max_withdrawable() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    liabilities: map![&e],
+                    collateral: map![&e, (0, 10_0000000)],
+                    supply: map![&e, (0, 5_0000000)],
+                },
+            );
+
+            // limited by the user's balance
+            assert_eq!(calc_max_withdrawable(&e, &underlying, &samwise), 15_0000000);
+            assert_eq!(calc_max_withdrawable(&e, &underlying, &frodo), 0);
+
+            // limited by the withdraw utilization cap (75 / 0.8 = 93.75 supply required)
+            storage::set_withdraw_util_cap(&e, &0_8000000);
+            assert_eq!(calc_max_withdrawable(&e, &underlying, &samwise), 6_2500000);
+        });
+    }
+
+    #[test]
+    fn test_load_positions_batch() {
+        let e = Env::default();
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let sam_positions = Positions {
+            liabilities: map![&e, (1, 2_0000000)],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_user_positions(&e, &samwise, &sam_positions);
+
+            let batch = load_positions_batch(&e, &vec![&e, frodo.clone(), samwise.clone()]);
+            assert_eq!(batch.len(), 2);
+            let (user, positions) = batch.get_unchecked(0);
+            assert_eq!(user, frodo);
+            assert_eq!(positions.collateral.len(), 0);
+            assert_eq!(positions.liabilities.len(), 0);
+            let (user, positions) = batch.get_unchecked(1);
+            assert_eq!(user, samwise);
+            assert_eq!(positions.collateral, sam_positions.collateral);
+            assert_eq!(positions.liabilities, sam_positions.liabilities);
+            assert_eq!(positions.supply.len(), 0);
+
+            assert_eq!(load_positions_batch(&e, &vec![&e]).len(), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_load_positions_batch_over_max() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        let mut users = vec![&e];
+        for _ in 0..MAX_POSITIONS_BATCH + 1 {
+            users.push_back(Address::generate(&e));
+        }
+        e.as_contract(&pool, || {
+            load_positions_batch(&e, &users);
+        });
+    }
+
+    #[test]
+    fn test_calc_user_net_rate() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+        let (blnd, _) = testutils::create_blnd_token(&e, &pool, &bombadil);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.c_supply = 100_0000000;
+        reserve_data.last_time = 600;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(blnd.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 5_0000000, 0_5000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let positions = Positions {
+            liabilities: map![&e, (1, 4_0000000)],
+            collateral: map![&e, (0, 50_0000000)],
+            supply: map![&e],
+        };
+        let stable_debt = StableDebt {
+            d_tokens: 1_0000000,
+            principal: 1_0000000,
+            rate: 0_2000000,
+            last_time: 600,
+        };
+        let res_token_id = emissions::to_res_token_id(0, 2);
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &positions);
+            storage::set_stable_debts(&e, &samwise, &map![&e, (1, stable_debt)]);
+            storage::set_res_emis_config(
+                &e,
+                &res_token_id,
+                &ReserveEmissionsConfig {
+                    expiration: 600 + 7 * 24 * 60 * 60,
+                    eps: 0_0010000,
+                },
+            );
+            storage::set_res_emis_data(
+                &e,
+                &res_token_id,
+                &ReserveEmissionsData {
+                    index: 0,
+                    last_time: 600,
+                },
+            );
+
+            let mut pool = Pool::load(&e);
+            let reserve_0 = pool.load_reserve(&e, &underlying_0, false);
+            let reserve_1 = pool.load_reserve(&e, &underlying_1, false);
+            let rates_0 = calc_rates_for_reserve(&e, pool_config.bstop_rate, &reserve_0);
+            let rates_1 = calc_rates_for_reserve(&e, pool_config.bstop_rate, &reserve_1);
+
+            let net_rate = calc_user_net_rate(&e, &samwise);
+            assert_eq!(net_rate.net_value, 50_0000000 - 20_0000000);
+            assert_eq!(
+                net_rate.supply_apr,
+                50_0000000 * rates_0.supply_apr / net_rate.net_value
+            );
+            // the stable rate debt is charged at its own rate
+            assert_eq!(
+                net_rate.borrow_apr,
+                (15_0000000 * rates_1.borrow_apr + 5_0000000 * 0_2000000) / net_rate.net_value
+            );
+            // a half of the collateral emissions, valued at 0.5 per BLND
+            let emitted = 0_0010000 * SECONDS_PER_YEAR / 2;
+            assert_eq!(
+                net_rate.emission_apr,
+                (emitted / 2)
+                    .fixed_div_floor(net_rate.net_value, SCALAR_7)
+                    .unwrap()
+            );
+            assert_eq!(
+                net_rate.net_apr,
+                net_rate.supply_apr - net_rate.borrow_apr + net_rate.emission_apr
+            );
+        });
+    }
+
+    #[test]
+    fn test_calc_user_net_rate_no_net_value() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+        testutils::create_blnd_token(&e, &pool, &bombadil);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![&e, Asset::Stellar(underlying_0.clone())],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let positions = Positions {
+            liabilities: map![&e, (0, 10_0000000)],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &positions);
+
+            let net_rate = calc_user_net_rate(&e, &samwise);
+            assert_eq!(
+                net_rate,
+                UserNetRate {
+                    net_value: 0,
+                    supply_apr: 0,
+                    borrow_apr: 0,
+                    emission_apr: 0,
+                    net_apr: 0,
+                }
+            );
+        });
+    }
+}
@@ -1,10 +1,22 @@
+use cast::i128;
 use soroban_sdk::Map;
-use soroban_sdk::{contracttype, panic_with_error, Address, Env, Symbol, Vec};
-
-use crate::{auctions, errors::PoolError, validator::require_nonnegative};
-
+use soroban_sdk::{contracttype, Address, Env, Symbol, Vec};
+
+use crate::{
+    auctions,
+    errors::{panic_with_context, PoolError},
+    storage::{self, QueuedWithdrawal},
+    validator::require_valid_request,
+};
+
+use super::close_position::close_position;
+use super::fixed_loan::get_fixed_d_tokens;
+use super::leverage::loop_supply_borrow;
 use super::pool::Pool;
-use super::User;
+use super::stable_rate::get_stable_d_tokens;
+use super::swap_repay::{repay_with_swap, SwapRepay};
+use super::withdraw_queue::{calc_withdraw_queue_delay, record_withdrawal, take_queued_withdrawal};
+use super::{Reserve, User};
 
 /// A request a user makes against the pool
 #[derive(Clone)]
@@ -29,6 +41,12 @@ pub enum RequestType {
     FillBadDebtAuction = 7,
     FillInterestAuction = 8,
     DeleteLiquidationAuction = 9,
+    ClosePosition = 10,
+    LoopSupplyBorrow = 11,
+    ExecuteQueuedWithdraw = 12,
+    RepayFillWithSwap = 13,
+    CapFillBid = 14,
+    FillSurplusAuction = 15,
 }
 
 impl RequestType {
@@ -48,15 +66,28 @@ impl RequestType {
             7 => RequestType::FillBadDebtAuction,
             8 => RequestType::FillInterestAuction,
             9 => RequestType::DeleteLiquidationAuction,
-            _ => panic_with_error!(e, PoolError::BadRequest),
+            10 => RequestType::ClosePosition,
+            11 => RequestType::LoopSupplyBorrow,
+            12 => RequestType::ExecuteQueuedWithdraw,
+            13 => RequestType::RepayFillWithSwap,
+            14 => RequestType::CapFillBid,
+            15 => RequestType::FillSurplusAuction,
+            _ => panic_with_context(e, PoolError::BadRequest, None, None),
         }
     }
 }
 
-/// Transfer actions to be taken by the sender and pool
+/// Transfer actions to be taken by the sender and pool, and the sender's state to be written
+/// once the requests are executed
 pub struct Actions {
     pub spender_transfer: Map<Address, i128>,
     pub pool_transfer: Map<Address, i128>,
+    pub borrowed: Vec<Address>,
+    pub collateralized: Vec<Address>, // the reserves the sender supplied collateral to
+    pub swap_repays: Vec<SwapRepay>,
+    pub action_nonce: Option<u64>, // the sender's last action nonce, if any action used one
+    pub withdrawn: Map<Address, i128>, // the amount withdrawn from each reserve without being queued
+    pub queued_withdrawals: Map<u32, QueuedWithdrawal>, // the withdrawals queued by reserve index
 }
 
 impl Actions {
@@ -65,6 +96,29 @@ impl Actions {
         Actions {
             spender_transfer: Map::new(e),
             pool_transfer: Map::new(e),
+            borrowed: Vec::new(e),
+            collateralized: Vec::new(e),
+            swap_repays: Vec::new(e),
+            action_nonce: None,
+            withdrawn: Map::new(e),
+            queued_withdrawals: Map::new(e),
+        }
+    }
+
+    /// Store the sender's action nonce, queued withdrawals, and the withdrawals made against
+    /// each reserve's withdraw window
+    ///
+    /// ### Arguments
+    /// * `from` - The sender of the requests
+    pub fn store(&self, e: &Env, from: &Address) {
+        if let Some(nonce) = self.action_nonce {
+            storage::set_action_nonce(e, from, nonce);
+        }
+        for (asset, amount) in self.withdrawn.iter() {
+            record_withdrawal(e, &asset, amount);
+        }
+        for (index, withdrawal) in self.queued_withdrawals.iter() {
+            storage::set_queued_withdrawal(e, from, index, &withdrawal);
         }
     }
 
@@ -83,11 +137,58 @@ impl Actions {
             amount + self.pool_transfer.get(asset.clone()).unwrap_or(0),
         );
     }
+
+    /// Add a withdrawal made from a reserve without being queued
+    pub fn add_withdrawn(&mut self, asset: &Address, amount: i128) {
+        self.withdrawn.set(
+            asset.clone(),
+            amount + self.withdrawn.get(asset.clone()).unwrap_or(0),
+        );
+    }
+
+    /// Queue a withdrawal of `amount` from a reserve that can be executed after `delay`
+    /// seconds, replacing any withdrawal already queued for the reserve
+    pub fn add_queued_withdrawal(
+        &mut self,
+        e: &Env,
+        reserve: &Reserve,
+        amount: i128,
+        delay: u64,
+        collateral: bool,
+    ) -> QueuedWithdrawal {
+        let withdrawal = QueuedWithdrawal {
+            amount,
+            unlock_time: e.ledger().timestamp() + delay,
+            collateral,
+        };
+        self.queued_withdrawals
+            .set(reserve.index, withdrawal.clone());
+        withdrawal
+    }
+
+    /// Add a reserve the sender borrowed from
+    pub fn add_borrowed(&mut self, asset: &Address) {
+        if !self.borrowed.contains(asset) {
+            self.borrowed.push_back(asset.clone());
+        }
+    }
+
+    /// Add a reserve the sender supplied collateral to
+    pub fn add_collateralized(&mut self, asset: &Address) {
+        if !self.collateralized.contains(asset) {
+            self.collateralized.push_back(asset.clone());
+        }
+    }
 }
 
 /// Build a set of pool actions and the new positions from the supplied requests. Validates that the requests
 /// are valid based on the status and supported reserves in the pool.
 ///
+/// Requests are applied sequentially, and each request sees the state changes of the requests
+/// before it, including auction fills. The "from" user's positions, action nonce and queued
+/// withdrawals only live in the returned state until the caller stores them, while any other
+/// user modified by a fill is loaded and stored as part of that fill.
+///
 /// ### Arguments
 /// * pool - The pool
 /// * from - The sender of the requests
@@ -108,113 +209,165 @@ pub fn build_actions_from_request(
     requests: Vec<Request>,
 ) -> (Actions, User, bool) {
     let mut actions = Actions::new(e);
-    let mut from_state = User::load(e, from);
+    let mut from_state = pool.load_user(e, from);
     let prev_positions_count = from_state.positions.effective_count();
     let mut check_health = false;
-    for request in requests.iter() {
+    // the most recent collateral supplied in the batch, which a leverage loop is applied to
+    let mut last_collateral: Option<(Address, i128)> = None;
+    // the liabilities taken on by the most recent user liquidation auction filled in the batch
+    let mut last_fill_bid: Option<Map<Address, i128>> = None;
+    // the bid of the most recent auction of any type filled in the batch, which bid caps are applied to
+    let mut last_auction_bid: Option<Map<Address, i128>> = None;
+    for (index, request) in requests.iter().enumerate() {
+        let request_index = index as u32;
         // verify the request is allowed
-        require_nonnegative(e, &request.amount);
+        require_valid_request(e, request_index, &request);
         pool.require_action_allowed(e, request.request_type);
         match RequestType::from_u32(e, request.request_type) {
             RequestType::Supply => {
                 let mut reserve = pool.load_reserve(e, &request.address, true);
-                let b_tokens_minted = reserve.to_b_token_down(request.amount);
+                let b_tokens_minted = reserve.mint_b_tokens(e, request.amount);
                 from_state.add_supply(e, &mut reserve, b_tokens_minted);
                 actions.add_for_spender_transfer(&reserve.asset, request.amount);
                 pool.cache_reserve(reserve);
+                let nonce = next_action_nonce(e, from, &mut actions.action_nonce);
                 e.events().publish(
                     (
                         Symbol::new(e, "supply"),
                         request.address.clone(),
                         from.clone(),
                     ),
-                    (request.amount, b_tokens_minted),
+                    (request.amount, b_tokens_minted, nonce),
                 );
             }
             RequestType::Withdraw => {
                 let mut reserve = pool.load_reserve(e, &request.address, true);
                 let cur_b_tokens = from_state.get_supply(reserve.index);
-                let mut to_burn = reserve.to_b_token_up(request.amount);
-                let mut tokens_out = request.amount;
-                if to_burn > cur_b_tokens {
-                    to_burn = cur_b_tokens;
-                    tokens_out = reserve.to_asset_from_b_token(cur_b_tokens);
+                let tokens_out = request
+                    .amount
+                    .min(reserve.to_asset_from_b_token(cur_b_tokens));
+                let withdrawn = actions.withdrawn.get(reserve.asset.clone()).unwrap_or(0);
+                if let Some(delay) = calc_withdraw_queue_delay(e, &reserve, withdrawn, tokens_out) {
+                    // large withdrawals are queued and executed with an ExecuteQueuedWithdraw request
+                    let withdrawal =
+                        actions.add_queued_withdrawal(e, &reserve, tokens_out, delay, false);
+                    e.events().publish(
+                        (
+                            Symbol::new(e, "queue_withdraw"),
+                            request.address.clone(),
+                            from.clone(),
+                        ),
+                        (withdrawal.amount, withdrawal.unlock_time),
+                    );
+                } else {
+                    actions.add_withdrawn(&reserve.asset, tokens_out);
+                    let nonce = next_action_nonce(e, from, &mut actions.action_nonce);
+                    withdraw_supply(
+                        e,
+                        &mut actions,
+                        &mut from_state,
+                        &mut reserve,
+                        request.amount,
+                        request_index,
+                        nonce,
+                    );
+                    pool.cache_reserve(reserve);
                 }
-                from_state.remove_supply(e, &mut reserve, to_burn);
-                actions.add_for_pool_transfer(&reserve.asset, tokens_out);
-                pool.cache_reserve(reserve);
-                e.events().publish(
-                    (
-                        Symbol::new(e, "withdraw"),
-                        request.address.clone(),
-                        from.clone(),
-                    ),
-                    (tokens_out, to_burn),
-                );
             }
             RequestType::SupplyCollateral => {
                 let mut reserve = pool.load_reserve(e, &request.address, true);
-                let b_tokens_minted = reserve.to_b_token_down(request.amount);
+                let b_tokens_minted = reserve.mint_b_tokens(e, request.amount);
                 from_state.add_collateral(e, &mut reserve, b_tokens_minted);
                 actions.add_for_spender_transfer(&reserve.asset, request.amount);
+                actions.add_collateralized(&reserve.asset);
+                last_collateral = Some((reserve.asset.clone(), request.amount));
                 pool.cache_reserve(reserve);
+                let nonce = next_action_nonce(e, from, &mut actions.action_nonce);
                 e.events().publish(
                     (
                         Symbol::new(e, "supply_collateral"),
                         request.address.clone(),
                         from.clone(),
                     ),
-                    (request.amount, b_tokens_minted),
+                    (request.amount, b_tokens_minted, nonce),
                 );
             }
             RequestType::WithdrawCollateral => {
                 let mut reserve = pool.load_reserve(e, &request.address, true);
                 let cur_b_tokens = from_state.get_collateral(reserve.index);
-                let mut to_burn = reserve.to_b_token_up(request.amount);
-                let mut tokens_out = request.amount;
-                if to_burn > cur_b_tokens {
-                    to_burn = cur_b_tokens;
-                    tokens_out = reserve.to_asset_from_b_token(cur_b_tokens);
+                let tokens_out = request
+                    .amount
+                    .min(reserve.to_asset_from_b_token(cur_b_tokens));
+                let withdrawn = actions.withdrawn.get(reserve.asset.clone()).unwrap_or(0);
+                if let Some(delay) = calc_withdraw_queue_delay(e, &reserve, withdrawn, tokens_out) {
+                    // large withdrawals are queued and executed with an ExecuteQueuedWithdraw request
+                    let withdrawal =
+                        actions.add_queued_withdrawal(e, &reserve, tokens_out, delay, true);
+                    e.events().publish(
+                        (
+                            Symbol::new(e, "queue_withdraw"),
+                            request.address.clone(),
+                            from.clone(),
+                        ),
+                        (withdrawal.amount, withdrawal.unlock_time),
+                    );
+                } else {
+                    actions.add_withdrawn(&reserve.asset, tokens_out);
+                    let nonce = next_action_nonce(e, from, &mut actions.action_nonce);
+                    withdraw_collateral(
+                        e,
+                        &mut actions,
+                        &mut from_state,
+                        &mut reserve,
+                        request.amount,
+                        request_index,
+                        nonce,
+                    );
+                    check_health = true;
+                    pool.cache_reserve(reserve);
                 }
-                from_state.remove_collateral(e, &mut reserve, to_burn);
-                actions.add_for_pool_transfer(&reserve.asset, tokens_out);
-                check_health = true;
-                pool.cache_reserve(reserve);
-                e.events().publish(
-                    (
-                        Symbol::new(e, "withdraw_collateral"),
-                        request.address.clone(),
-                        from.clone(),
-                    ),
-                    (tokens_out, to_burn),
-                );
             }
             RequestType::Borrow => {
+                require_not_restricted(e, pool, &request.address, request_index);
                 let mut reserve = pool.load_reserve(e, &request.address, true);
                 let d_tokens_minted = reserve.to_d_token_up(request.amount);
                 from_state.add_liabilities(e, &mut reserve, d_tokens_minted);
                 reserve.require_utilization_below_max(e);
                 actions.add_for_pool_transfer(&reserve.asset, request.amount);
+                actions.add_borrowed(&reserve.asset);
                 check_health = true;
                 pool.cache_reserve(reserve);
+                let nonce = next_action_nonce(e, from, &mut actions.action_nonce);
                 e.events().publish(
                     (
                         Symbol::new(e, "borrow"),
                         request.address.clone(),
                         from.clone(),
                     ),
-                    (request.amount, d_tokens_minted),
+                    (request.amount, d_tokens_minted, nonce),
                 );
             }
             RequestType::Repay => {
                 let mut reserve = pool.load_reserve(e, &request.address, true);
-                let cur_d_tokens = from_state.get_liabilities(reserve.index);
+                // liabilities backing a fixed-term loan can only be repaid at the loan's locked rate,
+                // and liabilities backing stable rate debt must first be swapped to variable
+                let cur_d_tokens = from_state.get_liabilities(reserve.index)
+                    - get_fixed_d_tokens(e, &from_state, reserve.index)
+                    - get_stable_d_tokens(e, &from_state, reserve.index);
                 let d_tokens_burnt = reserve.to_d_token_down(request.amount);
                 actions.add_for_spender_transfer(&reserve.asset, request.amount);
+                let nonce = next_action_nonce(e, from, &mut actions.action_nonce);
                 if d_tokens_burnt > cur_d_tokens {
                     let amount_to_refund =
                         request.amount - reserve.to_asset_from_d_token(cur_d_tokens);
-                    require_nonnegative(e, &amount_to_refund);
+                    if amount_to_refund.is_negative() {
+                        panic_with_context(
+                            e,
+                            PoolError::NegativeAmountError,
+                            Some(request_index),
+                            Some(request.address.clone()),
+                        );
+                    }
                     from_state.remove_liabilities(e, &mut reserve, cur_d_tokens);
                     actions.add_for_pool_transfer(&reserve.asset, amount_to_refund);
                     e.events().publish(
@@ -223,7 +376,7 @@ pub fn build_actions_from_request(
                             request.address.clone().clone(),
                             from.clone(),
                         ),
-                        (request.amount - amount_to_refund, cur_d_tokens),
+                        (request.amount - amount_to_refund, cur_d_tokens, nonce),
                     );
                 } else {
                     from_state.remove_liabilities(e, &mut reserve, d_tokens_burnt);
@@ -233,13 +386,13 @@ pub fn build_actions_from_request(
                             request.address.clone().clone(),
                             from.clone(),
                         ),
-                        (request.amount, d_tokens_burnt),
+                        (request.amount, d_tokens_burnt, nonce),
                     );
                 }
                 pool.cache_reserve(reserve);
             }
             RequestType::FillUserLiquidationAuction => {
-                auctions::fill(
+                let filled_auction = auctions::fill(
                     e,
                     pool,
                     0,
@@ -247,6 +400,8 @@ pub fn build_actions_from_request(
                     &mut from_state,
                     request.amount as u64,
                 );
+                last_auction_bid = Some(filled_auction.bid.clone());
+                last_fill_bid = Some(filled_auction.bid);
                 check_health = true;
 
                 e.events().publish(
@@ -260,7 +415,7 @@ pub fn build_actions_from_request(
             }
             RequestType::FillBadDebtAuction => {
                 // Note: will fail if input address is not the backstop since there cannot be a bad debt auction for a different address in storage
-                auctions::fill(
+                let filled_auction = auctions::fill(
                     e,
                     pool,
                     1,
@@ -268,6 +423,7 @@ pub fn build_actions_from_request(
                     &mut from_state,
                     request.amount as u64,
                 );
+                last_auction_bid = Some(filled_auction.bid);
                 check_health = true;
 
                 e.events().publish(
@@ -281,7 +437,7 @@ pub fn build_actions_from_request(
             }
             RequestType::FillInterestAuction => {
                 // Note: will fail if input address is not the backstop since there cannot be an interest auction for a different address in storage
-                auctions::fill(
+                let filled_auction = auctions::fill(
                     e,
                     pool,
                     2,
@@ -289,6 +445,11 @@ pub fn build_actions_from_request(
                     &mut from_state,
                     request.amount as u64,
                 );
+                // the lot is sent to "to" with the rest of the pool's transfers
+                for (asset, lot_amount) in filled_auction.lot.iter() {
+                    actions.add_for_pool_transfer(&asset, lot_amount);
+                }
+                last_auction_bid = Some(filled_auction.bid);
                 e.events().publish(
                     (
                         Symbol::new(e, "fill_auction"),
@@ -298,6 +459,27 @@ pub fn build_actions_from_request(
                     (from.clone(), request.amount),
                 );
             }
+            RequestType::FillSurplusAuction => {
+                let filled_auction = auctions::fill(
+                    e,
+                    pool,
+                    3,
+                    &request.address,
+                    &mut from_state,
+                    request.amount as u64,
+                );
+                last_auction_bid = Some(filled_auction.bid);
+                check_health = true;
+
+                e.events().publish(
+                    (
+                        Symbol::new(e, "fill_auction"),
+                        request.address.clone(),
+                        3_u32,
+                    ),
+                    (from.clone(), request.amount),
+                );
+            }
             RequestType::DeleteLiquidationAuction => {
                 // Note: request object is ignored besides type
                 auctions::delete_liquidation(e, &from);
@@ -307,6 +489,129 @@ pub fn build_actions_from_request(
                     (),
                 );
             }
+            RequestType::ClosePosition => {
+                // Note: request.address is the collateral asset sold and request.amount is the max amount sold
+                let (sold, remaining) =
+                    close_position(e, pool, &mut from_state, &request.address, request.amount);
+                if remaining > 0 {
+                    actions.add_for_pool_transfer(&request.address, remaining);
+                }
+                check_health = true;
+                e.events().publish(
+                    (
+                        Symbol::new(e, "close_position"),
+                        request.address.clone(),
+                        from.clone(),
+                    ),
+                    (sold, remaining),
+                );
+            }
+            RequestType::LoopSupplyBorrow => {
+                // Note: request.address is the borrow asset and request.amount is the target leverage
+                // of the collateral supplied by the previous SupplyCollateral request
+                require_not_restricted(e, pool, &request.address, request_index);
+                let (collateral_asset, equity) = last_collateral.clone().unwrap_or_else(|| {
+                    panic_with_context(
+                        e,
+                        PoolError::BadRequest,
+                        Some(request_index),
+                        Some(request.address.clone()),
+                    )
+                });
+                let (borrowed, supplied) = loop_supply_borrow(
+                    e,
+                    pool,
+                    &mut from_state,
+                    &collateral_asset,
+                    equity,
+                    &request.address,
+                    request.amount,
+                );
+                actions.add_borrowed(&request.address);
+                check_health = true;
+                e.events().publish(
+                    (
+                        Symbol::new(e, "loop_supply_borrow"),
+                        request.address.clone(),
+                        from.clone(),
+                    ),
+                    (collateral_asset, borrowed, supplied),
+                );
+            }
+            RequestType::RepayFillWithSwap => {
+                // Note: request.address is the token the filler pays with and request.amount is the
+                // max amount sold to repay the liabilities of the previous FillUserLiquidationAuction request
+                let bid = last_fill_bid.take().unwrap_or_else(|| {
+                    panic_with_context(
+                        e,
+                        PoolError::BadRequest,
+                        Some(request_index),
+                        Some(request.address.clone()),
+                    )
+                });
+                // the filler transfers the max amount sold before the pool swaps it, and any
+                // amount not sold is returned to "to"
+                let swap_repay = repay_with_swap(
+                    e,
+                    pool,
+                    &mut from_state,
+                    &bid,
+                    &request.address,
+                    request.amount,
+                    request_index,
+                );
+                actions.add_for_spender_transfer(&request.address, request.amount);
+                actions.swap_repays.push_back(swap_repay);
+            }
+            RequestType::CapFillBid => {
+                // Note: request.address is a bid asset and request.amount is the max amount of it the filler
+                // will pay for the previous fill request, in the units of the auction's bid
+                let bid = last_auction_bid.as_ref().unwrap_or_else(|| {
+                    panic_with_context(
+                        e,
+                        PoolError::BadRequest,
+                        Some(request_index),
+                        Some(request.address.clone()),
+                    )
+                });
+                if bid.get(request.address.clone()).unwrap_or(0) > request.amount {
+                    panic_with_context(
+                        e,
+                        PoolError::BidCapExceeded,
+                        Some(request_index),
+                        Some(request.address.clone()),
+                    );
+                }
+            }
+            RequestType::ExecuteQueuedWithdraw => {
+                // Note: request.amount is ignored and the queued amount is withdrawn
+                let mut reserve = pool.load_reserve(e, &request.address, true);
+                let withdrawal = take_queued_withdrawal(e, from, &reserve, request_index);
+                let nonce = next_action_nonce(e, from, &mut actions.action_nonce);
+                if withdrawal.collateral {
+                    withdraw_collateral(
+                        e,
+                        &mut actions,
+                        &mut from_state,
+                        &mut reserve,
+                        withdrawal.amount,
+                        request_index,
+                        nonce,
+                    );
+                    check_health = true;
+                } else {
+                    withdraw_supply(
+                        e,
+                        &mut actions,
+                        &mut from_state,
+                        &mut reserve,
+                        withdrawal.amount,
+                        request_index,
+                        nonce,
+                    );
+                }
+                pool.cache_reserve(reserve);
+            }
         }
     }
 
@@ -316,17 +621,130 @@ pub fn build_actions_from_request(
     (actions, from_state, check_health)
 }
 
+/// Fetch the nonce for the next supply, withdraw, borrow, or repay action of a user, which lets
+/// indexers order repeated actions on the same asset within a transaction. The user's last nonce
+/// is only loaded once it is first needed.
+fn next_action_nonce(e: &Env, user: &Address, action_nonce: &mut Option<u64>) -> u64 {
+    let nonce = action_nonce.unwrap_or_else(|| storage::get_action_nonce(e, user)) + 1;
+    *action_nonce = Some(nonce);
+    nonce
+}
+
+/// Withdraw up to `amount` underlying tokens of a user's supply from a reserve, publishing the
+/// withdrawal with the user's action nonce `nonce`
+///
+/// ### Panics
+/// If the withdrawal leaves the reserve above the pool's withdraw utilization cap
+fn withdraw_supply(
+    e: &Env,
+    actions: &mut Actions,
+    from_state: &mut User,
+    reserve: &mut Reserve,
+    amount: i128,
+    request_index: u32,
+    nonce: u64,
+) {
+    let cur_b_tokens = from_state.get_supply(reserve.index);
+    let mut to_burn = reserve.to_b_token_up(amount);
+    let mut tokens_out = amount;
+    if to_burn > cur_b_tokens {
+        to_burn = cur_b_tokens;
+        tokens_out = reserve.to_asset_from_b_token(cur_b_tokens);
+    }
+    from_state.remove_supply(e, reserve, to_burn);
+    require_withdraw_util_below_cap(e, reserve, request_index);
+    actions.add_for_pool_transfer(&reserve.asset, tokens_out);
+    e.events().publish(
+        (
+            Symbol::new(e, "withdraw"),
+            reserve.asset.clone(),
+            from_state.address.clone(),
+        ),
+        (tokens_out, to_burn, nonce),
+    );
+}
+
+/// Withdraw up to `amount` underlying tokens of a user's collateral from a reserve, publishing
+/// the withdrawal with the user's action nonce `nonce`
+///
+/// ### Panics
+/// If the withdrawal leaves the reserve above the pool's withdraw utilization cap
+fn withdraw_collateral(
+    e: &Env,
+    actions: &mut Actions,
+    from_state: &mut User,
+    reserve: &mut Reserve,
+    amount: i128,
+    request_index: u32,
+    nonce: u64,
+) {
+    let cur_b_tokens = from_state.get_collateral(reserve.index);
+    let mut to_burn = reserve.to_b_token_up(amount);
+    let mut tokens_out = amount;
+    if to_burn > cur_b_tokens {
+        to_burn = cur_b_tokens;
+        tokens_out = reserve.to_asset_from_b_token(cur_b_tokens);
+    }
+    from_state.remove_collateral(e, reserve, to_burn);
+    require_withdraw_util_below_cap(e, reserve, request_index);
+    actions.add_for_pool_transfer(&reserve.asset, tokens_out);
+    e.events().publish(
+        (
+            Symbol::new(e, "withdraw_collateral"),
+            reserve.asset.clone(),
+            from_state.address.clone(),
+        ),
+        (tokens_out, to_burn, nonce),
+    );
+}
+
+/// Require that borrowing from a reserve is not restricted by the price breaker. If the price
+/// breaker is enabled, the price of the reserve is loaded first so a price move observed by this
+/// request is checked.
+///
+/// ### Panics
+/// If the reserve is restricted
+fn require_not_restricted(e: &Env, pool: &mut Pool, asset: &Address, request_index: u32) {
+    if storage::get_price_breaker(e) > 0 {
+        pool.load_price(e, asset);
+    }
+    if pool.is_restricted(e, asset) {
+        panic_with_context(
+            e,
+            PoolError::ReserveRestricted,
+            Some(request_index),
+            Some(asset.clone()),
+        );
+    }
+}
+
+/// Require that a withdrawal did not leave the reserve above the pool's withdraw utilization cap
+///
+/// ### Panics
+/// If the reserve's utilization is above the cap
+fn require_withdraw_util_below_cap(e: &Env, reserve: &Reserve, request_index: u32) {
+    if reserve.is_utilization_above(i128(storage::get_withdraw_util_cap(e))) {
+        panic_with_context(
+            e,
+            PoolError::WithdrawUtilExceeded,
+            Some(request_index),
+            Some(reserve.asset.clone()),
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use crate::{
         constants::SCALAR_7,
-        storage::{self, PoolConfig},
+        storage::{self, FixedLoan, PoolConfig, QueuedWithdrawal, StableDebt, WithdrawQueueConfig},
         testutils::{self, create_comet_lp_pool, create_pool},
         AuctionData, AuctionType, Positions,
     };
 
     use super::*;
+    use sep_40_oracle::testutils::Asset;
     use soroban_sdk::{
         map,
         testutils::{Address as _, Ledger, LedgerInfo},
@@ -405,6 +823,90 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_build_actions_from_request_increments_action_nonce() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_action_nonce(&e, &samwise, 5);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Supply as u32,
+                    address: underlying.clone(),
+                    amount: 10_0000000,
+                },
+                Request {
+                    request_type: RequestType::Supply as u32,
+                    address: underlying.clone(),
+                    amount: 5_0000000,
+                },
+                Request {
+                    request_type: RequestType::Withdraw as u32,
+                    address: underlying.clone(),
+                    amount: 1_0000000,
+                },
+            ];
+            let (actions, _, _) = build_actions_from_request(&e, &mut pool, &samwise, requests);
+            assert_eq!(actions.action_nonce, Some(8));
+            assert_eq!(storage::get_action_nonce(&e, &samwise), 5);
+            actions.store(&e, &samwise);
+            assert_eq!(storage::get_action_nonce(&e, &samwise), 8);
+
+            // requests without a supply, withdraw, borrow, or repay action leave the nonce unchanged
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::DeleteLiquidationAuction as u32,
+                    address: underlying.clone(),
+                    amount: 0,
+                },
+            ];
+            storage::set_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &samwise,
+                &AuctionData {
+                    bid: map![&e],
+                    lot: map![&e],
+                    block: 1234,
+                },
+            );
+            let (actions, _, _) = build_actions_from_request(&e, &mut pool, &samwise, requests);
+            assert_eq!(actions.action_nonce, None);
+            assert_eq!(storage::get_action_nonce(&e, &samwise), 8);
+        });
+    }
+
     /***** withdraw *****/
 
     #[test]
@@ -482,7 +984,7 @@ mod tests {
     }
 
     #[test]
-    fn test_build_actions_from_request_withdraw_over_balance() {
+    fn test_build_actions_from_request_withdraw_queued() {
         let e = Env::default();
         e.mock_all_auths();
 
@@ -510,6 +1012,7 @@ mod tests {
             status: 0,
             max_positions: 2,
         };
+
         let user_positions = Positions {
             liabilities: map![&e],
             collateral: map![&e],
@@ -518,42 +1021,79 @@ mod tests {
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
             storage::set_user_positions(&e, &samwise, &user_positions);
+            // withdrawals over 10% of the ~25 tokens of available liquidity are queued
+            storage::set_withdraw_queue_config(
+                &e,
+                &underlying,
+                &WithdrawQueueConfig {
+                    threshold: 0_1000000,
+                    delay: 3600,
+                },
+            );
 
             let mut pool = Pool::load(&e);
-
             let requests = vec![
                 &e,
                 Request {
                     request_type: RequestType::Withdraw as u32,
                     address: underlying.clone(),
-                    amount: 21_0000000,
+                    amount: 10_1234567,
                 },
             ];
             let (actions, user, health_check) =
                 build_actions_from_request(&e, &mut pool, &samwise, requests);
 
-            assert_eq!(health_check, false);
-
-            let spender_transfer = actions.spender_transfer;
-            let pool_transfer = actions.pool_transfer;
-            assert_eq!(spender_transfer.len(), 0);
-            assert_eq!(pool_transfer.len(), 1);
-            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 20_0000137);
+            assert!(!health_check);
+            assert_eq!(actions.spender_transfer.len(), 0);
+            assert_eq!(actions.pool_transfer.len(), 0);
+            assert_eq!(user.get_supply(0), 20_0000000);
+            assert_eq!(storage::get_queued_withdrawal(&e, &samwise, 0), None);
+            actions.store(&e, &samwise);
+            assert_eq!(
+                storage::get_queued_withdrawal(&e, &samwise, 0),
+                Some(QueuedWithdrawal {
+                    amount: 10_1234567,
+                    unlock_time: 600 + 3600,
+                    collateral: false,
+                })
+            );
 
-            let positions = user.positions.clone();
-            assert_eq!(positions.liabilities.len(), 0);
-            assert_eq!(positions.collateral.len(), 0);
-            assert_eq!(positions.supply.len(), 0);
+            e.ledger().set(LedgerInfo {
+                timestamp: 600 + 3600,
+                protocol_version: 20,
+                sequence_number: 1234,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 10,
+                min_persistent_entry_ttl: 10,
+                max_entry_ttl: 3110400,
+            });
+            let mut pool = Pool::load(&e);
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::ExecuteQueuedWithdraw as u32,
+                    address: underlying.clone(),
+                    amount: 0,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
 
-            let reserve = pool.load_reserve(&e, &underlying.clone(), false);
-            assert_eq!(reserve.b_supply, reserve_data.b_supply - 20_0000000);
+            assert!(!health_check);
+            assert_eq!(actions.spender_transfer.len(), 0);
+            assert_eq!(actions.pool_transfer.len(), 1);
+            assert_eq!(
+                actions.pool_transfer.get_unchecked(underlying.clone()),
+                10_1234567
+            );
+            assert!(user.get_supply(0) < 10_0000000);
+            assert_eq!(storage::get_queued_withdrawal(&e, &samwise, 0), None);
         });
     }
 
-    /***** supply collateral *****/
-
     #[test]
-    fn test_build_actions_from_request_supply_collateral() {
+    fn test_build_actions_from_request_withdraw_collateral_queued() {
         let e = Env::default();
         e.mock_all_auths();
 
@@ -577,15 +1117,195 @@ mod tests {
         });
         let pool_config = PoolConfig {
             oracle: Address::generate(&e),
-            bstop_rate: 0_1000000,
+            bstop_rate: 0_2000000,
             status: 0,
             max_positions: 2,
         };
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-
-            let mut pool = Pool::load(&e);
-
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            // withdrawals over 10% of the ~25 tokens of available liquidity are queued
+            storage::set_withdraw_queue_config(
+                &e,
+                &underlying,
+                &WithdrawQueueConfig {
+                    threshold: 0_1000000,
+                    delay: 3600,
+                },
+            );
+
+            let mut pool = Pool::load(&e);
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::WithdrawCollateral as u32,
+                    address: underlying.clone(),
+                    amount: 10_1234567,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            assert!(!health_check);
+            assert_eq!(actions.spender_transfer.len(), 0);
+            assert_eq!(actions.pool_transfer.len(), 0);
+            assert_eq!(user.get_collateral(0), 20_0000000);
+            assert_eq!(storage::get_queued_withdrawal(&e, &samwise, 0), None);
+            actions.store(&e, &samwise);
+            assert_eq!(
+                storage::get_queued_withdrawal(&e, &samwise, 0),
+                Some(QueuedWithdrawal {
+                    amount: 10_1234567,
+                    unlock_time: 600 + 3600,
+                    collateral: true,
+                })
+            );
+
+            e.ledger().set(LedgerInfo {
+                timestamp: 600 + 3600,
+                protocol_version: 20,
+                sequence_number: 1234,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 10,
+                min_persistent_entry_ttl: 10,
+                max_entry_ttl: 3110400,
+            });
+            let mut pool = Pool::load(&e);
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::ExecuteQueuedWithdraw as u32,
+                    address: underlying.clone(),
+                    amount: 0,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            assert!(health_check);
+            assert_eq!(actions.spender_transfer.len(), 0);
+            assert_eq!(actions.pool_transfer.len(), 1);
+            assert_eq!(
+                actions.pool_transfer.get_unchecked(underlying.clone()),
+                10_1234567
+            );
+            assert!(user.get_collateral(0) < 10_0000000);
+            assert_eq!(storage::get_queued_withdrawal(&e, &samwise, 0), None);
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_withdraw_over_balance() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e],
+            supply: map![&e, (0, 20_0000000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Withdraw as u32,
+                    address: underlying.clone(),
+                    amount: 21_0000000,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            assert_eq!(health_check, false);
+
+            let spender_transfer = actions.spender_transfer;
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(spender_transfer.len(), 0);
+            assert_eq!(pool_transfer.len(), 1);
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 20_0000137);
+
+            let positions = user.positions.clone();
+            assert_eq!(positions.liabilities.len(), 0);
+            assert_eq!(positions.collateral.len(), 0);
+            assert_eq!(positions.supply.len(), 0);
+
+            let reserve = pool.load_reserve(&e, &underlying.clone(), false);
+            assert_eq!(reserve.b_supply, reserve_data.b_supply - 20_0000000);
+        });
+    }
+
+    /***** supply collateral *****/
+
+    #[test]
+    fn test_build_actions_from_request_supply_collateral() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
             let requests = vec![
                 &e,
                 Request {
@@ -812,6 +1532,7 @@ mod tests {
             let (actions, user, health_check) =
                 build_actions_from_request(&e, &mut pool, &samwise, requests);
             assert_eq!(health_check, true);
+            assert_eq!(actions.borrowed, vec![&e, underlying.clone()]);
 
             let spender_transfer = actions.spender_transfer;
             let pool_transfer = actions.pool_transfer;
@@ -979,6 +1700,141 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_build_actions_from_request_repay_keeps_fixed_loan() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 20_0000000)],
+            collateral: map![&e],
+            supply: map![&e],
+        };
+        let fixed_loan = FixedLoan {
+            d_tokens: 5_0000000,
+            principal: 5_0000000,
+            rate: 0_0500000,
+            start: 600,
+            maturity: 600 + 30 * 24 * 60 * 60,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_fixed_loans(&e, &samwise, &map![&e, (0, fixed_loan)]);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Repay as u32,
+                    address: underlying.clone(),
+                    amount: 21_0000000,
+                },
+            ];
+            let (actions, user, _) = build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            // only the liabilities not backing the fixed-term loan are repaid
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 5_9999828);
+            assert_eq!(user.get_liabilities(0), 5_0000000);
+
+            let reserve = pool.load_reserve(&e, &underlying, false);
+            assert_eq!(reserve.d_supply, reserve_data.d_supply - 15_0000000);
+        });
+    }
+
+    #[test]
+    fn test_build_actions_from_request_repay_keeps_stable_debt() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 20_0000000)],
+            collateral: map![&e],
+            supply: map![&e],
+        };
+        let stable_debt = StableDebt {
+            d_tokens: 5_0000000,
+            principal: 5_0000000,
+            rate: 0_0500000,
+            last_time: 600,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_stable_debts(&e, &samwise, &map![&e, (0, stable_debt)]);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Repay as u32,
+                    address: underlying.clone(),
+                    amount: 21_0000000,
+                },
+            ];
+            let (actions, user, _) = build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            // only the liabilities not backing the stable rate debt are repaid
+            let pool_transfer = actions.pool_transfer;
+            assert_eq!(pool_transfer.get_unchecked(underlying.clone()), 5_9999828);
+            assert_eq!(user.get_liabilities(0), 5_0000000);
+
+            let reserve = pool.load_reserve(&e, &underlying, false);
+            assert_eq!(reserve.d_supply, reserve_data.d_supply - 15_0000000);
+        });
+    }
+
     #[test]
     fn test_aggregating_actions() {
         let e = Env::default();
@@ -1098,93 +1954,13 @@ mod tests {
             max_entry_ttl: 3110400,
         });
 
-        let bombadil = Address::generate(&e);
         let samwise = Address::generate(&e);
         let frodo = Address::generate(&e);
 
         let pool_address = create_pool(&e);
-
-        let (oracle_address, _) = testutils::create_mock_oracle(&e);
-
-        // creating reserves for a pool exhausts the budget
-        e.budget().reset_unlimited();
-        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
-        let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
-        reserve_data_0.last_time = 12345;
-        reserve_data_0.b_rate = 1_100_000_000;
-        reserve_config_0.c_factor = 0_8500000;
-        reserve_config_0.l_factor = 0_9000000;
-        reserve_config_0.index = 0;
-        testutils::create_reserve(
-            &e,
-            &pool_address,
-            &underlying_0,
-            &reserve_config_0,
-            &reserve_data_0,
-        );
-
-        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
-        let (mut reserve_config_1, mut reserve_data_1) = testutils::default_reserve_meta();
-        reserve_data_1.b_rate = 1_200_000_000;
-        reserve_config_1.c_factor = 0_7500000;
-        reserve_config_1.l_factor = 0_7500000;
-        reserve_data_1.last_time = 12345;
-        reserve_config_1.index = 1;
-        testutils::create_reserve(
-            &e,
-            &pool_address,
-            &underlying_1,
-            &reserve_config_1,
-            &reserve_data_1,
-        );
-
-        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
-        let (mut reserve_config_2, reserve_data_2) = testutils::default_reserve_meta();
-        reserve_config_2.c_factor = 0_0000000;
-        reserve_config_2.l_factor = 0_7000000;
-        reserve_config_2.index = 2;
-        testutils::create_reserve(
-            &e,
-            &pool_address,
-            &underlying_2,
-            &reserve_config_2,
-            &reserve_data_2,
-        );
-
-        let auction_data = AuctionData {
-            bid: map![&e, (underlying_2.clone(), 1_2375000)],
-            lot: map![
-                &e,
-                (underlying_0.clone(), 30_5595329),
-                (underlying_1.clone(), 1_5395739)
-            ],
-            block: 176,
-        };
-        let pool_config = PoolConfig {
-            oracle: oracle_address,
-            bstop_rate: 0_1000000,
-            status: 0,
-            max_positions: 4,
-        };
-        let positions: Positions = Positions {
-            collateral: map![
-                &e,
-                (reserve_config_0.index, 90_9100000),
-                (reserve_config_1.index, 04_5800000),
-            ],
-            liabilities: map![&e, (reserve_config_2.index, 02_7500000),],
-            supply: map![&e],
-        };
+        let (underlying_0, underlying_1, underlying_2, _, _) =
+            testutils::create_user_liquidation(&e, &pool_address, &samwise);
         e.as_contract(&pool_address, || {
-            storage::set_pool_config(&e, &pool_config);
-            storage::set_user_positions(&e, &samwise, &positions);
-            storage::set_auction(
-                &e,
-                &(AuctionType::UserLiquidation as u32),
-                &samwise,
-                &auction_data,
-            );
-
             let mut pool = Pool::load(&e);
 
             let requests = vec![
@@ -1218,6 +1994,223 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_cap_fill_bid() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 176 + 200,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+        let (underlying_0, _, underlying_2, _, _) =
+            testutils::create_user_liquidation(&e, &pool_address, &samwise);
+        e.as_contract(&pool_address, || {
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::FillUserLiquidationAuction as u32,
+                    address: samwise.clone(),
+                    amount: 50,
+                },
+                Request {
+                    request_type: RequestType::CapFillBid as u32,
+                    address: underlying_2.clone(),
+                    amount: 6187500,
+                },
+                Request {
+                    request_type: RequestType::CapFillBid as u32,
+                    address: underlying_0.clone(),
+                    amount: 0,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &frodo, requests);
+
+            assert_eq!(health_check, true);
+            assert_eq!(user.get_liabilities(2), 6187500);
+            assert_eq!(actions.pool_transfer.len(), 0);
+            assert_eq!(actions.spender_transfer.len(), 0);
+        });
+    }
+
+    #[test]
+    fn test_fill_user_liquidation_batch() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 176 + 200,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pippin = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+        let (_, _, underlying_2, positions, auction_data) =
+            testutils::create_user_liquidation(&e, &pool_address, &samwise);
+        e.as_contract(&pool_address, || {
+            storage::set_user_positions(&e, &pippin, &positions);
+            storage::set_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &pippin,
+                &auction_data,
+            );
+
+            let mut pool = Pool::load(&e);
+
+            // both auctions are filled with a single health check for the filler
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::FillUserLiquidationAuction as u32,
+                    address: samwise.clone(),
+                    amount: 50,
+                },
+                Request {
+                    request_type: RequestType::FillUserLiquidationAuction as u32,
+                    address: pippin.clone(),
+                    amount: 100,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &frodo, requests);
+
+            assert_eq!(health_check, true);
+            assert_eq!(user.get_liabilities(2), 6187500 + 1_2375000);
+            assert_eq!(user.get_collateral(0), 15_2797664 + 30_5595329);
+            assert_eq!(user.get_collateral(1), 7697869 + 1_5395739);
+            assert_eq!(actions.pool_transfer.len(), 0);
+            assert_eq!(actions.spender_transfer.len(), 0);
+
+            let samwise_auction =
+                storage::get_auction(&e, &(AuctionType::UserLiquidation as u32), &samwise);
+            assert_eq!(
+                samwise_auction.bid.get_unchecked(underlying_2.clone()),
+                6187500
+            );
+            assert!(!storage::has_auction(
+                &e,
+                &(AuctionType::UserLiquidation as u32),
+                &pippin
+            ));
+            let pippin_positions = storage::get_user_positions(&e, &pippin);
+            assert_eq!(pippin_positions.collateral.len(), 2);
+            assert_eq!(
+                pippin_positions.liabilities.get_unchecked(2),
+                02_7500000 - 1_2375000
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1233)")]
+    fn test_cap_fill_bid_exceeded() {
+        let e = Env::default();
+
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 176 + 200,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let pool_address = create_pool(&e);
+        let (_, _, underlying_2, _, _) =
+            testutils::create_user_liquidation(&e, &pool_address, &samwise);
+        e.as_contract(&pool_address, || {
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::FillUserLiquidationAuction as u32,
+                    address: samwise.clone(),
+                    amount: 50,
+                },
+                Request {
+                    request_type: RequestType::CapFillBid as u32,
+                    address: underlying_2.clone(),
+                    amount: 6187499,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &frodo, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_cap_fill_bid_requires_fill_request() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_address = create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::CapFillBid as u32,
+                    address: underlying.clone(),
+                    amount: 1_0000000,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
+        });
+    }
+
     #[test]
     fn test_fill_bad_debt_auction() {
         let e = Env::default();
@@ -1244,16 +2237,18 @@ mod tests {
 
         // creating reserves for a pool exhausts the budget
         e.budget().reset_unlimited();
+        let (usdc_id, _) = testutils::create_token_contract(&e, &bombadil);
+        let (blnd_id, _) = testutils::create_token_contract(&e, &bombadil);
         let (backstop_token_id, backstop_token_client) =
-            testutils::create_token_contract(&e, &bombadil);
+            create_comet_lp_pool(&e, &bombadil, &blnd_id, &usdc_id);
         let (backstop_address, backstop_client) = testutils::create_backstop(&e);
         testutils::setup_backstop(
             &e,
             &pool_address,
             &backstop_address,
             &backstop_token_id,
-            &Address::generate(&e),
-            &Address::generate(&e),
+            &usdc_id,
+            &blnd_id,
         );
         let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
         let (mut reserve_config_0, mut reserve_data_0) = testutils::default_reserve_meta();
@@ -1304,8 +2299,7 @@ mod tests {
             ],
             supply: map![&e],
         };
-        backstop_token_client.mint(&samwise, &95_2000000);
-        backstop_token_client.approve(&samwise, &backstop_address, &i128::MAX, &1000000);
+        backstop_token_client.transfer(&bombadil, &samwise, &95_2000000);
         backstop_client.deposit(&samwise, &pool_address, &95_2000000);
         e.as_contract(&pool_address, || {
             storage::set_pool_config(&e, &pool_config);
@@ -1476,8 +2470,8 @@ mod tests {
                 backstop_token_client.balance(&backstop_address),
                 pre_fill_backstop_token_balance + 75_0000000
             );
-            assert_eq!(underlying_0_client.balance(&samwise), 100_0000000);
-            assert_eq!(underlying_1_client.balance(&samwise), 25_0000000);
+            assert_eq!(underlying_0_client.balance(&samwise), 0);
+            assert_eq!(underlying_1_client.balance(&samwise), 0);
             assert_eq!(health_check, false);
             assert_eq!(
                 storage::has_auction(
@@ -1487,7 +2481,15 @@ mod tests {
                 ),
                 false
             );
-            assert_eq!(actions.pool_transfer.len(), 0);
+            assert_eq!(actions.pool_transfer.len(), 2);
+            assert_eq!(
+                actions.pool_transfer.get_unchecked(underlying_0),
+                100_0000000
+            );
+            assert_eq!(
+                actions.pool_transfer.get_unchecked(underlying_1),
+                25_0000000
+            );
             assert_eq!(actions.spender_transfer.len(), 0);
         });
     }
@@ -1566,6 +2568,471 @@ mod tests {
         });
     }
 
+    /***** close position *****/
+
+    #[test]
+    fn test_close_position() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_address = create_pool(&e);
+
+        let (usdc, _) = testutils::create_token_contract(&e, &bombadil);
+        let (blnd, _) = testutils::create_token_contract(&e, &bombadil);
+        let (amm, _) = create_comet_lp_pool(&e, &bombadil, &blnd, &usdc);
+
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(&e, &pool_address, &usdc, &reserve_config, &reserve_data);
+        testutils::create_reserve(&e, &pool_address, &blnd, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 20_0000000)],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_amm(&e, &amm);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::ClosePosition as u32,
+                    address: usdc.clone(),
+                    amount: 5_0000000,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            assert!(health_check);
+            assert_eq!(user.positions.liabilities.len(), 0);
+            assert_eq!(user.positions.collateral.len(), 0);
+            assert_eq!(actions.spender_transfer.len(), 0);
+            assert_eq!(actions.pool_transfer.len(), 1);
+            // ~2.11 USDC is sold for the 20 BLND owed
+            assert_eq!(actions.pool_transfer.get_unchecked(usdc.clone()), 7_8895239);
+        });
+    }
+
+    /***** loop supply borrow *****/
+
+    #[test]
+    fn test_loop_supply_borrow() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_address = create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::SupplyCollateral as u32,
+                    address: underlying.clone(),
+                    amount: 10_0000000,
+                },
+                Request {
+                    request_type: RequestType::LoopSupplyBorrow as u32,
+                    address: underlying.clone(),
+                    amount: 3_0000000,
+                },
+            ];
+            let (actions, user, health_check) =
+                build_actions_from_request(&e, &mut pool, &samwise, requests);
+
+            assert!(health_check);
+            assert_eq!(user.get_collateral(0), 30_0000000);
+            assert_eq!(user.get_liabilities(0), 20_0000000);
+            assert_eq!(actions.spender_transfer.len(), 1);
+            assert_eq!(
+                actions.spender_transfer.get_unchecked(underlying.clone()),
+                10_0000000
+            );
+            assert_eq!(actions.pool_transfer.len(), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_loop_supply_borrow_requires_collateral_request() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_address = create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::LoopSupplyBorrow as u32,
+                    address: underlying.clone(),
+                    amount: 3_0000000,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1231)")]
+    fn test_build_actions_borrow_restricted_reserve_panics() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_address = create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_reserve_restricted(&e, &underlying, true);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Borrow as u32,
+                    address: underlying.clone(),
+                    amount: 1_0000000,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1231)")]
+    fn test_build_actions_borrow_trips_price_breaker_first() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_address = create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![&e, Asset::Stellar(underlying.clone())],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_5000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_price_breaker(&e, &0_2000000);
+            // the price moved 50% since it was last recorded
+            storage::set_last_price(&e, &underlying, &1_0000000);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Borrow as u32,
+                    address: underlying.clone(),
+                    amount: 1_0000000,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_repay_fill_with_swap_requires_fill_request() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_address = create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::RepayFillWithSwap as u32,
+                    address: underlying.clone(),
+                    amount: 3_0000000,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #8)")]
+    fn test_build_actions_negative_amount_panics() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_address = create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Supply as u32,
+                    address: underlying.clone(),
+                    amount: 1_0000000,
+                },
+                Request {
+                    request_type: RequestType::Supply as u32,
+                    address: underlying.clone(),
+                    amount: -1,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_build_actions_unknown_request_type_panics() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_address = create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Supply as u32,
+                    address: underlying.clone(),
+                    amount: 1_0000000,
+                },
+                Request {
+                    request_type: 12,
+                    address: underlying.clone(),
+                    amount: 1_0000000,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1228)")]
+    fn test_build_actions_withdraw_over_util_cap_panics() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool_address = create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(
+            &e,
+            &pool_address,
+            &underlying,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool_address, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_withdraw_util_cap(&e, &0_9000000);
+
+            let mut pool = Pool::load(&e);
+
+            // leaves the reserve at 75 / 82 = ~91.5% utilization
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::WithdrawCollateral as u32,
+                    address: underlying.clone(),
+                    amount: 18_0000000,
+                },
+            ];
+            build_actions_from_request(&e, &mut pool, &samwise, requests);
+        });
+    }
+
     /********** positions_under_max **********/
 
     #[test]
@@ -1,14 +1,18 @@
-use soroban_sdk::{panic_with_error, Address, Env, Symbol};
+use soroban_sdk::{panic_with_error, vec, Address, Env, Symbol, Vec};
 
 use crate::{
     errors::PoolError,
-    storage::{self},
+    storage::{self, BadDebtRecord},
 };
 
-use super::{user::User, Pool};
+use super::{fixed_loan::rebase_fixed_loans, user::User, Pool};
 
 /// Transfer bad debt from a user to the backstop. Validates that the user does hold bad debt
-/// and transfers all held d_tokens to the backstop.
+/// and transfers all held d_tokens to the backstop. Each transferred liability is appended to
+/// the bad debt log.
+///
+/// The backstop is not subject to the pool's max positions, as it can accumulate bad debt
+/// across every reserve.
 ///
 /// ### Arguments
 /// * `user` - The user who has bad debt
@@ -33,11 +37,24 @@ pub fn transfer_bad_debt_to_backstop(e: &Env, user: &Address) {
     let backstop_state = User::load(e, &backstop_address);
     let mut new_user_state = user_state.clone();
     let mut new_backstop_state = backstop_state.clone();
+    let mut log_count = storage::get_bad_debt_count(e);
     for (reserve_index, liability_balance) in user_state.positions.liabilities.iter() {
         let asset = reserve_list.get_unchecked(reserve_index);
         let mut reserve = pool.load_reserve(e, &asset, true);
         new_backstop_state.add_liabilities(e, &mut reserve, liability_balance);
         new_user_state.remove_liabilities(e, &mut reserve, liability_balance);
+        storage::set_bad_debt_record(
+            e,
+            &log_count,
+            &BadDebtRecord {
+                user: user.clone(),
+                asset: asset.clone(),
+                d_tokens: liability_balance,
+                amount: reserve.to_asset_from_d_token(liability_balance),
+                timestamp: e.ledger().timestamp(),
+            },
+        );
+        log_count += 1;
         pool.cache_reserve(reserve);
 
         e.events().publish(
@@ -46,9 +63,26 @@ pub fn transfer_bad_debt_to_backstop(e: &Env, user: &Address) {
         );
     }
 
+    storage::set_bad_debt_count(e, &log_count);
     pool.store_cached_reserves(e);
     new_backstop_state.store(e);
     new_user_state.store(e);
+    rebase_fixed_loans(e, &new_user_state);
+}
+
+/// Fetch records from the bad debt log, in the order the bad debt was transferred to the backstop
+///
+/// ### Arguments
+/// * `start` - The index in the bad debt log to start from
+/// * `limit` - The maximum number of records to return
+pub fn get_bad_debt_log(e: &Env, start: u32, limit: u32) -> Vec<BadDebtRecord> {
+    let count = storage::get_bad_debt_count(e);
+    let mut records = vec![e];
+    let end = start.saturating_add(limit).min(count);
+    for index in start..end {
+        records.push_back(storage::get_bad_debt_record(e, &index));
+    }
+    records
 }
 
 #[cfg(test)]
@@ -113,6 +147,21 @@ mod tests {
             e.budget().reset_unlimited();
             transfer_bad_debt_to_backstop(&e, &samwise);
 
+            let log = get_bad_debt_log(&e, 0, 10);
+            assert_eq!(log.len(), 2);
+            let record_0 = log.get_unchecked(0);
+            assert_eq!(record_0.user, samwise);
+            assert_eq!(record_0.asset, underlying_0);
+            assert_eq!(record_0.d_tokens, 24_0000000);
+            assert_eq!(record_0.amount, 24_0000275);
+            assert_eq!(record_0.timestamp, 600);
+            let record_1 = log.get_unchecked(1);
+            assert_eq!(record_1.user, samwise);
+            assert_eq!(record_1.asset, underlying_1);
+            assert_eq!(record_1.d_tokens, 25_0000000);
+            assert_eq!(get_bad_debt_log(&e, 1, 10).len(), 1);
+            assert_eq!(get_bad_debt_log(&e, 2, 10).len(), 0);
+
             let new_user_positions = storage::get_user_positions(&e, &samwise);
             let new_backstop_positions = storage::get_user_positions(&e, &backstop);
             assert_eq!(new_user_positions.collateral.len(), 0);
@@ -128,6 +177,65 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_transfer_bad_debt_ignores_backstop_max_positions() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let pool = testutils::create_pool(&e);
+        let backstop = Address::generate(&e);
+
+        let samwise = Address::generate(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.index = 1;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.index = 2;
+        testutils::create_reserve(&e, &pool, &underlying_2, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let backstop_positions = Positions {
+            liabilities: map![&e, (0, 10_0000000), (1, 10_0000000)],
+            collateral: map![&e],
+            supply: map![&e],
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (2, 5_0000000)],
+            collateral: map![&e],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_backstop(&e, &backstop);
+            storage::set_user_positions(&e, &backstop, &backstop_positions);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            e.budget().reset_unlimited();
+            transfer_bad_debt_to_backstop(&e, &samwise);
+
+            let new_backstop_positions = storage::get_user_positions(&e, &backstop);
+            assert_eq!(new_backstop_positions.effective_count(), 3);
+            assert_eq!(
+                new_backstop_positions.liabilities.get_unchecked(2),
+                5_0000000
+            );
+        });
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #1200)")]
     fn test_transfer_bad_debt_with_collateral_panics() {
@@ -0,0 +1,339 @@
+use soroban_sdk::{panic_with_error, Address, Env};
+
+use crate::{errors::PoolError, storage};
+
+use super::{swap::swap_exact_amount_out, Pool, User};
+
+/// Repay all of a user's liabilities by selling their collateral of a single asset through the
+/// pool's AMM, and remove the remaining collateral of that asset from the user. Only the amount
+/// of collateral required to repay each liability is sold.
+///
+/// Returns a tuple of (collateral_sold, collateral_remaining) where:
+/// * collateral_sold - The amount of the collateral asset sold or used to repay liabilities
+/// * collateral_remaining - The amount of the collateral asset removed from the user that needs
+///   to be transferred to them
+///
+/// ### Arguments
+/// * `user_state` - The user closing their position
+/// * `collateral_asset` - The underlying asset of the collateral being sold
+/// * `max_sold` - The maximum amount of collateral that can be sold
+///
+/// ### Panics
+/// If the user has no collateral in the asset or has an outstanding fixed-term loan or stable rate
+/// debt, if a swap is required and no AMM is set, or if repaying the liabilities requires selling
+/// more than `max_sold` collateral
+pub fn close_position(
+    e: &Env,
+    pool: &mut Pool,
+    user_state: &mut User,
+    collateral_asset: &Address,
+    max_sold: i128,
+) -> (i128, i128) {
+    let mut collateral_reserve = pool.load_reserve(e, collateral_asset, true);
+    let cur_b_tokens = user_state.get_collateral(collateral_reserve.index);
+    // fixed-term loans can only be repaid at their locked rate, and stable rate debt must first
+    // be swapped to variable
+    if cur_b_tokens == 0
+        || !storage::get_fixed_loans(e, &user_state.address).is_empty()
+        || !storage::get_stable_debts(e, &user_state.address).is_empty()
+    {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let sell_limit = collateral_reserve
+        .to_asset_from_b_token(cur_b_tokens)
+        .min(max_sold);
+
+    let reserve_list = storage::get_res_list(e);
+    let mut sold: i128 = 0;
+    for (reserve_index, d_tokens) in user_state.positions.liabilities.clone().iter() {
+        let asset = reserve_list.get_unchecked(reserve_index);
+        if asset == *collateral_asset {
+            // the liability can be repaid with the collateral directly
+            sold += collateral_reserve.to_asset_from_d_token(d_tokens);
+            if sold > sell_limit {
+                panic_with_error!(e, PoolError::BadRequest);
+            }
+            user_state.remove_liabilities(e, &mut collateral_reserve, d_tokens);
+        } else {
+            let mut reserve = pool.load_reserve(e, &asset, true);
+            let owed = reserve.to_asset_from_d_token(d_tokens);
+            sold += swap_exact_amount_out(e, collateral_asset, sell_limit - sold, &asset, owed);
+            user_state.remove_liabilities(e, &mut reserve, d_tokens);
+            pool.cache_reserve(reserve);
+        }
+    }
+
+    // the sold collateral is rounded up and the remaining collateral is rounded down
+    let sold_b_tokens = collateral_reserve.to_b_token_up(sold).min(cur_b_tokens);
+    let remaining = collateral_reserve.to_asset_from_b_token(cur_b_tokens - sold_b_tokens);
+    user_state.remove_collateral(e, &mut collateral_reserve, cur_b_tokens);
+    pool.cache_reserve(collateral_reserve);
+    (sold, remaining)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pool::Positions,
+        storage::{FixedLoan, PoolConfig, StableDebt},
+        testutils::{self, create_comet_lp_pool},
+    };
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+    };
+
+    #[test]
+    fn test_close_position() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (usdc, usdc_client) = testutils::create_token_contract(&e, &bombadil);
+        let (blnd, blnd_client) = testutils::create_token_contract(&e, &bombadil);
+        let (amm, _) = create_comet_lp_pool(&e, &bombadil, &blnd, &usdc);
+
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(&e, &pool, &usdc, &reserve_config, &reserve_data);
+        testutils::create_reserve(&e, &pool, &blnd, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 1_0000000), (1, 20_0000000)],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e],
+        };
+        // verify the pool authorizes the swap itself
+        e.set_auths(&[]);
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_amm(&e, &amm);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let pre_pool_usdc = usdc_client.balance(&pool);
+            let pre_pool_blnd = blnd_client.balance(&pool);
+
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            let (sold, remaining) =
+                close_position(&e, &mut pool_state, &mut user, &usdc, 5_0000000);
+
+            // 1 USDC is repaid directly and ~2 USDC is sold for 20 BLND at 0.1 USDC per BLND
+            assert!(sold > 3_0000000 && sold < 3_2000000);
+            assert_eq!(remaining, 10_0000000 - sold);
+            assert_eq!(user.positions.liabilities.len(), 0);
+            assert_eq!(user.positions.collateral.len(), 0);
+            assert_eq!(
+                usdc_client.balance(&pool),
+                pre_pool_usdc - (sold - 1_0000000)
+            );
+            assert_eq!(blnd_client.balance(&pool), pre_pool_blnd + 20_0000000);
+            assert_eq!(usdc_client.allowance(&pool, &amm), 0);
+
+            let usdc_reserve = pool_state.load_reserve(&e, &usdc, false);
+            assert_eq!(usdc_reserve.b_supply, reserve_data.b_supply - 10_0000000);
+            assert_eq!(usdc_reserve.d_supply, reserve_data.d_supply - 1_0000000);
+            let blnd_reserve = pool_state.load_reserve(&e, &blnd, false);
+            assert_eq!(blnd_reserve.d_supply, reserve_data.d_supply - 20_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_close_position_over_max_sold() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (usdc, _) = testutils::create_token_contract(&e, &bombadil);
+        let (blnd, _) = testutils::create_token_contract(&e, &bombadil);
+        let (amm, _) = create_comet_lp_pool(&e, &bombadil, &blnd, &usdc);
+
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &usdc, &reserve_config, &reserve_data);
+        testutils::create_reserve(&e, &pool, &blnd, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 20_0000000)],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_amm(&e, &amm);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let mut pool = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            close_position(&e, &mut pool, &mut user, &usdc, 1_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1227)")]
+    fn test_close_position_requires_amm() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (usdc, _) = testutils::create_token_contract(&e, &bombadil);
+        let (blnd, _) = testutils::create_token_contract(&e, &bombadil);
+
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &usdc, &reserve_config, &reserve_data);
+        testutils::create_reserve(&e, &pool, &blnd, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 20_0000000)],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let mut pool = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            close_position(&e, &mut pool, &mut user, &usdc, 5_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_close_position_with_fixed_loan() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (usdc, _) = testutils::create_token_contract(&e, &bombadil);
+        let (blnd, _) = testutils::create_token_contract(&e, &bombadil);
+        let (amm, _) = create_comet_lp_pool(&e, &bombadil, &blnd, &usdc);
+
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &usdc, &reserve_config, &reserve_data);
+        testutils::create_reserve(&e, &pool, &blnd, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 1_0000000)],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e],
+        };
+        let fixed_loan = FixedLoan {
+            d_tokens: 1_0000000,
+            principal: 1_0000000,
+            rate: 0_0500000,
+            start: 0,
+            maturity: 30 * 24 * 60 * 60,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_amm(&e, &amm);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_fixed_loans(&e, &samwise, &map![&e, (0, fixed_loan)]);
+
+            let mut pool = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            close_position(&e, &mut pool, &mut user, &usdc, 5_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_close_position_with_stable_debt() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (usdc, _) = testutils::create_token_contract(&e, &bombadil);
+        let (blnd, _) = testutils::create_token_contract(&e, &bombadil);
+        let (amm, _) = create_comet_lp_pool(&e, &bombadil, &blnd, &usdc);
+
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &usdc, &reserve_config, &reserve_data);
+        testutils::create_reserve(&e, &pool, &blnd, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 1_0000000)],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e],
+        };
+        let stable_debt = StableDebt {
+            d_tokens: 1_0000000,
+            principal: 1_0000000,
+            rate: 0_0500000,
+            last_time: 0,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_amm(&e, &amm);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+            storage::set_stable_debts(&e, &samwise, &map![&e, (0, stable_debt)]);
+
+            let mut pool = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            close_position(&e, &mut pool, &mut user, &usdc, 5_0000000);
+        });
+    }
+}
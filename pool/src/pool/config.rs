@@ -1,11 +1,24 @@
 use crate::{
-    constants::{SCALAR_7, SCALAR_9, SECONDS_PER_WEEK},
+    constants::{
+        MAX_AUCTION_CURVE_BLOCKS, MAX_C_FACTOR_RAMP, MAX_LIQUIDATION_GRACE,
+        MAX_WITHDRAW_QUEUE_DELAY, MIN_AUCTION_CURVE_BLOCKS, SCALAR_7, SCALAR_9, SECONDS_PER_WEEK,
+        UPGRADE_TIMELOCK,
+    },
+    dependencies::{BackstopClient, PartnerPoolClient},
     errors::PoolError,
     storage::{
-        self, has_queued_reserve_set, PoolConfig, QueuedReserveInit, ReserveConfig, ReserveData,
+        self, has_queued_reserve_set, AuctionCurve, ExchangeRateConfig, ExchangeRateUpdate,
+        PartnerCollateral, PoolConfig, PoolMetadata, QueuedAmm, QueuedExchangeRate,
+        QueuedPoolMetadata, QueuedReserveInit, QueuedUpgrade, ReserveBond, ReserveConfig,
+        ReserveData, WithdrawQueueConfig,
     },
+    validator::require_valid_risk_params,
+};
+use sep_41_token::TokenClient;
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    map, panic_with_error, vec, Address, BytesN, Env, IntoVal, Map, String, Symbol, Val, Vec,
 };
-use soroban_sdk::{panic_with_error, Address, Env, String};
 
 use super::pool::Pool;
 
@@ -22,6 +35,7 @@ pub fn execute_initialize(
     max_positions: &u32,
     backstop_address: &Address,
     blnd_id: &Address,
+    pause_registry: &Address,
 ) {
     if storage::get_is_init(e) {
         panic_with_error!(e, PoolError::AlreadyInitializedError);
@@ -38,7 +52,14 @@ pub fn execute_initialize(
     }
 
     storage::set_admin(e, admin);
-    storage::set_name(e, name);
+    storage::set_metadata(
+        e,
+        &PoolMetadata {
+            name: name.clone(),
+            icon_hash: BytesN::from_array(e, &[0; 32]),
+            description_uri: String::from_str(e, ""),
+        },
+    );
     storage::set_backstop(e, backstop_address);
     storage::set_pool_config(
         e,
@@ -50,6 +71,7 @@ pub fn execute_initialize(
         },
     );
     storage::set_blnd_token(e, blnd_id);
+    storage::set_pause_registry(e, pause_registry);
 
     storage::set_is_init(e);
 }
@@ -66,12 +88,432 @@ pub fn execute_update_pool(e: &Env, backstop_take_rate: u32, max_positions: u32)
     storage::set_pool_config(e, &pool_config);
 }
 
+/// Update the minimum percent of an auction that must be filled at once
+///
+/// ### Panics
+/// If the minimum fill percent is over 100
+pub fn execute_set_min_fill_pct(e: &Env, min_fill_pct: u64) {
+    if min_fill_pct > 100 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_min_fill_pct(e, &min_fill_pct);
+}
+
+/// Update the Dutch auction curve used to scale auctions when they are filled. The curve only
+/// applies to auctions created afterwards, as each auction keeps the curve it was created with.
+///
+/// ### Panics
+/// If the lot ramp or bid decay is outside of [MIN_AUCTION_CURVE_BLOCKS, MAX_AUCTION_CURVE_BLOCKS]
+pub fn execute_set_auction_curve(e: &Env, curve: &AuctionCurve) {
+    let valid_range = MIN_AUCTION_CURVE_BLOCKS..=MAX_AUCTION_CURVE_BLOCKS;
+    if !valid_range.contains(&curve.lot_blocks) || !valid_range.contains(&curve.bid_blocks) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_auction_curve(e, curve);
+}
+
+/// Update the maximum utilization a reserve can be left at by a withdrawal
+///
+/// ### Panics
+/// If the utilization cap is zero or over 100%
+pub fn execute_set_withdraw_util_cap(e: &Env, util_cap: u32) {
+    if util_cap == 0 || util_cap > SCALAR_7 as u32 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_withdraw_util_cap(e, &util_cap);
+}
+
+/// Execute queueing an update to the pool's metadata
+///
+/// ### Panics
+/// If a metadata update is already queued
+pub fn execute_queue_set_metadata(e: &Env, metadata: &PoolMetadata) {
+    if storage::has_queued_metadata(e) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let mut unlock_time = e.ledger().timestamp();
+    // require a timelock if pool status is not setup
+    if storage::get_pool_config(e).status != 6 {
+        unlock_time += SECONDS_PER_WEEK;
+    }
+    storage::set_queued_metadata(
+        e,
+        &QueuedPoolMetadata {
+            new_metadata: metadata.clone(),
+            unlock_time,
+        },
+    );
+}
+
+/// Execute cancelling a queued update to the pool's metadata
+pub fn execute_cancel_queued_set_metadata(e: &Env) {
+    storage::del_queued_metadata(e);
+}
+
+/// Execute queueing an update to the AMM used to sell collateral
+///
+/// ### Panics
+/// If an AMM update is already queued
+pub fn execute_queue_set_amm(e: &Env, amm: &Address) {
+    if storage::has_queued_amm(e) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let mut unlock_time = e.ledger().timestamp();
+    // require a timelock if pool status is not setup
+    if storage::get_pool_config(e).status != 6 {
+        unlock_time += SECONDS_PER_WEEK;
+    }
+    storage::set_queued_amm(
+        e,
+        &QueuedAmm {
+            amm: amm.clone(),
+            unlock_time,
+        },
+    );
+}
+
+/// Execute cancelling a queued update to the AMM used to sell collateral
+pub fn execute_cancel_queued_set_amm(e: &Env) {
+    storage::del_queued_amm(e);
+}
+
+/// Execute a queued update to the AMM used to sell collateral
+///
+/// Returns the new AMM
+///
+/// ### Panics
+/// If no AMM update is queued or it is still locked
+pub fn execute_set_amm(e: &Env) -> Address {
+    if !storage::has_queued_amm(e) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let queued_amm = storage::get_queued_amm(e);
+    if queued_amm.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, PoolError::InitNotUnlocked);
+    }
+    storage::del_queued_amm(e);
+    storage::set_amm(e, &queued_amm.amm);
+    queued_amm.amm
+}
+
+/// Execute a queued update to the pool's metadata
+///
+/// ### Panics
+/// If no metadata update is queued or it is still locked
+pub fn execute_set_metadata(e: &Env) -> PoolMetadata {
+    let queued_metadata = storage::get_queued_metadata(e);
+
+    if queued_metadata.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, PoolError::InitNotUnlocked);
+    }
+
+    storage::del_queued_metadata(e);
+    storage::set_metadata(e, &queued_metadata.new_metadata);
+    queued_metadata.new_metadata
+}
+
+/// Execute queueing an upgrade of the pool's WASM. Unlike other queued updates, upgrades are
+/// timelocked in every pool status, including setup, and for longer than the backstop's maximum
+/// Q4W lock time.
+///
+/// ### Panics
+/// If an upgrade is already queued
+pub fn execute_queue_upgrade(e: &Env, wasm_hash: &BytesN<32>) {
+    if storage::get_queued_upgrade(e).is_some() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_queued_upgrade(
+        e,
+        &QueuedUpgrade {
+            wasm_hash: wasm_hash.clone(),
+            unlock_time: e.ledger().timestamp() + UPGRADE_TIMELOCK,
+        },
+    );
+}
+
+/// Execute cancelling a queued upgrade of the pool's WASM
+pub fn execute_cancel_queued_upgrade(e: &Env) {
+    storage::del_queued_upgrade(e);
+}
+
+/// Execute a queued upgrade of the pool's WASM. The new WASM is used starting with the next
+/// invocation, and `execute_migrate` must then be run by the new WASM. All other state changing
+/// functions are blocked until it is.
+///
+/// Returns the hash of the new WASM
+///
+/// ### Panics
+/// If no upgrade is queued or it is still locked
+pub fn execute_upgrade(e: &Env) -> BytesN<32> {
+    let queued_upgrade = match storage::get_queued_upgrade(e) {
+        Some(queued_upgrade) => queued_upgrade,
+        None => panic_with_error!(e, PoolError::BadRequest),
+    };
+    if queued_upgrade.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, PoolError::InitNotUnlocked);
+    }
+
+    storage::del_queued_upgrade(e);
+    storage::set_migration_pending(e, true);
+    e.deployer()
+        .update_current_contract_wasm(queued_upgrade.wasm_hash.clone());
+    queued_upgrade.wasm_hash
+}
+
+/// Execute the migration of the pool's stored state after an upgrade. Every reserve's config and
+/// data is rewritten in the current storage layout, so no entry is left in an older layout.
+///
+/// ### Panics
+/// If no migration is pending
+pub fn execute_migrate(e: &Env) {
+    if !storage::get_migration_pending(e) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    for asset in storage::get_res_list(e).iter() {
+        let reserve_config = storage::get_res_config(e, &asset);
+        storage::set_res_config(e, &asset, &reserve_config);
+        let reserve_data = storage::get_res_data(e, &asset);
+        storage::set_res_data(e, &asset, &reserve_data);
+    }
+    storage::set_migration_pending(e, false);
+}
+
+/// Execute queueing an update to the exchange rate config of a reserve, or its removal if None
+///
+/// ### Panics
+/// If the asset is not a reserve, an update is already queued, the config is invalid, or the
+/// asset is priced as partner pool collateral
+pub fn execute_queue_set_exchange_rate(
+    e: &Env,
+    asset: &Address,
+    config: &Option<ExchangeRateConfig>,
+) {
+    if !storage::has_res(e, asset) || storage::has_queued_exchange_rate(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    if let Some(config) = config {
+        if config.max_age == 0
+            || config.base_asset == *asset
+            || storage::get_partner_collateral(e, asset).is_some()
+        {
+            panic_with_error!(e, PoolError::BadRequest);
+        }
+    }
+    let mut unlock_time = e.ledger().timestamp();
+    // require a timelock if pool status is not setup
+    if storage::get_pool_config(e).status != 6 {
+        unlock_time += SECONDS_PER_WEEK;
+    }
+    let update = match config {
+        Some(config) => ExchangeRateUpdate::Set(config.clone()),
+        None => ExchangeRateUpdate::Remove,
+    };
+    storage::set_queued_exchange_rate(
+        e,
+        asset,
+        &QueuedExchangeRate {
+            update,
+            unlock_time,
+        },
+    );
+}
+
+/// Execute cancelling a queued update to the exchange rate config of a reserve
+pub fn execute_cancel_queued_set_exchange_rate(e: &Env, asset: &Address) {
+    storage::del_queued_exchange_rate(e, asset);
+}
+
+/// Execute a queued update to the exchange rate config of a reserve
+///
+/// ### Panics
+/// If no update is queued or it is still locked
+pub fn execute_set_exchange_rate(e: &Env, asset: &Address) -> Option<ExchangeRateConfig> {
+    let queued = storage::get_queued_exchange_rate(e, asset);
+    if queued.unlock_time > e.ledger().timestamp() {
+        panic_with_error!(e, PoolError::InitNotUnlocked);
+    }
+    storage::del_queued_exchange_rate(e, asset);
+    // the price source changed, so the last price is no longer a valid reference
+    storage::del_last_price(e, asset);
+    match queued.update {
+        ExchangeRateUpdate::Set(config) => {
+            storage::set_exchange_rate_config(e, asset, &config);
+            Some(config)
+        }
+        ExchangeRateUpdate::Remove => {
+            storage::del_exchange_rate_config(e, asset);
+            None
+        }
+    }
+}
+
+/// Add or remove a pool from the partner pool whitelist. Only bTokens minted by whitelisted
+/// partner pools can be priced as partner collateral.
+///
+/// ### Panics
+/// If the pool is this pool, the pool already whitelists this pool, or the pool is being removed
+/// while a reserve is still priced from it
+pub fn execute_set_partner_pool(e: &Env, pool: &Address, enabled: bool) {
+    if enabled {
+        // two pools accepting each other's bTokens could count the same deposit as collateral twice
+        if *pool == e.current_contract_address()
+            || PartnerPoolClient::new(e, pool).is_partner_pool(&e.current_contract_address())
+        {
+            panic_with_error!(e, PoolError::BadRequest);
+        }
+        storage::set_partner_pool(e, pool);
+    } else {
+        for asset in storage::get_res_list(e).iter() {
+            if let Some(config) = storage::get_partner_collateral(e, &asset) {
+                if config.partner_pool == *pool {
+                    panic_with_error!(e, PoolError::BadRequest);
+                }
+            }
+        }
+        storage::del_partner_pool(e, pool);
+    }
+}
+
+/// Update the partner pool bToken config for a reserve, or remove it if None
+///
+/// ### Panics
+/// If the asset is not a reserve, the asset has an exchange rate config, the partner pool is
+/// not whitelisted, or the base asset is the asset itself or another partner pool bToken
+pub fn execute_set_partner_collateral(
+    e: &Env,
+    asset: &Address,
+    config: &Option<PartnerCollateral>,
+) {
+    if !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    match config {
+        Some(config) => {
+            if storage::get_exchange_rate_config(e, asset).is_some()
+                || !storage::is_partner_pool(e, &config.partner_pool)
+                || config.base_asset == *asset
+                || storage::get_partner_collateral(e, &config.base_asset).is_some()
+            {
+                panic_with_error!(e, PoolError::BadRequest);
+            }
+            storage::set_partner_collateral(e, asset, config);
+        }
+        None => storage::del_partner_collateral(e, asset),
+    }
+    // the price source changed, so the last price is no longer a valid reference
+    storage::del_last_price(e, asset);
+}
+
+/// Update the withdrawal queue config for a reserve, or remove it if None
+///
+/// ### Panics
+/// If the asset is not a reserve, the threshold is not in (0, 1], or the delay is not in
+/// (0, MAX_WITHDRAW_QUEUE_DELAY]
+pub fn execute_set_withdraw_queue(e: &Env, asset: &Address, config: &Option<WithdrawQueueConfig>) {
+    if !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    match config {
+        Some(config) => {
+            if config.threshold == 0
+                || config.threshold > SCALAR_7 as u32
+                || config.delay == 0
+                || config.delay > MAX_WITHDRAW_QUEUE_DELAY
+            {
+                panic_with_error!(e, PoolError::BadRequest);
+            }
+            storage::set_withdraw_queue_config(e, asset, config);
+        }
+        None => storage::del_withdraw_queue_config(e, asset),
+    }
+}
+
+/// Update the peg band for a stablecoin reserve, or remove it if None
+///
+/// ### Panics
+/// If the asset is not a reserve or the band is not in [0, 1)
+pub fn execute_set_peg_band(e: &Env, asset: &Address, band: &Option<u32>) {
+    if !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    match band {
+        Some(band) => {
+            if *band >= SCALAR_7 as u32 {
+                panic_with_error!(e, PoolError::BadRequest);
+            }
+            storage::set_peg_band(e, asset, *band);
+        }
+        None => storage::del_peg_band(e, asset),
+    }
+}
+
+/// Update the percent an asset's oracle price can move between pool interactions before its
+/// reserve is restricted
+///
+/// ### Panics
+/// If the threshold is over 100%
+pub fn execute_set_price_breaker(e: &Env, threshold: u32) {
+    if threshold > SCALAR_7 as u32 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_price_breaker(e, &threshold);
+}
+
+/// Update the percent a reserve's bToken or dToken supply can change within a ledger before the
+/// change is rejected
+///
+/// ### Panics
+/// If the threshold is over 100%
+pub fn execute_set_supply_breaker(e: &Env, threshold: u32) {
+    if threshold > SCALAR_7 as u32 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_supply_breaker(e, &threshold);
+}
+
+/// Update the time in seconds liquidations are blocked after the pool returns to an active status
+/// from on-ice or frozen
+///
+/// ### Panics
+/// If the grace period is over MAX_LIQUIDATION_GRACE
+pub fn execute_set_liquidation_grace(e: &Env, grace: u64) {
+    if grace > MAX_LIQUIDATION_GRACE {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_liquidation_grace(e, &grace);
+}
+
+/// Lift the price breaker restriction on a reserve. The current oracle price becomes the
+/// reference for future price moves.
+///
+/// ### Panics
+/// If the reserve is not restricted
+pub fn execute_clear_reserve_restriction(e: &Env, asset: &Address) {
+    if !storage::get_reserve_restricted(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_reserve_restricted(e, asset, false);
+    storage::del_last_price(e, asset);
+}
+
+/// Update the time a reduction to a reserve's collateral factor is ramped in over
+///
+/// ### Panics
+/// If the ramp period is over MAX_C_FACTOR_RAMP
+pub fn execute_set_c_factor_ramp(e: &Env, ramp_period: u64) {
+    if ramp_period > MAX_C_FACTOR_RAMP {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_c_factor_ramp(e, &ramp_period);
+}
+
 /// Execute a queueing a reserve initialization for the pool
 pub fn execute_queue_set_reserve(e: &Env, asset: &Address, metadata: &ReserveConfig) {
     if has_queued_reserve_set(e, asset) {
         panic_with_error!(&e, PoolError::BadRequest)
     }
     require_valid_reserve_metadata(e, metadata);
+    require_valid_reserve_token(e, asset, metadata);
     let mut unlock_time = e.ledger().timestamp();
     // require a timelock if pool status is not setup
     if storage::get_pool_config(e).status != 6 {
@@ -85,11 +527,114 @@ pub fn execute_queue_set_reserve(e: &Env, asset: &Address, metadata: &ReserveCon
         },
         &asset,
     );
+    let mut queued_list = storage::get_queued_reserve_list(e);
+    if !queued_list.contains(asset) {
+        queued_list.push_back(asset.clone());
+        storage::set_queued_reserve_list(e, &queued_list);
+    }
 }
 
-/// Execute cancelling a queueing a reserve initialization for the pool
-pub fn execute_cancel_queued_set_reserve(e: &Env, asset: &Address) {
+/// Execute cancelling a queueing a reserve initialization for the pool. If the reserve was
+/// proposed with a bond, the bond is slashed to the pool's backstop.
+///
+/// Returns the slashed bond, if one was posted
+pub fn execute_cancel_queued_set_reserve(e: &Env, asset: &Address) -> Option<ReserveBond> {
     storage::del_queued_reserve_set(&e, &asset);
+    remove_from_queued_reserve_list(e, asset);
+
+    let bond = storage::get_reserve_bond(e, asset);
+    if let Some(bond) = &bond {
+        storage::del_reserve_bond(e, asset);
+        let pool_address = e.current_contract_address();
+        let backstop = storage::get_backstop(e);
+        let backstop_client = BackstopClient::new(e, &backstop);
+        // the backstop pulls the bond from the pool during the donation
+        let args: Vec<Val> = vec![
+            e,
+            pool_address.into_val(e),
+            backstop.into_val(e),
+            bond.amount.into_val(e),
+        ];
+        e.authorize_as_current_contract(vec![
+            e,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: backstop_client.backstop_token(),
+                    fn_name: Symbol::new(e, "transfer"),
+                    args,
+                },
+                sub_invocations: vec![e],
+            }),
+        ]);
+        backstop_client.donate(&pool_address, &pool_address, &bond.amount);
+    }
+    bond
+}
+
+/// Execute proposing a new reserve for the pool. The proposer bonds backstop tokens to the pool,
+/// which are refunded once the reserve is set and slashed to the backstop if the proposal is
+/// cancelled.
+///
+/// ### Arguments
+/// * `from` - The address proposing the reserve and posting the bond
+/// * `asset` - The underlying asset to add as a reserve
+/// * `metadata` - The ReserveConfig for the reserve
+///
+/// ### Panics
+/// If reserve proposals are disabled, the asset is already a reserve or queued, or the metadata
+/// is invalid
+pub fn execute_propose_reserve(
+    e: &Env,
+    from: &Address,
+    asset: &Address,
+    metadata: &ReserveConfig,
+) -> ReserveBond {
+    let amount = storage::get_listing_bond(e);
+    if amount == 0 || storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    execute_queue_set_reserve(e, asset, metadata);
+
+    let backstop_token = BackstopClient::new(e, &storage::get_backstop(e)).backstop_token();
+    TokenClient::new(e, &backstop_token).transfer(from, &e.current_contract_address(), &amount);
+    let bond = ReserveBond {
+        proposer: from.clone(),
+        amount,
+    };
+    storage::set_reserve_bond(e, asset, &bond);
+    bond
+}
+
+/// Update the amount of backstop tokens that must be bonded to propose a new reserve
+///
+/// ### Panics
+/// If the amount is negative
+pub fn execute_set_listing_bond(e: &Env, amount: i128) {
+    if amount < 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    storage::set_listing_bond(e, &amount);
+}
+
+/// Fetch every pending queued reserve set, keyed by asset. Queued sets that expired before
+/// being executed are not included.
+pub fn get_queued_reserve_changes(e: &Env) -> Map<Address, QueuedReserveInit> {
+    let mut changes = map![e];
+    for asset in storage::get_queued_reserve_list(e).iter() {
+        if storage::has_queued_reserve_set(e, &asset) {
+            changes.set(asset.clone(), storage::get_queued_reserve_set(e, &asset));
+        }
+    }
+    changes
+}
+
+/// Remove an asset from the list of queued reserve sets, if it is in it
+fn remove_from_queued_reserve_list(e: &Env, asset: &Address) {
+    let mut queued_list = storage::get_queued_reserve_list(e);
+    if let Some(index) = queued_list.first_index_of(asset) {
+        queued_list.remove(index);
+        storage::set_queued_reserve_list(e, &queued_list);
+    }
 }
 
 /// Execute a queued reserve initialization for the pool
@@ -100,11 +645,27 @@ pub fn execute_set_reserve(e: &Env, asset: &Address) -> u32 {
         panic_with_error!(e, PoolError::InitNotUnlocked);
     }
 
+    // risk bounds are checked again in case they changed while the reserve was queued
+    require_valid_risk_params(e, &queued_init.new_config);
+
     // remove queued reserve
     storage::del_queued_reserve_set(e, asset);
+    remove_from_queued_reserve_list(e, asset);
 
     // initialize reserve
-    initialize_reserve(e, asset, &queued_init.new_config)
+    let index = initialize_reserve(e, asset, &queued_init.new_config);
+
+    // refund the bond of a proposed reserve
+    if let Some(bond) = storage::get_reserve_bond(e, asset) {
+        storage::del_reserve_bond(e, asset);
+        let backstop_token = BackstopClient::new(e, &storage::get_backstop(e)).backstop_token();
+        TokenClient::new(e, &backstop_token).transfer(
+            &e.current_contract_address(),
+            &bond.proposer,
+            &bond.amount,
+        );
+    }
+    index
 }
 
 /// sets reserve data for the pool
@@ -131,6 +692,17 @@ fn initialize_reserve(e: &Env, asset: &Address, config: &ReserveConfig) -> u32 {
         {
             reserve.ir_mod = SCALAR_9;
         }
+        // ramp in reductions to the collateral factor from the one currently in effect
+        let ramp_period = storage::get_c_factor_ramp(e);
+        if config.c_factor < reserve.c_factor && ramp_period > 0 {
+            reserve.ramp_c_factor = reserve.c_factor;
+            reserve.ramp_start = e.ledger().timestamp();
+            reserve.ramp_end = e.ledger().timestamp() + ramp_period;
+        } else {
+            reserve.ramp_c_factor = 0;
+            reserve.ramp_start = 0;
+            reserve.ramp_end = 0;
+        }
         reserve.store(e);
     } else {
         index = storage::push_res_list(e, asset);
@@ -138,10 +710,17 @@ fn initialize_reserve(e: &Env, asset: &Address, config: &ReserveConfig) -> u32 {
             b_rate: SCALAR_9,
             d_rate: SCALAR_9,
             ir_mod: SCALAR_9,
+            util_avg: 0,
             d_supply: 0,
             b_supply: 0,
+            c_supply: 0,
             last_time: e.ledger().timestamp(),
             backstop_credit: 0,
+            interest_paid: 0,
+            interest_earned: 0,
+            ramp_c_factor: 0,
+            ramp_start: 0,
+            ramp_end: 0,
         };
         storage::set_res_data(e, asset, &init_data);
     }
@@ -158,6 +737,9 @@ fn initialize_reserve(e: &Env, asset: &Address, config: &ReserveConfig) -> u32 {
         r_two: config.r_two,
         r_three: config.r_three,
         reactivity: config.reactivity,
+        fee_on_transfer: config.fee_on_transfer,
+        min_first_supply: 0,
+        locked_b_tokens: 0,
     };
     storage::set_res_config(e, asset, &reserve_config);
 
@@ -167,23 +749,54 @@ fn initialize_reserve(e: &Env, asset: &Address, config: &ReserveConfig) -> u32 {
 #[allow(clippy::zero_prefixed_literal)]
 fn require_valid_reserve_metadata(e: &Env, metadata: &ReserveConfig) {
     const SCALAR_7_U32: u32 = SCALAR_7 as u32;
+    require_valid_risk_params(e, metadata);
     if metadata.decimals > 18
-        || metadata.c_factor > SCALAR_7_U32
-        || metadata.l_factor > SCALAR_7_U32
-        || metadata.util > 0_9500000
         || (metadata.max_util > SCALAR_7_U32 || metadata.max_util <= metadata.util)
         || metadata.r_base >= 1_0000000
         || metadata.r_base < 0_0001000
         || (metadata.r_one > metadata.r_two || metadata.r_two > metadata.r_three)
-        || (metadata.reactivity > 0_0001000)
+        || metadata.min_first_supply < 0
+        || metadata.min_first_supply >= 1 << 96
+        || (metadata.locked_b_tokens > 0
+            && metadata.min_first_supply <= metadata.locked_b_tokens as i128)
     {
         panic_with_error!(e, PoolError::InvalidReserveMetadata);
     }
 }
 
+/// Require that the reserve asset implements the SEP-41 functions the pool relies on
+/// and that its decimals match the reserve metadata
+///
+/// The probe cannot detect a fee on transfer, as the pool holds none of the asset to transfer.
+/// Instead, every transfer into the pool is verified when it is received, and a shortfall is
+/// rejected unless the reserve is flagged as charging a fee on transfer.
+///
+/// ### Panics
+/// If the asset does not respond to `decimals`, `balance`, or `transfer`, or if the decimals do not match
+fn require_valid_reserve_token(e: &Env, asset: &Address, metadata: &ReserveConfig) {
+    let token_client = TokenClient::new(e, asset);
+    let pool_address = e.current_contract_address();
+    match token_client.try_decimals() {
+        Ok(Ok(decimals)) if decimals == metadata.decimals => {}
+        _ => panic_with_error!(e, PoolError::InvalidReserveToken),
+    }
+    if !matches!(token_client.try_balance(&pool_address), Ok(Ok(_))) {
+        panic_with_error!(e, PoolError::InvalidReserveToken);
+    }
+    if !matches!(
+        token_client.try_transfer(&pool_address, &pool_address, &0),
+        Ok(Ok(_))
+    ) {
+        panic_with_error!(e, PoolError::InvalidReserveToken);
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::storage::QueuedReserveInit;
+    use crate::storage::{
+        LegacyReserveConfig, LegacyReserveData, PackedReserveConfig, PackedReserveData,
+        PoolDataKey, QueuedReserveInit,
+    };
     use crate::testutils;
 
     use super::*;
@@ -201,6 +814,7 @@ mod tests {
         let max_positions = 2;
         let backstop_address = Address::generate(&e);
         let blnd_id = Address::generate(&e);
+        let pause_registry = Address::generate(&e);
 
         e.as_contract(&pool, || {
             execute_initialize(
@@ -212,6 +826,7 @@ mod tests {
                 &max_positions,
                 &backstop_address,
                 &blnd_id,
+                &pause_registry,
             );
 
             assert_eq!(storage::get_admin(&e), admin);
@@ -221,6 +836,11 @@ mod tests {
             assert_eq!(pool_config.status, 6);
             assert_eq!(storage::get_backstop(&e), backstop_address);
             assert_eq!(storage::get_blnd_token(&e), blnd_id);
+            assert_eq!(storage::get_pause_registry(&e), pause_registry);
+            let metadata = storage::get_metadata(&e);
+            assert_eq!(metadata.name, name);
+            assert_eq!(metadata.icon_hash, BytesN::from_array(&e, &[0; 32]));
+            assert_eq!(metadata.description_uri, String::from_str(&e, ""));
         });
     }
 
@@ -237,6 +857,7 @@ mod tests {
         let max_positions = 3;
         let backstop_address = Address::generate(&e);
         let blnd_id = Address::generate(&e);
+        let pause_registry = Address::generate(&e);
 
         e.as_contract(&pool, || {
             execute_initialize(
@@ -248,6 +869,7 @@ mod tests {
                 &max_positions,
                 &backstop_address,
                 &blnd_id,
+                &pause_registry,
             );
 
             execute_initialize(
@@ -259,6 +881,7 @@ mod tests {
                 &max_positions,
                 &backstop_address,
                 &blnd_id,
+                &pause_registry,
             );
         });
     }
@@ -276,6 +899,7 @@ mod tests {
         let max_positions = 3;
         let backstop_address = Address::generate(&e);
         let blnd_id = Address::generate(&e);
+        let pause_registry = Address::generate(&e);
 
         e.as_contract(&pool, || {
             execute_initialize(
@@ -287,6 +911,7 @@ mod tests {
                 &max_positions,
                 &backstop_address,
                 &blnd_id,
+                &pause_registry,
             );
         });
     }
@@ -304,6 +929,7 @@ mod tests {
         let max_positions = 1;
         let backstop_address = Address::generate(&e);
         let blnd_id = Address::generate(&e);
+        let pause_registry = Address::generate(&e);
 
         e.as_contract(&pool, || {
             execute_initialize(
@@ -315,6 +941,7 @@ mod tests {
                 &max_positions,
                 &backstop_address,
                 &blnd_id,
+                &pause_registry,
             );
         });
     }
@@ -363,54 +990,1190 @@ mod tests {
     }
 
     #[test]
-    fn test_queue_set_reserve_status_6() {
+    fn test_execute_set_min_fill_pct() {
         let e = Env::default();
         let pool = testutils::create_pool(&e);
-        let bombadil = Address::generate(&e);
 
-        let (asset_id_0, _) = testutils::create_token_contract(&e, &bombadil);
-
-        let metadata = ReserveConfig {
-            index: 0,
-            decimals: 7,
-            c_factor: 0_7500000,
-            l_factor: 0_7500000,
-            util: 0_5000000,
-            max_util: 0_9500000,
-            r_base: 0_0100000,
-            r_one: 0_0500000,
-            r_two: 0_5000000,
-            r_three: 1_5000000,
-            reactivity: 100,
-        };
-        let pool_config = PoolConfig {
-            oracle: Address::generate(&e),
-            bstop_rate: 0_1000000,
-            status: 6,
-            max_positions: 2,
-        };
         e.as_contract(&pool, || {
-            storage::set_pool_config(&e, &pool_config);
-            execute_queue_set_reserve(&e, &asset_id_0, &metadata);
-            let queued_res = storage::get_queued_reserve_set(&e, &asset_id_0);
-            let res_config_0 = queued_res.new_config;
-            assert_eq!(res_config_0.decimals, metadata.decimals);
-            assert_eq!(res_config_0.c_factor, metadata.c_factor);
-            assert_eq!(res_config_0.l_factor, metadata.l_factor);
-            assert_eq!(res_config_0.util, metadata.util);
-            assert_eq!(res_config_0.r_base, metadata.r_base);
-            assert_eq!(res_config_0.r_one, metadata.r_one);
-            assert_eq!(res_config_0.r_one, metadata.r_one);
-            assert_eq!(res_config_0.r_two, metadata.r_two);
-            assert_eq!(res_config_0.r_three, metadata.r_three);
-            assert_eq!(res_config_0.reactivity, metadata.reactivity);
-            assert_eq!(res_config_0.index, 0);
-            assert_eq!(queued_res.unlock_time, e.ledger().timestamp());
+            assert_eq!(storage::get_min_fill_pct(&e), 0);
+
+            execute_set_min_fill_pct(&e, 5);
+            assert_eq!(storage::get_min_fill_pct(&e), 5);
         });
     }
 
     #[test]
-    fn test_queue_set_reserve() {
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_min_fill_pct_validates() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_set_min_fill_pct(&e, 101);
+        });
+    }
+
+    #[test]
+    fn test_execute_set_auction_curve() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            assert_eq!(
+                storage::get_auction_curve(&e),
+                AuctionCurve {
+                    lot_blocks: 200,
+                    bid_blocks: 200,
+                }
+            );
+
+            let curve = AuctionCurve {
+                lot_blocks: MIN_AUCTION_CURVE_BLOCKS,
+                bid_blocks: MAX_AUCTION_CURVE_BLOCKS,
+            };
+            execute_set_auction_curve(&e, &curve);
+            assert_eq!(storage::get_auction_curve(&e), curve);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_auction_curve_validates_lot_blocks() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_set_auction_curve(
+                &e,
+                &AuctionCurve {
+                    lot_blocks: MIN_AUCTION_CURVE_BLOCKS - 1,
+                    bid_blocks: 200,
+                },
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_auction_curve_validates_bid_blocks() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_set_auction_curve(
+                &e,
+                &AuctionCurve {
+                    lot_blocks: 200,
+                    bid_blocks: MAX_AUCTION_CURVE_BLOCKS + 1,
+                },
+            );
+        });
+    }
+
+    #[test]
+    fn test_execute_set_withdraw_util_cap() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            assert_eq!(storage::get_withdraw_util_cap(&e), 1_0000000);
+
+            execute_set_withdraw_util_cap(&e, 0_9500000);
+            assert_eq!(storage::get_withdraw_util_cap(&e), 0_9500000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_withdraw_util_cap_validates() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_set_withdraw_util_cap(&e, 1_0000001);
+        });
+    }
+
+    #[test]
+    fn test_execute_set_c_factor_ramp() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            assert_eq!(storage::get_c_factor_ramp(&e), SECONDS_PER_WEEK);
+
+            execute_set_c_factor_ramp(&e, 3 * 24 * 60 * 60);
+            assert_eq!(storage::get_c_factor_ramp(&e), 3 * 24 * 60 * 60);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_c_factor_ramp_validates() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_set_c_factor_ramp(&e, MAX_C_FACTOR_RAMP + 1);
+        });
+    }
+
+    #[test]
+    fn test_execute_set_withdraw_queue() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let config = WithdrawQueueConfig {
+            threshold: 0_2000000,
+            delay: 6 * 60 * 60,
+        };
+        e.as_contract(&pool, || {
+            assert_eq!(storage::get_withdraw_queue_config(&e, &underlying), None);
+
+            execute_set_withdraw_queue(&e, &underlying, &Some(config.clone()));
+            assert_eq!(
+                storage::get_withdraw_queue_config(&e, &underlying),
+                Some(config)
+            );
+
+            execute_set_withdraw_queue(&e, &underlying, &None);
+            assert_eq!(storage::get_withdraw_queue_config(&e, &underlying), None);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_withdraw_queue_validates_delay() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let config = WithdrawQueueConfig {
+            threshold: 0_2000000,
+            delay: MAX_WITHDRAW_QUEUE_DELAY + 1,
+        };
+        e.as_contract(&pool, || {
+            execute_set_withdraw_queue(&e, &underlying, &Some(config));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_withdraw_queue_requires_reserve() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        let config = WithdrawQueueConfig {
+            threshold: 0_2000000,
+            delay: 6 * 60 * 60,
+        };
+        e.as_contract(&pool, || {
+            execute_set_withdraw_queue(&e, &Address::generate(&e), &Some(config));
+        });
+    }
+
+    #[test]
+    fn test_execute_set_peg_band() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            assert_eq!(storage::get_peg_band(&e, &underlying), None);
+
+            execute_set_peg_band(&e, &underlying, &Some(0_0200000));
+            assert_eq!(storage::get_peg_band(&e, &underlying), Some(0_0200000));
+
+            execute_set_peg_band(&e, &underlying, &None);
+            assert_eq!(storage::get_peg_band(&e, &underlying), None);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_peg_band_validates() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            execute_set_peg_band(&e, &underlying, &Some(1_0000000));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_peg_band_requires_reserve() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_set_peg_band(&e, &Address::generate(&e), &Some(0_0200000));
+        });
+    }
+
+    #[test]
+    fn test_execute_set_price_breaker() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            assert_eq!(storage::get_price_breaker(&e), 0);
+
+            execute_set_price_breaker(&e, 0_2500000);
+            assert_eq!(storage::get_price_breaker(&e), 0_2500000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_price_breaker_validates() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_set_price_breaker(&e, 1_0000001);
+        });
+    }
+
+    #[test]
+    fn test_execute_set_supply_breaker() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            assert_eq!(storage::get_supply_breaker(&e), 0);
+
+            execute_set_supply_breaker(&e, 0_2000000);
+            assert_eq!(storage::get_supply_breaker(&e), 0_2000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_supply_breaker_validates() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_set_supply_breaker(&e, 1_0000001);
+        });
+    }
+
+    #[test]
+    fn test_execute_set_liquidation_grace() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            assert_eq!(storage::get_liquidation_grace(&e), 0);
+
+            execute_set_liquidation_grace(&e, 6 * 60 * 60);
+            assert_eq!(storage::get_liquidation_grace(&e), 6 * 60 * 60);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_liquidation_grace_validates() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_set_liquidation_grace(&e, MAX_LIQUIDATION_GRACE + 1);
+        });
+    }
+
+    #[test]
+    fn test_execute_clear_reserve_restriction() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let asset = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_reserve_restricted(&e, &asset, true);
+            storage::set_last_price(&e, &asset, &1_0000000);
+
+            execute_clear_reserve_restriction(&e, &asset);
+            assert!(!storage::get_reserve_restricted(&e, &asset));
+            assert_eq!(storage::get_last_price(&e, &asset), None);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_clear_reserve_restriction_requires_restricted() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let asset = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            execute_clear_reserve_restriction(&e, &asset);
+        });
+    }
+
+    #[test]
+    fn test_queue_set_metadata() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        let metadata = PoolMetadata {
+            name: String::from_str(&e, "new_name"),
+            icon_hash: BytesN::from_array(&e, &[1; 32]),
+            description_uri: String::from_str(&e, "ipfs://description"),
+        };
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_metadata(&e, &metadata);
+            let queued_metadata = storage::get_queued_metadata(&e);
+            assert_eq!(queued_metadata.new_metadata, metadata);
+            assert_eq!(
+                queued_metadata.unlock_time,
+                e.ledger().timestamp() + SECONDS_PER_WEEK
+            );
+        });
+    }
+
+    #[test]
+    fn test_queue_set_metadata_status_6() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        let metadata = PoolMetadata {
+            name: String::from_str(&e, "new_name"),
+            icon_hash: BytesN::from_array(&e, &[1; 32]),
+            description_uri: String::from_str(&e, "ipfs://description"),
+        };
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 6,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_metadata(&e, &metadata);
+            let queued_metadata = storage::get_queued_metadata(&e);
+            assert_eq!(queued_metadata.unlock_time, e.ledger().timestamp());
+
+            // the update can be applied immediately while the pool is being setup
+            let new_metadata = execute_set_metadata(&e);
+            assert_eq!(new_metadata, metadata);
+            assert_eq!(storage::get_metadata(&e), metadata);
+            assert!(!storage::has_queued_metadata(&e));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_queue_set_metadata_duplicate() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        let metadata = PoolMetadata {
+            name: String::from_str(&e, "new_name"),
+            icon_hash: BytesN::from_array(&e, &[1; 32]),
+            description_uri: String::from_str(&e, "ipfs://description"),
+        };
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_metadata(&e, &metadata);
+            execute_queue_set_metadata(&e, &metadata);
+        });
+    }
+
+    #[test]
+    fn test_execute_cancel_queued_set_metadata() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        let metadata = PoolMetadata {
+            name: String::from_str(&e, "new_name"),
+            icon_hash: BytesN::from_array(&e, &[1; 32]),
+            description_uri: String::from_str(&e, "ipfs://description"),
+        };
+        e.as_contract(&pool, || {
+            storage::set_queued_metadata(
+                &e,
+                &QueuedPoolMetadata {
+                    new_metadata: metadata.clone(),
+                    unlock_time: e.ledger().timestamp(),
+                },
+            );
+            execute_cancel_queued_set_metadata(&e);
+            assert!(!storage::has_queued_metadata(&e));
+        });
+    }
+
+    #[test]
+    fn test_execute_set_metadata() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1_000_000,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let metadata = PoolMetadata {
+            name: String::from_str(&e, "new_name"),
+            icon_hash: BytesN::from_array(&e, &[1; 32]),
+            description_uri: String::from_str(&e, "ipfs://description"),
+        };
+        e.as_contract(&pool, || {
+            storage::set_queued_metadata(
+                &e,
+                &QueuedPoolMetadata {
+                    new_metadata: metadata.clone(),
+                    unlock_time: 1_000_000,
+                },
+            );
+            let new_metadata = execute_set_metadata(&e);
+            assert_eq!(new_metadata, metadata);
+            assert_eq!(storage::get_metadata(&e), metadata);
+            assert!(!storage::has_queued_metadata(&e));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1203)")]
+    fn test_execute_set_metadata_requires_unlock() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        let metadata = PoolMetadata {
+            name: String::from_str(&e, "new_name"),
+            icon_hash: BytesN::from_array(&e, &[1; 32]),
+            description_uri: String::from_str(&e, "ipfs://description"),
+        };
+        e.as_contract(&pool, || {
+            storage::set_queued_metadata(
+                &e,
+                &QueuedPoolMetadata {
+                    new_metadata: metadata.clone(),
+                    unlock_time: e.ledger().timestamp() + 1,
+                },
+            );
+            execute_set_metadata(&e);
+        });
+    }
+
+    #[test]
+    fn test_queue_set_amm() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        let amm = Address::generate(&e);
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_amm(&e, &amm);
+            let queued_amm = storage::get_queued_amm(&e);
+            assert_eq!(queued_amm.amm, amm);
+            assert_eq!(
+                queued_amm.unlock_time,
+                e.ledger().timestamp() + SECONDS_PER_WEEK
+            );
+            assert_eq!(storage::get_amm(&e), None);
+
+            execute_cancel_queued_set_amm(&e);
+            assert!(!storage::has_queued_amm(&e));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_queue_set_amm_duplicate() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_amm(&e, &Address::generate(&e));
+            execute_queue_set_amm(&e, &Address::generate(&e));
+        });
+    }
+
+    #[test]
+    fn test_execute_set_amm() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1_000_000,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let amm = Address::generate(&e);
+        e.as_contract(&pool, || {
+            storage::set_queued_amm(
+                &e,
+                &QueuedAmm {
+                    amm: amm.clone(),
+                    unlock_time: 1_000_000,
+                },
+            );
+            let new_amm = execute_set_amm(&e);
+            assert_eq!(new_amm, amm);
+            assert_eq!(storage::get_amm(&e), Some(amm));
+            assert!(!storage::has_queued_amm(&e));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1203)")]
+    fn test_execute_set_amm_requires_unlock() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_queued_amm(
+                &e,
+                &QueuedAmm {
+                    amm: Address::generate(&e),
+                    unlock_time: e.ledger().timestamp() + 1,
+                },
+            );
+            execute_set_amm(&e);
+        });
+    }
+
+    #[test]
+    fn test_queue_upgrade() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        let wasm_hash = BytesN::from_array(&e, &[1; 32]);
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_upgrade(&e, &wasm_hash);
+            let queued_upgrade = storage::get_queued_upgrade(&e).unwrap();
+            assert_eq!(queued_upgrade.wasm_hash, wasm_hash);
+            assert_eq!(
+                queued_upgrade.unlock_time,
+                e.ledger().timestamp() + UPGRADE_TIMELOCK
+            );
+
+            execute_cancel_queued_upgrade(&e);
+            assert!(storage::get_queued_upgrade(&e).is_none());
+        });
+    }
+
+    #[test]
+    fn test_queue_upgrade_setup_is_timelocked() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        let wasm_hash = BytesN::from_array(&e, &[1; 32]);
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 6,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_upgrade(&e, &wasm_hash);
+            let queued_upgrade = storage::get_queued_upgrade(&e).unwrap();
+            assert_eq!(
+                queued_upgrade.unlock_time,
+                e.ledger().timestamp() + UPGRADE_TIMELOCK
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_queue_upgrade_duplicate() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        let wasm_hash = BytesN::from_array(&e, &[1; 32]);
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_upgrade(&e, &wasm_hash);
+            execute_queue_upgrade(&e, &wasm_hash);
+        });
+    }
+
+    #[test]
+    fn test_execute_upgrade() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1_000_000,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let wasm_hash = e
+            .deployer()
+            .upload_contract_wasm(sep_41_token::testutils::MockTokenWASM);
+        e.as_contract(&pool, || {
+            storage::set_queued_upgrade(
+                &e,
+                &QueuedUpgrade {
+                    wasm_hash: wasm_hash.clone(),
+                    unlock_time: e.ledger().timestamp(),
+                },
+            );
+            let new_wasm_hash = execute_upgrade(&e);
+            assert_eq!(new_wasm_hash, wasm_hash);
+            assert!(storage::get_queued_upgrade(&e).is_none());
+            assert!(storage::get_migration_pending(&e));
+
+            execute_migrate(&e);
+            assert!(!storage::get_migration_pending(&e));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1203)")]
+    fn test_execute_upgrade_requires_unlock() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_queued_upgrade(
+                &e,
+                &QueuedUpgrade {
+                    wasm_hash: BytesN::from_array(&e, &[1; 32]),
+                    unlock_time: e.ledger().timestamp() + 1,
+                },
+            );
+            execute_upgrade(&e);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_upgrade_requires_queue() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_upgrade(&e);
+        });
+    }
+
+    #[test]
+    fn test_execute_migrate_repacks_reserves() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let asset = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            let index = storage::push_res_list(&e, &asset);
+            e.storage()
+                .persistent()
+                .set::<PoolDataKey, LegacyReserveConfig>(
+                    &PoolDataKey::ResConfig(asset.clone()),
+                    &LegacyReserveConfig {
+                        index,
+                        decimals: 7,
+                        c_factor: 0_7500000,
+                        l_factor: 0_7500000,
+                        util: 0_5000000,
+                        max_util: 0_9500000,
+                        r_base: 0_0001000,
+                        r_one: 0_0500000,
+                        r_two: 0_5000000,
+                        r_three: 1_5000000,
+                        reactivity: 0_0000020,
+                    },
+                );
+            e.storage()
+                .persistent()
+                .set::<PoolDataKey, LegacyReserveData>(
+                    &PoolDataKey::ResData(asset.clone()),
+                    &LegacyReserveData {
+                        d_rate: 1_001_000_000,
+                        b_rate: 1_000_500_000,
+                        ir_mod: 1_1000000,
+                        b_supply: 100_0000000,
+                        d_supply: 50_0000000,
+                        backstop_credit: 1234,
+                        last_time: 1_700_000_000,
+                    },
+                );
+            storage::set_migration_pending(&e, true);
+
+            execute_migrate(&e);
+            assert!(!storage::get_migration_pending(&e));
+            let packed_config = e
+                .storage()
+                .persistent()
+                .get::<PoolDataKey, PackedReserveConfig>(&PoolDataKey::ResConfig(asset.clone()))
+                .unwrap();
+            let config = ReserveConfig::unpack(&packed_config);
+            assert_eq!(config.index, index);
+            assert_eq!(config.c_factor, 0_7500000);
+            assert_eq!(config.reactivity, 0_0000020);
+            let packed_data = e
+                .storage()
+                .persistent()
+                .get::<PoolDataKey, PackedReserveData>(&PoolDataKey::ResData(asset.clone()))
+                .unwrap();
+            let data = ReserveData::unpack(&packed_data);
+            assert_eq!(data.b_rate, 1_000_500_000);
+            assert_eq!(data.b_supply, 100_0000000);
+            assert_eq!(data.backstop_credit, 1234);
+            assert_eq!(data.last_time, 1_700_000_000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_migrate_requires_upgrade() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_migrate(&e);
+        });
+    }
+
+    #[test]
+    fn test_queue_set_exchange_rate() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let config = ExchangeRateConfig {
+            rate_contract: Address::generate(&e),
+            base_asset: Address::generate(&e),
+            max_age: 3600,
+        };
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_exchange_rate(&e, &underlying, &Some(config.clone()));
+            let queued = storage::get_queued_exchange_rate(&e, &underlying);
+            assert_eq!(queued.update, ExchangeRateUpdate::Set(config.clone()));
+            assert_eq!(
+                queued.unlock_time,
+                e.ledger().timestamp() + SECONDS_PER_WEEK
+            );
+
+            execute_cancel_queued_set_exchange_rate(&e, &underlying);
+            assert!(!storage::has_queued_exchange_rate(&e, &underlying));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_queue_set_exchange_rate_requires_reserve() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        let config = ExchangeRateConfig {
+            rate_contract: Address::generate(&e),
+            base_asset: Address::generate(&e),
+            max_age: 3600,
+        };
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_exchange_rate(&e, &Address::generate(&e), &Some(config));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_queue_set_exchange_rate_validates_max_age() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let config = ExchangeRateConfig {
+            rate_contract: Address::generate(&e),
+            base_asset: Address::generate(&e),
+            max_age: 0,
+        };
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_exchange_rate(&e, &underlying, &Some(config));
+        });
+    }
+
+    #[test]
+    fn test_execute_set_exchange_rate() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let asset = Address::generate(&e);
+
+        let config = ExchangeRateConfig {
+            rate_contract: Address::generate(&e),
+            base_asset: Address::generate(&e),
+            max_age: 3600,
+        };
+        e.as_contract(&pool, || {
+            storage::set_last_price(&e, &asset, &1_0000000);
+            storage::set_queued_exchange_rate(
+                &e,
+                &asset,
+                &QueuedExchangeRate {
+                    update: ExchangeRateUpdate::Set(config.clone()),
+                    unlock_time: e.ledger().timestamp(),
+                },
+            );
+            let new_config = execute_set_exchange_rate(&e, &asset);
+            assert_eq!(new_config, Some(config.clone()));
+            assert_eq!(storage::get_exchange_rate_config(&e, &asset), Some(config));
+            assert_eq!(storage::get_last_price(&e, &asset), None);
+            assert!(!storage::has_queued_exchange_rate(&e, &asset));
+
+            // a queued None removes the config
+            storage::set_queued_exchange_rate(
+                &e,
+                &asset,
+                &QueuedExchangeRate {
+                    update: ExchangeRateUpdate::Remove,
+                    unlock_time: e.ledger().timestamp(),
+                },
+            );
+            execute_set_exchange_rate(&e, &asset);
+            assert_eq!(storage::get_exchange_rate_config(&e, &asset), None);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1203)")]
+    fn test_execute_set_exchange_rate_requires_unlock() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let asset = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            storage::set_queued_exchange_rate(
+                &e,
+                &asset,
+                &QueuedExchangeRate {
+                    update: ExchangeRateUpdate::Remove,
+                    unlock_time: e.ledger().timestamp() + 1,
+                },
+            );
+            execute_set_exchange_rate(&e, &asset);
+        });
+    }
+
+    #[test]
+    fn test_execute_set_partner_pool() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let partner_pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            assert!(!storage::is_partner_pool(&e, &partner_pool));
+
+            execute_set_partner_pool(&e, &partner_pool, true);
+            assert!(storage::is_partner_pool(&e, &partner_pool));
+
+            execute_set_partner_pool(&e, &partner_pool, false);
+            assert!(!storage::is_partner_pool(&e, &partner_pool));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_partner_pool_rejects_mutual_partner() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let partner_pool = testutils::create_pool(&e);
+
+        e.as_contract(&partner_pool, || {
+            storage::set_partner_pool(&e, &pool);
+        });
+        e.as_contract(&pool, || {
+            execute_set_partner_pool(&e, &partner_pool, true);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_partner_pool_remove_in_use() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let partner_pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.as_contract(&pool, || {
+            execute_set_partner_pool(&e, &partner_pool, true);
+            storage::set_partner_collateral(
+                &e,
+                &underlying,
+                &PartnerCollateral {
+                    partner_pool: partner_pool.clone(),
+                    base_asset: Address::generate(&e),
+                },
+            );
+            execute_set_partner_pool(&e, &partner_pool, false);
+        });
+    }
+
+    #[test]
+    fn test_execute_set_partner_collateral() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let partner_pool = Address::generate(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let config = PartnerCollateral {
+            partner_pool: partner_pool.clone(),
+            base_asset: Address::generate(&e),
+        };
+        e.as_contract(&pool, || {
+            storage::set_partner_pool(&e, &partner_pool);
+            storage::set_last_price(&e, &underlying, &1_0000000);
+
+            execute_set_partner_collateral(&e, &underlying, &Some(config.clone()));
+            assert_eq!(
+                storage::get_partner_collateral(&e, &underlying),
+                Some(config)
+            );
+            assert_eq!(storage::get_last_price(&e, &underlying), None);
+
+            execute_set_partner_collateral(&e, &underlying, &None);
+            assert_eq!(storage::get_partner_collateral(&e, &underlying), None);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_partner_collateral_requires_partner_pool() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let config = PartnerCollateral {
+            partner_pool: Address::generate(&e),
+            base_asset: Address::generate(&e),
+        };
+        e.as_contract(&pool, || {
+            execute_set_partner_collateral(&e, &underlying, &Some(config));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_partner_collateral_rejects_exchange_rate() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let partner_pool = Address::generate(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let config = PartnerCollateral {
+            partner_pool: partner_pool.clone(),
+            base_asset: Address::generate(&e),
+        };
+        e.as_contract(&pool, || {
+            storage::set_partner_pool(&e, &partner_pool);
+            storage::set_exchange_rate_config(
+                &e,
+                &underlying,
+                &ExchangeRateConfig {
+                    rate_contract: Address::generate(&e),
+                    base_asset: Address::generate(&e),
+                    max_age: 3600,
+                },
+            );
+            execute_set_partner_collateral(&e, &underlying, &Some(config));
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_queue_set_exchange_rate_rejects_partner_collateral() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let config = ExchangeRateConfig {
+            rate_contract: Address::generate(&e),
+            base_asset: Address::generate(&e),
+            max_age: 3600,
+        };
+        e.as_contract(&pool, || {
+            storage::set_partner_collateral(
+                &e,
+                &underlying,
+                &PartnerCollateral {
+                    partner_pool: Address::generate(&e),
+                    base_asset: Address::generate(&e),
+                },
+            );
+            execute_queue_set_exchange_rate(&e, &underlying, &Some(config));
+        });
+    }
+
+    #[test]
+    fn test_queue_set_reserve_status_6() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (asset_id_0, _) = testutils::create_token_contract(&e, &bombadil);
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 0_0100000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
+        };
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 6,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_reserve(&e, &asset_id_0, &metadata);
+            let queued_res = storage::get_queued_reserve_set(&e, &asset_id_0);
+            let res_config_0 = queued_res.new_config;
+            assert_eq!(res_config_0.decimals, metadata.decimals);
+            assert_eq!(res_config_0.c_factor, metadata.c_factor);
+            assert_eq!(res_config_0.l_factor, metadata.l_factor);
+            assert_eq!(res_config_0.util, metadata.util);
+            assert_eq!(res_config_0.r_base, metadata.r_base);
+            assert_eq!(res_config_0.r_one, metadata.r_one);
+            assert_eq!(res_config_0.r_one, metadata.r_one);
+            assert_eq!(res_config_0.r_two, metadata.r_two);
+            assert_eq!(res_config_0.r_three, metadata.r_three);
+            assert_eq!(res_config_0.reactivity, metadata.reactivity);
+            assert_eq!(res_config_0.index, 0);
+            assert_eq!(queued_res.unlock_time, e.ledger().timestamp());
+        });
+    }
+
+    #[test]
+    fn test_queue_set_reserve() {
         let e = Env::default();
         let pool = testutils::create_pool(&e);
         let bombadil = Address::generate(&e);
@@ -429,6 +2192,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         let pool_config = PoolConfig {
             oracle: Address::generate(&e),
@@ -458,6 +2224,64 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_get_queued_reserve_changes() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (asset_id_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (asset_id_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (asset_id_2, _) = testutils::create_token_contract(&e, &bombadil);
+
+        let (mut metadata, _) = testutils::default_reserve_meta();
+        metadata.index = 0;
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            assert_eq!(get_queued_reserve_changes(&e).len(), 0);
+
+            execute_queue_set_reserve(&e, &asset_id_0, &metadata);
+            execute_queue_set_reserve(&e, &asset_id_1, &metadata);
+            execute_queue_set_reserve(&e, &asset_id_2, &metadata);
+
+            let changes = get_queued_reserve_changes(&e);
+            assert_eq!(changes.len(), 3);
+            let queued_0 = changes.get(asset_id_0.clone()).unwrap();
+            assert_eq!(queued_0.new_config.c_factor, metadata.c_factor);
+            assert_eq!(
+                queued_0.unlock_time,
+                e.ledger().timestamp() + SECONDS_PER_WEEK
+            );
+
+            // cancelled sets are removed
+            execute_cancel_queued_set_reserve(&e, &asset_id_1);
+            let changes = get_queued_reserve_changes(&e);
+            assert_eq!(changes.len(), 2);
+            assert!(!changes.contains_key(asset_id_1.clone()));
+
+            // executed sets are removed
+            storage::set_queued_reserve_set(
+                &e,
+                &QueuedReserveInit {
+                    new_config: metadata.clone(),
+                    unlock_time: e.ledger().timestamp(),
+                },
+                &asset_id_2,
+            );
+            execute_set_reserve(&e, &asset_id_2);
+            let changes = get_queued_reserve_changes(&e);
+            assert_eq!(changes.len(), 1);
+            assert!(changes.contains_key(asset_id_0.clone()));
+            assert_eq!(storage::get_queued_reserve_list(&e).len(), 1);
+        });
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #1200)")]
     fn test_queue_set_reserve_duplicate() {
@@ -479,6 +2303,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         let pool_config = PoolConfig {
             oracle: Address::generate(&e),
@@ -518,7 +2345,173 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
+        };
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_queue_set_reserve(&e, &asset_id, &metadata);
+        });
+    }
+
+    #[test]
+    fn test_execute_cancel_queued_reserve_initialization() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (asset_id_0, _) = testutils::create_token_contract(&e, &bombadil);
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 0_0100000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
+        };
+        e.as_contract(&pool, || {
+            storage::set_queued_reserve_set(
+                &e,
+                &QueuedReserveInit {
+                    new_config: metadata.clone(),
+                    unlock_time: e.ledger().timestamp(),
+                },
+                &asset_id_0,
+            );
+            execute_cancel_queued_set_reserve(&e, &asset_id_0);
+            let result = storage::has_queued_reserve_set(&e, &asset_id_0);
+
+            assert!(!result);
+        });
+    }
+
+    #[test]
+    fn test_execute_set_reserve_first_reserve() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (asset_id_0, _) = testutils::create_token_contract(&e, &bombadil);
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 0_0100000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
+        };
+        e.as_contract(&pool, || {
+            storage::set_queued_reserve_set(
+                &e,
+                &QueuedReserveInit {
+                    new_config: metadata.clone(),
+                    unlock_time: e.ledger().timestamp(),
+                },
+                &asset_id_0,
+            );
+            execute_set_reserve(&e, &asset_id_0);
+            let res_config_0: ReserveConfig = storage::get_res_config(&e, &asset_id_0);
+            assert_eq!(res_config_0.decimals, metadata.decimals);
+            assert_eq!(res_config_0.c_factor, metadata.c_factor);
+            assert_eq!(res_config_0.l_factor, metadata.l_factor);
+            assert_eq!(res_config_0.util, metadata.util);
+            assert_eq!(res_config_0.max_util, metadata.max_util);
+            assert_eq!(res_config_0.r_one, metadata.r_one);
+            assert_eq!(res_config_0.r_two, metadata.r_two);
+            assert_eq!(res_config_0.r_three, metadata.r_three);
+            assert_eq!(res_config_0.reactivity, metadata.reactivity);
+            assert_eq!(res_config_0.index, 0);
+        });
+    }
+
+    #[test]
+    fn test_execute_propose_reserve_refunded_on_set() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let (asset_id_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (backstop_token, backstop_token_client) =
+            testutils::create_token_contract(&e, &bombadil);
+        let (backstop, _) = testutils::create_mock_backstop(&e, &pool, &backstop_token);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        let (metadata, _) = testutils::default_reserve_meta();
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 6,
+            max_positions: 2,
         };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            execute_set_listing_bond(&e, 100_0000000);
+
+            let bond = execute_propose_reserve(&e, &samwise, &asset_id_0, &metadata);
+            assert_eq!(
+                bond,
+                ReserveBond {
+                    proposer: samwise.clone(),
+                    amount: 100_0000000,
+                }
+            );
+            assert_eq!(storage::get_reserve_bond(&e, &asset_id_0), Some(bond));
+            assert!(storage::has_queued_reserve_set(&e, &asset_id_0));
+            assert_eq!(backstop_token_client.balance(&samwise), 0);
+            assert_eq!(backstop_token_client.balance(&pool), 100_0000000);
+
+            execute_set_reserve(&e, &asset_id_0);
+            assert!(storage::has_res(&e, &asset_id_0));
+            assert_eq!(storage::get_reserve_bond(&e, &asset_id_0), None);
+            assert_eq!(backstop_token_client.balance(&samwise), 100_0000000);
+            assert_eq!(backstop_token_client.balance(&pool), 0);
+            assert_eq!(backstop_token_client.balance(&backstop), 0);
+        });
+    }
+
+    #[test]
+    fn test_execute_propose_reserve_slashed_on_cancel() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let (asset_id_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (backstop_token, backstop_token_client) =
+            testutils::create_token_contract(&e, &bombadil);
+        let (backstop, backstop_client) =
+            testutils::create_mock_backstop(&e, &pool, &backstop_token);
+        backstop_token_client.mint(&samwise, &100_0000000);
+
+        let (metadata, _) = testutils::default_reserve_meta();
         let pool_config = PoolConfig {
             oracle: Address::generate(&e),
             bstop_rate: 0_1000000,
@@ -526,50 +2519,83 @@ mod tests {
             max_positions: 2,
         };
         e.as_contract(&pool, || {
-            storage::set_pool_config(&e, &pool_config);
-            execute_queue_set_reserve(&e, &asset_id, &metadata);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_listing_bond(&e, &100_0000000);
+
+            execute_propose_reserve(&e, &samwise, &asset_id_0, &metadata);
+            // the proposal is timelocked like any other reserve addition
+            let queued = storage::get_queued_reserve_set(&e, &asset_id_0);
+            assert_eq!(
+                queued.unlock_time,
+                e.ledger().timestamp() + SECONDS_PER_WEEK
+            );
+
+            let bond = execute_cancel_queued_set_reserve(&e, &asset_id_0);
+            assert_eq!(
+                bond,
+                Some(ReserveBond {
+                    proposer: samwise.clone(),
+                    amount: 100_0000000,
+                })
+            );
+            assert!(!storage::has_queued_reserve_set(&e, &asset_id_0));
+            assert_eq!(storage::get_reserve_bond(&e, &asset_id_0), None);
+        });
+        assert_eq!(backstop_token_client.balance(&samwise), 0);
+        assert_eq!(backstop_token_client.balance(&pool), 0);
+        assert_eq!(backstop_token_client.balance(&backstop), 100_0000000);
+        assert_eq!(backstop_client.pool_data(&pool).tokens, 100_0000000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_propose_reserve_disabled() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let (asset_id_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (metadata, _) = testutils::default_reserve_meta();
+        e.as_contract(&pool, || {
+            execute_propose_reserve(&e, &samwise, &asset_id_0, &metadata);
         });
     }
 
     #[test]
-    fn test_execute_cancel_queued_reserve_initialization() {
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_propose_reserve_existing_reserve() {
         let e = Env::default();
+        e.mock_all_auths();
         let pool = testutils::create_pool(&e);
         let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
 
-        let (asset_id_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
 
-        let metadata = ReserveConfig {
-            index: 0,
-            decimals: 7,
-            c_factor: 0_7500000,
-            l_factor: 0_7500000,
-            util: 0_5000000,
-            max_util: 0_9500000,
-            r_base: 0_0100000,
-            r_one: 0_0500000,
-            r_two: 0_5000000,
-            r_three: 1_5000000,
-            reactivity: 100,
-        };
         e.as_contract(&pool, || {
-            storage::set_queued_reserve_set(
-                &e,
-                &QueuedReserveInit {
-                    new_config: metadata.clone(),
-                    unlock_time: e.ledger().timestamp(),
-                },
-                &asset_id_0,
-            );
-            execute_cancel_queued_set_reserve(&e, &asset_id_0);
-            let result = storage::has_queued_reserve_set(&e, &asset_id_0);
+            storage::set_listing_bond(&e, &100_0000000);
+            execute_propose_reserve(&e, &samwise, &underlying, &reserve_config);
+        });
+    }
 
-            assert!(!result);
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_set_listing_bond_negative() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            execute_set_listing_bond(&e, -1);
         });
     }
 
     #[test]
-    fn test_execute_set_reserve_first_reserve() {
+    #[should_panic(expected = "Error(Contract, #1203)")]
+    fn test_execute_set_reserve_requires_block_passed() {
         let e = Env::default();
         let pool = testutils::create_pool(&e);
         let bombadil = Address::generate(&e);
@@ -588,34 +2614,26 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         e.as_contract(&pool, || {
             storage::set_queued_reserve_set(
                 &e,
                 &QueuedReserveInit {
                     new_config: metadata.clone(),
-                    unlock_time: e.ledger().timestamp(),
+                    unlock_time: e.ledger().timestamp() + 1,
                 },
                 &asset_id_0,
             );
             execute_set_reserve(&e, &asset_id_0);
-            let res_config_0: ReserveConfig = storage::get_res_config(&e, &asset_id_0);
-            assert_eq!(res_config_0.decimals, metadata.decimals);
-            assert_eq!(res_config_0.c_factor, metadata.c_factor);
-            assert_eq!(res_config_0.l_factor, metadata.l_factor);
-            assert_eq!(res_config_0.util, metadata.util);
-            assert_eq!(res_config_0.max_util, metadata.max_util);
-            assert_eq!(res_config_0.r_one, metadata.r_one);
-            assert_eq!(res_config_0.r_two, metadata.r_two);
-            assert_eq!(res_config_0.r_three, metadata.r_three);
-            assert_eq!(res_config_0.reactivity, metadata.reactivity);
-            assert_eq!(res_config_0.index, 0);
         });
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #1203)")]
-    fn test_execute_set_reserve_requires_block_passed() {
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_execute_set_reserve_validates_risk_params() {
         let e = Env::default();
         let pool = testutils::create_pool(&e);
         let bombadil = Address::generate(&e);
@@ -625,7 +2643,7 @@ mod tests {
         let metadata = ReserveConfig {
             index: 0,
             decimals: 7,
-            c_factor: 0_7500000,
+            c_factor: 0_9900000,
             l_factor: 0_7500000,
             util: 0_5000000,
             max_util: 0_9500000,
@@ -634,13 +2652,16 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         e.as_contract(&pool, || {
             storage::set_queued_reserve_set(
                 &e,
                 &QueuedReserveInit {
                     new_config: metadata.clone(),
-                    unlock_time: e.ledger().timestamp() + 1,
+                    unlock_time: e.ledger().timestamp(),
                 },
                 &asset_id_0,
             );
@@ -729,6 +2750,120 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_execute_set_reserve_update_ramps_c_factor() {
+        let e = Env::default();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 10000,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 10000;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let mut new_metadata = reserve_config.clone();
+        new_metadata.c_factor = 0_5500000;
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            storage::set_queued_reserve_set(
+                &e,
+                &QueuedReserveInit {
+                    new_config: new_metadata.clone(),
+                    unlock_time: e.ledger().timestamp(),
+                },
+                &underlying,
+            );
+            execute_set_reserve(&e, &underlying);
+            let res_config_updated = storage::get_res_config(&e, &underlying);
+            assert_eq!(res_config_updated.c_factor, 0_5500000);
+            let res_data = storage::get_res_data(&e, &underlying);
+            assert_eq!(res_data.ramp_c_factor, 0_7500000);
+            assert_eq!(res_data.ramp_start, 10000);
+            assert_eq!(res_data.ramp_end, 10000 + SECONDS_PER_WEEK);
+
+            // the reduction is applied linearly over the ramp period
+            let reserve = Pool::load(&e).load_reserve(&e, &underlying, false);
+            assert_eq!(reserve.c_factor, 0_7500000);
+        });
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 10000 + SECONDS_PER_WEEK / 2,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        e.as_contract(&pool, || {
+            let reserve = Pool::load(&e).load_reserve(&e, &underlying, false);
+            assert_eq!(reserve.c_factor, 0_6500000);
+
+            // an increase during a ramp is applied instantly and ends the ramp
+            new_metadata.c_factor = 0_7000000;
+            storage::set_queued_reserve_set(
+                &e,
+                &QueuedReserveInit {
+                    new_config: new_metadata.clone(),
+                    unlock_time: e.ledger().timestamp(),
+                },
+                &underlying,
+            );
+            execute_set_reserve(&e, &underlying);
+            let reserve = Pool::load(&e).load_reserve(&e, &underlying, false);
+            assert_eq!(reserve.c_factor, 0_7000000);
+            assert_eq!(reserve.ramp_end, 0);
+        });
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 10000 + SECONDS_PER_WEEK,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        e.as_contract(&pool, || {
+            // a reduction with no ramp period is applied instantly
+            execute_set_c_factor_ramp(&e, 0);
+            new_metadata.c_factor = 0_5000000;
+            storage::set_queued_reserve_set(
+                &e,
+                &QueuedReserveInit {
+                    new_config: new_metadata.clone(),
+                    unlock_time: e.ledger().timestamp(),
+                },
+                &underlying,
+            );
+            execute_set_reserve(&e, &underlying);
+            let reserve = Pool::load(&e).load_reserve(&e, &underlying, false);
+            assert_eq!(reserve.c_factor, 0_5000000);
+        });
+    }
+
     #[test]
     fn test_execute_set_reserve_update_resets_ir_mod() {
         let e = Env::default();
@@ -840,6 +2975,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 105,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
 
         let pool_config = PoolConfig {
@@ -884,6 +3022,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         e.as_contract(&pool, || {
             initialize_reserve(&e, &asset_id_0, &metadata);
@@ -922,6 +3063,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         require_valid_reserve_metadata(&e, &metadata);
         // no panic
@@ -945,6 +3089,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -966,6 +3113,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -987,6 +3137,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -1008,6 +3161,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -1029,6 +3185,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -1050,6 +3209,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -1071,6 +3233,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -1092,6 +3257,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
@@ -1113,7 +3281,127 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_0001001,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
+        };
+        require_valid_reserve_metadata(&e, &metadata);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_validate_reserve_metadata_validates_locked_b_tokens() {
+        let e = Env::default();
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 0_0001000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 1000,
+            locked_b_tokens: 1000,
+        };
+        require_valid_reserve_metadata(&e, &metadata);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_validate_reserve_metadata_validates_min_first_supply() {
+        let e = Env::default();
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 0_0001000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 1 << 96,
+            locked_b_tokens: 0,
+        };
+        require_valid_reserve_metadata(&e, &metadata);
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_validate_reserve_metadata_validates_max_c_factor() {
+        let e = Env::default();
+
+        let metadata = ReserveConfig {
+            index: 0,
+            decimals: 18,
+            c_factor: 0_9800001,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 0_0100000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
         };
         require_valid_reserve_metadata(&e, &metadata);
     }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1222)")]
+    fn test_queue_set_reserve_validates_token() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let asset_id = Address::generate(&e);
+
+        let (metadata, _) = testutils::default_reserve_meta();
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 6,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            execute_queue_set_reserve(&e, &asset_id, &metadata);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1222)")]
+    fn test_queue_set_reserve_validates_token_decimals() {
+        let e = Env::default();
+        e.mock_all_auths();
+        let pool = testutils::create_pool(&e);
+        let bombadil = Address::generate(&e);
+        let (asset_id, _) = testutils::create_token_contract(&e, &bombadil);
+
+        let (mut metadata, _) = testutils::default_reserve_meta();
+        metadata.decimals = 6;
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 6,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            execute_queue_set_reserve(&e, &asset_id, &metadata);
+        });
+    }
 }
@@ -0,0 +1,167 @@
+use soroban_sdk::{panic_with_error, Address, Env, Vec};
+
+use crate::{emissions, errors::PoolError, storage};
+
+use super::Pool;
+
+/// Bring a set of reserves up to date without changing any user positions. Interest is accrued
+/// and the interest rate modifier is updated, the emission indexes of each reserve token are
+/// refreshed, and the TTLs of the reserve's ledger entries are extended.
+///
+/// ### Arguments
+/// * `assets` - The underlying assets of the reserves to update
+///
+/// ### Panics
+/// If an asset is not a reserve
+pub fn execute_crank(e: &Env, assets: &Vec<Address>) {
+    let mut pool = Pool::load(e);
+    for asset in assets.iter() {
+        if !storage::has_res(e, &asset) {
+            panic_with_error!(e, PoolError::BadRequest);
+        }
+        let reserve = pool.load_reserve(e, &asset, true);
+        emissions::update_reserve_emissions(e, &reserve);
+        pool.cache_reserve(reserve);
+    }
+    pool.store_cached_reserves(e);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage::{PoolConfig, ReserveEmissionsConfig, ReserveEmissionsData},
+        testutils,
+    };
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec,
+    };
+
+    fn set_ledger(e: &Env, timestamp: u64, sequence_number: u32) {
+        e.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 20,
+            sequence_number,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+    }
+
+    #[test]
+    fn test_execute_crank() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        set_ledger(&e, 1000, 100);
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, _) = testutils::create_mock_oracle(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 1000;
+        reserve_data.d_supply = 80_0000000;
+        reserve_data.c_supply = 50_0000000;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            // emissions are configured for the dTokens and collateral bTokens
+            for res_token_id in [
+                emissions::to_res_token_id(0, 0),
+                emissions::to_res_token_id(0, 2),
+            ] {
+                storage::set_res_emis_config(
+                    &e,
+                    &res_token_id,
+                    &ReserveEmissionsConfig {
+                        expiration: 1_000_000,
+                        eps: 0_1000000,
+                    },
+                );
+                storage::set_res_emis_data(
+                    &e,
+                    &res_token_id,
+                    &ReserveEmissionsData {
+                        index: 0,
+                        last_time: 1000,
+                    },
+                );
+            }
+        });
+
+        set_ledger(&e, 1000 + 10_000, 101);
+        e.as_contract(&pool, || {
+            let prev_data = storage::get_res_data(&e, &underlying);
+            execute_crank(&e, &vec![&e, underlying.clone()]);
+
+            // interest is accrued and the rate modifier updated
+            let reserve_data = storage::get_res_data(&e, &underlying);
+            assert_eq!(reserve_data.last_time, 1000 + 10_000);
+            assert!(reserve_data.d_rate > prev_data.d_rate);
+            assert!(reserve_data.b_rate > prev_data.b_rate);
+            assert_ne!(reserve_data.ir_mod, prev_data.ir_mod);
+            assert_eq!(reserve_data.b_supply, prev_data.b_supply);
+            assert_eq!(reserve_data.d_supply, prev_data.d_supply);
+
+            // emission indexes are refreshed for each configured reserve token
+            // 10_000 seconds * 0.1 eps / 80 dTokens
+            let d_emis_data =
+                storage::get_res_emis_data(&e, &emissions::to_res_token_id(0, 0)).unwrap();
+            assert_eq!(d_emis_data.last_time, 1000 + 10_000);
+            assert_eq!(d_emis_data.index, 12_5000000);
+            // 10_000 seconds * 0.1 eps / 50 collateral bTokens
+            let c_emis_data =
+                storage::get_res_emis_data(&e, &emissions::to_res_token_id(0, 2)).unwrap();
+            assert_eq!(c_emis_data.last_time, 1000 + 10_000);
+            assert_eq!(c_emis_data.index, 20_0000000);
+            assert!(storage::get_res_emis_data(&e, &emissions::to_res_token_id(0, 1)).is_none());
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_execute_crank_not_reserve() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        set_ledger(&e, 1000, 100);
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, _) = testutils::create_mock_oracle(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 1000;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+        let not_reserve = Address::generate(&e);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+        });
+
+        e.as_contract(&pool, || {
+            execute_crank(&e, &vec![&e, underlying, not_reserve]);
+        });
+    }
+}
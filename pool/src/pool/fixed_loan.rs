@@ -0,0 +1,694 @@
+use cast::i128;
+use sep_41_token::TokenClient;
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::{
+        FIXED_EARLY_REPAY_PENALTY, MAX_FIXED_TERM, MIN_FIXED_TERM, SCALAR_7, SECONDS_PER_YEAR,
+    },
+    errors::PoolError,
+    storage::{self, FixedLoan},
+};
+
+use super::{
+    actions::RequestType, health_factor::PositionData, interest::calc_interest_rate,
+    submit::pull_from_spender, Pool, User,
+};
+
+/// Borrow from a reserve at the reserve's current borrow rate, locked for a fixed term. The loan
+/// is backed by dTokens held in the user's liabilities, so it is counted towards the user's
+/// health factor and can be liquidated like any other liability.
+///
+/// Returns the fixed-term loan
+///
+/// ### Arguments
+/// * `from` - The address of the user borrowing
+/// * `to` - The address receiving the borrowed tokens
+/// * `asset` - The underlying asset to borrow
+/// * `amount` - The amount of underlying tokens to borrow
+/// * `term` - The time in seconds the interest rate is locked for
+///
+/// ### Panics
+/// If the term is outside the allowed range, the user already has a fixed-term loan or stable
+/// rate debt for the reserve, or the borrow could not be made as a regular borrow
+pub fn execute_borrow_fixed(
+    e: &Env,
+    from: &Address,
+    to: &Address,
+    asset: &Address,
+    amount: i128,
+    term: u64,
+) -> FixedLoan {
+    if from == &e.current_contract_address() || to == &e.current_contract_address() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    if amount <= 0 || !(MIN_FIXED_TERM..=MAX_FIXED_TERM).contains(&term) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let mut pool = Pool::load(e);
+    pool.require_action_allowed(e, RequestType::Borrow as u32);
+    // loading the price checks the price breaker before the restriction is checked
+    if pool.is_price_emergency(e, asset) {
+        panic_with_error!(e, PoolError::PriceEmergency);
+    }
+    if storage::get_reserve_restricted(e, asset) {
+        panic_with_error!(e, PoolError::ReserveRestricted);
+    }
+    let mut user = User::load(e, from);
+    let prev_positions_count = user.positions.effective_count();
+    let mut reserve = pool.load_reserve(e, asset, true);
+    let mut loans = storage::get_fixed_loans(e, from);
+    // fixed-term and stable rate debt cannot back the same liabilities
+    if loans.contains_key(reserve.index)
+        || storage::get_stable_debts(e, from).contains_key(reserve.index)
+    {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let d_tokens_minted = reserve.to_d_token_up(amount);
+    user.add_liabilities(e, &mut reserve, d_tokens_minted);
+    reserve.require_utilization_below_max(e);
+    // lock the borrow rate the reserve charges once the loan is taken
+    let reserve_config = storage::get_res_config(e, asset);
+    let rate = calc_interest_rate(&reserve_config, reserve.utilization(), reserve.ir_mod);
+    let now = e.ledger().timestamp();
+    let loan = FixedLoan {
+        d_tokens: d_tokens_minted,
+        principal: amount,
+        rate,
+        start: now,
+        maturity: now + term,
+    };
+    loans.set(reserve.index, loan.clone());
+    pool.cache_reserve(reserve);
+    pool.require_under_max(e, &user.positions, prev_positions_count);
+
+    // min is 1.0000100 to prevent rounding errors
+    let position_data = PositionData::calculate_with_restrictions(e, &mut pool, &user.positions);
+    if position_data.is_hf_under(1_0000100) {
+        panic_with_error!(e, PoolError::InvalidHf);
+    }
+
+    pool.store_cached_reserves(e);
+    user.store(e);
+    storage::set_fixed_loans(e, from, &loans);
+
+    TokenClient::new(e, asset).transfer(&e.current_contract_address(), to, &amount);
+    loan
+}
+
+/// Repay a user's fixed-term loan from a reserve. The user pays the principal plus interest at
+/// the locked rate, and suppliers are credited the difference between this and the value of the
+/// dTokens backing the loan. Loans repaid before they mature also pay a penalty to the backstop.
+///
+/// If some of the loan's dTokens were removed from the user, like through a liquidation, only
+/// the portion of the loan still backed by the user's liabilities is repaid.
+///
+/// Returns a tuple of (repaid, penalty) where:
+/// * repaid - The amount of underlying tokens paid for principal and interest
+/// * penalty - The amount of underlying tokens paid to the backstop for repaying early
+///
+/// ### Arguments
+/// * `from` - The address of the user whose loan is repaid
+/// * `spender` - The address sending the tokens to the pool
+/// * `asset` - The underlying asset of the loan
+///
+/// ### Panics
+/// If the user has no fixed-term loan for the reserve
+pub fn execute_repay_fixed(
+    e: &Env,
+    from: &Address,
+    spender: &Address,
+    asset: &Address,
+) -> (i128, i128) {
+    if from == &e.current_contract_address() || spender == &e.current_contract_address() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let mut pool = Pool::load(e);
+    let mut user = User::load(e, from);
+    let mut reserve = pool.load_reserve(e, asset, true);
+    let mut loans = storage::get_fixed_loans(e, from);
+    let loan = loans
+        .get(reserve.index)
+        .unwrap_or_else(|| panic_with_error!(e, PoolError::BadRequest));
+
+    let d_tokens = loan.d_tokens.min(user.get_liabilities(reserve.index));
+    let (mut repaid, mut penalty) = calc_fixed_loan_owed(e, &loan);
+    if d_tokens < loan.d_tokens {
+        repaid = repaid
+            .fixed_mul_ceil(d_tokens, loan.d_tokens)
+            .unwrap_optimized();
+        penalty = penalty
+            .fixed_mul_ceil(d_tokens, loan.d_tokens)
+            .unwrap_optimized();
+    }
+    if d_tokens > 0 {
+        let debt = reserve.to_asset_from_d_token(d_tokens);
+        user.remove_liabilities(e, &mut reserve, d_tokens);
+        reserve.credit_suppliers(repaid - debt);
+        reserve.backstop_credit += penalty;
+    }
+    loans.remove(reserve.index);
+    pool.cache_reserve(reserve);
+
+    pool.store_cached_reserves(e);
+    user.store(e);
+    storage::set_fixed_loans(e, from, &loans);
+
+    if repaid + penalty > 0 {
+        pull_from_spender(e, spender, asset, repaid + penalty);
+    }
+    (repaid, penalty)
+}
+
+/// Fetch the amount of dTokens in a user's liabilities for a reserve that back a fixed-term loan.
+/// These can only be repaid through `execute_repay_fixed`.
+///
+/// ### Arguments
+/// * `user` - The user
+/// * `reserve_index` - The index of the reserve
+pub fn get_fixed_d_tokens(e: &Env, user: &User, reserve_index: u32) -> i128 {
+    match storage::get_fixed_loans(e, &user.address).get(reserve_index) {
+        Some(loan) => loan.d_tokens.min(user.get_liabilities(reserve_index)),
+        None => 0,
+    }
+}
+
+/// Reduce a user's fixed-term loans to the liabilities left backing them after liabilities were
+/// removed from the user outside of a repayment, like through a liquidation or a bad debt
+/// transfer. The principal is reduced in proportion to the dTokens removed, and loans without any
+/// liabilities left are removed.
+///
+/// This MUST be called whenever liabilities are removed from a user without repaying their
+/// fixed-term loans, such that later borrows cannot back a loan's removed dTokens.
+///
+/// ### Arguments
+/// * `user` - The user after their liabilities were removed
+pub fn rebase_fixed_loans(e: &Env, user: &User) {
+    let mut loans = storage::get_fixed_loans(e, &user.address);
+    let mut changed = false;
+    for (reserve_index, mut loan) in loans.iter() {
+        let liabilities = user.get_liabilities(reserve_index);
+        if liabilities >= loan.d_tokens {
+            continue;
+        }
+        changed = true;
+        if liabilities == 0 {
+            loans.remove(reserve_index);
+        } else {
+            loan.principal = loan
+                .principal
+                .fixed_mul_floor(liabilities, loan.d_tokens)
+                .unwrap_optimized();
+            loan.d_tokens = liabilities;
+            loans.set(reserve_index, loan);
+        }
+    }
+    if changed {
+        storage::set_fixed_loans(e, &user.address, &loans);
+    }
+}
+
+/// Calculate the amount owed to repay a fixed-term loan at the current ledger
+///
+/// Returns a tuple of (owed, penalty) where:
+/// * owed - The principal plus interest accrued at the locked rate
+/// * penalty - The early repayment penalty, or zero if the loan has matured
+///
+/// ### Arguments
+/// * `loan` - The fixed-term loan
+pub fn calc_fixed_loan_owed(e: &Env, loan: &FixedLoan) -> (i128, i128) {
+    let now = e.ledger().timestamp();
+    let interest = loan
+        .principal
+        .fixed_mul_ceil(loan.rate, SCALAR_7)
+        .unwrap_optimized()
+        .fixed_mul_ceil(i128(now - loan.start), SECONDS_PER_YEAR)
+        .unwrap_optimized();
+    let penalty = if now < loan.maturity {
+        loan.principal
+            .fixed_mul_ceil(FIXED_EARLY_REPAY_PENALTY, SCALAR_7)
+            .unwrap_optimized()
+    } else {
+        0
+    };
+    (loan.principal + interest, penalty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pool::{execute_borrow_stable, Positions},
+        testutils,
+    };
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+    };
+
+    fn set_ledger_time(e: &Env, timestamp: u64) {
+        e.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+    }
+
+    #[test]
+    fn test_borrow_fixed() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+        let underlying_1_client = TokenClient::new(&e, &underlying_1);
+
+        e.as_contract(&pool, || {
+            let pre_pool_balance = underlying_1_client.balance(&pool);
+
+            let loan = execute_borrow_fixed(
+                &e,
+                &samwise,
+                &frodo,
+                &underlying_1,
+                1_0000000,
+                30 * 24 * 60 * 60,
+            );
+            assert_eq!(loan.principal, 1_0000000);
+            assert_eq!(loan.start, 600);
+            assert_eq!(loan.maturity, 600 + 30 * 24 * 60 * 60);
+
+            let reserve_config = storage::get_res_config(&e, &underlying_1);
+            let mut pool_state = Pool::load(&e);
+            let reserve = pool_state.load_reserve(&e, &underlying_1, false);
+            assert_eq!(
+                loan.rate,
+                calc_interest_rate(&reserve_config, reserve.utilization(), reserve.ir_mod)
+            );
+
+            let positions = storage::get_user_positions(&e, &samwise);
+            assert_eq!(positions.liabilities.get_unchecked(1), loan.d_tokens);
+            assert_eq!(
+                storage::get_fixed_loans(&e, &samwise).get_unchecked(1),
+                loan
+            );
+            assert_eq!(
+                underlying_1_client.balance(&pool),
+                pre_pool_balance - 1_0000000
+            );
+            assert_eq!(underlying_1_client.balance(&frodo), 1_0000000);
+            assert_eq!(positions.collateral.get_unchecked(0), 20_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_borrow_fixed_term_too_short() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        e.as_contract(&pool, || {
+            execute_borrow_fixed(
+                &e,
+                &samwise,
+                &samwise,
+                &underlying_1,
+                1_0000000,
+                MIN_FIXED_TERM - 1,
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_borrow_fixed_existing_loan() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        e.as_contract(&pool, || {
+            execute_borrow_fixed(
+                &e,
+                &samwise,
+                &samwise,
+                &underlying_1,
+                1_0000000,
+                MIN_FIXED_TERM,
+            );
+            execute_borrow_fixed(
+                &e,
+                &samwise,
+                &samwise,
+                &underlying_1,
+                1_0000000,
+                MIN_FIXED_TERM,
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_borrow_fixed_with_stable_debt() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        e.as_contract(&pool, || {
+            execute_borrow_stable(&e, &samwise, &samwise, &underlying_1, 1_0000000);
+            execute_borrow_fixed(
+                &e,
+                &samwise,
+                &samwise,
+                &underlying_1,
+                1_0000000,
+                MIN_FIXED_TERM,
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1205)")]
+    fn test_borrow_fixed_under_health() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 5_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        e.as_contract(&pool, || {
+            execute_borrow_fixed(
+                &e,
+                &samwise,
+                &samwise,
+                &underlying_1,
+                1_0000000,
+                MIN_FIXED_TERM,
+            );
+        });
+    }
+
+    #[test]
+    fn test_repay_fixed_at_maturity() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+        let underlying_1_client = sep_41_token::testutils::MockTokenClient::new(&e, &underlying_1);
+        underlying_1_client.mint(&frodo, &10_0000000);
+
+        let loan = e.as_contract(&pool, || {
+            execute_borrow_fixed(
+                &e,
+                &samwise,
+                &samwise,
+                &underlying_1,
+                1_0000000,
+                MIN_FIXED_TERM,
+            )
+        });
+
+        set_ledger_time(&e, 600 + MIN_FIXED_TERM);
+        e.as_contract(&pool, || {
+            let mut pool_state = Pool::load(&e);
+            let pre_reserve = pool_state.load_reserve(&e, &underlying_1, false);
+            let debt = pre_reserve.to_asset_from_d_token(loan.d_tokens);
+            let pre_frodo_balance = underlying_1_client.balance(&frodo);
+
+            let (repaid, penalty) = execute_repay_fixed(&e, &samwise, &frodo, &underlying_1);
+            let (owed, _) = calc_fixed_loan_owed(&e, &loan);
+            assert_eq!(repaid, owed);
+            assert_eq!(penalty, 0);
+            assert!(repaid > loan.principal);
+            assert_eq!(
+                underlying_1_client.balance(&frodo),
+                pre_frodo_balance - repaid
+            );
+
+            // suppliers are credited the difference between the locked and variable interest
+            let mut pool_state = Pool::load(&e);
+            let reserve = pool_state.load_reserve(&e, &underlying_1, false);
+            assert_eq!(reserve.d_supply, pre_reserve.d_supply - loan.d_tokens);
+            assert_eq!(reserve.backstop_credit, pre_reserve.backstop_credit);
+            assert_eq!(
+                reserve.interest_earned,
+                pre_reserve.interest_earned + repaid - debt
+            );
+
+            let positions = storage::get_user_positions(&e, &samwise);
+            assert_eq!(positions.liabilities.len(), 0);
+            assert_eq!(storage::get_fixed_loans(&e, &samwise).len(), 0);
+        });
+    }
+
+    #[test]
+    fn test_repay_fixed_early_pays_penalty() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+        let underlying_1_client = sep_41_token::testutils::MockTokenClient::new(&e, &underlying_1);
+        underlying_1_client.mint(&samwise, &10_0000000);
+
+        e.as_contract(&pool, || {
+            execute_borrow_fixed(
+                &e,
+                &samwise,
+                &samwise,
+                &underlying_1,
+                1_0000000,
+                MAX_FIXED_TERM,
+            );
+        });
+
+        set_ledger_time(&e, 600 + MIN_FIXED_TERM);
+        e.as_contract(&pool, || {
+            let mut pool_state = Pool::load(&e);
+            let pre_reserve = pool_state.load_reserve(&e, &underlying_1, false);
+            let pre_balance = underlying_1_client.balance(&samwise);
+
+            let (repaid, penalty) = execute_repay_fixed(&e, &samwise, &samwise, &underlying_1);
+            assert_eq!(penalty, 0_0100000);
+            assert_eq!(
+                underlying_1_client.balance(&samwise),
+                pre_balance - repaid - penalty
+            );
+
+            let mut pool_state = Pool::load(&e);
+            let reserve = pool_state.load_reserve(&e, &underlying_1, false);
+            assert_eq!(
+                reserve.backstop_credit,
+                pre_reserve.backstop_credit + 0_0100000
+            );
+        });
+    }
+
+    #[test]
+    fn test_repay_fixed_after_liquidation() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+        let underlying_1_client = sep_41_token::testutils::MockTokenClient::new(&e, &underlying_1);
+        underlying_1_client.mint(&samwise, &10_0000000);
+
+        let loan = e.as_contract(&pool, || {
+            execute_borrow_fixed(
+                &e,
+                &samwise,
+                &samwise,
+                &underlying_1,
+                1_0000000,
+                MIN_FIXED_TERM,
+            )
+        });
+
+        set_ledger_time(&e, 600 + MIN_FIXED_TERM);
+        e.as_contract(&pool, || {
+            // half of the loan's dTokens were taken by a liquidator
+            let mut positions = storage::get_user_positions(&e, &samwise);
+            positions.liabilities.set(1, loan.d_tokens / 2);
+            storage::set_user_positions(&e, &samwise, &positions);
+
+            let (repaid, penalty) = execute_repay_fixed(&e, &samwise, &samwise, &underlying_1);
+            let (owed, _) = calc_fixed_loan_owed(&e, &loan);
+            assert_eq!(
+                repaid,
+                owed.fixed_mul_ceil(loan.d_tokens / 2, loan.d_tokens)
+                    .unwrap_optimized()
+            );
+            assert_eq!(penalty, 0);
+            assert_eq!(
+                storage::get_user_positions(&e, &samwise).liabilities.len(),
+                0
+            );
+            assert_eq!(storage::get_fixed_loans(&e, &samwise).len(), 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_repay_fixed_no_loan() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+        let (pool, _, underlying_1) =
+            testutils::create_priced_pool(&e, &samwise, &Positions::env_default(&e));
+
+        e.as_contract(&pool, || {
+            execute_repay_fixed(&e, &samwise, &samwise, &underlying_1);
+        });
+    }
+
+    #[test]
+    fn test_rebase_fixed_loans() {
+        let e = Env::default();
+        set_ledger_time(&e, 600);
+
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let loan = FixedLoan {
+            d_tokens: 10_0000000,
+            principal: 11_0000000,
+            rate: 0_0500000,
+            start: 600,
+            maturity: 600 + MIN_FIXED_TERM,
+        };
+        e.as_contract(&pool, || {
+            storage::set_fixed_loans(
+                &e,
+                &samwise,
+                &map![&e, (0, loan.clone()), (1, loan.clone()), (2, loan.clone())],
+            );
+
+            // a liquidation removed some of reserve 0's and all of reserve 1's liabilities
+            let user = User {
+                address: samwise.clone(),
+                simulation: false,
+                positions: Positions {
+                    liabilities: map![&e, (0, 4_0000000), (2, 12_0000000)],
+                    collateral: map![&e],
+                    supply: map![&e],
+                },
+            };
+            rebase_fixed_loans(&e, &user);
+
+            let loans = storage::get_fixed_loans(&e, &samwise);
+            assert_eq!(loans.len(), 2);
+            let loan_0 = loans.get_unchecked(0);
+            assert_eq!(loan_0.d_tokens, 4_0000000);
+            assert_eq!(loan_0.principal, 4_4000000);
+            assert_eq!(loan_0.rate, loan.rate);
+            assert_eq!(loan_0.maturity, loan.maturity);
+            assert_eq!(loans.get_unchecked(2), loan);
+
+            // a later borrow does not restore the removed dTokens
+            let user = User {
+                address: samwise.clone(),
+                simulation: false,
+                positions: Positions {
+                    liabilities: map![&e, (0, 20_0000000), (2, 12_0000000)],
+                    collateral: map![&e],
+                    supply: map![&e],
+                },
+            };
+            assert_eq!(get_fixed_d_tokens(&e, &user, 0), 4_0000000);
+        });
+    }
+
+    #[test]
+    fn test_calc_fixed_loan_owed() {
+        let e = Env::default();
+        set_ledger_time(&e, 1000 + SECONDS_PER_YEAR as u64 / 2);
+
+        let loan = FixedLoan {
+            d_tokens: 95_0000000,
+            principal: 100_0000000,
+            rate: 0_0500000,
+            start: 1000,
+            maturity: 1000 + SECONDS_PER_YEAR as u64,
+        };
+        // half a year at 5% and a 1% penalty for repaying early
+        assert_eq!(calc_fixed_loan_owed(&e, &loan), (102_5000000, 1_0000000));
+
+        set_ledger_time(&e, 1000 + SECONDS_PER_YEAR as u64);
+        assert_eq!(calc_fixed_loan_owed(&e, &loan), (105_0000000, 0));
+    }
+}
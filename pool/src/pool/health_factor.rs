@@ -21,10 +21,38 @@ pub struct PositionData {
 impl PositionData {
     /// Calculate the position data for a given set of of positions
     ///
+    /// Positions without liabilities can't be unhealthy, so their collateral is not valued and no
+    /// prices are loaded. All balances are returned as zero.
+    ///
     /// ### Arguments
     /// * pool - The pool
     /// * positions - The positions to calculate the health factor for
     pub fn calculate_from_positions(e: &Env, pool: &mut Pool, positions: &Positions) -> Self {
+        Self::calculate(e, pool, positions, false)
+    }
+
+    /// Calculate the position data for a given set of positions, valuing collateral in reserves
+    /// restricted by the price breaker or in a pricing emergency at zero. Used to check the health
+    /// of borrows, so a manipulated or stale collateral price can't be borrowed against. Other
+    /// health checks, including withdrawals and liquidations, value the collateral normally.
+    ///
+    /// ### Arguments
+    /// * pool - The pool
+    /// * positions - The positions to calculate the health factor for
+    pub fn calculate_with_restrictions(e: &Env, pool: &mut Pool, positions: &Positions) -> Self {
+        Self::calculate(e, pool, positions, true)
+    }
+
+    fn calculate(e: &Env, pool: &mut Pool, positions: &Positions, restrict: bool) -> Self {
+        if positions.liabilities.is_empty() {
+            return PositionData {
+                collateral_base: 0,
+                collateral_raw: 0,
+                liability_base: 0,
+                liability_raw: 0,
+                scalar: SCALAR_7,
+            };
+        }
         let oracle_scalar = 10i128.pow(pool.load_price_decimals(e));
 
         let reserve_list = storage::get_res_list(e);
@@ -39,15 +67,22 @@ impl PositionData {
                 continue;
             }
             let reserve = pool.load_reserve(e, &reserve_list.get_unchecked(i), false);
-            let asset_to_base = pool.load_price(e, &reserve.asset);
 
             if b_token_balance > 0 {
-                // append users effective collateral to collateral_base
+                // append users effective collateral to collateral_base. Collateral is capped at
+                // the asset's peg band and haircut if the asset is in a pricing emergency.
+                let mut collateral_to_base = pool.load_collateral_price(e, &reserve.asset);
+                if restrict
+                    && (pool.is_restricted(e, &reserve.asset)
+                        || pool.is_price_emergency(e, &reserve.asset))
+                {
+                    collateral_to_base = 0;
+                }
                 let asset_collateral = reserve.to_effective_asset_from_b_token(b_token_balance);
-                collateral_base += asset_to_base
+                collateral_base += collateral_to_base
                     .fixed_mul_floor(asset_collateral, reserve.scalar)
                     .unwrap_optimized();
-                collateral_raw += asset_to_base
+                collateral_raw += collateral_to_base
                     .fixed_mul_floor(
                         reserve.to_asset_from_b_token(b_token_balance),
                         reserve.scalar,
@@ -56,7 +91,9 @@ impl PositionData {
             }
 
             if d_token_balance > 0 {
-                // append users effective liability to liability_base
+                // append users effective liability to liability_base. Liabilities are floored
+                // at the asset's peg band.
+                let asset_to_base = pool.load_liability_price(e, &reserve.asset);
                 let asset_liability = reserve.to_effective_asset_from_d_token(d_token_balance);
                 liability_base += asset_to_base
                     .fixed_mul_ceil(asset_liability, reserve.scalar)
@@ -121,7 +158,11 @@ impl PositionData {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{storage::PoolConfig, testutils};
+    use crate::{
+        constants::PRICE_EMERGENCY_DECAY,
+        storage::{CachedPrice, PoolConfig},
+        testutils,
+    };
     use sep_40_oracle::testutils::Asset;
     use soroban_sdk::{
         map,
@@ -213,6 +254,272 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_calculate_with_restrictions() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.decimals = 9;
+        reserve_config.c_factor = 0_8500000;
+        reserve_config.l_factor = 0_8000000;
+        reserve_data.b_supply = 100_000_000_000;
+        reserve_data.d_supply = 70_000_000_000;
+        reserve_data.b_rate = 1_100_000_000;
+        reserve_data.d_rate = 1_150_000_000;
+        reserve_config.index = 1;
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        let (underlying_2, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.decimals = 6;
+        reserve_config.index = 2;
+        reserve_data.b_supply = 10_000_000;
+        reserve_data.d_supply = 5_000_000;
+        reserve_data.b_rate = 1_001_100_000;
+        reserve_data.d_rate = 1_001_200_000;
+        testutils::create_reserve(&e, &pool, &underlying_2, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0),
+                Asset::Stellar(underlying_1),
+                Asset::Stellar(underlying_2.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 2_5000000, 1000_0000000]);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 0,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 5,
+        };
+
+        let positions = Positions {
+            liabilities: map![&e, (0, 1_5000000), (1, 50_987_654_321)],
+            collateral: map![&e, (0, 100_1234567), (2, 0_250_000)],
+            supply: map![&e, (1, 120_987_654_321)],
+        };
+        let unrestricted_positions = Positions {
+            liabilities: positions.liabilities.clone(),
+            collateral: map![&e, (0, 100_1234567)],
+            supply: positions.supply.clone(),
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_reserve_restricted(&e, &underlying_2, true);
+            let mut pool = Pool::load(&e);
+            let full_data = PositionData::calculate_from_positions(&e, &mut pool, &positions);
+            assert_eq!(full_data.collateral_base, 262_7985925);
+
+            // the restricted reserve's collateral is valued at zero
+            let position_data =
+                PositionData::calculate_with_restrictions(&e, &mut pool, &positions);
+            let expected_data =
+                PositionData::calculate_from_positions(&e, &mut pool, &unrestricted_positions);
+            assert_eq!(position_data.collateral_base, expected_data.collateral_base);
+            assert_eq!(position_data.collateral_raw, expected_data.collateral_raw);
+            assert_eq!(position_data.liability_base, full_data.liability_base);
+            assert_eq!(position_data.liability_raw, full_data.liability_raw);
+        });
+    }
+
+    #[test]
+    fn test_calculate_from_positions_no_liabilities() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        // the oracle is not a contract, so loading a price would fail
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 5,
+        };
+
+        let positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 100_0000000)],
+            supply: map![&e, (0, 10_0000000)],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+            let position_data = PositionData::calculate_from_positions(&e, &mut pool, &positions);
+            assert_eq!(position_data.collateral_base, 0);
+            assert_eq!(position_data.liability_base, 0);
+            assert_eq!(position_data.collateral_raw, 0);
+            assert_eq!(position_data.liability_raw, 0);
+            assert_eq!(position_data.scalar, SCALAR_7);
+            assert!(!position_data.is_hf_under(1_0000100));
+        });
+    }
+
+    #[test]
+    fn test_calculate_from_positions_price_emergency() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1_000_000,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 1_000_000;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        // the oracle does not list underlying_0
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![&e, Asset::Stellar(Address::generate(&e))],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 5,
+        };
+
+        let positions = Positions {
+            liabilities: map![&e, (0, 1_0000000)],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_cached_price(
+                &e,
+                &underlying_0,
+                &CachedPrice {
+                    price: 2_0000000,
+                    timestamp: 1_000_000 - PRICE_EMERGENCY_DECAY / 2,
+                    emergency: false,
+                },
+            );
+            let mut pool = Pool::load(&e);
+            let position_data = PositionData::calculate_from_positions(&e, &mut pool, &positions);
+            // collateral is valued at the decayed cached price and liabilities at the cached price
+            assert_eq!(position_data.collateral_base, 7_5000000);
+            assert_eq!(position_data.liability_base, 2_6666668);
+            assert_eq!(position_data.collateral_raw, 10_0000000);
+            assert_eq!(position_data.liability_raw, 2_0000000);
+
+            // collateral in a pricing emergency can't be borrowed against
+            let position_data =
+                PositionData::calculate_with_restrictions(&e, &mut pool, &positions);
+            assert_eq!(position_data.collateral_base, 0);
+            assert_eq!(position_data.liability_base, 2_6666668);
+        });
+    }
+
+    #[test]
+    fn test_calculate_from_positions_peg_band() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        // underlying_0 trades over its peg and underlying_1 under it
+        oracle_client.set_price_stable(&vec![&e, 1_0500000, 0_9500000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 5,
+        };
+
+        let positions = Positions {
+            liabilities: map![&e, (1, 5_0000000)],
+            collateral: map![&e, (0, 10_0000000)],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_peg_band(&e, &underlying_0, 0_0200000);
+            storage::set_peg_band(&e, &underlying_1, 0_0200000);
+            let mut pool = Pool::load(&e);
+            let position_data = PositionData::calculate_from_positions(&e, &mut pool, &positions);
+            // collateral is valued at 1.02 and liabilities at 0.98
+            assert_eq!(position_data.collateral_base, 7_6500000);
+            assert_eq!(position_data.liability_base, 6_5333334);
+            assert_eq!(position_data.collateral_raw, 10_2000000);
+            assert_eq!(position_data.liability_raw, 4_9000000);
+        });
+    }
+
     #[test]
     fn test_as_health_factor_rounds_floor() {
         let position_data = PositionData {
@@ -1,32 +1,36 @@
 use cast::i128;
 use soroban_fixed_point_math::FixedPoint;
-use soroban_sdk::{unwrap::UnwrapOptimized, Env};
+use soroban_sdk::{contracttype, unwrap::UnwrapOptimized, Address, Env};
 
 use crate::{
-    constants::{SCALAR_7, SCALAR_9, SECONDS_PER_YEAR},
-    storage::ReserveConfig,
+    constants::{SCALAR_7, SCALAR_9, SECONDS_PER_YEAR, UTIL_AVG_WINDOW},
+    storage::{self, ReserveConfig},
 };
 
-/// Calculates the loan accrual ratio for the Reserve based on the current utilization and
-/// rate modifier for the reserve.
+use super::{Pool, Reserve};
+
+/// The current interest rates for a reserve
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ReserveRates {
+    pub borrow_apr: i128, // the annual interest rate paid by borrowers (7 decimals)
+    pub supply_apr: i128, // the annual interest rate earned by suppliers (7 decimals)
+    pub util: i128,       // the utilization rate of the reserve (7 decimals)
+    pub ir_mod: i128,     // the interest rate curve modifier (9 decimals)
+}
+
+/// Calculates the current borrow interest rate for the Reserve based on the current utilization
+/// and rate modifier for the reserve.
 ///
 /// ### Arguments
-/// * `config` - The Reserve config to calculate an accrual for
+/// * `config` - The Reserve config to calculate an interest rate for
 /// * `cur_util` - The current utilization rate of the reserve (7 decimals)
 /// * `ir_mod` - The current interest rate modifier of the reserve (9 decimals)
-/// * `last_block` - The last block an accrual was performed
 ///
 /// ### Returns
-/// * (i128, i128) - (accrual amount scaled to 9 decimal places, new interest rate modifier scaled to 9 decimal places)
+/// * i128 - The annual borrow interest rate scaled to 7 decimal places
 #[allow(clippy::zero_prefixed_literal)]
-pub fn calc_accrual(
-    e: &Env,
-    config: &ReserveConfig,
-    cur_util: i128,
-    ir_mod: i128,
-    last_time: u64,
-) -> (i128, i128) {
-    let cur_ir: i128;
+pub fn calc_interest_rate(config: &ReserveConfig, cur_util: i128, ir_mod: i128) -> i128 {
     let target_util: i128 = i128(config.util);
     if cur_util <= target_util {
         let util_scalar = cur_util
@@ -37,9 +41,9 @@ pub fn calc_accrual(
             .unwrap_optimized()
             + i128(config.r_base);
 
-        cur_ir = base_rate
+        base_rate
             .fixed_mul_ceil(ir_mod, SCALAR_9)
-            .unwrap_optimized();
+            .unwrap_optimized()
     } else if cur_util <= 0_9500000 {
         let util_scalar = (cur_util - target_util)
             .fixed_div_ceil(0_9500000 - target_util, SCALAR_7)
@@ -50,9 +54,9 @@ pub fn calc_accrual(
             + i128(config.r_one)
             + i128(config.r_base);
 
-        cur_ir = base_rate
+        base_rate
             .fixed_mul_ceil(ir_mod, SCALAR_9)
-            .unwrap_optimized();
+            .unwrap_optimized()
     } else {
         let util_scalar = (cur_util - 0_9500000)
             .fixed_div_ceil(0_0500000, SCALAR_7)
@@ -64,13 +68,97 @@ pub fn calc_accrual(
         let intersection = ir_mod
             .fixed_mul_ceil(i128(config.r_two + config.r_one + config.r_base), SCALAR_9)
             .unwrap_optimized();
-        cur_ir = extra_rate + intersection;
+        extra_rate + intersection
+    }
+}
+
+/// Calculates the current interest rates for a reserve, updated to the current ledger timestamp
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+///
+/// ### Panics
+/// If the asset is not a reserve in the pool
+pub fn calc_reserve_rates(e: &Env, asset: &Address) -> ReserveRates {
+    let mut pool = Pool::load(e);
+    let reserve = pool.load_reserve(e, asset, false);
+    calc_rates_for_reserve(e, pool.config.bstop_rate, &reserve)
+}
+
+/// Calculates the current interest rates for a loaded reserve
+///
+/// ### Arguments
+/// * `bstop_rate` - The pool's backstop take rate (7 decimals)
+/// * `reserve` - The reserve, updated to the current ledger timestamp
+pub(crate) fn calc_rates_for_reserve(e: &Env, bstop_rate: u32, reserve: &Reserve) -> ReserveRates {
+    let reserve_config = storage::get_res_config(e, &reserve.asset);
+
+    let util = if reserve.b_supply == 0 {
+        0
+    } else {
+        reserve.utilization()
+    };
+    let borrow_apr = calc_interest_rate(&reserve_config, util, reserve.ir_mod);
+    let supply_apr = borrow_apr
+        .fixed_mul_floor(util, SCALAR_7)
+        .unwrap_optimized()
+        .fixed_mul_floor(SCALAR_7 - i128(bstop_rate), SCALAR_7)
+        .unwrap_optimized();
+    ReserveRates {
+        borrow_apr,
+        supply_apr,
+        util,
+        ir_mod: reserve.ir_mod,
     }
+}
+
+/// Calculates the time-weighted utilization of the Reserve over the last UTIL_AVG_WINDOW
+/// seconds, given the utilization has been `cur_util` since the last update.
+///
+/// ### Arguments
+/// * `util_avg` - The time-weighted utilization as of the last update (7 decimals)
+/// * `cur_util` - The current utilization rate of the reserve (7 decimals)
+/// * `delta_time` - The seconds elapsed since the last update
+///
+/// ### Returns
+/// * i128 - The time-weighted utilization scaled to 7 decimal places
+pub fn calc_util_avg(util_avg: i128, cur_util: i128, delta_time: u64) -> i128 {
+    if delta_time >= UTIL_AVG_WINDOW {
+        return cur_util;
+    }
+    (util_avg * i128(UTIL_AVG_WINDOW - delta_time) + cur_util * i128(delta_time))
+        / i128(UTIL_AVG_WINDOW)
+}
+
+/// Calculates the loan accrual ratio for the Reserve based on the current utilization and
+/// rate modifier for the reserve. The rate modifier is updated based on the time-weighted
+/// utilization, so short lived utilization spikes have a limited effect on it.
+///
+/// ### Arguments
+/// * `config` - The Reserve config to calculate an accrual for
+/// * `cur_util` - The current utilization rate of the reserve (7 decimals)
+/// * `util_avg` - The time-weighted utilization rate of the reserve (7 decimals)
+/// * `ir_mod` - The current interest rate modifier of the reserve (9 decimals)
+/// * `last_block` - The last block an accrual was performed
+///
+/// ### Returns
+/// * (i128, i128) - (accrual amount scaled to 9 decimal places, new interest rate modifier scaled to 9 decimal places)
+#[allow(clippy::zero_prefixed_literal)]
+pub fn calc_accrual(
+    e: &Env,
+    config: &ReserveConfig,
+    cur_util: i128,
+    util_avg: i128,
+    ir_mod: i128,
+    last_time: u64,
+) -> (i128, i128) {
+    let cur_ir = calc_interest_rate(config, cur_util, ir_mod);
+    let target_util: i128 = i128(config.util);
 
     // update rate_modifier
     // scale delta blocks and util dif to 9 decimals
     let delta_time_scaled = i128(e.ledger().timestamp() - last_time) * SCALAR_9;
-    let util_dif_scaled = (cur_util - target_util) * 100;
+    let util_dif_scaled = (util_avg - target_util) * 100;
     let new_ir_mod: i128;
     if util_dif_scaled >= 0 {
         // rate modifier increasing
@@ -105,12 +193,20 @@ pub fn calc_accrual(
     }
 
     // calc accrual amount over blocks
+    // @dev: interest compounds every second, which is approximated with the first terms of the
+    //       series for e^x, where x is the interest rate over the elapsed time
     let time_weight = delta_time_scaled / SECONDS_PER_YEAR;
+    let rate_over_time = time_weight
+        .fixed_mul_ceil(cur_ir * 100, SCALAR_9)
+        .unwrap_optimized();
+    let second_term = rate_over_time
+        .fixed_mul_floor(rate_over_time, SCALAR_9 * 2)
+        .unwrap_optimized();
+    let third_term = second_term
+        .fixed_mul_floor(rate_over_time, SCALAR_9 * 3)
+        .unwrap_optimized();
     (
-        SCALAR_9
-            + time_weight
-                .fixed_mul_ceil(cur_ir * 100, SCALAR_9)
-                .unwrap_optimized(),
+        SCALAR_9 + rate_over_time + second_term + third_term,
         new_ir_mod,
     )
 }
@@ -118,7 +214,8 @@ pub fn calc_accrual(
 #[cfg(test)]
 mod tests {
     use super::*;
-    use soroban_sdk::testutils::{Ledger, LedgerInfo};
+    use crate::{storage::PoolConfig, testutils};
+    use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
 
     #[test]
     fn test_calc_accrual_util_under_target() {
@@ -135,6 +232,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_0000020,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
             index: 0,
         };
         let ir_mod: i128 = 1_000_000_000;
@@ -150,7 +250,7 @@ mod tests {
             max_entry_ttl: 3110400,
         });
 
-        let (accrual, ir_mod) = calc_accrual(&e, &reserve_config, 0_6565656, ir_mod, 0);
+        let (accrual, ir_mod) = calc_accrual(&e, &reserve_config, 0_6565656, 0_6565656, ir_mod, 0);
 
         assert_eq!(accrual, 1_000_000_853);
         assert_eq!(ir_mod, 0_999_906_566);
@@ -171,6 +271,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_0000020,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
             index: 0,
         };
         let ir_mod: i128 = 1_000_000_000;
@@ -186,7 +289,7 @@ mod tests {
             max_entry_ttl: 3110400,
         });
 
-        let (accrual, ir_mod) = calc_accrual(&e, &reserve_config, 0_7979797, ir_mod, 0);
+        let (accrual, ir_mod) = calc_accrual(&e, &reserve_config, 0_7979797, 0_7979797, ir_mod, 0);
 
         assert_eq!(accrual, 1_000_002_853);
         assert_eq!(ir_mod, 1_000_047_979);
@@ -207,6 +310,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_0000020,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
             index: 0,
         };
         let ir_mod: i128 = 1_000_000_000;
@@ -222,7 +328,7 @@ mod tests {
             max_entry_ttl: 3110400,
         });
 
-        let (accrual, ir_mod) = calc_accrual(&e, &reserve_config, 0_9696969, ir_mod, 0);
+        let (accrual, ir_mod) = calc_accrual(&e, &reserve_config, 0_9696969, 0_9696969, ir_mod, 0);
 
         assert_eq!(accrual, 1_000_018_247);
         assert_eq!(ir_mod, 1_000_219_696);
@@ -243,6 +349,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_0000020,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
             index: 0,
         };
         let ir_mod: i128 = 9_997_000_000;
@@ -258,7 +367,7 @@ mod tests {
             max_entry_ttl: 3110400,
         });
 
-        let (_accrual, ir_mod) = calc_accrual(&e, &reserve_config, 0_9696969, ir_mod, 0);
+        let (_accrual, ir_mod) = calc_accrual(&e, &reserve_config, 0_9696969, 0_9696969, ir_mod, 0);
 
         assert_eq!(ir_mod, 10_000_000_000);
     }
@@ -278,6 +387,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_0000020,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
             index: 0,
         };
         let ir_mod: i128 = 0_150_000_000;
@@ -293,7 +405,7 @@ mod tests {
             max_entry_ttl: 3110400,
         });
 
-        let (_accrual, ir_mod) = calc_accrual(&e, &reserve_config, 0_2020202, ir_mod, 0);
+        let (_accrual, ir_mod) = calc_accrual(&e, &reserve_config, 0_2020202, 0_2020202, ir_mod, 0);
 
         assert_eq!(ir_mod, 0_100_000_000);
     }
@@ -313,6 +425,9 @@ mod tests {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_0000020,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
             index: 0,
         };
         let ir_mod: i128 = 0_100_000_000;
@@ -328,12 +443,27 @@ mod tests {
             max_entry_ttl: 3110400,
         });
 
-        let (accrual, ir_mod) = calc_accrual(&e, &reserve_config, 0_0500000, ir_mod, 500);
+        let (accrual, ir_mod) =
+            calc_accrual(&e, &reserve_config, 0_0500000, 0_0500000, ir_mod, 500);
 
         assert_eq!(accrual, 1_000_000_001);
         assert_eq!(ir_mod, 0_100_000_000);
     }
 
+    #[test]
+    fn test_calc_util_avg() {
+        // a quarter of the window has passed
+        assert_eq!(calc_util_avg(0_5000000, 0_9000000, 900), 0_6000000);
+        assert_eq!(calc_util_avg(0_9000000, 0_5000000, 900), 0_8000000);
+
+        // the full window has passed
+        assert_eq!(calc_util_avg(0_5000000, 0_9000000, 3600), 0_9000000);
+        assert_eq!(calc_util_avg(0_5000000, 0_9000000, 100000), 0_9000000);
+
+        // no time has passed
+        assert_eq!(calc_util_avg(0_5000000, 0_9000000, 0), 0_5000000);
+    }
+
     #[test]
     fn test_calc_accrual_fixed_rate() {
         let e = Env::default();
@@ -349,6 +479,9 @@ mod tests {
             r_two: 0,
             r_three: 0,
             reactivity: 0_0000020,
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
             index: 0,
         };
         let ir_mod: i128 = 1_000_000_000;
@@ -364,10 +497,13 @@ mod tests {
             max_entry_ttl: 3110400,
         });
 
-        let (accrual_0, ir_mod_0) = calc_accrual(&e, &reserve_config, 0, ir_mod, 0);
-        let (accrual_1, ir_mod_1) = calc_accrual(&e, &reserve_config, 0_6565656, ir_mod, 0);
-        let (accrual_2, ir_mod_2) = calc_accrual(&e, &reserve_config, 0_7565656, ir_mod, 0);
-        let (accrual_3, ir_mod_3) = calc_accrual(&e, &reserve_config, 0_9565656, ir_mod, 0);
+        let (accrual_0, ir_mod_0) = calc_accrual(&e, &reserve_config, 0, 0, ir_mod, 0);
+        let (accrual_1, ir_mod_1) =
+            calc_accrual(&e, &reserve_config, 0_6565656, 0_6565656, ir_mod, 0);
+        let (accrual_2, ir_mod_2) =
+            calc_accrual(&e, &reserve_config, 0_7565656, 0_7565656, ir_mod, 0);
+        let (accrual_3, ir_mod_3) =
+            calc_accrual(&e, &reserve_config, 0_9565656, 0_9565656, ir_mod, 0);
 
         assert_eq!(accrual_0, 1_000_003_964);
         assert_eq!(ir_mod_0, 0_999_250_000);
@@ -378,4 +514,79 @@ mod tests {
         assert_eq!(accrual_3, 1_000_003_964);
         assert_eq!(ir_mod_3, 1_000_206_565);
     }
+
+    #[test]
+    fn test_calc_interest_rate() {
+        let (reserve_config, _) = testutils::default_reserve_meta();
+
+        assert_eq!(
+            calc_interest_rate(&reserve_config, 0_5000000, SCALAR_9),
+            0_0433334
+        );
+        assert_eq!(
+            calc_interest_rate(&reserve_config, 0_8500000, 2 * SCALAR_9),
+            0_6200000
+        );
+        assert_eq!(
+            calc_interest_rate(&reserve_config, 0_9750000, SCALAR_9),
+            1_3100000
+        );
+    }
+
+    #[test]
+    fn test_calc_reserve_rates() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 123456 * 5,
+            protocol_version: 20,
+            sequence_number: 123456,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.b_supply = 100_0000000;
+        reserve_data.d_supply = 50_0000000;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let reserve = Pool::load(&e).load_reserve(&e, &underlying, false);
+
+            let rates = calc_reserve_rates(&e, &underlying);
+            assert_eq!(rates.util, reserve.utilization());
+            assert_eq!(rates.ir_mod, reserve.ir_mod);
+            assert_eq!(
+                rates.borrow_apr,
+                calc_interest_rate(&reserve_config, rates.util, rates.ir_mod)
+            );
+            // supply rate is reduced by utilization and the backstop take rate
+            assert_eq!(
+                rates.supply_apr,
+                rates
+                    .borrow_apr
+                    .fixed_mul_floor(rates.util, SCALAR_7)
+                    .unwrap_optimized()
+                    .fixed_mul_floor(0_8000000, SCALAR_7)
+                    .unwrap_optimized()
+            );
+            assert!(rates.ir_mod < SCALAR_9);
+        });
+    }
 }
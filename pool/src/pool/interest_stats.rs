@@ -0,0 +1,269 @@
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{contracttype, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::SCALAR_9,
+    storage::{self, UserInterestData},
+};
+
+use super::Pool;
+
+/// The lifetime interest statistics for a reserve or a user's position in a reserve,
+/// expressed in the underlying asset
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+#[contracttype]
+pub struct InterestStats {
+    pub paid: i128,   // the interest paid by borrowers
+    pub earned: i128, // the interest earned by suppliers
+}
+
+/// Update the lifetime interest accrued by a user for a reserve's b or d token. This must be
+/// called before the user's balance of the token changes.
+///
+/// ### Arguments
+/// * `user` - The user
+/// * `res_token_id` - The reserve token being acted against => (reserve index * 2 + (0 for debtToken or 1 for blendToken))
+/// * `rate` - The current b or d rate of the reserve token
+/// * `balance` - The user's balance of the reserve token before the update
+pub fn update_user_interest(e: &Env, user: &Address, res_token_id: u32, rate: i128, balance: i128) {
+    let user_data = match storage::get_user_interest(e, user, &res_token_id) {
+        Some(cur_data) => {
+            if cur_data.rate == rate {
+                return;
+            }
+            UserInterestData {
+                rate,
+                accrued: cur_data.accrued + calc_interest(balance, rate, &cur_data, res_token_id),
+            }
+        }
+        None => UserInterestData { rate, accrued: 0 },
+    };
+    storage::set_user_interest(e, user, &res_token_id, &user_data);
+}
+
+/// Calculate the lifetime interest statistics for a reserve, including interest that has
+/// accrued since the reserve was last updated
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve
+///
+/// ### Panics
+/// If the asset is not a reserve in the pool
+pub fn calc_reserve_interest(e: &Env, asset: &Address) -> InterestStats {
+    let mut pool = Pool::load(e);
+    let reserve = pool.load_reserve(e, asset, false);
+    InterestStats {
+        paid: reserve.interest_paid,
+        earned: reserve.interest_earned,
+    }
+}
+
+/// Calculate the lifetime interest statistics for a user's position in a reserve, including
+/// interest that has accrued since the user's position was last updated
+///
+/// Interest is only tracked from the first time the user's position is modified.
+///
+/// ### Arguments
+/// * `user` - The user
+/// * `asset` - The underlying asset of the reserve
+///
+/// ### Panics
+/// If the asset is not a reserve in the pool
+pub fn calc_user_interest(e: &Env, user: &Address, asset: &Address) -> InterestStats {
+    let mut pool = Pool::load(e);
+    let reserve = pool.load_reserve(e, asset, false);
+    let positions = storage::get_user_positions(e, user);
+
+    let d_token_id = reserve.index * 2;
+    let d_balance = positions.liabilities.get(reserve.index).unwrap_or(0);
+    let b_token_id = reserve.index * 2 + 1;
+    let b_balance = positions.collateral.get(reserve.index).unwrap_or(0)
+        + positions.supply.get(reserve.index).unwrap_or(0);
+    InterestStats {
+        paid: calc_user_accrued(e, user, d_token_id, reserve.d_rate, d_balance),
+        earned: calc_user_accrued(e, user, b_token_id, reserve.b_rate, b_balance),
+    }
+}
+
+/// Calculate the lifetime interest accrued by a user for a reserve's b or d token at the given rate
+fn calc_user_accrued(
+    e: &Env,
+    user: &Address,
+    res_token_id: u32,
+    rate: i128,
+    balance: i128,
+) -> i128 {
+    match storage::get_user_interest(e, user, &res_token_id) {
+        Some(user_data) => {
+            user_data.accrued + calc_interest(balance, rate, &user_data, res_token_id)
+        }
+        None => 0,
+    }
+}
+
+/// Calculate the interest accrued by a balance of a reserve's b or d token since the
+/// user's last update. Interest paid by borrowers is rounded up and interest earned by
+/// suppliers is rounded down.
+fn calc_interest(
+    balance: i128,
+    rate: i128,
+    user_data: &UserInterestData,
+    res_token_id: u32,
+) -> i128 {
+    let rate_delta = rate - user_data.rate;
+    if balance == 0 || rate_delta <= 0 {
+        return 0;
+    }
+    if res_token_id % 2 == 1 {
+        balance
+            .fixed_mul_floor(rate_delta, SCALAR_9)
+            .unwrap_optimized()
+    } else {
+        balance
+            .fixed_mul_ceil(rate_delta, SCALAR_9)
+            .unwrap_optimized()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{storage::PoolConfig, testutils, Positions};
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+    };
+
+    #[test]
+    fn test_update_user_interest() {
+        let e = Env::default();
+        let pool = testutils::create_pool(&e);
+        let samwise = Address::generate(&e);
+
+        e.as_contract(&pool, || {
+            // first update sets the checkpoint
+            update_user_interest(&e, &samwise, 0, 1_100_000_000, 0);
+            let user_data = storage::get_user_interest(&e, &samwise, &0).unwrap_optimized();
+            assert_eq!(user_data.rate, 1_100_000_000);
+            assert_eq!(user_data.accrued, 0);
+
+            // d tokens round up
+            update_user_interest(&e, &samwise, 0, 1_150_000_001, 10_0000000);
+            let user_data = storage::get_user_interest(&e, &samwise, &0).unwrap_optimized();
+            assert_eq!(user_data.rate, 1_150_000_001);
+            assert_eq!(user_data.accrued, 0_5000001);
+
+            // b tokens round down
+            update_user_interest(&e, &samwise, 1, 1_050_000_000, 0);
+            update_user_interest(&e, &samwise, 1, 1_080_000_001, 10_0000000);
+            let user_data = storage::get_user_interest(&e, &samwise, &1).unwrap_optimized();
+            assert_eq!(user_data.rate, 1_080_000_001);
+            assert_eq!(user_data.accrued, 0_3000000);
+
+            // a decrease in rate does not accrue negative interest
+            update_user_interest(&e, &samwise, 1, 1_000_000_000, 10_0000000);
+            let user_data = storage::get_user_interest(&e, &samwise, &1).unwrap_optimized();
+            assert_eq!(user_data.rate, 1_000_000_000);
+            assert_eq!(user_data.accrued, 0_3000000);
+        });
+    }
+
+    #[test]
+    fn test_calc_interest_stats() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 123456 * 5,
+            protocol_version: 20,
+            sequence_number: 123456,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.d_rate = 1_345_678_123;
+        reserve_data.b_rate = 1_123_456_789;
+        reserve_data.d_supply = 65_0000000;
+        reserve_data.b_supply = 99_0000000;
+        reserve_data.interest_paid = 1_0000000;
+        reserve_data.interest_earned = 0_8000000;
+        reserve_data.last_time = 123456 * 5 - 100000;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(
+                &e,
+                &samwise,
+                &Positions {
+                    liabilities: map![&e, (0, 10_0000000)],
+                    collateral: map![&e, (0, 20_0000000)],
+                    supply: map![&e, (0, 5_0000000)],
+                },
+            );
+            storage::set_user_interest(
+                &e,
+                &samwise,
+                &0,
+                &UserInterestData {
+                    rate: 1_300_000_000,
+                    accrued: 1_0000000,
+                },
+            );
+            storage::set_user_interest(
+                &e,
+                &samwise,
+                &1,
+                &UserInterestData {
+                    rate: 1_100_000_000,
+                    accrued: 2_0000000,
+                },
+            );
+
+            let reserve = Pool::load(&e).load_reserve(&e, &underlying, false);
+            let reserve_stats = calc_reserve_interest(&e, &underlying);
+            assert_eq!(reserve_stats.paid, reserve.interest_paid);
+            assert_eq!(reserve_stats.earned, reserve.interest_earned);
+            assert!(reserve_stats.paid > 1_0000000);
+            assert!(reserve_stats.earned > 0_8000000);
+            assert!(reserve_stats.earned < reserve_stats.paid);
+
+            let user_stats = calc_user_interest(&e, &samwise, &underlying);
+            assert_eq!(
+                user_stats.paid,
+                1_0000000
+                    + 10_0000000
+                        .fixed_mul_ceil(reserve.d_rate - 1_300_000_000, SCALAR_9)
+                        .unwrap_optimized()
+            );
+            assert_eq!(
+                user_stats.earned,
+                2_0000000
+                    + 25_0000000
+                        .fixed_mul_floor(reserve.b_rate - 1_100_000_000, SCALAR_9)
+                        .unwrap_optimized()
+            );
+
+            // users without interest data have not accrued any tracked interest
+            let user_stats = calc_user_interest(&e, &frodo, &underlying);
+            assert_eq!(user_stats, InterestStats::default());
+        });
+    }
+}
@@ -0,0 +1,314 @@
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::{MAX_SWAP_SLIPPAGE, SCALAR_7},
+    errors::PoolError,
+};
+
+use super::{swap::swap_exact_amount_out, Pool, User};
+
+/// Lever a user's collateral up to a target leverage by borrowing an asset, swapping it through
+/// the pool's AMM for the collateral asset, and supplying the result as collateral.
+///
+/// The health of the user is only verified once all requests are processed, so the supply,
+/// borrow, and swap cycle is performed once for the full amount instead of being repeated.
+///
+/// Returns a tuple of (borrowed, supplied) where:
+/// * borrowed - The amount of the borrow asset borrowed
+/// * supplied - The amount of the collateral asset supplied
+///
+/// ### Arguments
+/// * `user_state` - The user levering their position
+/// * `collateral_asset` - The underlying asset of the collateral
+/// * `equity` - The amount of the collateral asset the user supplied
+/// * `borrow_asset` - The underlying asset being borrowed
+/// * `leverage` - The target leverage of the equity, expressed in 7 decimals (3_0000000 => 3x)
+///
+/// ### Panics
+/// If the leverage is not over 1x, if a swap is required and no AMM is set, or if the swap
+/// exceeds the maximum slippage from the oracle price
+pub fn loop_supply_borrow(
+    e: &Env,
+    pool: &mut Pool,
+    user_state: &mut User,
+    collateral_asset: &Address,
+    equity: i128,
+    borrow_asset: &Address,
+    leverage: i128,
+) -> (i128, i128) {
+    if leverage <= SCALAR_7 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let supplied = equity
+        .fixed_mul_floor(leverage - SCALAR_7, SCALAR_7)
+        .unwrap_optimized();
+
+    let borrowed = if collateral_asset == borrow_asset {
+        supplied
+    } else {
+        // bound the swap by the oracle price of the collateral in the borrow asset
+        let collateral_reserve = pool.load_reserve(e, collateral_asset, false);
+        let borrow_reserve = pool.load_reserve(e, borrow_asset, false);
+        let collateral_price = pool.load_price(e, collateral_asset);
+        let borrow_price = pool.load_price(e, borrow_asset);
+        let max_borrowed = supplied
+            .fixed_mul_ceil(collateral_price, borrow_price)
+            .unwrap_optimized()
+            .fixed_mul_ceil(borrow_reserve.scalar, collateral_reserve.scalar)
+            .unwrap_optimized()
+            .fixed_mul_ceil(SCALAR_7 + MAX_SWAP_SLIPPAGE, SCALAR_7)
+            .unwrap_optimized();
+        swap_exact_amount_out(e, borrow_asset, max_borrowed, collateral_asset, supplied)
+    };
+
+    let mut borrow_reserve = pool.load_reserve(e, borrow_asset, true);
+    let d_tokens_minted = borrow_reserve.to_d_token_up(borrowed);
+    user_state.add_liabilities(e, &mut borrow_reserve, d_tokens_minted);
+    borrow_reserve.require_utilization_below_max(e);
+    pool.cache_reserve(borrow_reserve);
+
+    let mut collateral_reserve = pool.load_reserve(e, collateral_asset, true);
+    let b_tokens_minted = collateral_reserve.mint_b_tokens(e, supplied);
+    user_state.add_collateral(e, &mut collateral_reserve, b_tokens_minted);
+    pool.cache_reserve(collateral_reserve);
+
+    (borrowed, supplied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        storage::{self, PoolConfig},
+        testutils::{self, create_comet_lp_pool},
+    };
+    use sep_40_oracle::testutils::Asset;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec, Symbol,
+    };
+
+    #[test]
+    fn test_loop_supply_borrow() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (usdc, usdc_client) = testutils::create_token_contract(&e, &bombadil);
+        let (blnd, blnd_client) = testutils::create_token_contract(&e, &bombadil);
+        let (amm, _) = create_comet_lp_pool(&e, &bombadil, &blnd, &usdc);
+
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(&e, &pool, &usdc, &reserve_config, &reserve_data);
+        testutils::create_reserve(&e, &pool, &blnd, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(usdc.clone()),
+                Asset::Stellar(blnd.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 0_1000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        // verify the pool authorizes the swap itself
+        e.set_auths(&[]);
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_amm(&e, &amm);
+
+            let pre_pool_usdc = usdc_client.balance(&pool);
+            let pre_pool_blnd = blnd_client.balance(&pool);
+
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            let (borrowed, supplied) = loop_supply_borrow(
+                &e,
+                &mut pool_state,
+                &mut user,
+                &blnd,
+                10_0000000,
+                &usdc,
+                2_0000000,
+            );
+
+            // ~1.03 USDC is swapped for 10 BLND at 0.1 USDC per BLND
+            assert_eq!(supplied, 10_0000000);
+            assert!(borrowed > 1_0000000 && borrowed < 1_0500000);
+            assert_eq!(user.get_liabilities(0), borrowed);
+            assert_eq!(user.get_collateral(1), 10_0000000);
+            assert_eq!(usdc_client.balance(&pool), pre_pool_usdc - borrowed);
+            assert_eq!(blnd_client.balance(&pool), pre_pool_blnd + 10_0000000);
+
+            let usdc_reserve = pool_state.load_reserve(&e, &usdc, false);
+            assert_eq!(usdc_reserve.d_supply, reserve_data.d_supply + borrowed);
+            let blnd_reserve = pool_state.load_reserve(&e, &blnd, false);
+            assert_eq!(blnd_reserve.b_supply, reserve_data.b_supply + 10_0000000);
+        });
+    }
+
+    #[test]
+    fn test_loop_supply_borrow_same_asset() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (usdc, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &usdc, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            let (borrowed, supplied) = loop_supply_borrow(
+                &e,
+                &mut pool_state,
+                &mut user,
+                &usdc,
+                10_0000000,
+                &usdc,
+                1_5000000,
+            );
+
+            assert_eq!(borrowed, 5_0000000);
+            assert_eq!(supplied, 5_0000000);
+            assert_eq!(user.get_liabilities(0), 5_0000000);
+            assert_eq!(user.get_collateral(0), 5_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_loop_supply_borrow_over_max_slippage() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (usdc, _) = testutils::create_token_contract(&e, &bombadil);
+        let (blnd, _) = testutils::create_token_contract(&e, &bombadil);
+        let (amm, _) = create_comet_lp_pool(&e, &bombadil, &blnd, &usdc);
+
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &usdc, &reserve_config, &reserve_data);
+        testutils::create_reserve(&e, &pool, &blnd, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(usdc.clone()),
+                Asset::Stellar(blnd.clone()),
+            ],
+            &7,
+            &300,
+        );
+        // the AMM price of BLND is ~10% over the oracle price
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 0_0900000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_amm(&e, &amm);
+
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            loop_supply_borrow(
+                &e,
+                &mut pool_state,
+                &mut user,
+                &blnd,
+                10_0000000,
+                &usdc,
+                2_0000000,
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_loop_supply_borrow_requires_leverage() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (usdc, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &usdc, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            loop_supply_borrow(
+                &e,
+                &mut pool_state,
+                &mut user,
+                &usdc,
+                10_0000000,
+                &usdc,
+                1_0000000,
+            );
+        });
+    }
+}
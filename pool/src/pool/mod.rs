@@ -1,35 +1,78 @@
+mod account;
+pub use account::{
+    calc_max_withdrawable, calc_user_net_rate, load_positions_batch, load_user_account,
+    UserAccount, UserNetRate,
+};
+
 mod actions;
 pub use actions::{Request, RequestType};
 
 mod bad_debt;
-pub use bad_debt::transfer_bad_debt_to_backstop;
+pub use bad_debt::{get_bad_debt_log, transfer_bad_debt_to_backstop};
+
+mod close_position;
+
+mod crank;
+pub use crank::execute_crank;
 
 mod config;
 pub use config::{
-    execute_cancel_queued_set_reserve, execute_initialize, execute_queue_set_reserve,
-    execute_set_reserve, execute_update_pool,
+    execute_cancel_queued_set_amm, execute_cancel_queued_set_exchange_rate,
+    execute_cancel_queued_set_metadata, execute_cancel_queued_set_reserve,
+    execute_cancel_queued_upgrade, execute_clear_reserve_restriction, execute_initialize,
+    execute_migrate, execute_propose_reserve, execute_queue_set_amm,
+    execute_queue_set_exchange_rate, execute_queue_set_metadata, execute_queue_set_reserve,
+    execute_queue_upgrade, execute_set_amm, execute_set_auction_curve, execute_set_c_factor_ramp,
+    execute_set_exchange_rate, execute_set_liquidation_grace, execute_set_listing_bond,
+    execute_set_metadata, execute_set_min_fill_pct, execute_set_partner_collateral,
+    execute_set_partner_pool, execute_set_peg_band, execute_set_price_breaker, execute_set_reserve,
+    execute_set_supply_breaker, execute_set_withdraw_queue, execute_set_withdraw_util_cap,
+    execute_update_pool, execute_upgrade, get_queued_reserve_changes,
 };
 
+mod fixed_loan;
+pub use fixed_loan::{execute_borrow_fixed, execute_repay_fixed, rebase_fixed_loans};
+
 mod health_factor;
 pub use health_factor::PositionData;
 
 mod interest;
+pub use interest::{calc_reserve_rates, ReserveRates};
+
+mod interest_stats;
+pub use interest_stats::{
+    calc_reserve_interest, calc_user_interest, update_user_interest, InterestStats,
+};
 
 mod submit;
 
-pub use submit::execute_submit;
+mod swap;
+mod swap_repay;
+
+pub use submit::{execute_submit, simulate_submit, SubmitSimulation, SubmitSummary};
 
 #[allow(clippy::module_inception)]
 mod pool;
 pub use pool::Pool;
 
+mod leverage;
+
 mod reserve;
 pub use reserve::Reserve;
 
+mod withdraw_queue;
+
+mod top_up;
+pub use top_up::{execute_set_top_up, execute_top_up};
+
 mod user;
 pub use user::{Positions, User};
 
+mod stable_rate;
+pub use stable_rate::{execute_borrow_stable, execute_rebalance_stable, execute_swap_to_variable};
+
 mod status;
 pub use status::{
-    calc_pool_backstop_threshold, execute_set_pool_status, execute_update_pool_status,
+    calc_pool_backstop_threshold, calc_total_liabilities, calc_total_liabilities_in,
+    execute_set_pool_status, execute_update_pool_status,
 };
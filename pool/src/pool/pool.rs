@@ -1,21 +1,37 @@
-use soroban_sdk::{map, panic_with_error, unwrap::UnwrapOptimized, vec, Address, Env, Map, Vec};
+use cast::i128;
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{
+    map, panic_with_error, unwrap::UnwrapOptimized, vec, Address, Env, Map, Symbol, Vec,
+};
 
 use sep_40_oracle::{Asset, PriceFeedClient};
 
 use crate::{
+    constants::{
+        MAX_RATE_CHECKPOINTS, PRICE_CACHE_INTERVAL, PRICE_EMERGENCY_DECAY,
+        RATE_CHECKPOINT_INTERVAL, SCALAR_7, SCALAR_9,
+    },
+    dependencies::{ExchangeRateClient, PartnerPoolClient},
     errors::PoolError,
-    storage::{self, PoolConfig},
+    storage::{self, CachedPrice, PoolConfig, RateCheckpoint, SupplySnapshot},
     Positions,
 };
 
-use super::reserve::Reserve;
+use super::{reserve::Reserve, user::User};
 
+/// The in-memory state of the pool for a single invocation. Reserves and oracle prices are
+/// loaded lazily and cached, such that every request in a submit or fill is evaluated against
+/// the same price snapshot and the oracle is only queried once per asset.
 pub struct Pool {
     pub config: PoolConfig,
     pub reserves: Map<Address, Reserve>,
     reserves_to_store: Vec<Address>,
     price_decimals: Option<u32>,
     prices: Map<Address, i128>,
+    emergency_prices: Map<Address, u64>,
+    peg_bands: Map<Address, u32>,
+    simulation: bool,
+    simulated_restrictions: Vec<Address>,
 }
 
 impl Pool {
@@ -28,9 +44,22 @@ impl Pool {
             reserves_to_store: vec![e],
             price_decimals: None,
             prices: map![e],
+            emergency_prices: map![e],
+            peg_bands: map![e],
+            simulation: false,
+            simulated_restrictions: vec![e],
         }
     }
 
+    /// Load the Pool from the ledger for a simulation. Loading prices does not write the cached
+    /// price or the price breaker state, and restrictions the price breaker would place are only
+    /// kept in memory.
+    pub fn load_for_simulation(e: &Env) -> Self {
+        let mut pool = Pool::load(e);
+        pool.simulation = true;
+        pool
+    }
+
     /// Load a Reserve from the ledger and update to the current ledger timestamp. Returns
     /// a cached version if it exists.
     ///
@@ -58,12 +87,21 @@ impl Pool {
     }
 
     /// Store the cached reserves to the ledger that need to be written.
+    ///
+    /// ### Panics
+    /// If the supply breaker is enabled and a reserve's bToken or dToken supply grew by more
+    /// than the threshold within the current ledger
     pub fn store_cached_reserves(&self, e: &Env) {
+        let supply_breaker = storage::get_supply_breaker(e);
         for address in self.reserves_to_store.iter() {
             let reserve = self
                 .reserves
                 .get(address)
                 .unwrap_or_else(|| panic_with_error!(e, PoolError::InternalReserveNotFound));
+            if supply_breaker > 0 {
+                check_supply_breaker(e, &reserve, supply_breaker);
+            }
+            record_rate_checkpoint(e, &reserve);
             reserve.store(e);
         }
     }
@@ -74,7 +112,7 @@ impl Pool {
     /// * `action_type` - The type of action being performed
     pub fn require_action_allowed(&self, e: &Env, action_type: u32) {
         // disable borrowing or auction cancellation for any non-active pool and disable supplying for any frozen pool
-        if (self.config.status > 1 && (action_type == 4 || action_type == 9))
+        if (self.config.status > 1 && (action_type == 4 || action_type == 9 || action_type == 11))
             || (self.config.status > 3 && (action_type == 2 || action_type == 0))
         {
             panic_with_error!(e, PoolError::InvalidPoolStatus);
@@ -111,24 +149,345 @@ impl Pool {
 
     /// Load a price from the Pool's oracle. Returns a cached version if one already exists.
     ///
+    /// Assets with an exchange rate config are priced as the oracle price of their base asset
+    /// multiplied by the exchange rate. Partner pool bTokens are priced as the oracle price of
+    /// their base asset multiplied by the partner pool's bToken rate.
+    ///
     /// ### Arguments
     /// * asset - The address of the underlying asset
     ///
+    /// If the oracle no longer reports a price for the asset, the asset enters a pricing emergency
+    /// and the last cached price is used instead.
+    ///
     /// ### Panics
-    /// If the price is stale
+    /// If the price or exchange rate is stale, or if no price is available for the asset
     pub fn load_price(&mut self, e: &Env, asset: &Address) -> i128 {
         if let Some(price) = self.prices.get(asset.clone()) {
             return price;
         }
-        let oracle_client = PriceFeedClient::new(e, &self.config.oracle);
-        let oracle_asset = Asset::Stellar(asset.clone());
-        let price_data = oracle_client.lastprice(&oracle_asset).unwrap_optimized();
-        if price_data.timestamp + 24 * 60 * 60 < e.ledger().timestamp() {
-            panic_with_error!(e, PoolError::StalePrice);
+        let (price, cached_at) = match storage::get_exchange_rate_config(e, asset) {
+            Some(config) => {
+                let (base_price, cached_at) = fetch_oracle_price(
+                    e,
+                    &self.config.oracle,
+                    &config.base_asset,
+                    !self.simulation,
+                );
+                let (rate, rate_timestamp) =
+                    ExchangeRateClient::new(e, &config.rate_contract).exchange_rate();
+                if rate <= 0 || rate_timestamp + config.max_age < e.ledger().timestamp() {
+                    panic_with_error!(e, PoolError::StaleExchangeRate);
+                }
+                let price = base_price
+                    .fixed_mul_floor(rate, SCALAR_9)
+                    .unwrap_optimized();
+                (price, cached_at)
+            }
+            None => match storage::get_partner_collateral(e, asset) {
+                Some(config) => {
+                    let (base_price, cached_at) = fetch_oracle_price(
+                        e,
+                        &self.config.oracle,
+                        &config.base_asset,
+                        !self.simulation,
+                    );
+                    let b_rate = PartnerPoolClient::new(e, &config.partner_pool)
+                        .get_b_rate(&config.base_asset);
+                    let price = base_price
+                        .fixed_mul_floor(b_rate, SCALAR_9)
+                        .unwrap_optimized();
+                    (price, cached_at)
+                }
+                None => fetch_oracle_price(e, &self.config.oracle, asset, !self.simulation),
+            },
+        };
+        if check_price_breaker(e, asset, price, !self.simulation) {
+            self.simulated_restrictions.push_back(asset.clone());
+        }
+        self.prices.set(asset.clone(), price);
+        if let Some(cached_at) = cached_at {
+            self.emergency_prices.set(asset.clone(), cached_at);
+        }
+        if let Some(band) = storage::get_peg_band(e, asset) {
+            self.peg_bands.set(asset.clone(), band);
+        }
+        price
+    }
+
+    /// Load the price used to value an asset as collateral. Returns the same price as
+    /// `load_price`, except:
+    /// * If the asset has a peg band, the price is capped at 1.00 + band
+    /// * If the asset is in a pricing emergency, the cached price decays linearly to zero over
+    ///   PRICE_EMERGENCY_DECAY seconds from when it was reported
+    ///
+    /// ### Arguments
+    /// * asset - The address of the underlying asset
+    pub fn load_collateral_price(&mut self, e: &Env, asset: &Address) -> i128 {
+        let mut price = self.load_price(e, asset);
+        if let Some(band) = self.peg_bands.get(asset.clone()) {
+            let peg = 10i128.pow(self.load_price_decimals(e));
+            let ceiling = peg + peg.fixed_mul_floor(i128(band), SCALAR_7).unwrap_optimized();
+            price = price.min(ceiling);
+        }
+        match self.emergency_prices.get(asset.clone()) {
+            Some(cached_at) => {
+                let elapsed = e.ledger().timestamp().saturating_sub(cached_at);
+                if elapsed >= PRICE_EMERGENCY_DECAY {
+                    0
+                } else {
+                    price
+                        .fixed_mul_floor(
+                            i128(PRICE_EMERGENCY_DECAY - elapsed),
+                            i128(PRICE_EMERGENCY_DECAY),
+                        )
+                        .unwrap_optimized()
+                }
+            }
+            None => price,
+        }
+    }
+
+    /// Load the price used to value an asset as a liability. Returns the same price as
+    /// `load_price`, unless the asset has a peg band, in which case the price is floored at
+    /// 1.00 - band.
+    ///
+    /// ### Arguments
+    /// * asset - The address of the underlying asset
+    pub fn load_liability_price(&mut self, e: &Env, asset: &Address) -> i128 {
+        let price = self.load_price(e, asset);
+        match self.peg_bands.get(asset.clone()) {
+            Some(band) => {
+                let peg = 10i128.pow(self.load_price_decimals(e));
+                let floor = peg - peg.fixed_mul_ceil(i128(band), SCALAR_7).unwrap_optimized();
+                price.max(floor)
+            }
+            None => price,
+        }
+    }
+
+    /// Load a user's positions from the ledger. Users loaded by a simulation do not checkpoint
+    /// emissions or interest as their positions change.
+    ///
+    /// ### Arguments
+    /// * address - The address of the user
+    pub fn load_user(&self, e: &Env, address: &Address) -> User {
+        if self.simulation {
+            User::load_for_simulation(e, address)
+        } else {
+            User::load(e, address)
+        }
+    }
+
+    /// Check if borrowing from a reserve is restricted by the price breaker. Only prices loaded
+    /// so far are checked against the price breaker.
+    ///
+    /// ### Arguments
+    /// * asset - The address of the underlying asset
+    pub fn is_restricted(&self, e: &Env, asset: &Address) -> bool {
+        self.simulated_restrictions.contains(asset) || storage::get_reserve_restricted(e, asset)
+    }
+
+    /// Check if an asset is in a pricing emergency, where the oracle no longer reports a price
+    /// for it
+    ///
+    /// ### Arguments
+    /// * asset - The address of the underlying asset
+    pub fn is_price_emergency(&mut self, e: &Env, asset: &Address) -> bool {
+        self.load_price(e, asset);
+        self.emergency_prices.contains_key(asset.clone())
+    }
+}
+
+/// Fetch the price of an asset from the oracle. The price is cached at most once every
+/// PRICE_CACHE_INTERVAL seconds so the pool can fall back to it if the oracle stops reporting a
+/// price for the asset.
+///
+/// Returns the price, and the oracle timestamp of the cached price if the asset is in a pricing
+/// emergency
+///
+/// ### Arguments
+/// * `oracle` - The pool's oracle
+/// * `asset` - The address of the underlying asset
+/// * `store` - If the cached price can be written to the ledger
+///
+/// ### Panics
+/// If the price is stale, or if the oracle has no price for the asset and none is cached
+fn fetch_oracle_price(
+    e: &Env,
+    oracle: &Address,
+    asset: &Address,
+    store: bool,
+) -> (i128, Option<u64>) {
+    let oracle_client = PriceFeedClient::new(e, oracle);
+    let oracle_asset = Asset::Stellar(asset.clone());
+    let cached_price = storage::get_cached_price(e, asset);
+    // an oracle that fails to report a price is treated the same as one that no longer lists
+    // the asset
+    let price_data = match oracle_client.try_lastprice(&oracle_asset) {
+        Ok(Ok(price_data)) => price_data,
+        _ => None,
+    };
+    match price_data {
+        Some(price_data) => {
+            if price_data.timestamp + 24 * 60 * 60 < e.ledger().timestamp() {
+                panic_with_error!(e, PoolError::StalePrice);
+            }
+            match cached_price {
+                _ if !store => {}
+                Some(cached_price)
+                    if !cached_price.emergency
+                        && price_data.timestamp < cached_price.timestamp + PRICE_CACHE_INTERVAL => {
+                }
+                _ => {
+                    if cached_price.is_some_and(|cached_price| cached_price.emergency) {
+                        e.events().publish(
+                            (Symbol::new(e, "price_emergency_end"), asset.clone()),
+                            price_data.price,
+                        );
+                    }
+                    storage::set_cached_price(
+                        e,
+                        asset,
+                        &CachedPrice {
+                            price: price_data.price,
+                            timestamp: price_data.timestamp,
+                            emergency: false,
+                        },
+                    );
+                }
+            }
+            (price_data.price, None)
+        }
+        None => match cached_price {
+            Some(mut cached_price) => {
+                if store && !cached_price.emergency {
+                    cached_price.emergency = true;
+                    storage::set_cached_price(e, asset, &cached_price);
+                    e.events().publish(
+                        (Symbol::new(e, "price_emergency"), asset.clone()),
+                        (cached_price.price, cached_price.timestamp),
+                    );
+                }
+                (cached_price.price, Some(cached_price.timestamp))
+            }
+            None => panic_with_error!(e, PoolError::StalePrice),
+        },
+    }
+}
+
+/// Record the oracle price of an asset and restrict borrowing from its reserve if the price
+/// moved more than the pool's price breaker threshold since the last price was recorded.
+///
+/// The interaction that observes the move is not reverted, so the restriction is persisted.
+///
+/// Returns true if the move would restrict the reserve but `store` is false, so nothing was
+/// written
+///
+/// ### Arguments
+/// * `asset` - The address of the underlying asset
+/// * `price` - The current oracle price of the asset
+/// * `store` - If the price and any restriction can be written to the ledger
+fn check_price_breaker(e: &Env, asset: &Address, price: i128, store: bool) -> bool {
+    let threshold = storage::get_price_breaker(e);
+    if threshold == 0 {
+        return false;
+    }
+    match storage::get_last_price(e, asset) {
+        Some(last_price) if last_price == price => return false,
+        Some(last_price) if last_price > 0 => {
+            let price_move = (price - last_price)
+                .abs()
+                .fixed_div_floor(last_price, SCALAR_7)
+                .unwrap_optimized();
+            if price_move > i128(threshold) && !storage::get_reserve_restricted(e, asset) {
+                if !store {
+                    return true;
+                }
+                storage::set_reserve_restricted(e, asset, true);
+                e.events().publish(
+                    (Symbol::new(e, "price_breaker"), asset.clone()),
+                    (last_price, price),
+                );
+            }
+        }
+        _ => {}
+    }
+    if store {
+        storage::set_last_price(e, asset, &price);
+    }
+    false
+}
+
+/// Check that a reserve's bToken and dToken supplies have not grown by more than `threshold`
+/// since before the reserve was first grown in the current ledger. Soroban has no storage scoped
+/// to a transaction, so the snapshot is kept in temporary storage for the ledger, which covers
+/// every invocation in the transaction and cannot be bypassed by splitting a change across them.
+///
+/// Only actions that increase a supply are limited. Repayments, withdrawals and liquidations that
+/// shrink the supplies are never blocked and do not write a snapshot.
+///
+/// Admin flows that write reserves directly are not checked.
+///
+/// ### Panics
+/// If either supply grew by more than `threshold`
+fn check_supply_breaker(e: &Env, reserve: &Reserve, threshold: u32) {
+    let sequence = e.ledger().sequence();
+    let snapshot = match storage::get_supply_snapshot(e, &reserve.asset) {
+        Some(snapshot) if snapshot.sequence == sequence => snapshot,
+        _ => {
+            let reserve_data = storage::get_res_data(e, &reserve.asset);
+            if reserve.b_supply <= reserve_data.b_supply
+                && reserve.d_supply <= reserve_data.d_supply
+            {
+                return;
+            }
+            let snapshot = SupplySnapshot {
+                sequence,
+                b_supply: reserve_data.b_supply,
+                d_supply: reserve_data.d_supply,
+            };
+            storage::set_supply_snapshot(e, &reserve.asset, &snapshot);
+            snapshot
+        }
+    };
+    if exceeds_supply_increase(snapshot.b_supply, reserve.b_supply, threshold)
+        || exceeds_supply_increase(snapshot.d_supply, reserve.d_supply, threshold)
+    {
+        panic_with_error!(e, PoolError::SupplyBreakerTripped);
+    }
+}
+
+/// Record a checkpoint of a reserve's bToken and dToken rates if at least
+/// `RATE_CHECKPOINT_INTERVAL` has passed since the last checkpoint. Only the latest
+/// `MAX_RATE_CHECKPOINTS` checkpoints are kept, with the oldest dropped first.
+fn record_rate_checkpoint(e: &Env, reserve: &Reserve) {
+    let mut history = storage::get_rate_history(e, &reserve.asset);
+    if let Some(last) = history.last() {
+        if reserve.last_time < last.timestamp + RATE_CHECKPOINT_INTERVAL {
+            return;
         }
-        self.prices.set(asset.clone(), price_data.price);
-        price_data.price
     }
+    if history.len() >= MAX_RATE_CHECKPOINTS {
+        history.pop_front();
+    }
+    history.push_back(RateCheckpoint {
+        timestamp: reserve.last_time,
+        b_rate: reserve.b_rate,
+        d_rate: reserve.d_rate,
+    });
+    storage::set_rate_history(e, &reserve.asset, &history);
+}
+
+/// Check if a supply grew from `start` to `current` by more than `threshold`. Growth from an
+/// empty supply is not limited.
+fn exceeds_supply_increase(start: i128, current: i128, threshold: u32) -> bool {
+    if start <= 0 || current <= start {
+        return false;
+    }
+    let change = (current - start)
+        .fixed_div_floor(start, SCALAR_7)
+        .unwrap_optimized();
+    change > i128(threshold)
 }
 
 #[cfg(test)]
@@ -139,7 +498,11 @@ mod tests {
         Symbol,
     };
 
-    use crate::{pool::User, storage::ReserveData, testutils};
+    use crate::{
+        pool::User,
+        storage::{ExchangeRateConfig, PartnerCollateral, ReserveData},
+        testutils,
+    };
 
     use super::*;
 
@@ -187,10 +550,17 @@ mod tests {
                     b_rate: 0,
                     d_rate: 0,
                     ir_mod: 0,
+                    util_avg: 0,
                     b_supply: 0,
+                    c_supply: 0,
                     d_supply: 0,
                     last_time: 0,
                     backstop_credit: 0,
+                    interest_paid: 0,
+                    interest_earned: 0,
+                    ramp_c_factor: 0,
+                    ramp_start: 0,
+                    ramp_end: 0,
                 },
             );
 
@@ -270,10 +640,17 @@ mod tests {
                     b_rate: 0,
                     d_rate: 0,
                     ir_mod: 0,
+                    util_avg: 0,
                     b_supply: 0,
+                    c_supply: 0,
                     d_supply: 0,
                     last_time: 0,
                     backstop_credit: 0,
+                    interest_paid: 0,
+                    interest_earned: 0,
+                    ramp_c_factor: 0,
+                    ramp_start: 0,
+                    ramp_end: 0,
                 },
             );
 
@@ -349,111 +726,328 @@ mod tests {
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #1206)")]
-    fn test_require_action_allowed_borrow_while_on_ice_panics() {
+    fn test_store_cached_reserves_supply_breaker() {
         let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 123456 * 5,
+            protocol_version: 20,
+            sequence_number: 123456,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
 
+        let bombadil = Address::generate(&e);
         let pool = testutils::create_pool(&e);
-        let oracle = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 123456 * 5;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
         let pool_config = PoolConfig {
-            oracle,
+            oracle: Address::generate(&e),
             bstop_rate: 0_2000000,
-            status: 2,
+            status: 0,
             max_positions: 2,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            let pool = Pool::load(&e);
+            storage::set_supply_breaker(&e, &0_1000000);
 
-            pool.require_action_allowed(&e, 4);
+            // 6% increase of the bToken supply and 8% decrease of the dToken supply
+            let mut pool = Pool::load(&e);
+            let mut reserve = pool.load_reserve(&e, &underlying, true);
+            reserve.b_supply += 6_0000000;
+            reserve.d_supply -= 6_0000000;
+            pool.cache_reserve(reserve);
+            pool.store_cached_reserves(&e);
+
+            let snapshot = storage::get_supply_snapshot(&e, &underlying).unwrap();
+            assert_eq!(snapshot.sequence, 123456);
+            assert_eq!(snapshot.b_supply, 100_0000000);
+            assert_eq!(snapshot.d_supply, 75_0000000);
+        });
+
+        // the breaker resets for a new ledger
+        e.ledger().set(LedgerInfo {
+            timestamp: 123457 * 5,
+            protocol_version: 20,
+            sequence_number: 123457,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        e.as_contract(&pool, || {
+            let mut pool = Pool::load(&e);
+            let mut reserve = pool.load_reserve(&e, &underlying, true);
+            reserve.b_supply += 6_0000000;
+            pool.cache_reserve(reserve);
+            pool.store_cached_reserves(&e);
+
+            let snapshot = storage::get_supply_snapshot(&e, &underlying).unwrap();
+            assert_eq!(snapshot.sequence, 123457);
+            assert_eq!(snapshot.b_supply, 106_0000000);
+            assert_eq!(storage::get_res_data(&e, &underlying).b_supply, 112_0000000);
         });
     }
 
     #[test]
-    fn test_require_action_allowed_borrow_while_active() {
+    #[should_panic(expected = "Error(Contract, #1236)")]
+    fn test_store_cached_reserves_supply_breaker_tripped_across_invocations() {
         let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 123456 * 5,
+            protocol_version: 20,
+            sequence_number: 123456,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
 
+        let bombadil = Address::generate(&e);
         let pool = testutils::create_pool(&e);
-        let oracle = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 123456 * 5;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
         let pool_config = PoolConfig {
-            oracle,
+            oracle: Address::generate(&e),
             bstop_rate: 0_2000000,
-            status: 1,
+            status: 0,
             max_positions: 2,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            let pool = Pool::load(&e);
-
-            pool.require_action_allowed(&e, 4);
+            storage::set_supply_breaker(&e, &0_1000000);
+
+            // two 6% increases of the dToken supply in the same ledger
+            for _ in 0..2 {
+                let mut pool = Pool::load(&e);
+                let mut reserve = pool.load_reserve(&e, &underlying, true);
+                reserve.d_supply += 4_5000000;
+                pool.cache_reserve(reserve);
+                pool.store_cached_reserves(&e);
+            }
         });
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #1206)")]
-    fn test_require_action_allowed_cancel_liquidation_while_on_ice_panics() {
+    fn test_store_cached_reserves_supply_breaker_ignores_decreases() {
         let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 123456 * 5,
+            protocol_version: 20,
+            sequence_number: 123456,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
 
+        let bombadil = Address::generate(&e);
         let pool = testutils::create_pool(&e);
-        let oracle = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 123456 * 5;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
         let pool_config = PoolConfig {
-            oracle,
+            oracle: Address::generate(&e),
             bstop_rate: 0_2000000,
-            status: 2,
+            status: 0,
             max_positions: 2,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            let pool = Pool::load(&e);
+            storage::set_supply_breaker(&e, &0_1000000);
 
-            pool.require_action_allowed(&e, 9);
+            // a 50% decrease of both supplies, like a large repayment and withdrawal
+            let mut pool = Pool::load(&e);
+            let mut reserve = pool.load_reserve(&e, &underlying, true);
+            reserve.b_supply -= 50_0000000;
+            reserve.d_supply -= 37_5000000;
+            pool.cache_reserve(reserve);
+            pool.store_cached_reserves(&e);
+
+            assert!(storage::get_supply_snapshot(&e, &underlying).is_none());
+            let reserve_data = storage::get_res_data(&e, &underlying);
+            assert_eq!(reserve_data.b_supply, 50_0000000);
+            assert_eq!(reserve_data.d_supply, 37_5000000);
         });
     }
 
     #[test]
-    fn test_require_action_allowed_cancel_liquidation_while_active() {
+    fn test_store_cached_reserves_records_rate_checkpoints() {
         let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 123456 * 5,
+            protocol_version: 20,
+            sequence_number: 123456,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
 
+        let bombadil = Address::generate(&e);
         let pool = testutils::create_pool(&e);
-        let oracle = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 123456 * 5;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
         let pool_config = PoolConfig {
-            oracle,
+            oracle: Address::generate(&e),
             bstop_rate: 0_2000000,
-            status: 1,
+            status: 0,
             max_positions: 2,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            let pool = Pool::load(&e);
 
-            pool.require_action_allowed(&e, 9);
+            let mut pool = Pool::load(&e);
+            let reserve = pool.load_reserve(&e, &underlying, true);
+            pool.cache_reserve(reserve);
+            pool.store_cached_reserves(&e);
+
+            let history = storage::get_rate_history(&e, &underlying);
+            assert_eq!(history.len(), 1);
+            let checkpoint = history.get_unchecked(0);
+            assert_eq!(checkpoint.timestamp, 123456 * 5);
+            assert_eq!(checkpoint.b_rate, reserve_data.b_rate);
+            assert_eq!(checkpoint.d_rate, reserve_data.d_rate);
+        });
+
+        // no checkpoint is taken within the interval of the last one
+        e.ledger().set(LedgerInfo {
+            timestamp: 123456 * 5 + RATE_CHECKPOINT_INTERVAL - 1,
+            protocol_version: 20,
+            sequence_number: 123457,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        e.as_contract(&pool, || {
+            let mut pool = Pool::load(&e);
+            let reserve = pool.load_reserve(&e, &underlying, true);
+            pool.cache_reserve(reserve);
+            pool.store_cached_reserves(&e);
+
+            assert_eq!(storage::get_rate_history(&e, &underlying).len(), 1);
+        });
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 123456 * 5 + RATE_CHECKPOINT_INTERVAL,
+            protocol_version: 20,
+            sequence_number: 123458,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        e.as_contract(&pool, || {
+            let mut pool = Pool::load(&e);
+            let reserve = pool.load_reserve(&e, &underlying, true);
+            let b_rate = reserve.b_rate;
+            let d_rate = reserve.d_rate;
+            pool.cache_reserve(reserve);
+            pool.store_cached_reserves(&e);
+
+            let history = storage::get_rate_history(&e, &underlying);
+            assert_eq!(history.len(), 2);
+            let checkpoint = history.get_unchecked(1);
+            assert_eq!(checkpoint.timestamp, 123456 * 5 + RATE_CHECKPOINT_INTERVAL);
+            assert_eq!(checkpoint.b_rate, b_rate);
+            assert_eq!(checkpoint.d_rate, d_rate);
+            assert!(checkpoint.b_rate > reserve_data.b_rate);
+            assert!(checkpoint.d_rate > reserve_data.d_rate);
         });
     }
 
     #[test]
-    #[should_panic(expected = "Error(Contract, #1206)")]
-    fn test_require_action_allowed_supply_while_frozen() {
+    fn test_store_cached_reserves_rate_checkpoints_drop_oldest() {
         let e = Env::default();
+        e.mock_all_auths();
+        e.budget().reset_unlimited();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 123456 * 5,
+            protocol_version: 20,
+            sequence_number: 123456,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
 
+        let bombadil = Address::generate(&e);
         let pool = testutils::create_pool(&e);
-        let oracle = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 123456 * 5;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
         let pool_config = PoolConfig {
-            oracle,
+            oracle: Address::generate(&e),
             bstop_rate: 0_2000000,
-            status: 4,
+            status: 0,
             max_positions: 2,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
-            let pool = Pool::load(&e);
+            let mut history = vec![&e];
+            for i in 0..MAX_RATE_CHECKPOINTS {
+                history.push_back(RateCheckpoint {
+                    timestamp: u64::from(i) * 60 * 60,
+                    b_rate: 1_000_000_000,
+                    d_rate: 1_000_000_000,
+                });
+            }
+            storage::set_rate_history(&e, &underlying, &history);
 
-            pool.require_action_allowed(&e, 0);
+            let mut pool = Pool::load(&e);
+            let reserve = pool.load_reserve(&e, &underlying, true);
+            pool.cache_reserve(reserve);
+            pool.store_cached_reserves(&e);
+
+            let history_post = storage::get_rate_history(&e, &underlying);
+            assert_eq!(history_post.len(), MAX_RATE_CHECKPOINTS);
+            assert_eq!(history_post.first_unchecked(), history.get_unchecked(1));
+            assert_eq!(history_post.last_unchecked().timestamp, 123456 * 5);
+            assert_eq!(history_post.last_unchecked().b_rate, reserve_data.b_rate);
         });
     }
 
     #[test]
     #[should_panic(expected = "Error(Contract, #1206)")]
-    fn test_require_action_allowed_supply_collateral_while_frozen() {
+    fn test_require_action_allowed_borrow_while_on_ice_panics() {
         let e = Env::default();
 
         let pool = testutils::create_pool(&e);
@@ -461,19 +1055,20 @@ mod tests {
         let pool_config = PoolConfig {
             oracle,
             bstop_rate: 0_2000000,
-            status: 4,
+            status: 2,
             max_positions: 2,
         };
         e.as_contract(&pool, || {
             storage::set_pool_config(&e, &pool_config);
             let pool = Pool::load(&e);
 
-            pool.require_action_allowed(&e, 2);
+            pool.require_action_allowed(&e, 4);
         });
     }
 
     #[test]
-    fn test_require_action_allowed_can_withdrawal_and_repay_while_frozen() {
+    #[should_panic(expected = "Error(Contract, #1206)")]
+    fn test_require_action_allowed_loop_while_on_ice_panics() {
         let e = Env::default();
 
         let pool = testutils::create_pool(&e);
@@ -481,7 +1076,130 @@ mod tests {
         let pool_config = PoolConfig {
             oracle,
             bstop_rate: 0_2000000,
-            status: 4,
+            status: 2,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let pool = Pool::load(&e);
+
+            pool.require_action_allowed(&e, 11);
+        });
+    }
+
+    #[test]
+    fn test_require_action_allowed_borrow_while_active() {
+        let e = Env::default();
+
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e);
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 1,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let pool = Pool::load(&e);
+
+            pool.require_action_allowed(&e, 4);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1206)")]
+    fn test_require_action_allowed_cancel_liquidation_while_on_ice_panics() {
+        let e = Env::default();
+
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e);
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 2,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let pool = Pool::load(&e);
+
+            pool.require_action_allowed(&e, 9);
+        });
+    }
+
+    #[test]
+    fn test_require_action_allowed_cancel_liquidation_while_active() {
+        let e = Env::default();
+
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e);
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 1,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let pool = Pool::load(&e);
+
+            pool.require_action_allowed(&e, 9);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1206)")]
+    fn test_require_action_allowed_supply_while_frozen() {
+        let e = Env::default();
+
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e);
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 4,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let pool = Pool::load(&e);
+
+            pool.require_action_allowed(&e, 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1206)")]
+    fn test_require_action_allowed_supply_collateral_while_frozen() {
+        let e = Env::default();
+
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e);
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 4,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let pool = Pool::load(&e);
+
+            pool.require_action_allowed(&e, 2);
+        });
+    }
+
+    #[test]
+    fn test_require_action_allowed_can_withdrawal_and_repay_while_frozen() {
+        let e = Env::default();
+
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e);
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 4,
             max_positions: 2,
         };
         e.as_contract(&pool, || {
@@ -496,6 +1214,225 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_load_price_trips_price_breaker() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let asset_0 = Address::generate(&e);
+        let asset_1 = Address::generate(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(asset_0.clone()),
+                Asset::Stellar(asset_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 2_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_price_breaker(&e, &0_2000000);
+
+            let mut pool = Pool::load(&e);
+            pool.load_price(&e, &asset_0);
+            pool.load_price(&e, &asset_1);
+            assert_eq!(storage::get_last_price(&e, &asset_0), Some(1_0000000));
+            assert_eq!(storage::get_last_price(&e, &asset_1), Some(2_0000000));
+
+            // asset 0 moves 20% and asset 1 moves 25%
+            oracle_client.set_price_stable(&vec![&e, 1_2000000, 1_5000000]);
+            let mut pool = Pool::load(&e);
+            let price = pool.load_price(&e, &asset_0);
+            assert_eq!(price, 1_2000000);
+            let price = pool.load_price(&e, &asset_1);
+            assert_eq!(price, 1_5000000);
+
+            assert!(!storage::get_reserve_restricted(&e, &asset_0));
+            assert!(storage::get_reserve_restricted(&e, &asset_1));
+            assert_eq!(storage::get_last_price(&e, &asset_0), Some(1_2000000));
+            assert_eq!(storage::get_last_price(&e, &asset_1), Some(1_5000000));
+        });
+    }
+
+    #[test]
+    fn test_load_price_emergency() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.ledger().set(LedgerInfo {
+            timestamp: 1_000_000,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let asset_0 = Address::generate(&e);
+        let asset_1 = Address::generate(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(asset_0.clone()),
+                Asset::Stellar(asset_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 2_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+            pool.load_price(&e, &asset_0);
+            pool.load_price(&e, &asset_1);
+            assert!(!pool.is_price_emergency(&e, &asset_0));
+            assert_eq!(
+                storage::get_cached_price(&e, &asset_0),
+                Some(CachedPrice {
+                    price: 1_0000000,
+                    timestamp: 1_000_000,
+                    emergency: false,
+                })
+            );
+
+            // the cached price is not updated within the cache interval
+            e.ledger().set(LedgerInfo {
+                timestamp: 1_000_000 + PRICE_CACHE_INTERVAL - 1,
+                ..e.ledger().get()
+            });
+            oracle_client.set_price_stable(&vec![&e, 1_1000000, 2_0000000]);
+            let mut pool = Pool::load(&e);
+            assert_eq!(pool.load_price(&e, &asset_0), 1_1000000);
+            assert_eq!(
+                storage::get_cached_price(&e, &asset_0).unwrap().price,
+                1_0000000
+            );
+
+            // the pool moves to an oracle that does not list asset 0
+            e.ledger().set(LedgerInfo {
+                timestamp: 1_000_000 + PRICE_CACHE_INTERVAL,
+                ..e.ledger().get()
+            });
+            let (new_oracle, new_oracle_client) = testutils::create_mock_oracle(&e);
+            new_oracle_client.set_data(
+                &bombadil,
+                &Asset::Other(Symbol::new(&e, "USD")),
+                &vec![&e, Asset::Stellar(asset_1.clone())],
+                &7,
+                &300,
+            );
+            new_oracle_client.set_price_stable(&vec![&e, 2_0000000]);
+            storage::set_pool_config(
+                &e,
+                &PoolConfig {
+                    oracle: new_oracle,
+                    ..pool_config.clone()
+                },
+            );
+            let mut pool = Pool::load(&e);
+            assert_eq!(pool.load_price(&e, &asset_0), 1_0000000);
+            assert!(pool.is_price_emergency(&e, &asset_0));
+            assert!(!pool.is_price_emergency(&e, &asset_1));
+            assert!(storage::get_cached_price(&e, &asset_0).unwrap().emergency);
+
+            // collateral valued at the cached price decays over time
+            e.ledger().set(LedgerInfo {
+                timestamp: 1_000_000 + PRICE_EMERGENCY_DECAY / 7,
+                ..e.ledger().get()
+            });
+            let mut pool = Pool::load(&e);
+            assert_eq!(pool.load_collateral_price(&e, &asset_0), 0_8571428);
+            assert_eq!(pool.load_collateral_price(&e, &asset_1), 2_0000000);
+            e.ledger().set(LedgerInfo {
+                timestamp: 1_000_000 + PRICE_EMERGENCY_DECAY,
+                ..e.ledger().get()
+            });
+            let mut pool = Pool::load(&e);
+            assert_eq!(pool.load_collateral_price(&e, &asset_0), 0);
+            assert_eq!(pool.load_price(&e, &asset_0), 1_0000000);
+
+            // the pool moves back to an oracle that lists asset 0
+            oracle_client.set_price_stable(&vec![&e, 0_9000000, 2_0000000]);
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+            assert_eq!(pool.load_collateral_price(&e, &asset_0), 0_9000000);
+            assert!(!pool.is_price_emergency(&e, &asset_0));
+            assert_eq!(
+                storage::get_cached_price(&e, &asset_0),
+                Some(CachedPrice {
+                    price: 0_9000000,
+                    timestamp: 1_000_000 + PRICE_EMERGENCY_DECAY,
+                    emergency: false,
+                })
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1210)")]
+    fn test_load_price_missing_without_cache() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let asset_0 = Address::generate(&e);
+        let asset_1 = Address::generate(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![&e, Asset::Stellar(asset_1.clone())],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let mut pool = Pool::load(&e);
+            pool.load_price(&e, &asset_0);
+        });
+    }
+
     #[test]
     fn test_load_price_decimals() {
         let e = Env::default();
@@ -569,6 +1506,67 @@ mod tests {
             oracle_client.set_price_stable(&vec![&e, 789, 101112]);
             let price = pool.load_price(&e, &asset_0);
             assert_eq!(price, 123);
+
+            // verify the snapshot is scoped to the loaded pool
+            let price = pool.load_price(&e, &asset_1);
+            assert_eq!(price, 456);
+            let mut new_pool = Pool::load(&e);
+            let price = new_pool.load_price(&e, &asset_0);
+            assert_eq!(price, 789);
+        });
+    }
+
+    #[test]
+    fn test_load_price_peg_band() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let asset_0 = Address::generate(&e);
+        let asset_1 = Address::generate(&e);
+        let asset_2 = Address::generate(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(asset_0.clone()),
+                Asset::Stellar(asset_1.clone()),
+                Asset::Stellar(asset_2.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0500000, 0_9500000, 1_0500000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_peg_band(&e, &asset_0, 0_0200000);
+            storage::set_peg_band(&e, &asset_1, 0_0200000);
+            let mut pool = Pool::load(&e);
+
+            // asset 0 is over its peg, so collateral is capped
+            assert_eq!(pool.load_price(&e, &asset_0), 1_0500000);
+            assert_eq!(pool.load_collateral_price(&e, &asset_0), 1_0200000);
+            assert_eq!(pool.load_liability_price(&e, &asset_0), 1_0500000);
+
+            // asset 1 is under its peg, so liabilities are floored
+            assert_eq!(pool.load_price(&e, &asset_1), 0_9500000);
+            assert_eq!(pool.load_collateral_price(&e, &asset_1), 0_9500000);
+            assert_eq!(pool.load_liability_price(&e, &asset_1), 0_9800000);
+
+            // asset 2 has no peg band
+            assert_eq!(pool.load_collateral_price(&e, &asset_2), 1_0500000);
+            assert_eq!(pool.load_liability_price(&e, &asset_2), 1_0500000);
         });
     }
 
@@ -616,6 +1614,195 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_load_price_exchange_rate() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let base_asset = Address::generate(&e);
+        let yield_asset = Address::generate(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+        let (rate_contract, rate_client) = testutils::create_mock_exchange_rate(&e);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![&e, Asset::Stellar(base_asset.clone())],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000]);
+        rate_client.set_rate(&1_050_000_000, &900);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_exchange_rate_config(
+                &e,
+                &yield_asset,
+                &ExchangeRateConfig {
+                    rate_contract,
+                    base_asset: base_asset.clone(),
+                    max_age: 3600,
+                },
+            );
+            let mut pool = Pool::load(&e);
+
+            let price = pool.load_price(&e, &yield_asset);
+            assert_eq!(price, 2_1000000);
+
+            let price = pool.load_price(&e, &base_asset);
+            assert_eq!(price, 2_0000000);
+        });
+    }
+
+    #[test]
+    fn test_load_price_partner_collateral() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let partner_pool = testutils::create_pool(&e);
+        let partner_b_token = Address::generate(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (base_asset, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.b_rate = 1_100_000_000;
+        reserve_data.last_time = 1000;
+        testutils::create_reserve(
+            &e,
+            &partner_pool,
+            &base_asset,
+            &reserve_config,
+            &reserve_data,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![&e, Asset::Stellar(base_asset.clone())],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&partner_pool, || {
+            storage::set_pool_config(&e, &pool_config);
+        });
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_partner_collateral(
+                &e,
+                &partner_b_token,
+                &PartnerCollateral {
+                    partner_pool: partner_pool.clone(),
+                    base_asset: base_asset.clone(),
+                },
+            );
+            let mut pool = Pool::load(&e);
+
+            let price = pool.load_price(&e, &partner_b_token);
+            assert_eq!(price, 2_2000000);
+
+            let price = pool.load_price(&e, &base_asset);
+            assert_eq!(price, 2_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1232)")]
+    fn test_load_price_panics_if_exchange_rate_stale() {
+        let e = Env::default();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 1000 + 3600 + 1,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let base_asset = Address::generate(&e);
+        let yield_asset = Address::generate(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+        let (rate_contract, rate_client) = testutils::create_mock_exchange_rate(&e);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![&e, Asset::Stellar(base_asset.clone())],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000]);
+        rate_client.set_rate(&1_050_000_000, &1000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_exchange_rate_config(
+                &e,
+                &yield_asset,
+                &ExchangeRateConfig {
+                    rate_contract,
+                    base_asset,
+                    max_age: 3600,
+                },
+            );
+            let mut pool = Pool::load(&e);
+
+            pool.load_price(&e, &yield_asset);
+        });
+    }
+
     #[test]
     fn test_require_under_max_empty() {
         let e = Env::default();
@@ -626,6 +1813,7 @@ mod tests {
         let (oracle, _) = testutils::create_mock_oracle(&e);
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         let pool_config = PoolConfig {
@@ -658,6 +1846,7 @@ mod tests {
         let (oracle, _) = testutils::create_mock_oracle(&e);
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         let pool_config = PoolConfig {
@@ -693,6 +1882,7 @@ mod tests {
         let (oracle, _) = testutils::create_mock_oracle(&e);
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         let pool_config = PoolConfig {
@@ -729,6 +1919,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         let (oracle, _) = testutils::create_mock_oracle(&e);
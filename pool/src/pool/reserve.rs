@@ -3,12 +3,12 @@ use soroban_fixed_point_math::FixedPoint;
 use soroban_sdk::{contracttype, panic_with_error, unwrap::UnwrapOptimized, Address, Env};
 
 use crate::{
-    constants::{SCALAR_7, SCALAR_9},
+    constants::{SCALAR_7, SCALAR_9, VIRTUAL_B_TOKENS},
     errors::PoolError,
     storage::{self, PoolConfig, ReserveData},
 };
 
-use super::interest::calc_accrual;
+use super::interest::{calc_accrual, calc_util_avg};
 
 #[derive(Clone)]
 #[contracttype]
@@ -23,9 +23,16 @@ pub struct Reserve {
     pub d_rate: i128,          // the conversion rate from dToken to underlying (9 decimals)
     pub b_rate: i128,          // the conversion rate from bToken to underlying (9 decimals)
     pub ir_mod: i128,          // the interest rate curve modifier (9 decimals)
+    pub util_avg: i128,        // the time-weighted utilization rate (7 decimals)
     pub b_supply: i128,        // the total supply of b tokens
+    pub c_supply: i128,        // the total supply of b tokens used as collateral
     pub d_supply: i128,        // the total supply of d tokens
     pub backstop_credit: i128, // the total amount of underlying tokens owed to the backstop
+    pub interest_paid: i128,   // the lifetime amount of interest paid by borrowers
+    pub interest_earned: i128, // the lifetime amount of interest earned by suppliers
+    pub ramp_c_factor: u32,    // the collateral factor a reduction is ramping down from
+    pub ramp_start: u64,       // the time the collateral factor reduction started ramping
+    pub ramp_end: u64,         // the time the collateral factor reduction is fully applied
 }
 
 impl Reserve {
@@ -47,16 +54,23 @@ impl Reserve {
             asset: asset.clone(),
             index: reserve_config.index,
             l_factor: reserve_config.l_factor,
-            c_factor: reserve_config.c_factor,
+            c_factor: calc_ramped_c_factor(e, reserve_config.c_factor, &reserve_data),
             max_util: reserve_config.max_util,
             last_time: reserve_data.last_time,
             scalar: 10i128.pow(reserve_config.decimals),
             d_rate: reserve_data.d_rate,
             b_rate: reserve_data.b_rate,
             ir_mod: reserve_data.ir_mod,
+            util_avg: reserve_data.util_avg,
             b_supply: reserve_data.b_supply,
+            c_supply: reserve_data.c_supply,
             d_supply: reserve_data.d_supply,
             backstop_credit: reserve_data.backstop_credit,
+            interest_paid: reserve_data.interest_paid,
+            interest_earned: reserve_data.interest_earned,
+            ramp_c_factor: reserve_data.ramp_c_factor,
+            ramp_start: reserve_data.ramp_start,
+            ramp_end: reserve_data.ramp_end,
         };
 
         // short circuit if the reserve has already been updated this ledger
@@ -64,12 +78,15 @@ impl Reserve {
             return reserve;
         }
 
+        let delta_time = e.ledger().timestamp() - reserve.last_time;
         if reserve.b_supply == 0 {
+            reserve.util_avg = calc_util_avg(reserve.util_avg, 0, delta_time);
             reserve.last_time = e.ledger().timestamp();
             return reserve;
         }
 
         let cur_util = reserve.utilization();
+        reserve.util_avg = calc_util_avg(reserve.util_avg, cur_util, delta_time);
         if cur_util == 0 {
             // if there are no assets borrowed, we don't need to update the reserve
             reserve.last_time = e.ledger().timestamp();
@@ -80,12 +97,12 @@ impl Reserve {
             e,
             &reserve_config,
             cur_util,
+            reserve.util_avg,
             reserve.ir_mod,
             reserve.last_time,
         );
         reserve.ir_mod = new_ir_mod;
 
-        let pre_update_supply = reserve.total_supply();
         let pre_update_liabilities = reserve.total_liabilities();
 
         reserve.d_rate = loan_accrual
@@ -103,27 +120,38 @@ impl Reserve {
                     .unwrap_optimized();
                 reserve.backstop_credit += new_backstop_credit;
             }
-            reserve.b_rate = (pre_update_supply + accrued_interest - new_backstop_credit)
-                .fixed_div_floor(reserve.b_supply, SCALAR_9)
-                .unwrap_optimized();
+            reserve.credit_b_rate(accrued_interest - new_backstop_credit);
+            reserve.interest_paid += accrued_interest;
+            reserve.interest_earned += accrued_interest - new_backstop_credit;
         }
 
         reserve.last_time = e.ledger().timestamp();
         reserve
     }
 
-    /// Store the updated reserve to the ledger.
+    /// Store the updated reserve to the ledger. Nothing is written if the reserve data is
+    /// unchanged, such as when the reserve was already updated earlier in the ledger and the
+    /// interaction did not change its supplies.
     pub fn store(&self, e: &Env) {
         let reserve_data = ReserveData {
             d_rate: self.d_rate,
             b_rate: self.b_rate,
             ir_mod: self.ir_mod,
+            util_avg: self.util_avg,
             b_supply: self.b_supply,
+            c_supply: self.c_supply,
             d_supply: self.d_supply,
             backstop_credit: self.backstop_credit,
+            interest_paid: self.interest_paid,
+            interest_earned: self.interest_earned,
+            ramp_c_factor: self.ramp_c_factor,
+            ramp_start: self.ramp_start,
+            ramp_end: self.ramp_end,
             last_time: self.last_time,
         };
-        storage::set_res_data(e, &self.asset, &reserve_data);
+        if storage::get_res_data(e, &self.asset) != reserve_data {
+            storage::set_res_data(e, &self.asset, &reserve_data);
+        }
     }
 
     /// Fetch the current utilization rate for the reserve normalized to 7 decimals
@@ -140,6 +168,63 @@ impl Reserve {
         }
     }
 
+    /// Check if the utilization rate is above the given cap. A reserve with liabilities but no
+    /// supply is always above the cap.
+    ///
+    /// ### Arguments
+    /// * `util_cap` - The utilization cap scaled expressed in 7 decimals
+    pub fn is_utilization_above(&self, util_cap: i128) -> bool {
+        if self.d_supply == 0 {
+            false
+        } else if self.b_supply == 0 {
+            true
+        } else {
+            self.utilization() > util_cap
+        }
+    }
+
+    /// Fetch the maximum amount of underlying that can be withdrawn from the reserve without
+    /// raising the utilization rate above the given cap
+    ///
+    /// ### Arguments
+    /// * `util_cap` - The utilization cap scaled expressed in 7 decimals
+    pub fn max_withdrawable(&self, util_cap: i128) -> i128 {
+        let min_supply = self
+            .total_liabilities()
+            .fixed_div_ceil(util_cap, SCALAR_7)
+            .unwrap_optimized();
+        let max_b_tokens = self.b_supply - self.to_b_token_up(min_supply);
+        if max_b_tokens <= 0 {
+            0
+        } else {
+            self.to_asset_from_b_token(max_b_tokens)
+        }
+    }
+
+    /// Credit suppliers with an amount of underlying tokens by adjusting the bToken rate. A
+    /// negative amount is a loss taken by suppliers.
+    ///
+    /// ### Arguments
+    /// * `amount` - The amount of underlying tokens to credit
+    pub fn credit_suppliers(&mut self, amount: i128) {
+        if amount == 0 || self.b_supply == 0 {
+            return;
+        }
+        self.credit_b_rate(amount);
+        self.interest_earned += amount;
+    }
+
+    /// Spread an amount of underlying tokens over the bToken supply, including the virtual
+    /// bTokens, by adjusting the bToken rate
+    ///
+    /// ### Arguments
+    /// * `amount` - The amount of underlying tokens to spread
+    fn credit_b_rate(&mut self, amount: i128) {
+        self.b_rate += amount
+            .fixed_div_floor(self.b_supply + VIRTUAL_B_TOKENS, SCALAR_9)
+            .unwrap_optimized();
+    }
+
     /// Fetch the total liabilities for the reserve in underlying tokens
     pub fn total_liabilities(&self) -> i128 {
         self.to_asset_from_d_token(self.d_supply)
@@ -162,7 +247,8 @@ impl Reserve {
             .unwrap_optimized()
     }
 
-    /// Convert b_tokens to the corresponding asset value
+    /// Convert b_tokens to the corresponding asset value. The virtual bTokens are priced at the same
+    /// bToken rate, so they are included in every conversion.
     ///
     /// ### Arguments
     /// * `b_tokens` - The amount of tokens to convert
@@ -235,6 +321,51 @@ impl Reserve {
             .fixed_div_floor(self.b_rate, SCALAR_9)
             .unwrap_optimized()
     }
+
+    /// Mint b tokens for asset tokens supplied to the reserve and return the supplier's share - round down
+    ///
+    /// The first supply to an empty reserve must be at least the reserve's minimum first supply,
+    /// and the reserve's locked b tokens are taken from it and added to the reserve's b token
+    /// supply without an owner, so they can never be withdrawn.
+    ///
+    /// ### Arguments
+    /// * `amount` - The amount of tokens supplied
+    ///
+    /// ### Panics
+    /// If the first supply to an empty reserve is too small
+    pub fn mint_b_tokens(&mut self, e: &Env, amount: i128) -> i128 {
+        let b_tokens = self.to_b_token_down(amount);
+        if self.b_supply != 0 {
+            return b_tokens;
+        }
+
+        let reserve_config = storage::get_res_config(e, &self.asset);
+        let locked_b_tokens = i128(reserve_config.locked_b_tokens);
+        if amount < reserve_config.min_first_supply
+            || (locked_b_tokens > 0 && b_tokens <= locked_b_tokens)
+        {
+            panic_with_error!(e, PoolError::FirstSupplyTooSmall);
+        }
+        self.b_supply += locked_b_tokens;
+        b_tokens - locked_b_tokens
+    }
+}
+
+/// Calculate the collateral factor in effect for a reserve. A reduction to the collateral factor
+/// is applied linearly between the ramp's start and end time.
+///
+/// ### Arguments
+/// * `c_factor` - The collateral factor in the reserve's config
+/// * `reserve_data` - The reserve's data
+fn calc_ramped_c_factor(e: &Env, c_factor: u32, reserve_data: &ReserveData) -> u32 {
+    let now = e.ledger().timestamp();
+    if now >= reserve_data.ramp_end || reserve_data.ramp_c_factor <= c_factor {
+        return c_factor;
+    }
+    let remaining = i128(reserve_data.ramp_end - now);
+    let ramp_period = i128(reserve_data.ramp_end - reserve_data.ramp_start);
+    let reduction = i128(reserve_data.ramp_c_factor - c_factor);
+    c_factor + (reduction * remaining / ramp_period) as u32
 }
 
 #[cfg(test)]
@@ -281,13 +412,13 @@ mod tests {
             storage::set_pool_config(&e, &pool_config);
             let reserve = Reserve::load(&e, &pool_config, &underlying);
 
-            // (accrual: 1_002_957_369, util: .7864353)
-            assert_eq!(reserve.d_rate, 1_349_657_800);
-            assert_eq!(reserve.b_rate, 1_125_547_124);
+            // (accrual: 1_002_961_753, util: .7864353)
+            assert_eq!(reserve.d_rate, 1_349_663_690);
+            assert_eq!(reserve.b_rate, 1_125_550_217);
             assert_eq!(reserve.ir_mod, 1_044_981_563);
             assert_eq!(reserve.d_supply, 65_0000000);
             assert_eq!(reserve.b_supply, 99_0000000);
-            assert_eq!(reserve.backstop_credit, 0_0517358);
+            assert_eq!(reserve.backstop_credit, 0_0518123);
             assert_eq!(reserve.last_time, 617280);
         });
     }
@@ -330,7 +461,7 @@ mod tests {
             storage::set_pool_config(&e, &pool_config);
             let reserve = Reserve::load(&e, &pool_config, &underlying);
 
-            // (accrual: 1_002_957_369, util: .7864352)q
+            // (accrual: 1_002_961_753, util: .7864352)q
             assert_eq!(reserve.d_rate, 0);
             assert_eq!(reserve.b_rate, 0);
             assert_eq!(reserve.ir_mod, 1_000_000_000);
@@ -341,6 +472,50 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_load_reserve_smooths_util() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 123456 * 5,
+            protocol_version: 20,
+            sequence_number: 123456,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.d_supply = 95_0000000;
+        reserve_data.last_time = 123456 * 5 - 36;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let reserve = Reserve::load(&e, &pool_config, &underlying);
+
+            // a utilization spike only moves the average by the elapsed fraction of the window
+            // (util_avg: .75 * .99 + .95 * .01)
+            assert_eq!(reserve.util_avg, 0_7520000);
+            assert_eq!(reserve.ir_mod, 1_000_000_144);
+            assert_eq!(reserve.last_time, 617280);
+        });
+    }
+
     #[test]
     fn test_load_reserve_zero_util() {
         let e = Env::default();
@@ -425,9 +600,9 @@ mod tests {
             storage::set_pool_config(&e, &pool_config);
             let reserve = Reserve::load(&e, &pool_config, &underlying);
 
-            // (accrual: 1_002_957_369, util: .7864353)
-            assert_eq!(reserve.d_rate, 1_349_657_800);
-            assert_eq!(reserve.b_rate, 1_126_069_708);
+            // (accrual: 1_002_961_753, util: .7864353)
+            assert_eq!(reserve.d_rate, 1_349_663_690);
+            assert_eq!(reserve.b_rate, 1_126_073_573);
             assert_eq!(reserve.ir_mod, 1_044_981_563);
             assert_eq!(reserve.d_supply, 65_0000000);
             assert_eq!(reserve.b_supply, 99_0000000);
@@ -477,13 +652,15 @@ mod tests {
 
             let reserve_data = storage::get_res_data(&e, &underlying);
 
-            // (accrual: 1_002_957_369, util: .7864353)
-            assert_eq!(reserve_data.d_rate, 1_349_657_800);
-            assert_eq!(reserve_data.b_rate, 1_125_547_124);
+            // (accrual: 1_002_961_753, util: .7864353)
+            assert_eq!(reserve_data.d_rate, 1_349_663_690);
+            assert_eq!(reserve_data.b_rate, 1_125_550_217);
             assert_eq!(reserve_data.ir_mod, 1_044_981_563);
             assert_eq!(reserve_data.d_supply, 65_0000000);
             assert_eq!(reserve_data.b_supply, 99_0000000);
-            assert_eq!(reserve_data.backstop_credit, 0_0517358);
+            assert_eq!(reserve_data.backstop_credit, 0_0518123);
+            assert_eq!(reserve_data.interest_paid, 0_2590619);
+            assert_eq!(reserve_data.interest_earned, 0_2072496);
             assert_eq!(reserve_data.last_time, 617280);
         });
     }
@@ -528,6 +705,47 @@ mod tests {
         reserve.require_utilization_below_max(&e);
     }
 
+    #[test]
+    fn test_is_utilization_above() {
+        let e = Env::default();
+
+        let mut reserve = testutils::default_reserve(&e);
+        reserve.b_supply = 100_0000000;
+        reserve.d_supply = 90_0000000;
+        assert!(!reserve.is_utilization_above(0_9000000));
+        assert!(reserve.is_utilization_above(0_8999999));
+
+        reserve.d_supply = 0;
+        reserve.b_supply = 0;
+        assert!(!reserve.is_utilization_above(0_9000000));
+
+        reserve.d_supply = 1;
+        assert!(reserve.is_utilization_above(1_0000000));
+    }
+
+    #[test]
+    fn test_max_withdrawable() {
+        let e = Env::default();
+
+        let mut reserve = testutils::default_reserve(&e);
+        reserve.d_rate = 1_345_678_123;
+        reserve.b_rate = 1_123_456_789;
+        reserve.b_supply = 99_0000000;
+        reserve.d_supply = 65_0000000;
+
+        let result = reserve.max_withdrawable(0_9500000);
+        assert_eq!(result, 19_1495083);
+
+        // withdrawing the max leaves the reserve at the cap
+        reserve.b_supply -= reserve.to_b_token_up(result);
+        assert!(!reserve.is_utilization_above(0_9500000));
+        reserve.b_supply -= 1;
+        assert!(reserve.is_utilization_above(0_9500000));
+
+        // nothing can be withdrawn from a reserve above the cap
+        assert_eq!(reserve.max_withdrawable(0_9000000), 0);
+    }
+
     /***** Token Transfer Math *****/
 
     #[test]
@@ -602,6 +820,62 @@ mod tests {
         assert_eq!(result, 118_5543250);
     }
 
+    #[test]
+    fn test_credit_suppliers() {
+        let e = Env::default();
+
+        let mut reserve = testutils::default_reserve(&e);
+        reserve.b_rate = 1_100_000_000;
+        reserve.b_supply = 99_0000000;
+        reserve.interest_earned = 0;
+
+        reserve.credit_suppliers(10_0000000);
+
+        assert_eq!(reserve.b_rate, 1_201_009_998);
+        assert_eq!(reserve.interest_earned, 10_0000000);
+    }
+
+    #[test]
+    fn test_credit_suppliers_near_empty_reserve() {
+        let e = Env::default();
+
+        let mut reserve = testutils::default_reserve(&e);
+        reserve.b_rate = 1_000_000_000;
+        reserve.b_supply = 1;
+
+        // the virtual bTokens absorb almost the entire credit
+        reserve.credit_suppliers(100_0000000);
+
+        assert_eq!(reserve.b_rate, 999_001_999_000_999);
+        assert_eq!(reserve.to_asset_from_b_token(1), 999_001);
+    }
+
+    #[test]
+    fn test_inflation_attack_on_near_empty_reserve() {
+        let e = Env::default();
+
+        // the attacker is the only supplier and holds a single bToken
+        let mut reserve = testutils::default_reserve(&e);
+        reserve.b_rate = 1_000_000_000;
+        reserve.b_supply = 1;
+        reserve.d_supply = 0;
+
+        // the attacker credits suppliers to inflate the bToken rate
+        reserve.credit_suppliers(100_0000000);
+
+        // the victim loses less than the value of one bToken to rounding, which the attacker paid
+        // over 1000 times as much to create
+        let b_tokens = reserve.mint_b_tokens(&e, 50_0000000);
+        reserve.b_supply += b_tokens;
+        assert_eq!(b_tokens, 500);
+        let victim_loss = 50_0000000 - reserve.to_asset_from_b_token(b_tokens);
+        assert_eq!(victim_loss, 499001);
+        assert!(victim_loss * 1000 < 100_0000000);
+
+        // the attacker can only redeem the share of the credit their bToken absorbed
+        assert_eq!(reserve.to_asset_from_b_token(1), 999_001);
+    }
+
     #[test]
     fn test_total_supply() {
         let e = Env::default();
@@ -671,4 +945,74 @@ mod tests {
 
         assert_eq!(result, 1_1234566);
     }
+
+    #[test]
+    fn test_mint_b_tokens_first_supply_locks_b_tokens() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.min_first_supply = 1_0000000;
+        reserve_config.locked_b_tokens = 1000;
+        reserve_data.b_supply = 0;
+        reserve_data.d_supply = 0;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 5,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut reserve = Reserve::load(&e, &pool_config, &underlying);
+
+            let result = reserve.mint_b_tokens(&e, 1_0000000);
+
+            assert_eq!(result, 1_0000000 - 1000);
+            assert_eq!(reserve.b_supply, 1000);
+            assert_eq!(storage::get_user_positions(&e, &pool).supply.len(), 0);
+
+            // later supplies mint the full amount of b tokens
+            let result = reserve.mint_b_tokens(&e, 1);
+            assert_eq!(result, 1);
+            assert_eq!(reserve.b_supply, 1000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1235)")]
+    fn test_mint_b_tokens_first_supply_under_min() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (mut reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_config.min_first_supply = 1_0000000;
+        reserve_config.locked_b_tokens = 1000;
+        reserve_data.b_supply = 0;
+        reserve_data.d_supply = 0;
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 5,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut reserve = Reserve::load(&e, &pool_config, &underlying);
+
+            reserve.mint_b_tokens(&e, 0_9999999);
+        });
+    }
 }
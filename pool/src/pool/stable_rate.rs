@@ -0,0 +1,637 @@
+use cast::i128;
+use sep_41_token::TokenClient;
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::{SCALAR_7, SECONDS_PER_YEAR, STABLE_RATE_PREMIUM, STABLE_REBALANCE_DELTA},
+    errors::PoolError,
+    storage::{self, StableDebt},
+};
+
+use super::{
+    actions::RequestType, health_factor::PositionData, interest::calc_interest_rate, Pool, Reserve,
+    User,
+};
+
+/// Borrow from a reserve at a stable rate, set to the reserve's variable borrow rate plus a
+/// premium. Borrowing again at a stable rate from the same reserve blends the existing rate with
+/// the current one, weighted by the amounts owed.
+///
+/// The debt is backed by dTokens held in the user's liabilities, so it is counted towards the
+/// user's health factor and can be liquidated like any other liability.
+///
+/// Returns a tuple of (d_tokens_minted, debt) where:
+/// * d_tokens_minted - The dTokens minted for the borrow
+/// * debt - The user's stable rate debt for the reserve
+///
+/// ### Arguments
+/// * `from` - The address of the user borrowing
+/// * `to` - The address receiving the borrowed tokens
+/// * `asset` - The underlying asset to borrow
+/// * `amount` - The amount of underlying tokens to borrow
+///
+/// ### Panics
+/// If the user has a fixed-term loan for the reserve, or the borrow could not be made as a
+/// regular borrow
+pub fn execute_borrow_stable(
+    e: &Env,
+    from: &Address,
+    to: &Address,
+    asset: &Address,
+    amount: i128,
+) -> (i128, StableDebt) {
+    if from == &e.current_contract_address() || to == &e.current_contract_address() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    if amount <= 0 {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+
+    let mut pool = Pool::load(e);
+    pool.require_action_allowed(e, RequestType::Borrow as u32);
+    // loading the price checks the price breaker before the restriction is checked
+    if pool.is_price_emergency(e, asset) {
+        panic_with_error!(e, PoolError::PriceEmergency);
+    }
+    if storage::get_reserve_restricted(e, asset) {
+        panic_with_error!(e, PoolError::ReserveRestricted);
+    }
+    let mut user = User::load(e, from);
+    let prev_positions_count = user.positions.effective_count();
+    let mut reserve = pool.load_reserve(e, asset, true);
+    // fixed-term and stable rate debt cannot back the same liabilities
+    if storage::get_fixed_loans(e, from).contains_key(reserve.index) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let mut debts = storage::get_stable_debts(e, from);
+    let mut stable_supply = storage::get_stable_supply(e, asset);
+
+    let mut debt = match debts.get(reserve.index) {
+        Some(mut debt) => {
+            stable_supply += checkpoint_stable_debt(e, &mut reserve, &mut user, &mut debt);
+            debt
+        }
+        None => StableDebt {
+            d_tokens: 0,
+            principal: 0,
+            rate: 0,
+            last_time: e.ledger().timestamp(),
+        },
+    };
+
+    let d_tokens_minted = reserve.to_d_token_up(amount);
+    user.add_liabilities(e, &mut reserve, d_tokens_minted);
+    reserve.require_utilization_below_max(e);
+    let rate = calc_stable_rate(e, &reserve);
+    debt.rate = (debt.principal * debt.rate + amount * rate) / (debt.principal + amount);
+    debt.principal += amount;
+    debt.d_tokens += d_tokens_minted;
+    stable_supply += d_tokens_minted;
+    debts.set(reserve.index, debt.clone());
+    pool.cache_reserve(reserve);
+    pool.require_under_max(e, &user.positions, prev_positions_count);
+
+    // min is 1.0000100 to prevent rounding errors
+    let position_data = PositionData::calculate_with_restrictions(e, &mut pool, &user.positions);
+    if position_data.is_hf_under(1_0000100) {
+        panic_with_error!(e, PoolError::InvalidHf);
+    }
+
+    pool.store_cached_reserves(e);
+    user.store(e);
+    storage::set_stable_debts(e, from, &debts);
+    storage::set_stable_supply(e, asset, stable_supply);
+
+    TokenClient::new(e, asset).transfer(&e.current_contract_address(), to, &amount);
+    (d_tokens_minted, debt)
+}
+
+/// Move a user's stable rate debt for a reserve to the variable rate. The debt is first accrued
+/// at its stable rate, after which the dTokens backing it can be repaid like any other liability.
+///
+/// Returns the dTokens moved to the variable rate
+///
+/// ### Arguments
+/// * `from` - The address of the user
+/// * `asset` - The underlying asset of the debt
+///
+/// ### Panics
+/// If the user has no stable rate debt for the reserve, or if called while a submit is in
+/// progress
+pub fn execute_swap_to_variable(e: &Env, from: &Address, asset: &Address) -> i128 {
+    let mut pool = Pool::load(e);
+    let mut user = User::load(e, from);
+    let mut reserve = pool.load_reserve(e, asset, true);
+    let mut debts = storage::get_stable_debts(e, from);
+    let mut debt = debts
+        .get(reserve.index)
+        .unwrap_or_else(|| panic_with_error!(e, PoolError::BadRequest));
+
+    let stable_supply = storage::get_stable_supply(e, asset)
+        + checkpoint_stable_debt(e, &mut reserve, &mut user, &mut debt)
+        - debt.d_tokens;
+    debts.remove(reserve.index);
+    pool.cache_reserve(reserve);
+
+    pool.store_cached_reserves(e);
+    user.store(e);
+    storage::set_stable_debts(e, from, &debts);
+    storage::set_stable_supply(e, asset, stable_supply);
+    debt.d_tokens
+}
+
+/// Rebalance a user's stable rate debt for a reserve to the current stable rate. This can only
+/// be done once the debt's rate deviates from the current stable rate by more than the
+/// rebalance delta.
+///
+/// Returns the new stable rate of the debt
+///
+/// ### Arguments
+/// * `user` - The address of the user whose debt is rebalanced
+/// * `asset` - The underlying asset of the debt
+///
+/// ### Panics
+/// If the user has no stable rate debt for the reserve, or the debt's rate is within the
+/// rebalance delta of the current stable rate
+pub fn execute_rebalance_stable(e: &Env, user: &Address, asset: &Address) -> i128 {
+    let mut pool = Pool::load(e);
+    let mut user_state = User::load(e, user);
+    let mut reserve = pool.load_reserve(e, asset, true);
+    let mut debts = storage::get_stable_debts(e, user);
+    let mut debt = debts
+        .get(reserve.index)
+        .unwrap_or_else(|| panic_with_error!(e, PoolError::BadRequest));
+
+    let rate = calc_stable_rate(e, &reserve);
+    if (debt.rate - rate).abs() <= STABLE_REBALANCE_DELTA {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let stable_supply = storage::get_stable_supply(e, asset)
+        + checkpoint_stable_debt(e, &mut reserve, &mut user_state, &mut debt);
+    debt.rate = rate;
+    if debt.d_tokens == 0 {
+        debts.remove(reserve.index);
+    } else {
+        debts.set(reserve.index, debt);
+    }
+    pool.cache_reserve(reserve);
+
+    pool.store_cached_reserves(e);
+    user_state.store(e);
+    storage::set_stable_debts(e, user, &debts);
+    storage::set_stable_supply(e, asset, stable_supply);
+    rate
+}
+
+/// Fetch the amount of dTokens in a user's liabilities for a reserve that back stable rate debt.
+/// These must be moved to the variable rate before they can be repaid.
+///
+/// ### Arguments
+/// * `user` - The user
+/// * `reserve_index` - The index of the reserve
+pub fn get_stable_d_tokens(e: &Env, user: &User, reserve_index: u32) -> i128 {
+    match storage::get_stable_debts(e, &user.address).get(reserve_index) {
+        Some(debt) => debt.d_tokens.min(user.get_liabilities(reserve_index)),
+        None => 0,
+    }
+}
+
+/// Calculate the stable rate a reserve currently offers
+///
+/// ### Arguments
+/// * `reserve` - The reserve
+pub fn calc_stable_rate(e: &Env, reserve: &Reserve) -> i128 {
+    let reserve_config = storage::get_res_config(e, &reserve.asset);
+    calc_interest_rate(&reserve_config, reserve.utilization(), reserve.ir_mod) + STABLE_RATE_PREMIUM
+}
+
+/// Accrue a user's stable rate debt at its rate, and re-mark the dTokens backing it so they are
+/// worth the amount owed. Suppliers are credited the difference between the debt's stable
+/// interest and the variable interest accrued on its dTokens. If the variable interest is larger,
+/// the shortfall is taken from the backstop's credit instead of from suppliers. Any shortfall
+/// beyond the backstop's credit is a loss taken by suppliers through the bToken rate.
+///
+/// If some of the debt's dTokens were removed from the user, like through a liquidation, the debt
+/// is reduced to the portion still backed by the user's liabilities.
+///
+/// Returns the change in the dTokens backing the debt
+///
+/// ### Arguments
+/// * `reserve` - The reserve of the debt
+/// * `user` - The user with the debt
+/// * `debt` - The user's stable rate debt
+fn checkpoint_stable_debt(
+    e: &Env,
+    reserve: &mut Reserve,
+    user: &mut User,
+    debt: &mut StableDebt,
+) -> i128 {
+    let now = e.ledger().timestamp();
+    let d_tokens = debt.d_tokens.min(user.get_liabilities(reserve.index));
+    let mut owed = debt.principal
+        + debt
+            .principal
+            .fixed_mul_ceil(debt.rate, SCALAR_7)
+            .unwrap_optimized()
+            .fixed_mul_ceil(i128(now - debt.last_time), SECONDS_PER_YEAR)
+            .unwrap_optimized();
+    if d_tokens < debt.d_tokens {
+        owed = owed
+            .fixed_mul_ceil(d_tokens, debt.d_tokens)
+            .unwrap_optimized();
+    }
+
+    let value = reserve.to_asset_from_d_token(d_tokens);
+    let new_d_tokens = if owed > 0 {
+        reserve.to_d_token_up(owed)
+    } else {
+        0
+    };
+    if new_d_tokens > d_tokens {
+        user.add_liabilities(e, reserve, new_d_tokens - d_tokens);
+    } else if new_d_tokens < d_tokens {
+        user.remove_liabilities(e, reserve, d_tokens - new_d_tokens);
+    }
+    let credit = reserve.to_asset_from_d_token(new_d_tokens) - value;
+    if credit > 0 {
+        reserve.credit_suppliers(credit);
+    } else {
+        let from_backstop = (-credit).min(reserve.backstop_credit.max(0));
+        reserve.backstop_credit -= from_backstop;
+        reserve.credit_suppliers(credit + from_backstop);
+    }
+
+    let change = new_d_tokens - debt.d_tokens;
+    debt.d_tokens = new_d_tokens;
+    debt.principal = owed;
+    debt.last_time = now;
+    change
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        constants::MIN_FIXED_TERM,
+        pool::{execute_borrow_fixed, Positions},
+        testutils,
+    };
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+    };
+
+    fn set_ledger_time(e: &Env, timestamp: u64) {
+        e.ledger().set(LedgerInfo {
+            timestamp,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+    }
+
+    #[test]
+    fn test_borrow_stable() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+        let underlying_1_client = TokenClient::new(&e, &underlying_1);
+
+        e.as_contract(&pool, || {
+            let (d_tokens_minted, debt) =
+                execute_borrow_stable(&e, &samwise, &frodo, &underlying_1, 1_0000000);
+            assert_eq!(debt.d_tokens, d_tokens_minted);
+            assert_eq!(debt.principal, 1_0000000);
+            assert_eq!(debt.last_time, 600);
+
+            let mut pool_state = Pool::load(&e);
+            let reserve = pool_state.load_reserve(&e, &underlying_1, false);
+            let reserve_config = storage::get_res_config(&e, &underlying_1);
+            assert_eq!(
+                debt.rate,
+                calc_interest_rate(&reserve_config, reserve.utilization(), reserve.ir_mod)
+                    + STABLE_RATE_PREMIUM
+            );
+            assert_eq!(storage::get_stable_supply(&e, &underlying_1), debt.d_tokens);
+            assert_eq!(
+                storage::get_user_positions(&e, &samwise)
+                    .liabilities
+                    .get_unchecked(1),
+                debt.d_tokens
+            );
+            assert_eq!(underlying_1_client.balance(&frodo), 1_0000000);
+        });
+    }
+
+    #[test]
+    fn test_borrow_stable_blends_rate() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        e.as_contract(&pool, || {
+            let (_, first) =
+                execute_borrow_stable(&e, &samwise, &samwise, &underlying_1, 1_0000000);
+            // lower the first borrow's rate to verify the second borrow blends it
+            let mut debts = storage::get_stable_debts(&e, &samwise);
+            let mut debt = debts.get_unchecked(1);
+            debt.rate = 0_0200000;
+            debts.set(1, debt);
+            storage::set_stable_debts(&e, &samwise, &debts);
+
+            let (_, second) =
+                execute_borrow_stable(&e, &samwise, &samwise, &underlying_1, 1_0000000);
+            let mut pool_state = Pool::load(&e);
+            let reserve = pool_state.load_reserve(&e, &underlying_1, false);
+            let rate = calc_stable_rate(&e, &reserve);
+            assert_eq!(second.principal, 2_0000000);
+            assert_eq!(
+                second.rate,
+                (1_0000000 * 0_0200000 + 1_0000000 * rate) / 2_0000000
+            );
+            assert!(second.d_tokens > first.d_tokens);
+            assert_eq!(
+                storage::get_stable_supply(&e, &underlying_1),
+                second.d_tokens
+            );
+        });
+    }
+
+    #[test]
+    fn test_swap_to_variable() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        let (_, debt) = e.as_contract(&pool, || {
+            execute_borrow_stable(&e, &samwise, &samwise, &underlying_1, 1_0000000)
+        });
+
+        set_ledger_time(&e, 600 + SECONDS_PER_YEAR as u64);
+        e.as_contract(&pool, || {
+            let mut pool_state = Pool::load(&e);
+            let pre_reserve = pool_state.load_reserve(&e, &underlying_1, false);
+
+            let d_tokens = execute_swap_to_variable(&e, &samwise, &underlying_1);
+
+            // the debt is accrued at its stable rate for a year before moving to variable
+            let owed = 1_0000000 + debt.rate;
+            let mut pool_state = Pool::load(&e);
+            let reserve = pool_state.load_reserve(&e, &underlying_1, false);
+            assert_eq!(d_tokens, reserve.to_d_token_up(owed));
+            assert_eq!(
+                storage::get_user_positions(&e, &samwise)
+                    .liabilities
+                    .get_unchecked(1),
+                d_tokens
+            );
+            assert_eq!(
+                reserve.d_supply,
+                pre_reserve.d_supply - debt.d_tokens + d_tokens
+            );
+            assert_eq!(storage::get_stable_debts(&e, &samwise).len(), 0);
+            assert_eq!(storage::get_stable_supply(&e, &underlying_1), 0);
+        });
+    }
+
+    #[test]
+    fn test_rebalance_stable() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        e.as_contract(&pool, || {
+            execute_borrow_stable(&e, &samwise, &samwise, &underlying_1, 1_0000000);
+            let mut debts = storage::get_stable_debts(&e, &samwise);
+            let mut debt = debts.get_unchecked(1);
+            debt.rate = 0;
+            debts.set(1, debt);
+            storage::set_stable_debts(&e, &samwise, &debts);
+
+            let mut pool_state = Pool::load(&e);
+            let reserve = pool_state.load_reserve(&e, &underlying_1, false);
+            let rate = calc_stable_rate(&e, &reserve);
+            assert!(rate > STABLE_REBALANCE_DELTA);
+
+            let new_rate = execute_rebalance_stable(&e, &samwise, &underlying_1);
+            assert_eq!(new_rate, rate);
+            assert_eq!(
+                storage::get_stable_debts(&e, &samwise)
+                    .get_unchecked(1)
+                    .rate,
+                rate
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_rebalance_stable_within_delta() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        e.as_contract(&pool, || {
+            execute_borrow_stable(&e, &samwise, &samwise, &underlying_1, 1_0000000);
+            execute_rebalance_stable(&e, &samwise, &underlying_1);
+        });
+    }
+
+    #[test]
+    fn test_checkpoint_stable_debt_after_liquidation() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        e.as_contract(&pool, || {
+            let (_, mut debt) =
+                execute_borrow_stable(&e, &samwise, &samwise, &underlying_1, 1_0000000);
+            // a liquidator took half of the debt's dTokens
+            let mut positions = storage::get_user_positions(&e, &samwise);
+            positions.liabilities.set(1, debt.d_tokens / 2);
+            storage::set_user_positions(&e, &samwise, &positions);
+
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            let mut reserve = pool_state.load_reserve(&e, &underlying_1, false);
+            let pre_d_tokens = debt.d_tokens;
+            let change = checkpoint_stable_debt(&e, &mut reserve, &mut user, &mut debt);
+            assert_eq!(debt.principal, 5000000);
+            assert_eq!(debt.d_tokens, user.get_liabilities(1));
+            assert_eq!(change, debt.d_tokens - pre_d_tokens);
+        });
+    }
+
+    #[test]
+    fn test_checkpoint_stable_debt_shortfall_taken_from_backstop() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        let (_, mut debt) = e.as_contract(&pool, || {
+            execute_borrow_stable(&e, &samwise, &samwise, &underlying_1, 1_0000000)
+        });
+        // the debt's rate is below the variable rate its dTokens accrue at
+        debt.rate = 0;
+
+        set_ledger_time(&e, 600 + SECONDS_PER_YEAR as u64);
+        e.as_contract(&pool, || {
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            let mut reserve = pool_state.load_reserve(&e, &underlying_1, false);
+            let pre_b_rate = reserve.b_rate;
+            let pre_backstop_credit = reserve.backstop_credit;
+            let value = reserve.to_asset_from_d_token(debt.d_tokens);
+
+            checkpoint_stable_debt(&e, &mut reserve, &mut user, &mut debt);
+            assert_eq!(debt.principal, 1_0000000);
+            assert_eq!(reserve.b_rate, pre_b_rate);
+            assert_eq!(
+                reserve.backstop_credit,
+                pre_backstop_credit + reserve.to_asset_from_d_token(debt.d_tokens) - value
+            );
+            assert!(reserve.backstop_credit < pre_backstop_credit);
+        });
+    }
+
+    #[test]
+    fn test_checkpoint_stable_debt_shortfall_exceeds_backstop_credit() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        let (_, mut debt) = e.as_contract(&pool, || {
+            execute_borrow_stable(&e, &samwise, &samwise, &underlying_1, 1_0000000)
+        });
+        // the debt's rate is below the variable rate its dTokens accrue at
+        debt.rate = 0;
+
+        set_ledger_time(&e, 600 + SECONDS_PER_YEAR as u64);
+        e.as_contract(&pool, || {
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            let mut reserve = pool_state.load_reserve(&e, &underlying_1, false);
+            reserve.backstop_credit = 10;
+            let pre_b_rate = reserve.b_rate;
+            let pre_interest_earned = reserve.interest_earned;
+            let value = reserve.to_asset_from_d_token(debt.d_tokens);
+
+            checkpoint_stable_debt(&e, &mut reserve, &mut user, &mut debt);
+            let shortfall = value - reserve.to_asset_from_d_token(debt.d_tokens);
+            assert!(shortfall > 10);
+            assert_eq!(reserve.backstop_credit, 0);
+            assert!(reserve.b_rate < pre_b_rate);
+            assert_eq!(
+                reserve.interest_earned,
+                pre_interest_earned - (shortfall - 10)
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_borrow_stable_with_fixed_loan() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        let user_positions = Positions {
+            liabilities: map![&e],
+            collateral: map![&e, (0, 20_0000000)],
+            supply: map![&e],
+        };
+        let (pool, _, underlying_1) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        e.as_contract(&pool, || {
+            execute_borrow_fixed(
+                &e,
+                &samwise,
+                &samwise,
+                &underlying_1,
+                1_0000000,
+                MIN_FIXED_TERM,
+            );
+            execute_borrow_stable(&e, &samwise, &samwise, &underlying_1, 1_0000000);
+        });
+    }
+}
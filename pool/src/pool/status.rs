@@ -1,15 +1,19 @@
 use crate::{
-    constants::SCALAR_7,
+    constants::{MIN_STATUS_DWELL, SCALAR_7, STATUS_ACTIVE_THRESHOLD, STATUS_Q4W_BAND},
     dependencies::{BackstopClient, PoolBackstopData},
     storage, PoolError,
 };
-use soroban_sdk::{panic_with_error, Env};
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use super::Pool;
 
 /// Update the pool status based on the backstop module
 #[allow(clippy::zero_prefixed_literal)]
 #[allow(clippy::inconsistent_digit_grouping)]
 pub fn execute_update_pool_status(e: &Env) -> u32 {
     let mut pool_config = storage::get_pool_config(e);
+    let prev_status = pool_config.status;
 
     // check the pool has met minimum backstop deposits
     let backstop_id = storage::get_backstop(e);
@@ -49,18 +53,40 @@ pub fn execute_update_pool_status(e: &Env) -> u32 {
         }
         // Admin status isn't set
         _ => {
-            if pool_backstop_data.q4w_pct >= 0_6000000 {
+            // leaving a status requires clearing its entry point by a band to prevent the
+            // status from oscillating while the backstop hovers near a threshold
+            let new_status = if pool_backstop_data.q4w_pct >= 0_6000000
+                || (prev_status == 5 && pool_backstop_data.q4w_pct >= 0_6000000 - STATUS_Q4W_BAND)
+            {
                 // Q4w over 60% sets pool to Frozen
-                pool_config.status = 5;
-            } else if pool_backstop_data.q4w_pct >= 0_3000000 || !met_threshold {
+                5
+            } else if pool_backstop_data.q4w_pct >= 0_3000000
+                || !met_threshold
+                || (prev_status != 1
+                    && (pool_backstop_data.q4w_pct >= 0_3000000 - STATUS_Q4W_BAND
+                        || threshold < STATUS_ACTIVE_THRESHOLD))
+            {
                 // Q4w over 30% sets pool to On-Ice
-                pool_config.status = 3;
+                3
             } else {
                 // Backstop is healthy and the pool is set to Active
-                pool_config.status = 1;
+                1
+            };
+            // a less restrictive status can only be set once the pool has remained in its
+            // current status for the minimum dwell time
+            let can_relax = match storage::get_status_time(e) {
+                Some(status_time) => e.ledger().timestamp() >= status_time + MIN_STATUS_DWELL,
+                None => true,
+            };
+            if new_status > prev_status || (new_status < prev_status && can_relax) {
+                pool_config.status = new_status;
             }
         }
     }
+    if pool_config.status != prev_status {
+        storage::set_status_time(e, e.ledger().timestamp());
+        record_unfreeze(e, prev_status, pool_config.status);
+    }
     storage::set_pool_config(e, &pool_config);
     pool_config.status
 }
@@ -70,6 +96,7 @@ pub fn execute_update_pool_status(e: &Env) -> u32 {
 #[allow(clippy::inconsistent_digit_grouping)]
 pub fn execute_set_pool_status(e: &Env, pool_status: u32) {
     let mut pool_config = storage::get_pool_config(e);
+    let prev_status = pool_config.status;
 
     // check the pool has met minimum backstop deposits
     let backstop_id = storage::get_backstop(e);
@@ -113,9 +140,18 @@ pub fn execute_set_pool_status(e: &Env, pool_status: u32) {
             panic_with_error!(e, PoolError::BadRequest);
         }
     }
+    record_unfreeze(e, prev_status, pool_config.status);
     storage::set_pool_config(e, &pool_config);
 }
 
+/// Record the time the pool returned to an active status from on-ice or frozen, which starts the
+/// liquidation grace period
+fn record_unfreeze(e: &Env, prev_status: u32, new_status: u32) {
+    if (2..=5).contains(&prev_status) && new_status <= 1 {
+        storage::set_unfreeze_time(e, e.ledger().timestamp());
+    }
+}
+
 /// Calculate the threshold for the pool's backstop balance
 ///
 /// Returns the threshold as a percentage^5 in SCALAR_7 points such that SCALAR_7 = 100%
@@ -147,6 +183,52 @@ pub fn calc_pool_backstop_threshold(pool_backstop_data: &PoolBackstopData) -> i1
     saturating_pool_pc / threshold_pc
 }
 
+/// Calculate the total liabilities of the pool, including interest accrued since each reserve
+/// was last updated
+///
+/// Returns the total liabilities valued in the oracle's base asset with 7 decimals
+///
+/// ### Panics
+/// If the price of a reserve with liabilities is stale
+pub fn calc_total_liabilities(e: &Env) -> i128 {
+    let mut pool = Pool::load(e);
+    let oracle_scalar = 10i128.pow(pool.load_price_decimals(e));
+    let reserve_list = storage::get_res_list(e);
+    let mut liability_base = 0;
+    for asset in reserve_list.iter() {
+        let reserve = pool.load_reserve(e, &asset, false);
+        if reserve.d_supply == 0 {
+            continue;
+        }
+        let asset_to_base = pool.load_price(e, &asset);
+        liability_base += asset_to_base
+            .fixed_mul_floor(reserve.total_liabilities(), reserve.scalar)
+            .unwrap_optimized();
+    }
+    liability_base
+        .fixed_mul_floor(SCALAR_7, oracle_scalar)
+        .unwrap_optimized()
+}
+
+/// Calculate the total liabilities of the pool valued in an asset priced by the pool's oracle
+///
+/// Returns the total liabilities as an amount of the asset with 7 decimals
+///
+/// ### Arguments
+/// * `asset` - The asset to value the liabilities in
+///
+/// ### Panics
+/// If the price of the asset or a reserve with liabilities is stale
+pub fn calc_total_liabilities_in(e: &Env, asset: &Address) -> i128 {
+    let liabilities = calc_total_liabilities(e);
+    let mut pool = Pool::load(e);
+    let oracle_scalar = 10i128.pow(pool.load_price_decimals(e));
+    let asset_to_base = pool.load_price(e, asset);
+    liabilities
+        .fixed_div_floor(asset_to_base, oracle_scalar)
+        .unwrap_optimized()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
@@ -158,7 +240,100 @@ mod tests {
     };
 
     use super::*;
-    use soroban_sdk::{testutils::Address as _, vec, Address};
+    use crate::testutils::{create_mock_oracle, create_reserve, default_reserve_meta};
+    use sep_40_oracle::testutils::Asset;
+    use soroban_sdk::{
+        testutils::{Address as _, Ledger, LedgerInfo},
+        vec, Address, Symbol,
+    };
+
+    #[test]
+    fn test_calc_total_liabilities() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+        e.ledger().set(LedgerInfo {
+            timestamp: 12345,
+            protocol_version: 20,
+            sequence_number: 100,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let pool_id = create_pool(&e);
+        let (oracle_id, oracle_client) = create_mock_oracle(&e);
+
+        let (underlying_0, _) = create_token_contract(&e, &bombadil);
+        let (reserve_config_0, mut reserve_data_0) = default_reserve_meta();
+        reserve_data_0.d_rate = 1_100_000_000;
+        reserve_data_0.last_time = 12345;
+        create_reserve(
+            &e,
+            &pool_id,
+            &underlying_0,
+            &reserve_config_0,
+            &reserve_data_0,
+        );
+
+        let (underlying_1, _) = create_token_contract(&e, &bombadil);
+        let (mut reserve_config_1, mut reserve_data_1) = default_reserve_meta();
+        reserve_config_1.index = 1;
+        reserve_data_1.d_supply = 0;
+        reserve_data_1.last_time = 12345;
+        create_reserve(
+            &e,
+            &pool_id,
+            &underlying_1,
+            &reserve_config_1,
+            &reserve_data_1,
+        );
+
+        let (underlying_2, _) = create_token_contract(&e, &bombadil);
+        let (mut reserve_config_2, mut reserve_data_2) = default_reserve_meta();
+        reserve_config_2.index = 2;
+        reserve_data_2.d_supply = 10_0000000;
+        reserve_data_2.last_time = 12345;
+        create_reserve(
+            &e,
+            &pool_id,
+            &underlying_2,
+            &reserve_config_2,
+            &reserve_data_2,
+        );
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0),
+                Asset::Stellar(underlying_1.clone()),
+                Asset::Stellar(underlying_2),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 2_0000000, 4_0000000, 0_5000000]);
+
+        let pool_config = PoolConfig {
+            oracle: oracle_id,
+            bstop_rate: 0,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_id, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            // 82.5 * 2 + 10 * 0.5
+            assert_eq!(calc_total_liabilities(&e), 170_0000000);
+            // 170 / 4
+            assert_eq!(calc_total_liabilities_in(&e, &underlying_1), 42_5000000);
+        });
+    }
 
     #[test]
     fn test_set_pool_status_active() {
@@ -204,6 +379,7 @@ mod tests {
 
             let new_pool_config = storage::get_pool_config(&e);
             assert_eq!(new_pool_config.status, 0);
+            assert_eq!(storage::get_unfreeze_time(&e), None);
         });
     }
 
@@ -1098,6 +1274,152 @@ mod tests {
 
             let new_pool_config = storage::get_pool_config(&e);
             assert_eq!(new_pool_config.status, 0);
+            assert_eq!(storage::get_unfreeze_time(&e), Some(e.ledger().timestamp()));
+        });
+    }
+
+    #[test]
+    fn test_update_pool_status_on_ice_q4w_band() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.ledger().set(LedgerInfo {
+            timestamp: 100_000,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_id = create_pool(&e);
+        let oracle_id = Address::generate(&e);
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let (blnd, blnd_client) = create_token_contract(&e, &bombadil);
+        let (usdc, usdc_client) = create_token_contract(&e, &bombadil);
+        let (lp_token, lp_token_client) = create_comet_lp_pool(&e, &bombadil, &blnd, &usdc);
+        let (backstop_id, backstop_client) = create_backstop(&e);
+        setup_backstop(&e, &pool_id, &backstop_id, &lp_token, &usdc, &blnd);
+
+        // mint lp tokens
+        blnd_client.mint(&samwise, &500_001_0000000);
+        blnd_client.approve(&samwise, &lp_token, &i128::MAX, &99999);
+        usdc_client.mint(&samwise, &12_501_0000000);
+        usdc_client.approve(&samwise, &lp_token, &i128::MAX, &99999);
+        lp_token_client.join_pool(
+            &50_000_0000000,
+            &vec![&e, 500_001_0000000, 12_501_0000000],
+            &samwise,
+        );
+        backstop_client.deposit(&samwise, &pool_id, &50_000_0000000);
+        backstop_client.update_tkn_val();
+        backstop_client.queue_withdrawal(&samwise, &pool_id, &14_000_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: oracle_id,
+            bstop_rate: 0,
+            status: 3,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_id, || {
+            storage::set_admin(&e, &bombadil);
+            storage::set_pool_config(&e, &pool_config);
+
+            // 28% q4w is under the on-ice entry point but within the band
+            let status = execute_update_pool_status(&e);
+            assert_eq!(status, 3);
+
+            // an active pool is not put on-ice within the band
+            storage::set_pool_config(
+                &e,
+                &PoolConfig {
+                    status: 1,
+                    ..pool_config.clone()
+                },
+            );
+            let status = execute_update_pool_status(&e);
+            assert_eq!(status, 1);
+        });
+    }
+
+    #[test]
+    fn test_update_pool_status_min_dwell() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+        e.ledger().set(LedgerInfo {
+            timestamp: 100_000,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_id = create_pool(&e);
+        let oracle_id = Address::generate(&e);
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+
+        let (blnd, blnd_client) = create_token_contract(&e, &bombadil);
+        let (usdc, usdc_client) = create_token_contract(&e, &bombadil);
+        let (lp_token, lp_token_client) = create_comet_lp_pool(&e, &bombadil, &blnd, &usdc);
+        let (backstop_id, backstop_client) = create_backstop(&e);
+        setup_backstop(&e, &pool_id, &backstop_id, &lp_token, &usdc, &blnd);
+
+        // mint lp tokens
+        blnd_client.mint(&samwise, &500_001_0000000);
+        blnd_client.approve(&samwise, &lp_token, &i128::MAX, &99999);
+        usdc_client.mint(&samwise, &12_501_0000000);
+        usdc_client.approve(&samwise, &lp_token, &i128::MAX, &99999);
+        lp_token_client.join_pool(
+            &50_000_0000000,
+            &vec![&e, 500_001_0000000, 12_501_0000000],
+            &samwise,
+        );
+        backstop_client.deposit(&samwise, &pool_id, &50_000_0000000);
+        backstop_client.update_tkn_val();
+
+        let pool_config = PoolConfig {
+            oracle: oracle_id,
+            bstop_rate: 0,
+            status: 3,
+            max_positions: 4,
+        };
+        e.as_contract(&pool_id, || {
+            storage::set_admin(&e, &bombadil);
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_status_time(&e, 100_000 - MIN_STATUS_DWELL + 1);
+
+            // the pool can't be relaxed before the dwell time passes
+            let status = execute_update_pool_status(&e);
+            assert_eq!(status, 3);
+            assert_eq!(
+                storage::get_status_time(&e),
+                Some(100_000 - MIN_STATUS_DWELL + 1)
+            );
+            assert_eq!(storage::get_unfreeze_time(&e), None);
+
+            e.ledger().set(LedgerInfo {
+                timestamp: 100_001,
+                protocol_version: 20,
+                sequence_number: 1235,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 10,
+                min_persistent_entry_ttl: 10,
+                max_entry_ttl: 3110400,
+            });
+            let status = execute_update_pool_status(&e);
+            assert_eq!(status, 1);
+            assert_eq!(storage::get_status_time(&e), Some(100_001));
+            assert_eq!(storage::get_unfreeze_time(&e), Some(100_001));
         });
     }
 
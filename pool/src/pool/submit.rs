@@ -1,17 +1,38 @@
 use sep_41_token::TokenClient;
-use soroban_sdk::{panic_with_error, Address, Env, Vec};
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{
+    contracttype, panic_with_error, unwrap::UnwrapOptimized, Address, Env, Map, Symbol, Vec,
+};
 
-use crate::PoolError;
+use crate::{
+    constants::SCALAR_7, dependencies::SubmitHookClient, errors::panic_with_context, storage,
+    PoolError,
+};
 
 use super::{
-    actions::{build_actions_from_request, Request},
+    actions::{build_actions_from_request, Request, RequestType},
     health_factor::PositionData,
     pool::Pool,
+    swap_repay::execute_swap_repay,
     Positions,
 };
 
+/// A summary of a completed submit, sent to the user's submit hook
+#[derive(Clone)]
+#[contracttype]
+pub struct SubmitSummary {
+    pub requests: Vec<Request>,               // the requests submitted
+    pub spender_transfer: Map<Address, i128>, // tokens the pool received from the spender
+    pub pool_transfer: Map<Address, i128>,    // tokens the pool sent to "to"
+    pub positions: Positions,                 // the user's positions after the requests
+}
+
 /// Execute a set of updates for a user against the pool.
 ///
+/// If the user registered a submit hook, it is called once all tokens have been transferred.
+/// Soroban does not allow the hook to re-enter the pool, and a hook that fails does not fail the
+/// submit.
+///
 /// ### Arguments
 /// * from - The address of the user whose positions are being modified
 /// * spender - The address of the user who is sending tokens to the pool
@@ -33,50 +54,232 @@ pub fn execute_submit(
     {
         panic_with_error!(e, &PoolError::BadRequest);
     }
+
     let mut pool = Pool::load(e);
+    let hook = storage::get_submit_hook(e, from);
+    let submitted = requests.clone();
 
-    let (actions, new_from_state, check_health) =
+    let (mut actions, new_from_state, check_health) =
         build_actions_from_request(e, &mut pool, from, requests);
+    let spender_transferred = actions.spender_transfer.clone();
 
     // panics if the new positions set does not meet the health factor requirement
     // min is 1.0000100 to prevent rounding errors
-    if check_health
-        && new_from_state.has_liabilities()
-        && PositionData::calculate_from_positions(e, &mut pool, &new_from_state.positions)
-            .is_hf_under(1_0000100)
-    {
-        panic_with_error!(e, PoolError::InvalidHf);
+    if check_health && new_from_state.has_liabilities() {
+        // positions with liabilities but no collateral can never be healthy, so they are rejected
+        // before any prices are loaded
+        if new_from_state.positions.collateral.is_empty() {
+            panic_with_error!(e, PoolError::InvalidHf);
+        }
+        // collateral that is restricted or in a pricing emergency can't be borrowed against, but
+        // still counts towards the health of other actions, like withdrawals
+        let position_data = if actions.borrowed.is_empty() {
+            PositionData::calculate_from_positions(e, &mut pool, &new_from_state.positions)
+        } else {
+            PositionData::calculate_with_restrictions(e, &mut pool, &new_from_state.positions)
+        };
+        if position_data.is_hf_under(1_0000100) {
+            panic_with_error!(e, PoolError::InvalidHf);
+        }
+        // borrowing is blocked from reserves in a pricing emergency. Borrowed reserves were
+        // priced by the health check.
+        for asset in actions.borrowed.iter() {
+            if pool.is_price_emergency(e, &asset) {
+                panic_with_context(e, PoolError::PriceEmergency, None, Some(asset));
+            }
+        }
+        // collateral can't be added to reserves that are restricted or in a pricing emergency
+        // while the health of the positions is checked. Collateral reserves were priced by the
+        // health check.
+        for asset in actions.collateralized.iter() {
+            if pool.is_restricted(e, &asset) {
+                panic_with_context(e, PoolError::ReserveRestricted, None, Some(asset));
+            }
+            if pool.is_price_emergency(e, &asset) {
+                panic_with_context(e, PoolError::PriceEmergency, None, Some(asset));
+            }
+        }
+        // publish the checked position so indexers can track health without re-pricing the account
+        e.events().publish(
+            (Symbol::new(e, "position_health"), from.clone()),
+            (position_data.collateral_base, position_data.liability_base),
+        );
     }
 
-    // transfer tokens from sender to pool
+    // store updated info to ledger before any token transfers occur
+    pool.store_cached_reserves(e);
+    new_from_state.store(e);
+    actions.store(e, from);
+
+    // transfer tokens from sender to pool, including the bids of any auction fills
     for (address, amount) in actions.spender_transfer.iter() {
-        TokenClient::new(e, &address).transfer(spender, &e.current_contract_address(), &amount);
+        pull_from_spender(e, spender, &address, amount);
     }
 
-    // store updated info to ledger
-    pool.store_cached_reserves(e);
-    new_from_state.store(e);
+    // liabilities repaid with a swap are bought with the tokens the spender just transferred
+    for swap_repay in actions.swap_repays.iter() {
+        let sold = execute_swap_repay(e, from, &swap_repay);
+        if swap_repay.max_sold > sold {
+            actions.add_for_pool_transfer(&swap_repay.token_in, swap_repay.max_sold - sold);
+        }
+    }
 
     // transfer tokens from pool to "to"
     for (address, amount) in actions.pool_transfer.iter() {
         TokenClient::new(e, &address).transfer(&e.current_contract_address(), to, &amount);
     }
 
+    if let Some(hook) = hook {
+        let summary = SubmitSummary {
+            requests: submitted,
+            spender_transfer: spender_transferred.clone(),
+            pool_transfer: actions.pool_transfer.clone(),
+            positions: new_from_state.positions.clone(),
+        };
+        call_submit_hook(e, &hook, from, &summary);
+    }
+
+    // publish the aggregate token flows so indexers can reconcile them against token events
+    e.events().publish(
+        (Symbol::new(e, "submit_transfers"), from.clone()),
+        (
+            spender.clone(),
+            to.clone(),
+            spender_transferred,
+            actions.pool_transfer,
+        ),
+    );
+
     new_from_state.positions
 }
 
+/// Call a user's submit hook. Any changes the hook makes are reverted if it fails, and the
+/// failure is published as an event instead of failing the submit.
+///
+/// The hook runs within the submit's resource budget, so a hook that exhausts it still fails
+/// the submit.
+fn call_submit_hook(e: &Env, hook: &Address, user: &Address, summary: &SubmitSummary) {
+    let result =
+        SubmitHookClient::new(e, hook).try_on_submit(&e.current_contract_address(), user, summary);
+    if !matches!(result, Ok(Ok(()))) {
+        e.events().publish(
+            (Symbol::new(e, "submit_hook_failed"), user.clone()),
+            hook.clone(),
+        );
+    }
+}
+
+/// The result of simulating a set of requests against the pool
+#[derive(Clone)]
+#[contracttype]
+pub struct SubmitSimulation {
+    pub spender_transfer: Map<Address, i128>, // tokens the spender would send to the pool
+    pub pool_transfer: Map<Address, i128>,    // tokens the pool would send to "to"
+    pub positions: Positions,                 // the user's positions after the requests
+    pub health_factor: i128, // the user's health factor after the requests (7 decimals), or i128::MAX if they have no liabilities
+}
+
+/// Simulate a set of updates for a user against the pool at the current ledger, without requiring
+/// auth, storing the user's positions, reserves, action nonce, withdrawals or prices, or
+/// transferring tokens.
+///
+/// Only requests that modify the user's own positions can be simulated. The spender transfers
+/// do not include the fee charged by fee on transfer reserves.
+///
+/// ### Arguments
+/// * from - The address of the user whose positions are being simulated
+/// * requests - A vec of requests to be simulated
+///
+/// ### Panics
+/// If a request cannot be simulated or is unable to be fully executed
+pub fn simulate_submit(e: &Env, from: &Address, requests: Vec<Request>) -> SubmitSimulation {
+    // auction fills and swaps modify other accounts or move tokens during the build step
+    for (index, request) in requests.iter().enumerate() {
+        if request.request_type > RequestType::Repay as u32 {
+            panic_with_context(
+                e,
+                PoolError::BadRequest,
+                Some(index as u32),
+                Some(request.address.clone()),
+            );
+        }
+    }
+
+    let mut pool = Pool::load_for_simulation(e);
+    let (actions, new_from_state, _) = build_actions_from_request(e, &mut pool, from, requests);
+
+    let health_factor = if new_from_state.has_liabilities() {
+        let position_data = if actions.borrowed.is_empty() {
+            PositionData::calculate_from_positions(e, &mut pool, &new_from_state.positions)
+        } else {
+            PositionData::calculate_with_restrictions(e, &mut pool, &new_from_state.positions)
+        };
+        position_data
+            .collateral_base
+            .fixed_div_floor(position_data.liability_base, SCALAR_7)
+            .unwrap_optimized()
+    } else {
+        i128::MAX
+    };
+
+    SubmitSimulation {
+        spender_transfer: actions.spender_transfer,
+        pool_transfer: actions.pool_transfer,
+        positions: new_from_state.positions,
+        health_factor,
+    }
+}
+
+/// Transfer tokens from the spender to the pool and verify the pool received `amount`. This is
+/// done after all state is written, so the amount was already accounted for.
+///
+/// If the asset is a reserve flagged as charging a fee on transfer, the spender covers the fee
+/// with a second transfer of the shortfall grossed up by the fee rate of the first transfer.
+///
+/// ### Arguments
+/// * spender - The address of the user who is sending tokens to the pool
+/// * asset - The asset being transferred
+/// * amount - The amount of the asset the pool must receive
+///
+/// ### Panics
+/// If the pool received less than `amount`
+pub(crate) fn pull_from_spender(e: &Env, spender: &Address, asset: &Address, amount: i128) {
+    let token_client = TokenClient::new(e, asset);
+    let pool_address = e.current_contract_address();
+    let balance_before = token_client.balance(&pool_address);
+    token_client.transfer(spender, &pool_address, &amount);
+    let received = token_client.balance(&pool_address) - balance_before;
+    if received >= amount {
+        return;
+    }
+
+    let fee_on_transfer =
+        storage::has_res(e, asset) && storage::get_res_config(e, asset).fee_on_transfer;
+    if !fee_on_transfer || received <= 0 {
+        panic_with_context(e, PoolError::TransferShortfall, None, Some(asset.clone()));
+    }
+    let shortfall = (amount - received)
+        .fixed_mul_ceil(amount, received)
+        .unwrap_optimized();
+    token_client.transfer(spender, &pool_address, &shortfall);
+    if token_client.balance(&pool_address) - balance_before < amount {
+        panic_with_context(e, PoolError::TransferShortfall, None, Some(asset.clone()));
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        storage::{self, PoolConfig},
+        storage::{self, CachedPrice, PoolConfig},
         testutils, RequestType,
     };
 
     use super::*;
     use sep_40_oracle::testutils::Asset;
     use soroban_sdk::{
-        testutils::{Address as _, Ledger, LedgerInfo},
-        vec, Symbol,
+        map,
+        testutils::{Address as _, Events, Ledger, LedgerInfo},
+        vec, IntoVal, Symbol,
     };
 
     #[test]
@@ -143,12 +346,12 @@ mod tests {
                 &e,
                 Request {
                     request_type: RequestType::SupplyCollateral as u32,
-                    address: underlying_0,
+                    address: underlying_0.clone(),
                     amount: 15_0000000,
                 },
                 Request {
                     request_type: RequestType::Borrow as u32,
-                    address: underlying_1,
+                    address: underlying_1.clone(),
                     amount: 1_5000000,
                 },
             ];
@@ -160,6 +363,38 @@ mod tests {
             assert_eq!(positions.collateral.get_unchecked(0), 14_9999884);
             assert_eq!(positions.liabilities.get_unchecked(1), 1_4999983);
 
+            // the checked position is published for indexers
+            let position_data =
+                PositionData::calculate_from_positions(&e, &mut Pool::load(&e), &positions);
+            let health_event = (
+                pool.clone(),
+                (Symbol::new(&e, "position_health"), samwise.clone()).into_val(&e),
+                (position_data.collateral_base, position_data.liability_base).into_val(&e),
+            );
+            assert!(e
+                .events()
+                .all()
+                .iter()
+                .any(|event| vec![&e, event] == vec![&e, health_event.clone()]));
+
+            // the aggregate token flows are published for indexers
+            let transfer_event = (
+                pool.clone(),
+                (Symbol::new(&e, "submit_transfers"), samwise.clone()).into_val(&e),
+                (
+                    frodo.clone(),
+                    merry.clone(),
+                    map![&e, (underlying_0.clone(), 15_0000000_i128)],
+                    map![&e, (underlying_1.clone(), 1_5000000_i128)],
+                )
+                    .into_val(&e),
+            );
+            assert!(e
+                .events()
+                .all()
+                .iter()
+                .any(|event| vec![&e, event] == vec![&e, transfer_event.clone()]));
+
             assert_eq!(
                 underlying_0_client.balance(&pool),
                 pre_pool_balance_0 + 15_0000000
@@ -333,6 +568,252 @@ mod tests {
         });
     }
 
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1205)")]
+    fn test_submit_requires_collateral_without_pricing() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying_0, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        // the oracle is not a contract, so loading a price would fail
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Borrow as u32,
+                    address: underlying_0,
+                    amount: 1_0000000,
+                },
+            ];
+            execute_submit(&e, &samwise, &frodo, &frodo, requests);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1238)")]
+    fn test_submit_borrow_price_emergency() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let merry = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&frodo, &16_0000000);
+
+        // the oracle does not list underlying_1
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![&e, Asset::Stellar(underlying_0.clone())],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000]);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_cached_price(
+                &e,
+                &underlying_1,
+                &CachedPrice {
+                    price: 1_0000000,
+                    timestamp: 600,
+                    emergency: false,
+                },
+            );
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::SupplyCollateral as u32,
+                    address: underlying_0,
+                    amount: 15_0000000,
+                },
+                Request {
+                    request_type: RequestType::Borrow as u32,
+                    address: underlying_1,
+                    amount: 1_0000000,
+                },
+            ];
+            execute_submit(&e, &samwise, &frodo, &merry, requests);
+        });
+    }
+
+    /// Create a pool with a priced reserve and a reserve the oracle no longer lists, and a user
+    /// with collateral in both and a liability in the first, returning the pool and both assets
+    fn setup_price_emergency(e: &Env, samwise: &Address) -> (Address, Address, Address) {
+        let bombadil = Address::generate(e);
+        let pool = testutils::create_pool(e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(e);
+
+        let (underlying_0, _) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(e, &bombadil);
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(e, &pool, &underlying_1, &reserve_config, &reserve_data);
+        underlying_1_client.mint(samwise, &10_0000000);
+
+        // the oracle does not list underlying_1
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(e, "USD")),
+            &vec![e, Asset::Stellar(underlying_0.clone())],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![e, 1_0000000]);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(e, &pool_config);
+            storage::set_cached_price(
+                e,
+                &underlying_1,
+                &CachedPrice {
+                    price: 1_0000000,
+                    timestamp: 600,
+                    emergency: false,
+                },
+            );
+            storage::set_user_positions(
+                e,
+                samwise,
+                &Positions {
+                    liabilities: map![e, (0, 5_0000000)],
+                    collateral: map![e, (0, 10_0000000), (1, 10_0000000)],
+                    supply: map![e],
+                },
+            );
+        });
+        (pool, underlying_0, underlying_1)
+    }
+
+    #[test]
+    fn test_submit_withdraw_with_price_emergency_collateral() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+        let (pool, underlying_0, _) = setup_price_emergency(&e, &samwise);
+
+        e.as_contract(&pool, || {
+            // the withdrawal is only healthy if the collateral in a pricing emergency is valued
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::WithdrawCollateral as u32,
+                    address: underlying_0,
+                    amount: 2_0000000,
+                },
+            ];
+            let positions = execute_submit(&e, &samwise, &samwise, &samwise, requests);
+            assert_eq!(positions.collateral.get_unchecked(0), 8_0000000);
+            assert_eq!(positions.collateral.get_unchecked(1), 10_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1238)")]
+    fn test_submit_supply_collateral_price_emergency() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+        let (pool, underlying_0, underlying_1) = setup_price_emergency(&e, &samwise);
+
+        e.as_contract(&pool, || {
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::SupplyCollateral as u32,
+                    address: underlying_1,
+                    amount: 10_0000000,
+                },
+                Request {
+                    request_type: RequestType::WithdrawCollateral as u32,
+                    address: underlying_0,
+                    amount: 5_0000000,
+                },
+            ];
+            execute_submit(&e, &samwise, &samwise, &samwise, requests);
+        });
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #1200)")]
     fn test_submit_from_is_not_self() {
@@ -512,4 +993,487 @@ mod tests {
             execute_submit(&e, &samwise, &samwise, &pool, requests);
         });
     }
+
+    #[test]
+    fn test_submit_fee_on_transfer_spender_covers_fee() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e); // will fail if executed against
+
+        let (underlying_0, underlying_0_client) = testutils::create_fee_token_contract(&e);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.fee_on_transfer = true;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&frodo, &110_0000000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            e.mock_all_auths_allowing_non_root_auth();
+            storage::set_pool_config(&e, &pool_config);
+
+            let pre_pool_balance_0 = underlying_0_client.balance(&pool);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Supply as u32,
+                    address: underlying_0.clone(),
+                    amount: 100_0000000,
+                },
+            ];
+            let positions = execute_submit(&e, &samwise, &frodo, &samwise, requests);
+
+            // 1% of each transfer is taken as a fee, and the shortfall of the first is grossed up
+            // to cover the fee on the second
+            assert_eq!(
+                underlying_0_client.balance(&frodo),
+                110_0000000 - 101_0101011
+            );
+            assert_eq!(
+                underlying_0_client.balance(&pool),
+                pre_pool_balance_0 + 100_0000001
+            );
+            let reserve = Pool::load(&e).load_reserve(&e, &underlying_0, false);
+            assert_eq!(
+                positions.supply.get_unchecked(0),
+                reserve.to_b_token_down(100_0000000)
+            );
+        });
+    }
+
+    #[test]
+    fn test_submit_fee_on_transfer_collateral_and_repay() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let oracle = Address::generate(&e); // will fail if executed against
+
+        let (underlying_0, underlying_0_client) = testutils::create_fee_token_contract(&e);
+        let (mut reserve_config, reserve_data) = testutils::default_reserve_meta();
+        reserve_config.fee_on_transfer = true;
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&frodo, &100_0000000);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 20_0000000)],
+            collateral: map![&e],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            e.mock_all_auths_allowing_non_root_auth();
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let pre_pool_balance_0 = underlying_0_client.balance(&pool);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::SupplyCollateral as u32,
+                    address: underlying_0.clone(),
+                    amount: 50_0000000,
+                },
+                Request {
+                    request_type: RequestType::Repay as u32,
+                    address: underlying_0.clone(),
+                    amount: 10_0000000,
+                },
+            ];
+            let positions = execute_submit(&e, &samwise, &frodo, &samwise, requests);
+
+            // the requests are pulled together, and the spender covers the 1% fee
+            assert_eq!(
+                underlying_0_client.balance(&frodo),
+                100_0000000 - 60_6060607
+            );
+            assert_eq!(
+                underlying_0_client.balance(&pool),
+                pre_pool_balance_0 + 60_0000001
+            );
+            let reserve = Pool::load(&e).load_reserve(&e, &underlying_0, false);
+            assert_eq!(
+                positions.collateral.get_unchecked(0),
+                reserve.to_b_token_down(50_0000000)
+            );
+            assert_eq!(
+                positions.liabilities.get_unchecked(0),
+                20_0000000 - reserve.to_d_token_down(10_0000000)
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1241)")]
+    fn test_submit_unflagged_fee_on_transfer_panics() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        // the reserve was not flagged as charging a fee on transfer
+        let (underlying_0, underlying_0_client) = testutils::create_fee_token_contract(&e);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&samwise, &100_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Supply as u32,
+                    address: underlying_0.clone(),
+                    amount: 50_0000000,
+                },
+            ];
+            execute_submit(&e, &samwise, &samwise, &samwise, requests);
+        });
+    }
+
+    #[test]
+    fn test_simulate_submit() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (oracle, oracle_client) = testutils::create_mock_oracle(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        let (underlying_1, underlying_1_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_1, &reserve_config, &reserve_data);
+
+        oracle_client.set_data(
+            &bombadil,
+            &Asset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                Asset::Stellar(underlying_0.clone()),
+                Asset::Stellar(underlying_1.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracle_client.set_price_stable(&vec![&e, 1_0000000, 5_0000000]);
+
+        let pool_config = PoolConfig {
+            oracle,
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        // verify the simulation does not require any auth
+        e.set_auths(&[]);
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+
+            let pre_pool_balance_0 = underlying_0_client.balance(&pool);
+            let pre_pool_balance_1 = underlying_1_client.balance(&pool);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::SupplyCollateral as u32,
+                    address: underlying_0.clone(),
+                    amount: 15_0000000,
+                },
+                Request {
+                    request_type: RequestType::Borrow as u32,
+                    address: underlying_1.clone(),
+                    amount: 1_5000000,
+                },
+            ];
+            let simulation = simulate_submit(&e, &samwise, requests);
+            assert_eq!(storage::get_cached_price(&e, &underlying_1), None);
+
+            assert_eq!(
+                simulation.spender_transfer,
+                map![&e, (underlying_0.clone(), 15_0000000_i128)]
+            );
+            assert_eq!(
+                simulation.pool_transfer,
+                map![&e, (underlying_1.clone(), 1_5000000_i128)]
+            );
+            assert_eq!(simulation.positions.collateral.get_unchecked(0), 14_9999884);
+            assert_eq!(simulation.positions.liabilities.get_unchecked(1), 1_4999983);
+            let position_data = PositionData::calculate_from_positions(
+                &e,
+                &mut Pool::load(&e),
+                &simulation.positions,
+            );
+            assert_eq!(
+                simulation.health_factor,
+                position_data
+                    .collateral_base
+                    .fixed_div_floor(position_data.liability_base, SCALAR_7)
+                    .unwrap_optimized()
+            );
+
+            // nothing is stored or transferred
+            assert_eq!(
+                storage::get_user_positions(&e, &samwise).collateral.len(),
+                0
+            );
+            assert_eq!(underlying_0_client.balance(&pool), pre_pool_balance_0);
+            assert_eq!(underlying_1_client.balance(&pool), pre_pool_balance_1);
+            assert_eq!(
+                storage::get_res_data(&e, &underlying_1).d_supply,
+                reserve_data.d_supply
+            );
+            assert_eq!(storage::get_action_nonce(&e, &samwise), 0);
+            assert!(storage::get_user_interest(&e, &samwise, &1).is_none());
+            assert!(storage::get_user_interest(&e, &samwise, &2).is_none());
+
+            // users without liabilities are reported as fully healthy
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Supply as u32,
+                    address: underlying_0.clone(),
+                    amount: 1_0000000,
+                },
+            ];
+            let simulation = simulate_submit(&e, &samwise, requests);
+            assert_eq!(simulation.health_factor, i128::MAX);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_simulate_submit_auction_fill_panics() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        e.as_contract(&pool, || {
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::FillUserLiquidationAuction as u32,
+                    address: Address::generate(&e),
+                    amount: 100,
+                },
+            ];
+            simulate_submit(&e, &samwise, requests);
+        });
+    }
+
+    #[test]
+    fn test_submit_calls_submit_hook() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (hook, hook_client) = testutils::create_mock_submit_hook(&e);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&frodo, &16_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        let requests = vec![
+            &e,
+            Request {
+                request_type: RequestType::Supply as u32,
+                address: underlying_0.clone(),
+                amount: 15_0000000,
+            },
+            Request {
+                request_type: RequestType::Withdraw as u32,
+                address: underlying_0.clone(),
+                amount: 5_0000000,
+            },
+        ];
+        let positions = e.as_contract(&pool, || {
+            e.mock_all_auths_allowing_non_root_auth();
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_submit_hook(&e, &samwise, &hook);
+
+            execute_submit(&e, &samwise, &frodo, &frodo, requests.clone())
+        });
+
+        let (user, summary) = hook_client.get_last().unwrap();
+        assert_eq!(user, samwise);
+        assert_eq!(summary.requests.len(), 2);
+        assert_eq!(summary.requests.get_unchecked(1).amount, 5_0000000);
+        assert_eq!(
+            summary.spender_transfer,
+            map![&e, (underlying_0.clone(), 15_0000000_i128)]
+        );
+        assert_eq!(summary.pool_transfer, map![&e, (underlying_0, 5_0000000)]);
+        assert_eq!(summary.positions.supply, positions.supply);
+    }
+
+    #[test]
+    fn test_submit_hook_failure_does_not_fail_submit() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+        let (hook, hook_client) = testutils::create_mock_submit_hook(&e);
+        hook_client.set_fail(&true);
+
+        let (underlying_0, underlying_0_client) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying_0, &reserve_config, &reserve_data);
+
+        underlying_0_client.mint(&frodo, &16_0000000);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            e.mock_all_auths_allowing_non_root_auth();
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_submit_hook(&e, &samwise, &hook);
+
+            let requests = vec![
+                &e,
+                Request {
+                    request_type: RequestType::Supply as u32,
+                    address: underlying_0.clone(),
+                    amount: 15_0000000,
+                },
+            ];
+            let positions = execute_submit(&e, &samwise, &frodo, &frodo, requests);
+            assert_eq!(positions.supply.get_unchecked(0), 14_9999884);
+            assert_eq!(underlying_0_client.balance(&frodo), 1_0000000);
+
+            let hook_event = (
+                pool.clone(),
+                (Symbol::new(&e, "submit_hook_failed"), samwise.clone()).into_val(&e),
+                hook.into_val(&e),
+            );
+            assert!(e
+                .events()
+                .all()
+                .iter()
+                .any(|event| vec![&e, event] == vec![&e, hook_event.clone()]));
+        });
+        assert!(hook_client.get_last().is_none());
+    }
 }
@@ -0,0 +1,84 @@
+use sep_41_token::TokenClient;
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    panic_with_error,
+    unwrap::UnwrapOptimized,
+    vec, Address, Env, IntoVal, Symbol, Val, Vec,
+};
+
+use crate::{
+    constants::{MAX_SWAP_SLIPPAGE, SCALAR_7},
+    dependencies::AmmClient,
+    errors::PoolError,
+    storage,
+};
+
+/// Swap the pool's tokens through the pool's AMM for an exact amount of another token
+///
+/// Returns the amount of tokens swapped in
+///
+/// ### Arguments
+/// * `token_in` - The token the pool is selling
+/// * `max_amount_in` - The maximum amount of `token_in` to sell
+/// * `token_out` - The token the pool is buying
+/// * `amount_out` - The exact amount of `token_out` to buy
+///
+/// ### Panics
+/// If no AMM is set, the swap requires more than `max_amount_in`, or the pool does not receive
+/// `amount_out` of `token_out`
+pub fn swap_exact_amount_out(
+    e: &Env,
+    token_in: &Address,
+    max_amount_in: i128,
+    token_out: &Address,
+    amount_out: i128,
+) -> i128 {
+    let amm = storage::get_amm(e).unwrap_or_else(|| panic_with_error!(e, PoolError::AmmNotSet));
+    let pool_address = e.current_contract_address();
+    let token_out_client = TokenClient::new(e, token_out);
+    let pre_balance_out = token_out_client.balance(&pool_address);
+    // the AMM approves itself to pull exactly `max_amount_in` on behalf of the pool, and the
+    // approval is cleared once the swap completes
+    let approval_ledger = (e.ledger().sequence() / 100000 + 1) * 100000;
+    let args: Vec<Val> = vec![
+        e,
+        pool_address.into_val(e),
+        amm.into_val(e),
+        max_amount_in.into_val(e),
+        approval_ledger.into_val(e),
+    ];
+    e.authorize_as_current_contract(vec![
+        e,
+        InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: token_in.clone(),
+                fn_name: Symbol::new(e, "approve"),
+                args,
+            },
+            sub_invocations: vec![e],
+        }),
+    ]);
+    // bound the spot price after the swap by the worst average price accepted plus the max slippage
+    let max_price = max_amount_in
+        .fixed_div_ceil(amount_out, SCALAR_7)
+        .unwrap_optimized()
+        .fixed_mul_ceil(SCALAR_7 + MAX_SWAP_SLIPPAGE, SCALAR_7)
+        .unwrap_optimized();
+    let (amount_in, _) = AmmClient::new(e, &amm).swap_exact_amount_out(
+        token_in,
+        &max_amount_in,
+        token_out,
+        &amount_out,
+        &max_price,
+        &pool_address,
+    );
+    if amount_in > max_amount_in
+        || token_out_client.balance(&pool_address) - pre_balance_out < amount_out
+    {
+        panic_with_error!(e, PoolError::AmmSwapFailed);
+    }
+    // clear any approval left unused by the AMM
+    TokenClient::new(e, token_in).approve(&pool_address, &amm, &0, &approval_ledger);
+    amount_in
+}
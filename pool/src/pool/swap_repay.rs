@@ -0,0 +1,299 @@
+use soroban_sdk::{contracttype, Address, Env, Map, Symbol};
+
+use crate::{
+    errors::{panic_with_context, PoolError},
+    storage,
+};
+
+use super::{swap::swap_exact_amount_out, Pool, User};
+
+/// Liabilities of a filler repaid by swapping a single token through the pool's AMM. The swaps
+/// are executed once the filler has transferred `max_sold` of the token to the pool.
+#[derive(Clone)]
+#[contracttype]
+pub struct SwapRepay {
+    pub token_in: Address,        // the token the filler is paying with
+    pub max_sold: i128,           // the amount of token_in the filler transfers to the pool
+    pub repaid: i128,             // the amount of token_in used to repay liabilities directly
+    pub owed: Map<Address, i128>, // the underlying tokens to buy for each liability asset
+    pub bid: Map<Address, i128>,  // the liabilities repaid, as a map of asset to dTokens
+}
+
+/// Repay the liabilities a filler took on from a user liquidation auction with a single token.
+/// Liabilities in other assets are bought through the pool's AMM by `execute_swap_repay` once
+/// the filler has transferred `max_sold` of the token to the pool.
+///
+/// Returns the swaps to execute
+///
+/// ### Arguments
+/// * `user_state` - The filler repaying their liabilities
+/// * `bid` - The liabilities taken on by the filler, as a map of asset to dTokens
+/// * `token_in` - The token the filler is paying with
+/// * `max_sold` - The maximum amount of `token_in` that can be sold
+/// * `request_index` - The index of the request being processed
+///
+/// ### Panics
+/// If `token_in` is not a reserve of the pool, or if repaying the liabilities directly requires
+/// more than `max_sold` of `token_in`
+pub fn repay_with_swap(
+    e: &Env,
+    pool: &mut Pool,
+    user_state: &mut User,
+    bid: &Map<Address, i128>,
+    token_in: &Address,
+    max_sold: i128,
+    request_index: u32,
+) -> SwapRepay {
+    if !storage::has_res(e, token_in) {
+        panic_with_context(
+            e,
+            PoolError::BadRequest,
+            Some(request_index),
+            Some(token_in.clone()),
+        );
+    }
+    let mut repaid: i128 = 0;
+    let mut owed = Map::new(e);
+    for (asset, d_tokens) in bid.iter() {
+        let mut reserve = pool.load_reserve(e, &asset, true);
+        let amount = reserve.to_asset_from_d_token(d_tokens);
+        if asset == *token_in {
+            // the liability can be repaid with the token directly
+            repaid += amount;
+            if repaid > max_sold {
+                panic_with_context(
+                    e,
+                    PoolError::BadRequest,
+                    Some(request_index),
+                    Some(token_in.clone()),
+                );
+            }
+        } else {
+            owed.set(asset, amount);
+        }
+        user_state.remove_liabilities(e, &mut reserve, d_tokens);
+        pool.cache_reserve(reserve);
+    }
+    SwapRepay {
+        token_in: token_in.clone(),
+        max_sold,
+        repaid,
+        owed,
+        bid: bid.clone(),
+    }
+}
+
+/// Buy the liability assets owed by a swap repayment through the pool's AMM, with the tokens the
+/// filler transferred to the pool
+///
+/// Returns the amount of `token_in` sold or used to repay liabilities
+///
+/// ### Arguments
+/// * `filler` - The filler repaying their liabilities
+/// * `swap_repay` - The swap repayment to execute
+///
+/// ### Panics
+/// If no AMM is set, or if buying the liability assets requires selling more than the filler
+/// transferred
+pub fn execute_swap_repay(e: &Env, filler: &Address, swap_repay: &SwapRepay) -> i128 {
+    let mut sold = swap_repay.repaid;
+    for (asset, amount) in swap_repay.owed.iter() {
+        sold += swap_exact_amount_out(
+            e,
+            &swap_repay.token_in,
+            swap_repay.max_sold - sold,
+            &asset,
+            amount,
+        );
+    }
+    if sold > swap_repay.max_sold {
+        panic_with_context(
+            e,
+            PoolError::BadRequest,
+            None,
+            Some(swap_repay.token_in.clone()),
+        );
+    }
+    e.events().publish(
+        (
+            Symbol::new(e, "repay_fill_with_swap"),
+            swap_repay.token_in.clone(),
+            filler.clone(),
+        ),
+        (swap_repay.bid.clone(), sold),
+    );
+    sold
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pool::Positions,
+        storage::{self, PoolConfig},
+        testutils::{self, create_comet_lp_pool},
+    };
+    use soroban_sdk::{
+        map,
+        testutils::{Address as _, Ledger, LedgerInfo},
+    };
+
+    #[test]
+    fn test_repay_with_swap() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (usdc, usdc_client) = testutils::create_token_contract(&e, &bombadil);
+        let (blnd, blnd_client) = testutils::create_token_contract(&e, &bombadil);
+        let (amm, _) = create_comet_lp_pool(&e, &bombadil, &blnd, &usdc);
+
+        let (reserve_config, mut reserve_data) = testutils::default_reserve_meta();
+        reserve_data.last_time = 600;
+        testutils::create_reserve(&e, &pool, &usdc, &reserve_config, &reserve_data);
+        testutils::create_reserve(&e, &pool, &blnd, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 1_0000000), (1, 25_0000000)],
+            collateral: map![&e],
+            supply: map![&e],
+        };
+        // the filler's payment is transferred to the pool before the swaps are executed
+        usdc_client.mint(&pool, &5_0000000);
+        // verify the pool authorizes the swap itself
+        e.set_auths(&[]);
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_amm(&e, &amm);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let pre_pool_usdc = usdc_client.balance(&pool);
+            let pre_pool_blnd = blnd_client.balance(&pool);
+
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            let bid = map![&e, (usdc.clone(), 1_0000000), (blnd.clone(), 20_0000000)];
+            let swap_repay =
+                repay_with_swap(&e, &mut pool_state, &mut user, &bid, &usdc, 5_0000000, 0);
+            assert_eq!(swap_repay.repaid, 1_0000000);
+            assert_eq!(swap_repay.owed, map![&e, (blnd.clone(), 20_0000000)]);
+            assert_eq!(user.positions.liabilities.len(), 1);
+            assert_eq!(user.get_liabilities(1), 5_0000000);
+            // nothing is swapped until the swap repayment is executed
+            assert_eq!(usdc_client.balance(&pool), pre_pool_usdc);
+            assert_eq!(blnd_client.balance(&pool), pre_pool_blnd);
+
+            let sold = execute_swap_repay(&e, &samwise, &swap_repay);
+
+            // 1 USDC is repaid directly and ~2 USDC is sold for 20 BLND at 0.1 USDC per BLND
+            assert!(sold > 3_0000000 && sold < 3_2000000);
+            assert_eq!(
+                usdc_client.balance(&pool),
+                pre_pool_usdc - (sold - 1_0000000)
+            );
+            assert_eq!(blnd_client.balance(&pool), pre_pool_blnd + 20_0000000);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_repay_with_swap_over_max_sold() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (usdc, _) = testutils::create_token_contract(&e, &bombadil);
+        let (blnd, _) = testutils::create_token_contract(&e, &bombadil);
+        let (amm, _) = create_comet_lp_pool(&e, &bombadil, &blnd, &usdc);
+
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &usdc, &reserve_config, &reserve_data);
+        testutils::create_reserve(&e, &pool, &blnd, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 2_0000000)],
+            collateral: map![&e],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_amm(&e, &amm);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            let bid = map![&e, (usdc.clone(), 2_0000000)];
+            repay_with_swap(&e, &mut pool_state, &mut user, &bid, &usdc, 1_0000000, 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_repay_with_swap_requires_reserve_token_in() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (usdc, _) = testutils::create_token_contract(&e, &bombadil);
+        let (blnd, _) = testutils::create_token_contract(&e, &bombadil);
+
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &usdc, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_1000000,
+            status: 0,
+            max_positions: 4,
+        };
+        let user_positions = Positions {
+            liabilities: map![&e, (0, 2_0000000)],
+            collateral: map![&e],
+            supply: map![&e],
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_user_positions(&e, &samwise, &user_positions);
+
+            let mut pool_state = Pool::load(&e);
+            let mut user = User::load(&e, &samwise);
+            let bid = map![&e, (usdc.clone(), 2_0000000)];
+            repay_with_swap(&e, &mut pool_state, &mut user, &bid, &blnd, 5_0000000, 0);
+        });
+    }
+}
@@ -0,0 +1,336 @@
+use cast::i128;
+use sep_41_token::TokenClient;
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{panic_with_error, unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::{SCALAR_7, TOP_UP_BOUNTY},
+    errors::PoolError,
+    storage::{self, TopUpRule},
+};
+
+use super::{actions::RequestType, health_factor::PositionData, Pool, User};
+
+/// Set or remove a user's collateral top-up rule for a reserve
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `asset` - The underlying asset of the reserve to top up
+/// * `rule` - The top-up rule, or None to remove the rule
+///
+/// ### Panics
+/// If the asset is not a reserve that can be used as collateral, or the rule does not trigger
+/// above a health factor of 1 or has no amount to top up
+pub fn execute_set_top_up(e: &Env, user: &Address, asset: &Address, rule: &Option<TopUpRule>) {
+    if !storage::has_res(e, asset) {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let reserve_config = storage::get_res_config(e, asset);
+    match rule {
+        Some(rule) => {
+            if rule.trigger_hf <= SCALAR_7 || rule.max_amount <= 0 || reserve_config.c_factor == 0 {
+                panic_with_error!(e, PoolError::BadRequest);
+            }
+            storage::set_top_up_rule(e, user, reserve_config.index, rule);
+        }
+        None => storage::del_top_up_rule(e, user, reserve_config.index),
+    }
+}
+
+/// Top up a user's collateral in a reserve from their allowance to the pool, restoring their
+/// health factor to the trigger of their top-up rule. The keeper executing the top-up is paid a
+/// bounty from the amount topped up.
+///
+/// The amount topped up is capped by the rule's remaining amount, which is reduced by each
+/// top-up. The rule is removed once the amount is used up.
+///
+/// Returns a tuple of (amount_supplied, bounty) where:
+/// * amount_supplied - The underlying tokens supplied as collateral for the user
+/// * bounty - The underlying tokens paid to the keeper
+///
+/// ### Arguments
+/// * `keeper` - The address executing the top-up
+/// * `user` - The address of the user whose collateral is topped up
+/// * `asset` - The underlying asset of the reserve to top up
+///
+/// ### Panics
+/// If the user has no top-up rule for the reserve, the user's health factor is not under the
+/// rule's trigger, the reserve takes a fee on transfer, or the user's allowance or balance
+/// is too low
+pub fn execute_top_up(e: &Env, keeper: &Address, user: &Address, asset: &Address) -> (i128, i128) {
+    if keeper == &e.current_contract_address() || user == &e.current_contract_address() {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let mut pool = Pool::load(e);
+    pool.require_action_allowed(e, RequestType::SupplyCollateral as u32);
+    let mut user_state = User::load(e, user);
+    let prev_positions_count = user_state.positions.effective_count();
+    let mut reserve = pool.load_reserve(e, asset, true);
+    if storage::get_res_config(e, asset).fee_on_transfer {
+        panic_with_error!(e, PoolError::BadRequest);
+    }
+    let reserve_index = reserve.index;
+    let mut rule = storage::get_top_up_rule(e, user, reserve_index)
+        .unwrap_or_else(|| panic_with_error!(e, PoolError::TopUpNotSet));
+
+    let position_data = PositionData::calculate_from_positions(e, &mut pool, &user_state.positions);
+    if !position_data.is_hf_under(rule.trigger_hf) {
+        panic_with_error!(e, PoolError::InvalidHf);
+    }
+
+    // the effective collateral needed to restore the trigger health factor, grossed up for the
+    // keeper's bounty
+    let deficit_base = position_data
+        .liability_base
+        .fixed_mul_ceil(rule.trigger_hf, SCALAR_7)
+        .unwrap_optimized()
+        - position_data.collateral_base;
+    let price = pool.load_price(e, asset);
+    let amount = deficit_base
+        .fixed_div_ceil(price, reserve.scalar)
+        .unwrap_optimized()
+        .fixed_div_ceil(i128(reserve.c_factor), SCALAR_7)
+        .unwrap_optimized()
+        .fixed_div_ceil(SCALAR_7 - TOP_UP_BOUNTY, SCALAR_7)
+        .unwrap_optimized()
+        .min(rule.max_amount);
+    let bounty = amount
+        .fixed_mul_floor(TOP_UP_BOUNTY, SCALAR_7)
+        .unwrap_optimized();
+    let amount_supplied = amount - bounty;
+
+    let b_tokens_minted = reserve.mint_b_tokens(e, amount_supplied);
+    user_state.add_collateral(e, &mut reserve, b_tokens_minted);
+    pool.cache_reserve(reserve);
+    pool.require_under_max(e, &user_state.positions, prev_positions_count);
+
+    pool.store_cached_reserves(e);
+    user_state.store(e);
+    rule.max_amount -= amount;
+    if rule.max_amount == 0 {
+        storage::del_top_up_rule(e, user, reserve_index);
+    } else {
+        storage::set_top_up_rule(e, user, reserve_index, &rule);
+    }
+
+    let token_client = TokenClient::new(e, asset);
+    token_client.transfer_from(
+        &e.current_contract_address(),
+        user,
+        &e.current_contract_address(),
+        &amount,
+    );
+    if bounty > 0 {
+        token_client.transfer(&e.current_contract_address(), keeper, &bounty);
+    }
+    (amount_supplied, bounty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{pool::Positions, testutils};
+    use soroban_sdk::{map, testutils::Address as _};
+
+    #[test]
+    fn test_set_top_up() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        // health factor of 15 * 0.75 / (2 * 5 / 0.75) = 0.84375
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 2_0000000)],
+            collateral: map![&e, (0, 15_0000000)],
+            supply: map![&e],
+        };
+        let (pool, underlying_0, _) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        let rule = TopUpRule {
+            trigger_hf: 1_2000000,
+            max_amount: 10_0000000,
+        };
+        e.as_contract(&pool, || {
+            execute_set_top_up(&e, &samwise, &underlying_0, &Some(rule.clone()));
+            assert_eq!(storage::get_top_up_rule(&e, &samwise, 0), Some(rule));
+
+            execute_set_top_up(&e, &samwise, &underlying_0, &None);
+            assert_eq!(storage::get_top_up_rule(&e, &samwise, 0), None);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_set_top_up_trigger_under_one() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths();
+
+        let samwise = Address::generate(&e);
+
+        // health factor of 15 * 0.75 / (2 * 5 / 0.75) = 0.84375
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 2_0000000)],
+            collateral: map![&e, (0, 15_0000000)],
+            supply: map![&e],
+        };
+        let (pool, underlying_0, _) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        let rule = TopUpRule {
+            trigger_hf: 1_0000000,
+            max_amount: 10_0000000,
+        };
+        e.as_contract(&pool, || {
+            execute_set_top_up(&e, &samwise, &underlying_0, &Some(rule));
+        });
+    }
+
+    #[test]
+    fn test_top_up() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        // health factor of 15 * 0.75 / (2 * 5 / 0.75) = 0.84375
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 2_0000000)],
+            collateral: map![&e, (0, 15_0000000)],
+            supply: map![&e],
+        };
+        let (pool, underlying_0, _) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+        let underlying_0_client = TokenClient::new(&e, &underlying_0);
+
+        e.as_contract(&pool, || {
+            storage::set_top_up_rule(
+                &e,
+                &samwise,
+                0,
+                &TopUpRule {
+                    trigger_hf: 1_2000000,
+                    max_amount: 50_0000000,
+                },
+            );
+
+            let (amount_supplied, bounty) = execute_top_up(&e, &frodo, &samwise, &underlying_0);
+            // (1.2 * 13.3333334 - 11.25) / 0.75 = 6.3333335 supplied, grossed up for the bounty
+            assert_eq!(amount_supplied, 6_3333337);
+            assert_eq!(bounty, 0_0318257);
+            assert_eq!(underlying_0_client.balance(&frodo), bounty);
+            assert_eq!(
+                underlying_0_client.balance(&samwise),
+                100_0000000 - amount_supplied - bounty
+            );
+
+            let user = User::load(&e, &samwise);
+            let mut pool_state = Pool::load(&e);
+            let position_data =
+                PositionData::calculate_from_positions(&e, &mut pool_state, &user.positions);
+            assert!(!position_data.is_hf_under(1_2000000));
+            assert_eq!(
+                storage::get_top_up_rule(&e, &samwise, 0)
+                    .unwrap()
+                    .max_amount,
+                50_0000000 - amount_supplied - bounty
+            );
+        });
+    }
+
+    #[test]
+    fn test_top_up_capped_by_max_amount() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        // health factor of 15 * 0.75 / (2 * 5 / 0.75) = 0.84375
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 2_0000000)],
+            collateral: map![&e, (0, 15_0000000)],
+            supply: map![&e],
+        };
+        let (pool, underlying_0, _) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        e.as_contract(&pool, || {
+            storage::set_top_up_rule(
+                &e,
+                &samwise,
+                0,
+                &TopUpRule {
+                    trigger_hf: 1_2000000,
+                    max_amount: 2_0000000,
+                },
+            );
+
+            let (amount_supplied, bounty) = execute_top_up(&e, &frodo, &samwise, &underlying_0);
+            assert_eq!(amount_supplied, 1_9900000);
+            assert_eq!(bounty, 0_0100000);
+            assert_eq!(storage::get_top_up_rule(&e, &samwise, 0), None);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1205)")]
+    fn test_top_up_above_trigger() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        // health factor of 15 * 0.75 / (2 * 5 / 0.75) = 0.84375
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 2_0000000)],
+            collateral: map![&e, (0, 15_0000000)],
+            supply: map![&e],
+        };
+        let (pool, underlying_0, _) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        e.as_contract(&pool, || {
+            storage::set_top_up_rule(
+                &e,
+                &samwise,
+                0,
+                &TopUpRule {
+                    trigger_hf: 0_8000000 + SCALAR_7,
+                    max_amount: 2_0000000,
+                },
+            );
+            let mut positions = storage::get_user_positions(&e, &samwise);
+            positions.collateral.set(0, 50_0000000);
+            storage::set_user_positions(&e, &samwise, &positions);
+
+            execute_top_up(&e, &frodo, &samwise, &underlying_0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1234)")]
+    fn test_top_up_not_set() {
+        let e = Env::default();
+        e.budget().reset_unlimited();
+        e.mock_all_auths_allowing_non_root_auth();
+
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+
+        // health factor of 15 * 0.75 / (2 * 5 / 0.75) = 0.84375
+        let user_positions = Positions {
+            liabilities: map![&e, (1, 2_0000000)],
+            collateral: map![&e, (0, 15_0000000)],
+            supply: map![&e],
+        };
+        let (pool, underlying_0, _) = testutils::create_priced_pool(&e, &samwise, &user_positions);
+
+        e.as_contract(&pool, || {
+            execute_top_up(&e, &frodo, &samwise, &underlying_0);
+        });
+    }
+}
@@ -2,7 +2,7 @@ use soroban_sdk::{contracttype, panic_with_error, Address, Env, Map};
 
 use crate::{emissions, storage, validator::require_nonnegative, PoolError};
 
-use super::{Pool, Reserve};
+use super::{update_user_interest, Pool, Reserve};
 
 /// A user / contracts position's with the pool, stored in the Reserve's decimals
 #[derive(Clone)]
@@ -37,6 +37,7 @@ impl Positions {
 pub struct User {
     pub address: Address,
     pub positions: Positions,
+    pub simulation: bool, // if emissions and interest are not checkpointed when positions change
 }
 
 impl User {
@@ -44,10 +45,19 @@ impl User {
     pub fn load(e: &Env, address: &Address) -> Self {
         User {
             address: address.clone(),
+            simulation: false,
             positions: storage::get_user_positions(e, address),
         }
     }
 
+    /// Load a User for a simulation, which does not checkpoint emissions or interest as the
+    /// positions change
+    pub fn load_for_simulation(e: &Env, address: &Address) -> Self {
+        let mut user = User::load(e, address);
+        user.simulation = true;
+        user
+    }
+
     /// Store the user's positions to the ledger
     pub fn store(&self, e: &Env) {
         storage::set_user_positions(e, &self.address, &self.positions);
@@ -71,6 +81,7 @@ impl User {
         }
         let balance = self.get_liabilities(reserve.index);
         self.update_d_emissions(e, reserve, balance);
+        self.update_d_interest(e, reserve, balance);
         self.positions
             .liabilities
             .set(reserve.index, balance + amount);
@@ -85,6 +96,7 @@ impl User {
         }
         let balance = self.get_liabilities(reserve.index);
         self.update_d_emissions(e, reserve, balance);
+        self.update_d_interest(e, reserve, balance);
         let new_balance = balance - amount;
         require_nonnegative(e, &new_balance);
         if new_balance == 0 {
@@ -108,10 +120,13 @@ impl User {
         }
         let balance = self.get_collateral(reserve.index);
         self.update_b_emissions(e, reserve, self.get_total_supply(reserve.index));
+        self.update_c_emissions(e, reserve, balance);
+        self.update_b_interest(e, reserve);
         self.positions
             .collateral
             .set(reserve.index, balance + amount);
         reserve.b_supply += amount;
+        reserve.c_supply += amount;
     }
 
     /// Remove collateral from the position expressed in blendTokens. Accrues emissions
@@ -122,6 +137,8 @@ impl User {
         }
         let balance = self.get_collateral(reserve.index);
         self.update_b_emissions(e, reserve, self.get_total_supply(reserve.index));
+        self.update_c_emissions(e, reserve, balance);
+        self.update_b_interest(e, reserve);
         let new_balance = balance - amount;
         require_nonnegative(e, &new_balance);
         if new_balance == 0 {
@@ -130,6 +147,9 @@ impl User {
             self.positions.collateral.set(reserve.index, new_balance);
         }
         reserve.b_supply -= amount;
+        // reserves stored before the collateral supply was tracked start it from zero, so
+        // collateral deposited before then can be withdrawn without it going negative
+        reserve.c_supply = (reserve.c_supply - amount).max(0);
     }
 
     /// Get the uncollateralized blendToken position for the reserve at the given index
@@ -145,6 +165,8 @@ impl User {
         }
         let balance = self.get_supply(reserve.index);
         self.update_b_emissions(e, reserve, self.get_total_supply(reserve.index));
+        self.update_s_emissions(e, reserve, balance);
+        self.update_b_interest(e, reserve);
         self.positions.supply.set(reserve.index, balance + amount);
         reserve.b_supply += amount;
     }
@@ -157,6 +179,8 @@ impl User {
         }
         let balance = self.get_supply(reserve.index);
         self.update_b_emissions(e, reserve, self.get_total_supply(reserve.index));
+        self.update_s_emissions(e, reserve, balance);
+        self.update_b_interest(e, reserve);
         let new_balance = balance - amount;
         require_nonnegative(e, &new_balance);
         if new_balance == 0 {
@@ -212,10 +236,20 @@ impl User {
         }
     }
 
+    fn update_d_interest(&self, e: &Env, reserve: &Reserve, amount: i128) {
+        if self.simulation {
+            return;
+        }
+        update_user_interest(e, &self.address, reserve.index * 2, reserve.d_rate, amount);
+    }
+
     fn update_d_emissions(&self, e: &Env, reserve: &Reserve, amount: i128) {
+        if self.simulation {
+            return;
+        }
         emissions::update_emissions(
             e,
-            reserve.index * 2,
+            emissions::to_res_token_id(reserve.index, 0),
             reserve.d_supply,
             reserve.scalar,
             &self.address,
@@ -223,16 +257,60 @@ impl User {
         );
     }
 
+    fn update_b_interest(&self, e: &Env, reserve: &Reserve) {
+        if self.simulation {
+            return;
+        }
+        update_user_interest(
+            e,
+            &self.address,
+            reserve.index * 2 + 1,
+            reserve.b_rate,
+            self.get_total_supply(reserve.index),
+        );
+    }
+
     fn update_b_emissions(&self, e: &Env, reserve: &Reserve, amount: i128) {
+        if self.simulation {
+            return;
+        }
         emissions::update_emissions(
             e,
-            reserve.index * 2 + 1,
+            emissions::to_res_token_id(reserve.index, 1),
             reserve.b_supply,
             reserve.scalar,
             &self.address,
             amount,
         );
     }
+
+    fn update_c_emissions(&self, e: &Env, reserve: &Reserve, amount: i128) {
+        if self.simulation {
+            return;
+        }
+        emissions::update_emissions(
+            e,
+            emissions::to_res_token_id(reserve.index, 2),
+            reserve.c_supply,
+            reserve.scalar,
+            &self.address,
+            amount,
+        );
+    }
+
+    fn update_s_emissions(&self, e: &Env, reserve: &Reserve, amount: i128) {
+        if self.simulation {
+            return;
+        }
+        emissions::update_emissions(
+            e,
+            emissions::to_res_token_id(reserve.index, 3),
+            reserve.b_supply - reserve.c_supply,
+            reserve.scalar,
+            &self.address,
+            amount,
+        );
+    }
 }
 
 #[cfg(test)]
@@ -256,6 +334,7 @@ mod tests {
 
         let user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions {
                 collateral: map![&e, (0, 10000)],
                 liabilities: map![&e],
@@ -288,6 +367,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         e.as_contract(&pool, || {
@@ -332,6 +412,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         e.as_contract(&pool, || {
@@ -376,6 +457,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions {
                 liabilities: map![&e, (reserve_0.index, 1000)],
                 collateral: map![&e],
@@ -421,6 +503,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         e.as_contract(&pool, || {
@@ -467,6 +550,7 @@ mod tests {
         };
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions {
                 liabilities: map![&e, (reserve_0.index, 1000)],
                 collateral: map![&e],
@@ -510,6 +594,7 @@ mod tests {
         let mut reserve_0 = testutils::default_reserve(&e);
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         e.as_contract(&pool, || {
@@ -535,6 +620,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         e.as_contract(&pool, || {
@@ -543,6 +629,7 @@ mod tests {
             user.add_collateral(&e, &mut reserve_0, 123);
             assert_eq!(user.get_collateral(0), 123);
             assert_eq!(reserve_0.b_supply, starting_b_supply_0 + 123);
+            assert_eq!(reserve_0.c_supply, 123);
 
             user.add_collateral(&e, &mut reserve_1, 456);
             assert_eq!(user.get_collateral(0), 123);
@@ -557,6 +644,7 @@ mod tests {
             assert_eq!(user.get_collateral(1), 0);
             assert_eq!(user.positions.collateral.len(), 1);
             assert_eq!(reserve_1.b_supply, starting_b_supply_1);
+            assert_eq!(reserve_1.c_supply, 0);
         });
     }
 
@@ -571,6 +659,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         e.as_contract(&pool, || {
@@ -615,6 +704,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions {
                 liabilities: map![&e],
                 collateral: map![&e, (reserve_0.index, 700)],
@@ -648,6 +738,84 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_add_collateral_accrues_collateral_emissions() {
+        let e = Env::default();
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        e.ledger().set(LedgerInfo {
+            protocol_version: 20,
+            sequence_number: 1,
+            timestamp: 10001000,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let mut reserve_0 = testutils::default_reserve(&e);
+        reserve_0.c_supply = 70_0000000;
+        let starting_b_token_supply = reserve_0.b_supply;
+
+        let emis_res_config = ReserveEmissionsConfig {
+            expiration: 20000000,
+            eps: 0_1000000,
+        };
+        let emis_res_data = ReserveEmissionsData {
+            index: 1000,
+            last_time: 10000000, // 1000s elapsed
+        };
+        let emis_user_data = UserEmissionData {
+            index: 900,
+            accrued: 0,
+        };
+
+        let mut user = User {
+            address: samwise.clone(),
+            simulation: false,
+            positions: Positions {
+                liabilities: map![&e],
+                collateral: map![&e, (reserve_0.index, 700)],
+                supply: map![&e, (reserve_0.index, 300)],
+            },
+        };
+        e.as_contract(&pool, || {
+            let res_0_c_token_index = emissions::to_res_token_id(reserve_0.index, 2);
+            let res_0_s_token_index = emissions::to_res_token_id(reserve_0.index, 3);
+            storage::set_res_emis_config(&e, &res_0_c_token_index, &emis_res_config);
+            storage::set_res_emis_data(&e, &res_0_c_token_index, &emis_res_data);
+            storage::set_user_emissions(&e, &samwise, &res_0_c_token_index, &emis_user_data);
+            storage::set_res_emis_config(&e, &res_0_s_token_index, &emis_res_config);
+            storage::set_res_emis_data(&e, &res_0_s_token_index, &emis_res_data);
+
+            user.add_collateral(&e, &mut reserve_0, 123);
+            assert_eq!(user.get_collateral(0), 823);
+            assert_eq!(reserve_0.b_supply, starting_b_token_supply + 123);
+            assert_eq!(reserve_0.c_supply, 70_0000123);
+
+            let new_emis_res_data = storage::get_res_emis_data(&e, &res_0_c_token_index).unwrap();
+            let new_index = 1000
+                + (1000i128 * 0_1000000)
+                    .fixed_div_floor(70_0000000, SCALAR_7)
+                    .unwrap();
+            assert_eq!(new_emis_res_data.last_time, 10001000);
+            assert_eq!(new_emis_res_data.index, new_index);
+            let user_emis_data =
+                storage::get_user_emissions(&e, &samwise, &res_0_c_token_index).unwrap();
+            let new_accrual = (new_index - emis_user_data.index)
+                .fixed_mul_floor(700, SCALAR_7)
+                .unwrap();
+            assert_eq!(user_emis_data.accrued, new_accrual);
+
+            // non-collateral supply emissions are not touched by collateral changes
+            let s_emis_res_data = storage::get_res_emis_data(&e, &res_0_s_token_index).unwrap();
+            assert_eq!(s_emis_res_data.last_time, 10000000);
+            assert!(storage::get_user_emissions(&e, &samwise, &res_0_s_token_index).is_none());
+        });
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #1217)")]
     fn test_remove_collateral_zero_burn() {
@@ -659,6 +827,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         e.as_contract(&pool, || {
@@ -706,6 +875,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions {
                 liabilities: map![&e],
                 collateral: map![&e, (reserve_0.index, 700)],
@@ -750,6 +920,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         e.as_contract(&pool, || {
@@ -775,6 +946,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         e.as_contract(&pool, || {
@@ -811,6 +983,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         e.as_contract(&pool, || {
@@ -855,6 +1028,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions {
                 liabilities: map![&e],
                 collateral: map![&e, (reserve_0.index, 700)],
@@ -888,6 +1062,84 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_add_supply_accrues_supply_emissions() {
+        let e = Env::default();
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        e.ledger().set(LedgerInfo {
+            protocol_version: 20,
+            sequence_number: 1,
+            timestamp: 10001000,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+
+        let mut reserve_0 = testutils::default_reserve(&e);
+        reserve_0.c_supply = 70_0000000;
+        let starting_b_token_supply = reserve_0.b_supply;
+
+        let emis_res_config = ReserveEmissionsConfig {
+            expiration: 20000000,
+            eps: 0_1000000,
+        };
+        let emis_res_data = ReserveEmissionsData {
+            index: 1000,
+            last_time: 10000000, // 1000s elapsed
+        };
+        let emis_user_data = UserEmissionData {
+            index: 900,
+            accrued: 0,
+        };
+
+        let mut user = User {
+            address: samwise.clone(),
+            simulation: false,
+            positions: Positions {
+                liabilities: map![&e],
+                collateral: map![&e, (reserve_0.index, 700)],
+                supply: map![&e, (reserve_0.index, 300)],
+            },
+        };
+        e.as_contract(&pool, || {
+            let res_0_s_token_index = emissions::to_res_token_id(reserve_0.index, 3);
+            let res_0_c_token_index = emissions::to_res_token_id(reserve_0.index, 2);
+            storage::set_res_emis_config(&e, &res_0_s_token_index, &emis_res_config);
+            storage::set_res_emis_data(&e, &res_0_s_token_index, &emis_res_data);
+            storage::set_user_emissions(&e, &samwise, &res_0_s_token_index, &emis_user_data);
+            storage::set_res_emis_config(&e, &res_0_c_token_index, &emis_res_config);
+            storage::set_res_emis_data(&e, &res_0_c_token_index, &emis_res_data);
+
+            user.add_supply(&e, &mut reserve_0, 123);
+            assert_eq!(user.get_supply(0), 423);
+            assert_eq!(reserve_0.b_supply, starting_b_token_supply + 123);
+            assert_eq!(reserve_0.c_supply, 70_0000000);
+
+            let new_emis_res_data = storage::get_res_emis_data(&e, &res_0_s_token_index).unwrap();
+            let new_index = 1000
+                + (1000i128 * 0_1000000)
+                    .fixed_div_floor(starting_b_token_supply - 70_0000000, SCALAR_7)
+                    .unwrap();
+            assert_eq!(new_emis_res_data.last_time, 10001000);
+            assert_eq!(new_emis_res_data.index, new_index);
+            let user_emis_data =
+                storage::get_user_emissions(&e, &samwise, &res_0_s_token_index).unwrap();
+            let new_accrual = (new_index - emis_user_data.index)
+                .fixed_mul_floor(300, SCALAR_7)
+                .unwrap();
+            assert_eq!(user_emis_data.accrued, new_accrual);
+
+            // collateral emissions are not touched by non-collateral supply changes
+            let c_emis_res_data = storage::get_res_emis_data(&e, &res_0_c_token_index).unwrap();
+            assert_eq!(c_emis_res_data.last_time, 10000000);
+            assert!(storage::get_user_emissions(&e, &samwise, &res_0_c_token_index).is_none());
+        });
+    }
+
     #[test]
     #[should_panic(expected = "Error(Contract, #1217)")]
     fn test_remove_supply_zero_burn() {
@@ -899,6 +1151,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         e.as_contract(&pool, || {
@@ -946,6 +1199,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions {
                 liabilities: map![&e],
                 collateral: map![&e, (reserve_0.index, 700)],
@@ -990,6 +1244,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         e.as_contract(&pool, || {
@@ -1013,6 +1268,7 @@ mod tests {
 
         let mut user = User {
             address: samwise.clone(),
+            simulation: false,
             positions: Positions::env_default(&e),
         };
         e.as_contract(&pool, || {
@@ -0,0 +1,366 @@
+use cast::i128;
+use soroban_fixed_point_math::FixedPoint;
+use soroban_sdk::{unwrap::UnwrapOptimized, Address, Env};
+
+use crate::{
+    constants::SCALAR_7,
+    errors::{panic_with_context, PoolError},
+    storage::{self, QueuedWithdrawal, WithdrawQueueConfig, WithdrawWindow},
+};
+
+use super::Reserve;
+
+/// Fetch the amount withdrawn from the reserve without being queued during the current window.
+/// A window lasts for the reserve's queue delay.
+fn load_window_withdrawn(e: &Env, reserve: &Reserve, config: &WithdrawQueueConfig) -> i128 {
+    match storage::get_withdraw_window(e, &reserve.asset) {
+        Some(window) if window.start + config.delay > e.ledger().timestamp() => window.withdrawn,
+        _ => 0,
+    }
+}
+
+/// Fetch the delay a withdrawal of `amount` underlying tokens from the reserve must be queued
+/// for, or None if it can be executed immediately.
+///
+/// Withdrawals are only queued for reserves with a withdrawal queue config, and only when the
+/// amount withdrawn from the reserve during the current window, including this withdrawal,
+/// exceeds the configured percent of the reserve's available liquidity at the start of the
+/// window.
+///
+/// ### Arguments
+/// * `reserve` - The reserve being withdrawn from
+/// * `pending` - The amount withdrawn from the reserve earlier in the submit, which is not yet
+///   recorded against the window
+/// * `amount` - The amount of underlying tokens being withdrawn
+pub fn calc_withdraw_queue_delay(
+    e: &Env,
+    reserve: &Reserve,
+    pending: i128,
+    amount: i128,
+) -> Option<u64> {
+    let config = storage::get_withdraw_queue_config(e, &reserve.asset)?;
+    let withdrawn = load_window_withdrawn(e, reserve, &config) + pending;
+    let available = (reserve.total_supply() - reserve.total_liabilities()).max(0) + withdrawn;
+    let max_amount = available
+        .fixed_mul_floor(i128(config.threshold), SCALAR_7)
+        .unwrap_optimized();
+    if withdrawn + amount > max_amount {
+        Some(config.delay)
+    } else {
+        None
+    }
+}
+
+/// Record a withdrawal executed from the reserve without being queued against the current
+/// window, starting a new window if the last one has ended
+///
+/// ### Arguments
+/// * `asset` - The underlying asset of the reserve withdrawn from
+/// * `amount` - The amount of underlying tokens withdrawn
+pub fn record_withdrawal(e: &Env, asset: &Address, amount: i128) {
+    if let Some(config) = storage::get_withdraw_queue_config(e, asset) {
+        let window = match storage::get_withdraw_window(e, asset) {
+            Some(window) if window.start + config.delay > e.ledger().timestamp() => {
+                WithdrawWindow {
+                    start: window.start,
+                    withdrawn: window.withdrawn + amount,
+                }
+            }
+            _ => WithdrawWindow {
+                start: e.ledger().timestamp(),
+                withdrawn: amount,
+            },
+        };
+        storage::set_withdraw_window(e, asset, &window);
+    }
+}
+
+/// Remove a user's queued withdrawal from the reserve so it can be executed
+///
+/// ### Arguments
+/// * `user` - The user withdrawing
+/// * `reserve` - The reserve being withdrawn from
+/// * `request_index` - The index of the request executing the withdrawal
+///
+/// ### Panics
+/// If the user has no queued withdrawal for the reserve, or it is not unlocked
+pub fn take_queued_withdrawal(
+    e: &Env,
+    user: &Address,
+    reserve: &Reserve,
+    request_index: u32,
+) -> QueuedWithdrawal {
+    let withdrawal = storage::get_queued_withdrawal(e, user, reserve.index).unwrap_or_else(|| {
+        panic_with_context(
+            e,
+            PoolError::BadRequest,
+            Some(request_index),
+            Some(reserve.asset.clone()),
+        )
+    });
+    if withdrawal.unlock_time > e.ledger().timestamp() {
+        panic_with_context(
+            e,
+            PoolError::WithdrawNotUnlocked,
+            Some(request_index),
+            Some(reserve.asset.clone()),
+        );
+    }
+    storage::del_queued_withdrawal(e, user, reserve.index);
+    withdrawal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        pool::Pool,
+        storage::{PoolConfig, WithdrawQueueConfig},
+        testutils,
+    };
+    use soroban_sdk::testutils::{Address as _, Ledger, LedgerInfo};
+
+    #[test]
+    fn test_calc_withdraw_queue_delay() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+            let reserve = pool.load_reserve(&e, &underlying, false);
+
+            // no config never requires a queue
+            assert_eq!(
+                calc_withdraw_queue_delay(&e, &reserve, 0, 100_0000000),
+                None
+            );
+
+            storage::set_withdraw_queue_config(
+                &e,
+                &underlying,
+                &WithdrawQueueConfig {
+                    threshold: 0_1000000,
+                    delay: 3600,
+                },
+            );
+            // 100 supplied and 75 borrowed leaves 25 available
+            assert_eq!(calc_withdraw_queue_delay(&e, &reserve, 0, 2_5000000), None);
+            assert_eq!(
+                calc_withdraw_queue_delay(&e, &reserve, 0, 2_5000001),
+                Some(3600)
+            );
+        });
+    }
+
+    #[test]
+    fn test_calc_withdraw_queue_delay_cumulative() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            storage::set_withdraw_queue_config(
+                &e,
+                &underlying,
+                &WithdrawQueueConfig {
+                    threshold: 0_1000000,
+                    delay: 3600,
+                },
+            );
+            let mut pool = Pool::load(&e);
+            let reserve = pool.load_reserve(&e, &underlying, false);
+
+            record_withdrawal(&e, &underlying, 2_0000000);
+            assert_eq!(
+                storage::get_withdraw_window(&e, &underlying),
+                Some(WithdrawWindow {
+                    start: 0,
+                    withdrawn: 2_0000000,
+                })
+            );
+
+            // 25 available plus the 2 withdrawn during the window allows 2.7 per window
+            assert_eq!(calc_withdraw_queue_delay(&e, &reserve, 0, 0_7000000), None);
+            assert_eq!(
+                calc_withdraw_queue_delay(&e, &reserve, 0, 0_7000001),
+                Some(3600)
+            );
+
+            e.ledger().set(LedgerInfo {
+                timestamp: 3600,
+                protocol_version: 20,
+                sequence_number: 1234,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 10,
+                min_persistent_entry_ttl: 10,
+                max_entry_ttl: 3110400,
+            });
+            // the window has ended
+            assert_eq!(calc_withdraw_queue_delay(&e, &reserve, 0, 2_5000000), None);
+            record_withdrawal(&e, &underlying, 1_0000000);
+            assert_eq!(
+                storage::get_withdraw_window(&e, &underlying),
+                Some(WithdrawWindow {
+                    start: 3600,
+                    withdrawn: 1_0000000,
+                })
+            );
+        });
+    }
+
+    #[test]
+    fn test_take_queued_withdrawal() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        e.ledger().set(LedgerInfo {
+            timestamp: 600,
+            protocol_version: 20,
+            sequence_number: 1234,
+            network_id: Default::default(),
+            base_reserve: 10,
+            min_temp_entry_ttl: 10,
+            min_persistent_entry_ttl: 10,
+            max_entry_ttl: 3110400,
+        });
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+            let reserve = pool.load_reserve(&e, &underlying, false);
+
+            let withdrawal = QueuedWithdrawal {
+                amount: 10_0000000,
+                unlock_time: 600 + 3600,
+                collateral: false,
+            };
+            storage::set_queued_withdrawal(&e, &samwise, reserve.index, &withdrawal);
+
+            e.ledger().set(LedgerInfo {
+                timestamp: 600 + 3600,
+                protocol_version: 20,
+                sequence_number: 1234,
+                network_id: Default::default(),
+                base_reserve: 10,
+                min_temp_entry_ttl: 10,
+                min_persistent_entry_ttl: 10,
+                max_entry_ttl: 3110400,
+            });
+            let taken = take_queued_withdrawal(&e, &samwise, &reserve, 0);
+            assert_eq!(taken, withdrawal);
+            assert_eq!(
+                storage::get_queued_withdrawal(&e, &samwise, reserve.index),
+                None
+            );
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1230)")]
+    fn test_take_queued_withdrawal_requires_unlock() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+            let reserve = pool.load_reserve(&e, &underlying, false);
+
+            storage::set_queued_withdrawal(
+                &e,
+                &samwise,
+                reserve.index,
+                &QueuedWithdrawal {
+                    amount: 10_0000000,
+                    unlock_time: 1,
+                    collateral: false,
+                },
+            );
+            take_queued_withdrawal(&e, &samwise, &reserve, 0);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1200)")]
+    fn test_take_queued_withdrawal_requires_queue() {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let bombadil = Address::generate(&e);
+        let samwise = Address::generate(&e);
+        let pool = testutils::create_pool(&e);
+
+        let (underlying, _) = testutils::create_token_contract(&e, &bombadil);
+        let (reserve_config, reserve_data) = testutils::default_reserve_meta();
+        testutils::create_reserve(&e, &pool, &underlying, &reserve_config, &reserve_data);
+
+        let pool_config = PoolConfig {
+            oracle: Address::generate(&e),
+            bstop_rate: 0_2000000,
+            status: 0,
+            max_positions: 2,
+        };
+        e.as_contract(&pool, || {
+            storage::set_pool_config(&e, &pool_config);
+            let mut pool = Pool::load(&e);
+            let reserve = pool.load_reserve(&e, &underlying, false);
+
+            take_queued_withdrawal(&e, &samwise, &reserve, 0);
+        });
+    }
+}
@@ -1,9 +1,14 @@
 use soroban_sdk::{
-    contracttype, map, panic_with_error, unwrap::UnwrapOptimized, vec, Address, Env, IntoVal, Map,
-    String, Symbol, TryFromVal, Val, Vec,
+    contracttype, map, panic_with_error, unwrap::UnwrapOptimized, vec, Address, BytesN, Env,
+    IntoVal, Map, MapObject, String, Symbol, TryFromVal, Val, Vec,
 };
 
-use crate::{auctions::AuctionData, pool::Positions, PoolError};
+use crate::{
+    auctions::AuctionData,
+    constants::{DEFAULT_AUCTION_CURVE_BLOCKS, SECONDS_PER_WEEK},
+    pool::Positions,
+    PoolError,
+};
 
 /********** Ledger Thresholds **********/
 
@@ -30,6 +35,38 @@ pub struct PoolConfig {
     pub max_positions: u32, // the maximum number of effective positions (collateral + liabilities) a single user can hold
 }
 
+/// The pool's display metadata
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PoolMetadata {
+    pub name: String,            // the name of the pool
+    pub icon_hash: BytesN<32>,   // the sha256 hash of the pool's icon
+    pub description_uri: String, // the URI of the pool's description
+}
+
+#[derive(Clone)]
+#[contracttype]
+pub struct QueuedPoolMetadata {
+    pub new_metadata: PoolMetadata,
+    pub unlock_time: u64,
+}
+
+/// A queued update to the AMM used to sell collateral
+#[derive(Clone)]
+#[contracttype]
+pub struct QueuedAmm {
+    pub amm: Address,
+    pub unlock_time: u64,
+}
+
+/// A queued upgrade of the pool's WASM
+#[derive(Clone)]
+#[contracttype]
+pub struct QueuedUpgrade {
+    pub wasm_hash: BytesN<32>,
+    pub unlock_time: u64,
+}
+
 /// The pool's emission config
 #[derive(Clone)]
 #[contracttype]
@@ -42,17 +79,138 @@ pub struct PoolEmissionConfig {
 #[derive(Clone)]
 #[contracttype]
 pub struct ReserveConfig {
-    pub index: u32,      // the index of the reserve in the list
-    pub decimals: u32,   // the decimals used in both the bToken and underlying contract
-    pub c_factor: u32,   // the collateral factor for the reserve scaled expressed in 7 decimals
-    pub l_factor: u32,   // the liability factor for the reserve scaled expressed in 7 decimals
-    pub util: u32,       // the target utilization rate scaled expressed in 7 decimals
-    pub max_util: u32,   // the maximum allowed utilization rate scaled expressed in 7 decimals
+    pub index: u32,             // the index of the reserve in the list
+    pub decimals: u32,          // the decimals used in both the bToken and underlying contract
+    pub c_factor: u32, // the collateral factor for the reserve scaled expressed in 7 decimals
+    pub l_factor: u32, // the liability factor for the reserve scaled expressed in 7 decimals
+    pub util: u32,     // the target utilization rate scaled expressed in 7 decimals
+    pub max_util: u32, // the maximum allowed utilization rate scaled expressed in 7 decimals
     pub r_base: u32, // the R0 value (base rate) in the interest rate formula scaled expressed in 7 decimals
     pub r_one: u32,  // the R1 value in the interest rate formula scaled expressed in 7 decimals
     pub r_two: u32,  // the R2 value in the interest rate formula scaled expressed in 7 decimals
     pub r_three: u32, // the R3 value in the interest rate formula scaled expressed in 7 decimals
     pub reactivity: u32, // the reactivity constant for the reserve scaled expressed in 7 decimals
+    pub fee_on_transfer: bool, // true if the underlying token charges a fee on transfer
+    pub min_first_supply: i128, // the minimum amount of underlying the first supply to an empty reserve must be
+    pub locked_b_tokens: u32, // the bTokens taken from the first supply to an empty reserve and locked in the reserve
+}
+
+/// The storage layout of a ReserveConfig, with four 32 bit fields packed into each of the first
+/// three words, and the locked bTokens and minimum first supply packed into the last word
+pub type PackedReserveConfig = (u128, u128, u128, u128);
+
+/// The original storage layout of a ReserveConfig, written as a map by pools deployed before
+/// reserve configs were packed
+#[derive(Clone)]
+#[contracttype]
+pub(crate) struct LegacyReserveConfig {
+    pub index: u32,
+    pub decimals: u32,
+    pub c_factor: u32,
+    pub l_factor: u32,
+    pub util: u32,
+    pub max_util: u32,
+    pub r_base: u32,
+    pub r_one: u32,
+    pub r_two: u32,
+    pub r_three: u32,
+    pub reactivity: u32,
+}
+
+impl ReserveConfig {
+    /// Pack the config into its storage layout
+    ///
+    /// ### Panics
+    /// If the minimum first supply does not fit in the 96 bits it is packed into
+    pub fn pack(&self, e: &Env) -> PackedReserveConfig {
+        if self.min_first_supply < 0 || self.min_first_supply >= 1 << 96 {
+            panic_with_error!(e, PoolError::InvalidReserveMetadata);
+        }
+        (
+            pack_u32s(self.index, self.decimals, self.c_factor, self.l_factor),
+            pack_u32s(self.util, self.max_util, self.r_base, self.r_one),
+            pack_u32s(
+                self.r_two,
+                self.r_three,
+                self.reactivity,
+                self.fee_on_transfer as u32,
+            ),
+            (self.locked_b_tokens as u128) | (self.min_first_supply as u128) << 32,
+        )
+    }
+
+    /// Unpack a config from its storage layout
+    pub fn unpack(packed: &PackedReserveConfig) -> ReserveConfig {
+        let (index, decimals, c_factor, l_factor) = unpack_u32s(packed.0);
+        let (util, max_util, r_base, r_one) = unpack_u32s(packed.1);
+        let (r_two, r_three, reactivity, fee_on_transfer) = unpack_u32s(packed.2);
+        ReserveConfig {
+            index,
+            decimals,
+            c_factor,
+            l_factor,
+            util,
+            max_util,
+            r_base,
+            r_one,
+            r_two,
+            r_three,
+            reactivity,
+            fee_on_transfer: fee_on_transfer != 0,
+            min_first_supply: (packed.3 >> 32) as i128,
+            locked_b_tokens: packed.3 as u32,
+        }
+    }
+
+    /// Unpack a config from the current storage layout, the three word layout, or the original
+    /// map layout. Configs in the older layouts did not store a minimum first supply or locked
+    /// bTokens, so both default to zero, and map layout configs are not fee on transfer.
+    pub fn unpack_stored(e: &Env, stored: &Val) -> ReserveConfig {
+        if MapObject::try_from(*stored).is_ok() {
+            let legacy = LegacyReserveConfig::try_from_val(e, stored).unwrap_optimized();
+            return ReserveConfig {
+                index: legacy.index,
+                decimals: legacy.decimals,
+                c_factor: legacy.c_factor,
+                l_factor: legacy.l_factor,
+                util: legacy.util,
+                max_util: legacy.max_util,
+                r_base: legacy.r_base,
+                r_one: legacy.r_one,
+                r_two: legacy.r_two,
+                r_three: legacy.r_three,
+                reactivity: legacy.reactivity,
+                fee_on_transfer: false,
+                min_first_supply: 0,
+                locked_b_tokens: 0,
+            };
+        }
+        let words = Vec::<u128>::try_from_val(e, stored).unwrap_optimized();
+        let last = if words.len() == 3 {
+            0
+        } else {
+            words.get_unchecked(3)
+        };
+        ReserveConfig::unpack(&(
+            words.get_unchecked(0),
+            words.get_unchecked(1),
+            words.get_unchecked(2),
+            last,
+        ))
+    }
+}
+
+fn pack_u32s(a: u32, b: u32, c: u32, d: u32) -> u128 {
+    (a as u128) | (b as u128) << 32 | (c as u128) << 64 | (d as u128) << 96
+}
+
+fn unpack_u32s(word: u128) -> (u32, u32, u32, u32) {
+    (
+        word as u32,
+        (word >> 32) as u32,
+        (word >> 64) as u32,
+        (word >> 96) as u32,
+    )
 }
 
 #[derive(Clone)]
@@ -62,17 +220,256 @@ pub struct QueuedReserveInit {
     pub unlock_time: u64,
 }
 
-/// The data for a reserve asset
+/// The bond posted in backstop tokens to propose a new reserve
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ReserveBond {
+    pub proposer: Address, // the address that posted the bond and is refunded if the reserve is set
+    pub amount: i128,      // the amount of backstop tokens posted
+}
+
+/// The Dutch auction curve used to scale auctions when they are filled. The lot is ramped in from
+/// 0% to 100% over `lot_blocks`, then the bid is decayed from 100% to 0% over `bid_blocks`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct AuctionCurve {
+    pub lot_blocks: u32, // the number of blocks the lot is ramped in over
+    pub bid_blocks: u32, // the number of blocks the bid is decayed over
+}
+
+/// The configuration for queueing large withdrawals from a reserve
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct WithdrawQueueConfig {
+    pub threshold: u32, // the percent of available liquidity a withdrawal can exceed before it must be queued expressed in 7 decimals
+    pub delay: u64, // the time in seconds a queued withdrawal must wait before it can be executed
+}
+
+/// The exchange rate source for a yield-bearing reserve asset priced from a base asset
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ExchangeRateConfig {
+    pub rate_contract: Address, // the contract reporting the exchange rate of the reserve asset to the base asset
+    pub base_asset: Address,    // the asset whose oracle price the exchange rate is applied to
+    pub max_age: u64,           // the maximum time in seconds since the exchange rate was updated
+}
+
+/// A reserve's token supplies before it was first modified in a ledger
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct SupplySnapshot {
+    pub sequence: u32,  // the ledger sequence the snapshot was taken in
+    pub b_supply: i128, // the total bToken supply
+    pub d_supply: i128, // the total dToken supply
+}
+
+/// A checkpoint of a reserve's bToken and dToken rates
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RateCheckpoint {
+    pub timestamp: u64, // the time the checkpoint was taken
+    pub b_rate: i128,   // the conversion rate from bToken to underlying (9 decimals)
+    pub d_rate: i128,   // the conversion rate from dToken to underlying (9 decimals)
+}
+
+/// The last oracle price the pool observed for an asset, used if the oracle stops reporting a
+/// price for the asset
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct CachedPrice {
+    pub price: i128,     // the oracle price
+    pub timestamp: u64,  // the oracle timestamp of the price
+    pub emergency: bool, // true if the oracle no longer reports a price for the asset
+}
+
+/// The partner pool whose bTokens a reserve asset represents, priced from the partner pool's
+/// bToken rate for the base asset
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PartnerCollateral {
+    pub partner_pool: Address, // the whitelisted partner pool the bTokens were minted by
+    pub base_asset: Address,   // the underlying asset of the partner pool's reserve
+}
+
+/// An update to the exchange rate config of a reserve
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ExchangeRateUpdate {
+    Set(ExchangeRateConfig),
+    Remove,
+}
+
 #[derive(Clone)]
 #[contracttype]
+pub struct QueuedExchangeRate {
+    pub update: ExchangeRateUpdate,
+    pub unlock_time: u64,
+}
+
+/// A withdrawal from a reserve waiting to be executed
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct QueuedWithdrawal {
+    pub amount: i128,     // the amount of underlying tokens to withdraw
+    pub unlock_time: u64, // the time the withdrawal can be executed at
+    pub collateral: bool, // whether the withdrawal is from the user's collateral or supply
+}
+
+/// The withdrawals executed from a reserve without being queued during the current window
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct WithdrawWindow {
+    pub start: u64,      // the time the window started
+    pub withdrawn: i128, // the amount of underlying tokens withdrawn during the window
+}
+
+/// A rule allowing keepers to top up a user's collateral in a reserve when their health factor
+/// falls below a trigger. Top-ups are pulled from the user's allowance to the pool.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TopUpRule {
+    pub trigger_hf: i128, // the health factor a top-up can be executed under, and restores (7 decimals)
+    pub max_amount: i128, // the remaining amount of underlying tokens that can be topped up
+}
+
+/// A borrow from a reserve at an interest rate locked for a fixed term
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct FixedLoan {
+    pub d_tokens: i128, // the dTokens backing the loan, held in the user's liabilities
+    pub principal: i128, // the amount of underlying tokens borrowed
+    pub rate: i128,     // the locked annual interest rate (7 decimals)
+    pub start: u64,     // the time the loan was taken
+    pub maturity: u64,  // the time the loan can be repaid without a penalty
+}
+
+/// A borrow from a reserve at a stable interest rate, which is only adjusted when it is
+/// rebalanced
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct StableDebt {
+    pub d_tokens: i128, // the dTokens backing the debt, held in the user's liabilities
+    pub principal: i128, // the amount of underlying tokens owed as of the last update
+    pub rate: i128,     // the stable annual interest rate (7 decimals)
+    pub last_time: u64, // the time the debt was last updated
+}
+
+/// The data for a reserve asset
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
 pub struct ReserveData {
     pub d_rate: i128, // the conversion rate from dToken to underlying expressed in 9 decimals
     pub b_rate: i128, // the conversion rate from bToken to underlying expressed with the underlying's decimals
     pub ir_mod: i128, // the interest rate curve modifier
+    pub util_avg: i128, // the time-weighted utilization rate used to update the interest rate curve modifier
     pub b_supply: i128, // the total supply of b tokens
+    pub c_supply: i128, // the total supply of b tokens used as collateral
     pub d_supply: i128, // the total supply of d tokens
     pub backstop_credit: i128, // the amount of underlying tokens currently owed to the backstop
-    pub last_time: u64, // the last block the data was updated
+    pub interest_paid: i128, // the lifetime amount of interest paid by borrowers in underlying tokens
+    pub interest_earned: i128, // the lifetime amount of interest earned by suppliers in underlying tokens
+    pub ramp_c_factor: u32, // the collateral factor a reduction is ramping down from scaled expressed in 7 decimals
+    pub ramp_start: u64,    // the time the collateral factor reduction started ramping
+    pub ramp_end: u64,      // the time the collateral factor reduction is fully applied
+    pub last_time: u64,     // the last block the data was updated
+}
+
+/// The storage layout of a ReserveData, with the collateral factor ramp and timestamps packed
+/// into the last two words
+pub type PackedReserveData = (
+    i128,
+    i128,
+    i128,
+    i128,
+    i128,
+    i128,
+    i128,
+    i128,
+    i128,
+    i128,
+    u128,
+    u128,
+);
+
+/// The original storage layout of a ReserveData, written as a map by pools deployed before
+/// reserve data was packed
+#[derive(Clone)]
+#[contracttype]
+pub(crate) struct LegacyReserveData {
+    pub d_rate: i128,
+    pub b_rate: i128,
+    pub ir_mod: i128,
+    pub b_supply: i128,
+    pub d_supply: i128,
+    pub backstop_credit: i128,
+    pub last_time: u64,
+}
+
+impl ReserveData {
+    /// Pack the data into its storage layout
+    pub fn pack(&self) -> PackedReserveData {
+        (
+            self.d_rate,
+            self.b_rate,
+            self.ir_mod,
+            self.util_avg,
+            self.b_supply,
+            self.c_supply,
+            self.d_supply,
+            self.backstop_credit,
+            self.interest_paid,
+            self.interest_earned,
+            (self.ramp_c_factor as u128) | (self.ramp_start as u128) << 64,
+            (self.ramp_end as u128) | (self.last_time as u128) << 64,
+        )
+    }
+
+    /// Unpack data from its storage layout
+    pub fn unpack(packed: &PackedReserveData) -> ReserveData {
+        ReserveData {
+            d_rate: packed.0,
+            b_rate: packed.1,
+            ir_mod: packed.2,
+            util_avg: packed.3,
+            b_supply: packed.4,
+            c_supply: packed.5,
+            d_supply: packed.6,
+            backstop_credit: packed.7,
+            interest_paid: packed.8,
+            interest_earned: packed.9,
+            ramp_c_factor: packed.10 as u32,
+            ramp_start: (packed.10 >> 64) as u64,
+            ramp_end: packed.11 as u64,
+            last_time: (packed.11 >> 64) as u64,
+        }
+    }
+
+    /// Unpack data from either the current storage layout or the original map layout. Data in the
+    /// original layout did not track the utilization average, collateral supply, lifetime interest
+    /// or a collateral factor ramp, so each starts from zero.
+    pub fn unpack_stored(e: &Env, stored: &Val) -> ReserveData {
+        if MapObject::try_from(*stored).is_ok() {
+            let legacy = LegacyReserveData::try_from_val(e, stored).unwrap_optimized();
+            return ReserveData {
+                d_rate: legacy.d_rate,
+                b_rate: legacy.b_rate,
+                ir_mod: legacy.ir_mod,
+                util_avg: 0,
+                b_supply: legacy.b_supply,
+                c_supply: 0,
+                d_supply: legacy.d_supply,
+                backstop_credit: legacy.backstop_credit,
+                interest_paid: 0,
+                interest_earned: 0,
+                ramp_c_factor: 0,
+                ramp_start: 0,
+                ramp_end: 0,
+                last_time: legacy.last_time,
+            };
+        }
+        let packed = PackedReserveData::try_from_val(e, stored).unwrap_optimized();
+        ReserveData::unpack(&packed)
+    }
 }
 
 /// The configuration of emissions for the reserve b or d token
@@ -101,16 +498,55 @@ pub struct UserEmissionData {
     pub accrued: i128,
 }
 
+/// The user interest data for the reserve b or d token
+#[derive(Clone)]
+#[contracttype]
+pub struct UserInterestData {
+    pub rate: i128,    // the b or d rate the accrued interest was last updated at
+    pub accrued: i128, // the lifetime interest accrued in underlying tokens
+}
+
+/// A record of bad debt transferred from a user to the backstop
+#[derive(Clone)]
+#[contracttype]
+pub struct BadDebtRecord {
+    pub user: Address,  // the user the bad debt originated from
+    pub asset: Address, // the underlying asset of the reserve
+    pub d_tokens: i128, // the d_tokens transferred to the backstop
+    pub amount: i128,   // the liability transferred to the backstop in underlying tokens
+    pub timestamp: u64, // the ledger timestamp of the transfer
+}
+
 /********** Storage Key Types **********/
 
 const IS_INIT_KEY: &str = "IsInit";
 const ADMIN_KEY: &str = "Admin";
-const NAME_KEY: &str = "Name";
+const METADATA_KEY: &str = "Metadata";
+const METADATA_INIT_KEY: &str = "MetadataInit";
+const UPGRADE_INIT_KEY: &str = "UpgradeInit";
+const MIGRATE_KEY: &str = "Migrate";
 const BACKSTOP_KEY: &str = "Backstop";
 const BLND_TOKEN_KEY: &str = "BLNDTkn";
+const PAUSE_REGISTRY_KEY: &str = "PauseReg";
+const AMM_KEY: &str = "Amm";
+const AMM_INIT_KEY: &str = "AmmInit";
 const POOL_CONFIG_KEY: &str = "Config";
+const STATUS_TIME_KEY: &str = "StatusTime";
+const MIN_FILL_KEY: &str = "MinFill";
+const BAD_DEBT_BID_KEY: &str = "BadDebtBid";
+const WITHDRAW_CAP_KEY: &str = "WdrawCap";
+const C_FACTOR_RAMP_KEY: &str = "CFactorRamp";
+const PRICE_BREAKER_KEY: &str = "PriceBreaker";
+const SUPPLY_BREAKER_KEY: &str = "SupplyBreaker";
+const LISTING_BOND_KEY: &str = "ListingBond";
+const UNFREEZE_TIME_KEY: &str = "UnfreezeTime";
+const LIQ_GRACE_KEY: &str = "LiqGrace";
+const AUCTION_CURVE_KEY: &str = "AuctionCurve";
 const RES_LIST_KEY: &str = "ResList";
+const RES_INIT_LIST_KEY: &str = "ResInitList";
 const POOL_EMIS_KEY: &str = "PoolEmis";
+const DIRECT_EMIS_KEY: &str = "DirectEmis";
+const BAD_DEBT_COUNT_KEY: &str = "BadDebtCnt";
 
 #[derive(Clone)]
 #[contracttype]
@@ -126,6 +562,37 @@ pub struct AuctionKey {
     auct_type: u32, // the type of auction taking place
 }
 
+#[derive(Clone)]
+#[contracttype]
+pub struct AuctionListKey {
+    auct_type: u32, // the type of auction taking place
+    index: u32,     // the position of the entry in the list
+}
+
+/// The versioned format auctions are stored in, so a new auction format can be introduced by an
+/// upgrade without breaking auctions already in progress. Auctions stored before versioning was
+/// added hold a bare `AuctionData` and are read as `V1`.
+///
+/// `V2` auctions hold the auction curve at the time they were created, so changing the pool's
+/// curve does not reprice auctions already in progress.
+#[derive(Clone)]
+#[contracttype]
+pub enum VersionedAuctionData {
+    V1(AuctionData),
+    V2(AuctionData, AuctionCurve),
+}
+
+impl VersionedAuctionData {
+    /// Convert a stored auction into the current auction format. `V1` auctions did not store a
+    /// curve, so they follow the pool's current auction curve.
+    pub fn into_latest(self, e: &Env) -> (AuctionData, AuctionCurve) {
+        match self {
+            VersionedAuctionData::V1(auction_data) => (auction_data, get_auction_curve(e)),
+            VersionedAuctionData::V2(auction_data, curve) => (auction_data, curve),
+        }
+    }
+}
+
 #[derive(Clone)]
 #[contracttype]
 pub enum PoolDataKey {
@@ -133,20 +600,76 @@ pub enum PoolDataKey {
     ResConfig(Address),
     // A map of underlying asset's contract address to queued reserve init
     ResInit(Address),
+    // A map of underlying asset's contract address to the bond posted to propose it as a reserve
+    ResBond(Address),
     // A map of underlying asset's contract address to reserve data
     ResData(Address),
+    // A map of underlying asset's contract address to withdrawal queue config
+    WdrawQueue(Address),
+    // A map of underlying asset's contract address to the current withdrawal queue window
+    WdrawWindow(Address),
+    // A map of underlying asset's contract address to the dTokens borrowed at a stable rate
+    StableSupply(Address),
+    // A map of underlying asset's contract address to the last oracle price loaded by the pool
+    LastPrice(Address),
+    // A map of an asset's contract address to the last oracle price cached by the pool
+    PriceCache(Address),
+    // A map of underlying asset's contract address to the reserve's token supplies at the start of the ledger
+    SupplySnap(Address),
+    // A map of underlying asset's contract address to the reserve's historical rate checkpoints
+    RateHist(Address),
+    // A map of underlying asset's contract address to exchange rate config
+    ExRate(Address),
+    // A map of underlying asset's contract address to queued exchange rate config
+    ExRateInit(Address),
+    // A map of underlying asset's contract address to partner pool bToken config
+    PartnerRes(Address),
+    // A map of pool address to whether it is a whitelisted partner pool
+    Partner(Address),
+    // A map of underlying asset's contract address to whether borrowing is restricted
+    Restricted(Address),
+    // A map of underlying asset's contract address to the band a stablecoin's price is held to
+    PegBand(Address),
     // The reserve's emission config
     EmisConfig(u32),
     // The reserve's emission data
     EmisData(u32),
     // Map of positions in the pool for a user
     Positions(Address),
+    // Map of reserve index to fixed-term loan for a user
+    FixedLoans(Address),
+    // Map of reserve index to stable rate debt for a user
+    StableDebt(Address),
+    // The nonce of the last supply, withdraw, borrow, or repay action a user took
+    Nonce(Address),
     // The emission information for a reserve asset for a user
     UserEmis(UserReserveKey),
+    // The interest information for a reserve asset for a user
+    UserInt(UserReserveKey),
+    // The address a user's emissions can be claimed to by anyone
+    AutoClaim(Address),
+    // The contract notified after a user's submit completes
+    SubmitHook(Address),
+    // The queued withdrawal from a reserve for a user
+    QueuedWdraw(UserReserveKey),
+    // The collateral top-up rule for a reserve for a user
+    TopUp(UserReserveKey),
+    // The minimum percent of a user's liabilities a liquidation auction can liquidate
+    MinLiq(Address),
+    // Whether a liquidation auction against a user was recently filled
+    LiqFilled(Address),
     // The auction's data
     Auction(AuctionKey),
     // A list of auctions and their associated data
     AuctData(Address),
+    // The number of entries in the list of users with an auction of the given type
+    AuctCount(u32),
+    // The user at a position in the list of users with an auction of the given type
+    AuctEntry(AuctionListKey),
+    // The position of a user in the list of users with an auction of the given type
+    AuctIndex(AuctionKey),
+    // A record in the bad debt log
+    BadDebt(u32),
 }
 
 /********** Storage **********/
@@ -222,6 +745,96 @@ pub fn set_user_positions(e: &Env, user: &Address, positions: &Positions) {
         .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
 }
 
+/// Fetch the user's fixed-term loans, keyed by reserve index
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_fixed_loans(e: &Env, user: &Address) -> Map<u32, FixedLoan> {
+    let key = PoolDataKey::FixedLoans(user.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || Map::new(e),
+        LEDGER_THRESHOLD_USER,
+        LEDGER_BUMP_USER,
+    )
+}
+
+/// Set the user's fixed-term loans, removing the entry if they have none
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `loans` - The user's fixed-term loans, keyed by reserve index
+pub fn set_fixed_loans(e: &Env, user: &Address, loans: &Map<u32, FixedLoan>) {
+    let key = PoolDataKey::FixedLoans(user.clone());
+    if loans.is_empty() {
+        e.storage().persistent().remove(&key);
+        return;
+    }
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Map<u32, FixedLoan>>(&key, loans);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Fetch the user's stable rate debts, keyed by reserve index
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_stable_debts(e: &Env, user: &Address) -> Map<u32, StableDebt> {
+    let key = PoolDataKey::StableDebt(user.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || Map::new(e),
+        LEDGER_THRESHOLD_USER,
+        LEDGER_BUMP_USER,
+    )
+}
+
+/// Set the user's stable rate debts, removing the entry if they have none
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `debts` - The user's stable rate debts, keyed by reserve index
+pub fn set_stable_debts(e: &Env, user: &Address, debts: &Map<u32, StableDebt>) {
+    let key = PoolDataKey::StableDebt(user.clone());
+    if debts.is_empty() {
+        e.storage().persistent().remove(&key);
+        return;
+    }
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Map<u32, StableDebt>>(&key, debts);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_action_nonce(e: &Env, user: &Address) -> u64 {
+    let key = PoolDataKey::Nonce(user.clone());
+    get_persistent_default(e, &key, || 0, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
+}
+
+/// Set the nonce of the last supply, withdraw, borrow, or repay action a user took
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `nonce` - The nonce of the action
+pub fn set_action_nonce(e: &Env, user: &Address, nonce: u64) {
+    let key = PoolDataKey::Nonce(user.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, u64>(&key, &nonce);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
 /********** Admin **********/
 
 // Fetch the current admin Address
@@ -247,14 +860,112 @@ pub fn set_admin(e: &Env, new_admin: &Address) {
 
 /********** Metadata **********/
 
-/// Set a pool name
+/// Fetch the pool's metadata
+///
+/// ### Panics
+/// If the metadata does not exist
+pub fn get_metadata(e: &Env) -> PoolMetadata {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, METADATA_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the pool's metadata
+///
+/// ### Arguments
+/// * `metadata` - The metadata of the pool
+pub fn set_metadata(e: &Env, metadata: &PoolMetadata) {
+    e.storage()
+        .instance()
+        .set::<Symbol, PoolMetadata>(&Symbol::new(e, METADATA_KEY), metadata);
+}
+
+/// Fetch the queued metadata update
+///
+/// ### Panics
+/// If no metadata update has been queued
+pub fn get_queued_metadata(e: &Env) -> QueuedPoolMetadata {
+    e.storage()
+        .temporary()
+        .get::<Symbol, QueuedPoolMetadata>(&Symbol::new(e, METADATA_INIT_KEY))
+        .unwrap_optimized()
+}
+
+/// Check if a metadata update is actively queued
+pub fn has_queued_metadata(e: &Env) -> bool {
+    e.storage()
+        .temporary()
+        .has(&Symbol::new(e, METADATA_INIT_KEY))
+}
+
+/// Set a new queued metadata update
+///
+/// ### Arguments
+/// * `queued_metadata` - The queued metadata update
+pub fn set_queued_metadata(e: &Env, queued_metadata: &QueuedPoolMetadata) {
+    let key = Symbol::new(e, METADATA_INIT_KEY);
+    e.storage()
+        .temporary()
+        .set::<Symbol, QueuedPoolMetadata>(&key, queued_metadata);
+    e.storage()
+        .temporary()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Delete the queued metadata update
+pub fn del_queued_metadata(e: &Env) {
+    e.storage()
+        .temporary()
+        .remove(&Symbol::new(e, METADATA_INIT_KEY));
+}
+
+/********** Upgrade **********/
+
+/// Fetch the queued upgrade of the pool's WASM, if one exists
+pub fn get_queued_upgrade(e: &Env) -> Option<QueuedUpgrade> {
+    e.storage()
+        .temporary()
+        .get::<Symbol, QueuedUpgrade>(&Symbol::new(e, UPGRADE_INIT_KEY))
+}
+
+/// Set a new queued upgrade of the pool's WASM
+///
+/// ### Arguments
+/// * `queued_upgrade` - The queued upgrade
+pub fn set_queued_upgrade(e: &Env, queued_upgrade: &QueuedUpgrade) {
+    let key = Symbol::new(e, UPGRADE_INIT_KEY);
+    e.storage()
+        .temporary()
+        .set::<Symbol, QueuedUpgrade>(&key, queued_upgrade);
+    e.storage()
+        .temporary()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Delete the queued upgrade of the pool's WASM
+pub fn del_queued_upgrade(e: &Env) {
+    e.storage()
+        .temporary()
+        .remove(&Symbol::new(e, UPGRADE_INIT_KEY));
+}
+
+/// Check if the pool has been upgraded and its stored state has not been migrated
+pub fn get_migration_pending(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, MIGRATE_KEY))
+        .unwrap_or(false)
+}
+
+/// Set whether the pool's stored state needs to be migrated
 ///
 /// ### Arguments
-/// * `name` - The Name of the pool
-pub fn set_name(e: &Env, name: &String) {
+/// * `pending` - If a migration is pending
+pub fn set_migration_pending(e: &Env, pending: bool) {
     e.storage()
         .instance()
-        .set::<Symbol, String>(&Symbol::new(e, NAME_KEY), name);
+        .set::<Symbol, bool>(&Symbol::new(e, MIGRATE_KEY), &pending);
 }
 
 /********** Backstop **********/
@@ -300,122 +1011,1030 @@ pub fn set_blnd_token(e: &Env, blnd_token_id: &Address) {
         .set::<Symbol, Address>(&Symbol::new(e, BLND_TOKEN_KEY), blnd_token_id);
 }
 
-/********** Pool Config **********/
+/********** Pause Registry **********/
 
-/// Fetch the pool configuration
-///
-/// ### Panics
-/// If the pool's config is not set
-pub fn get_pool_config(e: &Env) -> PoolConfig {
+/// Fetch the pause registry ID
+pub fn get_pause_registry(e: &Env) -> Address {
     e.storage()
         .instance()
-        .get(&Symbol::new(e, POOL_CONFIG_KEY))
+        .get(&Symbol::new(e, PAUSE_REGISTRY_KEY))
         .unwrap_optimized()
 }
 
-/// Set the pool configuration
+/// Set a new pause registry ID
 ///
 /// ### Arguments
-/// * `config` - The contract address of the oracle
-pub fn set_pool_config(e: &Env, config: &PoolConfig) {
+/// * `pause_registry_id` - The ID of the pause registry
+pub fn set_pause_registry(e: &Env, pause_registry_id: &Address) {
     e.storage()
         .instance()
-        .set::<Symbol, PoolConfig>(&Symbol::new(e, POOL_CONFIG_KEY), config);
+        .set::<Symbol, Address>(&Symbol::new(e, PAUSE_REGISTRY_KEY), pause_registry_id);
 }
 
-/********** Reserve Config (ResConfig) **********/
+/********** AMM **********/
 
-/// Fetch the reserve data for an asset
+/// Fetch the AMM used to sell collateral when closing positions, if one is set
+pub fn get_amm(e: &Env) -> Option<Address> {
+    e.storage().instance().get(&Symbol::new(e, AMM_KEY))
+}
+
+/// Set the AMM used to sell collateral when closing positions
 ///
 /// ### Arguments
-/// * `asset` - The contract address of the asset
+/// * `amm` - The contract address of the AMM
+pub fn set_amm(e: &Env, amm: &Address) {
+    e.storage()
+        .instance()
+        .set::<Symbol, Address>(&Symbol::new(e, AMM_KEY), amm);
+}
+
+/// Fetch the queued AMM update
 ///
 /// ### Panics
-/// If the reserve does not exist
-pub fn get_res_config(e: &Env, asset: &Address) -> ReserveConfig {
+/// If no AMM update has been queued
+pub fn get_queued_amm(e: &Env) -> QueuedAmm {
+    e.storage()
+        .temporary()
+        .get::<Symbol, QueuedAmm>(&Symbol::new(e, AMM_INIT_KEY))
+        .unwrap_optimized()
+}
+
+/// Check if an AMM update is actively queued
+pub fn has_queued_amm(e: &Env) -> bool {
+    e.storage().temporary().has(&Symbol::new(e, AMM_INIT_KEY))
+}
+
+/// Set a new queued AMM update
+///
+/// ### Arguments
+/// * `queued_amm` - The queued AMM update
+pub fn set_queued_amm(e: &Env, queued_amm: &QueuedAmm) {
+    let key = Symbol::new(e, AMM_INIT_KEY);
+    e.storage()
+        .temporary()
+        .set::<Symbol, QueuedAmm>(&key, queued_amm);
+    e.storage()
+        .temporary()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Delete the queued AMM update
+pub fn del_queued_amm(e: &Env) {
+    e.storage()
+        .temporary()
+        .remove(&Symbol::new(e, AMM_INIT_KEY));
+}
+
+/********** Pool Config **********/
+
+/// Fetch the pool configuration
+///
+/// ### Panics
+/// If the pool's config is not set
+pub fn get_pool_config(e: &Env) -> PoolConfig {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, POOL_CONFIG_KEY))
+        .unwrap_optimized()
+}
+
+/// Set the pool configuration
+///
+/// ### Arguments
+/// * `config` - The contract address of the oracle
+pub fn set_pool_config(e: &Env, config: &PoolConfig) {
+    e.storage()
+        .instance()
+        .set::<Symbol, PoolConfig>(&Symbol::new(e, POOL_CONFIG_KEY), config);
+}
+
+/// Fetch the time the pool status last changed, if it has been recorded
+pub fn get_status_time(e: &Env) -> Option<u64> {
+    e.storage().instance().get(&Symbol::new(e, STATUS_TIME_KEY))
+}
+
+/// Set the time the pool status last changed
+///
+/// ### Arguments
+/// * `time` - The ledger timestamp of the status change
+pub fn set_status_time(e: &Env, time: u64) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u64>(&Symbol::new(e, STATUS_TIME_KEY), &time);
+}
+
+/// Fetch the time the pool last returned to an active status from on-ice or frozen, if it has
+/// been recorded
+pub fn get_unfreeze_time(e: &Env) -> Option<u64> {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, UNFREEZE_TIME_KEY))
+}
+
+/// Set the time the pool last returned to an active status from on-ice or frozen
+///
+/// ### Arguments
+/// * `time` - The ledger timestamp of the status change
+pub fn set_unfreeze_time(e: &Env, time: u64) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u64>(&Symbol::new(e, UNFREEZE_TIME_KEY), &time);
+}
+
+/// Fetch the time in seconds liquidations are blocked after the pool returns to an active
+/// status, or 0 if not set
+pub fn get_liquidation_grace(e: &Env) -> u64 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, LIQ_GRACE_KEY))
+        .unwrap_or(0)
+}
+
+/// Set the time in seconds liquidations are blocked after the pool returns to an active status
+///
+/// ### Arguments
+/// * `grace` - The grace period in seconds, or 0 to disable
+pub fn set_liquidation_grace(e: &Env, grace: &u64) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u64>(&Symbol::new(e, LIQ_GRACE_KEY), grace);
+}
+
+/// Fetch the minimum percent of an auction that must be filled at once, or 0 if not set
+pub fn get_min_fill_pct(e: &Env) -> u64 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, MIN_FILL_KEY))
+        .unwrap_or(0)
+}
+
+/// Set the minimum percent of an auction that must be filled at once
+///
+/// ### Arguments
+/// * `min_fill_pct` - The minimum fill percent (5 => 5%)
+pub fn set_min_fill_pct(e: &Env, min_fill_pct: &u64) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u64>(&Symbol::new(e, MIN_FILL_KEY), min_fill_pct);
+}
+
+/// Fetch the maximum number of liabilities included in the bid of a bad debt auction, or 0 if
+/// every liability is included
+pub fn get_bad_debt_max_assets(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, BAD_DEBT_BID_KEY))
+        .unwrap_or(0)
+}
+
+/// Set the maximum number of liabilities included in the bid of a bad debt auction
+///
+/// ### Arguments
+/// * `max_assets` - The maximum number of bid assets, or 0 to include every liability
+pub fn set_bad_debt_max_assets(e: &Env, max_assets: &u32) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u32>(&Symbol::new(e, BAD_DEBT_BID_KEY), max_assets);
+}
+
+/// Fetch the maximum utilization a reserve can be left at by a withdrawal, or 100% if not set
+pub fn get_withdraw_util_cap(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, WITHDRAW_CAP_KEY))
+        .unwrap_or(1_0000000)
+}
+
+/// Set the maximum utilization a reserve can be left at by a withdrawal
+///
+/// ### Arguments
+/// * `util_cap` - The utilization cap scaled expressed in 7 decimals
+pub fn set_withdraw_util_cap(e: &Env, util_cap: &u32) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u32>(&Symbol::new(e, WITHDRAW_CAP_KEY), util_cap);
+}
+
+/// Fetch the time in seconds a reduction to a reserve's collateral factor is ramped in over,
+/// or one week if not set
+pub fn get_c_factor_ramp(e: &Env) -> u64 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, C_FACTOR_RAMP_KEY))
+        .unwrap_or(SECONDS_PER_WEEK)
+}
+
+/// Set the time in seconds a reduction to a reserve's collateral factor is ramped in over
+///
+/// ### Arguments
+/// * `ramp_period` - The ramp period in seconds
+pub fn set_c_factor_ramp(e: &Env, ramp_period: &u64) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u64>(&Symbol::new(e, C_FACTOR_RAMP_KEY), ramp_period);
+}
+
+/// Fetch the percent an asset's oracle price can move between pool interactions before its
+/// reserve is restricted, or 0 if the price breaker is disabled
+pub fn get_price_breaker(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, PRICE_BREAKER_KEY))
+        .unwrap_or(0)
+}
+
+/// Set the percent an asset's oracle price can move between pool interactions before its
+/// reserve is restricted
+///
+/// ### Arguments
+/// * `threshold` - The price move threshold expressed in 7 decimals, or 0 to disable
+pub fn set_price_breaker(e: &Env, threshold: &u32) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u32>(&Symbol::new(e, PRICE_BREAKER_KEY), threshold);
+}
+
+/// Fetch the percent a reserve's bToken or dToken supply can change within a ledger before
+/// the change is rejected, or 0 if the supply breaker is disabled
+pub fn get_supply_breaker(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, SUPPLY_BREAKER_KEY))
+        .unwrap_or(0)
+}
+
+/// Set the percent a reserve's bToken or dToken supply can change within a ledger before the
+/// change is rejected
+///
+/// ### Arguments
+/// * `threshold` - The supply change threshold expressed in 7 decimals, or 0 to disable
+pub fn set_supply_breaker(e: &Env, threshold: &u32) {
+    e.storage()
+        .instance()
+        .set::<Symbol, u32>(&Symbol::new(e, SUPPLY_BREAKER_KEY), threshold);
+}
+
+/// Fetch the amount of backstop tokens that must be bonded to propose a new reserve, or 0 if
+/// reserves cannot be proposed
+pub fn get_listing_bond(e: &Env) -> i128 {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, LISTING_BOND_KEY))
+        .unwrap_or(0)
+}
+
+/// Set the amount of backstop tokens that must be bonded to propose a new reserve
+///
+/// ### Arguments
+/// * `amount` - The bond amount, or 0 to disable reserve proposals
+pub fn set_listing_bond(e: &Env, amount: &i128) {
+    e.storage()
+        .instance()
+        .set::<Symbol, i128>(&Symbol::new(e, LISTING_BOND_KEY), amount);
+}
+
+/// Fetch the Dutch auction curve used to scale auctions, or a 200 block lot ramp and bid decay
+/// if not set
+pub fn get_auction_curve(e: &Env) -> AuctionCurve {
+    e.storage()
+        .instance()
+        .get(&Symbol::new(e, AUCTION_CURVE_KEY))
+        .unwrap_or(AuctionCurve {
+            lot_blocks: DEFAULT_AUCTION_CURVE_BLOCKS,
+            bid_blocks: DEFAULT_AUCTION_CURVE_BLOCKS,
+        })
+}
+
+/// Set the Dutch auction curve used to scale auctions
+///
+/// ### Arguments
+/// * `curve` - The auction curve
+pub fn set_auction_curve(e: &Env, curve: &AuctionCurve) {
+    e.storage()
+        .instance()
+        .set::<Symbol, AuctionCurve>(&Symbol::new(e, AUCTION_CURVE_KEY), curve);
+}
+
+/********** Exchange Rates **********/
+
+/// Fetch the exchange rate config for a reserve, if one is set
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_exchange_rate_config(e: &Env, asset: &Address) -> Option<ExchangeRateConfig> {
+    let key = PoolDataKey::ExRate(asset.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the exchange rate config for a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `config` - The exchange rate config for the reserve
+pub fn set_exchange_rate_config(e: &Env, asset: &Address, config: &ExchangeRateConfig) {
+    let key = PoolDataKey::ExRate(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, ExchangeRateConfig>(&key, config);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Remove the exchange rate config for a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn del_exchange_rate_config(e: &Env, asset: &Address) {
+    let key = PoolDataKey::ExRate(asset.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/********** Partner Pools **********/
+
+/// Check if a pool is a whitelisted partner pool
+///
+/// ### Arguments
+/// * `pool` - The address of the pool
+pub fn is_partner_pool(e: &Env, pool: &Address) -> bool {
+    let key = PoolDataKey::Partner(pool.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || false,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Whitelist a partner pool
+///
+/// ### Arguments
+/// * `pool` - The address of the pool
+pub fn set_partner_pool(e: &Env, pool: &Address) {
+    let key = PoolDataKey::Partner(pool.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, bool>(&key, &true);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Remove a partner pool from the whitelist
+///
+/// ### Arguments
+/// * `pool` - The address of the pool
+pub fn del_partner_pool(e: &Env, pool: &Address) {
+    let key = PoolDataKey::Partner(pool.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/// Fetch the partner pool bToken config for a reserve, if one is set
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_partner_collateral(e: &Env, asset: &Address) -> Option<PartnerCollateral> {
+    let key = PoolDataKey::PartnerRes(asset.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the partner pool bToken config for a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `config` - The partner pool bToken config for the reserve
+pub fn set_partner_collateral(e: &Env, asset: &Address, config: &PartnerCollateral) {
+    let key = PoolDataKey::PartnerRes(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, PartnerCollateral>(&key, config);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Remove the partner pool bToken config for a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn del_partner_collateral(e: &Env, asset: &Address) {
+    let key = PoolDataKey::PartnerRes(asset.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/// Fetch a queued exchange rate config update
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+///
+/// ### Panics
+/// If no update has been queued
+pub fn get_queued_exchange_rate(e: &Env, asset: &Address) -> QueuedExchangeRate {
+    let key = PoolDataKey::ExRateInit(asset.clone());
+    e.storage()
+        .temporary()
+        .get::<PoolDataKey, QueuedExchangeRate>(&key)
+        .unwrap_optimized()
+}
+
+/// Check if an exchange rate config update is actively queued
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn has_queued_exchange_rate(e: &Env, asset: &Address) -> bool {
+    let key = PoolDataKey::ExRateInit(asset.clone());
+    e.storage().temporary().has(&key)
+}
+
+/// Set a queued exchange rate config update
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `queued` - The queued update
+pub fn set_queued_exchange_rate(e: &Env, asset: &Address, queued: &QueuedExchangeRate) {
+    let key = PoolDataKey::ExRateInit(asset.clone());
+    e.storage()
+        .temporary()
+        .set::<PoolDataKey, QueuedExchangeRate>(&key, queued);
+    e.storage()
+        .temporary()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Delete a queued exchange rate config update
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn del_queued_exchange_rate(e: &Env, asset: &Address) {
+    let key = PoolDataKey::ExRateInit(asset.clone());
+    e.storage().temporary().remove(&key);
+}
+
+/********** Price Breaker **********/
+
+/// Fetch the last oracle price loaded by the pool for an asset, if one exists
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_last_price(e: &Env, asset: &Address) -> Option<i128> {
+    let key = PoolDataKey::LastPrice(asset.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the last oracle price loaded by the pool for an asset
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `price` - The oracle price
+pub fn set_last_price(e: &Env, asset: &Address, price: &i128) {
+    let key = PoolDataKey::LastPrice(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, i128>(&key, price);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Remove the last oracle price loaded by the pool for an asset
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn del_last_price(e: &Env, asset: &Address) {
+    let key = PoolDataKey::LastPrice(asset.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/********** Price Cache **********/
+
+/// Fetch the last oracle price cached by the pool for an asset, if one exists
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_cached_price(e: &Env, asset: &Address) -> Option<CachedPrice> {
+    let key = PoolDataKey::PriceCache(asset.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the last oracle price cached by the pool for an asset
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `cached_price` - The cached price
+pub fn set_cached_price(e: &Env, asset: &Address, cached_price: &CachedPrice) {
+    let key = PoolDataKey::PriceCache(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, CachedPrice>(&key, cached_price);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Fetch the supply snapshot of a reserve, if one exists
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_supply_snapshot(e: &Env, asset: &Address) -> Option<SupplySnapshot> {
+    let key = PoolDataKey::SupplySnap(asset.clone());
+    e.storage()
+        .temporary()
+        .get::<PoolDataKey, SupplySnapshot>(&key)
+}
+
+/// Set the supply snapshot of a reserve. The snapshot is only relevant for the ledger it was
+/// taken in, so it is kept in temporary storage.
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `snapshot` - The supply snapshot
+pub fn set_supply_snapshot(e: &Env, asset: &Address, snapshot: &SupplySnapshot) {
+    let key = PoolDataKey::SupplySnap(asset.clone());
+    e.storage()
+        .temporary()
+        .set::<PoolDataKey, SupplySnapshot>(&key, snapshot);
+}
+
+/// Fetch the historical rate checkpoints of a reserve, ordered from oldest to newest
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_rate_history(e: &Env, asset: &Address) -> Vec<RateCheckpoint> {
+    let key = PoolDataKey::RateHist(asset.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || vec![e],
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the historical rate checkpoints of a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `history` - The rate checkpoints, ordered from oldest to newest
+pub fn set_rate_history(e: &Env, asset: &Address, history: &Vec<RateCheckpoint>) {
+    let key = PoolDataKey::RateHist(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Vec<RateCheckpoint>>(&key, history);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Check if borrowing from a reserve is restricted
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_reserve_restricted(e: &Env, asset: &Address) -> bool {
+    let key = PoolDataKey::Restricted(asset.clone());
+    e.storage().persistent().has(&key)
+}
+
+/// Set if borrowing from a reserve is restricted
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `restricted` - True if borrowing is restricted
+pub fn set_reserve_restricted(e: &Env, asset: &Address, restricted: bool) {
+    let key = PoolDataKey::Restricted(asset.clone());
+    if restricted {
+        e.storage()
+            .persistent()
+            .set::<PoolDataKey, bool>(&key, &true);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+/********** Reserve Config (ResConfig) **********/
+
+/// Fetch the reserve data for an asset
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+///
+/// ### Panics
+/// If the reserve does not exist
+pub fn get_res_config(e: &Env, asset: &Address) -> ReserveConfig {
+    let key = PoolDataKey::ResConfig(asset.clone());
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+    let stored = e
+        .storage()
+        .persistent()
+        .get::<PoolDataKey, Val>(&key)
+        .unwrap_optimized();
+    ReserveConfig::unpack_stored(e, &stored)
+}
+
+/// Set the reserve configuration for an asset
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `config` - The reserve configuration for the asset
+pub fn set_res_config(e: &Env, asset: &Address, config: &ReserveConfig) {
+    let key = PoolDataKey::ResConfig(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, PackedReserveConfig>(&key, &config.pack(e));
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Checks if a reserve exists for an asset
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn has_res(e: &Env, asset: &Address) -> bool {
     let key = PoolDataKey::ResConfig(asset.clone());
+    e.storage().persistent().has(&key)
+}
+
+/// Fetch a queued reserve set
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+///
+/// ### Panics
+/// If the reserve set has not been queued
+pub fn get_queued_reserve_set(e: &Env, asset: &Address) -> QueuedReserveInit {
+    let key = PoolDataKey::ResInit(asset.clone());
+    e.storage()
+        .temporary()
+        .get::<PoolDataKey, QueuedReserveInit>(&key)
+        .unwrap_optimized()
+}
+
+/// Check if a reserve is actively queued
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn has_queued_reserve_set(e: &Env, asset: &Address) -> bool {
+    let key = PoolDataKey::ResInit(asset.clone());
+    e.storage().temporary().has(&key)
+}
+
+/// Set a new queued reserve set
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `config` - The reserve configuration for the asset
+pub fn set_queued_reserve_set(e: &Env, res_init: &QueuedReserveInit, asset: &Address) {
+    let key = PoolDataKey::ResInit(asset.clone());
+    e.storage()
+        .temporary()
+        .set::<PoolDataKey, QueuedReserveInit>(&key, res_init);
+    e.storage()
+        .temporary()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Delete a queued reserve set
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+///
+/// ### Panics
+/// If the reserve set has not been queued
+pub fn del_queued_reserve_set(e: &Env, asset: &Address) {
+    let key = PoolDataKey::ResInit(asset.clone());
+    e.storage().temporary().remove(&key);
+}
+
+/// Fetch the bond posted to propose a reserve, if one exists
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_reserve_bond(e: &Env, asset: &Address) -> Option<ReserveBond> {
+    let key = PoolDataKey::ResBond(asset.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the bond posted to propose a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `bond` - The bond
+pub fn set_reserve_bond(e: &Env, asset: &Address, bond: &ReserveBond) {
+    let key = PoolDataKey::ResBond(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, ReserveBond>(&key, bond);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Remove the bond posted to propose a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn del_reserve_bond(e: &Env, asset: &Address) {
+    let key = PoolDataKey::ResBond(asset.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/// Fetch the list of assets with a queued reserve set. Queued sets are held in temporary storage,
+/// so an asset in the list may no longer have a queued set if it expired.
+pub fn get_queued_reserve_list(e: &Env) -> Vec<Address> {
+    get_persistent_default(
+        e,
+        &Symbol::new(e, RES_INIT_LIST_KEY),
+        || vec![e],
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the list of assets with a queued reserve set
+///
+/// ### Arguments
+/// * `assets` - The contract addresses of the assets
+pub fn set_queued_reserve_list(e: &Env, assets: &Vec<Address>) {
+    let key = Symbol::new(e, RES_INIT_LIST_KEY);
+    e.storage()
+        .persistent()
+        .set::<Symbol, Vec<Address>>(&key, assets);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/********** Stable Rate Debt **********/
+
+/// Fetch the amount of a reserve's dTokens borrowed at a stable rate
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_stable_supply(e: &Env, asset: &Address) -> i128 {
+    let key = PoolDataKey::StableSupply(asset.clone());
+    get_persistent_default(e, &key, || 0, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED)
+}
+
+/// Set the amount of a reserve's dTokens borrowed at a stable rate
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `d_tokens` - The amount of dTokens borrowed at a stable rate
+pub fn set_stable_supply(e: &Env, asset: &Address, d_tokens: i128) {
+    let key = PoolDataKey::StableSupply(asset.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, i128>(&key, &d_tokens);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/********** Withdraw Queue **********/
+
+/// Fetch the withdrawal queue config for a reserve, if one is set
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_withdraw_queue_config(e: &Env, asset: &Address) -> Option<WithdrawQueueConfig> {
+    let key = PoolDataKey::WdrawQueue(asset.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the withdrawal queue config for a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `config` - The withdrawal queue config for the reserve
+pub fn set_withdraw_queue_config(e: &Env, asset: &Address, config: &WithdrawQueueConfig) {
+    let key = PoolDataKey::WdrawQueue(asset.clone());
     e.storage()
         .persistent()
-        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+        .set::<PoolDataKey, WithdrawQueueConfig>(&key, config);
     e.storage()
         .persistent()
-        .get::<PoolDataKey, ReserveConfig>(&key)
-        .unwrap_optimized()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
 }
 
-/// Set the reserve configuration for an asset
+/// Remove the withdrawal queue config for a reserve
 ///
 /// ### Arguments
 /// * `asset` - The contract address of the asset
-/// * `config` - The reserve configuration for the asset
-pub fn set_res_config(e: &Env, asset: &Address, config: &ReserveConfig) {
-    let key = PoolDataKey::ResConfig(asset.clone());
+pub fn del_withdraw_queue_config(e: &Env, asset: &Address) {
+    let key = PoolDataKey::WdrawQueue(asset.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/// Fetch the current withdrawal queue window for a reserve, if one has started
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+pub fn get_withdraw_window(e: &Env, asset: &Address) -> Option<WithdrawWindow> {
+    let key = PoolDataKey::WdrawWindow(asset.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the current withdrawal queue window for a reserve
+///
+/// ### Arguments
+/// * `asset` - The contract address of the asset
+/// * `window` - The withdrawal queue window for the reserve
+pub fn set_withdraw_window(e: &Env, asset: &Address, window: &WithdrawWindow) {
+    let key = PoolDataKey::WdrawWindow(asset.clone());
     e.storage()
         .persistent()
-        .set::<PoolDataKey, ReserveConfig>(&key, config);
+        .set::<PoolDataKey, WithdrawWindow>(&key, window);
     e.storage()
         .persistent()
         .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
 }
 
-/// Checks if a reserve exists for an asset
+/// Fetch the peg band for a stablecoin reserve, if one is set
 ///
 /// ### Arguments
 /// * `asset` - The contract address of the asset
-pub fn has_res(e: &Env, asset: &Address) -> bool {
-    let key = PoolDataKey::ResConfig(asset.clone());
-    e.storage().persistent().has(&key)
+pub fn get_peg_band(e: &Env, asset: &Address) -> Option<u32> {
+    let key = PoolDataKey::PegBand(asset.clone());
+    get_persistent_default(
+        e,
+        &key,
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
 }
 
-/// Fetch a queued reserve set
+/// Set the peg band for a stablecoin reserve
 ///
 /// ### Arguments
 /// * `asset` - The contract address of the asset
-///
-/// ### Panics
-/// If the reserve set has not been queued
-pub fn get_queued_reserve_set(e: &Env, asset: &Address) -> QueuedReserveInit {
-    let key = PoolDataKey::ResInit(asset.clone());
+/// * `band` - The peg band expressed in 7 decimals
+pub fn set_peg_band(e: &Env, asset: &Address, band: u32) {
+    let key = PoolDataKey::PegBand(asset.clone());
     e.storage()
-        .temporary()
-        .get::<PoolDataKey, QueuedReserveInit>(&key)
-        .unwrap_optimized()
+        .persistent()
+        .set::<PoolDataKey, u32>(&key, &band);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
 }
 
-/// Check if a reserve is actively queued
+/// Remove the peg band for a stablecoin reserve
 ///
 /// ### Arguments
 /// * `asset` - The contract address of the asset
-pub fn has_queued_reserve_set(e: &Env, asset: &Address) -> bool {
-    let key = PoolDataKey::ResInit(asset.clone());
-    e.storage().temporary().has(&key)
+pub fn del_peg_band(e: &Env, asset: &Address) {
+    let key = PoolDataKey::PegBand(asset.clone());
+    e.storage().persistent().remove(&key);
 }
 
-/// Set a new queued reserve set
+/// Fetch a user's queued withdrawal from a reserve, if one exists
 ///
 /// ### Arguments
-/// * `asset` - The contract address of the asset
-/// * `config` - The reserve configuration for the asset
-pub fn set_queued_reserve_set(e: &Env, res_init: &QueuedReserveInit, asset: &Address) {
-    let key = PoolDataKey::ResInit(asset.clone());
+/// * `user` - The address of the user
+/// * `reserve_index` - The index of the reserve
+pub fn get_queued_withdrawal(
+    e: &Env,
+    user: &Address,
+    reserve_index: u32,
+) -> Option<QueuedWithdrawal> {
+    let key = PoolDataKey::QueuedWdraw(UserReserveKey {
+        user: user.clone(),
+        reserve_id: reserve_index,
+    });
+    get_persistent_default(e, &key, || None, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
+}
+
+/// Set a user's queued withdrawal from a reserve
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `reserve_index` - The index of the reserve
+/// * `withdrawal` - The queued withdrawal
+pub fn set_queued_withdrawal(
+    e: &Env,
+    user: &Address,
+    reserve_index: u32,
+    withdrawal: &QueuedWithdrawal,
+) {
+    let key = PoolDataKey::QueuedWdraw(UserReserveKey {
+        user: user.clone(),
+        reserve_id: reserve_index,
+    });
     e.storage()
-        .temporary()
-        .set::<PoolDataKey, QueuedReserveInit>(&key, res_init);
+        .persistent()
+        .set::<PoolDataKey, QueuedWithdrawal>(&key, withdrawal);
     e.storage()
-        .temporary()
+        .persistent()
         .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
 }
 
-/// Delete a queued reserve set
+/// Remove a user's queued withdrawal from a reserve
 ///
 /// ### Arguments
-/// * `asset` - The contract address of the asset
+/// * `user` - The address of the user
+/// * `reserve_index` - The index of the reserve
+pub fn del_queued_withdrawal(e: &Env, user: &Address, reserve_index: u32) {
+    let key = PoolDataKey::QueuedWdraw(UserReserveKey {
+        user: user.clone(),
+        reserve_id: reserve_index,
+    });
+    e.storage().persistent().remove(&key);
+}
+
+/********** Collateral Top-Ups **********/
+
+/// Fetch a user's collateral top-up rule for a reserve, if one is registered
 ///
-/// ### Panics
-/// If the reserve set has not been queued
-pub fn del_queued_reserve_set(e: &Env, asset: &Address) {
-    let key = PoolDataKey::ResInit(asset.clone());
-    e.storage().temporary().remove(&key);
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `reserve_index` - The index of the reserve
+pub fn get_top_up_rule(e: &Env, user: &Address, reserve_index: u32) -> Option<TopUpRule> {
+    let key = PoolDataKey::TopUp(UserReserveKey {
+        user: user.clone(),
+        reserve_id: reserve_index,
+    });
+    get_persistent_default(e, &key, || None, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
+}
+
+/// Set a user's collateral top-up rule for a reserve
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `reserve_index` - The index of the reserve
+/// * `rule` - The top-up rule
+pub fn set_top_up_rule(e: &Env, user: &Address, reserve_index: u32, rule: &TopUpRule) {
+    let key = PoolDataKey::TopUp(UserReserveKey {
+        user: user.clone(),
+        reserve_id: reserve_index,
+    });
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, TopUpRule>(&key, rule);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Remove a user's collateral top-up rule for a reserve
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `reserve_index` - The index of the reserve
+pub fn del_top_up_rule(e: &Env, user: &Address, reserve_index: u32) {
+    let key = PoolDataKey::TopUp(UserReserveKey {
+        user: user.clone(),
+        reserve_id: reserve_index,
+    });
+    e.storage().persistent().remove(&key);
 }
 
 /********** Reserve Data (ResData) **********/
@@ -432,10 +2051,12 @@ pub fn get_res_data(e: &Env, asset: &Address) -> ReserveData {
     e.storage()
         .persistent()
         .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
-    e.storage()
+    let stored = e
+        .storage()
         .persistent()
-        .get::<PoolDataKey, ReserveData>(&key)
-        .unwrap_optimized()
+        .get::<PoolDataKey, Val>(&key)
+        .unwrap_optimized();
+    ReserveData::unpack_stored(e, &stored)
 }
 
 /// Set the reserve data for an asset
@@ -447,7 +2068,7 @@ pub fn set_res_data(e: &Env, asset: &Address, data: &ReserveData) {
     let key = PoolDataKey::ResData(asset.clone());
     e.storage()
         .persistent()
-        .set::<PoolDataKey, ReserveData>(&key, data);
+        .set::<PoolDataKey, PackedReserveData>(&key, &data.pack());
     e.storage()
         .persistent()
         .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
@@ -493,105 +2114,281 @@ pub fn push_res_list(e: &Env, asset: &Address) -> u32 {
     new_index
 }
 
-/********** Reserve Emissions **********/
+/********** Reserve Emissions **********/
+
+/// Fetch the emission config for the reserve b or d token
+///
+/// ### Arguments
+/// * `res_token_index` - The d/bToken index for the reserve
+pub fn get_res_emis_config(e: &Env, res_token_index: &u32) -> Option<ReserveEmissionsConfig> {
+    let key = PoolDataKey::EmisConfig(*res_token_index);
+    get_persistent_default(
+        e,
+        &key,
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the emission config for the reserve b or d token
+///
+/// ### Arguments
+/// * `res_token_index` - The d/bToken index for the reserve
+/// * `res_emis_config` - The new emission config for the reserve token
+pub fn set_res_emis_config(
+    e: &Env,
+    res_token_index: &u32,
+    res_emis_config: &ReserveEmissionsConfig,
+) {
+    let key = PoolDataKey::EmisConfig(*res_token_index);
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, ReserveEmissionsConfig>(&key, res_emis_config);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Fetch the emission data for the reserve b or d token
+///
+/// ### Arguments
+/// * `res_token_index` - The d/bToken index for the reserve
+pub fn get_res_emis_data(e: &Env, res_token_index: &u32) -> Option<ReserveEmissionsData> {
+    let key = PoolDataKey::EmisData(*res_token_index);
+    get_persistent_default(
+        e,
+        &key,
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the emission data for the reserve b or d token
+///
+/// ### Arguments
+/// * `res_token_index` - The d/bToken index for the reserve
+/// * `res_emis_data` - The new emission data for the reserve token
+pub fn set_res_emis_data(e: &Env, res_token_index: &u32, res_emis_data: &ReserveEmissionsData) {
+    let key = PoolDataKey::EmisData(*res_token_index);
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, ReserveEmissionsData>(&key, res_emis_data);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/********** User Emissions **********/
+
+/// Fetch the users emission data for a reserve's b or d token
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `res_token_index` - The d/bToken index for the reserve
+pub fn get_user_emissions(
+    e: &Env,
+    user: &Address,
+    res_token_index: &u32,
+) -> Option<UserEmissionData> {
+    let key = PoolDataKey::UserEmis(UserReserveKey {
+        user: user.clone(),
+        reserve_id: *res_token_index,
+    });
+    get_persistent_default(e, &key, || None, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
+}
+
+/// Set the users emission data for a reserve's d or d token
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `res_token_index` - The d/bToken index for the reserve
+/// * `data` - The new user emission d ata for the d/bToken
+pub fn set_user_emissions(e: &Env, user: &Address, res_token_index: &u32, data: &UserEmissionData) {
+    let key = PoolDataKey::UserEmis(UserReserveKey {
+        user: user.clone(),
+        reserve_id: *res_token_index,
+    });
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, UserEmissionData>(&key, data)
+}
+
+/********** User Auto Claim **********/
+
+/// Fetch the address anyone can claim a user's emissions to, if one is registered
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn get_auto_claim(e: &Env, user: &Address) -> Option<Address> {
+    let key = PoolDataKey::AutoClaim(user.clone());
+    get_persistent_default(e, &key, || None, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
+}
+
+/// Set the address anyone can claim a user's emissions to
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `to` - The address the emissions are sent to
+pub fn set_auto_claim(e: &Env, user: &Address, to: &Address) {
+    let key = PoolDataKey::AutoClaim(user.clone());
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Address>(&key, to);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Remove a user's registered auto claim address
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn del_auto_claim(e: &Env, user: &Address) {
+    let key = PoolDataKey::AutoClaim(user.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/********** User Minimum Liquidation **********/
 
-/// Fetch the emission config for the reserve b or d token
+/// Fetch the minimum percent of a user's liabilities a liquidation auction can liquidate, if one
+/// is registered
 ///
 /// ### Arguments
-/// * `res_token_index` - The d/bToken index for the reserve
-pub fn get_res_emis_config(e: &Env, res_token_index: &u32) -> Option<ReserveEmissionsConfig> {
-    let key = PoolDataKey::EmisConfig(*res_token_index);
-    get_persistent_default(
-        e,
-        &key,
-        || None,
-        LEDGER_THRESHOLD_SHARED,
-        LEDGER_BUMP_SHARED,
-    )
+/// * `user` - The address of the user
+pub fn get_min_liquidation(e: &Env, user: &Address) -> Option<u64> {
+    let key = PoolDataKey::MinLiq(user.clone());
+    get_persistent_default(e, &key, || None, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
 }
 
-/// Set the emission config for the reserve b or d token
+/// Set the minimum percent of a user's liabilities a liquidation auction can liquidate
 ///
 /// ### Arguments
-/// * `res_token_index` - The d/bToken index for the reserve
-/// * `res_emis_config` - The new emission config for the reserve token
-pub fn set_res_emis_config(
-    e: &Env,
-    res_token_index: &u32,
-    res_emis_config: &ReserveEmissionsConfig,
-) {
-    let key = PoolDataKey::EmisConfig(*res_token_index);
+/// * `user` - The address of the user
+/// * `percent` - The minimum percent liquidated (15 => 15%)
+pub fn set_min_liquidation(e: &Env, user: &Address, percent: u64) {
+    let key = PoolDataKey::MinLiq(user.clone());
     e.storage()
         .persistent()
-        .set::<PoolDataKey, ReserveEmissionsConfig>(&key, res_emis_config);
+        .set::<PoolDataKey, u64>(&key, &percent);
     e.storage()
         .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
+}
+
+/// Remove a user's registered minimum liquidation
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn del_min_liquidation(e: &Env, user: &Address) {
+    let key = PoolDataKey::MinLiq(user.clone());
+    e.storage().persistent().remove(&key);
+}
+
+/********** Filled Liquidations **********/
+
+/// Check if a liquidation auction against a user was filled recently enough for a surplus auction
+/// to be created for them
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn has_liquidation_filled(e: &Env, user: &Address) -> bool {
+    let key = PoolDataKey::LiqFilled(user.clone());
+    e.storage().temporary().has(&key)
+}
+
+/// Record that a liquidation auction against a user was filled
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn set_liquidation_filled(e: &Env, user: &Address) {
+    let key = PoolDataKey::LiqFilled(user.clone());
+    e.storage()
+        .temporary()
+        .set::<PoolDataKey, bool>(&key, &true);
+    e.storage()
+        .temporary()
         .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
 }
 
-/// Fetch the emission data for the reserve b or d token
+/// Remove the record that a liquidation auction against a user was filled
 ///
 /// ### Arguments
-/// * `res_token_index` - The d/bToken index for the reserve
-pub fn get_res_emis_data(e: &Env, res_token_index: &u32) -> Option<ReserveEmissionsData> {
-    let key = PoolDataKey::EmisData(*res_token_index);
-    get_persistent_default(
-        e,
-        &key,
-        || None,
-        LEDGER_THRESHOLD_SHARED,
-        LEDGER_BUMP_SHARED,
-    )
+/// * `user` - The address of the user
+pub fn del_liquidation_filled(e: &Env, user: &Address) {
+    let key = PoolDataKey::LiqFilled(user.clone());
+    e.storage().temporary().remove(&key);
 }
 
-/// Set the emission data for the reserve b or d token
+/********** User Submit Hook **********/
+
+/// Fetch the contract notified after a user's submit completes, if one is registered
 ///
 /// ### Arguments
-/// * `res_token_index` - The d/bToken index for the reserve
-/// * `res_emis_data` - The new emission data for the reserve token
-pub fn set_res_emis_data(e: &Env, res_token_index: &u32, res_emis_data: &ReserveEmissionsData) {
-    let key = PoolDataKey::EmisData(*res_token_index);
+/// * `user` - The address of the user
+pub fn get_submit_hook(e: &Env, user: &Address) -> Option<Address> {
+    let key = PoolDataKey::SubmitHook(user.clone());
+    get_persistent_default(e, &key, || None, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
+}
+
+/// Set the contract notified after a user's submit completes
+///
+/// ### Arguments
+/// * `user` - The address of the user
+/// * `hook` - The address of the hook contract
+pub fn set_submit_hook(e: &Env, user: &Address, hook: &Address) {
+    let key = PoolDataKey::SubmitHook(user.clone());
     e.storage()
         .persistent()
-        .set::<PoolDataKey, ReserveEmissionsData>(&key, res_emis_data);
+        .set::<PoolDataKey, Address>(&key, hook);
     e.storage()
         .persistent()
-        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+        .extend_ttl(&key, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER);
 }
 
-/********** User Emissions **********/
+/// Remove a user's registered submit hook
+///
+/// ### Arguments
+/// * `user` - The address of the user
+pub fn del_submit_hook(e: &Env, user: &Address) {
+    let key = PoolDataKey::SubmitHook(user.clone());
+    e.storage().persistent().remove(&key);
+}
 
-/// Fetch the users emission data for a reserve's b or d token
+/********** User Interest **********/
+
+/// Fetch the users interest data for a reserve's b or d token
 ///
 /// ### Arguments
 /// * `user` - The address of the user
 /// * `res_token_index` - The d/bToken index for the reserve
-pub fn get_user_emissions(
+pub fn get_user_interest(
     e: &Env,
     user: &Address,
     res_token_index: &u32,
-) -> Option<UserEmissionData> {
-    let key = PoolDataKey::UserEmis(UserReserveKey {
+) -> Option<UserInterestData> {
+    let key = PoolDataKey::UserInt(UserReserveKey {
         user: user.clone(),
         reserve_id: *res_token_index,
     });
     get_persistent_default(e, &key, || None, LEDGER_THRESHOLD_USER, LEDGER_BUMP_USER)
 }
 
-/// Set the users emission data for a reserve's d or d token
+/// Set the users interest data for a reserve's b or d token
 ///
 /// ### Arguments
 /// * `user` - The address of the user
 /// * `res_token_index` - The d/bToken index for the reserve
-/// * `data` - The new user emission d ata for the d/bToken
-pub fn set_user_emissions(e: &Env, user: &Address, res_token_index: &u32, data: &UserEmissionData) {
-    let key = PoolDataKey::UserEmis(UserReserveKey {
+/// * `data` - The new user interest data for the d/bToken
+pub fn set_user_interest(e: &Env, user: &Address, res_token_index: &u32, data: &UserInterestData) {
+    let key = PoolDataKey::UserInt(UserReserveKey {
         user: user.clone(),
         reserve_id: *res_token_index,
     });
     e.storage()
         .persistent()
-        .set::<PoolDataKey, UserEmissionData>(&key, data)
+        .set::<PoolDataKey, UserInterestData>(&key, data)
 }
 
 /********** Pool Emissions **********/
@@ -623,9 +2420,35 @@ pub fn set_pool_emissions(e: &Env, emissions: &Map<u32, u64>) {
     );
 }
 
+/// Fetch the balance of BLND tokens held by the pool from direct emissions that have not been claimed
+pub fn get_direct_emissions(e: &Env) -> i128 {
+    get_persistent_default(
+        e,
+        &Symbol::new(e, DIRECT_EMIS_KEY),
+        || 0i128,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the balance of BLND tokens held by the pool from direct emissions that have not been claimed
+///
+/// ### Arguments
+/// * `balance` - The unclaimed direct emissions balance
+pub fn set_direct_emissions(e: &Env, balance: &i128) {
+    e.storage()
+        .persistent()
+        .set::<Symbol, i128>(&Symbol::new(e, DIRECT_EMIS_KEY), balance);
+    e.storage().persistent().extend_ttl(
+        &Symbol::new(e, DIRECT_EMIS_KEY),
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    );
+}
+
 /********** Auctions ***********/
 
-/// Fetch the auction data for an auction
+/// Fetch the auction data for an auction, converted into the current auction format
 ///
 /// ### Arguments
 /// * `auction_type` - The type of auction
@@ -634,14 +2457,38 @@ pub fn set_pool_emissions(e: &Env, emissions: &Map<u32, u64>) {
 /// ### Panics
 /// If the auction does not exist
 pub fn get_auction(e: &Env, auction_type: &u32, user: &Address) -> AuctionData {
+    get_auction_with_curve(e, auction_type, user).0
+}
+
+/// Fetch the auction data for an auction and the auction curve it is scaled by
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `user` - The user who is auctioning off assets
+///
+/// ### Panics
+/// If the auction does not exist
+pub fn get_auction_with_curve(
+    e: &Env,
+    auction_type: &u32,
+    user: &Address,
+) -> (AuctionData, AuctionCurve) {
     let key = PoolDataKey::Auction(AuctionKey {
         user: user.clone(),
         auct_type: *auction_type,
     });
-    e.storage()
+    let stored = e
+        .storage()
         .temporary()
-        .get::<PoolDataKey, AuctionData>(&key)
-        .unwrap_optimized()
+        .get::<PoolDataKey, Val>(&key)
+        .unwrap_optimized();
+    match VersionedAuctionData::try_from_val(e, &stored) {
+        Ok(versioned) => versioned.into_latest(e),
+        Err(_) => (
+            AuctionData::try_from_val(e, &stored).unwrap_optimized(),
+            get_auction_curve(e),
+        ),
+    }
 }
 
 /// Check if an auction exists for the given type and user
@@ -657,20 +2504,40 @@ pub fn has_auction(e: &Env, auction_type: &u32, user: &Address) -> bool {
     e.storage().temporary().has(&key)
 }
 
-/// Set the the starting block for an auction
+/// Set the the starting block for an auction, scaled by the pool's current auction curve
 ///
 /// ### Arguments
 /// * `auction_type` - The type of auction
 /// * `user` - The user who is auctioning off assets
 /// * `auction_data` - The auction data
 pub fn set_auction(e: &Env, auction_type: &u32, user: &Address, auction_data: &AuctionData) {
+    set_auction_with_curve(e, auction_type, user, auction_data, &get_auction_curve(e));
+}
+
+/// Set the auction data for an auction and the auction curve it is scaled by
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `user` - The user who is auctioning off assets
+/// * `auction_data` - The auction data
+/// * `curve` - The auction curve
+pub fn set_auction_with_curve(
+    e: &Env,
+    auction_type: &u32,
+    user: &Address,
+    auction_data: &AuctionData,
+    curve: &AuctionCurve,
+) {
     let key = PoolDataKey::Auction(AuctionKey {
         user: user.clone(),
         auct_type: *auction_type,
     });
     e.storage()
         .temporary()
-        .set::<PoolDataKey, AuctionData>(&key, auction_data);
+        .set::<PoolDataKey, VersionedAuctionData>(
+            &key,
+            &VersionedAuctionData::V2(auction_data.clone(), curve.clone()),
+        );
     e.storage()
         .temporary()
         .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
@@ -688,3 +2555,469 @@ pub fn del_auction(e: &Env, auction_type: &u32, user: &Address) {
     });
     e.storage().temporary().remove(&key);
 }
+
+/// Fetch the number of entries in the list of users with an auction of the given type. The
+/// list can contain users whose auction has since expired.
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+pub fn get_auction_count(e: &Env, auction_type: &u32) -> u32 {
+    get_persistent_default(
+        e,
+        &PoolDataKey::AuctCount(*auction_type),
+        || 0,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the number of entries in the list of users with an auction of the given type
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `count` - The number of entries
+pub fn set_auction_count(e: &Env, auction_type: &u32, count: u32) {
+    let key = PoolDataKey::AuctCount(*auction_type);
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, u32>(&key, &count);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Fetch the user at a position in the list of users with an auction of the given type
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `index` - The position in the list
+///
+/// ### Panics
+/// If there is no entry at the position
+pub fn get_auction_list_entry(e: &Env, auction_type: &u32, index: u32) -> Address {
+    let key = PoolDataKey::AuctEntry(AuctionListKey {
+        auct_type: *auction_type,
+        index,
+    });
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+    e.storage()
+        .persistent()
+        .get::<PoolDataKey, Address>(&key)
+        .unwrap_optimized()
+}
+
+/// Fetch the position of a user in the list of users with an auction of the given type, or
+/// None if the user is not listed
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `user` - The user who is auctioning off assets
+pub fn get_auction_list_index(e: &Env, auction_type: &u32, user: &Address) -> Option<u32> {
+    get_persistent_default(
+        e,
+        &PoolDataKey::AuctIndex(AuctionKey {
+            user: user.clone(),
+            auct_type: *auction_type,
+        }),
+        || None,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the user at a position in the list of users with an auction of the given type
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `index` - The position in the list
+/// * `user` - The user who is auctioning off assets
+pub fn set_auction_list_entry(e: &Env, auction_type: &u32, index: u32, user: &Address) {
+    let entry_key = PoolDataKey::AuctEntry(AuctionListKey {
+        auct_type: *auction_type,
+        index,
+    });
+    let index_key = PoolDataKey::AuctIndex(AuctionKey {
+        user: user.clone(),
+        auct_type: *auction_type,
+    });
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, Address>(&entry_key, user);
+    e.storage()
+        .persistent()
+        .extend_ttl(&entry_key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, u32>(&index_key, &index);
+    e.storage()
+        .persistent()
+        .extend_ttl(&index_key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Remove the user at a position in the list of users with an auction of the given type
+///
+/// ### Arguments
+/// * `auction_type` - The type of auction
+/// * `index` - The position in the list
+/// * `user` - The user at the position
+pub fn del_auction_list_entry(e: &Env, auction_type: &u32, index: u32, user: &Address) {
+    e.storage()
+        .persistent()
+        .remove(&PoolDataKey::AuctEntry(AuctionListKey {
+            auct_type: *auction_type,
+            index,
+        }));
+    e.storage()
+        .persistent()
+        .remove(&PoolDataKey::AuctIndex(AuctionKey {
+            user: user.clone(),
+            auct_type: *auction_type,
+        }));
+}
+
+/********** Bad Debt Log **********/
+
+/// Fetch the number of records in the bad debt log
+pub fn get_bad_debt_count(e: &Env) -> u32 {
+    get_persistent_default(
+        e,
+        &Symbol::new(e, BAD_DEBT_COUNT_KEY),
+        || 0u32,
+        LEDGER_THRESHOLD_SHARED,
+        LEDGER_BUMP_SHARED,
+    )
+}
+
+/// Set the number of records in the bad debt log
+///
+/// ### Arguments
+/// * `count` - The number of records
+pub fn set_bad_debt_count(e: &Env, count: &u32) {
+    let key = Symbol::new(e, BAD_DEBT_COUNT_KEY);
+    e.storage().persistent().set::<Symbol, u32>(&key, count);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+/// Fetch a record from the bad debt log
+///
+/// ### Arguments
+/// * `index` - The index of the record
+///
+/// ### Panics
+/// If the record does not exist
+pub fn get_bad_debt_record(e: &Env, index: &u32) -> BadDebtRecord {
+    let key = PoolDataKey::BadDebt(*index);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+    e.storage()
+        .persistent()
+        .get::<PoolDataKey, BadDebtRecord>(&key)
+        .unwrap_optimized()
+}
+
+/// Set a record in the bad debt log
+///
+/// ### Arguments
+/// * `index` - The index of the record
+/// * `record` - The bad debt record
+pub fn set_bad_debt_record(e: &Env, index: &u32, record: &BadDebtRecord) {
+    let key = PoolDataKey::BadDebt(*index);
+    e.storage()
+        .persistent()
+        .set::<PoolDataKey, BadDebtRecord>(&key, record);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, LEDGER_THRESHOLD_SHARED, LEDGER_BUMP_SHARED);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use soroban_sdk::testutils::Address as _;
+
+    #[test]
+    fn test_reserve_config_pack_roundtrip() {
+        let e = Env::default();
+        let config = ReserveConfig {
+            index: 1,
+            decimals: 2,
+            c_factor: 3,
+            l_factor: 4,
+            util: 5,
+            max_util: 6,
+            r_base: 7,
+            r_one: 8,
+            r_two: 9,
+            r_three: u32::MAX,
+            reactivity: 11,
+            fee_on_transfer: true,
+            min_first_supply: (1 << 96) - 1,
+            locked_b_tokens: 13,
+        };
+        let unpacked = ReserveConfig::unpack(&config.pack(&e));
+        assert_eq!(unpacked.index, 1);
+        assert_eq!(unpacked.decimals, 2);
+        assert_eq!(unpacked.c_factor, 3);
+        assert_eq!(unpacked.l_factor, 4);
+        assert_eq!(unpacked.util, 5);
+        assert_eq!(unpacked.max_util, 6);
+        assert_eq!(unpacked.r_base, 7);
+        assert_eq!(unpacked.r_one, 8);
+        assert_eq!(unpacked.r_two, 9);
+        assert_eq!(unpacked.r_three, u32::MAX);
+        assert_eq!(unpacked.reactivity, 11);
+        assert!(unpacked.fee_on_transfer);
+        assert_eq!(unpacked.min_first_supply, (1 << 96) - 1);
+        assert_eq!(unpacked.locked_b_tokens, 13);
+    }
+
+    #[test]
+    fn test_get_res_config_v1_layout() {
+        let e = Env::default();
+        let pool = crate::testutils::create_pool(&e);
+        let asset = Address::generate(&e);
+
+        let config = ReserveConfig {
+            index: 1,
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 0_0001000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            fee_on_transfer: true,
+            min_first_supply: 1000,
+            locked_b_tokens: 10,
+        };
+        let packed = config.pack(&e);
+        e.as_contract(&pool, || {
+            e.storage()
+                .persistent()
+                .set::<PoolDataKey, (u128, u128, u128)>(
+                    &PoolDataKey::ResConfig(asset.clone()),
+                    &(packed.0, packed.1, packed.2),
+                );
+
+            let stored = get_res_config(&e, &asset);
+            assert_eq!(stored.index, 1);
+            assert_eq!(stored.c_factor, 0_7500000);
+            assert_eq!(stored.r_three, 1_5000000);
+            assert_eq!(stored.reactivity, 100);
+            assert!(stored.fee_on_transfer);
+            assert_eq!(stored.min_first_supply, 0);
+            assert_eq!(stored.locked_b_tokens, 0);
+
+            set_res_config(&e, &asset, &config);
+            let stored = get_res_config(&e, &asset);
+            assert_eq!(stored.min_first_supply, 1000);
+            assert_eq!(stored.locked_b_tokens, 10);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "Error(Contract, #1202)")]
+    fn test_reserve_config_pack_negative_min_first_supply() {
+        let e = Env::default();
+        let config = ReserveConfig {
+            index: 1,
+            decimals: 7,
+            c_factor: 0_7500000,
+            l_factor: 0_7500000,
+            util: 0_5000000,
+            max_util: 0_9500000,
+            r_base: 0_0001000,
+            r_one: 0_0500000,
+            r_two: 0_5000000,
+            r_three: 1_5000000,
+            reactivity: 100,
+            fee_on_transfer: false,
+            min_first_supply: -1,
+            locked_b_tokens: 0,
+        };
+        config.pack(&e);
+    }
+
+    #[test]
+    fn test_get_res_config_and_data_map_layout() {
+        let e = Env::default();
+        let pool = crate::testutils::create_pool(&e);
+        let asset = Address::generate(&e);
+
+        let legacy_config = LegacyReserveConfig {
+            index: 2,
+            decimals: 6,
+            c_factor: 0_9000000,
+            l_factor: 0_9500000,
+            util: 0_8000000,
+            max_util: 0_9500000,
+            r_base: 0_0003000,
+            r_one: 0_0400000,
+            r_two: 0_2000000,
+            r_three: 1_0000000,
+            reactivity: 0_0000020,
+        };
+        let legacy_data = LegacyReserveData {
+            d_rate: 1_001_000_000,
+            b_rate: 1_000_500_000,
+            ir_mod: 1_1000000,
+            b_supply: 100_0000000,
+            d_supply: 50_0000000,
+            backstop_credit: 1234,
+            last_time: 1_700_000_000,
+        };
+        e.as_contract(&pool, || {
+            e.storage()
+                .persistent()
+                .set::<PoolDataKey, LegacyReserveConfig>(
+                    &PoolDataKey::ResConfig(asset.clone()),
+                    &legacy_config,
+                );
+            e.storage()
+                .persistent()
+                .set::<PoolDataKey, LegacyReserveData>(
+                    &PoolDataKey::ResData(asset.clone()),
+                    &legacy_data,
+                );
+
+            let config = get_res_config(&e, &asset);
+            assert_eq!(config.index, 2);
+            assert_eq!(config.decimals, 6);
+            assert_eq!(config.c_factor, 0_9000000);
+            assert_eq!(config.l_factor, 0_9500000);
+            assert_eq!(config.util, 0_8000000);
+            assert_eq!(config.max_util, 0_9500000);
+            assert_eq!(config.r_base, 0_0003000);
+            assert_eq!(config.r_one, 0_0400000);
+            assert_eq!(config.r_two, 0_2000000);
+            assert_eq!(config.r_three, 1_0000000);
+            assert_eq!(config.reactivity, 0_0000020);
+            assert!(!config.fee_on_transfer);
+            assert_eq!(config.min_first_supply, 0);
+            assert_eq!(config.locked_b_tokens, 0);
+
+            let data = get_res_data(&e, &asset);
+            assert_eq!(data.d_rate, 1_001_000_000);
+            assert_eq!(data.b_rate, 1_000_500_000);
+            assert_eq!(data.ir_mod, 1_1000000);
+            assert_eq!(data.util_avg, 0);
+            assert_eq!(data.b_supply, 100_0000000);
+            assert_eq!(data.c_supply, 0);
+            assert_eq!(data.d_supply, 50_0000000);
+            assert_eq!(data.backstop_credit, 1234);
+            assert_eq!(data.interest_paid, 0);
+            assert_eq!(data.interest_earned, 0);
+            assert_eq!(data.ramp_end, 0);
+            assert_eq!(data.last_time, 1_700_000_000);
+
+            // the entries are rewritten in the packed layout the next time they are stored
+            set_res_config(&e, &asset, &config);
+            set_res_data(&e, &asset, &data);
+            assert!(e
+                .storage()
+                .persistent()
+                .get::<PoolDataKey, PackedReserveConfig>(&PoolDataKey::ResConfig(asset.clone()))
+                .is_some());
+            assert_eq!(get_res_config(&e, &asset).r_three, 1_0000000);
+            assert_eq!(get_res_data(&e, &asset).b_supply, 100_0000000);
+        });
+    }
+
+    #[test]
+    fn test_reserve_data_pack_roundtrip() {
+        let data = ReserveData {
+            d_rate: 1,
+            b_rate: 2,
+            ir_mod: 3,
+            util_avg: 4,
+            b_supply: 5,
+            c_supply: 6,
+            d_supply: 7,
+            backstop_credit: 8,
+            interest_paid: 9,
+            interest_earned: 10,
+            ramp_c_factor: u32::MAX,
+            ramp_start: u64::MAX,
+            ramp_end: 12,
+            last_time: u64::MAX - 1,
+        };
+        let unpacked = ReserveData::unpack(&data.pack());
+        assert_eq!(unpacked.d_rate, 1);
+        assert_eq!(unpacked.b_rate, 2);
+        assert_eq!(unpacked.ir_mod, 3);
+        assert_eq!(unpacked.util_avg, 4);
+        assert_eq!(unpacked.b_supply, 5);
+        assert_eq!(unpacked.c_supply, 6);
+        assert_eq!(unpacked.d_supply, 7);
+        assert_eq!(unpacked.backstop_credit, 8);
+        assert_eq!(unpacked.interest_paid, 9);
+        assert_eq!(unpacked.interest_earned, 10);
+        assert_eq!(unpacked.ramp_c_factor, u32::MAX);
+        assert_eq!(unpacked.ramp_start, u64::MAX);
+        assert_eq!(unpacked.ramp_end, 12);
+        assert_eq!(unpacked.last_time, u64::MAX - 1);
+    }
+
+    #[test]
+    fn test_get_auction_versions() {
+        let e = Env::default();
+        let pool = crate::testutils::create_pool(&e);
+        let samwise = Address::generate(&e);
+        let frodo = Address::generate(&e);
+        let asset = Address::generate(&e);
+
+        let auction_data = AuctionData {
+            bid: map![&e, (asset.clone(), 1_0000000)],
+            lot: map![&e, (asset.clone(), 2_0000000)],
+            block: 123,
+        };
+        e.as_contract(&pool, || {
+            // auctions stored before versioning hold a bare AuctionData
+            let legacy_key = PoolDataKey::Auction(AuctionKey {
+                user: samwise.clone(),
+                auct_type: 0,
+            });
+            e.storage()
+                .temporary()
+                .set::<PoolDataKey, AuctionData>(&legacy_key, &auction_data);
+            let legacy = get_auction(&e, &0, &samwise);
+            assert_eq!(legacy.bid, auction_data.bid);
+            assert_eq!(legacy.lot, auction_data.lot);
+            assert_eq!(legacy.block, 123);
+
+            set_auction(&e, &0, &frodo, &auction_data);
+            let versioned_key = PoolDataKey::Auction(AuctionKey {
+                user: frodo.clone(),
+                auct_type: 0,
+            });
+            assert!(e
+                .storage()
+                .temporary()
+                .get::<PoolDataKey, VersionedAuctionData>(&versioned_key)
+                .is_some());
+            let versioned = get_auction(&e, &0, &frodo);
+            assert_eq!(versioned.bid, auction_data.bid);
+            assert_eq!(versioned.lot, auction_data.lot);
+            assert_eq!(versioned.block, 123);
+
+            // auctions keep the curve they were created with, while legacy auctions follow the
+            // pool's current curve
+            let created_curve = get_auction_curve(&e);
+            let new_curve = AuctionCurve {
+                lot_blocks: 50,
+                bid_blocks: 100,
+            };
+            set_auction_curve(&e, &new_curve);
+            assert_eq!(get_auction_with_curve(&e, &0, &frodo).1, created_curve);
+            assert_eq!(get_auction_with_curve(&e, &0, &samwise).1, new_curve);
+        });
+    }
+}
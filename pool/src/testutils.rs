@@ -1,19 +1,27 @@
 #![cfg(test)]
 
 use crate::{
+    auctions::{AuctionData, AuctionType},
     constants::{SCALAR_7, SCALAR_9},
-    pool::Reserve,
-    storage::{self, ReserveConfig, ReserveData},
+    pool::{Positions, Reserve, SubmitSummary},
+    storage::{self, PoolConfig, ReserveConfig, ReserveData},
     PoolContract,
 };
 use emitter::{EmitterClient, EmitterContract};
-use sep_40_oracle::testutils::{MockPriceOracleClient, MockPriceOracleWASM};
+use sep_40_oracle::testutils::{Asset, MockPriceOracleClient, MockPriceOracleWASM};
 use sep_41_token::testutils::{MockTokenClient, MockTokenWASM};
 use soroban_fixed_point_math::FixedPoint;
-use soroban_sdk::{testutils::Address as _, unwrap::UnwrapOptimized, vec, Address, Env, IntoVal};
+use soroban_sdk::{
+    contract, contractimpl, map,
+    testutils::{Address as _, Ledger, LedgerInfo},
+    unwrap::UnwrapOptimized,
+    vec, Address, Env, IntoVal, Symbol,
+};
 
 use backstop::{BackstopClient, BackstopContract};
+use mock_backstop::{MockBackstop, MockBackstopClient};
 use mock_pool_factory::{MockPoolFactory, MockPoolFactoryClient};
+use pause_registry::{PauseRegistryClient, PauseRegistryContract};
 
 pub(crate) fn create_pool(e: &Env) -> Address {
     e.register_contract(None, PoolContract {})
@@ -49,6 +57,106 @@ pub(crate) fn create_blnd_token<'a>(
     (contract_address, client)
 }
 
+// ***** Fee on Transfer Token *****
+
+/// A minimal token that burns 1% of every transfer
+#[contract]
+pub struct MockFeeToken;
+
+#[contractimpl]
+impl MockFeeToken {
+    pub fn mint(e: Env, to: Address, amount: i128) {
+        let balance = Self::balance(e.clone(), to.clone());
+        e.storage().persistent().set(&to, &(balance + amount));
+    }
+
+    pub fn balance(e: Env, id: Address) -> i128 {
+        e.storage().persistent().get(&id).unwrap_or(0)
+    }
+
+    pub fn decimals(_e: Env) -> u32 {
+        7
+    }
+
+    pub fn transfer(e: Env, from: Address, to: Address, amount: i128) {
+        from.require_auth();
+        let from_balance = Self::balance(e.clone(), from.clone());
+        e.storage()
+            .persistent()
+            .set(&from, &(from_balance - amount));
+        let fee = amount / 100;
+        let to_balance = Self::balance(e.clone(), to.clone());
+        e.storage()
+            .persistent()
+            .set(&to, &(to_balance + amount - fee));
+    }
+}
+
+pub(crate) fn create_fee_token_contract<'a>(e: &Env) -> (Address, MockFeeTokenClient<'a>) {
+    let contract_address = e.register_contract(None, MockFeeToken {});
+    (
+        contract_address.clone(),
+        MockFeeTokenClient::new(e, &contract_address),
+    )
+}
+
+// ***** Exchange Rate *****
+
+/// A minimal exchange rate source for a yield-bearing asset
+#[contract]
+pub struct MockExchangeRate;
+
+#[contractimpl]
+impl MockExchangeRate {
+    pub fn set_rate(e: Env, rate: i128, timestamp: u64) {
+        e.storage().instance().set(&0u32, &(rate, timestamp));
+    }
+
+    pub fn exchange_rate(e: Env) -> (i128, u64) {
+        e.storage().instance().get(&0u32).unwrap_optimized()
+    }
+}
+
+pub(crate) fn create_mock_exchange_rate<'a>(e: &Env) -> (Address, MockExchangeRateClient<'a>) {
+    let contract_address = e.register_contract(None, MockExchangeRate {});
+    (
+        contract_address.clone(),
+        MockExchangeRateClient::new(e, &contract_address),
+    )
+}
+
+// ***** Submit Hook *****
+
+/// A submit hook that records the last summary it received, or fails if set to
+#[contract]
+pub struct MockSubmitHook;
+
+#[contractimpl]
+impl MockSubmitHook {
+    pub fn set_fail(e: Env, fail: bool) {
+        e.storage().instance().set(&0u32, &fail);
+    }
+
+    pub fn on_submit(e: Env, _pool: Address, user: Address, summary: SubmitSummary) {
+        if e.storage().instance().get(&0u32).unwrap_or(false) {
+            panic!("hook failed");
+        }
+        e.storage().instance().set(&1u32, &(user, summary));
+    }
+
+    pub fn get_last(e: Env) -> Option<(Address, SubmitSummary)> {
+        e.storage().instance().get(&1u32)
+    }
+}
+
+pub(crate) fn create_mock_submit_hook<'a>(e: &Env) -> (Address, MockSubmitHookClient<'a>) {
+    let contract_address = e.register_contract(None, MockSubmitHook {});
+    (
+        contract_address.clone(),
+        MockSubmitHookClient::new(e, &contract_address),
+    )
+}
+
 //***** Oracle ******
 
 pub(crate) fn create_mock_oracle(e: &Env) -> (Address, MockPriceOracleClient) {
@@ -69,17 +177,34 @@ pub(crate) fn create_mock_pool_factory(e: &Env) -> (Address, MockPoolFactoryClie
     )
 }
 
-//***** Pool Factory ******
+//***** Pause Registry ******
+
+pub(crate) fn create_pause_registry<'a>(
+    e: &Env,
+    pool_address: &Address,
+    admin: &Address,
+) -> (Address, PauseRegistryClient<'a>) {
+    let contract_address = e.register_contract(None, PauseRegistryContract {});
+    let client = PauseRegistryClient::new(e, &contract_address);
+    client.initialize(admin);
+    e.as_contract(pool_address, || {
+        storage::set_pause_registry(e, &contract_address);
+    });
+    (contract_address, client)
+}
+
+//***** Emitter ******
 
 pub(crate) fn create_emitter<'a>(
     e: &Env,
     backstop_id: &Address,
     backstop_token: &Address,
     blnd_token: &Address,
+    pause_registry: &Address,
 ) -> (Address, EmitterClient<'a>) {
     let contract_address = e.register_contract(None, EmitterContract {});
     let client = EmitterClient::new(e, &contract_address);
-    client.initialize(blnd_token, backstop_id, backstop_token);
+    client.initialize(blnd_token, backstop_id, backstop_token, pause_registry);
     (contract_address.clone(), client)
 }
 
@@ -107,7 +232,8 @@ pub(crate) fn setup_backstop(
 ) {
     let (pool_factory, mock_pool_factory_client) = create_mock_pool_factory(e);
     mock_pool_factory_client.set_pool(pool_address);
-    let (emitter, _) = create_emitter(e, backstop_id, backstop_token, blnd_token);
+    let (pause_registry, _) = create_pause_registry(e, pool_address, &Address::generate(e));
+    let (emitter, _) = create_emitter(e, backstop_id, backstop_token, blnd_token, &pause_registry);
     let backstop_client: BackstopClient = BackstopClient::new(e, backstop_id);
     backstop_client.initialize(
         backstop_token,
@@ -115,6 +241,7 @@ pub(crate) fn setup_backstop(
         usdc_token,
         blnd_token,
         &pool_factory,
+        &pause_registry,
         &vec![e, (pool_address.clone(), 50_000_000 * SCALAR_7)],
     );
     e.as_contract(pool_address, || {
@@ -122,6 +249,22 @@ pub(crate) fn setup_backstop(
     });
 }
 
+/// Deploy a mock backstop that can be configured to fail calls or report arbitrary pool data,
+/// and set it as the pool's backstop
+pub(crate) fn create_mock_backstop<'a>(
+    e: &Env,
+    pool_address: &Address,
+    backstop_token: &Address,
+) -> (Address, MockBackstopClient<'a>) {
+    let contract_address = e.register_contract(None, MockBackstop {});
+    let client = MockBackstopClient::new(e, &contract_address);
+    client.initialize(backstop_token, &Address::generate(e));
+    e.as_contract(pool_address, || {
+        storage::set_backstop(e, &contract_address);
+    });
+    (contract_address, client)
+}
+
 /// Deploy a test Comet LP pool of 80% BLND / 20% USDC and set it as the backstop token.
 ///
 /// Initializes the pool with the following settings:
@@ -173,9 +316,16 @@ pub(crate) fn default_reserve(e: &Env) -> Reserve {
         d_rate: 1_000_000_000,
         b_rate: 1_000_000_000,
         ir_mod: 1_000_000_000,
+        util_avg: 0_7500000,
         b_supply: 100_0000000,
+        c_supply: 0,
         d_supply: 75_0000000,
         backstop_credit: 0,
+        interest_paid: 0,
+        interest_earned: 0,
+        ramp_c_factor: 0,
+        ramp_start: 0,
+        ramp_end: 0,
     }
 }
 
@@ -192,16 +342,26 @@ pub(crate) fn default_reserve_meta() -> (ReserveConfig, ReserveData) {
             r_two: 0_5000000,
             r_three: 1_5000000,
             reactivity: 0_0000020, // 2e-6
+            fee_on_transfer: false,
+            min_first_supply: 0,
+            locked_b_tokens: 0,
             index: 0,
         },
         ReserveData {
             b_rate: 1_000_000_000,
             d_rate: 1_000_000_000,
             ir_mod: 1_000_000_000,
+            util_avg: 0_7500000,
             b_supply: 100_0000000,
+            c_supply: 0,
             d_supply: 75_0000000,
             last_time: 0,
             backstop_credit: 0,
+            interest_paid: 0,
+            interest_earned: 0,
+            ramp_c_factor: 0,
+            ramp_start: 0,
+            ramp_end: 0,
         },
     )
 }
@@ -242,3 +402,170 @@ pub(crate) fn create_reserve(
         .mock_all_auths()
         .mint(&pool_address, &to_mint_pool);
 }
+
+/// Create a pool with two default reserves priced at 1 and 5 by the mock oracle and a pool config
+/// using the mock oracle, and write `positions` for `user`. The user holds 100 of the first asset
+/// and has approved the pool to spend it, and the pool holds an extra 100 of the second asset.
+///
+/// Sets the ledger to a timestamp of 600, when the reserves were last updated.
+///
+/// Returns the pool and the two underlying asset addresses
+pub(crate) fn create_priced_pool(
+    e: &Env,
+    user: &Address,
+    positions: &Positions,
+) -> (Address, Address, Address) {
+    e.ledger().set(LedgerInfo {
+        timestamp: 600,
+        protocol_version: 20,
+        sequence_number: 1234,
+        network_id: Default::default(),
+        base_reserve: 10,
+        min_temp_entry_ttl: 10,
+        min_persistent_entry_ttl: 10,
+        max_entry_ttl: 3110400,
+    });
+
+    let bombadil = Address::generate(e);
+    let pool = create_pool(e);
+    let (oracle, oracle_client) = create_mock_oracle(e);
+
+    let (underlying_0, underlying_0_client) = create_token_contract(e, &bombadil);
+    let (reserve_config, mut reserve_data) = default_reserve_meta();
+    reserve_data.last_time = 600;
+    create_reserve(e, &pool, &underlying_0, &reserve_config, &reserve_data);
+    underlying_0_client.mint(user, &100_0000000);
+    underlying_0_client.approve(user, &pool, &100_0000000, &1000000);
+
+    let (underlying_1, underlying_1_client) = create_token_contract(e, &bombadil);
+    create_reserve(e, &pool, &underlying_1, &reserve_config, &reserve_data);
+    underlying_1_client.mint(&pool, &100_0000000);
+
+    oracle_client.set_data(
+        &bombadil,
+        &Asset::Other(Symbol::new(e, "USD")),
+        &vec![
+            e,
+            Asset::Stellar(underlying_0.clone()),
+            Asset::Stellar(underlying_1.clone()),
+        ],
+        &7,
+        &300,
+    );
+    oracle_client.set_price_stable(&vec![e, 1_0000000, 5_0000000]);
+
+    let pool_config = PoolConfig {
+        oracle,
+        bstop_rate: 0_1000000,
+        status: 0,
+        max_positions: 4,
+    };
+    e.as_contract(&pool, || {
+        storage::set_pool_config(e, &pool_config);
+        storage::set_user_positions(e, user, positions);
+    });
+    (pool, underlying_0, underlying_1)
+}
+
+/// Create three reserves and a user liquidation auction against `user` that started at block 176.
+/// The user's positions and the auction are written to the pool along with a pool config using
+/// the mock oracle.
+///
+/// Expects the ledger to be at timestamp 12345.
+///
+/// Returns the three underlying asset addresses, the user's positions and the auction
+pub(crate) fn create_user_liquidation(
+    e: &Env,
+    pool_address: &Address,
+    user: &Address,
+) -> (Address, Address, Address, Positions, AuctionData) {
+    let bombadil = Address::generate(e);
+    let (oracle_address, _) = create_mock_oracle(e);
+
+    // creating reserves for a pool exhausts the budget
+    e.budget().reset_unlimited();
+    let (underlying_0, _) = create_token_contract(e, &bombadil);
+    let (mut reserve_config_0, mut reserve_data_0) = default_reserve_meta();
+    reserve_data_0.last_time = 12345;
+    reserve_data_0.b_rate = 1_100_000_000;
+    reserve_config_0.c_factor = 0_8500000;
+    reserve_config_0.l_factor = 0_9000000;
+    reserve_config_0.index = 0;
+    create_reserve(
+        e,
+        pool_address,
+        &underlying_0,
+        &reserve_config_0,
+        &reserve_data_0,
+    );
+
+    let (underlying_1, _) = create_token_contract(e, &bombadil);
+    let (mut reserve_config_1, mut reserve_data_1) = default_reserve_meta();
+    reserve_data_1.b_rate = 1_200_000_000;
+    reserve_config_1.c_factor = 0_7500000;
+    reserve_config_1.l_factor = 0_7500000;
+    reserve_data_1.last_time = 12345;
+    reserve_config_1.index = 1;
+    create_reserve(
+        e,
+        pool_address,
+        &underlying_1,
+        &reserve_config_1,
+        &reserve_data_1,
+    );
+
+    let (underlying_2, _) = create_token_contract(e, &bombadil);
+    let (mut reserve_config_2, reserve_data_2) = default_reserve_meta();
+    reserve_config_2.c_factor = 0_0000000;
+    reserve_config_2.l_factor = 0_7000000;
+    reserve_config_2.index = 2;
+    create_reserve(
+        e,
+        pool_address,
+        &underlying_2,
+        &reserve_config_2,
+        &reserve_data_2,
+    );
+
+    let auction_data = AuctionData {
+        bid: map![e, (underlying_2.clone(), 1_2375000)],
+        lot: map![
+            e,
+            (underlying_0.clone(), 30_5595329),
+            (underlying_1.clone(), 1_5395739)
+        ],
+        block: 176,
+    };
+    let pool_config = PoolConfig {
+        oracle: oracle_address,
+        bstop_rate: 0_1000000,
+        status: 0,
+        max_positions: 4,
+    };
+    let positions: Positions = Positions {
+        collateral: map![
+            e,
+            (reserve_config_0.index, 90_9100000),
+            (reserve_config_1.index, 04_5800000),
+        ],
+        liabilities: map![e, (reserve_config_2.index, 02_7500000),],
+        supply: map![e],
+    };
+    e.as_contract(pool_address, || {
+        storage::set_pool_config(e, &pool_config);
+        storage::set_user_positions(e, user, &positions);
+        storage::set_auction(
+            e,
+            &(AuctionType::UserLiquidation as u32),
+            user,
+            &auction_data,
+        );
+    });
+    (
+        underlying_0,
+        underlying_1,
+        underlying_2,
+        positions,
+        auction_data,
+    )
+}
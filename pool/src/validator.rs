@@ -1,6 +1,12 @@
-use soroban_sdk::{panic_with_error, Env};
+use soroban_sdk::Env;
 
-use crate::errors::PoolError;
+use crate::{
+    constants::{MAX_C_FACTOR, MAX_L_FACTOR, MAX_REACTIVITY, MAX_TARGET_UTIL, SCALAR_7},
+    dependencies::PauseRegistryClient,
+    errors::{panic_with_context, PoolError},
+    pool::{Request, RequestType},
+    storage::{self, ReserveConfig},
+};
 
 /// Require that an incoming amount is not negative
 ///
@@ -11,7 +17,75 @@ use crate::errors::PoolError;
 /// If the number is negative
 pub fn require_nonnegative(e: &Env, amount: &i128) {
     if amount.is_negative() {
-        panic_with_error!(e, PoolError::NegativeAmountError);
+        panic_with_context(e, PoolError::NegativeAmountError, None, None);
+    }
+}
+
+/// Require that a submitted request has a known request type and a nonnegative amount
+///
+/// ### Arguments
+/// * `request_index` - The index of the request in the submitted requests
+/// * `request` - The request to check
+///
+/// ### Panics
+/// If the request type is unknown or the amount is negative
+pub fn require_valid_request(e: &Env, request_index: u32, request: &Request) {
+    if request.amount.is_negative() {
+        panic_with_context(
+            e,
+            PoolError::NegativeAmountError,
+            Some(request_index),
+            Some(request.address.clone()),
+        );
+    }
+    if request.request_type > RequestType::FillSurplusAuction as u32 {
+        panic_with_context(
+            e,
+            PoolError::BadRequest,
+            Some(request_index),
+            Some(request.address.clone()),
+        );
+    }
+}
+
+/// Require that the protocol is not paused by the pause registry
+///
+/// ### Panics
+/// If the pause registry reports the protocol as paused
+pub fn require_not_paused(e: &Env) {
+    let pause_registry = storage::get_pause_registry(e);
+    if PauseRegistryClient::new(e, &pause_registry).is_paused() {
+        panic_with_context(e, PoolError::ContractPaused, None, None);
+    }
+}
+
+/// Require that no migration is pending after an upgrade. State written before the migration
+/// runs could be in a layout the new WASM does not expect.
+///
+/// ### Panics
+/// If a migration is pending
+pub fn require_not_migrating(e: &Env) {
+    if storage::get_migration_pending(e) {
+        panic_with_context(e, PoolError::MigrationPending, None, None);
+    }
+}
+
+/// Require that a reserve's risk parameters are within the pool's global bounds
+///
+/// ### Arguments
+/// * `config` - The reserve config to check
+///
+/// ### Panics
+/// If the collateral factor, liability factor, target utilization, or reactivity is out of bounds,
+/// or if the collateral factor multiplied by the liability factor is not under 1
+pub fn require_valid_risk_params(e: &Env, config: &ReserveConfig) {
+    if config.c_factor > MAX_C_FACTOR
+        || config.l_factor > MAX_L_FACTOR
+        || config.util >= MAX_TARGET_UTIL
+        || config.reactivity > MAX_REACTIVITY
+        || i128::from(config.c_factor) * i128::from(config.l_factor) >= SCALAR_7 * SCALAR_7
+    {
+        panic_with_context(e, PoolError::InvalidReserveMetadata, None, None);
     }
 }
 
@@ -4,6 +4,7 @@ mod backstop_contract_wasm {
     soroban_sdk::contractimport!(file = "../target/wasm32-unknown-unknown/optimized/backstop.wasm");
 }
 use backstop::{BackstopClient, BackstopContract};
+pub use backstop_contract_wasm::WASM as BACKSTOP_WASM;
 
 pub fn create_backstop<'a>(e: &Env, wasm: bool) -> (Address, BackstopClient<'a>) {
     let contract_id = Address::generate(e);
@@ -3,9 +3,11 @@ pub mod backstop;
 pub mod emitter;
 pub mod liquidity_pool;
 pub mod oracle;
+pub mod pause_registry;
 pub mod pool;
 pub mod pool_factory;
 mod setup;
+pub mod snapshot;
 pub use setup::create_fixture_with_data;
 pub mod assertions;
 pub mod test_fixture;
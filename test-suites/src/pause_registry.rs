@@ -0,0 +1,21 @@
+use soroban_sdk::{testutils::Address as _, Address, Env};
+
+mod pause_registry_contract {
+    soroban_sdk::contractimport!(
+        file = "../target/wasm32-unknown-unknown/optimized/pause_registry.wasm"
+    );
+}
+use pause_registry::{PauseRegistryClient, PauseRegistryContract};
+
+pub fn create_pause_registry<'a>(e: &Env, wasm: bool) -> (Address, PauseRegistryClient<'a>) {
+    let contract_id = Address::generate(e);
+    if wasm {
+        e.register_contract_wasm(&contract_id, pause_registry_contract::WASM);
+    } else {
+        e.register_contract(&contract_id, PauseRegistryContract {});
+    }
+    (
+        contract_id.clone(),
+        PauseRegistryClient::new(e, &contract_id),
+    )
+}
@@ -17,6 +17,9 @@ pub fn default_reserve_metadata() -> ReserveConfig {
         r_two: 0_5000000,
         r_three: 1_5000000,
         reactivity: 0_0000020, // 2e-6
+        fee_on_transfer: false,
+        min_first_supply: 0,
+        locked_b_tokens: 0,
         index: 0,
     }
 }
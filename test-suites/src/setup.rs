@@ -67,16 +67,18 @@ pub fn create_fixture_with_data<'a>(wasm: bool) -> TestFixture<'a> {
     ];
     pool_fixture.pool.set_emissions_config(&reserve_emissions);
 
-    // deposit into backstop, add to reward zone
+    // deposit into backstop, add to reward zone once the pool has been above the backstop
+    // threshold for a week
+    fixture.backstop.update_tkn_val();
     fixture
         .backstop
         .deposit(&frodo, &pool_fixture.pool.address, &(50_000 * SCALAR_7));
-    fixture.backstop.update_tkn_val();
+    pool_fixture.pool.set_status(&3);
+    pool_fixture.pool.update_status();
+    fixture.jump(7 * 24 * 60 * 60);
     fixture
         .backstop
         .add_reward(&pool_fixture.pool.address, &Address::generate(&fixture.env));
-    pool_fixture.pool.set_status(&3);
-    pool_fixture.pool.update_status();
 
     // enable emissions
     fixture.emitter.distribute();
@@ -190,13 +192,14 @@ mod tests {
         );
 
         // validate emissions are turned on
+        // emissions are skipped for the minute before the first borrow, when no dTokens existed
         let (emis_config, emis_data) = fixture.read_reserve_emissions(0, TokenIndex::STABLE, 0);
         assert_eq!(
             emis_data.last_time,
-            fixture.env.ledger().timestamp() - 60 * 61
+            fixture.env.ledger().timestamp() - 60 * 60
         );
         assert_eq!(emis_data.index, 0);
-        assert_eq!(0_180_0000, emis_config.eps);
+        assert_eq!(0_360_0000, emis_config.eps);
         assert_eq!(
             fixture.env.ledger().timestamp() + 7 * 24 * 60 * 60 - 60 * 61,
             emis_config.expiration
@@ -247,13 +250,14 @@ mod tests {
         );
 
         // validate emissions are turned on
+        // emissions are skipped for the minute before the first borrow, when no dTokens existed
         let (emis_config, emis_data) = fixture.read_reserve_emissions(0, TokenIndex::STABLE, 0);
         assert_eq!(
             emis_data.last_time,
-            fixture.env.ledger().timestamp() - 60 * 61
+            fixture.env.ledger().timestamp() - 60 * 60
         );
         assert_eq!(emis_data.index, 0);
-        assert_eq!(0_180_0000, emis_config.eps);
+        assert_eq!(0_360_0000, emis_config.eps);
         assert_eq!(
             fixture.env.ledger().timestamp() + 7 * 24 * 60 * 60 - 60 * 61,
             emis_config.expiration
@@ -0,0 +1,150 @@
+use std::{rc::Rc, vec::Vec as StdVec};
+
+use pool::{PoolClient, PoolDataKey};
+use soroban_sdk::{
+    xdr::{
+        ContractDataDurability, ContractExecutable, Hash, LedgerEntryData, LedgerKey,
+        LedgerKeyContractData, ScAddress, ScVal,
+    },
+    Address, Env, IntoVal, Symbol, TryFromVal, Val, Vec,
+};
+
+/// Load a ledger snapshot, like one exported from mainnet, into a new test Env
+///
+/// ### Arguments
+/// * `path` - The path to the snapshot JSON file
+pub fn load_snapshot(path: &str) -> Env {
+    let e = Env::from_ledger_snapshot_file(path);
+    e.budget().reset_unlimited();
+    e
+}
+
+/// Replace the WASM a deployed contract executes, keeping its instance storage and TTL. This
+/// mimics an in-place upgrade without relying on the contract exposing an upgrade function.
+///
+/// ### Arguments
+/// * `contract` - The address of the contract to upgrade
+/// * `wasm` - The candidate WASM to install
+pub fn upgrade_contract_wasm(e: &Env, contract: &Address, wasm: &[u8]) {
+    let wasm_hash = e.deployer().upload_contract_wasm(wasm);
+    let key = Rc::new(LedgerKey::ContractData(LedgerKeyContractData {
+        contract: ScAddress::try_from(contract).unwrap(),
+        key: ScVal::LedgerKeyContractInstance,
+        durability: ContractDataDurability::Persistent,
+    }));
+    let budget = e.host().budget_cloned();
+    e.host()
+        .with_mut_storage(|storage| {
+            let mut entry = (*storage.get(&key, &budget)?).clone();
+            let live_until = storage
+                .map
+                .get::<Rc<LedgerKey>>(&key, &budget)?
+                .and_then(|stored| stored.as_ref().and_then(|(_, live_until)| *live_until));
+            if let LedgerEntryData::ContractData(ref mut data) = entry.data {
+                if let ScVal::ContractInstance(ref mut instance) = data.val {
+                    instance.executable = ContractExecutable::Wasm(Hash(wasm_hash.to_array()));
+                }
+            }
+            storage.put(&key, &Rc::new(entry), live_until, &budget)
+        })
+        .unwrap();
+}
+
+/// The values read from a pool that an upgrade must not change, labeled by where they were read
+/// from. Only functions and storage entries the deployed WASM already has are read, so the view
+/// can be loaded before the upgrade.
+pub struct PoolView {
+    pub entries: StdVec<(String, ScVal)>,
+}
+
+impl PoolView {
+    /// Load the view of a pool through the WASM it currently executes and its raw storage
+    ///
+    /// ### Arguments
+    /// * `pool` - The address of the pool
+    /// * `users` - The users whose positions are included
+    pub fn load(e: &Env, pool: &Address, users: &[Address]) -> PoolView {
+        let pool_client = PoolClient::new(e, pool);
+        let mut entries = StdVec::new();
+        let mut push = |label: String, val: Val| {
+            entries.push((label, ScVal::try_from_val(e, &val).unwrap()));
+        };
+
+        push(
+            String::from("storage Config"),
+            read_instance(e, pool, "Config"),
+        );
+        push(
+            String::from("storage ResList"),
+            read_persistent(e, pool, Symbol::new(e, "ResList")),
+        );
+        for asset in read_reserve_list(e, pool).iter() {
+            push(
+                format!("storage ResConfig {:?}", asset),
+                read_persistent(e, pool, PoolDataKey::ResConfig(asset.clone())),
+            );
+            push(
+                format!("storage ResData {:?}", asset),
+                read_persistent(e, pool, PoolDataKey::ResData(asset.clone())),
+            );
+        }
+        for user in users {
+            push(
+                format!("positions {:?}", user),
+                pool_client.get_positions(user).into_val(e),
+            );
+        }
+        PoolView { entries }
+    }
+}
+
+/// Replay a candidate WASM against a pool's existing state and verify every user's positions and
+/// the pool's stored config and reserves are unchanged by the upgrade
+///
+/// ### Arguments
+/// * `pool` - The address of the pool to upgrade
+/// * `users` - The users whose positions are checked
+/// * `wasm` - The candidate pool WASM
+///
+/// ### Panics
+/// If any value read from the pool differs between the current and candidate WASM
+pub fn replay_pool_upgrade(e: &Env, pool: &Address, users: &[Address], wasm: &[u8]) {
+    let before = PoolView::load(e, pool, users);
+    upgrade_contract_wasm(e, pool, wasm);
+    let after = PoolView::load(e, pool, users);
+
+    assert_eq!(before.entries.len(), after.entries.len());
+    for ((label, prev), (_, next)) in before.entries.iter().zip(after.entries.iter()) {
+        assert_eq!(prev, next, "upgrade changed {}", label);
+    }
+}
+
+/// Read an entry from a pool's instance storage, or void if it is not set
+fn read_instance(e: &Env, pool: &Address, key: &str) -> Val {
+    e.as_contract(pool, || {
+        e.storage()
+            .instance()
+            .get::<Symbol, Val>(&Symbol::new(e, key))
+            .unwrap_or(().into_val(e))
+    })
+}
+
+/// Read an entry from a pool's persistent storage, or void if it is not set
+fn read_persistent<K: IntoVal<Env, Val>>(e: &Env, pool: &Address, key: K) -> Val {
+    e.as_contract(pool, || {
+        e.storage()
+            .persistent()
+            .get::<K, Val>(&key)
+            .unwrap_or(().into_val(e))
+    })
+}
+
+/// Read the list of reserves from a pool's storage
+fn read_reserve_list(e: &Env, pool: &Address) -> Vec<Address> {
+    e.as_contract(pool, || {
+        e.storage()
+            .persistent()
+            .get(&Symbol::new(e, "ResList"))
+            .unwrap_or(Vec::new(e))
+    })
+}
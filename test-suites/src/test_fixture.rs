@@ -5,11 +5,13 @@ use crate::backstop::create_backstop;
 use crate::emitter::create_emitter;
 use crate::liquidity_pool::{create_lp_pool, LPClient};
 use crate::oracle::create_mock_oracle;
+use crate::pause_registry::create_pause_registry;
 use crate::pool::POOL_WASM;
 use crate::pool_factory::create_pool_factory;
 use crate::token::{create_stellar_token, create_token};
 use backstop::BackstopClient;
 use emitter::EmitterClient;
+use pause_registry::PauseRegistryClient;
 use pool::{
     PoolClient, PoolConfig, PoolDataKey, ReserveConfig, ReserveData, ReserveEmissionsConfig,
     ReserveEmissionsData,
@@ -17,8 +19,14 @@ use pool::{
 use pool_factory::{PoolFactoryClient, PoolInitMeta};
 use sep_40_oracle::testutils::{Asset, MockPriceOracleClient};
 use sep_41_token::testutils::MockTokenClient;
+use soroban_env_host::storage::{AccessType, Footprint};
 use soroban_sdk::testutils::{Address as _, BytesN as _, Ledger, LedgerInfo};
-use soroban_sdk::{vec as svec, Address, BytesN, Env, Map, String, Symbol};
+use soroban_sdk::xdr::{
+    ContractDataDurability, LedgerKey, LedgerKeyContractData, ScAddress, ScVal,
+};
+use soroban_sdk::{
+    vec as svec, Address, BytesN, Env, IntoVal, Map, String, Symbol, TryFromVal, Val,
+};
 
 pub const SCALAR_7: i128 = 1_000_0000;
 pub const SCALAR_9: i128 = 1_000_000_000;
@@ -52,6 +60,7 @@ pub struct TestFixture<'a> {
     pub emitter: EmitterClient<'a>,
     pub backstop: BackstopClient<'a>,
     pub pool_factory: PoolFactoryClient<'a>,
+    pub pause_registry: PauseRegistryClient<'a>,
     pub oracle: MockPriceOracleClient<'a>,
     pub lp: LPClient<'a>,
     pub pools: Vec<PoolFixture<'a>>,
@@ -93,13 +102,17 @@ impl TestFixture<'_> {
         let (backstop_id, backstop_client) = create_backstop(&e, wasm);
         let (emitter_id, emitter_client) = create_emitter(&e, wasm);
         let (pool_factory_id, _) = create_pool_factory(&e, wasm);
+        let (pause_registry_id, pause_registry_client) = create_pause_registry(&e, wasm);
 
         // deploy external contracts
         let (lp, lp_client) = create_lp_pool(&e, &bombadil, &blnd_id, &usdc_id);
 
+        // initialize pause registry
+        pause_registry_client.initialize(&bombadil);
+
         // initialize emitter
         blnd_client.set_admin(&emitter_id);
-        emitter_client.initialize(&blnd_id, &backstop_id, &lp);
+        emitter_client.initialize(&blnd_id, &backstop_id, &lp, &pause_registry_id);
 
         // initialize backstop
         backstop_client.initialize(
@@ -108,6 +121,7 @@ impl TestFixture<'_> {
             &usdc_id,
             &blnd_id,
             &pool_factory_id,
+            &pause_registry_id,
             &svec![
                 &e,
                 (bombadil.clone(), 10_000_000 * SCALAR_7),
@@ -121,6 +135,7 @@ impl TestFixture<'_> {
             backstop: backstop_id.clone(),
             pool_hash: pool_hash.clone(),
             blnd_id: blnd_id.clone(),
+            pause_registry: pause_registry_id.clone(),
         };
         let pool_factory_client = PoolFactoryClient::new(&e, &pool_factory_id);
         pool_factory_client.initialize(&pool_init_meta);
@@ -158,6 +173,7 @@ impl TestFixture<'_> {
             emitter: emitter_client,
             backstop: backstop_client,
             pool_factory: pool_factory_client,
+            pause_registry: pause_registry_client,
             oracle: mock_oracle_client,
             lp: lp_client,
             pools: vec![],
@@ -181,6 +197,7 @@ impl TestFixture<'_> {
             &self.oracle.address,
             &backstop_take_rate,
             &max_positions,
+            &(21 * 24 * 60 * 60),
         );
         self.pools.push(PoolFixture {
             pool: PoolClient::new(&self.env, &pool_id),
@@ -233,11 +250,14 @@ impl TestFixture<'_> {
         let token = &self.tokens[asset_index];
         self.env.as_contract(&pool_fixture.pool.address, || {
             let token_id = &token.address;
-            self.env
-                .storage()
-                .persistent()
-                .get(&PoolDataKey::ResConfig(token_id.clone()))
-                .unwrap()
+            ReserveConfig::unpack(
+                &self
+                    .env
+                    .storage()
+                    .persistent()
+                    .get(&PoolDataKey::ResConfig(token_id.clone()))
+                    .unwrap(),
+            )
         })
     }
 
@@ -246,11 +266,14 @@ impl TestFixture<'_> {
         let token = &self.tokens[asset_index];
         self.env.as_contract(&pool_fixture.pool.address, || {
             let token_id = &token.address;
-            self.env
-                .storage()
-                .persistent()
-                .get(&PoolDataKey::ResData(token_id.clone()))
-                .unwrap()
+            ReserveData::unpack(
+                &self
+                    .env
+                    .storage()
+                    .persistent()
+                    .get(&PoolDataKey::ResData(token_id.clone()))
+                    .unwrap(),
+            )
         })
     }
 
@@ -308,4 +331,110 @@ impl TestFixture<'_> {
             max_entry_ttl: 9999999,
         });
     }
+
+    /********** Budget Helpers ***********/
+
+    /// Reset the budget and recorded ledger footprint so the resources used by the next
+    /// invocations can be read with `read_invocation_cost`
+    pub fn reset_invocation_cost(&self) {
+        self.env.budget().reset_unlimited();
+        self.env
+            .host()
+            .with_mut_storage(|storage| {
+                storage.footprint = Footprint::default();
+                Ok(())
+            })
+            .unwrap();
+    }
+
+    /// Read the resources used since the last call to `reset_invocation_cost`
+    pub fn read_invocation_cost(&self) -> InvocationCost {
+        let budget = self.env.host().budget_cloned();
+        let (read_entries, write_entries) = self
+            .env
+            .host()
+            .with_mut_storage(|storage| {
+                let mut reads = 0;
+                let mut writes = 0;
+                for (_, access) in storage.footprint.0.iter(&budget)? {
+                    match access {
+                        AccessType::ReadOnly => reads += 1,
+                        AccessType::ReadWrite => writes += 1,
+                    }
+                }
+                Ok((reads, writes))
+            })
+            .unwrap();
+        InvocationCost {
+            cpu_insns: self.env.budget().cpu_instruction_cost(),
+            mem_bytes: self.env.budget().memory_bytes_cost(),
+            read_entries,
+            write_entries,
+        }
+    }
+    /// Check if a reserve's data was written since the last call to `reset_invocation_cost`
+    pub fn is_reserve_data_written(&self, pool_index: usize, asset_index: TokenIndex) -> bool {
+        let pool_fixture = &self.pools[pool_index];
+        let token = &self.tokens[asset_index];
+        let data_key: Val = PoolDataKey::ResData(token.address.clone()).into_val(&self.env);
+        let key = LedgerKey::ContractData(LedgerKeyContractData {
+            contract: ScAddress::try_from(&pool_fixture.pool.address).unwrap(),
+            key: ScVal::try_from_val(&self.env, &data_key).unwrap(),
+            durability: ContractDataDurability::Persistent,
+        });
+        let budget = self.env.host().budget_cloned();
+        self.env
+            .host()
+            .with_mut_storage(|storage| {
+                for (entry_key, access) in storage.footprint.0.iter(&budget)? {
+                    if **entry_key == key {
+                        return Ok(*access == AccessType::ReadWrite);
+                    }
+                }
+                Ok(false)
+            })
+            .unwrap()
+    }
+}
+
+/// The resources used by a set of contract invocations
+#[derive(Clone, Debug)]
+pub struct InvocationCost {
+    pub cpu_insns: u64,
+    pub mem_bytes: u64,
+    pub read_entries: u32,  // ledger entries only read
+    pub write_entries: u32, // ledger entries written (and possibly read)
+}
+
+/// Assert that the resources used by a flow have not regressed from its baseline. CPU and memory
+/// may exceed the baseline by `tolerance_pct` percent, and ledger entry counts may not exceed it.
+pub fn assert_cost_within(
+    flow: &str,
+    cost: &InvocationCost,
+    baseline: &InvocationCost,
+    tolerance_pct: u64,
+) {
+    let max_cpu = baseline.cpu_insns * (100 + tolerance_pct) / 100;
+    let max_mem = baseline.mem_bytes * (100 + tolerance_pct) / 100;
+    assert!(
+        cost.cpu_insns <= max_cpu,
+        "{} cpu regressed: {:?} vs baseline {:?}",
+        flow,
+        cost,
+        baseline
+    );
+    assert!(
+        cost.mem_bytes <= max_mem,
+        "{} memory regressed: {:?} vs baseline {:?}",
+        flow,
+        cost,
+        baseline
+    );
+    assert!(
+        cost.read_entries <= baseline.read_entries && cost.write_entries <= baseline.write_entries,
+        "{} ledger entries regressed: {:?} vs baseline {:?}",
+        flow,
+        cost,
+        baseline
+    );
 }
@@ -7,6 +7,7 @@ use soroban_sdk::{
 };
 use test_suites::{
     assertions::assert_approx_eq_abs,
+    backstop::BACKSTOP_WASM,
     create_fixture_with_data,
     test_fixture::{TokenIndex, SCALAR_7},
 };
@@ -29,6 +30,7 @@ fn test_backstop() {
         &Address::generate(&fixture.env),
         &Address::generate(&fixture.env),
         &Address::generate(&fixture.env),
+        &Address::generate(&fixture.env),
         &vec![&fixture.env],
     );
     assert!(result.is_err());
@@ -458,12 +460,12 @@ fn test_backstop() {
             }
         )
     );
-    //6d23hr at full
+    //6d23hr at full, at 2 tokens per second since the fixture waits a week for the reward zone
     //7 days at none
     //7 at 6250 + (60 * 60 * 24 * 16 + 1)
     let emission_share_1 = 0_7000000.fixed_mul_floor(0_2000000, SCALAR_7).unwrap();
     let emission_share_2 = 0_7000000.fixed_mul_floor(0_1111111, SCALAR_7).unwrap();
-    let emitted_blnd_1 = ((7 * 24 * 60 * 60 - 61 * 60) * SCALAR_7)
+    let emitted_blnd_1 = ((7 * 24 * 60 * 60 - 61 * 60) * 2 * SCALAR_7)
         .fixed_mul_floor(emission_share_1, SCALAR_7)
         .unwrap();
     let emitted_blnd_2 = ((14 * 24 * 60 * 60 + 1) * SCALAR_7 + 2096022)
@@ -487,9 +489,141 @@ fn test_backstop() {
             &fixture.env,
             (
                 fixture.backstop.address.clone(),
-                (Symbol::new(&fixture.env, "claim"), sam.clone()).into_val(&fixture.env),
+                (Symbol::new(&fixture.env, "claim"), sam.clone(), sam.clone())
+                    .into_val(&fixture.env),
                 (emitted_blnd_1 + emitted_blnd_2).into_val(&fixture.env),
             )
         ]
     );
 }
+
+/// Test that the backstop's coverage view values the pool's liabilities in USDC, the same units
+/// as the backstop's value
+#[test]
+fn test_backstop_coverage() {
+    let fixture = create_fixture_with_data(false);
+    let pool = &fixture.pools[0].pool;
+
+    // USDC trades below the oracle's base asset
+    fixture.oracle.set_price_stable(&vec![
+        &fixture.env,
+        2000_0000000, // eth
+        0_8000000,    // usdc
+        0_1000000,    // xlm
+        1_0000000,    // stable
+    ]);
+    let usdc = &fixture.tokens[TokenIndex::USDC].address;
+    let liabilities = pool.get_total_liabilities_in(usdc);
+    assert!(liabilities > 0);
+    assert_eq!(
+        liabilities,
+        pool.get_total_liabilities()
+            .fixed_div_floor(0_8000000, SCALAR_7)
+            .unwrap()
+    );
+
+    let pool_data = fixture.backstop.pool_data(&pool.address);
+    let expected_coverage = (pool_data.usdc * 5)
+        .fixed_div_floor(liabilities, SCALAR_7)
+        .unwrap();
+    assert_eq!(
+        fixture.backstop.get_pool_coverage(&pool.address),
+        expected_coverage
+    );
+}
+
+/// Test the backstop can be upgraded in place after the timelock without changing any deposits
+#[test]
+fn test_backstop_upgrade() {
+    let fixture = create_fixture_with_data(true);
+    let frodo = fixture.users[0].clone();
+    let pool = fixture.pools[0].pool.address.clone();
+    assert_eq!(fixture.backstop.storage_version(), 1);
+
+    let wasm_hash = fixture.env.deployer().upload_contract_wasm(BACKSTOP_WASM);
+    fixture.backstop.queue_upgrade(&wasm_hash);
+    assert_eq!(
+        fixture.env.auths()[0],
+        (
+            fixture.bombadil.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    fixture.backstop.address.clone(),
+                    Symbol::new(&fixture.env, "queue_upgrade"),
+                    vec![&fixture.env, wasm_hash.to_val()]
+                )),
+                sub_invocations: std::vec![]
+            }
+        )
+    );
+    let queued_upgrade = fixture.backstop.get_queued_upgrade().unwrap();
+    assert_eq!(queued_upgrade.wasm_hash, wasm_hash);
+    assert_eq!(
+        queued_upgrade.unlock_time,
+        fixture.env.ledger().timestamp() + 211 * 24 * 60 * 60
+    );
+
+    // the upgrade is timelocked for longer than the longest deposit lock plus the maximum Q4W
+    // lock time
+    fixture.jump(210 * 24 * 60 * 60);
+    let result = fixture.backstop.try_upgrade();
+    assert!(result.is_err());
+
+    fixture.jump(24 * 60 * 60);
+    let pre_pool_data = fixture.backstop.pool_data(&pool);
+    let pre_user_balance = fixture.backstop.user_balance(&pool, &frodo);
+    fixture.backstop.upgrade();
+    assert!(fixture.backstop.get_queued_upgrade().is_none());
+
+    let pool_data = fixture.backstop.pool_data(&pool);
+    assert_eq!(pool_data.tokens, pre_pool_data.tokens);
+    assert_eq!(pool_data.q4w_pct, pre_pool_data.q4w_pct);
+    let user_balance = fixture.backstop.user_balance(&pool, &frodo);
+    assert_eq!(user_balance.shares, pre_user_balance.shares);
+    assert_eq!(user_balance.q4w.len(), pre_user_balance.q4w.len());
+
+    // the stored state is already at the upgraded layout version
+    let result = fixture.backstop.try_migrate();
+    assert!(result.is_err());
+    assert_eq!(fixture.backstop.storage_version(), 1);
+}
+
+/// Test a locked deposit cannot be queued for withdrawal until its lock time has passed
+#[test]
+fn test_backstop_deposit_lock() {
+    let fixture = create_fixture_with_data(true);
+    let frodo = fixture.users[0].clone();
+    let pool = fixture.pools[0].pool.address.clone();
+
+    let pre_shares = fixture.backstop.user_balance(&pool, &frodo).shares;
+    let amount = 1_000 * SCALAR_7;
+    let shares = fixture
+        .backstop
+        .deposit_with_lock(&frodo, &pool, &amount, &1);
+    let lock = fixture.backstop.deposit_lock(&pool, &frodo).unwrap();
+    assert_eq!(lock.shares, shares);
+    assert_eq!(lock.bonus, shares / 10);
+    assert_eq!(
+        lock.unlock_time,
+        fixture.env.ledger().timestamp() + 30 * 24 * 60 * 60
+    );
+    assert_eq!(
+        fixture.backstop.user_balance(&pool, &frodo).shares,
+        pre_shares + shares
+    );
+
+    // only the shares deposited without a lock can be queued
+    let result = fixture
+        .backstop
+        .try_queue_withdrawal(&frodo, &pool, &(pre_shares + 1));
+    assert!(result.is_err());
+
+    fixture.jump(30 * 24 * 60 * 60);
+    fixture
+        .backstop
+        .queue_withdrawal(&frodo, &pool, &(pre_shares + shares));
+    assert!(fixture.backstop.deposit_lock(&pool, &frodo).is_none());
+    let user_balance = fixture.backstop.user_balance(&pool, &frodo);
+    assert_eq!(user_balance.shares, 0);
+    assert_eq!(user_balance.q4w.last().unwrap().amount, pre_shares + shares);
+}
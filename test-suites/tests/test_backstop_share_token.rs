@@ -0,0 +1,161 @@
+#![cfg(test)]
+use backstop_share_token::{BackstopShareTokenClient, BackstopShareTokenContract};
+use sep_41_token::TokenClient;
+use soroban_sdk::{
+    testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation},
+    vec, Address, Error, IntoVal, String, Symbol,
+};
+use test_suites::{create_fixture_with_data, test_fixture::SCALAR_7};
+
+#[test]
+fn test_backstop_share_token() {
+    let fixture = create_fixture_with_data(false);
+    let frodo = fixture.users.first().unwrap();
+    let pool_fixture = &fixture.pools[0];
+    let pool = &pool_fixture.pool.address;
+
+    let share_token_address = fixture
+        .env
+        .register_contract(None, BackstopShareTokenContract {});
+    let share_token = BackstopShareTokenClient::new(&fixture.env, &share_token_address);
+    let share_token_sep41 = TokenClient::new(&fixture.env, &share_token_address);
+    let name = String::from_str(&fixture.env, "Blend Backstop Share");
+    let symbol = String::from_str(&fixture.env, "BBS");
+    share_token.initialize(&fixture.backstop.address, pool, &name, &symbol);
+
+    // verify initialize can't be run twice
+    let result = share_token.try_initialize(&fixture.backstop.address, pool, &name, &symbol);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(3))));
+
+    assert_eq!(share_token.backstop(), fixture.backstop.address);
+    assert_eq!(share_token.pool(), pool.clone());
+    assert_eq!(share_token_sep41.name(), name);
+    assert_eq!(share_token_sep41.symbol(), symbol);
+    assert_eq!(share_token_sep41.decimals(), 7);
+
+    // frodo wraps some of their backstop shares
+    let frodo_shares = fixture.backstop.user_balance(pool, frodo).shares;
+    let amount = 1_000 * SCALAR_7;
+    share_token.wrap(frodo, &amount);
+    assert_eq!(
+        fixture.env.auths()[0],
+        (
+            frodo.clone(),
+            AuthorizedInvocation {
+                function: AuthorizedFunction::Contract((
+                    share_token_address.clone(),
+                    Symbol::new(&fixture.env, "wrap"),
+                    vec![&fixture.env, frodo.to_val(), amount.into_val(&fixture.env)]
+                )),
+                sub_invocations: std::vec![AuthorizedInvocation {
+                    function: AuthorizedFunction::Contract((
+                        fixture.backstop.address.clone(),
+                        Symbol::new(&fixture.env, "transfer_shares"),
+                        vec![
+                            &fixture.env,
+                            frodo.to_val(),
+                            pool.to_val(),
+                            share_token_address.to_val(),
+                            amount.into_val(&fixture.env)
+                        ]
+                    )),
+                    sub_invocations: std::vec![]
+                }]
+            }
+        )
+    );
+    assert_eq!(share_token_sep41.balance(frodo), amount);
+    assert_eq!(
+        fixture.backstop.user_balance(pool, frodo).shares,
+        frodo_shares - amount
+    );
+    assert_eq!(
+        fixture
+            .backstop
+            .user_balance(pool, &share_token_address)
+            .shares,
+        amount
+    );
+
+    // share tokens are transferable
+    let samwise = Address::generate(&fixture.env);
+    share_token_sep41.transfer(frodo, &samwise, &(400 * SCALAR_7));
+    assert_eq!(share_token_sep41.balance(frodo), 600 * SCALAR_7);
+    assert_eq!(share_token_sep41.balance(&samwise), 400 * SCALAR_7);
+
+    let result = share_token_sep41.try_transfer(&samwise, frodo, &(400 * SCALAR_7 + 1));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(10))));
+
+    // share tokens can be spent with an allowance
+    let merry = Address::generate(&fixture.env);
+    let live_until = fixture.env.ledger().sequence() + 100;
+    share_token_sep41.approve(frodo, &merry, &(100 * SCALAR_7), &live_until);
+    assert_eq!(share_token_sep41.allowance(frodo, &merry), 100 * SCALAR_7);
+    share_token_sep41.transfer_from(&merry, frodo, &merry, &(60 * SCALAR_7));
+    assert_eq!(share_token_sep41.allowance(frodo, &merry), 40 * SCALAR_7);
+    assert_eq!(share_token_sep41.balance(&merry), 60 * SCALAR_7);
+
+    let result = share_token_sep41.try_transfer_from(&merry, frodo, &merry, &(40 * SCALAR_7 + 1));
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(9))));
+
+    let result = share_token_sep41.try_approve(
+        frodo,
+        &merry,
+        &(100 * SCALAR_7),
+        &(fixture.env.ledger().sequence() - 1),
+    );
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1500))));
+
+    // samwise unwraps their share tokens into backstop shares and can queue them for withdrawal
+    share_token.unwrap(&samwise, &(400 * SCALAR_7));
+    assert_eq!(share_token_sep41.balance(&samwise), 0);
+    assert_eq!(
+        fixture.backstop.user_balance(pool, &samwise).shares,
+        400 * SCALAR_7
+    );
+    assert_eq!(
+        fixture
+            .backstop
+            .user_balance(pool, &share_token_address)
+            .shares,
+        600 * SCALAR_7
+    );
+    fixture
+        .backstop
+        .queue_withdrawal(&samwise, pool, &(400 * SCALAR_7));
+
+    // queued shares cannot be wrapped
+    let result = share_token.try_wrap(&samwise, &1);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(10))));
+    let sam_balance = fixture.backstop.user_balance(pool, &samwise);
+    assert_eq!(sam_balance.shares, 0);
+    assert_eq!(sam_balance.q4w.get_unchecked(0).amount, 400 * SCALAR_7);
+
+    let result = share_token.try_unwrap(&samwise, &1);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(10))));
+
+    // emissions on wrapped shares are distributed to holders by their share token balances
+    fixture.jump(60 * 60 * 24);
+    let frodo_shares = fixture.backstop.user_balance(pool, frodo).shares;
+    let frodo_claimed = share_token.claim(frodo);
+    let merry_claimed = share_token.claim(&merry);
+    let sam_claimed = share_token.claim(&samwise);
+    assert!(frodo_claimed > 0);
+    assert_eq!(frodo_claimed / 9, merry_claimed);
+    assert_eq!(sam_claimed, 0);
+    assert_eq!(
+        fixture.backstop.user_balance(pool, frodo).shares,
+        frodo_shares + frodo_claimed
+    );
+    assert_eq!(
+        fixture.backstop.user_balance(pool, &merry).shares,
+        merry_claimed
+    );
+    assert_eq!(share_token.claim(frodo), 0);
+    let held = fixture
+        .backstop
+        .user_balance(pool, &share_token_address)
+        .shares;
+    assert!(held >= 600 * SCALAR_7);
+    assert!(held - 600 * SCALAR_7 <= 2);
+}
@@ -0,0 +1,182 @@
+#![cfg(test)]
+
+use pool::{Request, RequestType};
+use soroban_sdk::{testutils::Address as _, vec, Address};
+use test_suites::{
+    create_fixture_with_data,
+    test_fixture::{assert_cost_within, InvocationCost, TokenIndex, SCALAR_7},
+};
+
+/// The percent CPU and memory usage can grow over a flow's baseline before the test fails
+const TOLERANCE_PCT: u64 = 5;
+
+/// Test the resources used by core pool flows have not regressed. Baselines should be updated
+/// whenever a change intentionally alters a flow's resource usage.
+#[test]
+fn test_budget_regression() {
+    let fixture = create_fixture_with_data(true);
+    let pool_fixture = &fixture.pools[0];
+    let frodo = fixture.users[0].clone();
+    let xlm = &fixture.tokens[TokenIndex::XLM];
+    let stable = &fixture.tokens[TokenIndex::STABLE];
+
+    let samwise = Address::generate(&fixture.env);
+    xlm.mint(&samwise, &(20_000 * SCALAR_7));
+
+    // supply
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: xlm.address.clone(),
+            amount: 20_000 * SCALAR_7,
+        },
+    ];
+    fixture.reset_invocation_cost();
+    pool_fixture
+        .pool
+        .submit(&samwise, &samwise, &samwise, &requests);
+    assert_cost_within(
+        "supply",
+        &fixture.read_invocation_cost(),
+        &InvocationCost {
+            cpu_insns: 58_333_125,
+            mem_bytes: 8_992_936,
+            read_entries: 10,
+            write_entries: 9,
+        },
+        TOLERANCE_PCT,
+    );
+
+    // borrow
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::Borrow as u32,
+            address: stable.address.clone(),
+            amount: 500 * 10i128.pow(6),
+        },
+    ];
+    fixture.reset_invocation_cost();
+    pool_fixture
+        .pool
+        .submit(&samwise, &samwise, &samwise, &requests);
+    assert_cost_within(
+        "borrow",
+        &fixture.read_invocation_cost(),
+        &InvocationCost {
+            cpu_insns: 75_628_021,
+            mem_bytes: 15_565_658,
+            read_entries: 26,
+            write_entries: 11,
+        },
+        TOLERANCE_PCT,
+    );
+
+    // fill user liquidation
+    fixture.oracle.set_price_stable(&vec![
+        &fixture.env,
+        2000_0000000, // eth
+        1_0000000,    // usdc
+        0_0300000,    // xlm
+        1_0000000,    // stable
+    ]);
+    pool_fixture.pool.new_liquidation_auction(&samwise, &85);
+    fixture.jump_with_sequence(200 * 5);
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::FillUserLiquidationAuction as u32,
+            address: samwise.clone(),
+            amount: 100,
+        },
+    ];
+    fixture.reset_invocation_cost();
+    pool_fixture.pool.submit(&frodo, &frodo, &frodo, &requests);
+    assert_cost_within(
+        "fill user liquidation",
+        &fixture.read_invocation_cost(),
+        &InvocationCost {
+            cpu_insns: 83_308_360,
+            mem_bytes: 16_144_458,
+            read_entries: 36,
+            write_entries: 21,
+        },
+        TOLERANCE_PCT,
+    );
+
+    // claim
+    fixture.jump(24 * 60 * 60);
+    fixture.reset_invocation_cost();
+    pool_fixture
+        .pool
+        .claim(&frodo, &vec![&fixture.env, 0, 3], &frodo);
+    assert_cost_within(
+        "claim",
+        &fixture.read_invocation_cost(),
+        &InvocationCost {
+            cpu_insns: 57_041_735,
+            mem_bytes: 8_860_656,
+            read_entries: 14,
+            write_entries: 8,
+        },
+        TOLERANCE_PCT,
+    );
+
+    // repay
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::Repay as u32,
+            address: stable.address.clone(),
+            amount: 100 * 10i128.pow(6),
+        },
+    ];
+    fixture.reset_invocation_cost();
+    pool_fixture.pool.submit(&frodo, &frodo, &frodo, &requests);
+    assert_cost_within(
+        "repay",
+        &fixture.read_invocation_cost(),
+        &InvocationCost {
+            cpu_insns: 67_891_640,
+            mem_bytes: 13_592_162,
+            read_entries: 13,
+            write_entries: 8,
+        },
+        TOLERANCE_PCT,
+    );
+
+    // withdraw collateral without liabilities
+    let merry = Address::generate(&fixture.env);
+    xlm.mint(&merry, &(1_000 * SCALAR_7));
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: xlm.address.clone(),
+            amount: 1_000 * SCALAR_7,
+        },
+    ];
+    pool_fixture.pool.submit(&merry, &merry, &merry, &requests);
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::WithdrawCollateral as u32,
+            address: xlm.address.clone(),
+            amount: 1_000 * SCALAR_7,
+        },
+    ];
+    fixture.reset_invocation_cost();
+    pool_fixture.pool.submit(&merry, &merry, &merry, &requests);
+    assert_cost_within(
+        "withdraw collateral without liabilities",
+        &fixture.read_invocation_cost(),
+        &InvocationCost {
+            cpu_insns: 56_677_832,
+            mem_bytes: 8_789_228,
+            read_entries: 14,
+            write_entries: 6,
+        },
+        TOLERANCE_PCT,
+    );
+}
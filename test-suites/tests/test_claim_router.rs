@@ -0,0 +1,83 @@
+#![cfg(test)]
+use claim_router::{ClaimResult, ClaimRouterClient, ClaimRouterContract, PoolClaim};
+use soroban_sdk::{testutils::Address as _, vec, Address, Error};
+use test_suites::{create_fixture_with_data, test_fixture::TokenIndex};
+
+#[test]
+fn test_claim_router() {
+    let fixture = create_fixture_with_data(false);
+    let frodo = fixture.users.first().unwrap();
+    let pool_fixture = &fixture.pools[0];
+    let blnd = &fixture.tokens[TokenIndex::BLND];
+
+    let router_address = fixture.env.register_contract(None, ClaimRouterContract {});
+    let router = ClaimRouterClient::new(&fixture.env, &router_address);
+    router.initialize(&fixture.backstop.address, &blnd.address);
+
+    // verify initialize can't be run twice
+    let result = router.try_initialize(&fixture.backstop.address, &blnd.address);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(3))));
+
+    // nothing to claim
+    let samwise = Address::generate(&fixture.env);
+    let result = router.try_claim(frodo, &vec![&fixture.env], &vec![&fixture.env], &samwise);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1400))));
+
+    // claim frodo's setup emissions (1h1m passes during setup)
+    // - 60 * 60 * 2 * .3 = 2160 BLND from the pool claims
+    // - 60 * 61 * 2 * .7 = 5124 BLND from the backstop claim
+    let backstop_blnd_balance = blnd.balance(&fixture.backstop.address);
+    let result = router.claim(
+        frodo,
+        &vec![
+            &fixture.env,
+            PoolClaim {
+                pool: pool_fixture.pool.address.clone(),
+                reserve_token_ids: vec![&fixture.env, 0],
+            },
+            PoolClaim {
+                pool: pool_fixture.pool.address.clone(),
+                reserve_token_ids: vec![&fixture.env, 3],
+            },
+        ],
+        &vec![&fixture.env, pool_fixture.pool.address.clone()],
+        &samwise,
+    );
+    assert_eq!(
+        result,
+        ClaimResult {
+            pool_emissions: 2160_0000000,
+            backstop_emissions: 5124_0000000,
+        }
+    );
+    assert_eq!(blnd.balance(&samwise), 2160_0000000);
+    assert_eq!(blnd.balance(&router_address), 0);
+    assert_eq!(
+        blnd.balance(&fixture.backstop.address),
+        backstop_blnd_balance - 2160_0000000 - 5124_0000000
+    );
+    // the backstop emissions are deposited on behalf of the recipient
+    assert!(
+        fixture
+            .backstop
+            .user_balance(&pool_fixture.pool.address, &samwise)
+            .shares
+            > 0
+    );
+
+    // the emissions can't be claimed twice
+    let result = router.claim(
+        frodo,
+        &vec![
+            &fixture.env,
+            PoolClaim {
+                pool: pool_fixture.pool.address.clone(),
+                reserve_token_ids: vec![&fixture.env, 0, 3],
+            },
+        ],
+        &vec![&fixture.env],
+        &samwise,
+    );
+    assert_eq!(result, ClaimResult::default());
+    assert_eq!(blnd.balance(&samwise), 2160_0000000);
+}
@@ -1,10 +1,12 @@
 #![cfg(test)]
 
 use emitter::Swap;
+use mock_backstop::{MockBackstop, MockBackstopClient};
 use pool::{Request, RequestType, ReserveEmissionMetadata};
 use soroban_sdk::{
+    map as smap,
     testutils::{Address as _, Events},
-    vec as svec, Address, IntoVal, String, Symbol, Vec as SVec,
+    vec as svec, Address, Error, IntoVal, String, Symbol, Vec as SVec,
 };
 use test_suites::{
     create_fixture_with_data,
@@ -108,12 +110,12 @@ fn test_emitter_no_reward_zone() {
     );
     assert!(result == 0);
 
+    fixture.backstop.update_tkn_val();
     fixture.backstop.deposit(
         &fixture.users[0],
         &pool_fixture.pool.address,
         &(50_000 * SCALAR_7),
     );
-    fixture.backstop.update_tkn_val();
     pool_fixture.pool.set_status(&3);
     pool_fixture.pool.update_status();
 
@@ -127,6 +129,8 @@ fn test_emitter_no_reward_zone() {
     ];
     pool_fixture.pool.submit(&frodo, &frodo, &frodo, &requests);
 
+    // the pool must be above the backstop threshold for a week to enter the reward zone
+    fixture.jump(7 * 24 * 60 * 60);
     fixture
         .backstop
         .add_reward(&pool_fixture.pool.address, &Address::generate(&fixture.env));
@@ -171,6 +175,7 @@ fn test_emitter() {
         &Address::generate(&fixture.env),
         &Address::generate(&fixture.env),
         &Address::generate(&fixture.env),
+        &Address::generate(&fixture.env),
     );
     assert!(result.is_err());
     assert_eq!(
@@ -211,9 +216,12 @@ fn test_emitter() {
         ]
     );
 
-    // Mint enough tokens to a new backstop address to perform a swap, then queue the swap
+    // Deploy a new backstop for the LP token and mint enough tokens to it to perform a swap,
+    // then queue the swap
     let old_backstop_balance = bstop_token.balance(&fixture.backstop.address);
-    let new_backstop = Address::generate(&fixture.env);
+    let new_backstop = fixture.env.register_contract(None, MockBackstop {});
+    MockBackstopClient::new(&fixture.env, &new_backstop)
+        .initialize(&fixture.lp.address, &fixture.emitter.address);
     fixture.tokens[TokenIndex::BLND].mint(&new_backstop, &(600_001 * SCALAR_7));
     fixture.tokens[TokenIndex::USDC].mint(&new_backstop, &(20_501 * SCALAR_7));
     fixture.lp.join_pool(
@@ -230,6 +238,13 @@ fn test_emitter() {
         fixture.emitter.get_backstop(),
         fixture.backstop.address.clone()
     );
+    let queued_swap = fixture.emitter.get_queued_swap().unwrap();
+    assert_eq!(queued_swap.new_backstop, new_backstop);
+    assert_eq!(queued_swap.unlock_time, swap_unlock_time);
+    assert_eq!(
+        fixture.emitter.get_swap_unlock_time(),
+        Some(swap_unlock_time)
+    );
     let event = svec![&fixture.env, fixture.env.events().all().last_unchecked()];
     assert_eq!(
         event,
@@ -259,6 +274,8 @@ fn test_emitter() {
         fixture.emitter.get_backstop(),
         fixture.backstop.address.clone()
     );
+    assert!(fixture.emitter.get_queued_swap().is_none());
+    assert_eq!(fixture.emitter.get_swap_unlock_time(), None);
     let event = svec![&fixture.env, fixture.env.events().all().last_unchecked()];
     assert_eq!(
         event,
@@ -303,4 +320,113 @@ fn test_emitter() {
         ]
     );
     assert_eq!(fixture.emitter.get_backstop(), new_backstop.clone());
+    assert!(fixture.emitter.get_queued_swap().is_none());
+}
+
+/// Test that emissions streamed directly to a pool are timelocked, distributed to the pool's
+/// reserves without passing through the backstop, and claimable by pool users.
+#[test]
+fn test_emitter_direct_emissions() {
+    let fixture = create_fixture_with_data(true);
+    let frodo = fixture.users[0].clone();
+    let pool_fixture = &fixture.pools[0];
+    let blnd_token = &fixture.tokens[TokenIndex::BLND];
+    let pool_address = pool_fixture.pool.address.clone();
+
+    // queue a direct stream of 20% of the base emission rate to the pool
+    let config = smap![&fixture.env, (pool_address.clone(), 0_2000000)];
+    fixture.emitter.queue_direct_emissions(&config);
+    assert_eq!(fixture.env.auths()[0].0, fixture.backstop.address);
+    let queue = fixture.emitter.get_queued_direct_emissions().unwrap();
+    assert_eq!(queue.config, config);
+    assert_eq!(
+        queue.unlock_time,
+        fixture.env.ledger().timestamp() + 31 * 24 * 60 * 60
+    );
+
+    // the config is timelocked
+    let result = fixture.emitter.try_set_direct_emissions();
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1110))));
+    fixture.jump(31 * 24 * 60 * 60);
+    fixture.emitter.set_direct_emissions();
+    assert_eq!(fixture.emitter.get_direct_emissions(), config);
+    assert!(fixture.emitter.get_queued_direct_emissions().is_none());
+    fixture.emitter.distribute();
+
+    // the pool gulps the direct emissions into its reserves
+    fixture.jump(24 * 60 * 60);
+    let pre_pool_blnd = blnd_token.balance(&pool_address);
+    let pre_backstop_blnd = blnd_token.balance(&fixture.backstop.address);
+    let result = pool_fixture.pool.gulp_direct_emissions();
+    assert_eq!(result, 24 * 60 * 60 * 0_2000000);
+    assert_eq!(blnd_token.balance(&pool_address), pre_pool_blnd + result);
+    assert_eq!(
+        blnd_token.balance(&fixture.backstop.address),
+        pre_backstop_blnd
+    );
+
+    // the direct emissions are deducted from the backstop's emissions
+    let distributed = fixture.emitter.distribute();
+    assert_eq!(distributed, 24 * 60 * 60 * 0_8000000);
+    let (emis_config, _) = fixture.read_reserve_emissions(0, TokenIndex::STABLE, 0);
+    assert_eq!(
+        emis_config.expiration,
+        fixture.env.ledger().timestamp() + 7 * 24 * 60 * 60
+    );
+
+    // the pool cannot gulp again until more tokens are streamed
+    let gulp_result = pool_fixture.pool.try_gulp_direct_emissions();
+    assert!(gulp_result.is_err());
+
+    // direct emissions are claimed out of the pool's balance first
+    fixture.jump(24 * 60 * 60);
+    let claimed = pool_fixture
+        .pool
+        .claim(&frodo, &svec![&fixture.env, 0, 3], &frodo);
+    assert!(claimed > 0);
+    assert_eq!(
+        blnd_token.balance(&pool_address),
+        pre_pool_blnd + result - claimed.min(result)
+    );
+}
+
+/// Test the guardian can pause emissions for a limited time, and the emissions accrued while
+/// paused are distributed once the pause ends
+#[test]
+fn test_emitter_emission_pause() {
+    let fixture = create_fixture_with_data(false);
+    let guardian = Address::generate(&fixture.env);
+
+    fixture.emitter.set_guardian(&guardian);
+    assert_eq!(fixture.emitter.get_guardian(), Some(guardian.clone()));
+
+    let pool_fixture = &fixture.pools[0];
+    let result = fixture.emitter.distribute();
+    fixture.backstop.gulp_emissions();
+    pool_fixture.pool.gulp_emissions();
+    fixture.emitter.pause_emissions();
+    assert_eq!(fixture.env.auths()[0].0, guardian);
+    let pause = fixture.emitter.get_emission_pause().unwrap();
+    assert_eq!(pause.end_time - pause.start_time, 7 * 24 * 60 * 60);
+    assert!(result > 0);
+
+    fixture.jump(7 * 24 * 60 * 60 - 1);
+    let result = fixture.emitter.try_distribute();
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1114))));
+
+    // the pause ends on its own, the paused week is never distributed, and the guardian can't
+    // immediately pause again
+    fixture.jump(1);
+    let result = fixture.emitter.distribute();
+    assert_eq!(result, 0);
+    fixture.jump(60 * 60);
+    let result = fixture.emitter.distribute();
+    assert_eq!(result, 60 * 60 * SCALAR_7);
+    let result = fixture.emitter.try_pause_emissions();
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1115))));
+
+    // the backstop only distributes the BLND that was minted, so the paused week is skipped
+    fixture.backstop.gulp_emissions();
+    let result = pool_fixture.pool.gulp_emissions();
+    assert_eq!(result, 60 * 60 * 300_0000);
 }
@@ -1,6 +1,9 @@
 #![cfg(test)]
 use cast::i128;
-use pool::{PoolDataKey, Positions, Request, RequestType, ReserveConfig, ReserveData};
+use pool::{
+    PackedReserveConfig, PackedReserveData, PoolDataKey, Positions, Request, RequestType,
+    ReserveConfig, ReserveData,
+};
 use soroban_fixed_point_math::FixedPoint;
 use soroban_sdk::{
     testutils::{Address as AddressTestTrait, Events},
@@ -69,19 +72,19 @@ fn test_liquidations() {
             .env
             .storage()
             .persistent()
-            .set::<PoolDataKey, ReserveConfig>(&key, &usdc_config);
+            .set::<PoolDataKey, PackedReserveConfig>(&key, &usdc_config.pack(&fixture.env));
         let key = PoolDataKey::ResConfig(fixture.tokens[TokenIndex::XLM].address.clone());
         fixture
             .env
             .storage()
             .persistent()
-            .set::<PoolDataKey, ReserveConfig>(&key, &xlm_config);
+            .set::<PoolDataKey, PackedReserveConfig>(&key, &xlm_config.pack(&fixture.env));
         let key = PoolDataKey::ResConfig(fixture.tokens[TokenIndex::WETH].address.clone());
         fixture
             .env
             .storage()
             .persistent()
-            .set::<PoolDataKey, ReserveConfig>(&key, &weth_config);
+            .set::<PoolDataKey, PackedReserveConfig>(&key, &weth_config.pack(&fixture.env));
     });
 
     // Create a user
@@ -162,18 +165,18 @@ fn test_liquidations() {
     let stable_interest_lot_amount = auction_data
         .lot
         .get_unchecked(fixture.tokens[TokenIndex::STABLE].address.clone());
-    assert_approx_eq_abs(stable_interest_lot_amount, 256_746831, 5000000);
+    assert_approx_eq_abs(stable_interest_lot_amount, 266_119930, 5000000);
     let xlm_interest_lot_amount = auction_data
         .lot
         .get_unchecked(fixture.tokens[TokenIndex::XLM].address.clone());
-    assert_approx_eq_abs(xlm_interest_lot_amount, 179_5067018, 5000000);
+    assert_approx_eq_abs(xlm_interest_lot_amount, 181_1314297, 5000000);
     let weth_interest_lot_amount = auction_data
         .lot
         .get_unchecked(fixture.tokens[TokenIndex::WETH].address.clone());
-    assert_approx_eq_abs(weth_interest_lot_amount, 0_002671545, 5000);
+    assert_approx_eq_abs(weth_interest_lot_amount, 0_002678680, 5000);
     let lp_donate_bid_amount = auction_data.bid.get_unchecked(fixture.lp.address.clone());
     //NOTE: bid STABLE amount is seven decimals whereas reserve(and lot) STABLE has 6 decomals
-    assert_approx_eq_abs(lp_donate_bid_amount, 313_7415968, SCALAR_7);
+    assert_approx_eq_abs(lp_donate_bid_amount, 324_3412848, SCALAR_7);
     assert_eq!(auction_data.block, 151);
     let liq_pct = 30;
     let events = fixture.env.events().all();
@@ -222,11 +225,11 @@ fn test_liquidations() {
     let xlm_lot_amount = auction_data
         .lot
         .get_unchecked(fixture.tokens[TokenIndex::XLM].address.clone());
-    assert_approx_eq_abs(xlm_lot_amount, 40100_6654560, SCALAR_7);
+    assert_approx_eq_abs(xlm_lot_amount, 40179_5518022, SCALAR_7);
     let weth_lot_amount = auction_data
         .lot
         .get_unchecked(fixture.tokens[TokenIndex::WETH].address.clone());
-    assert_approx_eq_abs(weth_lot_amount, 4_260750195, 1000);
+    assert_approx_eq_abs(weth_lot_amount, 4_269131977, 1000);
     let events = fixture.env.events().all();
     let event = vec![&fixture.env, events.get_unchecked(events.len() - 1)];
     assert_eq!(
@@ -305,7 +308,7 @@ fn test_liquidations() {
     );
     assert_approx_eq_abs(
         frodo_positions_post_fill.liabilities.get_unchecked(0),
-        8_000 * 10i128.pow(6) + 559_285757,
+        8_000 * 10i128.pow(6) + 578_279041,
         100000,
     );
     assert_approx_eq_abs(
@@ -333,7 +336,7 @@ fn test_liquidations() {
         10i128.pow(9),
     );
     let events = fixture.env.events().all();
-    let event = vec![&fixture.env, events.get_unchecked(events.len() - 16)];
+    let event = vec![&fixture.env, events.get_unchecked(events.len() - 15)];
     let fill_pct_1: i128 = 25;
     let fill_pct_2: i128 = 100;
     let fill_pct_3: i128 = 99;
@@ -368,7 +371,7 @@ fn test_liquidations() {
             )
         ]
     );
-    let event = vec![&fixture.env, events.get_unchecked(events.len() - 15)];
+    let event = vec![&fixture.env, events.get_unchecked(events.len() - 14)];
     assert_eq!(
         event,
         vec![
@@ -385,7 +388,7 @@ fn test_liquidations() {
             )
         ]
     );
-    let event = vec![&fixture.env, events.get_unchecked(events.len() - 9)];
+    let event = vec![&fixture.env, events.get_unchecked(events.len() - 11)];
     assert_eq!(
         event,
         vec![
@@ -402,7 +405,7 @@ fn test_liquidations() {
             )
         ]
     );
-    let event = vec![&fixture.env, events.get_unchecked(events.len() - 3)];
+    let event = vec![&fixture.env, events.get_unchecked(events.len() - 8)];
     assert_eq!(
         event,
         vec![
@@ -450,7 +453,7 @@ fn test_liquidations() {
     let xlm_lot_amount = auction_data_2
         .lot
         .get_unchecked(fixture.tokens[TokenIndex::XLM].address.clone());
-    assert_approx_eq_abs(xlm_lot_amount, 139947_2453890, SCALAR_7);
+    assert_approx_eq_abs(xlm_lot_amount, 139907_8022156, SCALAR_7);
     let weth_lot_amount = auction_data_2
         .lot
         .get_unchecked(fixture.tokens[TokenIndex::WETH].address.clone());
@@ -505,12 +508,12 @@ fn test_liquidations() {
         SCALAR_7,
     );
     assert_approx_eq_abs(
-        frodo_positions_post_fill.liabilities.get(0).unwrap() + usdc_filled - 9147_499950,
+        frodo_positions_post_fill.liabilities.get(0).unwrap() + usdc_filled - 9125_258402,
         new_frodo_positions.liabilities.get(0).unwrap(),
         10i128.pow(6),
     );
     assert_approx_eq_abs(
-        frodo_positions_post_fill.liabilities.get(1).unwrap() + xlm_filled - 22438_6298700,
+        frodo_positions_post_fill.liabilities.get(1).unwrap() + xlm_filled - 22436_4259979,
         new_frodo_positions.liabilities.get(1).unwrap(),
         SCALAR_7,
     );
@@ -546,6 +549,15 @@ fn test_liquidations() {
         samwise_positions_pre_bd.liabilities.get(1).unwrap(),
         backstop_positions.liabilities.get(1).unwrap()
     );
+    let bad_debt_log = pool_fixture.pool.get_bad_debt_log(&0, &10);
+    assert_eq!(bad_debt_log.len(), 2);
+    for record in bad_debt_log.iter() {
+        assert_eq!(record.user, samwise);
+    }
+    assert_eq!(
+        bad_debt_log.get_unchecked(0).d_tokens,
+        samwise_positions_pre_bd.liabilities.get(0).unwrap()
+    );
 
     // create a bad debt auction
     let auction_type: u32 = 1;
@@ -570,7 +582,7 @@ fn test_liquidations() {
         bad_debt_auction_data
             .lot
             .get_unchecked(fixture.lp.address.clone()),
-        7171_0435309, // lp_token value is $1.25 each
+        7185_4566739, // lp_token value is $1.25 each
         SCALAR_7,
     );
     let events = fixture.env.events().all();
@@ -628,12 +640,12 @@ fn test_liquidations() {
     );
     assert_approx_eq_abs(
         fixture.lp.balance(&frodo),
-        frodo_bstop_pre_fill + 717_1043530,
+        frodo_bstop_pre_fill + 718_5456673,
         SCALAR_7,
     );
     assert_approx_eq_abs(
         fixture.lp.balance(&fixture.backstop.address),
-        backstop_bstop_pre_fill - 717_1043531,
+        backstop_bstop_pre_fill - 718_5456674,
         SCALAR_7,
     );
     let new_auction = pool_fixture
@@ -668,12 +680,12 @@ fn test_liquidations() {
         bad_debt_auction_data
             .lot
             .get_unchecked(fixture.lp.address.clone())
-            - 1434_2087060,
+            - 1437_0913347,
         SCALAR_7,
     );
     assert_eq!(new_auction.block, bad_debt_auction_data.block);
     let events = fixture.env.events().all();
-    let event = vec![&fixture.env, events.get_unchecked(events.len() - 1)];
+    let event = vec![&fixture.env, events.get_unchecked(events.len() - 3)];
     let fill_pct: i128 = 20;
     let event_data: Vec<Val> = vec![
         &fixture.env,
@@ -727,16 +739,16 @@ fn test_liquidations() {
     );
     assert_approx_eq_abs(
         fixture.lp.balance(&frodo),
-        frodo_bstop_pre_fill + 4302_6261190,
+        frodo_bstop_pre_fill + 4311_2740044,
         SCALAR_7,
     );
     assert_approx_eq_abs(
         fixture.lp.balance(&fixture.backstop.address),
-        backstop_bstop_pre_fill - 4302_6261190,
+        backstop_bstop_pre_fill - 4311_2740044,
         SCALAR_7,
     );
     let events = fixture.env.events().all();
-    let event = vec![&fixture.env, events.get_unchecked(events.len() - 1)];
+    let event = vec![&fixture.env, events.get_unchecked(events.len() - 3)];
     let fill_pct: i128 = 100;
     let event_data: Vec<Val> = vec![
         &fixture.env,
@@ -773,7 +785,7 @@ fn test_liquidations() {
         .withdraw(&frodo, &pool_fixture.pool.address, &original_deposit);
     assert_approx_eq_abs(
         fixture.lp.balance(&frodo) - pre_withdraw_frodo_bstp,
-        original_deposit - 717_1043530 - 4302_6261190 + 313_7415968,
+        original_deposit - 718_5456673 - 4311_2740044 + 324_3412848,
         SCALAR_7,
     );
     fixture
@@ -869,7 +881,7 @@ fn test_liquidations() {
 
     let events = fixture.env.events().all();
     let event = vec![&fixture.env, events.get_unchecked(events.len() - 1)];
-    let bad_debt: i128 = 92903008;
+    let bad_debt: i128 = 92675880;
     assert_eq!(
         event,
         vec![
@@ -914,15 +926,17 @@ fn test_liquidations() {
         assert_eq!(positions.liabilities.get(0).unwrap(), bad_debt);
     });
     // check d_supply
-    let d_supply = 19104605847;
+    let d_supply = 19145568846;
     fixture.env.as_contract(&pool_fixture.pool.address, || {
         let key = PoolDataKey::ResData(fixture.tokens[TokenIndex::STABLE].address.clone());
-        let data = fixture
-            .env
-            .storage()
-            .persistent()
-            .get::<PoolDataKey, ReserveData>(&key)
-            .unwrap();
+        let data = ReserveData::unpack(
+            &fixture
+                .env
+                .storage()
+                .persistent()
+                .get::<PoolDataKey, PackedReserveData>(&key)
+                .unwrap(),
+        );
         assert_eq!(data.d_supply, d_supply);
     });
     let bad_debt_fill_request = vec![
@@ -951,16 +965,18 @@ fn test_liquidations() {
             .unwrap();
         assert_eq!(positions.liabilities.len(), 0);
         let key = PoolDataKey::ResData(fixture.tokens[TokenIndex::STABLE].address.clone());
-        let data = fixture
-            .env
-            .storage()
-            .persistent()
-            .get::<PoolDataKey, ReserveData>(&key)
-            .unwrap();
+        let data = ReserveData::unpack(
+            &fixture
+                .env
+                .storage()
+                .persistent()
+                .get::<PoolDataKey, PackedReserveData>(&key)
+                .unwrap(),
+        );
         assert_eq!(data.d_supply, d_supply - bad_debt);
     });
     let events = fixture.env.events().all();
-    let event = vec![&fixture.env, events.get_unchecked(events.len() - 2)];
+    let event = vec![&fixture.env, events.get_unchecked(events.len() - 4)];
     assert_eq!(
         event,
         vec![
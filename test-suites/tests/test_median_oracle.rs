@@ -0,0 +1,160 @@
+#![cfg(test)]
+use median_oracle::{MedianOracleClient, MedianOracleContract};
+use sep_40_oracle::{testutils::Asset as MockAsset, Asset, PriceFeedClient};
+use soroban_sdk::{
+    testutils::{Address as _, Ledger, LedgerInfo},
+    vec, Address, Env, Error, Symbol,
+};
+use test_suites::oracle::create_mock_oracle;
+
+#[test]
+fn test_median_oracle() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.budget().reset_unlimited();
+    e.ledger().set(LedgerInfo {
+        timestamp: 1_000_000,
+        protocol_version: 20,
+        sequence_number: 100,
+        network_id: Default::default(),
+        base_reserve: 10,
+        min_temp_entry_ttl: 10,
+        min_persistent_entry_ttl: 10,
+        max_entry_ttl: 3110400,
+    });
+
+    let bombadil = Address::generate(&e);
+    let xlm = Address::generate(&e);
+    let usdc = Address::generate(&e);
+    let mut oracles = vec![&e];
+    let mut oracle_clients = std::vec![];
+    for _ in 0..3 {
+        let (oracle, oracle_client) = create_mock_oracle(&e);
+        oracle_client.set_data(
+            &bombadil,
+            &MockAsset::Other(Symbol::new(&e, "USD")),
+            &vec![
+                &e,
+                MockAsset::Stellar(xlm.clone()),
+                MockAsset::Stellar(usdc.clone()),
+            ],
+            &7,
+            &300,
+        );
+        oracles.push_back(oracle);
+        oracle_clients.push(oracle_client);
+    }
+    oracle_clients[0].set_price_stable(&vec![&e, 0_1000000, 1_0000000]);
+    oracle_clients[1].set_price_stable(&vec![&e, 0_1100000, 1_0010000]);
+    oracle_clients[2].set_price_stable(&vec![&e, 0_0900000, 0_9000000]);
+
+    let median_address = e.register_contract(None, MedianOracleContract {});
+    let median = MedianOracleClient::new(&e, &median_address);
+    let price_feed = PriceFeedClient::new(&e, &median_address);
+
+    // the minimum number of sources must be in [1, oracles]
+    let result = median.try_initialize(&oracles, &600, &4);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1600))));
+    let result = median.try_initialize(&oracles, &600, &0);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1600))));
+
+    median.initialize(&oracles, &600, &2);
+
+    // verify initialize can't be run twice
+    let result = median.try_initialize(&oracles, &600, &2);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(3))));
+
+    assert_eq!(median.oracles(), oracles);
+    assert_eq!(median.max_age(), 600);
+    assert_eq!(median.min_sources(), 2);
+    assert_eq!(price_feed.decimals(), 7);
+    assert_eq!(price_feed.resolution(), 300);
+    assert_eq!(price_feed.assets().len(), 2);
+
+    // the median of the three prices is served
+    let price = price_feed.lastprice(&Asset::Stellar(xlm.clone())).unwrap();
+    assert_eq!(price.price, 0_1000000);
+    assert_eq!(price.timestamp, 1_000_000);
+    let price = price_feed.lastprice(&Asset::Stellar(usdc.clone())).unwrap();
+    assert_eq!(price.price, 1_0000000);
+
+    // an outlier can't move the price
+    oracle_clients[2].set_price_stable(&vec![&e, 10_0000000, 0_9000000]);
+    let price = price_feed.lastprice(&Asset::Stellar(xlm.clone())).unwrap();
+    assert_eq!(price.price, 0_1100000);
+
+    // stale prices are ignored, and the median of two prices is their average
+    oracle_clients[2].set_price(&vec![&e, 0_0900000, 0_9000000], &(1_000_000 - 601));
+    let price = price_feed.lastprice(&Asset::Stellar(xlm.clone())).unwrap();
+    assert_eq!(price.price, 0_1050000);
+    assert_eq!(price.timestamp, 1_000_000);
+
+    // no price is served when fewer than the minimum number of sources are fresh
+    e.ledger().set(LedgerInfo {
+        timestamp: 1_000_000 + 601,
+        ..e.ledger().get()
+    });
+    oracle_clients[1].set_price(&vec![&e, 0_1100000, 1_0010000], &1_000_000);
+    let price = price_feed.lastprice(&Asset::Stellar(xlm.clone()));
+    assert!(price.is_none());
+}
+
+#[test]
+fn test_median_oracle_requires_matching_decimals() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let bombadil = Address::generate(&e);
+    let xlm = Address::generate(&e);
+    let (oracle_0, oracle_client_0) = create_mock_oracle(&e);
+    oracle_client_0.set_data(
+        &bombadil,
+        &MockAsset::Other(Symbol::new(&e, "USD")),
+        &vec![&e, MockAsset::Stellar(xlm.clone())],
+        &7,
+        &300,
+    );
+    let (oracle_1, oracle_client_1) = create_mock_oracle(&e);
+    oracle_client_1.set_data(
+        &bombadil,
+        &MockAsset::Other(Symbol::new(&e, "USD")),
+        &vec![&e, MockAsset::Stellar(xlm.clone())],
+        &14,
+        &300,
+    );
+
+    let median_address = e.register_contract(None, MedianOracleContract {});
+    let median = MedianOracleClient::new(&e, &median_address);
+    let result = median.try_initialize(&vec![&e, oracle_0, oracle_1], &600, &1);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1600))));
+}
+
+#[test]
+fn test_median_oracle_requires_matching_base() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let bombadil = Address::generate(&e);
+    let xlm = Address::generate(&e);
+    let (oracle_0, oracle_client_0) = create_mock_oracle(&e);
+    oracle_client_0.set_data(
+        &bombadil,
+        &MockAsset::Other(Symbol::new(&e, "USD")),
+        &vec![&e, MockAsset::Stellar(xlm.clone())],
+        &7,
+        &300,
+    );
+    let (oracle_1, oracle_client_1) = create_mock_oracle(&e);
+    oracle_client_1.set_data(
+        &bombadil,
+        &MockAsset::Other(Symbol::new(&e, "EUR")),
+        &vec![&e, MockAsset::Stellar(xlm.clone())],
+        &7,
+        &300,
+    );
+
+    let median_address = e.register_contract(None, MedianOracleContract {});
+    let median = MedianOracleClient::new(&e, &median_address);
+    let result = median.try_initialize(&vec![&e, oracle_0, oracle_1], &600, &1);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1600))));
+}
@@ -0,0 +1,62 @@
+#![cfg(test)]
+
+use pool::{Request, RequestType};
+use soroban_sdk::{testutils::Address as _, vec, Address, Error};
+use test_suites::{
+    create_fixture_with_data,
+    test_fixture::{TokenIndex, SCALAR_7},
+};
+
+/// Test that the pause registry halts state-mutating actions across the pool, backstop,
+/// and emitter with a single transaction, and that unpausing restores them.
+#[test]
+fn test_pause_registry() {
+    let fixture = create_fixture_with_data(true);
+    let frodo = fixture.users[0].clone();
+    let pool_fixture = &fixture.pools[0];
+    let samwise = Address::generate(&fixture.env);
+
+    fixture.tokens[TokenIndex::STABLE].mint(&samwise, &(1_000 * 10i128.pow(6)));
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: fixture.tokens[TokenIndex::STABLE].address.clone(),
+            amount: 100 * 10i128.pow(6),
+        },
+    ];
+
+    fixture.pause_registry.set_paused(&true);
+    assert!(fixture.pause_registry.is_paused());
+
+    let result = pool_fixture
+        .pool
+        .try_submit(&samwise, &samwise, &samwise, &requests);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1220))));
+    let result = pool_fixture
+        .pool
+        .try_claim(&frodo, &vec![&fixture.env, 0], &frodo);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1220))));
+
+    let result = fixture
+        .backstop
+        .try_deposit(&frodo, &pool_fixture.pool.address, &SCALAR_7);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1008))));
+
+    let result = fixture.emitter.try_distribute();
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1106))));
+
+    // views and admin functions are still available
+    assert_eq!(pool_fixture.pool.update_status(), 1);
+
+    fixture.pause_registry.set_paused(&false);
+    assert!(!fixture.pause_registry.is_paused());
+
+    pool_fixture
+        .pool
+        .submit(&samwise, &samwise, &samwise, &requests);
+    fixture
+        .backstop
+        .deposit(&frodo, &pool_fixture.pool.address, &SCALAR_7);
+    fixture.emitter.distribute();
+}
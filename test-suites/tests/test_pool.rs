@@ -1,15 +1,17 @@
 #![cfg(test)]
 
-use pool::{Request, RequestType, ReserveEmissionMetadata};
+use pool::{PoolError, Request, RequestType, ReserveEmissionMetadata, TopUpRule};
 use soroban_fixed_point_math::FixedPoint;
 use soroban_sdk::{
+    map,
     testutils::{Address as _, AuthorizedFunction, AuthorizedInvocation, Events},
-    vec, Address, IntoVal, String, Symbol, Val,
+    vec, Address, Error, IntoVal, Map, String, Symbol, Val,
 };
 use test_suites::{
     assertions::assert_approx_eq_abs,
     create_fixture_with_data,
-    pool::default_reserve_metadata,
+    pool::{default_reserve_metadata, POOL_WASM},
+    snapshot::PoolView,
     test_fixture::{TokenIndex, SCALAR_7, SCALAR_9},
 };
 
@@ -97,7 +99,7 @@ fn test_pool_user() {
         10,
     );
     let events = fixture.env.events().all();
-    let event = vec![&fixture.env, events.get_unchecked(events.len() - 2)];
+    let event = vec![&fixture.env, events.get_unchecked(events.len() - 3)];
     let event_data: soroban_sdk::Vec<Val> = vec![
         &fixture.env,
         amount.into_val(&fixture.env),
@@ -105,6 +107,7 @@ fn test_pool_user() {
             .supply
             .get_unchecked(weth_pool_index)
             .into_val(&fixture.env),
+        1_u64.into_val(&fixture.env),
     ];
     assert_eq!(
         event,
@@ -122,6 +125,25 @@ fn test_pool_user() {
             )
         ]
     );
+    let event = vec![&fixture.env, events.last_unchecked()];
+    let empty_transfers: Map<Address, i128> = map![&fixture.env];
+    assert_eq!(
+        event,
+        vec![
+            &fixture.env,
+            (
+                pool_fixture.pool.address.clone(),
+                (Symbol::new(&fixture.env, "submit_transfers"), sam.clone()).into_val(&fixture.env),
+                (
+                    sam.clone(),
+                    sam.clone(),
+                    map![&fixture.env, (weth.address.clone(), amount)],
+                    empty_transfers,
+                )
+                    .into_val(&fixture.env)
+            )
+        ]
+    );
 
     // Skip 1 day
     fixture.jump(24 * 60 * 60);
@@ -173,11 +195,12 @@ fn test_pool_user() {
     );
     assert_ne!(sam_weth_btoken_balance, 0); // some interest was earned
     let events = fixture.env.events().all();
-    let event = vec![&fixture.env, events.get_unchecked(events.len() - 2)];
+    let event = vec![&fixture.env, events.get_unchecked(events.len() - 3)];
     let event_data: soroban_sdk::Vec<Val> = vec![
         &fixture.env,
         amount.into_val(&fixture.env),
         pool_tokens.into_val(&fixture.env),
+        2_u64.into_val(&fixture.env),
     ];
     assert_eq!(
         event,
@@ -253,7 +276,7 @@ fn test_pool_user() {
         10,
     );
     let events = fixture.env.events().all();
-    let event = vec![&fixture.env, events.get_unchecked(events.len() - 2)];
+    let event = vec![&fixture.env, events.get_unchecked(events.len() - 3)];
     let event_data: soroban_sdk::Vec<Val> = vec![
         &fixture.env,
         amount.into_val(&fixture.env),
@@ -261,6 +284,7 @@ fn test_pool_user() {
             .collateral
             .get_unchecked(xlm_pool_index)
             .into_val(&fixture.env),
+        3_u64.into_val(&fixture.env),
     ];
     assert_eq!(
         event,
@@ -328,7 +352,13 @@ fn test_pool_user() {
         10,
     );
     let events = fixture.env.events().all();
-    let event = vec![&fixture.env, events.get_unchecked(events.len() - 2)];
+    let health_event = events.get_unchecked(events.len() - 3);
+    assert_eq!(health_event.0, pool_fixture.pool.address.clone());
+    assert_eq!(
+        health_event.1,
+        (Symbol::new(&fixture.env, "position_health"), sam.clone()).into_val(&fixture.env)
+    );
+    let event = vec![&fixture.env, events.get_unchecked(events.len() - 4)];
     let event_data: soroban_sdk::Vec<Val> = vec![
         &fixture.env,
         amount.into_val(&fixture.env),
@@ -336,6 +366,7 @@ fn test_pool_user() {
             .liabilities
             .get_unchecked(weth_pool_index)
             .into_val(&fixture.env),
+        4_u64.into_val(&fixture.env),
     ];
     assert_eq!(
         event,
@@ -425,11 +456,12 @@ fn test_pool_user() {
     assert_eq!(result.collateral.len(), 0);
     let events = fixture.env.events().all();
     // @dev: three transfer events follow the pool events, 1 pool event follows
-    let event = vec![&fixture.env, events.get_unchecked(events.len() - 5)];
+    let event = vec![&fixture.env, events.get_unchecked(events.len() - 6)];
     let event_data: soroban_sdk::Vec<Val> = vec![
         &fixture.env,
         est_xlm.into_val(&fixture.env),
         sam_xlm_btoken_balance.into_val(&fixture.env),
+        5_u64.into_val(&fixture.env),
     ];
     assert_eq!(
         event,
@@ -462,11 +494,12 @@ fn test_pool_user() {
     );
     assert_eq!(result.liabilities.len(), 0);
     // @dev: three transfer events follow the pool events
-    let event = vec![&fixture.env, events.get_unchecked(events.len() - 4)];
+    let event = vec![&fixture.env, events.get_unchecked(events.len() - 5)];
     let event_data: soroban_sdk::Vec<Val> = vec![
         &fixture.env,
         est_weth.into_val(&fixture.env),
         sam_weth_dtoken_balance.into_val(&fixture.env),
+        6_u64.into_val(&fixture.env),
     ];
     assert_eq!(
         event,
@@ -510,7 +543,7 @@ fn test_pool_user() {
             }
         )
     );
-    assert_eq!(result, 2940_3113155); // ~ 4.99k / (100k + 4.99k) * 0.12 (xlm eps) * 5d23hr59m in seconds
+    assert_eq!(result, 5880_6224080); // ~ 4.99k / (100k + 4.99k) * 0.24 (xlm eps) * 5d23hr59m in seconds
     assert_eq!(blnd.balance(&sam), sam_blnd_balance + result);
     let event = vec![&fixture.env, fixture.env.events().all().last_unchecked()];
     assert_eq!(
@@ -548,6 +581,7 @@ fn test_pool_config() {
         &4,
         &Address::generate(&fixture.env),
         &Address::generate(&fixture.env),
+        &Address::generate(&fixture.env),
     );
     assert!(result.is_err());
 
@@ -837,7 +871,13 @@ fn test_pool_config() {
         &(25_000 * SCALAR_7),
     );
 
+    // Update status (backstop is healthy, but the pool must remain on-ice for the minimum dwell time)
+    pool_fixture.pool.update_status();
+    let new_pool_config = fixture.read_pool_config(0);
+    assert_eq!(new_pool_config.status, 3);
+
     // Update status (backstop is healthy, so this should update to active)
+    fixture.jump(12 * 60 * 60);
     pool_fixture.pool.update_status();
     assert_eq!(fixture.env.auths().len(), 0);
     let new_pool_config = fixture.read_pool_config(0);
@@ -895,3 +935,527 @@ fn test_pool_config() {
     assert_eq!(new_emissions_config.get_unchecked(1 * 2 + 1), 0_400_0000);
     assert_eq!(new_emissions_config.get_unchecked(3 * 2 + 1), 0_200_0000);
 }
+
+/// Test the pool can be upgraded in place after the timelock without changing any user's positions
+#[test]
+fn test_pool_upgrade() {
+    let fixture = create_fixture_with_data(true);
+    let pool_fixture = &fixture.pools[0];
+    let users = [fixture.users[0].clone(), fixture.bombadil.clone()];
+
+    let wasm_hash = fixture.env.deployer().upload_contract_wasm(POOL_WASM);
+    pool_fixture.pool.queue_upgrade(&wasm_hash);
+
+    // the upgrade is timelocked
+    let result = pool_fixture.pool.try_upgrade();
+    assert!(result.is_err());
+
+    fixture.jump(31 * 24 * 60 * 60);
+    let before = PoolView::load(&fixture.env, &pool_fixture.pool.address, &users);
+    pool_fixture.pool.upgrade();
+    let event = vec![&fixture.env, fixture.env.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &fixture.env,
+            (
+                pool_fixture.pool.address.clone(),
+                (Symbol::new(&fixture.env, "upgrade"),).into_val(&fixture.env),
+                wasm_hash.into_val(&fixture.env)
+            )
+        ]
+    );
+    // state changes are blocked until the migration runs
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::Supply as u32,
+            address: fixture.tokens[TokenIndex::STABLE].address.clone(),
+            amount: 1,
+        },
+    ];
+    let result = pool_fixture
+        .pool
+        .try_submit(&users[0], &users[0], &users[0], &requests);
+    assert_eq!(
+        result.err(),
+        Some(Ok(Error::from_contract_error(
+            PoolError::MigrationPending as u32
+        )))
+    );
+    pool_fixture.pool.migrate();
+
+    let after = PoolView::load(&fixture.env, &pool_fixture.pool.address, &users);
+    assert_eq!(before.entries, after.entries);
+
+    // the migration can only be run once per upgrade
+    let result = pool_fixture.pool.try_migrate();
+    assert!(result.is_err());
+}
+
+/// Test that a fixed-term loan is repaid at its locked rate, and that repaying early pays a
+/// penalty to the backstop
+#[test]
+fn test_pool_fixed_term_loan() {
+    let fixture = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let xlm_pool_index = pool_fixture.reserves[&TokenIndex::XLM];
+    let stable_pool_index = pool_fixture.reserves[&TokenIndex::STABLE];
+    let xlm = &fixture.tokens[TokenIndex::XLM];
+    let stable = &fixture.tokens[TokenIndex::STABLE];
+
+    let sam = Address::generate(&fixture.env);
+    xlm.mint(&sam, &(10_000 * SCALAR_7));
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: xlm.address.clone(),
+            amount: 10_000 * SCALAR_7,
+        },
+    ];
+    pool_fixture.pool.submit(&sam, &sam, &sam, &requests);
+
+    // Sam borrows STABLE at a fixed rate for 30 days
+    let term = 30 * 24 * 60 * 60;
+    let amount = 100 * 10i128.pow(6);
+    let loan = pool_fixture
+        .pool
+        .borrow_fixed(&sam, &sam, &stable.address, &amount, &term);
+    assert_eq!(loan.principal, amount);
+    assert_eq!(loan.maturity, fixture.env.ledger().timestamp() + term);
+    assert_eq!(stable.balance(&sam), amount);
+    let positions = pool_fixture.pool.get_positions(&sam);
+    assert_eq!(
+        positions.liabilities.get_unchecked(stable_pool_index),
+        loan.d_tokens
+    );
+    assert_eq!(positions.collateral.len(), 1);
+    assert!(positions.collateral.contains_key(xlm_pool_index));
+    let event = vec![&fixture.env, fixture.env.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &fixture.env,
+            (
+                pool_fixture.pool.address.clone(),
+                (
+                    Symbol::new(&fixture.env, "borrow_fixed"),
+                    stable.address.clone(),
+                    sam.clone()
+                )
+                    .into_val(&fixture.env),
+                (loan.principal, loan.d_tokens, loan.rate, loan.maturity).into_val(&fixture.env),
+            )
+        ]
+    );
+
+    // a regular repay cannot repay the fixed-term loan
+    stable.mint(&sam, &(10 * 10i128.pow(6)));
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::Repay as u32,
+            address: stable.address.clone(),
+            amount: 10 * 10i128.pow(6),
+        },
+    ];
+    let result = pool_fixture.pool.try_submit(&sam, &sam, &sam, &requests);
+    assert_eq!(
+        result.err(),
+        Some(Ok(Error::from_contract_error(
+            PoolError::InvalidDTokenBurnAmount as u32
+        )))
+    );
+
+    // repay half way through the term, paying interest at the locked rate and a 1% penalty
+    fixture.jump(term / 2);
+    let pre_stable_balance = stable.balance(&sam);
+    let pre_backstop_credit = fixture
+        .read_reserve_data(0, TokenIndex::STABLE)
+        .backstop_credit;
+    let paid = pool_fixture.pool.repay_fixed(&sam, &sam, &stable.address);
+    let interest = amount
+        .fixed_mul_ceil(loan.rate, SCALAR_7)
+        .unwrap()
+        .fixed_mul_ceil((term / 2) as i128, 31536000)
+        .unwrap();
+    let penalty = amount / 100;
+    assert_eq!(paid, amount + interest + penalty);
+    assert_eq!(stable.balance(&sam), pre_stable_balance - paid);
+    let reserve_data = fixture.read_reserve_data(0, TokenIndex::STABLE);
+    assert!(reserve_data.backstop_credit >= pre_backstop_credit + penalty);
+    let positions = pool_fixture.pool.get_positions(&sam);
+    assert!(positions.liabilities.is_empty());
+    assert!(pool_fixture.pool.get_fixed_loans(&sam).is_empty());
+    let event = vec![&fixture.env, fixture.env.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &fixture.env,
+            (
+                pool_fixture.pool.address.clone(),
+                (
+                    Symbol::new(&fixture.env, "repay_fixed"),
+                    stable.address.clone(),
+                    sam.clone()
+                )
+                    .into_val(&fixture.env),
+                (amount + interest, penalty).into_val(&fixture.env),
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_pool_stable_rate_borrow() {
+    let fixture = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let stable_pool_index = pool_fixture.reserves[&TokenIndex::STABLE];
+    let xlm = &fixture.tokens[TokenIndex::XLM];
+    let stable = &fixture.tokens[TokenIndex::STABLE];
+
+    let sam = Address::generate(&fixture.env);
+    xlm.mint(&sam, &(10_000 * SCALAR_7));
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: xlm.address.clone(),
+            amount: 10_000 * SCALAR_7,
+        },
+    ];
+    pool_fixture.pool.submit(&sam, &sam, &sam, &requests);
+
+    // Sam borrows STABLE at a stable rate
+    let amount = 100 * 10i128.pow(6);
+    let debt = pool_fixture
+        .pool
+        .borrow_stable(&sam, &sam, &stable.address, &amount);
+    assert_eq!(debt.principal, amount);
+    assert_eq!(stable.balance(&sam), amount);
+    assert_eq!(
+        pool_fixture
+            .pool
+            .get_positions(&sam)
+            .liabilities
+            .get_unchecked(stable_pool_index),
+        debt.d_tokens
+    );
+    assert_eq!(
+        pool_fixture.pool.get_stable_supply(&stable.address),
+        debt.d_tokens
+    );
+    let event = vec![&fixture.env, fixture.env.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &fixture.env,
+            (
+                pool_fixture.pool.address.clone(),
+                (
+                    Symbol::new(&fixture.env, "borrow_stable"),
+                    stable.address.clone(),
+                    sam.clone()
+                )
+                    .into_val(&fixture.env),
+                (amount, debt.d_tokens, debt.rate).into_val(&fixture.env),
+            )
+        ]
+    );
+
+    // the debt's rate is within the rebalance delta of the current stable rate
+    let result = pool_fixture
+        .pool
+        .try_rebalance_stable(&sam, &stable.address);
+    assert_eq!(
+        result.err(),
+        Some(Ok(Error::from_contract_error(PoolError::BadRequest as u32)))
+    );
+
+    // a regular repay cannot repay stable rate debt
+    stable.mint(&sam, &(10 * 10i128.pow(6)));
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::Repay as u32,
+            address: stable.address.clone(),
+            amount: 10 * 10i128.pow(6),
+        },
+    ];
+    let result = pool_fixture.pool.try_submit(&sam, &sam, &sam, &requests);
+    assert_eq!(
+        result.err(),
+        Some(Ok(Error::from_contract_error(
+            PoolError::InvalidDTokenBurnAmount as u32
+        )))
+    );
+
+    // swap to variable after 30 days, accruing interest at the stable rate
+    fixture.jump(30 * 24 * 60 * 60);
+    let d_tokens = pool_fixture.pool.swap_to_variable(&sam, &stable.address);
+    assert!(pool_fixture.pool.get_stable_debts(&sam).is_empty());
+    assert_eq!(pool_fixture.pool.get_stable_supply(&stable.address), 0);
+    assert_eq!(
+        pool_fixture
+            .pool
+            .get_positions(&sam)
+            .liabilities
+            .get_unchecked(stable_pool_index),
+        d_tokens
+    );
+    let event = vec![&fixture.env, fixture.env.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &fixture.env,
+            (
+                pool_fixture.pool.address.clone(),
+                (
+                    Symbol::new(&fixture.env, "swap_to_variable"),
+                    stable.address.clone(),
+                    sam.clone()
+                )
+                    .into_val(&fixture.env),
+                d_tokens.into_val(&fixture.env),
+            )
+        ]
+    );
+
+    // the debt can now be repaid like any other liability
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::Repay as u32,
+            address: stable.address.clone(),
+            amount: amount + 10 * 10i128.pow(6),
+        },
+    ];
+    pool_fixture.pool.submit(&sam, &sam, &sam, &requests);
+    assert!(pool_fixture.pool.get_positions(&sam).liabilities.is_empty());
+}
+
+#[test]
+fn test_pool_collateral_top_up() {
+    let fixture = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let xlm = &fixture.tokens[TokenIndex::XLM];
+    let stable = &fixture.tokens[TokenIndex::STABLE];
+
+    let sam = Address::generate(&fixture.env);
+    let merry = Address::generate(&fixture.env);
+    xlm.mint(&sam, &(11_000 * SCALAR_7));
+    let requests = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: xlm.address.clone(),
+            amount: 10_000 * SCALAR_7,
+        },
+        Request {
+            request_type: RequestType::Borrow as u32,
+            address: stable.address.clone(),
+            amount: 500 * 10i128.pow(6),
+        },
+    ];
+    pool_fixture.pool.submit(&sam, &sam, &sam, &requests);
+
+    // Sam allows keepers to top up XLM collateral from a 1000 XLM allowance
+    let rule = TopUpRule {
+        trigger_hf: 1_2000000,
+        max_amount: 1_000 * SCALAR_7,
+    };
+    pool_fixture
+        .pool
+        .set_top_up(&sam, &xlm.address, &Some(rule.clone()));
+    xlm.approve(
+        &sam,
+        &pool_fixture.pool.address,
+        &(1_000 * SCALAR_7),
+        &(fixture.env.ledger().sequence() + 100_000),
+    );
+    assert_eq!(pool_fixture.pool.get_top_up(&sam, &xlm.address), Some(rule));
+
+    // the top-up cannot be executed while sam is above the trigger
+    let result = pool_fixture.pool.try_top_up(&merry, &sam, &xlm.address);
+    assert_eq!(
+        result.err(),
+        Some(Ok(Error::from_contract_error(PoolError::InvalidHf as u32)))
+    );
+
+    // XLM drops 20%, leaving sam with a health factor of ~1.14
+    fixture.oracle.set_price_stable(&vec![
+        &fixture.env,
+        2000_0000000, // eth
+        1_0000000,    // usdc
+        0_0800000,    // xlm
+        1_0000000,    // stable
+    ]);
+    let pre_sam_balance = xlm.balance(&sam);
+    let amount_supplied = pool_fixture.pool.top_up(&merry, &sam, &xlm.address);
+    let bounty = xlm.balance(&merry);
+    assert!(bounty > 0);
+    assert_eq!(
+        xlm.balance(&sam),
+        pre_sam_balance - amount_supplied - bounty
+    );
+    let simulation = pool_fixture.pool.simulate_submit(&sam, &vec![&fixture.env]);
+    assert!(simulation.health_factor >= 1_2000000);
+    assert_eq!(
+        pool_fixture
+            .pool
+            .get_top_up(&sam, &xlm.address)
+            .unwrap()
+            .max_amount,
+        1_000 * SCALAR_7 - amount_supplied - bounty
+    );
+    let event = vec![&fixture.env, fixture.env.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &fixture.env,
+            (
+                pool_fixture.pool.address.clone(),
+                (
+                    Symbol::new(&fixture.env, "top_up"),
+                    xlm.address.clone(),
+                    sam.clone()
+                )
+                    .into_val(&fixture.env),
+                (merry.clone(), amount_supplied, bounty).into_val(&fixture.env),
+            )
+        ]
+    );
+}
+
+/// Test that reserve rate checkpoints are taken as the pool is used and can be fetched
+#[test]
+fn test_pool_rate_history() {
+    let fixture = create_fixture_with_data(true);
+    let pool_fixture = &fixture.pools[0];
+    let xlm = &fixture.tokens[TokenIndex::XLM];
+    let sam = Address::generate(&fixture.env);
+    xlm.mint(&sam, &(1_000 * SCALAR_7));
+
+    let history_pre = pool_fixture.pool.get_rate_history(&xlm.address, &10);
+    assert_eq!(history_pre.len(), 1);
+
+    // a checkpoint is taken by the first update to the reserve a day later
+    fixture.jump(24 * 60 * 60);
+    let request = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::Supply as u32,
+            address: xlm.address.clone(),
+            amount: 100 * SCALAR_7,
+        },
+    ];
+    pool_fixture.pool.submit(&sam, &sam, &sam, &request);
+    pool_fixture.pool.submit(&sam, &sam, &sam, &request);
+
+    let history = pool_fixture.pool.get_rate_history(&xlm.address, &10);
+    assert_eq!(history.len(), 2);
+    let first = history.first_unchecked();
+    let last = history.last_unchecked();
+    assert_eq!(first, history_pre.first_unchecked());
+    assert_eq!(last.timestamp, fixture.env.ledger().timestamp());
+    assert!(last.b_rate > first.b_rate);
+    assert!(last.d_rate > first.d_rate);
+
+    // only the latest records are returned
+    let latest = pool_fixture.pool.get_rate_history(&xlm.address, &1);
+    assert_eq!(latest, vec![&fixture.env, last]);
+}
+
+#[test]
+fn test_pool_crank() {
+    let fixture = create_fixture_with_data(true);
+    let pool_fixture = &fixture.pools[0];
+    let xlm = &fixture.tokens[TokenIndex::XLM];
+    let stable = &fixture.tokens[TokenIndex::STABLE];
+    let xlm_b_token_id = pool_fixture.reserves[&TokenIndex::XLM] * 2 + 1;
+    let stable_d_token_id = pool_fixture.reserves[&TokenIndex::STABLE] * 2;
+
+    let xlm_b_rate = pool_fixture.pool.get_b_rate(&xlm.address);
+    let history_pre = pool_fixture.pool.get_rate_history(&xlm.address, &10);
+
+    // anyone can bring quiet reserves up to date without any user activity
+    fixture.jump(24 * 60 * 60);
+    let assets = vec![&fixture.env, xlm.address.clone(), stable.address.clone()];
+    pool_fixture.pool.crank(&assets);
+    let event = vec![&fixture.env, fixture.env.events().all().last_unchecked()];
+    assert_eq!(
+        event,
+        vec![
+            &fixture.env,
+            (
+                pool_fixture.pool.address.clone(),
+                (Symbol::new(&fixture.env, "crank"),).into_val(&fixture.env),
+                assets.into_val(&fixture.env)
+            )
+        ]
+    );
+
+    let now = fixture.env.ledger().timestamp();
+    assert!(pool_fixture.pool.get_b_rate(&xlm.address) > xlm_b_rate);
+    let history = pool_fixture.pool.get_rate_history(&xlm.address, &10);
+    assert_eq!(history.len(), history_pre.len() + 1);
+    assert_eq!(history.last_unchecked().timestamp, now);
+    let xlm_emissions = pool_fixture
+        .pool
+        .get_emission_config(&xlm_b_token_id)
+        .unwrap();
+    assert_eq!(xlm_emissions.last_time, now);
+    let stable_emissions = pool_fixture
+        .pool
+        .get_emission_config(&stable_d_token_id)
+        .unwrap();
+    assert_eq!(stable_emissions.last_time, now);
+
+    // non-reserve assets are rejected
+    let result = pool_fixture
+        .pool
+        .try_crank(&vec![&fixture.env, Address::generate(&fixture.env)]);
+    assert_eq!(
+        result.err(),
+        Some(Ok(Error::from_contract_error(PoolError::BadRequest as u32)))
+    );
+}
+
+#[test]
+fn test_pool_positions_batch() {
+    let fixture = create_fixture_with_data(true);
+    let pool_fixture = &fixture.pools[0];
+    let frodo = fixture.users[0].clone();
+    let samwise = Address::generate(&fixture.env);
+
+    // positions are returned in the order requested, and users without positions are empty
+    let batch =
+        pool_fixture
+            .pool
+            .get_positions_batch(&vec![&fixture.env, samwise.clone(), frodo.clone()]);
+    assert_eq!(batch.len(), 2);
+    let (user, positions) = batch.get_unchecked(0);
+    assert_eq!(user, samwise);
+    assert!(positions.collateral.is_empty());
+    assert!(positions.liabilities.is_empty());
+    assert!(positions.supply.is_empty());
+    let (user, positions) = batch.get_unchecked(1);
+    assert_eq!(user, frodo);
+    let frodo_positions = pool_fixture.pool.get_positions(&frodo);
+    assert_eq!(positions.collateral, frodo_positions.collateral);
+    assert_eq!(positions.liabilities, frodo_positions.liabilities);
+    assert_eq!(positions.supply, frodo_positions.supply);
+    assert!(!positions.liabilities.is_empty());
+
+    // batches are limited to 50 users
+    let mut users = vec![&fixture.env];
+    for _ in 0..51 {
+        users.push_back(Address::generate(&fixture.env));
+    }
+    let result = pool_fixture.pool.try_get_positions_batch(&users);
+    assert_eq!(
+        result.err(),
+        Some(Ok(Error::from_contract_error(PoolError::BadRequest as u32)))
+    );
+}
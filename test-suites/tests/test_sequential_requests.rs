@@ -0,0 +1,276 @@
+#![cfg(test)]
+use pool::{Request, RequestType};
+use soroban_sdk::{testutils::Address as AddressTestTrait, vec, Address, Error, Vec};
+use test_suites::{
+    assertions::assert_approx_eq_abs,
+    create_fixture_with_data,
+    test_fixture::{TestFixture, TokenIndex, SCALAR_7},
+};
+
+/// Create a user supplying $1k of STABLE and borrowing XLM up to their borrow limit
+fn create_max_borrower(fixture: &TestFixture) -> Address {
+    let pool_fixture = &fixture.pools[0];
+    let samwise = Address::generate(&fixture.env);
+    fixture.tokens[TokenIndex::STABLE].mint(&samwise, &(1000 * 10i128.pow(6)));
+
+    let setup_request: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: fixture.tokens[TokenIndex::STABLE].address.clone(),
+            amount: 1000 * 10i128.pow(6),
+        },
+        Request {
+            request_type: RequestType::Borrow as u32,
+            address: fixture.tokens[TokenIndex::XLM].address.clone(),
+            amount: 6075 * SCALAR_7,
+        },
+    ];
+    pool_fixture
+        .pool
+        .submit(&samwise, &samwise, &samwise, &setup_request);
+    samwise
+}
+
+/// Make the max borrower liquidatable and start a 50% liquidation auction against them that
+/// is fully scaled
+fn create_scaled_liquidation(fixture: &TestFixture, samwise: &Address) {
+    // simulate 20% XLM price increase ($972 liabilities, $900 limit)
+    fixture.oracle.set_price_stable(&vec![
+        &fixture.env,
+        2000_0000000, // eth
+        1_0000000,    // usdc
+        0_1200000,    // xlm
+        1_0000000,    // stable
+    ]);
+    fixture.pools[0].pool.new_liquidation_auction(samwise, &50);
+
+    // jump 200 blocks so the entire lot is available for the entire bid
+    fixture.jump_with_sequence(200 * 5);
+}
+
+#[test]
+fn test_self_liquidation_fill_rejected() {
+    let fixture = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let samwise = create_max_borrower(&fixture);
+    create_scaled_liquidation(&fixture, &samwise);
+
+    let positions_pre = pool_fixture.pool.get_positions(&samwise);
+    let auction_pre = pool_fixture.pool.get_auction(&0, &samwise);
+
+    // the liquidated user cannot fill their own auction, even alongside other requests
+    let fill_request: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::FillUserLiquidationAuction as u32,
+            address: samwise.clone(),
+            amount: 100,
+        },
+        Request {
+            request_type: RequestType::WithdrawCollateral as u32,
+            address: fixture.tokens[TokenIndex::STABLE].address.clone(),
+            amount: i128::MAX,
+        },
+    ];
+    let result = pool_fixture
+        .pool
+        .try_submit(&samwise, &samwise, &samwise, &fill_request);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1211))));
+
+    let positions_post = pool_fixture.pool.get_positions(&samwise);
+    assert_eq!(positions_post.collateral, positions_pre.collateral);
+    assert_eq!(positions_post.liabilities, positions_pre.liabilities);
+    let auction_post = pool_fixture.pool.get_auction(&0, &samwise);
+    assert_eq!(auction_post.bid, auction_pre.bid);
+    assert_eq!(auction_post.lot, auction_pre.lot);
+    assert_eq!(auction_post.block, auction_pre.block);
+}
+
+#[test]
+fn test_fill_then_withdraw() {
+    let fixture = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let samwise = create_max_borrower(&fixture);
+    create_scaled_liquidation(&fixture, &samwise);
+
+    // a filler with no prior positions can exit the filled position in the same batch
+    let merry = Address::generate(&fixture.env);
+    fixture.tokens[TokenIndex::XLM].mint(&merry, &(10_000 * SCALAR_7));
+
+    let fill_request: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::FillUserLiquidationAuction as u32,
+            address: samwise.clone(),
+            amount: 100,
+        },
+        Request {
+            request_type: RequestType::Repay as u32,
+            address: fixture.tokens[TokenIndex::XLM].address.clone(),
+            amount: 10_000 * SCALAR_7,
+        },
+        Request {
+            request_type: RequestType::WithdrawCollateral as u32,
+            address: fixture.tokens[TokenIndex::STABLE].address.clone(),
+            amount: 10_000 * 10i128.pow(6),
+        },
+    ];
+    let merry_positions = pool_fixture
+        .pool
+        .submit(&merry, &merry, &merry, &fill_request);
+
+    assert_eq!(merry_positions.collateral.len(), 0);
+    assert_eq!(merry_positions.liabilities.len(), 0);
+    assert_eq!(
+        pool_fixture.pool.get_positions(&merry).collateral,
+        merry_positions.collateral
+    );
+    assert!(pool_fixture.pool.try_get_auction(&0, &samwise).is_err());
+
+    // merry repaid roughly half of the 6075 XLM debt and withdrew the STABLE lot
+    assert_approx_eq_abs(
+        fixture.tokens[TokenIndex::XLM].balance(&merry),
+        (10_000 - 3037) * SCALAR_7,
+        5 * SCALAR_7,
+    );
+    assert!(fixture.tokens[TokenIndex::STABLE].balance(&merry) > 0);
+}
+
+#[test]
+fn test_repay_then_borrow() {
+    let fixture = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let xlm_pool_index = pool_fixture.reserves[&TokenIndex::XLM];
+    let samwise = create_max_borrower(&fixture);
+
+    // samwise is at their borrow limit, so the borrow alone fails
+    let borrow_request: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::Borrow as u32,
+            address: fixture.tokens[TokenIndex::XLM].address.clone(),
+            amount: 900 * SCALAR_7,
+        },
+    ];
+    let result = pool_fixture
+        .pool
+        .try_submit(&samwise, &samwise, &samwise, &borrow_request);
+    assert_eq!(result.err(), Some(Ok(Error::from_contract_error(1205))));
+
+    // the borrow succeeds when it follows a larger repayment in the same batch
+    let repay_borrow_request: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::Repay as u32,
+            address: fixture.tokens[TokenIndex::XLM].address.clone(),
+            amount: 1000 * SCALAR_7,
+        },
+        Request {
+            request_type: RequestType::Borrow as u32,
+            address: fixture.tokens[TokenIndex::XLM].address.clone(),
+            amount: 900 * SCALAR_7,
+        },
+    ];
+    let sam_positions =
+        pool_fixture
+            .pool
+            .submit(&samwise, &samwise, &samwise, &repay_borrow_request);
+    assert_approx_eq_abs(
+        sam_positions.liabilities.get_unchecked(xlm_pool_index),
+        5975 * SCALAR_7,
+        SCALAR_7,
+    );
+
+    // the reversed order is validated against the same end state, so it also succeeds
+    let borrow_repay_request: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::Borrow as u32,
+            address: fixture.tokens[TokenIndex::XLM].address.clone(),
+            amount: 900 * SCALAR_7,
+        },
+        Request {
+            request_type: RequestType::Repay as u32,
+            address: fixture.tokens[TokenIndex::XLM].address.clone(),
+            amount: 1000 * SCALAR_7,
+        },
+    ];
+    let sam_positions =
+        pool_fixture
+            .pool
+            .submit(&samwise, &samwise, &samwise, &borrow_repay_request);
+    assert_approx_eq_abs(
+        sam_positions.liabilities.get_unchecked(xlm_pool_index),
+        5875 * SCALAR_7,
+        SCALAR_7,
+    );
+}
+
+#[test]
+fn test_same_ledger_submit_skips_unchanged_reserve_write() {
+    let fixture = create_fixture_with_data(false);
+    let pool_fixture = &fixture.pools[0];
+    let xlm = &fixture.tokens[TokenIndex::XLM];
+
+    let samwise = Address::generate(&fixture.env);
+    let merry = Address::generate(&fixture.env);
+    xlm.mint(&samwise, &(100 * SCALAR_7));
+    xlm.mint(&merry, &(100 * SCALAR_7));
+
+    // samwise's supply accrues interest on the reserve and stores it
+    let supply_request: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: xlm.address.clone(),
+            amount: 100 * SCALAR_7,
+        },
+    ];
+    fixture.reset_invocation_cost();
+    pool_fixture
+        .pool
+        .submit(&samwise, &samwise, &samwise, &supply_request);
+    assert!(fixture.is_reserve_data_written(0, TokenIndex::XLM));
+    let reserve_data = fixture.read_reserve_data(0, TokenIndex::XLM);
+
+    // merry supplies and withdraws everything in the same ledger, leaving the reserve unchanged
+    let round_trip_request: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: xlm.address.clone(),
+            amount: 100 * SCALAR_7,
+        },
+        Request {
+            request_type: RequestType::WithdrawCollateral as u32,
+            address: xlm.address.clone(),
+            amount: 200 * SCALAR_7,
+        },
+    ];
+    fixture.reset_invocation_cost();
+    pool_fixture
+        .pool
+        .submit(&merry, &merry, &merry, &round_trip_request);
+    assert!(!fixture.is_reserve_data_written(0, TokenIndex::XLM));
+    assert_eq!(fixture.read_reserve_data(0, TokenIndex::XLM), reserve_data);
+    assert_approx_eq_abs(xlm.balance(&merry), 100 * SCALAR_7, 2);
+
+    // a same-ledger submit that changes the reserve still stores it
+    let supply_request: Vec<Request> = vec![
+        &fixture.env,
+        Request {
+            request_type: RequestType::SupplyCollateral as u32,
+            address: xlm.address.clone(),
+            amount: 50 * SCALAR_7,
+        },
+    ];
+    fixture.reset_invocation_cost();
+    pool_fixture
+        .pool
+        .submit(&merry, &merry, &merry, &supply_request);
+    assert!(fixture.is_reserve_data_written(0, TokenIndex::XLM));
+    let new_reserve_data = fixture.read_reserve_data(0, TokenIndex::XLM);
+    assert_eq!(new_reserve_data.last_time, reserve_data.last_time);
+    assert!(new_reserve_data.b_supply > reserve_data.b_supply);
+}
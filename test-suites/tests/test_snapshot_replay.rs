@@ -0,0 +1,55 @@
+#![cfg(test)]
+
+use soroban_sdk::{xdr::ScAddress, Address, String, TryFromVal};
+use test_suites::{
+    create_fixture_with_data,
+    pool::POOL_WASM,
+    snapshot::{load_snapshot, replay_pool_upgrade},
+};
+
+/// Test a snapshot of a pool's state can be reloaded and upgraded in place without changing any
+/// user's positions
+#[test]
+fn test_replay_pool_upgrade() {
+    let fixture = create_fixture_with_data(true);
+    let pool_address = ScAddress::try_from(&fixture.pools[0].pool.address).unwrap();
+    let users = [
+        ScAddress::try_from(&fixture.users[0]).unwrap(),
+        ScAddress::try_from(&fixture.bombadil).unwrap(),
+    ];
+
+    let path = std::env::temp_dir().join("blend_test_replay_pool_upgrade.json");
+    fixture.env.to_ledger_snapshot().write_file(&path).unwrap();
+
+    // addresses are bound to an Env, so they must be reloaded in the snapshot's Env
+    let e = load_snapshot(path.to_str().unwrap());
+    let pool_address = Address::try_from_val(&e, &pool_address).unwrap();
+    let users = users.map(|user| Address::try_from_val(&e, &user).unwrap());
+    replay_pool_upgrade(&e, &pool_address, &users, POOL_WASM);
+    std::fs::remove_file(&path).unwrap();
+}
+
+/// Replay the current pool WASM against a snapshot of a deployed pool. Configure it with:
+/// * `SNAPSHOT_PATH` - The path to the ledger snapshot JSON file
+/// * `SNAPSHOT_POOL` - The pool's contract address
+/// * `SNAPSHOT_USERS` - A comma separated list of user addresses to check
+///
+/// Run with `cargo test --test test_snapshot_replay -- --ignored`
+#[test]
+#[ignore]
+fn test_replay_pool_upgrade_from_snapshot_file() {
+    let path = std::env::var("SNAPSHOT_PATH").unwrap();
+    let e = load_snapshot(&path);
+    let pool_address = Address::from_string(&String::from_str(
+        &e,
+        &std::env::var("SNAPSHOT_POOL").unwrap(),
+    ));
+    let users: std::vec::Vec<Address> = std::env::var("SNAPSHOT_USERS")
+        .unwrap_or_default()
+        .split(',')
+        .filter(|user| !user.is_empty())
+        .map(|user| Address::from_string(&String::from_str(&e, user)))
+        .collect();
+
+    replay_pool_upgrade(&e, &pool_address, &users, POOL_WASM);
+}
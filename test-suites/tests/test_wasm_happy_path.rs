@@ -176,15 +176,17 @@ fn test_wasm_happy_path() {
     //  * rate will be dragged down due to rate modifier
 
     // claim frodo's setup emissions (1h1m passes during setup)
-    // - Frodo should receive 60 * 61 * .3 = 1098 BLND from the pool claim
-    // - Frodo should receive 60 * 61 * .7 = 2562 BLND from the backstop claim
+    // - the fixture waits a week to join the reward zone, so 2 BLND per second are emitted
+    // - Frodo should receive 60 * 60 * 2 * .3 = 2160 BLND from the pool claim, as no tokens
+    //   existed to earn emissions during the first minute
+    // - Frodo should receive 60 * 61 * 2 * .7 = 5124 BLND from the backstop claim
     let mut backstop_blnd_balance =
         fixture.tokens[TokenIndex::BLND].balance(&fixture.backstop.address);
     let claim_amount = pool_fixture
         .pool
         .claim(&frodo, &vec![&fixture.env, 0, 3], &frodo);
     backstop_blnd_balance -= claim_amount;
-    assert_eq!(claim_amount, 1098_0000000);
+    assert_eq!(claim_amount, 2160_0000000);
     assert_eq!(
         fixture.tokens[TokenIndex::BLND].balance(&fixture.backstop.address),
         backstop_blnd_balance
@@ -194,8 +196,8 @@ fn test_wasm_happy_path() {
         &vec![&fixture.env, pool_fixture.pool.address.clone()],
         &frodo,
     );
-    assert_eq!(claim_amount, 2562_0000000);
-    backstop_blnd_balance -= 2562_0000000;
+    assert_eq!(claim_amount, 5124_0000000);
+    backstop_blnd_balance -= 5124_0000000;
     assert_eq!(
         fixture.tokens[TokenIndex::BLND].balance(&fixture.backstop.address),
         backstop_blnd_balance
@@ -212,7 +214,7 @@ fn test_wasm_happy_path() {
         .pool
         .claim(&frodo, &vec![&fixture.env, 0, 3], &frodo);
     backstop_blnd_balance -= claim_amount;
-    assert_eq!(claim_amount, 4665_6384000);
+    assert_eq!(claim_amount, 9331_2776000);
     assert_eq!(
         fixture.tokens[TokenIndex::BLND].balance(&fixture.backstop.address),
         backstop_blnd_balance
@@ -228,7 +230,7 @@ fn test_wasm_happy_path() {
         .pool
         .claim(&sam, &vec![&fixture.env, 0, 3], &sam);
     backstop_blnd_balance -= claim_amount;
-    assert_eq!(claim_amount, 730943066650);
+    assert_eq!(claim_amount, 1461886245299);
     assert_eq!(
         fixture.tokens[TokenIndex::BLND].balance(&fixture.backstop.address),
         backstop_blnd_balance
@@ -376,7 +378,7 @@ fn test_wasm_happy_path() {
         .pool
         .claim(&frodo, &vec![&fixture.env, 0, 3], &frodo);
     backstop_blnd_balance -= claim_amount;
-    assert_eq!(claim_amount, 11673_1656000);
+    assert_eq!(claim_amount, 23346_3196000);
     assert_eq!(
         fixture.tokens[TokenIndex::BLND].balance(&fixture.backstop.address),
         backstop_blnd_balance
@@ -391,7 +393,7 @@ fn test_wasm_happy_path() {
         &vec![&fixture.env, pool_fixture.pool.address.clone()],
         &frodo,
     );
-    assert_eq!(claim_amount, 420797_9972539);
+    assert_eq!(claim_amount, 841595_9962184);
     backstop_blnd_balance -= claim_amount;
     assert_eq!(
         fixture.tokens[TokenIndex::BLND].balance(&fixture.backstop.address),
@@ -404,7 +406,7 @@ fn test_wasm_happy_path() {
         .pool
         .claim(&sam, &vec![&fixture.env, 0, 3], &sam);
     backstop_blnd_balance -= claim_amount;
-    assert_eq!(claim_amount, 90908_8243725);
+    assert_eq!(claim_amount, 181817_5982499);
     assert_eq!(
         fixture.tokens[TokenIndex::BLND].balance(&fixture.backstop.address),
         backstop_blnd_balance
@@ -433,8 +435,8 @@ fn test_wasm_happy_path() {
         &vec![&fixture.env, pool_fixture.pool.address.clone()],
         &frodo,
     );
-    assert_eq!(claim_amount, 22014719_9950114); //actual amount is 22014720_0000000 but get's rounded down
-    backstop_blnd_balance -= 22014719_9950114;
+    assert_eq!(claim_amount, 22014719_9950598); //actual amount is 22014720_0000000 but get's rounded down
+    backstop_blnd_balance -= 22014719_9950598;
     assert_eq!(
         fixture.tokens[TokenIndex::BLND].balance(&fixture.backstop.address),
         backstop_blnd_balance
@@ -445,7 +447,7 @@ fn test_wasm_happy_path() {
         .pool
         .claim(&frodo, &vec![&fixture.env, 0, 3], &frodo);
     backstop_blnd_balance -= claim_amount;
-    assert_eq!(claim_amount, 1073628_1628000);
+    assert_eq!(claim_amount, 1073627_9320000);
     assert_eq!(
         fixture.tokens[TokenIndex::BLND].balance(&fixture.backstop.address),
         backstop_blnd_balance
@@ -456,7 +458,7 @@ fn test_wasm_happy_path() {
         .pool
         .claim(&sam, &vec![&fixture.env, 0, 3], &sam);
     backstop_blnd_balance -= claim_amount;
-    assert_eq!(claim_amount, 8361251_6449409);
+    assert_eq!(claim_amount, 8361251_9873000);
     assert_eq!(
         fixture.tokens[TokenIndex::BLND].balance(&fixture.backstop.address),
         backstop_blnd_balance